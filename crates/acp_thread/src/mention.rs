@@ -46,6 +46,11 @@ pub enum MentionUri {
     Fetch {
         url: Url,
     },
+    ContextServerResource {
+        server_id: String,
+        uri: String,
+        name: String,
+    },
 }
 
 impl MentionUri {
@@ -115,6 +120,18 @@ impl MentionUri {
                         id: rule_id.into(),
                         name,
                     })
+                } else if path == "/agent/context-server-resource" {
+                    let query_param = |name: &str| {
+                        url.query_pairs()
+                            .find(|(key, _)| key == name)
+                            .map(|(_, value)| value.into_owned())
+                            .with_context(|| format!("Missing context server resource {name}"))
+                    };
+                    Ok(Self::ContextServerResource {
+                        server_id: query_param("server")?,
+                        uri: query_param("uri")?,
+                        name: query_param("name")?,
+                    })
                 } else if path.starts_with("/agent/pasted-image") {
                     Ok(Self::PastedImage)
                 } else if path.starts_with("/agent/untitled-buffer") {
@@ -153,6 +170,7 @@ impl MentionUri {
                 ..
             } => selection_name(path.as_deref(), line_range),
             MentionUri::Fetch { url } => url.to_string(),
+            MentionUri::ContextServerResource { name, .. } => name.clone(),
         }
     }
 
@@ -170,6 +188,7 @@ impl MentionUri {
             MentionUri::Rule { .. } => IconName::Reader.path().into(),
             MentionUri::Selection { .. } => IconName::Reader.path().into(),
             MentionUri::Fetch { .. } => IconName::ToolWeb.path().into(),
+            MentionUri::ContextServerResource { .. } => IconName::ToolHammer.path().into(),
         }
     }
 
@@ -241,6 +260,19 @@ impl MentionUri {
                 url
             }
             MentionUri::Fetch { url } => url.clone(),
+            MentionUri::ContextServerResource {
+                server_id,
+                uri,
+                name,
+            } => {
+                let mut url = Url::parse("zed:///").unwrap();
+                url.set_path("/agent/context-server-resource");
+                url.query_pairs_mut()
+                    .append_pair("server", server_id)
+                    .append_pair("uri", uri)
+                    .append_pair("name", name);
+                url
+            }
         }
     }
 }
@@ -452,6 +484,25 @@ mod tests {
         assert_eq!(parsed.to_uri().to_string(), https_uri);
     }
 
+    #[test]
+    fn test_parse_context_server_resource_uri() {
+        let resource_uri = "zed:///agent/context-server-resource?server=github&uri=repo%3A%2F%2Fzed%2FREADME.md&name=README.md";
+        let parsed = MentionUri::parse(resource_uri).unwrap();
+        match &parsed {
+            MentionUri::ContextServerResource {
+                server_id,
+                uri,
+                name,
+            } => {
+                assert_eq!(server_id, "github");
+                assert_eq!(uri, "repo://zed/README.md");
+                assert_eq!(name, "README.md");
+            }
+            _ => panic!("Expected ContextServerResource variant"),
+        }
+        assert_eq!(parsed.to_uri().to_string(), resource_uri);
+    }
+
     #[test]
     fn test_invalid_scheme() {
         assert!(MentionUri::parse("ftp://example.com").is_err());
@@ -562,6 +562,7 @@ impl ThreadStore {
                     }
                 }
             }
+            _ => {}
         }
     }
 
@@ -1,8 +1,11 @@
+use crate::{
+    ContextServerPrompt, HistoryStore, TerminalHandle, ThreadEnvironment, TitleUpdated,
+    TokenUsageUpdated,
+};
 use crate::{
     ContextServerRegistry, Thread, ThreadEvent, ThreadsDatabase, ToolCallAuthorization,
     UserMessageContent, templates::Templates,
 };
-use crate::{HistoryStore, TerminalHandle, ThreadEnvironment, TitleUpdated, TokenUsageUpdated};
 use acp_thread::{AcpThread, AgentModelSelector};
 use action_log::ActionLog;
 use agent_client_protocol as acp;
@@ -242,12 +245,17 @@ impl NativeAgent {
             .await;
 
         cx.new(|cx| {
+            let context_server_registry = cx
+                .new(|cx| ContextServerRegistry::new(project.read(cx).context_server_store(), cx));
             let mut subscriptions = vec![
                 cx.subscribe(&project, Self::handle_project_event),
                 cx.subscribe(
                     &LanguageModelRegistry::global(cx),
                     Self::handle_models_updated_event,
                 ),
+                cx.observe(&context_server_registry, |this, _, cx| {
+                    this.update_available_commands(cx)
+                }),
             ];
             if let Some(prompt_store) = prompt_store.as_ref() {
                 subscriptions.push(cx.subscribe(prompt_store, Self::handle_prompts_updated_event))
@@ -263,9 +271,7 @@ impl NativeAgent {
                 _maintain_project_context: cx.spawn(async move |this, cx| {
                     Self::maintain_project_context(this, project_context_needs_refresh_rx, cx).await
                 }),
-                context_server_registry: cx.new(|cx| {
-                    ContextServerRegistry::new(project.read(cx).context_server_store(), cx)
-                }),
+                context_server_registry,
                 templates,
                 models: LanguageModels::new(cx),
                 project,
@@ -334,9 +340,112 @@ impl NativeAgent {
                 pending_save: Task::ready(()),
             },
         );
+        // Defer the update so the thread's observers are subscribed by the time it's sent.
+        cx.spawn(async move |this, cx| {
+            this.update(cx, |this, cx| this.update_available_commands(cx))
+        })
+        .detach_and_log_err(cx);
         acp_thread
     }
 
+    /// The prompts of running context servers, offered as slash commands.
+    fn available_commands(&self, cx: &App) -> Vec<acp::AvailableCommand> {
+        self.context_server_registry
+            .read(cx)
+            .prompts()
+            .map(
+                |ContextServerPrompt { server_id, prompt }| acp::AvailableCommand {
+                    name: prompt.name.clone(),
+                    description: prompt
+                        .description
+                        .clone()
+                        .unwrap_or_else(|| format!("Run '{}' from {}", prompt.name, server_id)),
+                    input: prompt
+                        .arguments
+                        .as_ref()
+                        .filter(|arguments| !arguments.is_empty())
+                        .map(|arguments| acp::AvailableCommandInput::Unstructured {
+                            hint: arguments
+                                .iter()
+                                .map(|argument| argument.name.as_str())
+                                .collect::<Vec<_>>()
+                                .join(" "),
+                        }),
+                    meta: None,
+                },
+            )
+            .collect()
+    }
+
+    fn update_available_commands(&self, cx: &mut Context<Self>) {
+        let available_commands = self.available_commands(cx);
+        for session in self.sessions.values() {
+            session
+                .acp_thread
+                .update(cx, |thread, cx| {
+                    thread.handle_session_update(
+                        acp::SessionUpdate::AvailableCommandsUpdate {
+                            available_commands: available_commands.clone(),
+                        },
+                        cx,
+                    )
+                })
+                .ok();
+        }
+    }
+
+    /// Expands a message starting with `/name arguments` into the messages of the context
+    /// server prompt called `name`. Messages that don't name a prompt are left untouched.
+    fn expand_context_server_prompt(
+        &self,
+        mut content: Vec<acp::ContentBlock>,
+        cx: &App,
+    ) -> Task<Result<Vec<acp::ContentBlock>>> {
+        let Some(acp::ContentBlock::Text(text)) = content.first() else {
+            return Task::ready(Ok(content));
+        };
+        let Some(command) = text.text.strip_prefix('/') else {
+            return Task::ready(Ok(content));
+        };
+        let (name, argument_text) = command
+            .split_once(char::is_whitespace)
+            .unwrap_or((command, ""));
+        let registry = self.context_server_registry.read(cx);
+        let Some(prompt) = registry.find_prompt(name) else {
+            return Task::ready(Ok(content));
+        };
+        let arguments = match prompt_arguments(&prompt.prompt, argument_text) {
+            Ok(arguments) => arguments,
+            Err(error) => return Task::ready(Err(error)),
+        };
+        let response = registry.get_prompt(prompt, arguments, cx);
+        cx.foreground_executor().spawn(async move {
+            let response = response.await?;
+            anyhow::ensure!(
+                response
+                    .messages
+                    .iter()
+                    .all(|message| message.role == context_server::types::Role::User),
+                "Prompt contains non-user roles, which is not supported"
+            );
+            let text = response
+                .messages
+                .into_iter()
+                .filter_map(|message| match message.content {
+                    context_server::types::MessageContent::Text { text, .. } => Some(text),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+                .join("\n\n");
+            content[0] = acp::ContentBlock::Text(acp::TextContent {
+                text,
+                annotations: None,
+                meta: None,
+            });
+            Ok(content)
+        })
+    }
+
     pub fn models(&self) -> &LanguageModels {
         &self.models
     }
@@ -992,17 +1101,25 @@ impl acp_thread::AgentConnection for NativeAgentConnection {
         log::info!("Received prompt request for session: {}", session_id);
         log::debug!("Prompt blocks count: {}", params.prompt.len());
 
-        self.run_turn(session_id, cx, |thread, cx| {
-            let content: Vec<UserMessageContent> = params
-                .prompt
-                .into_iter()
-                .map(Into::into)
-                .collect::<Vec<_>>();
-            log::debug!("Converted prompt to message: {} chars", content.len());
-            log::debug!("Message id: {:?}", id);
-            log::debug!("Message content: {:?}", content);
-
-            thread.update(cx, |thread, cx| thread.send(id, content, cx))
+        let prompt = self
+            .0
+            .read(cx)
+            .expand_context_server_prompt(params.prompt, cx);
+        let this = self.clone();
+        cx.spawn(async move |cx| {
+            let prompt = prompt.await?;
+            cx.update(|cx| {
+                this.run_turn(session_id, cx, |thread, cx| {
+                    let content: Vec<UserMessageContent> =
+                        prompt.into_iter().map(Into::into).collect::<Vec<_>>();
+                    log::debug!("Converted prompt to message: {} chars", content.len());
+                    log::debug!("Message id: {:?}", id);
+                    log::debug!("Message content: {:?}", content);
+
+                    thread.update(cx, |thread, cx| thread.send(id, content, cx))
+                })
+            })?
+            .await
         })
     }
 
@@ -1196,6 +1313,41 @@ impl TerminalHandle for AcpTerminalHandle {
     }
 }
 
+/// Maps the text following a slash command to the prompt's arguments in the order they are
+/// declared, with the last argument receiving the remainder of the text.
+fn prompt_arguments(
+    prompt: &context_server::types::Prompt,
+    text: &str,
+) -> Result<collections::HashMap<String, String>> {
+    let declared = prompt.arguments.as_deref().unwrap_or_default();
+    let mut arguments = collections::HashMap::default();
+    let mut rest = text.trim();
+    for (ix, argument) in declared.iter().enumerate() {
+        if rest.is_empty() {
+            break;
+        }
+        let value = if ix + 1 == declared.len() {
+            std::mem::take(&mut rest)
+        } else {
+            let (value, remainder) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+            rest = remainder.trim_start();
+            value
+        };
+        arguments.insert(argument.name.clone(), value.to_string());
+    }
+    anyhow::ensure!(
+        rest.is_empty(),
+        "Prompt expects no arguments but some were given"
+    );
+    if let Some(missing) = declared
+        .iter()
+        .find(|argument| argument.required == Some(true) && !arguments.contains_key(&argument.name))
+    {
+        anyhow::bail!("Missing required argument `{}`", missing.name);
+    }
+    Ok(arguments)
+}
+
 #[cfg(test)]
 mod tests {
     use crate::HistoryEntryId;
@@ -1566,6 +1718,32 @@ mod tests {
         })
     }
 
+    #[test]
+    fn test_prompt_arguments() {
+        let prompt: context_server::types::Prompt = serde_json::from_value(json!({
+            "name": "review",
+            "arguments": [
+                { "name": "branch", "required": true },
+                { "name": "focus" }
+            ]
+        }))
+        .unwrap();
+
+        let arguments = prompt_arguments(&prompt, " main  error handling ").unwrap();
+        assert_eq!(arguments.get("branch").unwrap(), "main");
+        assert_eq!(arguments.get("focus").unwrap(), "error handling");
+
+        let arguments = prompt_arguments(&prompt, "main").unwrap();
+        assert_eq!(arguments.len(), 1);
+
+        assert!(prompt_arguments(&prompt, "").is_err());
+
+        let prompt: context_server::types::Prompt =
+            serde_json::from_value(json!({ "name": "summarize" })).unwrap();
+        assert!(prompt_arguments(&prompt, "").unwrap().is_empty());
+        assert!(prompt_arguments(&prompt, "unexpected").is_err());
+    }
+
     fn init_test(cx: &mut TestAppContext) {
         env_logger::try_init().ok();
         cx.update(|cx| {
@@ -14,9 +14,19 @@ pub struct ContextServerRegistry {
     _subscription: gpui::Subscription,
 }
 
+#[derive(Default)]
 struct RegisteredContextServer {
     tools: BTreeMap<SharedString, Arc<dyn AnyAgentTool>>,
-    load_tools: Task<Result<()>>,
+    prompts: BTreeMap<SharedString, ContextServerPrompt>,
+    load_tools: Option<Task<Result<()>>>,
+    load_prompts: Option<Task<Result<()>>>,
+}
+
+/// A prompt offered by a context server, which users can run as a slash command.
+#[derive(Clone, Debug)]
+pub struct ContextServerPrompt {
+    pub server_id: ContextServerId,
+    pub prompt: context_server::types::Prompt,
 }
 
 impl ContextServerRegistry {
@@ -28,6 +38,7 @@ impl ContextServerRegistry {
         };
         for server in server_store.read(cx).running_servers() {
             this.reload_tools_for_server(server.id(), cx);
+            this.reload_prompts_for_server(server.id(), cx);
         }
         this
     }
@@ -45,6 +56,45 @@ impl ContextServerRegistry {
             .map(|(id, server)| (id, &server.tools))
     }
 
+    pub fn prompts(&self) -> impl Iterator<Item = &ContextServerPrompt> {
+        self.registered_servers
+            .values()
+            .flat_map(|server| server.prompts.values())
+    }
+
+    pub fn find_prompt(&self, name: &str) -> Option<&ContextServerPrompt> {
+        self.prompts().find(|prompt| prompt.prompt.name == name)
+    }
+
+    /// Renders `prompt` with the given arguments into the messages it is made of.
+    pub fn get_prompt(
+        &self,
+        prompt: &ContextServerPrompt,
+        arguments: HashMap<String, String>,
+        cx: &App,
+    ) -> Task<Result<context_server::types::PromptsGetResponse>> {
+        let Some(client) = self
+            .server_store
+            .read(cx)
+            .get_running_server(&prompt.server_id)
+            .and_then(|server| server.client())
+        else {
+            return Task::ready(Err(anyhow!("Context server not found")));
+        };
+        let name = prompt.prompt.name.clone();
+        cx.foreground_executor().spawn(async move {
+            client
+                .request::<context_server::types::requests::PromptsGet>(
+                    context_server::types::PromptsGetParams {
+                        name,
+                        arguments: Some(arguments),
+                        meta: None,
+                    },
+                )
+                .await
+        })
+    }
+
     fn reload_tools_for_server(&mut self, server_id: ContextServerId, cx: &mut Context<Self>) {
         let Some(server) = self.server_store.read(cx).get_running_server(&server_id) else {
             return;
@@ -56,14 +106,11 @@ impl ContextServerRegistry {
             return;
         }
 
-        let registered_server =
-            self.registered_servers
-                .entry(server_id.clone())
-                .or_insert(RegisteredContextServer {
-                    tools: BTreeMap::default(),
-                    load_tools: Task::ready(Ok(())),
-                });
-        registered_server.load_tools = cx.spawn(async move |this, cx| {
+        let registered_server = self
+            .registered_servers
+            .entry(server_id.clone())
+            .or_default();
+        registered_server.load_tools = Some(cx.spawn(async move |this, cx| {
             let response = client
                 .request::<context_server::types::requests::ListTools>(())
                 .await;
@@ -86,7 +133,49 @@ impl ContextServerRegistry {
                     cx.notify();
                 }
             })
-        });
+        }));
+    }
+
+    fn reload_prompts_for_server(&mut self, server_id: ContextServerId, cx: &mut Context<Self>) {
+        let Some(server) = self.server_store.read(cx).get_running_server(&server_id) else {
+            return;
+        };
+        let Some(client) = server.client() else {
+            return;
+        };
+        if !client.capable(context_server::protocol::ServerCapability::Prompts) {
+            return;
+        }
+
+        let registered_server = self
+            .registered_servers
+            .entry(server_id.clone())
+            .or_default();
+        registered_server.load_prompts = Some(cx.spawn(async move |this, cx| {
+            let response = client
+                .request::<context_server::types::requests::PromptsList>(())
+                .await;
+
+            this.update(cx, |this, cx| {
+                let Some(registered_server) = this.registered_servers.get_mut(&server_id) else {
+                    return;
+                };
+
+                registered_server.prompts.clear();
+                if let Some(response) = response.log_err() {
+                    for prompt in response.prompts {
+                        registered_server.prompts.insert(
+                            prompt.name.clone().into(),
+                            ContextServerPrompt {
+                                server_id: server_id.clone(),
+                                prompt,
+                            },
+                        );
+                    }
+                    cx.notify();
+                }
+            })
+        }));
     }
 
     fn handle_context_server_store_event(
@@ -101,6 +190,7 @@ impl ContextServerRegistry {
                     ContextServerStatus::Starting => {}
                     ContextServerStatus::Running => {
                         self.reload_tools_for_server(server_id.clone(), cx);
+                        self.reload_prompts_for_server(server_id.clone(), cx);
                     }
                     ContextServerStatus::Stopped | ContextServerStatus::Error(_) => {
                         self.registered_servers.remove(server_id);
@@ -108,6 +198,10 @@ impl ContextServerRegistry {
                     }
                 }
             }
+            project::context_server_store::Event::PromptsChanged { server_id } => {
+                self.reload_prompts_for_server(server_id.clone(), cx);
+            }
+            _ => {}
         }
     }
 }
@@ -215,12 +215,32 @@ impl AgentConnection for AcpConnection {
         let cwd = cwd.to_path_buf();
         let context_server_store = project.read(cx).context_server_store().read(cx);
         let mcp_servers = if project.read(cx).is_local() {
-            context_server_store
+            let configurations = context_server_store
                 .configured_server_ids()
-                .iter()
+                .into_iter()
                 .filter_map(|id| {
-                    let configuration = context_server_store.configuration_for_server(id)?;
-                    let command = configuration.command();
+                    let configuration = context_server_store.configuration_for_server(&id)?;
+                    Some((id, configuration))
+                })
+                .collect::<Vec<_>>();
+            // Agents are only told how to spawn context servers, so they can't reach the ones
+            // Zed talks to over HTTP.
+            let http_server_ids = configurations
+                .iter()
+                .filter(|(_, configuration)| configuration.command().is_none())
+                .map(|(id, _)| id.0.to_string())
+                .collect::<Vec<_>>();
+            if !http_server_ids.is_empty() {
+                return Task::ready(Err(anyhow!(
+                    "{name} can't use context servers reached over HTTP ({}). \
+                    Disable them to start a thread.",
+                    http_server_ids.join(", ")
+                )));
+            }
+            configurations
+                .iter()
+                .filter_map(|(id, configuration)| {
+                    let command = configuration.command()?;
                     Some(acp::McpServer::Stdio {
                         name: id.0.to_string(),
                         command: command.path.clone(),
@@ -8,7 +8,11 @@ use acp_thread::MentionUri;
 use agent_client_protocol as acp;
 use agent2::{HistoryEntry, HistoryStore};
 use anyhow::Result;
+use context_server::ContextServerId;
+use context_server::protocol::ServerCapability;
+use context_server::types::Resource;
 use editor::{CompletionProvider, Editor, ExcerptId};
+use futures::future::join_all;
 use fuzzy::{StringMatch, StringMatchCandidate};
 use gpui::{App, Entity, Task, WeakEntity};
 use language::{Buffer, CodeLabel, HighlightId};
@@ -22,6 +26,7 @@ use prompt_store::PromptStore;
 use rope::Point;
 use text::{Anchor, ToPoint as _};
 use ui::prelude::*;
+use util::ResultExt as _;
 use workspace::Workspace;
 
 use crate::AgentPanel;
@@ -41,6 +46,7 @@ pub(crate) enum Match {
     RecentThread(HistoryEntry),
     Fetch(SharedString),
     Rules(RulesContextEntry),
+    Resource(ContextServerId, Resource),
     Entry(EntryMatch),
 }
 
@@ -59,6 +65,7 @@ impl Match {
             Match::Symbol(_) => 1.,
             Match::Rules(_) => 1.,
             Match::Fetch(_) => 1.,
+            Match::Resource(..) => 1.,
         }
     }
 }
@@ -185,6 +192,41 @@ impl ContextPickerCompletionProvider {
         }
     }
 
+    fn completion_for_resource(
+        server_id: ContextServerId,
+        resource: Resource,
+        source_range: Range<Anchor>,
+        editor: WeakEntity<MessageEditor>,
+        cx: &mut App,
+    ) -> Completion {
+        let uri = MentionUri::ContextServerResource {
+            server_id: server_id.0.to_string(),
+            uri: resource.uri.to_string(),
+            name: resource.name.clone(),
+        };
+        let new_text = format!("{} ", uri.as_link());
+        let new_text_len = new_text.len();
+        let icon_path = uri.icon_path(cx);
+        Completion {
+            replace_range: source_range.clone(),
+            new_text,
+            label: CodeLabel::plain(resource.name.clone(), None),
+            documentation: resource
+                .description
+                .map(|description| CompletionDocumentation::SingleLine(description.into())),
+            insert_text_mode: None,
+            source: project::CompletionSource::Custom,
+            icon_path: Some(icon_path),
+            confirm: Some(confirm_completion_callback(
+                resource.name.into(),
+                source_range.start,
+                new_text_len - 1,
+                editor,
+                uri,
+            )),
+        }
+    }
+
     pub(crate) fn completion_for_path(
         project_path: ProjectPath,
         path_prefix: &str,
@@ -478,6 +520,10 @@ impl ContextPickerCompletionProvider {
                 }
             }
 
+            Some(ContextPickerMode::Resources) => {
+                search_resources(query, cancellation_flag, &workspace, cx)
+            }
+
             None if query.is_empty() => {
                 let mut matches = self.recent_context_picker_entries(&workspace, cx);
 
@@ -653,12 +699,90 @@ impl ContextPickerCompletionProvider {
             }
 
             entries.push(ContextPickerEntry::Mode(ContextPickerMode::Fetch));
+
+            let has_resources = workspace
+                .read(cx)
+                .project()
+                .read(cx)
+                .context_server_store()
+                .read(cx)
+                .running_servers()
+                .iter()
+                .any(|server| {
+                    server
+                        .client()
+                        .is_some_and(|client| client.capable(ServerCapability::Resources))
+                });
+            if has_resources {
+                entries.push(ContextPickerEntry::Mode(ContextPickerMode::Resources));
+            }
         }
 
         entries
     }
 }
 
+/// Lists the resources of every running context server and fuzzy-matches them by name.
+fn search_resources(
+    query: String,
+    cancellation_flag: Arc<AtomicBool>,
+    workspace: &Entity<Workspace>,
+    cx: &mut App,
+) -> Task<Vec<Match>> {
+    let context_server_store = workspace.read(cx).project().read(cx).context_server_store();
+    let list_tasks = context_server_store
+        .read(cx)
+        .running_servers()
+        .into_iter()
+        .map(|server| {
+            let server_id = server.id();
+            let resources = context_server_store.read(cx).list_resources(&server_id, cx);
+            async move { (server_id, resources.await.log_err().unwrap_or_default()) }
+        })
+        .collect::<Vec<_>>();
+    let executor = cx.background_executor().clone();
+    cx.spawn(async move |_| {
+        let resources = join_all(list_tasks)
+            .await
+            .into_iter()
+            .flat_map(|(server_id, resources)| {
+                resources
+                    .into_iter()
+                    .map(move |resource| (server_id.clone(), resource))
+            })
+            .collect::<Vec<_>>();
+        if query.is_empty() {
+            return resources
+                .into_iter()
+                .map(|(server_id, resource)| Match::Resource(server_id, resource))
+                .collect();
+        }
+
+        let candidates = resources
+            .iter()
+            .enumerate()
+            .map(|(ix, (_, resource))| StringMatchCandidate::new(ix, &resource.name))
+            .collect::<Vec<_>>();
+        let matches = fuzzy::match_strings(
+            &candidates,
+            &query,
+            false,
+            true,
+            100,
+            &cancellation_flag,
+            executor,
+        )
+        .await;
+        matches
+            .into_iter()
+            .map(|mat| {
+                let (server_id, resource) = resources[mat.candidate_id].clone();
+                Match::Resource(server_id, resource)
+            })
+            .collect()
+    })
+}
+
 fn build_code_label_for_full_path(file_name: &str, directory: Option<&str>, cx: &App) -> CodeLabel {
     let comment_id = cx.theme().syntax().highlight_id("comment").map(HighlightId);
     let mut label = CodeLabel::default();
@@ -844,6 +968,16 @@ impl CompletionProvider for ContextPickerCompletionProvider {
                                     cx,
                                 )),
 
+                                Match::Resource(server_id, resource) => {
+                                    Some(Self::completion_for_resource(
+                                        server_id,
+                                        resource,
+                                        source_range.clone(),
+                                        editor.clone(),
+                                        cx,
+                                    ))
+                                }
+
                                 Match::Fetch(url) => Self::completion_for_fetch(
                                     source_range.clone(),
                                     url,
@@ -10,6 +10,7 @@ use anyhow::{Result, anyhow};
 use assistant_slash_commands::codeblock_fence_for_path;
 use assistant_tool::outline;
 use collections::{HashMap, HashSet};
+use context_server::{ContextServerId, types::ResourceContentsType};
 use editor::{
     Addon, Anchor, AnchorRangeExt, ContextMenuOptions, ContextMenuPlacement, Editor, EditorElement,
     EditorEvent, EditorMode, EditorSnapshot, EditorStyle, ExcerptId, FoldPlaceholder, InlayId,
@@ -31,6 +32,7 @@ use language_model::LanguageModelImage;
 use postage::stream::Stream as _;
 use project::{
     CompletionIntent, InlayHint, InlayHintLabel, Project, ProjectItem, ProjectPath, Worktree,
+    context_server_store::ContextServerStore,
 };
 use prompt_store::{PromptId, PromptStore};
 use rope::Point;
@@ -67,6 +69,8 @@ pub struct MessageEditor {
     prompt_capabilities: Rc<RefCell<acp::PromptCapabilities>>,
     available_commands: Rc<RefCell<Vec<acp::AvailableCommand>>>,
     agent_name: SharedString,
+    /// The context server resources mentioned in this editor, kept up to date as they change.
+    resource_subscriptions: Vec<(ContextServerId, url::Url)>,
     _subscriptions: Vec<Subscription>,
     _parse_slash_command_task: Task<()>,
 }
@@ -173,6 +177,28 @@ impl MessageEditor {
             }
         }));
 
+        let context_server_store = project.read(cx).context_server_store();
+        subscriptions.push(cx.subscribe(
+            &context_server_store,
+            |this, context_server_store, event, cx| {
+                if let project::context_server_store::Event::ResourceUpdated { server_id, uri } =
+                    event
+                {
+                    this.reload_context_server_resource(&context_server_store, server_id, uri, cx);
+                }
+            },
+        ));
+        cx.on_release(move |this: &mut Self, cx| {
+            context_server_store.update(cx, |store, cx| {
+                for (server_id, uri) in this.resource_subscriptions.drain(..) {
+                    store
+                        .unsubscribe_from_resource(&server_id, &uri, cx)
+                        .detach_and_log_err(cx);
+                }
+            })
+        })
+        .detach();
+
         Self {
             editor,
             project,
@@ -183,6 +209,7 @@ impl MessageEditor {
             prompt_capabilities,
             available_commands,
             agent_name,
+            resource_subscriptions: Vec::new(),
             _subscriptions: subscriptions,
             _parse_slash_command_task: Task::ready(()),
         }
@@ -374,6 +401,9 @@ impl MessageEditor {
                 ..
             } => self.confirm_mention_for_symbol(abs_path, line_range, cx),
             MentionUri::Rule { id, .. } => self.confirm_mention_for_rule(id, cx),
+            MentionUri::ContextServerResource { server_id, uri, .. } => {
+                self.confirm_mention_for_context_server_resource(server_id, uri, cx)
+            }
             MentionUri::PastedImage => {
                 debug_panic!("pasted image URI should not be included in completions");
                 Task::ready(Err(anyhow!(
@@ -580,6 +610,67 @@ impl MessageEditor {
         })
     }
 
+    fn confirm_mention_for_context_server_resource(
+        &mut self,
+        server_id: String,
+        uri: String,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Mention>> {
+        let server_id = ContextServerId(server_id.into());
+        let uri = match url::Url::parse(&uri) {
+            Ok(uri) => uri,
+            Err(error) => return Task::ready(Err(error.into())),
+        };
+        let context_server_store = self.project.read(cx).context_server_store();
+        if !self
+            .resource_subscriptions
+            .iter()
+            .any(|subscription| subscription == &(server_id.clone(), uri.clone()))
+        {
+            context_server_store.update(cx, |store, cx| {
+                store
+                    .subscribe_to_resource(&server_id, uri.clone(), cx)
+                    .detach_and_log_err(cx)
+            });
+            self.resource_subscriptions
+                .push((server_id.clone(), uri.clone()));
+        }
+        read_context_server_resource(&context_server_store, &server_id, uri, cx)
+    }
+
+    /// Reloads the mentions of a resource after the context server reported that it changed.
+    fn reload_context_server_resource(
+        &mut self,
+        context_server_store: &Entity<ContextServerStore>,
+        server_id: &ContextServerId,
+        uri: &str,
+        cx: &mut Context<Self>,
+    ) {
+        let Ok(parsed_uri) = url::Url::parse(uri) else {
+            return;
+        };
+        for (mention_uri, task) in self.mention_set.mentions.values_mut() {
+            if let MentionUri::ContextServerResource {
+                server_id: mention_server_id,
+                uri: mention_resource_uri,
+                ..
+            } = mention_uri
+                && mention_server_id.as_str() == server_id.0.as_ref()
+                && mention_resource_uri == uri
+            {
+                let content = read_context_server_resource(
+                    context_server_store,
+                    server_id,
+                    parsed_uri.clone(),
+                    cx,
+                );
+                *task = cx
+                    .spawn(async move |_, _| content.await.map_err(|e| e.to_string()))
+                    .shared();
+            }
+        }
+    }
+
     fn confirm_mention_for_symbol(
         &mut self,
         abs_path: PathBuf,
@@ -900,6 +991,14 @@ impl MessageEditor {
     }
 
     pub fn clear(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let context_server_store = self.project.read(cx).context_server_store();
+        context_server_store.update(cx, |store, cx| {
+            for (server_id, uri) in self.resource_subscriptions.drain(..) {
+                store
+                    .unsubscribe_from_resource(&server_id, &uri, cx)
+                    .detach_and_log_err(cx);
+            }
+        });
         self.editor.update(cx, |editor, cx| {
             editor.clear(window, cx);
             editor.remove_creases(
@@ -1505,6 +1604,34 @@ pub struct MentionImage {
     pub format: ImageFormat,
 }
 
+fn read_context_server_resource(
+    context_server_store: &Entity<ContextServerStore>,
+    server_id: &ContextServerId,
+    uri: url::Url,
+    cx: &App,
+) -> Task<Result<Mention>> {
+    let contents = context_server_store
+        .read(cx)
+        .read_resource(server_id, uri, cx);
+    cx.background_spawn(async move {
+        let content = contents
+            .await?
+            .into_iter()
+            .map(|contents| match contents {
+                ResourceContentsType::Text(text) => Ok(text.text),
+                ResourceContentsType::Blob(blob) => {
+                    Err(anyhow!("binary resource {} is not supported", blob.uri))
+                }
+            })
+            .collect::<Result<Vec<_>>>()?
+            .join("\n\n");
+        Ok(Mention::Text {
+            content,
+            tracked_buffers: Vec::new(),
+        })
+    })
+}
+
 #[derive(Default)]
 pub struct MentionSet {
     mentions: HashMap<CreaseId, (MentionUri, Shared<Task<Result<Mention, String>>>)>,
@@ -4310,6 +4310,11 @@ impl AcpThreadView {
                 MentionUri::Fetch { url } => {
                     cx.open_url(url.as_str());
                 }
+                MentionUri::ContextServerResource { uri, .. } => {
+                    if uri.starts_with("https://") || uri.starts_with("http://") {
+                        cx.open_url(&uri);
+                    }
+                }
             })
         } else {
             cx.open_url(&url);
@@ -4,7 +4,8 @@ use std::{
 };
 
 use anyhow::{Context as _, Result};
-use context_server::{ContextServerCommand, ContextServerId};
+use context_server::{ContextServerCommand, ContextServerEndpoint, ContextServerId};
+use credentials_provider::CredentialsProvider;
 use editor::{Editor, EditorElement, EditorStyle};
 use gpui::{
     AsyncWindowContext, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, Task,
@@ -25,6 +26,7 @@ use theme::ThemeSettings;
 use ui::{
     CommonAnimationExt, KeyBinding, Modal, ModalFooter, ModalHeader, Section, Tooltip, prelude::*,
 };
+use ui_input::SingleLineInput;
 use util::ResultExt as _;
 use workspace::{ModalView, Workspace};
 
@@ -36,6 +38,10 @@ enum ConfigurationTarget {
         id: ContextServerId,
         command: ContextServerCommand,
     },
+    ExistingHttp {
+        id: ContextServerId,
+        endpoint: ContextServerEndpoint,
+    },
     Extension {
         id: ContextServerId,
         repository_url: Option<SharedString>,
@@ -64,6 +70,14 @@ impl ConfigurationSource {
         !matches!(self, ConfigurationSource::Extension { editor: None, .. })
     }
 
+    /// Whether the configured server may be an HTTP one, which can authenticate with a bearer token.
+    fn accepts_bearer_token(&self) -> bool {
+        matches!(
+            self,
+            ConfigurationSource::New { .. } | ConfigurationSource::Existing { .. }
+        )
+    }
+
     fn is_new(&self) -> bool {
         matches!(self, ConfigurationSource::New { .. })
     }
@@ -105,6 +119,14 @@ impl ConfigurationSource {
                     cx,
                 ),
             },
+            ConfigurationTarget::ExistingHttp { id, endpoint } => ConfigurationSource::Existing {
+                editor: create_editor(
+                    http_context_server_input(id, endpoint),
+                    jsonc_language,
+                    window,
+                    cx,
+                ),
+            },
             ConfigurationTarget::Extension {
                 id,
                 repository_url,
@@ -141,15 +163,7 @@ impl ConfigurationSource {
     fn output(&self, cx: &mut App) -> Result<(ContextServerId, ContextServerSettings)> {
         match self {
             ConfigurationSource::New { editor } | ConfigurationSource::Existing { editor } => {
-                parse_input(&editor.read(cx).text(cx)).map(|(id, command)| {
-                    (
-                        id,
-                        ContextServerSettings::Custom {
-                            enabled: true,
-                            command,
-                        },
-                    )
-                })
+                parse_input(&editor.read(cx).text(cx))
             }
             ConfigurationSource::Extension {
                 id,
@@ -211,6 +225,17 @@ fn context_server_input(existing: Option<(ContextServerId, ContextServerCommand)
     )
 }
 
+fn http_context_server_input(id: ContextServerId, endpoint: ContextServerEndpoint) -> String {
+    let mut server = serde_json::json!({
+        "url": endpoint.url,
+        "headers": endpoint.headers.unwrap_or_default(),
+    });
+    if let Some(timeout) = endpoint.timeout {
+        server["timeout"] = timeout.into();
+    }
+    serde_json::to_string_pretty(&serde_json::json!({ id.0.to_string(): server })).unwrap()
+}
+
 fn resolve_context_server_extension(
     id: ContextServerId,
     worktree_store: Entity<WorktreeStore>,
@@ -250,6 +275,8 @@ pub struct ConfigureContextServerModal {
     context_server_store: Entity<ContextServerStore>,
     workspace: WeakEntity<Workspace>,
     source: ConfigurationSource,
+    /// A bearer token for HTTP servers, stored in the system keychain rather than the settings.
+    bearer_token: Entity<SingleLineInput>,
     state: State,
     original_server_id: Option<ContextServerId>,
 }
@@ -310,6 +337,13 @@ impl ConfigureContextServerModal {
                     id: server_id,
                     command,
                 }),
+                ContextServerSettings::Http {
+                    enabled: _,
+                    endpoint,
+                } => Some(ConfigurationTarget::ExistingHttp {
+                    id: server_id,
+                    endpoint,
+                }),
                 ContextServerSettings::Extension { .. } => {
                     match workspace
                         .update(cx, |workspace, cx| {
@@ -348,9 +382,22 @@ impl ConfigureContextServerModal {
                 workspace.toggle_modal(window, cx, |window, cx| Self {
                     context_server_store,
                     workspace: workspace_handle,
+                    bearer_token: cx.new(|cx| {
+                        let input = SingleLineInput::new(
+                            window,
+                            cx,
+                            "Leave empty to keep the stored token",
+                        )
+                        .label("Bearer Token");
+                        input
+                            .editor()
+                            .update(cx, |editor, cx| editor.set_masked(true, cx));
+                        input
+                    }),
                     state: State::Idle,
                     original_server_id: match &target {
-                        ConfigurationTarget::Existing { id, .. } => Some(id.clone()),
+                        ConfigurationTarget::Existing { id, .. }
+                        | ConfigurationTarget::ExistingHttp { id, .. } => Some(id.clone()),
                         ConfigurationTarget::Extension { id, .. } => Some(id.clone()),
                         ConfigurationTarget::New => None,
                     },
@@ -387,6 +434,34 @@ impl ConfigureContextServerModal {
 
         self.state = State::Waiting;
 
+        let bearer_token = self.bearer_token.read(cx).text(cx);
+        match &settings {
+            ContextServerSettings::Http { endpoint, .. } if !bearer_token.is_empty() => {
+                // Store the token before the settings, so that the restarted server sends it.
+                let url = endpoint.url.clone();
+                let credentials_provider = <dyn CredentialsProvider>::global(cx);
+                cx.spawn(async move |this, cx| {
+                    let result = credentials_provider
+                        .write_credentials(&url, "Bearer", bearer_token.as_bytes(), cx)
+                        .await;
+                    this.update(cx, |this, cx| match result {
+                        Ok(()) => this.save_settings(workspace, id, settings, cx),
+                        Err(error) => this.set_error(error.to_string(), cx),
+                    })
+                })
+                .detach_and_log_err(cx);
+            }
+            _ => self.save_settings(workspace, id, settings, cx),
+        }
+    }
+
+    fn save_settings(
+        &mut self,
+        workspace: Entity<Workspace>,
+        id: ContextServerId,
+        settings: ContextServerSettings,
+        cx: &mut Context<Self>,
+    ) {
         let existing_server = self.context_server_store.read(cx).get_running_server(&id);
         if existing_server.is_some() {
             self.context_server_store.update(cx, |store, cx| {
@@ -464,13 +539,26 @@ impl ConfigureContextServerModal {
     }
 }
 
-fn parse_input(text: &str) -> Result<(ContextServerId, ContextServerCommand)> {
+fn parse_input(text: &str) -> Result<(ContextServerId, ContextServerSettings)> {
     let value: serde_json::Value = serde_json_lenient::from_str(text)?;
     let object = value.as_object().context("Expected object")?;
     anyhow::ensure!(object.len() == 1, "Expected exactly one key-value pair");
     let (context_server_name, value) = object.into_iter().next().unwrap();
-    let command: ContextServerCommand = serde_json::from_value(value.clone())?;
-    Ok((ContextServerId(context_server_name.clone().into()), command))
+    let settings = if value.get("url").is_some() {
+        ContextServerSettings::Http {
+            enabled: true,
+            endpoint: serde_json::from_value(value.clone())?,
+        }
+    } else {
+        ContextServerSettings::Custom {
+            enabled: true,
+            command: serde_json::from_value(value.clone())?,
+        }
+    };
+    Ok((
+        ContextServerId(context_server_name.clone().into()),
+        settings,
+    ))
 }
 
 impl ModalView for ConfigureContextServerModal {}
@@ -703,6 +791,20 @@ impl Render for ConfigureContextServerModal {
                         Section::new()
                             .child(self.render_modal_description(window, cx))
                             .child(self.render_modal_content(cx))
+                            .when(self.source.accepts_bearer_token(), |section| {
+                                section.child(
+                                    v_flex()
+                                        .gap_1()
+                                        .child(self.bearer_token.clone())
+                                        .child(
+                                            Label::new(
+                                                "Only sent to HTTP servers, and stored in the system keychain.",
+                                            )
+                                            .size(LabelSize::Small)
+                                            .color(Color::Muted),
+                                        ),
+                                )
+                            })
                             .child(match &self.state {
                                 State::Idle => div(),
                                 State::Waiting => Self::render_waiting_for_context_server(),
@@ -749,6 +851,7 @@ fn wait_for_context_server(
                 _ => {}
             }
         }
+        _ => {}
     });
 
     cx.spawn(async move |_cx| {
@@ -788,3 +891,31 @@ pub(crate) fn default_markdown_style(window: &Window, cx: &App) -> MarkdownStyle
         ..Default::default()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_http_context_server_input() {
+        let endpoint = ContextServerEndpoint {
+            url: r#"https://mcp.example.com/"quoted"\path"#.to_string(),
+            headers: Some(
+                [("X-Team".to_string(), "zed".to_string())]
+                    .into_iter()
+                    .collect(),
+            ),
+            timeout: Some(30_000),
+        };
+        let input = http_context_server_input(ContextServerId("remote".into()), endpoint.clone());
+        let (id, settings) = parse_input(&input).unwrap();
+        assert_eq!(id, ContextServerId("remote".into()));
+        assert_eq!(
+            settings,
+            ContextServerSettings::Http {
+                enabled: true,
+                endpoint,
+            }
+        );
+    }
+}
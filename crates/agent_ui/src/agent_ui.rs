@@ -6,6 +6,7 @@ mod agent_panel;
 mod buffer_codegen;
 mod context_picker;
 mod context_server_configuration;
+mod context_server_sampling;
 mod context_strip;
 mod inline_assistant;
 mod inline_prompt_editor;
@@ -267,6 +268,7 @@ pub fn init(
     agent::init(cx);
     agent_panel::init(cx);
     context_server_configuration::init(language_registry.clone(), fs.clone(), cx);
+    context_server_sampling::init(cx);
     TextThreadEditor::init(cx);
 
     register_slash_commands(cx);
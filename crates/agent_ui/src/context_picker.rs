@@ -78,6 +78,7 @@ pub(crate) enum ContextPickerMode {
     Fetch,
     Thread,
     Rules,
+    Resources,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -115,6 +116,7 @@ impl TryFrom<&str> for ContextPickerMode {
             "fetch" => Ok(Self::Fetch),
             "thread" => Ok(Self::Thread),
             "rule" => Ok(Self::Rules),
+            "resource" => Ok(Self::Resources),
             _ => Err(format!("Invalid context picker mode: {}", value)),
         }
     }
@@ -128,6 +130,7 @@ impl ContextPickerMode {
             Self::Fetch => "fetch",
             Self::Thread => "thread",
             Self::Rules => "rule",
+            Self::Resources => "resource",
         }
     }
 
@@ -138,6 +141,7 @@ impl ContextPickerMode {
             Self::Fetch => "Fetch",
             Self::Thread => "Threads",
             Self::Rules => "Rules",
+            Self::Resources => "MCP Resources",
         }
     }
 
@@ -148,6 +152,7 @@ impl ContextPickerMode {
             Self::Fetch => IconName::ToolWeb,
             Self::Thread => IconName::Thread,
             Self::Rules => RULES_ICON,
+            Self::Resources => IconName::ToolHammer,
         }
     }
 }
@@ -363,6 +368,8 @@ impl ContextPicker {
                         }));
                     }
                 }
+                // Context server resources can only be mentioned in external agent threads.
+                ContextPickerMode::Resources => {}
             },
             ContextPickerEntry::Action(action) => match action {
                 ContextPickerAction::AddSelections => {
@@ -146,6 +146,8 @@ fn search(
             }
         }
 
+        Some(ContextPickerMode::Resources) => Task::ready(Vec::new()),
+
         None => {
             if query.is_empty() {
                 let mut matches = recent_entries
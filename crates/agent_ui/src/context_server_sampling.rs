@@ -0,0 +1,133 @@
+use std::sync::Arc;
+
+use anyhow::{Result, anyhow};
+use context_server::types::{CreateMessageRequest, CreateMessageResult, MessageContent, Role};
+use futures::StreamExt as _;
+use gpui::{AsyncApp, PromptLevel};
+use language_model::{
+    ConfiguredModel, LanguageModel, LanguageModelRegistry, LanguageModelRequest,
+    LanguageModelRequestMessage,
+};
+use project::context_server_store::{self, ContextServerSamplingRequest};
+use ui::prelude::*;
+use workspace::Workspace;
+
+/// The number of characters of the sampled conversation shown when asking for approval.
+const MAX_DETAIL_LEN: usize = 500;
+
+/// Answers the sampling requests of the project's context servers with the default model,
+/// after asking the user for approval.
+pub(crate) fn init(cx: &mut App) {
+    cx.observe_new(|workspace: &mut Workspace, window, cx| {
+        let Some(window) = window else {
+            return;
+        };
+        let context_server_store = workspace.project().read(cx).context_server_store();
+        cx.subscribe_in(&context_server_store, window, |_, _, event, window, cx| {
+            if let context_server_store::Event::SamplingRequested(request) = event {
+                handle_sampling_request(request.clone(), window, cx);
+            }
+        })
+        .detach();
+    })
+    .detach();
+}
+
+fn handle_sampling_request(
+    request: ContextServerSamplingRequest,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let Some(ConfiguredModel { model, .. }) =
+        LanguageModelRegistry::read_global(cx).default_model()
+    else {
+        cx.background_spawn(request.respond(Err(anyhow!("No language model is configured"))))
+            .detach();
+        return;
+    };
+
+    let detail = request
+        .params
+        .messages
+        .iter()
+        .filter_map(|message| match &message.content {
+            MessageContent::Text { text, .. } => Some(text.as_str()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    let answer = window.prompt(
+        PromptLevel::Info,
+        &format!(
+            "Allow the {} MCP server to use {}?",
+            request.server_id,
+            model.name().0
+        ),
+        Some(&util::truncate_and_trailoff(&detail, MAX_DETAIL_LEN)),
+        &["Allow", "Deny"],
+        cx,
+    );
+    cx.spawn(async move |_, cx| {
+        let result = if answer.await == Ok(0) {
+            sample(model, &request.params, cx).await
+        } else {
+            Err(anyhow!("User declined the sampling request"))
+        };
+        request.respond(result).await;
+    })
+    .detach();
+}
+
+async fn sample(
+    model: Arc<dyn LanguageModel>,
+    params: &CreateMessageRequest,
+    cx: &AsyncApp,
+) -> Result<CreateMessageResult> {
+    let mut stream = model
+        .stream_completion_text(sampling_request(params)?, cx)
+        .await?
+        .stream;
+    let mut text = String::new();
+    while let Some(chunk) = stream.next().await {
+        text.push_str(&chunk?);
+    }
+    Ok(CreateMessageResult {
+        role: Role::Assistant,
+        content: MessageContent::Text {
+            text,
+            annotations: None,
+        },
+        model: model.id().0.to_string(),
+        stop_reason: Some("endTurn".into()),
+    })
+}
+
+fn sampling_request(params: &CreateMessageRequest) -> Result<LanguageModelRequest> {
+    let mut messages = Vec::new();
+    if let Some(system_prompt) = &params.system_prompt {
+        messages.push(LanguageModelRequestMessage {
+            role: language_model::Role::System,
+            content: vec![system_prompt.clone().into()],
+            cache: false,
+        });
+    }
+    for message in &params.messages {
+        let MessageContent::Text { text, .. } = &message.content else {
+            return Err(anyhow!("Only text sampling messages are supported"));
+        };
+        messages.push(LanguageModelRequestMessage {
+            role: match message.role {
+                Role::User => language_model::Role::User,
+                Role::Assistant => language_model::Role::Assistant,
+            },
+            content: vec![text.clone().into()],
+            cache: false,
+        });
+    }
+    Ok(LanguageModelRequest {
+        messages,
+        stop: params.stop_sequences.clone().unwrap_or_default(),
+        temperature: params.temperature.map(|temperature| temperature as f32),
+        ..Default::default()
+    })
+}
@@ -875,6 +875,19 @@ impl ContextStore {
                     _ => {}
                 }
             }
+            project::context_server_store::Event::PromptsChanged { server_id } => {
+                if let Some(slash_command_ids) =
+                    self.context_server_slash_command_ids.remove(server_id)
+                {
+                    self.slash_commands.remove(&slash_command_ids);
+                }
+                self.load_context_server_slash_commands(
+                    server_id.clone(),
+                    context_server_store,
+                    cx,
+                );
+            }
+            _ => {}
         }
     }
 
@@ -38,6 +38,10 @@ type ResponseHandler = Box<dyn Send + FnOnce(Result<String, Error>)>;
 type NotificationHandler = Box<dyn Send + FnMut(Value, AsyncApp)>;
 type RequestHandler = Box<dyn Send + FnMut(RequestId, &RawValue, AsyncApp)>;
 
+/// Handles a request sent by the context server, resolving to the JSON result of the request.
+pub type IncomingRequestHandler =
+    Box<dyn 'static + Send + FnMut(Value, AsyncApp) -> Task<Result<Value>>>;
+
 #[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum RequestId {
@@ -51,6 +55,7 @@ pub(crate) struct Client {
     outbound_tx: channel::Sender<String>,
     name: Arc<str>,
     notification_handlers: Arc<Mutex<HashMap<&'static str, NotificationHandler>>>,
+    request_handlers: Arc<Mutex<HashMap<&'static str, RequestHandler>>>,
     response_handlers: Arc<Mutex<Option<HashMap<RequestId, ResponseHandler>>>>,
     #[allow(clippy::type_complexity)]
     #[allow(dead_code)]
@@ -100,7 +105,6 @@ struct AnyResponse<'a> {
 }
 
 #[derive(Serialize, Deserialize)]
-#[allow(dead_code)]
 pub(crate) struct Response<T> {
     pub jsonrpc: &'static str,
     pub id: RequestId,
@@ -113,7 +117,6 @@ pub(crate) struct Response<T> {
 pub(crate) enum CspResult<T> {
     #[serde(rename = "result")]
     Ok(Option<T>),
-    #[allow(dead_code)]
     Error(Option<Error>),
 }
 
@@ -237,6 +240,7 @@ impl Client {
         Ok(Self {
             server_id,
             notification_handlers,
+            request_handlers,
             response_handlers,
             name: server_name,
             next_id: Default::default(),
@@ -274,6 +278,17 @@ impl Client {
                         request.params.unwrap_or(RawValue::NULL),
                         cx.clone(),
                     );
+                } else {
+                    log::debug!("unhandled context server request: {}", request.method);
+                    let response = serde_json::to_string(&Response::<()> {
+                        jsonrpc: JSON_RPC_VERSION,
+                        id: request.id,
+                        value: CspResult::Error(Some(Error {
+                            message: format!("Method not found: {}", request.method),
+                            code: METHOD_NOT_FOUND,
+                        })),
+                    })?;
+                    transport.send(response).await.log_err();
                 }
             } else if let Ok(response) = serde_json::from_str::<AnyResponse>(&message) {
                 if let Some(handlers) = response_handlers.lock().as_mut()
@@ -458,6 +473,41 @@ impl Client {
     ) {
         self.notification_handlers.lock().insert(method, f);
     }
+
+    /// Registers a handler for requests sent by the context server to the client.
+    /// The handler's result, or error, is sent back to the server as the response.
+    pub fn on_request(&self, method: &'static str, mut f: IncomingRequestHandler) {
+        let outbound_tx = self.outbound_tx.clone();
+        self.request_handlers.lock().insert(
+            method,
+            Box::new(move |id, params, cx| {
+                let params = serde_json::from_str(params.get()).unwrap_or(Value::Null);
+                let response = f(params, cx.clone());
+                let outbound_tx = outbound_tx.clone();
+                let respond = async move {
+                    let value = match response.await {
+                        Ok(result) => CspResult::Ok(Some(result)),
+                        Err(error) => CspResult::Error(Some(Error {
+                            message: error.to_string(),
+                            code: INTERNAL_ERROR,
+                        })),
+                    };
+                    let response = serde_json::to_string(&Response {
+                        jsonrpc: JSON_RPC_VERSION,
+                        id,
+                        value,
+                    })?;
+                    outbound_tx.try_send(response)?;
+                    anyhow::Ok(())
+                };
+                cx.foreground_executor().spawn(respond.log_err()).detach();
+            }),
+        );
+    }
+
+    pub fn handles_request(&self, method: &str) -> bool {
+        self.request_handlers.lock().contains_key(method)
+    }
 }
 
 #[derive(Debug)]
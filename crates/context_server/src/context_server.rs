@@ -8,13 +8,17 @@ pub mod types;
 
 use std::path::Path;
 use std::sync::Arc;
+use std::time::Duration;
 use std::{fmt::Display, path::PathBuf};
 
-use anyhow::Result;
+use anyhow::{Context as _, Result};
 use client::Client;
+use credentials_provider::CredentialsProvider;
 use gpui::AsyncApp;
+use http_client::{HttpClient, Url};
 use parking_lot::RwLock;
-pub use settings::ContextServerCommand;
+pub use settings::{ContextServerCommand, ContextServerEndpoint};
+use util::ResultExt as _;
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct ContextServerId(pub Arc<str>);
@@ -27,6 +31,7 @@ impl Display for ContextServerId {
 
 enum ContextServerTransport {
     Stdio(ContextServerCommand, Option<PathBuf>),
+    Http(ContextServerEndpoint, Arc<dyn HttpClient>),
     Custom(Arc<dyn crate::transport::Transport>),
 }
 
@@ -52,6 +57,18 @@ impl ContextServer {
         }
     }
 
+    pub fn http(
+        id: ContextServerId,
+        endpoint: ContextServerEndpoint,
+        http_client: Arc<dyn HttpClient>,
+    ) -> Self {
+        Self {
+            id,
+            client: RwLock::new(None),
+            configuration: ContextServerTransport::Http(endpoint, http_client),
+        }
+    }
+
     pub fn new(id: ContextServerId, transport: Arc<dyn crate::transport::Transport>) -> Self {
         Self {
             id,
@@ -69,7 +86,7 @@ impl ContextServer {
     }
 
     pub async fn start(&self, cx: &AsyncApp) -> Result<()> {
-        self.initialize(self.new_client(cx)?).await
+        self.initialize(self.new_client(cx).await?).await
     }
 
     /// Starts the context server, making sure handlers are registered before initialization happens
@@ -79,16 +96,20 @@ impl ContextServer {
             &'static str,
             Box<dyn 'static + Send + FnMut(serde_json::Value, AsyncApp)>,
         )>,
+        request_handlers: Vec<(&'static str, client::IncomingRequestHandler)>,
         cx: &AsyncApp,
     ) -> Result<()> {
-        let client = self.new_client(cx)?;
+        let client = self.new_client(cx).await?;
         for (method, handler) in notification_handlers {
             client.on_notification(method, handler);
         }
+        for (method, handler) in request_handlers {
+            client.on_request(method, handler);
+        }
         self.initialize(client).await
     }
 
-    fn new_client(&self, cx: &AsyncApp) -> Result<Client> {
+    async fn new_client(&self, cx: &AsyncApp) -> Result<Client> {
         Ok(match &self.configuration {
             ContextServerTransport::Stdio(command, working_directory) => Client::stdio(
                 client::ContextServerId(self.id.0.clone()),
@@ -101,6 +122,30 @@ impl ContextServer {
                 working_directory,
                 cx.clone(),
             )?,
+            ContextServerTransport::Http(endpoint, http_client) => {
+                let url = Url::parse(&endpoint.url)
+                    .with_context(|| format!("invalid context server URL {:?}", endpoint.url))?;
+                let mut headers = endpoint.headers.clone().unwrap_or_default();
+                if !headers
+                    .keys()
+                    .any(|name| name.eq_ignore_ascii_case("authorization"))
+                    && let Some(token) = Self::read_bearer_token(&endpoint.url, cx).await
+                {
+                    headers.insert("Authorization".to_string(), format!("Bearer {token}"));
+                }
+                Client::new(
+                    client::ContextServerId(self.id.0.clone()),
+                    self.id().0,
+                    Arc::new(transport::HttpTransport::new(
+                        http_client.clone(),
+                        url,
+                        headers,
+                        cx,
+                    )),
+                    endpoint.timeout.map(Duration::from_millis),
+                    cx.clone(),
+                )?
+            }
             ContextServerTransport::Custom(transport) => Client::new(
                 client::ContextServerId(self.id.0.clone()),
                 self.id().0,
@@ -111,6 +156,16 @@ impl ContextServer {
         })
     }
 
+    /// Reads the bearer token stored in the system keychain for `url`, if any.
+    async fn read_bearer_token(url: &str, cx: &AsyncApp) -> Option<String> {
+        let credentials_provider = cx.update(|cx| <dyn CredentialsProvider>::global(cx)).ok()?;
+        let (_, token) = credentials_provider
+            .read_credentials(url, cx)
+            .await
+            .log_err()??;
+        String::from_utf8(token).log_err()
+    }
+
     async fn initialize(&self, client: Client) -> Result<()> {
         log::debug!("starting context server {}", self.id);
         let protocol = crate::protocol::ModelContextProtocol::new(client);
@@ -39,7 +39,10 @@ impl ModelContextProtocol {
             protocol_version: types::ProtocolVersion(types::LATEST_PROTOCOL_VERSION.to_string()),
             capabilities: types::ClientCapabilities {
                 experimental: None,
-                sampling: None,
+                sampling: self
+                    .inner
+                    .handles_request(types::requests::CreateMessage::METHOD)
+                    .then(|| serde_json::json!({})),
                 roots: None,
             },
             meta: None,
@@ -81,6 +84,7 @@ pub enum ServerCapability {
     Logging,
     Prompts,
     Resources,
+    ResourceSubscriptions,
     Tools,
 }
 
@@ -92,6 +96,12 @@ impl InitializedContextServerProtocol {
             ServerCapability::Logging => self.initialize.capabilities.logging.is_some(),
             ServerCapability::Prompts => self.initialize.capabilities.prompts.is_some(),
             ServerCapability::Resources => self.initialize.capabilities.resources.is_some(),
+            ServerCapability::ResourceSubscriptions => self
+                .initialize
+                .capabilities
+                .resources
+                .as_ref()
+                .is_some_and(|resources| resources.subscribe == Some(true)),
             ServerCapability::Tools => self.initialize.capabilities.tools.is_some(),
         }
     }
@@ -41,6 +41,8 @@ pub struct FakeTransport {
     >,
     tx: futures::channel::mpsc::UnboundedSender<String>,
     rx: Arc<Mutex<futures::channel::mpsc::UnboundedReceiver<String>>>,
+    /// The responses the client sent to requests initiated by the server.
+    client_responses: parking_lot::Mutex<Vec<serde_json::Value>>,
     executor: BackgroundExecutor,
 }
 
@@ -51,10 +53,20 @@ impl FakeTransport {
             request_handlers: Default::default(),
             tx,
             rx: Arc::new(Mutex::new(rx)),
+            client_responses: Default::default(),
             executor,
         }
     }
 
+    /// Sends a request or notification to the client, as if the server initiated it.
+    pub fn send_to_client(&self, message: serde_json::Value) {
+        self.tx.unbounded_send(message.to_string()).ok();
+    }
+
+    pub fn client_responses(&self) -> Vec<serde_json::Value> {
+        self.client_responses.lock().clone()
+    }
+
     pub fn on_request<T, Fut>(
         mut self,
         handler: impl 'static + Send + Sync + Fn(T::Params) -> Fut,
@@ -101,6 +113,8 @@ impl Transport for FakeTransport {
                 } else {
                     log::debug!("No handler registered for MCP request '{method}'");
                 }
+            } else {
+                self.client_responses.lock().push(msg);
             }
         }
         Ok(())
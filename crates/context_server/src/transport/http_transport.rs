@@ -0,0 +1,733 @@
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{Context as _, Result, anyhow};
+use async_trait::async_trait;
+use collections::HashMap;
+use futures::{AsyncBufReadExt as _, AsyncReadExt as _, Stream, channel::oneshot, io::BufReader};
+use gpui::{AsyncApp, BackgroundExecutor, Task};
+use http_client::{AsyncBody, HttpClient, Method, Request, Response, StatusCode, Url, http};
+use parking_lot::Mutex;
+use serde_json::{Value, json};
+use smol::channel;
+use util::ResultExt as _;
+
+use crate::client::INTERNAL_ERROR;
+use crate::transport::Transport;
+use crate::types::{Notification as _, Request as _, notifications, requests};
+
+const SESSION_ID_HEADER: &str = "Mcp-Session-Id";
+const LAST_EVENT_ID_HEADER: &str = "Last-Event-ID";
+const EVENT_STREAM: &str = "text/event-stream";
+const MAX_STREAM_RECONNECTS: usize = 5;
+const STREAM_RECONNECT_DELAY: Duration = Duration::from_secs(1);
+
+/// Talks to a context server over MCP's Streamable HTTP transport, falling back to the older
+/// HTTP with SSE transport for servers that reject the initial POST.
+pub struct HttpTransport {
+    outbound_tx: channel::Sender<String>,
+    inbound_rx: channel::Receiver<String>,
+    err_rx: channel::Receiver<String>,
+    state: Arc<HttpState>,
+    executor: BackgroundExecutor,
+    _send_task: Task<()>,
+}
+
+enum HttpMode {
+    StreamableHttp,
+    /// The legacy transport, where messages are posted to the endpoint announced by the
+    /// server and every response arrives over a single SSE stream.
+    Sse {
+        endpoint: Url,
+    },
+}
+
+struct HttpState {
+    http_client: Arc<dyn HttpClient>,
+    url: Url,
+    headers: HashMap<String, String>,
+    /// The session the server assigned when initializing, sent back with every request.
+    session_id: Mutex<Option<String>>,
+    /// The `initialize` request, replayed to start a new session when the server expires ours.
+    initialize_message: Mutex<Option<String>>,
+    /// Held while starting a new session, so that concurrent requests only start one.
+    reinitialize_lock: smol::lock::Mutex<()>,
+    /// The id of the last event received over SSE, used to resume the stream after a disconnect.
+    last_event_id: Mutex<Option<String>>,
+    inbound_tx: channel::Sender<String>,
+    err_tx: channel::Sender<String>,
+}
+
+impl HttpTransport {
+    pub fn new(
+        http_client: Arc<dyn HttpClient>,
+        url: Url,
+        headers: HashMap<String, String>,
+        cx: &AsyncApp,
+    ) -> Self {
+        let (outbound_tx, outbound_rx) = channel::unbounded::<String>();
+        let (inbound_tx, inbound_rx) = channel::unbounded::<String>();
+        let (err_tx, err_rx) = channel::unbounded::<String>();
+        let executor = cx.background_executor().clone();
+        let state = Arc::new(HttpState {
+            http_client,
+            url,
+            headers,
+            session_id: Mutex::new(None),
+            initialize_message: Mutex::new(None),
+            reinitialize_lock: smol::lock::Mutex::new(()),
+            last_event_id: Mutex::new(None),
+            inbound_tx,
+            err_tx,
+        });
+        let _send_task = executor.spawn(Self::send_messages(
+            state.clone(),
+            outbound_rx,
+            executor.clone(),
+        ));
+        Self {
+            outbound_tx,
+            inbound_rx,
+            err_rx,
+            state,
+            executor,
+            _send_task,
+        }
+    }
+
+    /// Posts the first message on its own, so that the session id assigned in response to
+    /// `initialize` is known before anything else is sent. Requests are then posted
+    /// concurrently, so that a slow response doesn't hold up the others, while notifications
+    /// and responses are posted in order.
+    async fn send_messages(
+        state: Arc<HttpState>,
+        outbound_rx: channel::Receiver<String>,
+        executor: BackgroundExecutor,
+    ) {
+        let mut mode = None;
+        // Dropped together with this task when the transport is dropped.
+        let mut _stream_task = None;
+        while let Ok(message) = outbound_rx.recv().await {
+            log::trace!("outgoing message: {}", message);
+            let url = match &mode {
+                Some(HttpMode::StreamableHttp) => state.url.clone(),
+                Some(HttpMode::Sse { endpoint }) => endpoint.clone(),
+                None => {
+                    match state.connect(&message, &executor).await {
+                        Ok((connected_mode, stream_task)) => {
+                            mode = Some(connected_mode);
+                            _stream_task = Some(stream_task);
+                        }
+                        Err(error) => state.fail(&message, error).await,
+                    }
+                    continue;
+                }
+            };
+            let is_request = request_id(&message).is_some();
+            let post = {
+                let state = state.clone();
+                let executor = executor.clone();
+                async move {
+                    if let Err(error) = state.post(&url, &message, &executor).await {
+                        state.fail(&message, error).await;
+                    }
+                }
+            };
+            if is_request {
+                executor.spawn(post).detach();
+            } else {
+                post.await;
+            }
+        }
+    }
+}
+
+impl HttpState {
+    /// Sends the first message, detecting which transport the server speaks, and starts
+    /// listening for messages the server sends on its own.
+    async fn connect(
+        self: &Arc<Self>,
+        message: &str,
+        executor: &BackgroundExecutor,
+    ) -> Result<(HttpMode, Task<()>)> {
+        if serde_json::from_str::<Value>(message)
+            .is_ok_and(|message| message["method"] == requests::Initialize::METHOD)
+        {
+            *self.initialize_message.lock() = Some(message.to_string());
+        }
+        let response = self.send_post(&self.url, message).await?;
+        let status = response.status();
+        if status.is_client_error()
+            && status != StatusCode::UNAUTHORIZED
+            && status != StatusCode::FORBIDDEN
+        {
+            log::debug!(
+                "context server at {} responded with {status}, falling back to HTTP with SSE",
+                self.url
+            );
+            let (endpoint, stream_task) = self.open_legacy_stream(executor).await?;
+            self.post(&endpoint, message, executor).await?;
+            return Ok((HttpMode::Sse { endpoint }, stream_task));
+        }
+
+        self.handle_response(response, executor).await?;
+        let stream_task = executor.spawn(self.clone().listen(executor.clone()));
+        Ok((HttpMode::StreamableHttp, stream_task))
+    }
+
+    async fn open_legacy_stream(
+        self: &Arc<Self>,
+        executor: &BackgroundExecutor,
+    ) -> Result<(Url, Task<()>)> {
+        let request = self
+            .request(Method::GET, &self.url, EVENT_STREAM)
+            .body(AsyncBody::default())?;
+        let response = self.http_client.send(request).await?;
+        if !response.status().is_success() {
+            return Err(error_for_response(response).await);
+        }
+
+        let (endpoint_tx, endpoint_rx) = oneshot::channel();
+        let this = self.clone();
+        let stream_task = executor.spawn(async move {
+            this.read_events(response.into_body(), Some(endpoint_tx))
+                .await
+                .log_err();
+        });
+        let endpoint = endpoint_rx
+            .await
+            .context("context server closed the SSE stream before announcing its endpoint")?;
+        let endpoint = self
+            .url
+            .join(&endpoint)
+            .with_context(|| format!("invalid endpoint {endpoint:?}"))?;
+        Ok((endpoint, stream_task))
+    }
+
+    /// Keeps a stream open for requests and notifications initiated by the server, resuming
+    /// it from the last received event when the connection drops.
+    async fn listen(self: Arc<Self>, executor: BackgroundExecutor) {
+        let mut reconnects = 0;
+        loop {
+            let mut request = self.request(Method::GET, &self.url, EVENT_STREAM);
+            if let Some(last_event_id) = self.last_event_id.lock().clone() {
+                request = request.header(LAST_EVENT_ID_HEADER, last_event_id);
+            }
+            let Some(request) = request.body(AsyncBody::default()).log_err() else {
+                return;
+            };
+            match self.http_client.send(request).await {
+                // The server doesn't offer a stream of its own.
+                Ok(response) if response.status() == StatusCode::METHOD_NOT_ALLOWED => return,
+                Ok(response) if response.status().is_success() => {
+                    reconnects = 0;
+                    self.read_events(response.into_body(), None).await.log_err();
+                }
+                Ok(response) => {
+                    log::debug!(
+                        "failed to open context server stream: {}",
+                        error_for_response(response).await
+                    );
+                }
+                Err(error) => log::debug!("failed to open context server stream: {error}"),
+            }
+
+            reconnects += 1;
+            if reconnects > MAX_STREAM_RECONNECTS || self.inbound_tx.is_closed() {
+                return;
+            }
+            executor.timer(STREAM_RECONNECT_DELAY).await;
+        }
+    }
+
+    async fn post(
+        self: &Arc<Self>,
+        url: &Url,
+        message: &str,
+        executor: &BackgroundExecutor,
+    ) -> Result<()> {
+        let session_id = self.session_id.lock().clone();
+        let mut response = self.send_post(url, message).await?;
+        if response.status() == StatusCode::NOT_FOUND
+            && let Some(session_id) = session_id
+        {
+            self.reinitialize(&session_id).await?;
+            response = self.send_post(url, message).await?;
+        }
+        self.handle_response(response, executor).await
+    }
+
+    /// Starts a new session once the server responded that ours expired, replaying the
+    /// handshake without forwarding the server's response, which the client already got when
+    /// initializing the first session.
+    async fn reinitialize(&self, expired_session_id: &str) -> Result<()> {
+        let _lock = self.reinitialize_lock.lock().await;
+        // Another request may have started a new session while this one waited.
+        if self.session_id.lock().as_deref() != Some(expired_session_id) {
+            return Ok(());
+        }
+        log::info!(
+            "context server session at {} expired, starting a new one",
+            self.url
+        );
+        *self.session_id.lock() = None;
+        let initialize = self
+            .initialize_message
+            .lock()
+            .clone()
+            .context("context server session expired before initializing")?;
+        let response = self.send_post(&self.url, &initialize).await?;
+        self.update_session_id(&response);
+        if !response.status().is_success() {
+            return Err(error_for_response(response).await);
+        }
+        let mut body = Vec::new();
+        response.into_body().read_to_end(&mut body).await?;
+
+        let initialized = json!({
+            "jsonrpc": "2.0",
+            "method": notifications::Initialized::METHOD,
+        });
+        let response = self.send_post(&self.url, &initialized.to_string()).await?;
+        if !response.status().is_success() {
+            return Err(error_for_response(response).await);
+        }
+        Ok(())
+    }
+
+    async fn send_post(&self, url: &Url, message: &str) -> Result<Response<AsyncBody>> {
+        let request = self
+            .request(
+                Method::POST,
+                url,
+                &format!("application/json, {EVENT_STREAM}"),
+            )
+            .header("Content-Type", "application/json")
+            .body(AsyncBody::from(message.to_string()))?;
+        self.http_client.send(request).await
+    }
+
+    fn request(&self, method: Method, url: &Url, accept: &str) -> http::request::Builder {
+        let mut request = Request::builder()
+            .method(method)
+            .uri(url.as_str())
+            .header("Accept", accept);
+        for (name, value) in &self.headers {
+            request = request.header(name.as_str(), value.as_str());
+        }
+        if let Some(session_id) = self.session_id.lock().as_deref() {
+            request = request.header(SESSION_ID_HEADER, session_id);
+        }
+        request
+    }
+
+    async fn handle_response(
+        self: &Arc<Self>,
+        response: Response<AsyncBody>,
+        executor: &BackgroundExecutor,
+    ) -> Result<()> {
+        self.update_session_id(&response);
+        let status = response.status();
+        if !status.is_success() {
+            return Err(error_for_response(response).await);
+        }
+        if status == StatusCode::ACCEPTED {
+            return Ok(());
+        }
+
+        let is_event_stream = response
+            .headers()
+            .get("Content-Type")
+            .and_then(|value| value.to_str().ok())
+            .is_some_and(|content_type| content_type.starts_with(EVENT_STREAM));
+        if is_event_stream {
+            // Responses may take a while to stream in, and shouldn't hold up other requests.
+            let this = self.clone();
+            executor
+                .spawn(async move {
+                    this.read_events(response.into_body(), None).await.log_err();
+                })
+                .detach();
+            return Ok(());
+        }
+
+        let mut body = String::new();
+        response.into_body().read_to_string(&mut body).await?;
+        if body.trim().is_empty() {
+            return Ok(());
+        }
+        match serde_json::from_str::<Value>(&body)? {
+            Value::Array(messages) => {
+                for message in messages {
+                    self.inbound_tx.send(message.to_string()).await?;
+                }
+            }
+            _ => self.inbound_tx.send(body).await?,
+        }
+        Ok(())
+    }
+
+    fn update_session_id(&self, response: &Response<AsyncBody>) {
+        if let Some(session_id) = response
+            .headers()
+            .get(SESSION_ID_HEADER)
+            .and_then(|value| value.to_str().ok())
+        {
+            *self.session_id.lock() = Some(session_id.to_string());
+        }
+    }
+
+    async fn read_events(
+        &self,
+        body: AsyncBody,
+        mut endpoint_tx: Option<oneshot::Sender<String>>,
+    ) -> Result<()> {
+        let mut reader = BufReader::new(body);
+        let mut parser = SseParser::default();
+        let mut line = String::new();
+        loop {
+            line.clear();
+            if reader.read_line(&mut line).await? == 0 {
+                return Ok(());
+            }
+            let Some(event) = parser.push_line(line.trim_end_matches(['\r', '\n'])) else {
+                continue;
+            };
+            if let Some(id) = event.id {
+                *self.last_event_id.lock() = Some(id);
+            }
+            match event.event.as_deref() {
+                None | Some("message") if !event.data.is_empty() => {
+                    self.inbound_tx.send(event.data).await?;
+                }
+                Some("endpoint") => {
+                    if let Some(endpoint_tx) = endpoint_tx.take() {
+                        endpoint_tx.send(event.data).ok();
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Reports a message that couldn't be delivered, answering requests with an error so that
+    /// callers don't wait for a response that will never come.
+    async fn fail(&self, message: &str, error: anyhow::Error) {
+        log::error!("failed to send message to context server: {error:#}");
+        match request_id(message) {
+            Some(id) => {
+                let response = json!({
+                    "jsonrpc": "2.0",
+                    "id": id,
+                    "error": { "code": INTERNAL_ERROR, "message": format!("{error:#}") },
+                });
+                self.inbound_tx.send(response.to_string()).await.ok();
+            }
+            None => {
+                self.err_tx.send(format!("{error:#}")).await.ok();
+            }
+        }
+    }
+}
+
+/// Returns the id of `message` if it's a request, rather than a notification or a response.
+fn request_id(message: &str) -> Option<Value> {
+    serde_json::from_str::<Value>(message)
+        .ok()
+        .filter(|message| message.get("method").is_some())
+        .and_then(|message| message.get("id").cloned())
+}
+
+async fn error_for_response(response: Response<AsyncBody>) -> anyhow::Error {
+    let status = response.status();
+    let mut body = String::new();
+    response.into_body().read_to_string(&mut body).await.ok();
+    anyhow!("context server responded with {status}: {}", body.trim())
+}
+
+#[async_trait]
+impl Transport for HttpTransport {
+    async fn send(&self, message: String) -> Result<()> {
+        Ok(self.outbound_tx.send(message).await?)
+    }
+
+    fn receive(&self) -> Pin<Box<dyn Stream<Item = String> + Send>> {
+        Box::pin(self.inbound_rx.clone())
+    }
+
+    fn receive_err(&self) -> Pin<Box<dyn Stream<Item = String> + Send>> {
+        Box::pin(self.err_rx.clone())
+    }
+}
+
+impl Drop for HttpTransport {
+    fn drop(&mut self) {
+        // Let the server release the session's resources.
+        if self.state.session_id.lock().is_none() {
+            return;
+        }
+        let Some(request) = self
+            .state
+            .request(Method::DELETE, &self.state.url, "application/json")
+            .body(AsyncBody::default())
+            .log_err()
+        else {
+            return;
+        };
+        let http_client = self.state.http_client.clone();
+        self.executor
+            .spawn(async move {
+                http_client.send(request).await.log_err();
+            })
+            .detach();
+    }
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct SseEvent {
+    event: Option<String>,
+    data: String,
+    id: Option<String>,
+}
+
+/// Assembles server-sent events from the lines of an `text/event-stream` body.
+#[derive(Default)]
+struct SseParser {
+    event: Option<String>,
+    data: Vec<String>,
+    id: Option<String>,
+}
+
+impl SseParser {
+    /// Returns the event completed by `line`, if any.
+    fn push_line(&mut self, line: &str) -> Option<SseEvent> {
+        if line.is_empty() {
+            if self.data.is_empty() && self.event.is_none() && self.id.is_none() {
+                return None;
+            }
+            return Some(SseEvent {
+                event: self.event.take(),
+                data: std::mem::take(&mut self.data).join("\n"),
+                id: self.id.take(),
+            });
+        }
+        if line.starts_with(':') {
+            return None;
+        }
+        let (field, value) = line.split_once(':').unwrap_or((line, ""));
+        let value = value.strip_prefix(' ').unwrap_or(value);
+        match field {
+            "event" => self.event = Some(value.to_string()),
+            "data" => self.data.push(value.to_string()),
+            "id" => self.id = Some(value.to_string()),
+            _ => {}
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+    use futures::StreamExt as _;
+    use gpui::TestAppContext;
+    use http_client::FakeHttpClient;
+
+    use super::*;
+
+    fn request(id: u64, method: &str) -> String {
+        json!({ "jsonrpc": "2.0", "id": id, "method": method }).to_string()
+    }
+
+    async fn read_message(request: Request<AsyncBody>) -> Value {
+        let mut body = String::new();
+        request.into_body().read_to_string(&mut body).await.unwrap();
+        serde_json::from_str(&body).unwrap()
+    }
+
+    fn json_response(message: &Value, session_id: Option<String>) -> Response<AsyncBody> {
+        let mut response = Response::builder()
+            .status(StatusCode::OK)
+            .header("Content-Type", "application/json");
+        if let Some(session_id) = session_id {
+            response = response.header(SESSION_ID_HEADER, session_id);
+        }
+        let body = json!({ "jsonrpc": "2.0", "id": message["id"], "result": {} });
+        response.body(AsyncBody::from(body.to_string())).unwrap()
+    }
+
+    fn status_response(status: StatusCode) -> Response<AsyncBody> {
+        Response::builder()
+            .status(status)
+            .body(AsyncBody::default())
+            .unwrap()
+    }
+
+    async fn next_response_id(inbound: &mut Pin<Box<dyn Stream<Item = String> + Send>>) -> Value {
+        let message = inbound.next().await.unwrap();
+        serde_json::from_str::<Value>(&message).unwrap()["id"].clone()
+    }
+
+    #[gpui::test]
+    async fn test_requests_are_posted_concurrently(cx: &mut TestAppContext) {
+        let (release_tx, release_rx) = oneshot::channel::<()>();
+        let release_rx = Arc::new(Mutex::new(Some(release_rx)));
+        let http_client = FakeHttpClient::create(move |request| {
+            let release_rx = release_rx.clone();
+            async move {
+                if request.method() != Method::POST {
+                    return Ok(status_response(StatusCode::METHOD_NOT_ALLOWED));
+                }
+                let message = read_message(request).await;
+                if message["method"] == "slow" {
+                    let release_rx = release_rx.lock().take();
+                    if let Some(release_rx) = release_rx {
+                        release_rx.await.ok();
+                    }
+                }
+                Ok(json_response(&message, None))
+            }
+        });
+        let transport = HttpTransport::new(
+            http_client,
+            Url::parse("http://test.example/mcp").unwrap(),
+            HashMap::default(),
+            &cx.to_async(),
+        );
+        let mut inbound = transport.receive();
+
+        transport.send(request(0, "initialize")).await.unwrap();
+        assert_eq!(next_response_id(&mut inbound).await, json!(0));
+
+        transport.send(request(1, "slow")).await.unwrap();
+        transport.send(request(2, "fast")).await.unwrap();
+        assert_eq!(
+            next_response_id(&mut inbound).await,
+            json!(2),
+            "a slow response shouldn't hold up the requests sent after it"
+        );
+        release_tx.send(()).unwrap();
+        assert_eq!(next_response_id(&mut inbound).await, json!(1));
+    }
+
+    #[gpui::test]
+    async fn test_expired_session_is_reinitialized(cx: &mut TestAppContext) {
+        let sessions_started = Arc::new(AtomicUsize::new(0));
+        let first_session_expired = Arc::new(AtomicBool::new(false));
+        let posted_methods = Arc::new(Mutex::new(Vec::new()));
+        let http_client = FakeHttpClient::create({
+            let sessions_started = sessions_started.clone();
+            let first_session_expired = first_session_expired.clone();
+            let posted_methods = posted_methods.clone();
+            move |request| {
+                let sessions_started = sessions_started.clone();
+                let first_session_expired = first_session_expired.clone();
+                let posted_methods = posted_methods.clone();
+                async move {
+                    if request.method() == Method::DELETE {
+                        return Ok(status_response(StatusCode::OK));
+                    }
+                    if request.method() != Method::POST {
+                        return Ok(status_response(StatusCode::METHOD_NOT_ALLOWED));
+                    }
+                    let session_id = request
+                        .headers()
+                        .get(SESSION_ID_HEADER)
+                        .and_then(|value| value.to_str().ok())
+                        .map(ToString::to_string);
+                    let message = read_message(request).await;
+                    let method = message["method"].as_str().unwrap_or_default().to_string();
+                    posted_methods.lock().push(method.clone());
+
+                    if method == "initialize" {
+                        let session = sessions_started.fetch_add(1, Ordering::SeqCst) + 1;
+                        return Ok(json_response(&message, Some(format!("session-{session}"))));
+                    }
+                    if session_id.as_deref() == Some("session-1")
+                        && first_session_expired.load(Ordering::SeqCst)
+                    {
+                        return Ok(status_response(StatusCode::NOT_FOUND));
+                    }
+                    if message.get("id").is_none() {
+                        return Ok(status_response(StatusCode::ACCEPTED));
+                    }
+                    Ok(json_response(&message, None))
+                }
+            }
+        });
+        let transport = HttpTransport::new(
+            http_client,
+            Url::parse("http://test.example/mcp").unwrap(),
+            HashMap::default(),
+            &cx.to_async(),
+        );
+        let mut inbound = transport.receive();
+
+        transport.send(request(0, "initialize")).await.unwrap();
+        assert_eq!(next_response_id(&mut inbound).await, json!(0));
+        transport
+            .send(json!({ "jsonrpc": "2.0", "method": "notifications/initialized" }).to_string())
+            .await
+            .unwrap();
+
+        first_session_expired.store(true, Ordering::SeqCst);
+        transport.send(request(1, "tools/list")).await.unwrap();
+        assert_eq!(
+            next_response_id(&mut inbound).await,
+            json!(1),
+            "the request should be retried in a new session, without forwarding its handshake"
+        );
+        assert_eq!(sessions_started.load(Ordering::SeqCst), 2);
+        assert_eq!(
+            transport.state.session_id.lock().as_deref(),
+            Some("session-2")
+        );
+        assert_eq!(
+            *posted_methods.lock(),
+            [
+                "initialize",
+                "notifications/initialized",
+                "tools/list",
+                "initialize",
+                "notifications/initialized",
+                "tools/list",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_sse_parser() {
+        let mut parser = SseParser::default();
+        let events = [
+            ": keep-alive",
+            "",
+            "event: endpoint",
+            "data: /messages?session=1",
+            "",
+            "id: 42",
+            "data: {\"jsonrpc\":",
+            "data:\"2.0\"}",
+            "",
+        ]
+        .into_iter()
+        .filter_map(|line| parser.push_line(line))
+        .collect::<Vec<_>>();
+        assert_eq!(
+            events,
+            [
+                SseEvent {
+                    event: Some("endpoint".into()),
+                    data: "/messages?session=1".into(),
+                    id: None,
+                },
+                SseEvent {
+                    event: None,
+                    data: "{\"jsonrpc\":\n\"2.0\"}".into(),
+                    id: Some("42".into()),
+                },
+            ]
+        );
+    }
+}
@@ -991,6 +991,12 @@ impl RunningState {
                 let Some(task) = task_template.resolve_task("debug-build-task", &task_context) else {
                     anyhow::bail!("Could not resolve task variables within a debug scenario");
                 };
+                let task = weak_workspace
+                    .update_in(cx, |workspace, window, cx| {
+                        workspace.resolve_task_inputs(task, "debug-build-task", window, cx)
+                    })?
+                    .await
+                    .context("The inputs of the build task were not provided")?;
 
                 let locator_name = if let Some(locator_name) = locator_name {
                     extra_config = config.clone();
@@ -1204,6 +1210,7 @@ impl RunningState {
             show_summary: false,
             show_command: false,
             show_rerun: false,
+            problem_matcher: Vec::new(),
         };
 
         let workspace = self.workspace.clone();
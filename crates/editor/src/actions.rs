@@ -496,6 +496,10 @@ actions!(
         GoToParentModule,
         /// Goes to the previous change in the file.
         GoToPreviousChange,
+        /// Goes to the direct subtypes of the type at cursor.
+        GoToSubtype,
+        /// Goes to the direct supertypes of the type at cursor.
+        GoToSupertype,
         /// Goes to the type definition of the symbol at cursor.
         GoToTypeDefinition,
         /// Goes to type definition in a split pane.
@@ -74,6 +74,16 @@ use wrap_map::{WrapMap, WrapSnapshot};
 
 pub use crate::display_map::{fold_map::FoldMap, inlay_map::InlayMap, tab_map::TabMap};
 
+/// A foldable range reported by a language server.
+#[derive(Clone, Debug)]
+pub struct LspFold {
+    pub range: Range<text::Anchor>,
+    /// Replaces the default placeholder, to show the collapsed text reported by the server.
+    pub placeholder: Option<FoldPlaceholder>,
+}
+
+type LspFolds = Arc<HashMap<BufferId, Arc<[LspFold]>>>;
+
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum FoldStatus {
     Folded,
@@ -82,6 +92,9 @@ pub enum FoldStatus {
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum HighlightKey {
+    /// Language server semantic tokens, ordered first so that every other highlight is layered
+    /// over them.
+    SemanticToken(usize),
     Type(TypeId),
     TypePlus(TypeId, usize),
 }
@@ -117,6 +130,9 @@ pub struct DisplayMap {
     inlay_highlights: InlayHighlights,
     /// A container for explicitly foldable ranges, which supersede indentation based fold range suggestions.
     crease_map: CreaseMap,
+    /// Foldable ranges reported by language servers, which supersede the indentation based fold
+    /// range suggestions of their buffers.
+    lsp_folds: LspFolds,
     pub(crate) fold_placeholder: FoldPlaceholder,
     pub clip_at_line_ends: bool,
     pub(crate) masked: bool,
@@ -157,6 +173,7 @@ impl DisplayMap {
             wrap_map,
             block_map,
             crease_map,
+            lsp_folds: Default::default(),
             fold_placeholder,
             diagnostics_max_severity,
             text_highlights: Default::default(),
@@ -187,6 +204,7 @@ impl DisplayMap {
             block_snapshot,
             diagnostics_max_severity: self.diagnostics_max_severity,
             crease_snapshot: self.crease_map.snapshot(),
+            lsp_folds: self.lsp_folds.clone(),
             text_highlights: self.text_highlights.clone(),
             inlay_highlights: self.inlay_highlights.clone(),
             clip_at_line_ends: self.clip_at_line_ends,
@@ -489,6 +507,30 @@ impl DisplayMap {
         self.text_highlights.insert(key, Arc::new((style, ranges)));
     }
 
+    /// Replaces all semantic token highlights, one entry per distinct style.
+    pub(crate) fn set_semantic_token_highlights(
+        &mut self,
+        highlights: Vec<(HighlightStyle, Vec<Range<Anchor>>)>,
+    ) {
+        self.text_highlights
+            .retain(|key, _| !matches!(key, HighlightKey::SemanticToken(_)));
+        for (ix, highlight) in highlights.into_iter().enumerate() {
+            self.text_highlights
+                .insert(HighlightKey::SemanticToken(ix), Arc::new(highlight));
+        }
+    }
+
+    /// Replaces the language server folds of the buffer, which fall back to indentation based
+    /// folds when empty.
+    pub(crate) fn set_lsp_folds(&mut self, buffer_id: BufferId, folds: Vec<LspFold>) {
+        let lsp_folds = Arc::make_mut(&mut self.lsp_folds);
+        if folds.is_empty() {
+            lsp_folds.remove(&buffer_id);
+        } else {
+            lsp_folds.insert(buffer_id, folds.into());
+        }
+    }
+
     pub(crate) fn highlight_inlays(
         &mut self,
         type_id: TypeId,
@@ -765,6 +807,7 @@ pub struct DisplaySnapshot {
     pub buffer_snapshot: MultiBufferSnapshot,
     pub fold_snapshot: FoldSnapshot,
     pub crease_snapshot: CreaseSnapshot,
+    lsp_folds: LspFolds,
     inlay_snapshot: InlaySnapshot,
     tab_snapshot: TabSnapshot,
     wrap_snapshot: WrapSnapshot,
@@ -1270,6 +1313,57 @@ impl DisplaySnapshot {
             .unwrap_or(false)
     }
 
+    /// Whether a language server or indentation based fold range suggestion starts on the row.
+    pub fn starts_fold(&self, buffer_row: MultiBufferRow) -> bool {
+        match self.lsp_fold_crease_for_buffer_row(buffer_row) {
+            Some(crease) => crease.is_some(),
+            None => self.starts_indent(buffer_row),
+        }
+    }
+
+    /// Returns the crease of the language server fold starting on the row, or `None` when the
+    /// row's buffer has no language server folds and indentation should be used instead.
+    fn lsp_fold_crease_for_buffer_row(
+        &self,
+        buffer_row: MultiBufferRow,
+    ) -> Option<Option<Crease<Point>>> {
+        let (buffer, line_range) = self.buffer_snapshot.buffer_line_for_row(buffer_row)?;
+        let folds = self.lsp_folds.get(&buffer.remote_id())?;
+        let row = line_range.start.row;
+        let row_of = |anchor: &text::Anchor| buffer.summary_for_anchor::<Point>(anchor).row;
+
+        let start_ix = folds.partition_point(|fold| row_of(&fold.range.start) < row);
+        let Some(fold) = folds[start_ix..]
+            .iter()
+            .take_while(|fold| row_of(&fold.range.start) == row)
+            .find(|fold| row_of(&fold.range.end) > row)
+        else {
+            return Some(None);
+        };
+
+        // The folded lines must all be part of the same excerpt.
+        let end_row = MultiBufferRow(buffer_row.0 + row_of(&fold.range.end) - row);
+        let ends_in_excerpt = self
+            .buffer_snapshot
+            .buffer_line_for_row(end_row)
+            .is_some_and(|(end_buffer, end_line_range)| {
+                end_buffer.remote_id() == buffer.remote_id()
+                    && end_line_range.start.row == row_of(&fold.range.end)
+            });
+        if !ends_in_excerpt {
+            return Some(None);
+        }
+
+        let start = Point::new(buffer_row.0, self.buffer_snapshot.line_len(buffer_row));
+        let end = Point::new(end_row.0, self.buffer_snapshot.line_len(end_row));
+        Some(Some(Crease::simple(
+            start..end,
+            fold.placeholder
+                .clone()
+                .unwrap_or_else(|| self.fold_placeholder.clone()),
+        )))
+    }
+
     pub fn crease_for_buffer_row(&self, buffer_row: MultiBufferRow) -> Option<Crease<Point>> {
         let start = MultiBufferPoint::new(buffer_row.0, self.buffer_snapshot.line_len(buffer_row));
         if let Some(crease) = self
@@ -1306,6 +1400,8 @@ impl DisplaySnapshot {
                     render_toggle: render_toggle.clone(),
                 }),
             }
+        } else if let Some(crease) = self.lsp_fold_crease_for_buffer_row(buffer_row) {
+            crease.filter(|_| !self.is_line_folded(buffer_row))
         } else if self.starts_indent(MultiBufferRow(start.row))
             && !self.is_line_folded(MultiBufferRow(start.row))
         {
@@ -20,6 +20,7 @@ pub mod display_map;
 mod editor_settings;
 mod editor_settings_controls;
 mod element;
+mod folding_ranges;
 mod git;
 mod highlight_matching_bracket;
 mod hover_links;
@@ -37,7 +38,10 @@ mod persistence;
 mod proposed_changes_editor;
 mod rust_analyzer_ext;
 pub mod scroll;
+mod selection_ranges;
 mod selections_collection;
+mod semantic_tokens;
+mod snippet_variables;
 pub mod tasks;
 
 #[cfg(test)]
@@ -148,9 +152,9 @@ use parking_lot::Mutex;
 use persistence::DB;
 use project::{
     BreakpointWithPosition, CodeAction, Completion, CompletionDisplayOptions, CompletionIntent,
-    CompletionResponse, CompletionSource, DisableAiSettings, DocumentHighlight, InlayHint,
-    Location, LocationLink, PrepareRenameResponse, Project, ProjectItem, ProjectPath,
-    ProjectTransaction, TaskSourceKind,
+    CompletionResponse, CompletionSource, DisableAiSettings, DocumentHighlight, DocumentLink,
+    InlayHint, Location, LocationLink, PrepareRenameResponse, Project, ProjectItem, ProjectPath,
+    ProjectTransaction, TaskSourceKind, TypeHierarchyDirection,
     debugger::{
         breakpoint_store::{
             Breakpoint, BreakpointEditAction, BreakpointSessionState, BreakpointState,
@@ -168,10 +172,12 @@ use scroll::{Autoscroll, OngoingScroll, ScrollAnchor, ScrollManager};
 use selections_collection::{
     MutableSelectionsCollection, SelectionsCollection, resolve_selections,
 };
+use semantic_tokens::SemanticTokenHighlights;
 use serde::{Deserialize, Serialize};
 use settings::{GitGutterSetting, Settings, SettingsLocation, SettingsStore, update_settings_file};
 use smallvec::{SmallVec, smallvec};
 use snippet::Snippet;
+use snippet_variables::resolve_snippet_variable;
 use std::{
     any::{Any, TypeId},
     borrow::Cow,
@@ -1150,6 +1156,8 @@ pub struct Editor {
     gutter_breakpoint_indicator: (Option<PhantomBreakpointIndicator>, Option<Task<()>>),
     hovered_diff_hunk_row: Option<DisplayRow>,
     pull_diagnostics_task: Task<()>,
+    selection_ranges_task: Task<()>,
+    pending_selection_range_expansions: usize,
     in_project_search: bool,
     previous_search_ranges: Option<Arc<[Range<Anchor>]>>,
     breadcrumb_header: Option<String>,
@@ -1173,6 +1181,8 @@ pub struct Editor {
     selection_drag_state: SelectionDragState,
     next_color_inlay_id: usize,
     colors: Option<LspColorData>,
+    semantic_token_highlights: Option<SemanticTokenHighlights>,
+    folding_range_tasks: HashMap<BufferId, Task<()>>,
     folding_newlines: Task<()>,
     pub lookup_key: Option<Box<dyn Any + Send + Sync>>,
 }
@@ -1506,6 +1516,15 @@ struct SnippetState {
     ranges: Vec<Vec<Range<Anchor>>>,
     active_index: usize,
     choices: Vec<Option<Vec<String>>>,
+    transformations: Vec<Vec<SnippetTransformation>>,
+}
+
+/// A mirror of a snippet tabstop whose text is transformed with a regular expression.
+struct SnippetTransformation {
+    /// The tabstop range the mirror is computed from, in the same snippet insertion.
+    source: Range<Anchor>,
+    target: Range<Anchor>,
+    transformation: snippet::Transformation,
 }
 
 #[doc(hidden)]
@@ -1599,6 +1618,8 @@ pub enum GotoDefinitionKind {
     Declaration,
     Type,
     Implementation,
+    Supertype,
+    Subtype,
 }
 
 #[derive(Debug, Clone)]
@@ -1790,39 +1811,7 @@ impl Editor {
         };
         let style = window.text_style();
         let font_size = style.font_size.to_pixels(window.rem_size());
-        let editor = cx.entity().downgrade();
-        let fold_placeholder = FoldPlaceholder {
-            constrain_width: false,
-            render: Arc::new(move |fold_id, fold_range, cx| {
-                let editor = editor.clone();
-                div()
-                    .id(fold_id)
-                    .bg(cx.theme().colors().ghost_element_background)
-                    .hover(|style| style.bg(cx.theme().colors().ghost_element_hover))
-                    .active(|style| style.bg(cx.theme().colors().ghost_element_active))
-                    .rounded_xs()
-                    .size_full()
-                    .cursor_pointer()
-                    .child("⋯")
-                    .on_mouse_down(MouseButton::Left, |_, _, cx| cx.stop_propagation())
-                    .on_click(move |_, _window, cx| {
-                        editor
-                            .update(cx, |editor, cx| {
-                                editor.unfold_ranges(
-                                    &[fold_range.start..fold_range.end],
-                                    true,
-                                    false,
-                                    cx,
-                                );
-                                cx.stop_propagation();
-                            })
-                            .ok();
-                    })
-                    .into_any()
-            }),
-            merge_adjacent: true,
-            ..FoldPlaceholder::default()
-        };
+        let fold_placeholder = clickable_fold_placeholder(cx.entity().downgrade(), "⋯".into());
         let display_map = display_map.unwrap_or_else(|| {
             cx.new(|cx| {
                 DisplayMap::new(
@@ -1864,6 +1853,9 @@ impl Editor {
                     project::Event::RefreshInlayHints => {
                         editor.refresh_inlay_hints(InlayHintRefreshReason::RefreshRequested, cx);
                     }
+                    project::Event::RefreshSemanticTokens => {
+                        editor.refresh_semantic_tokens(true, None, window, cx);
+                    }
                     project::Event::LanguageServerAdded(..)
                     | project::Event::LanguageServerRemoved(..) => {
                         if editor.tasks_update_task.is_none() {
@@ -2227,7 +2219,11 @@ impl Editor {
                 .unwrap_or_default(),
             tasks_update_task: None,
             pull_diagnostics_task: Task::ready(()),
+            selection_ranges_task: Task::ready(()),
+            pending_selection_range_expansions: 0,
             colors: None,
+            semantic_token_highlights: None,
+            folding_range_tasks: HashMap::default(),
             next_color_inlay_id: 0,
             linked_edit_ranges: Default::default(),
             in_project_search: false,
@@ -2377,6 +2373,7 @@ impl Editor {
             editor.minimap =
                 editor.create_minimap(EditorSettings::get_global(cx).minimap, window, cx);
             editor.colors = Some(LspColorData::new(cx));
+            editor.semantic_token_highlights = Some(SemanticTokenHighlights::default());
             editor.update_lsp_data(false, None, window, cx);
         }
 
@@ -3071,7 +3068,10 @@ impl Editor {
         self.select_prev_state = None;
         self.select_syntax_node_history.try_clear();
         self.invalidate_autoclose_regions(&selection_anchors, buffer);
-        self.snippet_stack.invalidate(&selection_anchors, buffer);
+        let finished_snippets = self.snippet_stack.invalidate(&selection_anchors, buffer);
+        for snippet in &finished_snippets {
+            self.apply_snippet_transformations(snippet, cx);
+        }
         self.take_rename(false, window, cx);
 
         let newest_selection = self.selections.newest_anchor();
@@ -3940,7 +3940,8 @@ impl Editor {
             return true;
         }
 
-        if self.snippet_stack.pop().is_some() {
+        if let Some(snippet) = self.snippet_stack.pop() {
+            self.apply_snippet_transformations(&snippet, cx);
             return true;
         }
 
@@ -5931,16 +5932,35 @@ impl Editor {
         cx.stop_propagation();
 
         let buffer_handle = completions_menu.buffer.clone();
+        let selections = self.selections.all::<usize>(cx);
+        let newest_selection_id = self.selections.newest_anchor().id;
+        let newest_selection_index = selections
+            .iter()
+            .position(|selection| selection.id == newest_selection_id)
+            .unwrap_or_default();
+        let selected_text = selections
+            .get(newest_selection_index)
+            .map(|selection| {
+                self.buffer
+                    .read(cx)
+                    .snapshot(cx)
+                    .text_for_range(selection.range())
+                    .collect::<String>()
+            })
+            .unwrap_or_default();
 
         let CompletionEdit {
             new_text,
             snippet,
+            snippet_source,
             replace_range,
         } = process_completion_for_edit(
             &completion,
             intent,
             &buffer_handle,
             &completions_menu.initial_position.text_anchor,
+            &selected_text,
+            newest_selection_index,
             cx,
         );
 
@@ -5976,7 +5996,6 @@ impl Editor {
         let prefix = &old_text[..old_text.len().saturating_sub(lookahead)];
         let suffix = &old_text[lookbehind.min(old_text.len())..];
 
-        let selections = self.selections.all::<usize>(cx);
         let mut ranges = Vec::new();
         let mut linked_edits = HashMap::<_, Vec<_>>::default();
 
@@ -6030,12 +6049,25 @@ impl Editor {
             text: new_text[common_prefix_len..].into(),
         });
 
+        let snippets = snippet.map(|snippet| {
+            // Variables such as `TM_SELECTED_TEXT` or `CURSOR_INDEX` differ between selections.
+            let mut snippets = snippet_source
+                .and_then(|source| snippets_for_selections(&source, &selections, &snapshot, cx))
+                .filter(|snippets| {
+                    snippets
+                        .iter()
+                        .all(|other| other.tabstops.len() == snippet.tabstops.len())
+                })
+                .unwrap_or_else(|| vec![snippet.clone(); selections.len()]);
+            if let Some(newest_snippet) = snippets.get_mut(newest_selection_index) {
+                *newest_snippet = snippet;
+            }
+            ranges.iter().cloned().zip(snippets).collect::<Vec<_>>()
+        });
+
         self.transact(window, cx, |editor, window, cx| {
-            if let Some(mut snippet) = snippet {
-                snippet.text = new_text.to_string();
-                editor
-                    .insert_snippet(&ranges, snippet, window, cx)
-                    .log_err();
+            if let Some(snippets) = snippets {
+                editor.insert_snippets(&snippets, window, cx).log_err();
             } else {
                 editor.buffer.update(cx, |multi_buffer, cx| {
                     let auto_indent = match completion.insert_text_mode {
@@ -9622,57 +9654,100 @@ impl Editor {
         snippet: Snippet,
         window: &mut Window,
         cx: &mut Context<Self>,
+    ) -> Result<()> {
+        let insertions = insertion_ranges
+            .iter()
+            .map(|range| (range.clone(), snippet.clone()))
+            .collect::<Vec<_>>();
+        self.insert_snippets(&insertions, window, cx)
+    }
+
+    /// Inserts a snippet at each of the given ranges. The snippets may differ in their text,
+    /// e.g. when their variables were resolved for each selection, but not in their tabstops.
+    pub fn insert_snippets(
+        &mut self,
+        insertions: &[(Range<usize>, Snippet)],
+        window: &mut Window,
+        cx: &mut Context<Self>,
     ) -> Result<()> {
         struct Tabstop<T> {
             is_end_tabstop: bool,
             ranges: Vec<Range<T>>,
             choices: Option<Vec<String>>,
+            transformations: Vec<SnippetTransformation>,
         }
 
-        let tabstops = self.buffer.update(cx, |buffer, cx| {
-            let snippet_text: Arc<str> = snippet.text.clone().into();
-            let edits = insertion_ranges
+        let Some((_, first_snippet)) = insertions.first() else {
+            return Ok(());
+        };
+        anyhow::ensure!(
+            insertions
                 .iter()
-                .cloned()
-                .map(|range| (range, snippet_text.clone()));
+                .all(|(_, snippet)| snippet.tabstops.len() == first_snippet.tabstops.len()),
+            "snippets must have the same tabstops"
+        );
+
+        let mut tabstops = self.buffer.update(cx, |buffer, cx| {
+            let edits = insertions
+                .iter()
+                .map(|(range, snippet)| (range.clone(), snippet.text.clone()));
             let autoindent_mode = AutoindentMode::Block {
                 original_indent_columns: Vec::new(),
             };
             buffer.edit(edits, Some(autoindent_mode), cx);
 
             let snapshot = &*buffer.read(cx);
-            let snippet = &snippet;
-            snippet
+            let mut delta = 0_isize;
+            let insertion_starts = insertions
+                .iter()
+                .map(|(range, snippet)| {
+                    let insertion_start = range.start as isize + delta;
+                    delta += snippet.text.len() as isize - range.len() as isize;
+                    insertion_start
+                })
+                .collect::<Vec<_>>();
+            let anchor_range = |insertion_start: isize, tabstop_range: &Range<isize>| {
+                let start = ((insertion_start + tabstop_range.start) as usize).min(snapshot.len());
+                let end = ((insertion_start + tabstop_range.end) as usize).min(snapshot.len());
+                snapshot.anchor_before(start)..snapshot.anchor_after(end)
+            };
+            first_snippet
                 .tabstops
                 .iter()
-                .map(|tabstop| {
+                .enumerate()
+                .map(|(tabstop_index, tabstop)| {
                     let is_end_tabstop = tabstop.ranges.first().is_some_and(|tabstop| {
-                        tabstop.is_empty() && tabstop.start == snippet.text.len() as isize
+                        tabstop.is_empty() && tabstop.start == first_snippet.text.len() as isize
                     });
-                    let mut tabstop_ranges = tabstop
-                        .ranges
-                        .iter()
-                        .flat_map(|tabstop_range| {
-                            let mut delta = 0_isize;
-                            insertion_ranges.iter().map(move |insertion_range| {
-                                let insertion_start = insertion_range.start as isize + delta;
-                                delta +=
-                                    snippet.text.len() as isize - insertion_range.len() as isize;
-
-                                let start = ((insertion_start + tabstop_range.start) as usize)
-                                    .min(snapshot.len());
-                                let end = ((insertion_start + tabstop_range.end) as usize)
-                                    .min(snapshot.len());
-                                snapshot.anchor_before(start)..snapshot.anchor_after(end)
-                            })
-                        })
-                        .collect::<Vec<_>>();
+                    let mut tabstop_ranges = Vec::new();
+                    let mut transformations = Vec::new();
+                    for (&insertion_start, (_, snippet)) in insertion_starts.iter().zip(insertions)
+                    {
+                        let tabstop = &snippet.tabstops[tabstop_index];
+                        tabstop_ranges.extend(
+                            tabstop
+                                .ranges
+                                .iter()
+                                .map(|range| anchor_range(insertion_start, range)),
+                        );
+                        if let Some(source) = tabstop.ranges.first() {
+                            let source = anchor_range(insertion_start, source);
+                            transformations.extend(tabstop.transformations.iter().map(
+                                |(target, transformation)| SnippetTransformation {
+                                    source: source.clone(),
+                                    target: anchor_range(insertion_start, target),
+                                    transformation: transformation.clone(),
+                                },
+                            ));
+                        }
+                    }
                     tabstop_ranges.sort_unstable_by(|a, b| a.start.cmp(&b.start, snapshot));
 
                     Tabstop {
                         is_end_tabstop,
                         ranges: tabstop_ranges,
                         choices: tabstop.choices.clone(),
+                        transformations,
                     }
                 })
                 .collect::<Vec<_>>()
@@ -9697,6 +9772,10 @@ impl Editor {
                     .iter()
                     .map(|tabstop| tabstop.choices.clone())
                     .collect();
+                let transformations = tabstops
+                    .iter_mut()
+                    .map(|tabstop| mem::take(&mut tabstop.transformations))
+                    .collect();
 
                 let ranges = tabstops
                     .into_iter()
@@ -9707,6 +9786,7 @@ impl Editor {
                     active_index: 0,
                     ranges,
                     choices,
+                    transformations,
                 });
             }
 
@@ -9796,6 +9876,7 @@ impl Editor {
         cx: &mut Context<Self>,
     ) -> bool {
         if let Some(mut snippet) = self.snippet_stack.pop() {
+            self.apply_snippet_transformations(&snippet, cx);
             match bias {
                 Bias::Left => {
                     if snippet.active_index > 0 {
@@ -9838,6 +9919,46 @@ impl Editor {
         false
     }
 
+    /// Updates the transformed mirrors of the active snippet tabstop with its current text.
+    ///
+    /// The edit is grouped with the last transaction, so that undoing the text typed into
+    /// the tabstop also reverts its mirrors.
+    fn apply_snippet_transformations(&mut self, snippet: &SnippetState, cx: &mut Context<Self>) {
+        let Some(transformations) = snippet.transformations.get(snippet.active_index) else {
+            return;
+        };
+
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let edits = transformations
+            .iter()
+            .filter_map(|mirror| {
+                let source_text = snapshot
+                    .text_for_range(mirror.source.clone())
+                    .collect::<String>();
+                let new_text = mirror.transformation.apply(&source_text);
+                let old_text = snapshot
+                    .text_for_range(mirror.target.clone())
+                    .collect::<String>();
+                (new_text != old_text).then(|| (mirror.target.clone(), new_text))
+            })
+            .collect::<Vec<_>>();
+        if edits.is_empty() {
+            return;
+        }
+
+        self.buffer.update(cx, |buffer, cx| {
+            let last_transaction_id = buffer.last_transaction_id(cx);
+            buffer.start_transaction(cx);
+            buffer.edit(edits, None, cx);
+            if let Some((transaction_id, last_transaction_id)) =
+                buffer.end_transaction(cx).zip(last_transaction_id)
+                && transaction_id != last_transaction_id
+            {
+                buffer.merge_transactions(transaction_id, last_transaction_id, cx);
+            }
+        });
+    }
+
     pub fn clear(&mut self, window: &mut Window, cx: &mut Context<Self>) {
         self.transact(window, cx, |this, window, cx| {
             this.select_all(&SelectAll, window, cx);
@@ -15008,9 +15129,9 @@ impl Editor {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
-        let Some(visible_row_count) = self.visible_row_count() else {
+        if self.visible_row_count().is_none() {
             return;
-        };
+        }
         let old_selections: Box<[_]> = self.selections.all::<usize>(cx).into();
         if old_selections.is_empty() {
             return;
@@ -15018,69 +15139,43 @@ impl Editor {
 
         self.hide_mouse_cursor(HideMouseCursorOrigin::MovementAction, cx);
 
+        if self.pending_selection_range_expansions > 0 {
+            self.pending_selection_range_expansions += 1;
+            return;
+        }
+        if self.select_larger_lsp_selection_ranges(&old_selections, window, cx) {
+            return;
+        }
+
         let display_map = self.display_map.update(cx, |map, cx| map.snapshot(cx));
         let buffer = self.buffer.read(cx).snapshot(cx);
-
-        let mut selected_larger_node = false;
-        let mut new_selections = old_selections
+        let new_selections = old_selections
             .iter()
-            .map(|selection| {
-                let old_range = selection.start..selection.end;
-
-                if let Some((node, _)) = buffer.syntax_ancestor(old_range.clone()) {
-                    // manually select word at selection
-                    if ["string_content", "inline"].contains(&node.kind()) {
-                        let (word_range, _) = buffer.surrounding_word(old_range.start, None);
-                        // ignore if word is already selected
-                        if !word_range.is_empty() && old_range != word_range {
-                            let (last_word_range, _) = buffer.surrounding_word(old_range.end, None);
-                            // only select word if start and end point belongs to same word
-                            if word_range == last_word_range {
-                                selected_larger_node = true;
-                                return Selection {
-                                    id: selection.id,
-                                    start: word_range.start,
-                                    end: word_range.end,
-                                    goal: SelectionGoal::None,
-                                    reversed: selection.reversed,
-                                };
-                            }
-                        }
-                    }
-                }
-
-                let mut new_range = old_range.clone();
-                while let Some((node, containing_range)) = buffer.syntax_ancestor(new_range.clone())
-                {
-                    new_range = match containing_range {
-                        MultiOrSingleBufferOffsetRange::Single(_) => break,
-                        MultiOrSingleBufferOffsetRange::Multi(range) => range,
-                    };
-                    if !node.is_named() {
-                        continue;
-                    }
-                    if !display_map.intersects_fold(new_range.start)
-                        && !display_map.intersects_fold(new_range.end)
-                    {
-                        break;
-                    }
-                }
-
-                selected_larger_node |= new_range != old_range;
-                Selection {
-                    id: selection.id,
-                    start: new_range.start,
-                    end: new_range.end,
-                    goal: SelectionGoal::None,
-                    reversed: selection.reversed,
-                }
-            })
-            .collect::<Vec<_>>();
+            .map(|selection| larger_syntax_node_selection(selection, &buffer, &display_map))
+            .collect();
+        self.select_larger_ranges(old_selections, new_selections, window, cx);
+    }
 
+    fn select_larger_ranges(
+        &mut self,
+        old_selections: Box<[Selection<usize>]>,
+        mut new_selections: Vec<Selection<usize>>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(visible_row_count) = self.visible_row_count() else {
+            return;
+        };
+        let selected_larger_node = old_selections
+            .iter()
+            .zip(&new_selections)
+            .any(|(old_selection, new_selection)| old_selection.range() != new_selection.range());
         if !selected_larger_node {
             return; // don't put this call in the history
         }
 
+        let display_map = self.display_map.update(cx, |map, cx| map.snapshot(cx));
+
         // scroll based on transformation done to the last selection created by the user
         let (last_old, last_new) = old_selections
             .last()
@@ -15095,13 +15190,11 @@ impl Editor {
             should_newest_selection_be_reversed
         };
 
-        if selected_larger_node {
-            self.select_syntax_node_history.disable_clearing = true;
-            self.change_selections(SelectionEffects::no_scroll(), window, cx, |s| {
-                s.select(new_selections.clone());
-            });
-            self.select_syntax_node_history.disable_clearing = false;
-        }
+        self.select_syntax_node_history.disable_clearing = true;
+        self.change_selections(SelectionEffects::no_scroll(), window, cx, |s| {
+            s.select(new_selections);
+        });
+        self.select_syntax_node_history.disable_clearing = false;
 
         let start_row = last_new.start.to_display_point(&display_map).row().0;
         let end_row = last_new.end.to_display_point(&display_map).row().0;
@@ -16251,6 +16344,24 @@ impl Editor {
         self.go_to_definition_of_kind(GotoDefinitionKind::Type, true, window, cx)
     }
 
+    pub fn go_to_supertype(
+        &mut self,
+        _: &GoToSupertype,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Navigated>> {
+        self.go_to_definition_of_kind(GotoDefinitionKind::Supertype, false, window, cx)
+    }
+
+    pub fn go_to_subtype(
+        &mut self,
+        _: &GoToSubtype,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Navigated>> {
+        self.go_to_definition_of_kind(GotoDefinitionKind::Subtype, false, window, cx)
+    }
+
     fn go_to_definition_of_kind(
         &mut self,
         kind: GotoDefinitionKind,
@@ -16419,6 +16530,8 @@ impl Editor {
                     Some(GotoDefinitionKind::Symbol) | None => "Definitions",
                     Some(GotoDefinitionKind::Declaration) => "Declarations",
                     Some(GotoDefinitionKind::Type) => "Types",
+                    Some(GotoDefinitionKind::Supertype) => "Supertypes",
+                    Some(GotoDefinitionKind::Subtype) => "Subtypes",
                 };
                 let title = editor
                     .update_in(acx, |_, _, cx| {
@@ -20824,6 +20937,12 @@ impl Editor {
             self.refresh_colors(false, None, window, cx);
         }
 
+        if let Some(semantic_token_highlights) = &mut self.semantic_token_highlights {
+            semantic_token_highlights.invalidate_styles();
+        }
+        self.refresh_semantic_tokens(false, None, window, cx);
+        self.refresh_folding_ranges(None, window, cx);
+
         cx.notify();
     }
 
@@ -21740,6 +21859,99 @@ impl Editor {
     ) {
         self.pull_diagnostics(for_buffer, window, cx);
         self.refresh_colors(ignore_cache, for_buffer, window, cx);
+        self.refresh_semantic_tokens(ignore_cache, for_buffer, window, cx);
+        self.refresh_folding_ranges(for_buffer, window, cx);
+    }
+}
+
+/// Returns the selection expanded to the smallest named syntax node enclosing it, or to the
+/// surrounding word within strings and comments.
+fn larger_syntax_node_selection(
+    selection: &Selection<usize>,
+    buffer: &MultiBufferSnapshot,
+    display_map: &DisplaySnapshot,
+) -> Selection<usize> {
+    let old_range = selection.start..selection.end;
+
+    if let Some((node, _)) = buffer.syntax_ancestor(old_range.clone()) {
+        // manually select word at selection
+        if ["string_content", "inline"].contains(&node.kind()) {
+            let (word_range, _) = buffer.surrounding_word(old_range.start, None);
+            // ignore if word is already selected
+            if !word_range.is_empty() && old_range != word_range {
+                let (last_word_range, _) = buffer.surrounding_word(old_range.end, None);
+                // only select word if start and end point belongs to same word
+                if word_range == last_word_range {
+                    return Selection {
+                        id: selection.id,
+                        start: word_range.start,
+                        end: word_range.end,
+                        goal: SelectionGoal::None,
+                        reversed: selection.reversed,
+                    };
+                }
+            }
+        }
+    }
+
+    let mut new_range = old_range.clone();
+    while let Some((node, containing_range)) = buffer.syntax_ancestor(new_range.clone()) {
+        new_range = match containing_range {
+            MultiOrSingleBufferOffsetRange::Single(_) => break,
+            MultiOrSingleBufferOffsetRange::Multi(range) => range,
+        };
+        if !node.is_named() {
+            continue;
+        }
+        if !display_map.intersects_fold(new_range.start)
+            && !display_map.intersects_fold(new_range.end)
+        {
+            break;
+        }
+    }
+
+    Selection {
+        id: selection.id,
+        start: new_range.start,
+        end: new_range.end,
+        goal: SelectionGoal::None,
+        reversed: selection.reversed,
+    }
+}
+
+/// A fold placeholder showing the label, which unfolds the fold when clicked.
+fn clickable_fold_placeholder(editor: WeakEntity<Editor>, label: SharedString) -> FoldPlaceholder {
+    FoldPlaceholder {
+        constrain_width: false,
+        render: Arc::new(move |fold_id, fold_range, cx| {
+            let editor = editor.clone();
+            div()
+                .id(fold_id)
+                .bg(cx.theme().colors().ghost_element_background)
+                .hover(|style| style.bg(cx.theme().colors().ghost_element_hover))
+                .active(|style| style.bg(cx.theme().colors().ghost_element_active))
+                .rounded_xs()
+                .size_full()
+                .cursor_pointer()
+                .child(label.clone())
+                .on_mouse_down(MouseButton::Left, |_, _, cx| cx.stop_propagation())
+                .on_click(move |_, _window, cx| {
+                    editor
+                        .update(cx, |editor, cx| {
+                            editor.unfold_ranges(
+                                &[fold_range.start..fold_range.end],
+                                true,
+                                false,
+                                cx,
+                            );
+                            cx.stop_propagation();
+                        })
+                        .ok();
+                })
+                .into_any()
+        }),
+        merge_adjacent: true,
+        ..FoldPlaceholder::default()
     }
 }
 
@@ -21774,11 +21986,13 @@ fn process_completion_for_edit(
     intent: CompletionIntent,
     buffer: &Entity<Buffer>,
     cursor_position: &text::Anchor,
+    selected_text: &str,
+    cursor_index: usize,
     cx: &mut Context<Editor>,
 ) -> CompletionEdit {
     let buffer = buffer.read(cx);
     let buffer_snapshot = buffer.snapshot();
-    let (snippet, new_text) = if completion.is_snippet() {
+    let (snippet, snippet_source, new_text) = if completion.is_snippet() {
         // Workaround for typescript language server issues so that methods don't expand within
         // strings and functions with type expressions. The previous point is used because the query
         // for function identifier doesn't match when the cursor is immediately after. See PR #30312
@@ -21795,12 +22009,27 @@ fn process_completion_for_edit(
         {
             snippet_source = label;
         }
-        match Snippet::parse(&snippet_source).log_err() {
-            Some(parsed_snippet) => (Some(parsed_snippet.clone()), parsed_snippet.text),
-            None => (None, completion.new_text.clone()),
+        let cursor_point = text::ToPoint::to_point(cursor_position, buffer);
+        let resolve_variable = |name: &str| {
+            resolve_snippet_variable(
+                name,
+                &buffer_snapshot,
+                cursor_point,
+                selected_text,
+                cursor_index,
+                &*cx,
+            )
+        };
+        match Snippet::parse_with_variables(&snippet_source, resolve_variable).log_err() {
+            Some(parsed_snippet) => (
+                Some(parsed_snippet.clone()),
+                Some(snippet_source),
+                parsed_snippet.text,
+            ),
+            None => (None, None, completion.new_text.clone()),
         }
     } else {
-        (None, completion.new_text.clone())
+        (None, None, completion.new_text.clone())
     };
 
     let mut range_to_replace = {
@@ -21904,6 +22133,7 @@ fn process_completion_for_edit(
         new_text,
         replace_range: range_to_replace.to_offset(buffer),
         snippet,
+        snippet_source,
     }
 }
 
@@ -21911,6 +22141,32 @@ struct CompletionEdit {
     new_text: String,
     replace_range: Range<usize>,
     snippet: Option<Snippet>,
+    /// The source the snippet was parsed from, to resolve its variables for other selections.
+    snippet_source: Option<String>,
+}
+
+/// Parses a snippet for each selection, resolving the variables at that selection.
+fn snippets_for_selections(
+    snippet_source: &str,
+    selections: &[Selection<usize>],
+    snapshot: &MultiBufferSnapshot,
+    cx: &App,
+) -> Option<Vec<Snippet>> {
+    selections
+        .iter()
+        .enumerate()
+        .map(|(cursor_index, selection)| {
+            let head = snapshot.offset_to_point(selection.head());
+            let (buffer, cursor, _) = snapshot.point_to_buffer_point(head)?;
+            let selected_text = snapshot
+                .text_for_range(selection.range())
+                .collect::<String>();
+            Snippet::parse_with_variables(snippet_source, |name| {
+                resolve_snippet_variable(name, buffer, cursor, &selected_text, cursor_index, cx)
+            })
+            .log_err()
+        })
+        .collect()
 }
 
 fn insert_extra_newline_brackets(
@@ -22436,6 +22692,21 @@ pub trait SemanticsProvider {
 
     fn supports_inlay_hints(&self, buffer: &Entity<Buffer>, cx: &mut App) -> bool;
 
+    fn supports_selection_ranges(&self, buffer: &Entity<Buffer>, cx: &mut App) -> bool;
+
+    fn selection_ranges(
+        &self,
+        buffer: &Entity<Buffer>,
+        positions: Vec<text::Anchor>,
+        cx: &mut App,
+    ) -> Option<Task<Result<Vec<Vec<Range<text::Anchor>>>>>>;
+
+    fn document_links(
+        &self,
+        buffer: &Entity<Buffer>,
+        cx: &mut App,
+    ) -> Option<Task<Result<Vec<DocumentLink>>>>;
+
     fn document_highlights(
         &self,
         buffer: &Entity<Buffer>,
@@ -22896,6 +23167,12 @@ impl SemanticsProvider for Entity<Project> {
             GotoDefinitionKind::Declaration => project.declarations(buffer, position, cx),
             GotoDefinitionKind::Type => project.type_definitions(buffer, position, cx),
             GotoDefinitionKind::Implementation => project.implementations(buffer, position, cx),
+            GotoDefinitionKind::Supertype => {
+                project.related_type_links(buffer, position, TypeHierarchyDirection::Supertypes, cx)
+            }
+            GotoDefinitionKind::Subtype => {
+                project.related_type_links(buffer, position, TypeHierarchyDirection::Subtypes, cx)
+            }
         }))
     }
 
@@ -22914,6 +23191,33 @@ impl SemanticsProvider for Entity<Project> {
         })
     }
 
+    fn supports_selection_ranges(&self, buffer: &Entity<Buffer>, cx: &mut App) -> bool {
+        self.update(cx, |project, cx| {
+            buffer.update(cx, |buffer, cx| {
+                project.any_language_server_supports_selection_ranges(buffer, cx)
+            })
+        })
+    }
+
+    fn selection_ranges(
+        &self,
+        buffer: &Entity<Buffer>,
+        positions: Vec<text::Anchor>,
+        cx: &mut App,
+    ) -> Option<Task<Result<Vec<Vec<Range<text::Anchor>>>>>> {
+        Some(self.update(cx, |project, cx| {
+            project.selection_ranges(buffer, positions, cx)
+        }))
+    }
+
+    fn document_links(
+        &self,
+        buffer: &Entity<Buffer>,
+        cx: &mut App,
+    ) -> Option<Task<Result<Vec<DocumentLink>>>> {
+        Some(self.update(cx, |project, cx| project.document_links(buffer, cx)))
+    }
+
     fn inline_values(
         &self,
         buffer_handle: Entity<Buffer>,
@@ -23325,7 +23629,7 @@ impl EditorSnapshot {
             }
         }
 
-        is_foldable |= self.starts_indent(buffer_row);
+        is_foldable |= self.starts_fold(buffer_row);
 
         if folded || (is_foldable && (row_contains_cursor || self.gutter_hovered)) {
             Some(
@@ -23830,10 +24134,12 @@ impl<T: ToPoint + ToOffset> SelectionExt for Selection<T> {
 }
 
 impl<T: InvalidationRegion> InvalidationStack<T> {
-    fn invalidate<S>(&mut self, selections: &[Selection<S>], buffer: &MultiBufferSnapshot)
+    /// Pops the regions that no longer contain the selections, returning them.
+    fn invalidate<S>(&mut self, selections: &[Selection<S>], buffer: &MultiBufferSnapshot) -> Vec<T>
     where
         S: Clone + ToOffset,
     {
+        let mut invalidated = Vec::new();
         while let Some(region) = self.last() {
             let all_selections_inside_invalidation_ranges =
                 if selections.len() == region.ranges().len() {
@@ -23851,9 +24157,10 @@ impl<T: InvalidationRegion> InvalidationStack<T> {
             if all_selections_inside_invalidation_ranges {
                 break;
             } else {
-                self.pop();
+                invalidated.extend(self.pop());
             }
         }
+        invalidated
     }
 }
 
@@ -8711,6 +8711,123 @@ async fn test_select_larger_smaller_syntax_node(cx: &mut TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_select_larger_syntax_node_with_lsp_selection_ranges(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorLspTestContext::new_rust(
+        lsp::ServerCapabilities {
+            selection_range_provider: Some(lsp::SelectionRangeProviderCapability::Simple(true)),
+            ..Default::default()
+        },
+        cx,
+    )
+    .await;
+
+    cx.set_state(indoc! {"
+        fn main() {
+            let a = foˇo(1);
+            let b = baˇr(2);
+        }
+    "});
+
+    // Every position is in a word, within a call, within a statement.
+    let _requests = cx.set_request_handler::<lsp::request::SelectionRangeRequest, _, _>(
+        move |_, params, _| async move {
+            let range = |line, start_column, end_column| {
+                lsp::Range::new(
+                    lsp::Position::new(line, start_column),
+                    lsp::Position::new(line, end_column),
+                )
+            };
+            Ok(Some(
+                params
+                    .positions
+                    .iter()
+                    .map(|position| {
+                        let line = position.line;
+                        let statement = lsp::SelectionRange {
+                            range: range(line, 4, 19),
+                            parent: None,
+                        };
+                        let call = lsp::SelectionRange {
+                            range: range(line, 12, 18),
+                            parent: Some(Box::new(statement)),
+                        };
+                        lsp::SelectionRange {
+                            range: range(line, 12, 15),
+                            parent: Some(Box::new(call)),
+                        }
+                    })
+                    .collect(),
+            ))
+        },
+    );
+
+    cx.update_editor(|editor, window, cx| {
+        editor.select_larger_syntax_node(&SelectLargerSyntaxNode, window, cx)
+    });
+    cx.run_until_parked();
+    cx.assert_editor_state(indoc! {"
+        fn main() {
+            let a = «fooˇ»(1);
+            let b = «ˇbar»(2);
+        }
+    "});
+
+    cx.update_editor(|editor, window, cx| {
+        editor.select_larger_syntax_node(&SelectLargerSyntaxNode, window, cx)
+    });
+    cx.run_until_parked();
+    cx.assert_editor_state(indoc! {"
+        fn main() {
+            let a = «foo(1)ˇ»;
+            let b = «bar(2)ˇ»;
+        }
+    "});
+
+    cx.update_editor(|editor, window, cx| {
+        editor.select_larger_syntax_node(&SelectLargerSyntaxNode, window, cx)
+    });
+    cx.run_until_parked();
+    cx.assert_editor_state(indoc! {"
+        fn main() {
+            «let a = foo(1);ˇ»
+            «ˇlet b = bar(2);»
+        }
+    "});
+
+    // Expanding again before the ranges arrive expands once more when they do.
+    cx.set_state(indoc! {"
+        fn main() {
+            let a = foˇo(1);
+            let b = baˇr(2);
+        }
+    "});
+    cx.update_editor(|editor, window, cx| {
+        editor.select_larger_syntax_node(&SelectLargerSyntaxNode, window, cx);
+        editor.select_larger_syntax_node(&SelectLargerSyntaxNode, window, cx);
+    });
+    cx.run_until_parked();
+    cx.assert_editor_state(indoc! {"
+        fn main() {
+            let a = «foo(1)ˇ»;
+            let b = «bar(2)ˇ»;
+        }
+    "});
+
+    // Both expansions are undone one at a time.
+    cx.update_editor(|editor, window, cx| {
+        editor.select_smaller_syntax_node(&SelectSmallerSyntaxNode, window, cx)
+    });
+    cx.assert_editor_state(indoc! {"
+        fn main() {
+            let a = «fooˇ»(1);
+            let b = «ˇbar»(2);
+        }
+    "});
+}
+
 #[gpui::test]
 async fn test_select_larger_syntax_node_for_cursor_at_end(cx: &mut TestAppContext) {
     init_test(cx, |_| {});
@@ -10811,6 +10928,144 @@ async fn test_snippets(cx: &mut TestAppContext) {
     "});
 }
 
+#[gpui::test]
+async fn test_snippet_transformations(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+    cx.set_state(indoc! {"
+        ˇ
+        ˇ
+    "});
+
+    cx.update_editor(|editor, window, cx| {
+        let snippet =
+            Snippet::parse("let ${1:name} = ${1/(.*)/${1:/pascalcase}/}::new();$0").unwrap();
+        let insertion_ranges = editor
+            .selections
+            .all(cx)
+            .iter()
+            .map(|s| s.range())
+            .collect::<Vec<_>>();
+        editor
+            .insert_snippet(&insertion_ranges, snippet, window, cx)
+            .unwrap();
+    });
+    cx.assert_editor_state(indoc! {"
+        let «nameˇ» = Name::new();
+        let «nameˇ» = Name::new();
+    "});
+
+    // Mirrors are transformed when the tabstop is left.
+    cx.simulate_input("task_queue");
+    cx.assert_editor_state(indoc! {"
+        let task_queueˇ = Name::new();
+        let task_queueˇ = Name::new();
+    "});
+    cx.update_editor(|editor, window, cx| assert!(editor.move_to_next_snippet_tabstop(window, cx)));
+    cx.assert_editor_state(indoc! {"
+        let task_queue = TaskQueue::new();ˇ
+        let task_queue = TaskQueue::new();ˇ
+    "});
+
+    // Each mirror is computed from the tabstop of its own insertion.
+    cx.set_state(indoc! {"
+        ˇ
+        ˇ
+    "});
+    cx.update_editor(|editor, window, cx| {
+        let snippet =
+            Snippet::parse("let ${1:name} = ${1/(.*)/${1:/pascalcase}/}::new();$0").unwrap();
+        let insertion_ranges = editor
+            .selections
+            .all(cx)
+            .iter()
+            .map(|s| s.range())
+            .collect::<Vec<_>>();
+        editor
+            .insert_snippet(&insertion_ranges, snippet, window, cx)
+            .unwrap();
+    });
+    cx.simulate_input("queue");
+    cx.update_buffer(|buffer, cx| {
+        buffer.edit([(Point::new(1, 9)..Point::new(1, 9), "s")], None, cx)
+    });
+    cx.update_editor(|editor, window, cx| assert!(editor.move_to_next_snippet_tabstop(window, cx)));
+    assert_eq!(
+        cx.buffer_text(),
+        "let queue = Queue::new();\nlet queues = Queues::new();\n"
+    );
+
+    // Mirrors are also transformed when the snippet is dismissed.
+    cx.set_state("ˇ");
+    cx.update_editor(|editor, window, cx| {
+        let snippet = Snippet::parse("let ${1:name}: ${1/(.*)/${1:/upcase}/} = $2;").unwrap();
+        editor.insert_snippet(&[0..0], snippet, window, cx).unwrap();
+    });
+    cx.simulate_input("id");
+    cx.update_editor(|editor, window, cx| editor.cancel(&Cancel, window, cx));
+    cx.assert_editor_state("let idˇ: ID = ;");
+    cx.update_editor(|editor, _, _| assert!(editor.snippet_stack.is_empty()));
+}
+
+#[gpui::test]
+async fn test_snippet_transformations_undo(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+    // Ensure automatic grouping doesn't occur.
+    cx.update_buffer(|buffer, _| buffer.set_group_interval(Duration::ZERO));
+    cx.set_state("ˇ");
+    cx.update_editor(|editor, window, cx| {
+        let snippet =
+            Snippet::parse("let ${1:name} = ${1/(.*)/${1:/pascalcase}/}::new();$0").unwrap();
+        editor.insert_snippet(&[0..0], snippet, window, cx).unwrap();
+    });
+    cx.update_editor(|editor, window, cx| {
+        editor.handle_input("task_queue", window, cx);
+        assert!(editor.move_to_next_snippet_tabstop(window, cx));
+    });
+    assert_eq!(cx.buffer_text(), "let task_queue = TaskQueue::new();");
+
+    // Undoing the typing in the tabstop also reverts its mirrors.
+    cx.update_editor(|editor, window, cx| editor.undo(&Undo, window, cx));
+    assert_eq!(cx.buffer_text(), "let name = Name::new();");
+}
+
+#[gpui::test]
+async fn test_snippet_variables_for_selections(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+    cx.set_state(indoc! {"
+        «oneˇ»
+        «twoˇ»
+    "});
+    let snippets = cx.update_editor(|editor, _, cx| {
+        let selections = editor.selections.all::<usize>(cx);
+        let snapshot = editor.buffer.read(cx).snapshot(cx);
+        snippets_for_selections(
+            "$CURSOR_NUMBER. ${1:$TM_SELECTED_TEXT}",
+            &selections,
+            &snapshot,
+            cx,
+        )
+        .unwrap()
+    });
+    assert_eq!(
+        snippets
+            .iter()
+            .map(|snippet| snippet.text.as_str())
+            .collect::<Vec<_>>(),
+        ["1. one", "2. two"]
+    );
+    assert!(
+        snippets
+            .iter()
+            .all(|snippet| snippet.tabstops.len() == snippets[0].tabstops.len())
+    );
+}
+
 #[gpui::test]
 async fn test_snippet_indentation(cx: &mut TestAppContext) {
     init_test(cx, |_| {});
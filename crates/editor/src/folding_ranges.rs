@@ -0,0 +1,91 @@
+use std::time::Duration;
+
+use language::language_settings::language_settings;
+use text::BufferId;
+use ui::{Context, Window};
+
+use crate::{Editor, clickable_fold_placeholder, display_map::LspFold};
+
+/// Folding ranges are requested again on every edit, so wait for the typing to settle first.
+const FOLDING_RANGES_DEBOUNCE: Duration = Duration::from_millis(200);
+
+impl Editor {
+    pub(super) fn refresh_folding_ranges(
+        &mut self,
+        for_buffer: Option<BufferId>,
+        _: &Window,
+        cx: &mut Context<Self>,
+    ) {
+        if !self.mode().is_full() {
+            return;
+        }
+        let Some(project) = self.project.clone() else {
+            return;
+        };
+
+        let buffers = self
+            .buffer
+            .read(cx)
+            .all_buffers()
+            .into_iter()
+            .filter(|buffer| {
+                for_buffer.is_none_or(|buffer_id| buffer_id == buffer.read(cx).remote_id())
+            })
+            .collect::<Vec<_>>();
+        for buffer in buffers {
+            let buffer_id = buffer.read(cx).remote_id();
+            let enabled = {
+                let buffer = buffer.read(cx);
+                language_settings(buffer.language().map(|l| l.name()), buffer.file(), cx)
+                    .lsp_folding_ranges
+            };
+            if !enabled {
+                if self.folding_range_tasks.remove(&buffer_id).is_some() {
+                    self.display_map.update(cx, |display_map, _| {
+                        display_map.set_lsp_folds(buffer_id, Vec::new())
+                    });
+                    cx.notify();
+                }
+                continue;
+            }
+
+            let project = project.clone();
+            let task = cx.spawn(async move |editor, cx| {
+                cx.background_executor()
+                    .timer(FOLDING_RANGES_DEBOUNCE)
+                    .await;
+                let Ok(folding_ranges_task) =
+                    project.update(cx, |project, cx| project.folding_ranges(&buffer, cx))
+                else {
+                    return;
+                };
+                let folding_ranges = match folding_ranges_task.await {
+                    Ok(folding_ranges) => folding_ranges,
+                    Err(e) => {
+                        log::error!("Failed to fetch folding ranges: {e:#}");
+                        return;
+                    }
+                };
+                editor
+                    .update(cx, |editor, cx| {
+                        let weak_editor = cx.entity().downgrade();
+                        let folds = folding_ranges
+                            .into_iter()
+                            .map(|folding_range| LspFold {
+                                range: folding_range.range,
+                                placeholder: folding_range.collapsed_text.map(|collapsed_text| {
+                                    clickable_fold_placeholder(weak_editor.clone(), collapsed_text)
+                                }),
+                            })
+                            .collect();
+                        editor.display_map.update(cx, |display_map, _| {
+                            display_map.set_lsp_folds(buffer_id, folds)
+                        });
+                        cx.notify();
+                    })
+                    .ok();
+            });
+            self.folding_range_tasks.insert(buffer_id, task);
+        }
+    }
+}
@@ -1,6 +1,7 @@
 use crate::{
     Anchor, Editor, EditorSettings, EditorSnapshot, FindAllReferences, GoToDefinition,
     GoToTypeDefinition, GotoDefinitionKind, InlayId, Navigated, PointForPosition, SelectPhase,
+    SemanticsProvider,
     editor_settings::GoToDefinitionFallback,
     hover_popover::{self, InlayHover},
     scroll::ScrollAmount,
@@ -14,8 +15,9 @@ use project::{
     ResolveState, ResolvedPath,
 };
 use settings::Settings;
-use std::ops::Range;
+use std::{ops::Range, rc::Rc};
 use theme::ActiveTheme as _;
+use url::Url;
 use util::{ResultExt, TryFutureExt as _, maybe};
 
 #[derive(Debug)]
@@ -539,7 +541,18 @@ pub fn show_link_definition(
         async move {
             let result = match &trigger_point {
                 TriggerPoint::Text(_) => {
-                    if let Some((url_range, url)) = find_url(&buffer, buffer_position, cx.clone()) {
+                    if let Some((link_range, link)) =
+                        find_document_link(&buffer, provider.as_ref(), buffer_position, cx).await
+                    {
+                        let range = maybe!({
+                            let start = snapshot.anchor_in_excerpt(excerpt_id, link_range.start)?;
+                            let end = snapshot.anchor_in_excerpt(excerpt_id, link_range.end)?;
+                            Some(RangeInEditor::Text(start..end))
+                        });
+                        Some((range, vec![link]))
+                    } else if let Some((url_range, url)) =
+                        find_url(&buffer, buffer_position, cx.clone())
+                    {
                         this.read_with(cx, |_, _| {
                             let range = maybe!({
                                 let start =
@@ -659,6 +672,41 @@ pub fn show_link_definition(
     editor.hovered_link_state = Some(hovered_link_state);
 }
 
+/// Finds the language server document link at the position, and what it leads to.
+pub(crate) async fn find_document_link(
+    buffer: &Entity<language::Buffer>,
+    provider: Option<&Rc<dyn SemanticsProvider>>,
+    position: text::Anchor,
+    cx: &mut AsyncWindowContext,
+) -> Option<(Range<text::Anchor>, HoverLink)> {
+    let links_task = cx
+        .update(|_, cx| provider?.document_links(buffer, cx))
+        .ok()??;
+    let links = links_task.await.log_err()?;
+    let snapshot = buffer.read_with(cx, |buffer, _| buffer.snapshot()).ok()?;
+    links
+        .into_iter()
+        .filter(|link| {
+            link.range.start.cmp(&position, &snapshot).is_le()
+                && link.range.end.cmp(&position, &snapshot).is_ge()
+        })
+        .find_map(|link| {
+            let target = link.target?;
+            let file_path = Url::parse(&target)
+                .ok()
+                .filter(|url| url.scheme() == "file")
+                .and_then(|url| url.to_file_path().ok());
+            let hover_link = match file_path {
+                Some(path) => HoverLink::File(ResolvedPath::AbsPath {
+                    path,
+                    is_dir: false,
+                }),
+                None => HoverLink::Url(target),
+            };
+            Some((link.range, hover_link))
+        })
+}
+
 pub(crate) fn find_url(
     buffer: &Entity<language::Buffer>,
     position: text::Anchor,
@@ -1509,6 +1557,53 @@ mod tests {
         assert_eq!(cx.opened_url(), Some("https://zed.dev/releases".into()));
     }
 
+    #[gpui::test]
+    async fn test_document_links(cx: &mut gpui::TestAppContext) {
+        init_test(cx, |_| {});
+        let mut cx = EditorLspTestContext::new_rust(
+            lsp::ServerCapabilities {
+                document_link_provider: Some(lsp::DocumentLinkOptions {
+                    resolve_provider: Some(false),
+                    work_done_progress_options: Default::default(),
+                }),
+                ..Default::default()
+            },
+            cx,
+        )
+        .await;
+
+        cx.set_state(indoc! {"
+            use serdeˇ::Deserialize;
+        "});
+        let link_range = cx.lsp_range(indoc! {"
+            use «serde»::Deserialize;
+        "});
+
+        let mut requests = cx.set_request_handler::<lsp::request::DocumentLinkRequest, _, _>(
+            move |_, _, _| async move {
+                Ok(Some(vec![lsp::DocumentLink {
+                    range: link_range,
+                    target: Some("https://docs.rs/serde".parse().unwrap()),
+                    tooltip: None,
+                    data: None,
+                }]))
+            },
+        );
+
+        let screen_coord = cx.pixel_position(indoc! {"
+            use serˇde::Deserialize;
+        "});
+        cx.simulate_mouse_move(screen_coord, None, Modifiers::secondary_key());
+        requests.next().await;
+        cx.run_until_parked();
+        cx.assert_editor_text_highlights::<HoveredLinkState>(indoc! {"
+            use «serdeˇ»::Deserialize;
+        "});
+
+        cx.simulate_click(screen_coord, Modifiers::secondary_key());
+        assert_eq!(cx.opened_url(), Some("https://docs.rs/serde".into()));
+    }
+
     #[gpui::test]
     async fn test_surrounding_filename(cx: &mut gpui::TestAppContext) {
         init_test(cx, |_| {});
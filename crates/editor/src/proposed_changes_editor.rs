@@ -474,6 +474,33 @@ impl SemanticsProvider for BranchBufferSemanticsProvider {
         }
     }
 
+    fn supports_selection_ranges(&self, buffer: &Entity<Buffer>, cx: &mut App) -> bool {
+        if let Some(buffer) = self.to_base(buffer, &[], cx) {
+            self.0.supports_selection_ranges(&buffer, cx)
+        } else {
+            false
+        }
+    }
+
+    fn selection_ranges(
+        &self,
+        buffer: &Entity<Buffer>,
+        positions: Vec<text::Anchor>,
+        cx: &mut App,
+    ) -> Option<Task<anyhow::Result<Vec<Vec<Range<text::Anchor>>>>>> {
+        let buffer = self.to_base(buffer, &positions, cx)?;
+        self.0.selection_ranges(&buffer, positions, cx)
+    }
+
+    fn document_links(
+        &self,
+        buffer: &Entity<Buffer>,
+        cx: &mut App,
+    ) -> Option<Task<anyhow::Result<Vec<project::DocumentLink>>>> {
+        let buffer = self.to_base(buffer, &[], cx)?;
+        self.0.document_links(&buffer, cx)
+    }
+
     fn document_highlights(
         &self,
         buffer: &Entity<Buffer>,
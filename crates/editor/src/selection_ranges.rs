@@ -0,0 +1,157 @@
+use collections::HashMap;
+use futures::future::join_all;
+use gpui::Entity;
+use language::{Buffer, Selection, SelectionGoal};
+use multi_buffer::{ExcerptId, ToOffset as _};
+use text::BufferId;
+use ui::{Context, Window};
+
+use crate::{Editor, larger_syntax_node_selection};
+
+impl Editor {
+    /// Expands the selections to the enclosing ranges reported by the language servers, falling
+    /// back to the syntax tree for the selections they report nothing larger for.
+    ///
+    /// The ranges arrive asynchronously: the calls made before they do are counted in
+    /// `pending_selection_range_expansions`, and expand the selections once more each.
+    ///
+    /// Returns `false` when no language server provides selection ranges for the selections.
+    pub(super) fn select_larger_lsp_selection_ranges(
+        &mut self,
+        old_selections: &[Selection<usize>],
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        let Some(provider) = self.semantics_provider.clone() else {
+            return false;
+        };
+
+        let multi_buffer = self.buffer.read(cx);
+        let snapshot = multi_buffer.snapshot(cx);
+        let mut positions_by_buffer =
+            HashMap::<BufferId, (Entity<Buffer>, Vec<(usize, ExcerptId, text::Anchor)>)>::default();
+        for (ix, selection) in old_selections.iter().enumerate() {
+            let position = snapshot.anchor_before(selection.start);
+            let Some(buffer) = position
+                .buffer_id
+                .and_then(|buffer_id| multi_buffer.buffer(buffer_id))
+            else {
+                continue;
+            };
+            positions_by_buffer
+                .entry(buffer.read(cx).remote_id())
+                .or_insert_with(|| (buffer, Vec::new()))
+                .1
+                .push((ix, position.excerpt_id, position.text_anchor));
+        }
+
+        let ranges_tasks = positions_by_buffer
+            .into_values()
+            .filter(|(buffer, _)| provider.supports_selection_ranges(buffer, cx))
+            .filter_map(|(buffer, positions)| {
+                let ranges_task = provider.selection_ranges(
+                    &buffer,
+                    positions.iter().map(|(_, _, position)| *position).collect(),
+                    cx,
+                )?;
+                Some(async move { (positions, ranges_task.await) })
+            })
+            .collect::<Vec<_>>();
+        if ranges_tasks.is_empty() {
+            return false;
+        }
+
+        let old_selections: Box<[Selection<usize>]> = old_selections.into();
+        self.pending_selection_range_expansions = 1;
+        self.selection_ranges_task = cx.spawn_in(window, async move |editor, cx| {
+            let mut ranges_by_selection = HashMap::default();
+            for (positions, ranges) in join_all(ranges_tasks).await {
+                match ranges {
+                    Ok(ranges) => ranges_by_selection.extend(
+                        positions
+                            .into_iter()
+                            .zip(ranges)
+                            .map(|((ix, excerpt_id, _), ranges)| (ix, (excerpt_id, ranges))),
+                    ),
+                    Err(e) => log::error!("Failed to fetch selection ranges: {e:#}"),
+                }
+            }
+
+            editor
+                .update_in(cx, |editor, window, cx| {
+                    let expansions = std::mem::take(&mut editor.pending_selection_range_expansions);
+                    let selections = editor.selections.all::<usize>(cx);
+                    if !selections
+                        .iter()
+                        .map(Selection::range)
+                        .eq(old_selections.iter().map(Selection::range))
+                    {
+                        // The selections moved while the ranges were fetched.
+                        return;
+                    }
+
+                    let display_map = editor.display_map.update(cx, |map, cx| map.snapshot(cx));
+                    let buffer = editor.buffer.read(cx).snapshot(cx);
+                    let larger_selection = |ix: usize, selection: &Selection<usize>| {
+                        let Some((excerpt_id, ranges)) = ranges_by_selection.get(&ix) else {
+                            return larger_syntax_node_selection(selection, &buffer, &display_map);
+                        };
+                        // The ranges go from the innermost to the outermost, and stop at the
+                        // excerpt boundaries.
+                        let larger_range = ranges
+                            .iter()
+                            .map_while(|range| {
+                                let start = buffer.anchor_in_excerpt(*excerpt_id, range.start)?;
+                                let end = buffer.anchor_in_excerpt(*excerpt_id, range.end)?;
+                                Some(start.to_offset(&buffer)..end.to_offset(&buffer))
+                            })
+                            .find(|range| {
+                                range.start <= selection.start
+                                    && selection.end <= range.end
+                                    && *range != selection.range()
+                                    && !display_map.intersects_fold(range.start)
+                                    && !display_map.intersects_fold(range.end)
+                            });
+                        match larger_range {
+                            Some(range) => Selection {
+                                id: selection.id,
+                                start: range.start,
+                                end: range.end,
+                                goal: SelectionGoal::None,
+                                reversed: selection.reversed,
+                            },
+                            None => larger_syntax_node_selection(selection, &buffer, &display_map),
+                        }
+                    };
+
+                    // The ranges enclose the selections as they get larger, so the expansions
+                    // requested while they were fetched are all made from them.
+                    let mut old_selections = old_selections;
+                    for _ in 0..expansions {
+                        let new_selections = old_selections
+                            .iter()
+                            .enumerate()
+                            .map(|(ix, selection)| larger_selection(ix, selection))
+                            .collect::<Vec<_>>();
+                        if old_selections
+                            .iter()
+                            .map(Selection::range)
+                            .eq(new_selections.iter().map(Selection::range))
+                        {
+                            break;
+                        }
+                        let selection_count = new_selections.len();
+                        editor.select_larger_ranges(old_selections, new_selections, window, cx);
+                        let selections = editor.selections.all::<usize>(cx);
+                        if selections.len() != selection_count {
+                            // Overlapping selections got merged, and no longer match the ranges.
+                            break;
+                        }
+                        old_selections = selections.into();
+                    }
+                })
+                .ok();
+        });
+        true
+    }
+}
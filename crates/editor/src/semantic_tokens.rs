@@ -0,0 +1,253 @@
+use std::ops::Range;
+
+use collections::HashMap;
+use futures::future::join_all;
+use gpui::{Entity, HighlightStyle};
+use itertools::Itertools;
+use language::{Buffer, language_settings::language_settings, semantic_token_style};
+use lsp::LanguageServerId;
+use multi_buffer::Anchor;
+use project::lsp_store::LspFetchStrategy;
+use text::{Bias, BufferId, OffsetRangeExt as _};
+use theme::ActiveTheme as _;
+use ui::{Context, Window};
+
+use crate::Editor;
+
+#[derive(Debug, Default)]
+pub(super) struct SemanticTokenHighlights {
+    buffer_highlights: HashMap<BufferId, BufferSemanticTokenHighlights>,
+}
+
+#[derive(Debug, Default)]
+struct BufferSemanticTokenHighlights {
+    highlights: Vec<(Range<Anchor>, HighlightStyle)>,
+    cache_version_used: Option<usize>,
+}
+
+impl SemanticTokenHighlights {
+    /// Forgets which tokens were resolved, so that the next refresh styles them again.
+    pub(super) fn invalidate_styles(&mut self) {
+        for buffer_highlights in self.buffer_highlights.values_mut() {
+            buffer_highlights.cache_version_used = None;
+        }
+    }
+}
+
+impl Editor {
+    pub(super) fn refresh_semantic_tokens(
+        &mut self,
+        ignore_cache: bool,
+        buffer_id: Option<BufferId>,
+        _: &Window,
+        cx: &mut Context<Self>,
+    ) {
+        if !self.mode().is_full() || self.semantic_token_highlights.is_none() {
+            return;
+        }
+        let Some(project) = self.project.clone() else {
+            return;
+        };
+
+        let visible_buffers = self
+            .visible_excerpts(None, cx)
+            .into_values()
+            .map(|(buffer, ..)| buffer)
+            .filter(|editor_buffer| {
+                buffer_id.is_none_or(|buffer_id| buffer_id == editor_buffer.read(cx).remote_id())
+            })
+            .unique_by(|buffer| buffer.read(cx).remote_id())
+            .collect::<Vec<_>>();
+
+        let multi_buffer = self.buffer.read(cx);
+        let is_enabled = |buffer: &Entity<Buffer>| {
+            let buffer = buffer.read(cx);
+            language_settings(buffer.language().map(|l| l.name()), buffer.file(), cx)
+                .semantic_tokens
+        };
+        let visible_buffers = visible_buffers
+            .into_iter()
+            .filter(|buffer| is_enabled(buffer))
+            .collect::<Vec<_>>();
+        let Some(semantic_token_highlights) = &mut self.semantic_token_highlights else {
+            return;
+        };
+        let highlights_count = semantic_token_highlights.buffer_highlights.len();
+        semantic_token_highlights
+            .buffer_highlights
+            .retain(|buffer_id, _| {
+                multi_buffer
+                    .buffer(*buffer_id)
+                    .is_some_and(|buffer| is_enabled(&buffer))
+            });
+        if semantic_token_highlights.buffer_highlights.len() != highlights_count {
+            self.update_semantic_token_highlights(cx);
+        }
+        if visible_buffers.is_empty() {
+            return;
+        }
+
+        let all_tokens_task = project.read(cx).lsp_store().update(cx, |lsp_store, cx| {
+            visible_buffers
+                .into_iter()
+                .filter_map(|buffer| {
+                    let buffer_id = buffer.read(cx).remote_id();
+                    let fetch_strategy = if ignore_cache {
+                        LspFetchStrategy::IgnoreCache
+                    } else {
+                        LspFetchStrategy::UseCache {
+                            known_cache_version: self.semantic_token_highlights.as_ref().and_then(
+                                |highlights| {
+                                    highlights
+                                        .buffer_highlights
+                                        .get(&buffer_id)?
+                                        .cache_version_used
+                                },
+                            ),
+                        }
+                    };
+                    let tokens_task = lsp_store.semantic_tokens(fetch_strategy, buffer, cx)?;
+                    Some(async move { (buffer_id, tokens_task.await) })
+                })
+                .collect::<Vec<_>>()
+        });
+        cx.spawn(async move |editor, cx| {
+            let all_tokens = join_all(all_tokens_task).await;
+            if all_tokens.is_empty() {
+                return;
+            }
+            editor
+                .update(cx, |editor, cx| {
+                    let syntax_theme = cx.theme().syntax().clone();
+                    let multi_buffer_snapshot = editor.buffer().read(cx).snapshot(cx);
+                    let editor_excerpts = multi_buffer_snapshot.excerpts().fold(
+                        HashMap::default(),
+                        |mut acc, (excerpt_id, buffer_snapshot, excerpt_range)| {
+                            let excerpt_data = acc
+                                .entry(buffer_snapshot.remote_id())
+                                .or_insert_with(Vec::new);
+                            let excerpt_point_range =
+                                excerpt_range.context.to_point_utf16(buffer_snapshot);
+                            excerpt_data.push((excerpt_id, buffer_snapshot, excerpt_point_range));
+                            acc
+                        },
+                    );
+
+                    let Some(semantic_token_highlights) = &mut editor.semantic_token_highlights
+                    else {
+                        return;
+                    };
+                    let mut styles =
+                        HashMap::<(LanguageServerId, u32, u32), Option<HighlightStyle>>::default();
+                    let mut updated = false;
+                    for (buffer_id, tokens) in all_tokens {
+                        let tokens = match tokens {
+                            Ok(tokens) => tokens,
+                            Err(e) => {
+                                log::error!("Failed to retrieve semantic tokens: {e}");
+                                continue;
+                            }
+                        };
+                        // The buffer changed while the tokens were fetched, keep the old ones.
+                        let Some(cache_version) = tokens.cache_version else {
+                            continue;
+                        };
+                        let Some(excerpts) = editor_excerpts.get(&buffer_id) else {
+                            continue;
+                        };
+
+                        let mut new_highlights = Vec::new();
+                        for (server_id, server_tokens) in &tokens.servers {
+                            for token in &server_tokens.tokens {
+                                let Some(style) = *styles
+                                    .entry((*server_id, token.token_type, token.token_modifiers))
+                                    .or_insert_with(|| {
+                                        semantic_token_style(
+                                            server_tokens.token_type(token)?,
+                                            server_tokens.token_modifiers(token),
+                                            &syntax_theme,
+                                        )
+                                    })
+                                else {
+                                    continue;
+                                };
+
+                                for (excerpt_id, buffer_snapshot, excerpt_range) in excerpts {
+                                    let token_start =
+                                        buffer_snapshot.clip_point_utf16(token.start, Bias::Left);
+                                    let token_end =
+                                        buffer_snapshot.clip_point_utf16(token.end(), Bias::Right);
+                                    if token_start == token_end
+                                        || !excerpt_range.contains(&token_start)
+                                        || !excerpt_range.contains(&token_end)
+                                    {
+                                        continue;
+                                    }
+                                    let Some(start_anchor) = multi_buffer_snapshot
+                                        .anchor_in_excerpt(
+                                            *excerpt_id,
+                                            buffer_snapshot.anchor_after(token_start),
+                                        )
+                                    else {
+                                        continue;
+                                    };
+                                    let Some(end_anchor) = multi_buffer_snapshot.anchor_in_excerpt(
+                                        *excerpt_id,
+                                        buffer_snapshot.anchor_before(token_end),
+                                    ) else {
+                                        continue;
+                                    };
+                                    new_highlights.push((start_anchor..end_anchor, style));
+                                    break;
+                                }
+                            }
+                        }
+
+                        semantic_token_highlights.buffer_highlights.insert(
+                            buffer_id,
+                            BufferSemanticTokenHighlights {
+                                highlights: new_highlights,
+                                cache_version_used: Some(cache_version),
+                            },
+                        );
+                        updated = true;
+                    }
+
+                    if updated {
+                        editor.update_semantic_token_highlights(cx);
+                    }
+                })
+                .ok();
+        })
+        .detach();
+    }
+
+    fn update_semantic_token_highlights(&mut self, cx: &mut Context<Self>) {
+        let Some(semantic_token_highlights) = &self.semantic_token_highlights else {
+            return;
+        };
+        let multi_buffer_snapshot = self.buffer.read(cx).snapshot(cx);
+        let mut ranges_by_style = HashMap::<HighlightStyle, Vec<Range<Anchor>>>::default();
+        for (range, style) in semantic_token_highlights
+            .buffer_highlights
+            .values()
+            .flat_map(|buffer_highlights| &buffer_highlights.highlights)
+        {
+            ranges_by_style
+                .entry(*style)
+                .or_default()
+                .push(range.clone());
+        }
+        let highlights = ranges_by_style
+            .into_iter()
+            .map(|(style, mut ranges)| {
+                ranges.sort_by(|a, b| a.start.cmp(&b.start, &multi_buffer_snapshot));
+                (style, ranges)
+            })
+            .collect();
+        self.display_map.update(cx, |display_map, _| {
+            display_map.set_semantic_token_highlights(highlights)
+        });
+        cx.notify();
+    }
+}
@@ -0,0 +1,105 @@
+use chrono::Local;
+use gpui::App;
+use language::{BufferSnapshot, Point};
+use rand::Rng as _;
+use std::path::Path;
+use util::maybe;
+
+/// Resolves a snippet variable, supporting the same set of variables as VS Code:
+/// https://code.visualstudio.com/docs/editing/userdefinedsnippets#_variables
+///
+/// Returns `None` for unknown variables, and an empty string for known variables
+/// that have no value in the current context (e.g. a file path of an unsaved buffer).
+pub(crate) fn resolve_snippet_variable(
+    name: &str,
+    buffer: &BufferSnapshot,
+    cursor: Point,
+    selected_text: &str,
+    cursor_index: usize,
+    cx: &App,
+) -> Option<String> {
+    let file = buffer.file();
+    let now = Local::now();
+    let value = match name {
+        "TM_SELECTED_TEXT" => Some(selected_text.to_string()),
+        "TM_CURRENT_LINE" => Some(
+            buffer
+                .text_for_range(
+                    Point::new(cursor.row, 0)..Point::new(cursor.row, buffer.line_len(cursor.row)),
+                )
+                .collect(),
+        ),
+        "TM_CURRENT_WORD" => {
+            let (range, _) = buffer.surrounding_word(cursor, None);
+            Some(buffer.text_for_range(range).collect())
+        }
+        "TM_LINE_INDEX" => Some(cursor.row.to_string()),
+        "TM_LINE_NUMBER" => Some((cursor.row + 1).to_string()),
+        "TM_FILENAME" => file.map(|file| file.file_name(cx).to_string_lossy().into_owned()),
+        "TM_FILENAME_BASE" => maybe!({
+            let file_name = file?.file_name(cx);
+            Some(
+                Path::new(file_name)
+                    .file_stem()?
+                    .to_string_lossy()
+                    .into_owned(),
+            )
+        }),
+        "TM_DIRECTORY" => maybe!({
+            let abs_path = file?.as_local()?.abs_path(cx);
+            Some(abs_path.parent()?.to_string_lossy().into_owned())
+        }),
+        "TM_FILEPATH" => maybe!({
+            Some(
+                file?
+                    .as_local()?
+                    .abs_path(cx)
+                    .to_string_lossy()
+                    .into_owned(),
+            )
+        }),
+        "RELATIVE_FILEPATH" => file.map(|file| file.path().to_string_lossy().into_owned()),
+        "WORKSPACE_NAME" => maybe!({
+            let full_path = file?.full_path(cx);
+            let worktree_name = full_path.components().next()?;
+            Some(worktree_name.as_os_str().to_string_lossy().into_owned())
+        }),
+        "WORKSPACE_FOLDER" => maybe!({
+            let file = file?.as_local()?;
+            let abs_path = file.abs_path(cx);
+            let worktree_root = abs_path.ancestors().nth(file.path().components().count())?;
+            Some(worktree_root.to_string_lossy().into_owned())
+        }),
+        "CLIPBOARD" => cx.read_from_clipboard().and_then(|item| item.text()),
+        "CURSOR_INDEX" => Some(cursor_index.to_string()),
+        "CURSOR_NUMBER" => Some((cursor_index + 1).to_string()),
+        "CURRENT_YEAR" => Some(now.format("%Y").to_string()),
+        "CURRENT_YEAR_SHORT" => Some(now.format("%y").to_string()),
+        "CURRENT_MONTH" => Some(now.format("%m").to_string()),
+        "CURRENT_MONTH_NAME" => Some(now.format("%B").to_string()),
+        "CURRENT_MONTH_NAME_SHORT" => Some(now.format("%b").to_string()),
+        "CURRENT_DATE" => Some(now.format("%d").to_string()),
+        "CURRENT_DAY_NAME" => Some(now.format("%A").to_string()),
+        "CURRENT_DAY_NAME_SHORT" => Some(now.format("%a").to_string()),
+        "CURRENT_HOUR" => Some(now.format("%H").to_string()),
+        "CURRENT_MINUTE" => Some(now.format("%M").to_string()),
+        "CURRENT_SECOND" => Some(now.format("%S").to_string()),
+        "CURRENT_SECONDS_UNIX" => Some(now.timestamp().to_string()),
+        "CURRENT_TIMEZONE_OFFSET" => Some(now.format("%:z").to_string()),
+        "RANDOM" => Some(format!("{:06}", rand::rng().random_range(0..1_000_000))),
+        "RANDOM_HEX" => Some(format!("{:06x}", rand::rng().random_range(0..0x1000000))),
+        "UUID" => Some(uuid::Uuid::new_v4().to_string()),
+        "LINE_COMMENT" => buffer.language_scope_at(cursor).and_then(|scope| {
+            let prefix = scope.line_comment_prefixes().first()?;
+            Some(prefix.trim_end().to_string())
+        }),
+        "BLOCK_COMMENT_START" => buffer
+            .language_scope_at(cursor)
+            .and_then(|scope| Some(scope.block_comment()?.start.trim_end().to_string())),
+        "BLOCK_COMMENT_END" => buffer
+            .language_scope_at(cursor)
+            .and_then(|scope| Some(scope.block_comment()?.end.trim_start().to_string())),
+        _ => return None,
+    };
+    Some(value.unwrap_or_default())
+}
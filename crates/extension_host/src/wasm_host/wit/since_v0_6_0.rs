@@ -977,6 +977,12 @@ impl ExtensionImports for WasmState {
                                 }),
                                 settings: None,
                             })?),
+                            project::project_settings::ContextServerSettings::Http { .. } => {
+                                Ok(serde_json::to_string(&settings::ContextServerSettings {
+                                    command: None,
+                                    settings: None,
+                                })?)
+                            }
                             project::project_settings::ContextServerSettings::Extension {
                                 enabled: _,
                                 settings,
@@ -5,18 +5,25 @@ use futures::future::{self, BoxFuture, join_all};
 use git::{
     Oid,
     blame::Blame,
+    history::{LogEntry, LogOptions},
+    rebase::{RebaseAction, RebaseCommand, RebaseStop, RebaseTodo, RebaseTodoEntry},
     repository::{
         AskPassDelegate, Branch, CommitDetails, CommitOptions, FetchOptions, GitRepository,
         GitRepositoryCheckpoint, PushOptions, Remote, RepoPath, ResetMode,
     },
+    sequencer::{SequencerCommand, SequencerOperation, SequencerState},
     status::{FileStatus, GitStatus, StatusCode, TrackedStatus, UnmergedStatus},
+    worktree::Worktree,
 };
 use gpui::{AsyncApp, BackgroundExecutor, SharedString, Task};
 use ignore::gitignore::GitignoreBuilder;
 use parking_lot::Mutex;
 use rope::Rope;
 use smol::future::FutureExt as _;
-use std::{path::PathBuf, sync::Arc};
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 
 #[derive(Clone)]
 pub struct FakeGitRepository {
@@ -39,6 +46,33 @@ pub struct FakeGitRepositoryState {
     pub branches: HashSet<String>,
     pub simulated_index_write_error_message: Option<String>,
     pub refs: HashMap<String, String>,
+    /// The commits of the current branch as `(sha, message)` pairs, oldest first.
+    pub commit_history: Vec<(String, String)>,
+    /// The paths changed by each commit, keyed by sha, for filtering the log by path.
+    pub changed_paths: HashMap<String, Vec<RepoPath>>,
+    /// Commits that stop an interactive rebase the first time they are applied.
+    pub simulated_rebase_conflicts: HashSet<String>,
+    pub rebase: Option<FakeRebase>,
+    /// Commits that stop a cherry-pick, revert or merge the first time they are applied.
+    pub simulated_sequencer_conflicts: HashSet<String>,
+    pub sequencer: Option<FakeSequencer>,
+    pub linked_worktrees: Vec<Worktree>,
+}
+
+#[derive(Debug, Clone)]
+pub struct FakeRebase {
+    pub original_history: Vec<(String, String)>,
+    pub rewritten_history: Vec<(String, String)>,
+    pub remaining: Vec<RebaseTodoEntry>,
+    pub total: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct FakeSequencer {
+    pub operation: SequencerOperation,
+    pub original_history: Vec<(String, String)>,
+    /// The commits left to apply, starting with the one that stopped the operation.
+    pub remaining: Vec<String>,
 }
 
 impl FakeGitRepositoryState {
@@ -53,7 +87,127 @@ impl FakeGitRepositoryState {
             branches: Default::default(),
             simulated_index_write_error_message: Default::default(),
             refs: HashMap::from_iter([("HEAD".into(), "abc".into())]),
+            commit_history: Default::default(),
+            changed_paths: Default::default(),
+            simulated_rebase_conflicts: Default::default(),
+            rebase: Default::default(),
+            simulated_sequencer_conflicts: Default::default(),
+            sequencer: Default::default(),
+            linked_worktrees: Default::default(),
+        }
+    }
+
+    fn start_sequencer(
+        &mut self,
+        operation: SequencerOperation,
+        commits: Vec<String>,
+    ) -> Result<Option<SequencerState>> {
+        if self.sequencer.is_some() || self.rebase.is_some() {
+            bail!("another operation is already in progress");
+        }
+        self.sequencer = Some(FakeSequencer {
+            operation,
+            original_history: self.commit_history.clone(),
+            remaining: commits,
+        });
+        Ok(self.advance_sequencer())
+    }
+
+    /// Applies the remaining commits of the cherry-pick, revert or merge in progress, until one
+    /// of them conflicts.
+    fn advance_sequencer(&mut self) -> Option<SequencerState> {
+        let operation = self.sequencer.as_ref()?.operation;
+        while let Some(commit) = self.sequencer.as_ref()?.remaining.first().cloned() {
+            if self.simulated_sequencer_conflicts.remove(&commit) {
+                self.refs
+                    .insert(operation.head_ref().to_string(), commit.clone());
+                return self.sequencer_state();
+            }
+            self.sequencer.as_mut()?.remaining.remove(0);
+            let entry = match operation {
+                SequencerOperation::CherryPick => {
+                    let message = self
+                        .commit_history
+                        .iter()
+                        .find(|(sha, _)| *sha == commit)
+                        .map(|(_, message)| message.clone())
+                        .unwrap_or_else(|| format!("Commit {commit}"));
+                    (format!("{commit}-picked"), message)
+                }
+                SequencerOperation::Revert => {
+                    (format!("{commit}-reverted"), format!("Revert {commit}"))
+                }
+                SequencerOperation::Merge => {
+                    (format!("{commit}-merged"), format!("Merge {commit}"))
+                }
+            };
+            self.refs.insert("HEAD".into(), entry.0.clone());
+            self.commit_history.push(entry);
         }
+        self.sequencer = None;
+        self.refs.remove(operation.head_ref());
+        None
+    }
+
+    fn sequencer_state(&self) -> Option<SequencerState> {
+        let sequencer = self.sequencer.as_ref()?;
+        let (head, remaining) = sequencer.remaining.split_first()?;
+        Some(SequencerState {
+            operation: sequencer.operation,
+            head: Some(head.clone().into()),
+            remaining: remaining.iter().cloned().map(Into::into).collect(),
+        })
+    }
+
+    fn linked_worktree_mut(&mut self, path: &Path) -> Result<&mut Worktree> {
+        self.linked_worktrees
+            .iter_mut()
+            .find(|worktree| worktree.path == *path)
+            .with_context(|| format!("{} is not a linked worktree", path.display()))
+    }
+
+    /// Replays the remaining entries of the rebase in progress, until one of them conflicts.
+    fn advance_rebase(&mut self) -> Option<RebaseStop> {
+        let rebase = self.rebase.as_mut()?;
+        while let Some(entry) = rebase.remaining.first().cloned() {
+            if self.simulated_rebase_conflicts.remove(entry.sha.as_ref()) {
+                return Some(RebaseStop {
+                    sha: Some(entry.sha),
+                    done: rebase.total - rebase.remaining.len() + 1,
+                    total: rebase.total,
+                });
+            }
+            rebase.remaining.remove(0);
+            let message = rebase
+                .original_history
+                .iter()
+                .find(|(sha, _)| *sha == entry.sha.as_ref())
+                .map(|(_, message)| message.clone())
+                .unwrap_or_else(|| entry.subject.to_string());
+            match entry.action {
+                RebaseAction::Pick => rebase
+                    .rewritten_history
+                    .push((entry.sha.to_string(), message)),
+                RebaseAction::Reword => rebase.rewritten_history.push((
+                    entry.sha.to_string(),
+                    entry.message.clone().unwrap_or(message),
+                )),
+                RebaseAction::Squash => {
+                    if let Some((_, previous_message)) = rebase.rewritten_history.last_mut() {
+                        previous_message.push_str("\n\n");
+                        previous_message.push_str(&message);
+                    }
+                }
+                RebaseAction::Fixup | RebaseAction::Drop => {}
+            }
+        }
+
+        let rebase = self.rebase.take()?;
+        if let Some((sha, _)) = rebase.rewritten_history.last() {
+            self.refs.insert("HEAD".into(), sha.clone());
+        }
+        self.commit_history = rebase.rewritten_history;
+        None
     }
 }
 
@@ -115,6 +269,70 @@ impl GitRepository for FakeGitRepository {
         unimplemented!()
     }
 
+    fn log(&self, options: LogOptions) -> BoxFuture<'_, Result<Vec<LogEntry>>> {
+        self.with_state_async(false, move |state| {
+            let history = &state.commit_history;
+            let end = match options.revision.as_deref() {
+                None | Some("HEAD") => history.len(),
+                Some(revision) => {
+                    history
+                        .iter()
+                        .position(|(sha, _)| sha == revision)
+                        .with_context(|| format!("unknown revision {revision}"))?
+                        + 1
+                }
+            };
+            Ok((0..end)
+                .rev()
+                .filter(|ix| {
+                    options.path.as_ref().is_none_or(|path| {
+                        state
+                            .changed_paths
+                            .get(&history[*ix].0)
+                            .is_some_and(|paths| paths.contains(path))
+                    })
+                })
+                .skip(options.skip)
+                .take(options.limit)
+                .map(|ix| {
+                    let (sha, message) = &history[ix];
+                    LogEntry {
+                        sha: sha.clone().into(),
+                        parents: ix
+                            .checked_sub(1)
+                            .map(|parent| history[parent].0.clone().into())
+                            .into_iter()
+                            .collect(),
+                        subject: message
+                            .lines()
+                            .next()
+                            .unwrap_or_default()
+                            .to_string()
+                            .into(),
+                        path: options.path.clone(),
+                        ..Default::default()
+                    }
+                })
+                .collect())
+        })
+    }
+
+    fn load_text_at_revision(
+        &self,
+        revision: String,
+        path: RepoPath,
+    ) -> BoxFuture<'_, Result<Option<String>>> {
+        self.with_state_async(false, move |state| {
+            let is_head = revision == "HEAD"
+                || state
+                    .commit_history
+                    .last()
+                    .is_some_and(|(sha, _)| *sha == revision);
+            anyhow::ensure!(is_head, "only HEAD is available in a fake repository");
+            Ok(state.head_contents.get(&path).cloned())
+        })
+    }
+
     fn set_index_text(
         &self,
         path: RepoPath,
@@ -573,14 +791,269 @@ impl GitRepository for FakeGitRepository {
     fn default_branch(&self) -> BoxFuture<'_, Result<Option<SharedString>>> {
         unimplemented!()
     }
+
+    fn rebase_todo(&self, upstream: String) -> BoxFuture<'_, Result<RebaseTodo>> {
+        self.with_state_async(false, move |state| {
+            let onto = state.refs.get(&upstream).unwrap_or(&upstream).clone();
+            let base_ix = state
+                .commit_history
+                .iter()
+                .position(|(sha, _)| *sha == onto)
+                .with_context(|| format!("unknown revision {upstream}"))?;
+            let entries = state.commit_history[base_ix + 1..]
+                .iter()
+                .map(|(sha, message)| RebaseTodoEntry {
+                    action: RebaseAction::Pick,
+                    sha: sha.clone().into(),
+                    subject: message
+                        .lines()
+                        .next()
+                        .unwrap_or_default()
+                        .to_string()
+                        .into(),
+                    message: None,
+                })
+                .collect();
+            Ok(RebaseTodo {
+                onto: onto.into(),
+                entries,
+            })
+        })
+    }
+
+    fn rebase_interactive(
+        &self,
+        todo: RebaseTodo,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<Option<RebaseStop>>> {
+        self.with_state_async(true, move |state| {
+            todo.validate()?;
+            if state.rebase.is_some() {
+                bail!("a rebase is already in progress");
+            }
+            let base_ix = state
+                .commit_history
+                .iter()
+                .position(|(sha, _)| *sha == todo.onto.as_ref())
+                .with_context(|| format!("unknown revision {}", todo.onto))?;
+            state.rebase = Some(FakeRebase {
+                original_history: state.commit_history.clone(),
+                rewritten_history: state.commit_history[..=base_ix].to_vec(),
+                total: todo.entries.len(),
+                remaining: todo.entries,
+            });
+            Ok(state.advance_rebase())
+        })
+    }
+
+    fn rebase_command(
+        &self,
+        command: RebaseCommand,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<Option<RebaseStop>>> {
+        self.with_state_async(true, move |state| {
+            let Some(rebase) = state.rebase.as_mut() else {
+                bail!("no rebase in progress");
+            };
+            match command {
+                RebaseCommand::Continue => {}
+                RebaseCommand::Skip => {
+                    if !rebase.remaining.is_empty() {
+                        rebase.remaining.remove(0);
+                    }
+                }
+                RebaseCommand::Abort => {
+                    state.commit_history = rebase.original_history.clone();
+                    state.rebase = None;
+                    return Ok(None);
+                }
+            }
+            Ok(state.advance_rebase())
+        })
+    }
+
+    fn rebase_stop(&self) -> BoxFuture<'_, Result<Option<RebaseStop>>> {
+        self.with_state_async(false, |state| {
+            Ok(state.rebase.as_ref().and_then(|rebase| {
+                let entry = rebase.remaining.first()?;
+                Some(RebaseStop {
+                    sha: Some(entry.sha.clone()),
+                    done: rebase.total - rebase.remaining.len() + 1,
+                    total: rebase.total,
+                })
+            }))
+        })
+    }
+
+    fn cherry_pick(
+        &self,
+        commits: Vec<String>,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<Option<SequencerState>>> {
+        self.with_state_async(true, move |state| {
+            state.start_sequencer(SequencerOperation::CherryPick, commits)
+        })
+    }
+
+    fn revert(
+        &self,
+        commits: Vec<String>,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<Option<SequencerState>>> {
+        self.with_state_async(true, move |state| {
+            state.start_sequencer(SequencerOperation::Revert, commits)
+        })
+    }
+
+    fn merge(
+        &self,
+        revision: String,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<Option<SequencerState>>> {
+        self.with_state_async(true, move |state| {
+            state.start_sequencer(SequencerOperation::Merge, vec![revision])
+        })
+    }
+
+    fn sequencer_command(
+        &self,
+        operation: SequencerOperation,
+        command: SequencerCommand,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<Option<SequencerState>>> {
+        self.with_state_async(true, move |state| {
+            let Some(sequencer) = state.sequencer.as_mut() else {
+                bail!("no {} in progress", operation.as_command());
+            };
+            if sequencer.operation != operation || !operation.supports(command) {
+                bail!("cannot {} {}", operation.as_command(), command.as_arg());
+            }
+            match command {
+                SequencerCommand::Continue => {}
+                SequencerCommand::Skip => {
+                    if !sequencer.remaining.is_empty() {
+                        sequencer.remaining.remove(0);
+                    }
+                }
+                SequencerCommand::Abort => {
+                    state.commit_history = sequencer.original_history.clone();
+                    state.sequencer = None;
+                    state.refs.remove(operation.head_ref());
+                    return Ok(None);
+                }
+            }
+            Ok(state.advance_sequencer())
+        })
+    }
+
+    fn sequencer_state(&self) -> BoxFuture<'_, Result<Option<SequencerState>>> {
+        self.with_state_async(false, |state| Ok(state.sequencer_state()))
+    }
+
+    fn worktrees(&self) -> BoxFuture<'_, Result<Vec<Worktree>>> {
+        let main_path = self.repository_dir_path.parent().unwrap().to_path_buf();
+        self.with_state_async(false, move |state| {
+            let main_worktree = Worktree {
+                path: main_path,
+                head_sha: state.refs.get("HEAD").map(|sha| sha.clone().into()),
+                ref_name: state
+                    .current_branch_name
+                    .as_ref()
+                    .map(|branch| format!("refs/heads/{branch}").into()),
+                is_main: true,
+                ..Default::default()
+            };
+            Ok(std::iter::once(main_worktree)
+                .chain(state.linked_worktrees.iter().cloned())
+                .collect())
+        })
+    }
+
+    fn create_worktree(
+        &self,
+        path: PathBuf,
+        branch: String,
+        create_branch: bool,
+    ) -> BoxFuture<'_, Result<()>> {
+        self.with_state_async(true, move |state| {
+            let ref_name = format!("refs/heads/{branch}");
+            if create_branch {
+                if !state.branches.insert(branch.clone()) {
+                    bail!("a branch named '{branch}' already exists");
+                }
+            } else if state.current_branch_name.as_ref() == Some(&branch)
+                || state
+                    .linked_worktrees
+                    .iter()
+                    .any(|worktree| worktree.ref_name.as_deref() == Some(ref_name.as_str()))
+            {
+                bail!("'{branch}' is already checked out");
+            }
+            if state
+                .linked_worktrees
+                .iter()
+                .any(|worktree| worktree.path == path)
+            {
+                bail!("'{}' already exists", path.display());
+            }
+            state.linked_worktrees.push(Worktree {
+                path,
+                head_sha: state.refs.get("HEAD").map(|sha| sha.clone().into()),
+                ref_name: Some(ref_name.into()),
+                ..Default::default()
+            });
+            Ok(())
+        })
+    }
+
+    fn lock_worktree(&self, path: PathBuf, reason: Option<String>) -> BoxFuture<'_, Result<()>> {
+        self.with_state_async(true, move |state| {
+            let worktree = state.linked_worktree_mut(&path)?;
+            if worktree.is_locked {
+                bail!("'{}' is already locked", path.display());
+            }
+            worktree.is_locked = true;
+            worktree.lock_reason = reason.map(Into::into);
+            Ok(())
+        })
+    }
+
+    fn unlock_worktree(&self, path: PathBuf) -> BoxFuture<'_, Result<()>> {
+        self.with_state_async(true, move |state| {
+            let worktree = state.linked_worktree_mut(&path)?;
+            if !worktree.is_locked {
+                bail!("'{}' is not locked", path.display());
+            }
+            worktree.is_locked = false;
+            worktree.lock_reason = None;
+            Ok(())
+        })
+    }
+
+    fn remove_worktree(&self, path: PathBuf, _force: bool) -> BoxFuture<'_, Result<()>> {
+        self.with_state_async(true, move |state| {
+            if state.linked_worktree_mut(&path)?.is_locked {
+                bail!("cannot remove a locked working tree");
+            }
+            state
+                .linked_worktrees
+                .retain(|worktree| worktree.path != path);
+            Ok(())
+        })
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::{FakeFs, Fs};
+    use git::{
+        history::LogOptions,
+        rebase::{RebaseAction, RebaseCommand},
+        sequencer::{SequencerCommand, SequencerOperation},
+    };
     use gpui::BackgroundExecutor;
     use serde_json::json;
-    use std::path::Path;
+    use std::path::{Path, PathBuf};
     use util::path;
 
     #[gpui::test]
@@ -633,4 +1106,249 @@ mod tests {
             ]
         );
     }
+
+    #[gpui::test]
+    async fn test_interactive_rebase(executor: BackgroundExecutor) {
+        let fs = FakeFs::new(executor);
+        fs.insert_tree(path!("/foo"), json!({ ".git": {}, "a": "lorem" }))
+            .await;
+        fs.with_git_state(Path::new("/foo/.git"), true, |state| {
+            state.commit_history = ["base", "one", "two", "three"]
+                .into_iter()
+                .map(|sha| (sha.to_string(), format!("Commit {sha}")))
+                .collect();
+            state.simulated_rebase_conflicts.insert("three".into());
+        })
+        .unwrap();
+        let repository = fs.open_repo(Path::new("/foo/.git")).unwrap();
+
+        let mut todo = repository.rebase_todo("base".into()).await.unwrap();
+        assert_eq!(todo.entries.len(), 3);
+        assert_eq!(todo.entries[0].subject, "Commit one");
+
+        todo.move_entry(2, 0);
+        todo.set_action(2, RebaseAction::Squash);
+        todo.set_message(1, "First commit".into());
+        let stop = repository
+            .rebase_interactive(todo, Default::default())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(stop.sha.as_deref(), Some("three"));
+        assert_eq!((stop.done, stop.total), (1, 3));
+        assert_eq!(repository.rebase_stop().await.unwrap(), Some(stop));
+
+        let stop = repository
+            .rebase_command(RebaseCommand::Continue, Default::default())
+            .await
+            .unwrap();
+        assert_eq!(stop, None);
+        assert_eq!(repository.rebase_stop().await.unwrap(), None);
+        let history = fs
+            .with_git_state(Path::new("/foo/.git"), false, |state| {
+                state.commit_history.clone()
+            })
+            .unwrap();
+        assert_eq!(
+            history,
+            [
+                ("base".to_string(), "Commit base".to_string()),
+                ("three".to_string(), "Commit three".to_string()),
+                ("one".to_string(), "First commit\n\nCommit two".to_string()),
+            ]
+        );
+    }
+
+    #[gpui::test]
+    async fn test_cherry_pick_with_conflicts(executor: BackgroundExecutor) {
+        let fs = FakeFs::new(executor);
+        fs.insert_tree(path!("/foo"), json!({ ".git": {}, "a": "lorem" }))
+            .await;
+        fs.with_git_state(Path::new("/foo/.git"), true, |state| {
+            state.commit_history = vec![("base".into(), "Commit base".into())];
+            state.simulated_sequencer_conflicts.insert("two".into());
+        })
+        .unwrap();
+        let repository = fs.open_repo(Path::new("/foo/.git")).unwrap();
+
+        let commits = ["one", "two", "three"].map(String::from).to_vec();
+        let state = repository
+            .cherry_pick(commits, Default::default())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(state.operation, SequencerOperation::CherryPick);
+        assert_eq!(state.head.as_deref(), Some("two"));
+        assert_eq!(state.remaining, ["three"]);
+        assert_eq!(repository.sequencer_state().await.unwrap(), Some(state));
+        assert_eq!(
+            repository
+                .revparse_batch(vec!["CHERRY_PICK_HEAD".into()])
+                .await
+                .unwrap(),
+            [Some("two".to_string())]
+        );
+        assert!(
+            repository
+                .sequencer_command(
+                    SequencerOperation::Merge,
+                    SequencerCommand::Continue,
+                    Default::default()
+                )
+                .await
+                .is_err()
+        );
+
+        let state = repository
+            .sequencer_command(
+                SequencerOperation::CherryPick,
+                SequencerCommand::Skip,
+                Default::default(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(state, None);
+        assert_eq!(repository.sequencer_state().await.unwrap(), None);
+        let history = fs
+            .with_git_state(Path::new("/foo/.git"), false, |state| {
+                state.commit_history.clone()
+            })
+            .unwrap();
+        assert_eq!(
+            history
+                .iter()
+                .map(|(sha, _)| sha.as_str())
+                .collect::<Vec<_>>(),
+            ["base", "one-picked", "three-picked"]
+        );
+
+        fs.with_git_state(Path::new("/foo/.git"), true, |state| {
+            state.simulated_sequencer_conflicts.insert("topic".into());
+        })
+        .unwrap();
+        let state = repository
+            .merge("topic".into(), Default::default())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(state.operation, SequencerOperation::Merge);
+        repository
+            .sequencer_command(
+                SequencerOperation::Merge,
+                SequencerCommand::Abort,
+                Default::default(),
+            )
+            .await
+            .unwrap();
+        let history = fs
+            .with_git_state(Path::new("/foo/.git"), false, |state| {
+                state.commit_history.len()
+            })
+            .unwrap();
+        assert_eq!(history, 3);
+    }
+
+    #[gpui::test]
+    async fn test_linked_worktrees(executor: BackgroundExecutor) {
+        let fs = FakeFs::new(executor);
+        fs.insert_tree(path!("/foo"), json!({ ".git": {}, "a": "lorem" }))
+            .await;
+        fs.with_git_state(Path::new("/foo/.git"), true, |state| {
+            state.current_branch_name = Some("main".into());
+            state.branches.extend(["main".into(), "review".into()]);
+        })
+        .unwrap();
+        let repository = fs.open_repo(Path::new("/foo/.git")).unwrap();
+
+        let review_path = PathBuf::from(path!("/foo-review"));
+        let feature_path = PathBuf::from(path!("/foo-feature"));
+        assert!(
+            repository
+                .create_worktree(review_path.clone(), "main".into(), false)
+                .await
+                .is_err()
+        );
+        repository
+            .create_worktree(review_path.clone(), "review".into(), false)
+            .await
+            .unwrap();
+        repository
+            .create_worktree(feature_path.clone(), "feature".into(), true)
+            .await
+            .unwrap();
+
+        let worktrees = repository.worktrees().await.unwrap();
+        assert_eq!(
+            worktrees
+                .iter()
+                .map(|worktree| (worktree.is_main, worktree.branch_name()))
+                .collect::<Vec<_>>(),
+            [
+                (true, Some("main")),
+                (false, Some("review")),
+                (false, Some("feature"))
+            ]
+        );
+
+        repository
+            .lock_worktree(review_path.clone(), Some("in review".into()))
+            .await
+            .unwrap();
+        assert!(
+            repository
+                .remove_worktree(review_path.clone(), true)
+                .await
+                .is_err()
+        );
+        repository
+            .unlock_worktree(review_path.clone())
+            .await
+            .unwrap();
+        repository
+            .remove_worktree(review_path, false)
+            .await
+            .unwrap();
+
+        let worktrees = repository.worktrees().await.unwrap();
+        assert_eq!(worktrees.len(), 2);
+        assert_eq!(worktrees[1].path, feature_path);
+    }
+
+    #[gpui::test]
+    async fn test_log(executor: BackgroundExecutor) {
+        let fs = FakeFs::new(executor);
+        fs.insert_tree(path!("/foo"), json!({ ".git": {}, "a": "lorem" }))
+            .await;
+        fs.with_git_state(Path::new("/foo/.git"), true, |state| {
+            state.commit_history = ["one", "two", "three"]
+                .into_iter()
+                .map(|sha| (sha.to_string(), format!("Commit {sha}\n\nDetails")))
+                .collect();
+        })
+        .unwrap();
+        let repository = fs.open_repo(Path::new("/foo/.git")).unwrap();
+
+        let entries = repository.log(LogOptions::default()).await.unwrap();
+        assert_eq!(
+            entries
+                .iter()
+                .map(|entry| entry.sha.as_ref())
+                .collect::<Vec<_>>(),
+            ["three", "two", "one"]
+        );
+        assert_eq!(entries[0].subject, "Commit three");
+        assert_eq!(entries[0].parents, ["two"]);
+        assert!(entries[2].parents.is_empty());
+
+        let page = repository
+            .log(LogOptions {
+                skip: 1,
+                limit: 1,
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(page.len(), 1);
+        assert_eq!(page[0].sha, "two");
+    }
 }
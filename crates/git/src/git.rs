@@ -1,10 +1,14 @@
 pub mod blame;
 pub mod commit;
+pub mod history;
 mod hosting_provider;
+pub mod rebase;
 mod remote;
 pub mod repository;
+pub mod sequencer;
 pub mod stash;
 pub mod status;
+pub mod worktree;
 
 pub use crate::hosting_provider::*;
 pub use crate::remote::*;
@@ -99,6 +103,22 @@ actions!(
         OpenModifiedFiles,
         /// Clones a repository.
         Clone,
+        /// Applies the commit shown in the commit view on top of the current branch.
+        CherryPickCommit,
+        /// Creates a commit that undoes the commit shown in the commit view.
+        RevertCommit,
+        /// Continues the cherry-pick, revert or merge in progress once its conflicts are resolved.
+        ContinueOperation,
+        /// Skips the commit that stopped the cherry-pick or revert in progress.
+        SkipOperationCommit,
+        /// Aborts the cherry-pick, revert or merge in progress.
+        AbortOperation,
+        /// Opens the commit history of the active repository.
+        ViewLog,
+        /// Opens the commit history of the active file.
+        FileHistory,
+        /// Opens the commit history of the lines selected in the active editor.
+        SelectionHistory,
     ]
 );
 
@@ -0,0 +1,358 @@
+use crate::repository::RepoPath;
+use gpui::SharedString;
+use std::{ffi::OsString, ops::RangeInclusive};
+
+/// The number of commits loaded at a time when paging through the log.
+pub const LOG_PAGE_SIZE: usize = 200;
+
+/// The format passed to `git log`: a record separator followed by NUL-separated fields, so that
+/// the patches printed by `-L` can be told apart from the commit headers.
+const LOG_FORMAT: &str = "--format=%x1e%H%x00%P%x00%an%x00%ae%x00%ct%x00%D%x00%s";
+
+/// Selects the commits listed by `GitRepository::log`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LogOptions {
+    /// The revision to list the history of, or `HEAD` when unset.
+    pub revision: Option<String>,
+    /// Only lists the commits that changed this path, following it across renames.
+    pub path: Option<RepoPath>,
+    /// Only lists the commits that changed these lines of `path`, as `git log -L` does.
+    ///
+    /// Lines are 1-based and refer to the file as of `revision`.
+    pub line_range: Option<RangeInclusive<u32>>,
+    /// The number of commits to skip, for pagination.
+    pub skip: usize,
+    pub limit: usize,
+}
+
+impl Default for LogOptions {
+    fn default() -> Self {
+        Self {
+            revision: None,
+            path: None,
+            line_range: None,
+            skip: 0,
+            limit: LOG_PAGE_SIZE,
+        }
+    }
+}
+
+impl LogOptions {
+    pub(crate) fn args(&self) -> Vec<OsString> {
+        let mut args: Vec<OsString> = vec![
+            "--no-optional-locks".into(),
+            // Prints the paths of renamed files unquoted, for `LogEntry::path`.
+            "-c".into(),
+            "core.quotePath=false".into(),
+            "log".into(),
+            "--no-color".into(),
+            "--decorate=short".into(),
+            LOG_FORMAT.into(),
+            format!("--skip={}", self.skip).into(),
+            format!("--max-count={}", self.limit).into(),
+        ];
+        let revision = self.revision.as_deref().unwrap_or("HEAD");
+        match (&self.path, &self.line_range) {
+            (Some(path), Some(range)) => {
+                let mut arg = OsString::from(format!("-L{},{}:", range.start(), range.end()));
+                arg.push(path.to_unix_style());
+                args.extend([arg, revision.into()]);
+            }
+            (Some(path), None) => {
+                args.extend([
+                    "--follow".into(),
+                    "--name-only".into(),
+                    revision.into(),
+                    "--".into(),
+                    path.to_unix_style().into_owned(),
+                ]);
+            }
+            (None, _) => args.push(revision.into()),
+        }
+        args
+    }
+}
+
+/// A commit listed by `GitRepository::log`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LogEntry {
+    pub sha: SharedString,
+    /// The parents of the commit, first parent first.
+    pub parents: Vec<SharedString>,
+    pub subject: SharedString,
+    pub author_name: SharedString,
+    pub author_email: SharedString,
+    /// This is a unix timestamp
+    pub commit_timestamp: i64,
+    /// The branches and tags pointing at the commit.
+    pub refs: Vec<SharedString>,
+    /// The path of the file the log is filtered by, as of this commit. It differs from
+    /// [`LogOptions::path`] in the commits before the file was renamed.
+    pub path: Option<RepoPath>,
+}
+
+impl LogEntry {
+    pub fn is_merge(&self) -> bool {
+        self.parents.len() > 1
+    }
+}
+
+/// Parses the output of `git log` run with [`LogOptions::args`].
+pub fn parse_log(output: &str) -> Vec<LogEntry> {
+    output
+        .split('\x1e')
+        .filter_map(|record| {
+            let mut lines = record.lines();
+            let header = lines.next()?;
+            let path = record_path(lines);
+            let mut fields = header.split('\0');
+            let sha = fields.next().filter(|sha| !sha.is_empty())?;
+            let parents = fields.next()?;
+            let author_name = fields.next()?;
+            let author_email = fields.next()?;
+            let commit_timestamp = fields.next()?.parse().ok()?;
+            let refs = fields.next()?;
+            let subject = fields.next().unwrap_or_default();
+            Some(LogEntry {
+                sha: sha.to_string().into(),
+                parents: parents
+                    .split_whitespace()
+                    .map(|parent| parent.to_string().into())
+                    .collect(),
+                subject: subject.to_string().into(),
+                author_name: author_name.to_string().into(),
+                author_email: author_email.to_string().into(),
+                commit_timestamp,
+                refs: refs
+                    .split(", ")
+                    .map(|name| name.strip_prefix("HEAD -> ").unwrap_or(name))
+                    .filter(|name| !name.is_empty())
+                    .map(|name| name.to_string().into())
+                    .collect(),
+                path: path.map(RepoPath::from_str),
+            })
+        })
+        .collect()
+}
+
+/// Finds the path of the file in the lines following a commit header: the new path of the patch
+/// printed by `-L`, or the path printed by `--name-only`.
+fn record_path<'a>(mut lines: impl Iterator<Item = &'a str> + Clone) -> Option<&'a str> {
+    let mut patch_lines = lines
+        .clone()
+        .skip_while(|line| !line.starts_with("diff --git "));
+    if patch_lines.next().is_some() {
+        return patch_lines.find_map(|line| line.strip_prefix("+++ b/"));
+    }
+    lines.find(|line| !line.is_empty())
+}
+
+/// How a commit of the log is drawn in the commit graph.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GraphRow {
+    /// The lane the commit is drawn in.
+    pub lane: usize,
+    /// Whether a commit above is a child of this one, so that the lane continues upwards.
+    pub has_children: bool,
+    /// The lanes that end at this commit, because it is the parent they were waiting for.
+    pub merged_lanes: Vec<usize>,
+    /// The lanes that continue below this commit towards its parents, first parent first.
+    pub parent_lanes: Vec<usize>,
+    /// The lanes of other commits that pass alongside this commit.
+    pub passing_lanes: Vec<usize>,
+}
+
+impl GraphRow {
+    /// The number of lanes the row spans.
+    pub fn width(&self) -> usize {
+        self.merged_lanes
+            .iter()
+            .chain(&self.parent_lanes)
+            .chain(&self.passing_lanes)
+            .copied()
+            .chain([self.lane])
+            .max()
+            .map_or(0, |lane| lane + 1)
+    }
+}
+
+/// Lays out the commits of a log in lanes, one page at a time.
+///
+/// Commits must be pushed in log order, children before their parents.
+#[derive(Clone, Debug, Default)]
+pub struct CommitGraph {
+    /// The commit each lane is waiting for, or `None` for a free lane.
+    lanes: Vec<Option<SharedString>>,
+}
+
+impl CommitGraph {
+    pub fn push(&mut self, entry: &LogEntry) -> GraphRow {
+        let waiting = self
+            .lanes
+            .iter()
+            .enumerate()
+            .filter(|(_, sha)| sha.as_ref() == Some(&entry.sha))
+            .map(|(lane, _)| lane)
+            .collect::<Vec<_>>();
+        let has_children = !waiting.is_empty();
+        let lane = match waiting.first() {
+            Some(lane) => *lane,
+            None => self.free_lane(),
+        };
+        let merged_lanes = waiting.into_iter().skip(1).collect::<Vec<_>>();
+        for merged_lane in &merged_lanes {
+            self.lanes[*merged_lane] = None;
+        }
+        let passing_lanes = self
+            .lanes
+            .iter()
+            .enumerate()
+            .filter(|(ix, sha)| *ix != lane && sha.is_some())
+            .map(|(ix, _)| ix)
+            .collect();
+
+        self.lanes[lane] = entry.parents.first().cloned();
+        let mut parent_lanes = Vec::with_capacity(entry.parents.len());
+        if !entry.parents.is_empty() {
+            parent_lanes.push(lane);
+        }
+        for parent in entry.parents.iter().skip(1) {
+            let parent_lane = match self
+                .lanes
+                .iter()
+                .position(|sha| sha.as_ref() == Some(parent))
+            {
+                Some(parent_lane) => parent_lane,
+                None => {
+                    let parent_lane = self.free_lane();
+                    self.lanes[parent_lane] = Some(parent.clone());
+                    parent_lane
+                }
+            };
+            parent_lanes.push(parent_lane);
+        }
+        while self.lanes.last().is_some_and(Option::is_none) {
+            self.lanes.pop();
+        }
+
+        GraphRow {
+            lane,
+            has_children,
+            merged_lanes,
+            parent_lanes,
+            passing_lanes,
+        }
+    }
+
+    fn free_lane(&mut self) -> usize {
+        match self.lanes.iter().position(Option::is_none) {
+            Some(lane) => lane,
+            None => {
+                self.lanes.push(None);
+                self.lanes.len() - 1
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_log() {
+        let output = "\x1eaaaa\0bbbb cccc\0Ada\0ada@example.com\01700000000\0HEAD -> main, origin/main, tag: v1.0\0Merge branch 'feature'\n\
+            \x1ebbbb\0dddd\0Grace\0grace@example.com\01690000000\0\0Fix the build\n\
+            \n\
+            diff --git a/src/main.rs b/src/main.rs\n\
+            --- a/src/main.rs\n\
+            +++ b/src/main.rs\n\
+            @@ -1,1 +1,1 @@\n\
+            \x1edddd\0\0Grace\0grace@example.com\01680000000\0\0Initial commit\n";
+        let entries = parse_log(output);
+        assert_eq!(entries.len(), 3);
+
+        assert_eq!(entries[0].sha, "aaaa");
+        assert!(entries[0].is_merge());
+        assert_eq!(
+            entries[0].refs,
+            vec![
+                SharedString::from("main"),
+                "origin/main".into(),
+                "tag: v1.0".into()
+            ]
+        );
+        assert_eq!(entries[0].subject, "Merge branch 'feature'");
+
+        assert_eq!(entries[1].author_name, "Grace");
+        assert_eq!(entries[1].commit_timestamp, 1690000000);
+        assert!(entries[1].refs.is_empty());
+        assert_eq!(entries[1].subject, "Fix the build");
+        assert_eq!(entries[1].path, Some(RepoPath::from_str("src/main.rs")));
+
+        assert!(entries[2].parents.is_empty());
+        assert_eq!(entries[2].path, None);
+    }
+
+    #[test]
+    fn test_parse_log_with_renames() {
+        let output = "\x1eaaaa\0bbbb\0Ada\0ada@example.com\01700000000\0\0Rename the parser\n\
+            \n\
+            src/parser.rs\n\
+            \x1ebbbb\0\0Ada\0ada@example.com\01690000000\0\0Add the parser\n\
+            \n\
+            src/parse.rs\n";
+        let paths = parse_log(output)
+            .into_iter()
+            .map(|entry| entry.path)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            paths,
+            [
+                Some(RepoPath::from_str("src/parser.rs")),
+                Some(RepoPath::from_str("src/parse.rs"))
+            ]
+        );
+    }
+
+    #[test]
+    fn test_commit_graph() {
+        let entry = |sha: &str, parents: &[&str]| LogEntry {
+            sha: sha.to_string().into(),
+            parents: parents
+                .iter()
+                .map(|parent| parent.to_string().into())
+                .collect(),
+            ..Default::default()
+        };
+        // d merges c into b, and both branches fork from a.
+        let entries = [
+            entry("d", &["b", "c"]),
+            entry("c", &["a"]),
+            entry("b", &["a"]),
+            entry("a", &[]),
+        ];
+        let mut graph = CommitGraph::default();
+        let rows = entries
+            .iter()
+            .map(|entry| graph.push(entry))
+            .collect::<Vec<_>>();
+
+        assert_eq!(rows[0].lane, 0);
+        assert!(!rows[0].has_children);
+        assert_eq!(rows[0].parent_lanes, vec![0, 1]);
+
+        assert_eq!(rows[1].lane, 1);
+        assert!(rows[1].has_children);
+        assert_eq!(rows[1].passing_lanes, vec![0]);
+        assert_eq!(rows[1].width(), 2);
+
+        assert_eq!(rows[2].lane, 0);
+        assert_eq!(rows[2].passing_lanes, vec![1]);
+
+        assert_eq!(rows[3].lane, 0);
+        assert_eq!(rows[3].merged_lanes, vec![1]);
+        assert!(rows[3].parent_lanes.is_empty());
+        assert!(graph.lanes.is_empty());
+    }
+}
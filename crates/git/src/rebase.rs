@@ -0,0 +1,269 @@
+use anyhow::{Result, anyhow};
+use gpui::SharedString;
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+
+/// The environment variable holding the path of the todo list that Zed, acting as git's sequence
+/// editor, copies over the one git generated for an interactive rebase.
+pub const REBASE_TODO_ENV_VAR: &str = "ZED_REBASE_TODO";
+
+/// What to do with a commit when replaying it during an interactive rebase.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RebaseAction {
+    /// Keep the commit as is.
+    Pick,
+    /// Keep the commit, but change its message.
+    Reword,
+    /// Meld the commit into the previous one, combining both messages.
+    Squash,
+    /// Meld the commit into the previous one, discarding its message.
+    Fixup,
+    /// Remove the commit.
+    Drop,
+}
+
+impl RebaseAction {
+    pub const ALL: [RebaseAction; 5] = [
+        RebaseAction::Pick,
+        RebaseAction::Reword,
+        RebaseAction::Squash,
+        RebaseAction::Fixup,
+        RebaseAction::Drop,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RebaseAction::Pick => "pick",
+            RebaseAction::Reword => "reword",
+            RebaseAction::Squash => "squash",
+            RebaseAction::Fixup => "fixup",
+            RebaseAction::Drop => "drop",
+        }
+    }
+
+    /// Whether the commit is melded into the one before it.
+    pub fn melds_into_previous(&self) -> bool {
+        matches!(self, RebaseAction::Squash | RebaseAction::Fixup)
+    }
+}
+
+impl fmt::Display for RebaseAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for RebaseAction {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "p" | "pick" => Ok(RebaseAction::Pick),
+            "r" | "reword" => Ok(RebaseAction::Reword),
+            "s" | "squash" => Ok(RebaseAction::Squash),
+            "f" | "fixup" => Ok(RebaseAction::Fixup),
+            "d" | "drop" => Ok(RebaseAction::Drop),
+            _ => Err(anyhow!("unsupported rebase action: {s}")),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RebaseTodoEntry {
+    pub action: RebaseAction,
+    pub sha: SharedString,
+    pub subject: SharedString,
+    /// The new message of the commit, for entries whose action is [`RebaseAction::Reword`].
+    pub message: Option<String>,
+}
+
+/// The plan for an interactive rebase, in the order the commits will be replayed.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RebaseTodo {
+    /// The commit on top of which the entries are replayed.
+    pub onto: SharedString,
+    pub entries: Vec<RebaseTodoEntry>,
+}
+
+impl RebaseTodo {
+    pub fn set_action(&mut self, ix: usize, action: RebaseAction) {
+        if let Some(entry) = self.entries.get_mut(ix) {
+            entry.action = action;
+        }
+    }
+
+    pub fn set_message(&mut self, ix: usize, message: String) {
+        if let Some(entry) = self.entries.get_mut(ix) {
+            entry.action = RebaseAction::Reword;
+            entry.message = Some(message);
+        }
+    }
+
+    /// Moves the entry at `from` so that it ends up at index `to`.
+    pub fn move_entry(&mut self, from: usize, to: usize) {
+        if from < self.entries.len() && to < self.entries.len() {
+            let entry = self.entries.remove(from);
+            self.entries.insert(to, entry);
+        }
+    }
+
+    /// Checks that the plan can be executed, i.e. that every squash or fixup has a commit to meld into.
+    pub fn validate(&self) -> Result<()> {
+        let first_kept = self
+            .entries
+            .iter()
+            .find(|entry| entry.action != RebaseAction::Drop);
+        if let Some(entry) = first_kept
+            && entry.action.melds_into_previous()
+        {
+            anyhow::bail!(
+                "cannot {} {} without a previous commit",
+                entry.action,
+                entry.sha
+            );
+        }
+        Ok(())
+    }
+
+    /// Renders the plan in the format of git's `rebase-merge/git-rebase-todo` file.
+    ///
+    /// Reworded entries are picked, then amended by running the command returned by `reword_command`.
+    pub fn to_todo_file(&self, reword_command: impl Fn(usize) -> String) -> String {
+        let mut todo = String::new();
+        for (ix, entry) in self.entries.iter().enumerate() {
+            match (entry.action, &entry.message) {
+                (RebaseAction::Reword, Some(_)) => {
+                    todo.push_str(&format!("pick {} {}\n", entry.sha, entry.subject));
+                    todo.push_str(&format!("exec {}\n", reword_command(ix)));
+                }
+                (RebaseAction::Reword, None) => {
+                    todo.push_str(&format!("pick {} {}\n", entry.sha, entry.subject));
+                }
+                (action, _) => {
+                    todo.push_str(&format!("{action} {} {}\n", entry.sha, entry.subject));
+                }
+            }
+        }
+        todo
+    }
+}
+
+/// Where an interactive rebase stopped, waiting for the user to resolve conflicts.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RebaseStop {
+    /// The commit that could not be applied.
+    pub sha: Option<SharedString>,
+    /// The number of todo entries that were processed, including the one that stopped.
+    pub done: usize,
+    pub total: usize,
+}
+
+impl RebaseStop {
+    /// Parses the contents of the `msgnum`, `end` and `stopped-sha` files in `rebase-merge/`.
+    pub fn parse(msgnum: &str, end: &str, stopped_sha: Option<&str>) -> Result<Self> {
+        Ok(Self {
+            sha: stopped_sha
+                .map(str::trim)
+                .filter(|sha| !sha.is_empty())
+                .map(|sha| SharedString::from(sha.to_string())),
+            done: msgnum.trim().parse()?,
+            total: end.trim().parse()?,
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RebaseCommand {
+    /// Continues after the conflicts of the current commit were resolved.
+    Continue,
+    /// Skips the current commit.
+    Skip,
+    /// Restores the branch to its state before the rebase started.
+    Abort,
+}
+
+impl RebaseCommand {
+    pub fn as_arg(&self) -> &'static str {
+        match self {
+            RebaseCommand::Continue => "--continue",
+            RebaseCommand::Skip => "--skip",
+            RebaseCommand::Abort => "--abort",
+        }
+    }
+}
+
+/// The main function for when Zed runs as git's sequence editor, given the todo list to replace.
+/// Called from the main function of the zed binary, as rebases only run in local repositories.
+pub fn sequence_editor_main(todo_path: &Path) {
+    let Some(plan_path) = std::env::var_os(REBASE_TODO_ENV_VAR) else {
+        eprintln!("{REBASE_TODO_ENV_VAR} is not set");
+        std::process::exit(1);
+    };
+    if let Err(err) = std::fs::copy(&plan_path, todo_path) {
+        eprintln!(
+            "Error copying {} to {}: {err}",
+            Path::new(&plan_path).display(),
+            todo_path.display()
+        );
+        std::process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(action: RebaseAction, sha: &str, subject: &str) -> RebaseTodoEntry {
+        RebaseTodoEntry {
+            action,
+            sha: sha.to_string().into(),
+            subject: subject.to_string().into(),
+            message: None,
+        }
+    }
+
+    #[test]
+    fn test_rebase_todo_file() {
+        let mut todo = RebaseTodo {
+            onto: "base".into(),
+            entries: vec![
+                entry(RebaseAction::Pick, "aaa", "First"),
+                entry(RebaseAction::Pick, "bbb", "Second"),
+                entry(RebaseAction::Pick, "ccc", "Third"),
+                entry(RebaseAction::Pick, "ddd", "Fourth"),
+            ],
+        };
+        todo.move_entry(2, 0);
+        todo.set_action(1, RebaseAction::Fixup);
+        todo.set_action(2, RebaseAction::Drop);
+        todo.set_message(3, "Fourth, reworded".into());
+        todo.validate().unwrap();
+
+        assert_eq!(
+            todo.to_todo_file(|ix| format!("reword {ix}")),
+            "pick ccc Third\n\
+             fixup aaa First\n\
+             drop bbb Second\n\
+             pick ddd Fourth\n\
+             exec reword 3\n"
+        );
+
+        todo.set_action(0, RebaseAction::Drop);
+        assert!(todo.validate().is_err());
+    }
+
+    #[test]
+    fn test_parse_rebase_stop() {
+        assert_eq!(
+            RebaseStop::parse("2\n", "5\n", Some("0123abcd\n")).unwrap(),
+            RebaseStop {
+                sha: Some("0123abcd".into()),
+                done: 2,
+                total: 5,
+            }
+        );
+        assert_eq!(RebaseStop::parse("1", "1", None).unwrap().sha, None);
+        assert!(RebaseStop::parse("", "1", None).is_err());
+    }
+}
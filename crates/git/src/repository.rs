@@ -1,6 +1,12 @@
 use crate::commit::parse_git_diff_name_status;
+use crate::history::{LogEntry, LogOptions, parse_log};
+use crate::rebase::{
+    REBASE_TODO_ENV_VAR, RebaseAction, RebaseCommand, RebaseStop, RebaseTodo, RebaseTodoEntry,
+};
+use crate::sequencer::{SequencerCommand, SequencerOperation, SequencerState};
 use crate::stash::GitStash;
 use crate::status::{GitStatus, StatusCode};
+use crate::worktree::{Worktree, parse_worktree_list};
 use crate::{Oid, SHORT_SHA_LENGTH};
 use anyhow::{Context as _, Result, anyhow, bail};
 use collections::HashMap;
@@ -365,6 +371,18 @@ pub trait GitRepository: Send + Sync {
     fn show(&self, commit: String) -> BoxFuture<'_, Result<CommitDetails>>;
 
     fn load_commit(&self, commit: String, cx: AsyncApp) -> BoxFuture<'_, Result<CommitDiff>>;
+
+    /// Lists the commits selected by `options`, newest first.
+    fn log(&self, options: LogOptions) -> BoxFuture<'_, Result<Vec<LogEntry>>>;
+
+    /// Loads the contents of `path` as of `revision`, or `None` if it didn't exist at that
+    /// revision.
+    fn load_text_at_revision(
+        &self,
+        revision: String,
+        path: RepoPath,
+    ) -> BoxFuture<'_, Result<Option<String>>>;
+
     fn blame(&self, path: RepoPath, content: Rope) -> BoxFuture<'_, Result<crate::blame::Blame>>;
 
     /// Returns the absolute path to the repository. For worktrees, this will be the path to the
@@ -484,6 +502,81 @@ pub trait GitRepository: Send + Sync {
     ) -> BoxFuture<'_, Result<String>>;
 
     fn default_branch(&self) -> BoxFuture<'_, Result<Option<SharedString>>>;
+
+    /// Lists the commits between `upstream` and HEAD as a rebase plan that picks every commit, oldest first.
+    fn rebase_todo(&self, upstream: String) -> BoxFuture<'_, Result<RebaseTodo>>;
+
+    /// Rewrites the current branch according to the given plan.
+    ///
+    /// Returns where the rebase stopped if a commit could not be applied cleanly.
+    fn rebase_interactive(
+        &self,
+        todo: RebaseTodo,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<Option<RebaseStop>>>;
+
+    /// Continues, skips or aborts a stopped rebase.
+    fn rebase_command(
+        &self,
+        command: RebaseCommand,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<Option<RebaseStop>>>;
+
+    /// Returns where the rebase in progress stopped, if any.
+    fn rebase_stop(&self) -> BoxFuture<'_, Result<Option<RebaseStop>>>;
+
+    /// Applies the changes introduced by `commits` on top of HEAD, in order.
+    ///
+    /// Returns where the cherry-pick stopped if a commit could not be applied cleanly.
+    fn cherry_pick(
+        &self,
+        commits: Vec<String>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<Option<SequencerState>>>;
+
+    /// Creates commits that undo the changes introduced by `commits`, in order.
+    fn revert(
+        &self,
+        commits: Vec<String>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<Option<SequencerState>>>;
+
+    /// Merges `revision` into the current branch.
+    fn merge(
+        &self,
+        revision: String,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<Option<SequencerState>>>;
+
+    /// Continues, skips or aborts a stopped cherry-pick, revert or merge.
+    fn sequencer_command(
+        &self,
+        operation: SequencerOperation,
+        command: SequencerCommand,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<Option<SequencerState>>>;
+
+    /// Returns the cherry-pick, revert or merge in progress, if any.
+    fn sequencer_state(&self) -> BoxFuture<'_, Result<Option<SequencerState>>>;
+
+    /// Lists the main worktree of the repository, followed by its linked worktrees.
+    fn worktrees(&self) -> BoxFuture<'_, Result<Vec<Worktree>>>;
+
+    /// Adds a linked worktree at `path` with `branch` checked out, creating the branch from HEAD if `create_branch` is set.
+    fn create_worktree(
+        &self,
+        path: PathBuf,
+        branch: String,
+        create_branch: bool,
+    ) -> BoxFuture<'_, Result<()>>;
+
+    /// Prevents a linked worktree from being pruned, moved or removed.
+    fn lock_worktree(&self, path: PathBuf, reason: Option<String>) -> BoxFuture<'_, Result<()>>;
+
+    fn unlock_worktree(&self, path: PathBuf) -> BoxFuture<'_, Result<()>>;
+
+    /// Removes a linked worktree, discarding its uncommitted changes if `force` is set.
+    fn remove_worktree(&self, path: PathBuf, force: bool) -> BoxFuture<'_, Result<()>>;
 }
 
 pub enum DiffType {
@@ -531,6 +624,43 @@ impl RealGitRepository {
             .context("failed to read git work directory")
             .map(Path::to_path_buf)
     }
+
+    /// Runs a cherry-pick, revert or merge (or one of their `--continue`, `--skip` and `--abort`
+    /// commands), accepting the default commit messages.
+    fn run_sequencer_command(
+        &self,
+        operation: SequencerOperation,
+        args: Vec<String>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<Option<SequencerState>>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.git_binary_path.clone();
+        let git_dir = self.path();
+        self.executor
+            .spawn(async move {
+                let output = new_smol_command(&git_binary_path)
+                    .current_dir(&working_directory?)
+                    .envs(env.iter())
+                    .env("GIT_EDITOR", "true")
+                    .arg(operation.as_command())
+                    .args(&args)
+                    .output()
+                    .await?;
+
+                let state = load_sequencer_state(&git_dir).await;
+                let aborted = args
+                    .iter()
+                    .any(|arg| arg == SequencerCommand::Abort.as_arg());
+                anyhow::ensure!(
+                    output.status.success() || (!aborted && state.is_some()),
+                    "Failed to {}:\n{}",
+                    operation.as_command(),
+                    String::from_utf8_lossy(&output.stderr)
+                );
+                Ok(state)
+            })
+            .boxed()
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -737,6 +867,44 @@ impl GitRepository for RealGitRepository {
         .boxed()
     }
 
+    fn log(&self, options: LogOptions) -> BoxFuture<'_, Result<Vec<LogEntry>>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.git_binary_path.clone();
+        let executor = self.executor.clone();
+        self.executor
+            .spawn(async move {
+                let output = GitBinary::new(git_binary_path, working_directory?, executor)
+                    .run(options.args())
+                    .await?;
+                Ok(parse_log(&output))
+            })
+            .boxed()
+    }
+
+    fn load_text_at_revision(
+        &self,
+        revision: String,
+        path: RepoPath,
+    ) -> BoxFuture<'_, Result<Option<String>>> {
+        let repo = self.repository.clone();
+        self.executor
+            .spawn(async move {
+                let repo = repo.lock();
+                let tree = repo
+                    .revparse_single(&revision)
+                    .and_then(|object| object.peel_to_tree())
+                    .with_context(|| format!("resolving revision {revision}"))?;
+                let Ok(entry) = tree.get_path(&path) else {
+                    return Ok(None);
+                };
+                let content = repo.find_blob(entry.id())?.content().to_owned();
+                Ok(Some(
+                    String::from_utf8(content).context("file is not valid UTF-8")?,
+                ))
+            })
+            .boxed()
+    }
+
     fn reset(
         &self,
         commit: String,
@@ -1760,6 +1928,346 @@ impl GitRepository for RealGitRepository {
             })
             .boxed()
     }
+
+    fn rebase_todo(&self, upstream: String) -> BoxFuture<'_, Result<RebaseTodo>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.git_binary_path.clone();
+        let executor = self.executor.clone();
+        self.executor
+            .spawn(async move {
+                let working_directory = working_directory?;
+                let git = GitBinary::new(git_binary_path, working_directory, executor);
+                let onto = git.run(&["rev-parse", "--verify", &upstream]).await?;
+                let output = git
+                    .run(&[
+                        "log",
+                        "--reverse",
+                        "--no-merges",
+                        "--format=%H%x00%s",
+                        &format!("{onto}..HEAD"),
+                    ])
+                    .await?;
+                let entries = output
+                    .lines()
+                    .filter_map(|line| {
+                        let (sha, subject) = line.split_once('\0')?;
+                        Some(RebaseTodoEntry {
+                            action: RebaseAction::Pick,
+                            sha: sha.to_string().into(),
+                            subject: subject.to_string().into(),
+                            message: None,
+                        })
+                    })
+                    .collect();
+                Ok(RebaseTodo {
+                    onto: onto.into(),
+                    entries,
+                })
+            })
+            .boxed()
+    }
+
+    fn rebase_interactive(
+        &self,
+        todo: RebaseTodo,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<Option<RebaseStop>>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.git_binary_path.clone();
+        let git_dir = self.path();
+        self.executor
+            .spawn(async move {
+                todo.validate()?;
+                let plan_dir = git_dir.join("zed-rebase");
+                smol::fs::create_dir_all(&plan_dir).await?;
+                for (ix, entry) in todo.entries.iter().enumerate() {
+                    if let Some(message) = &entry.message {
+                        smol::fs::write(plan_dir.join(format!("message-{ix}")), message).await?;
+                    }
+                }
+                let todo_path = plan_dir.join("todo");
+                let todo_file = todo.to_todo_file(|ix| {
+                    let message_path = plan_dir.join(format!("message-{ix}"));
+                    format!(
+                        "git commit --amend --only --allow-empty --quiet --cleanup=strip -F {}",
+                        shell_quote(&message_path.to_string_lossy())
+                    )
+                });
+                smol::fs::write(&todo_path, todo_file).await?;
+
+                // Git runs the sequence editor with the path of its todo file, so having Zed
+                // copy our plan over it replaces the list of commits without an interactive
+                // editor, nor relying on a POSIX `cp` being around.
+                let output = new_smol_command(&git_binary_path)
+                    .current_dir(&working_directory?)
+                    .envs(env.iter())
+                    .env("GIT_SEQUENCE_EDITOR", sequence_editor_command()?)
+                    .env(REBASE_TODO_ENV_VAR, &todo_path)
+                    .env("GIT_EDITOR", "true")
+                    .args(["rebase", "--interactive", todo.onto.as_ref()])
+                    .output()
+                    .await?;
+
+                let stop = load_rebase_stop(&git_dir).await?;
+                if stop.is_none() {
+                    smol::fs::remove_dir_all(&plan_dir).await.log_err();
+                }
+                anyhow::ensure!(
+                    output.status.success() || stop.is_some(),
+                    "Failed to rebase:\n{}",
+                    String::from_utf8_lossy(&output.stderr)
+                );
+                Ok(stop)
+            })
+            .boxed()
+    }
+
+    fn rebase_command(
+        &self,
+        command: RebaseCommand,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<Option<RebaseStop>>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.git_binary_path.clone();
+        let git_dir = self.path();
+        self.executor
+            .spawn(async move {
+                let output = new_smol_command(&git_binary_path)
+                    .current_dir(&working_directory?)
+                    .envs(env.iter())
+                    .env("GIT_EDITOR", "true")
+                    .args(["rebase", command.as_arg()])
+                    .output()
+                    .await?;
+
+                let stop = load_rebase_stop(&git_dir).await?;
+                if stop.is_none() {
+                    smol::fs::remove_dir_all(git_dir.join("zed-rebase"))
+                        .await
+                        .ok();
+                }
+                anyhow::ensure!(
+                    output.status.success() || (command != RebaseCommand::Abort && stop.is_some()),
+                    "Failed to {} rebase:\n{}",
+                    command.as_arg().trim_start_matches('-'),
+                    String::from_utf8_lossy(&output.stderr)
+                );
+                Ok(stop)
+            })
+            .boxed()
+    }
+
+    fn rebase_stop(&self) -> BoxFuture<'_, Result<Option<RebaseStop>>> {
+        let git_dir = self.path();
+        self.executor
+            .spawn(async move { load_rebase_stop(&git_dir).await })
+            .boxed()
+    }
+
+    fn cherry_pick(
+        &self,
+        commits: Vec<String>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<Option<SequencerState>>> {
+        self.run_sequencer_command(SequencerOperation::CherryPick, commits, env)
+    }
+
+    fn revert(
+        &self,
+        commits: Vec<String>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<Option<SequencerState>>> {
+        let args = std::iter::once("--no-edit".to_string())
+            .chain(commits)
+            .collect();
+        self.run_sequencer_command(SequencerOperation::Revert, args, env)
+    }
+
+    fn merge(
+        &self,
+        revision: String,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<Option<SequencerState>>> {
+        self.run_sequencer_command(
+            SequencerOperation::Merge,
+            vec!["--no-edit".to_string(), revision],
+            env,
+        )
+    }
+
+    fn sequencer_command(
+        &self,
+        operation: SequencerOperation,
+        command: SequencerCommand,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<Option<SequencerState>>> {
+        if !operation.supports(command) {
+            return future::ready(Err(anyhow!(
+                "{} does not support {}",
+                operation.as_command(),
+                command.as_arg()
+            )))
+            .boxed();
+        }
+        self.run_sequencer_command(operation, vec![command.as_arg().to_string()], env)
+    }
+
+    fn sequencer_state(&self) -> BoxFuture<'_, Result<Option<SequencerState>>> {
+        let git_dir = self.path();
+        self.executor
+            .spawn(async move { Ok(load_sequencer_state(&git_dir).await) })
+            .boxed()
+    }
+
+    fn worktrees(&self) -> BoxFuture<'_, Result<Vec<Worktree>>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.git_binary_path.clone();
+        let executor = self.executor.clone();
+        self.executor
+            .spawn(async move {
+                let output = GitBinary::new(git_binary_path, working_directory?, executor)
+                    .run(&["worktree", "list", "--porcelain"])
+                    .await?;
+                Ok(parse_worktree_list(&output))
+            })
+            .boxed()
+    }
+
+    fn create_worktree(
+        &self,
+        path: PathBuf,
+        branch: String,
+        create_branch: bool,
+    ) -> BoxFuture<'_, Result<()>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.git_binary_path.clone();
+        let executor = self.executor.clone();
+        self.executor
+            .spawn(async move {
+                let mut args: Vec<OsString> = vec!["worktree".into(), "add".into()];
+                if create_branch {
+                    args.extend(["-b".into(), branch.into(), path.into_os_string()]);
+                } else {
+                    args.extend([path.into_os_string(), branch.into()]);
+                }
+                GitBinary::new(git_binary_path, working_directory?, executor)
+                    .run(args)
+                    .await?;
+                Ok(())
+            })
+            .boxed()
+    }
+
+    fn lock_worktree(&self, path: PathBuf, reason: Option<String>) -> BoxFuture<'_, Result<()>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.git_binary_path.clone();
+        let executor = self.executor.clone();
+        self.executor
+            .spawn(async move {
+                let mut args: Vec<OsString> = vec!["worktree".into(), "lock".into()];
+                if let Some(reason) = reason {
+                    args.extend(["--reason".into(), reason.into()]);
+                }
+                args.push(path.into_os_string());
+                GitBinary::new(git_binary_path, working_directory?, executor)
+                    .run(args)
+                    .await?;
+                Ok(())
+            })
+            .boxed()
+    }
+
+    fn unlock_worktree(&self, path: PathBuf) -> BoxFuture<'_, Result<()>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.git_binary_path.clone();
+        let executor = self.executor.clone();
+        self.executor
+            .spawn(async move {
+                GitBinary::new(git_binary_path, working_directory?, executor)
+                    .run([
+                        OsStr::new("worktree"),
+                        OsStr::new("unlock"),
+                        path.as_os_str(),
+                    ])
+                    .await?;
+                Ok(())
+            })
+            .boxed()
+    }
+
+    fn remove_worktree(&self, path: PathBuf, force: bool) -> BoxFuture<'_, Result<()>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.git_binary_path.clone();
+        let executor = self.executor.clone();
+        self.executor
+            .spawn(async move {
+                let mut args: Vec<OsString> = vec!["worktree".into(), "remove".into()];
+                if force {
+                    args.push("--force".into());
+                }
+                args.push(path.into_os_string());
+                GitBinary::new(git_binary_path, working_directory?, executor)
+                    .run(args)
+                    .await?;
+                Ok(())
+            })
+            .boxed()
+    }
+}
+
+/// Reads the state of a stopped interactive rebase from the `rebase-merge` directory.
+async fn load_rebase_stop(git_dir: &Path) -> Result<Option<RebaseStop>> {
+    let rebase_dir = git_dir.join("rebase-merge");
+    let Ok(msgnum) = smol::fs::read_to_string(rebase_dir.join("msgnum")).await else {
+        return Ok(None);
+    };
+    let end = smol::fs::read_to_string(rebase_dir.join("end")).await?;
+    let stopped_sha = match smol::fs::read_to_string(rebase_dir.join("stopped-sha")).await {
+        Ok(stopped_sha) => Some(stopped_sha),
+        Err(_) => smol::fs::read_to_string(git_dir.join("REBASE_HEAD"))
+            .await
+            .ok(),
+    };
+    RebaseStop::parse(&msgnum, &end, stopped_sha.as_deref()).map(Some)
+}
+
+/// Reads the state of a stopped cherry-pick, revert or merge from the git directory.
+async fn load_sequencer_state(git_dir: &Path) -> Option<SequencerState> {
+    let mut operation = None;
+    let mut head = None;
+    for candidate in [
+        SequencerOperation::Merge,
+        SequencerOperation::CherryPick,
+        SequencerOperation::Revert,
+    ] {
+        if let Ok(contents) = smol::fs::read_to_string(git_dir.join(candidate.head_ref())).await {
+            operation = Some(candidate);
+            head = Some(contents);
+            break;
+        }
+    }
+    let todo = smol::fs::read_to_string(git_dir.join("sequencer").join("todo"))
+        .await
+        .ok();
+    SequencerState::parse(operation, head.as_deref(), todo.as_deref())
+}
+
+/// Returns the command git runs as its sequence editor, which is Zed itself: git runs it through a
+/// shell, appending the path of the todo list, see [`crate::rebase::sequence_editor_main`].
+fn sequence_editor_command() -> Result<String> {
+    #[cfg(not(target_os = "windows"))]
+    let zed_path = util::get_shell_safe_zed_path()?;
+    #[cfg(target_os = "windows")]
+    let zed_path = shell_quote(
+        &std::env::current_exe()
+            .context("finding current executable path for use as the sequence editor")?
+            .to_string_lossy(),
+    );
+    Ok(format!("{zed_path} --git-sequence-editor"))
+}
+
+fn shell_quote(arg: &str) -> String {
+    format!("'{}'", arg.replace('\'', "'\\''"))
 }
 
 fn git_status_args(path_prefixes: &[RepoPath]) -> Vec<OsString> {
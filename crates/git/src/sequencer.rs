@@ -0,0 +1,160 @@
+use gpui::SharedString;
+use std::fmt;
+
+/// An operation that applies commits to the current branch and stops when they conflict.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SequencerOperation {
+    CherryPick,
+    Revert,
+    Merge,
+}
+
+impl SequencerOperation {
+    /// The git subcommand that runs the operation.
+    pub fn as_command(&self) -> &'static str {
+        match self {
+            SequencerOperation::CherryPick => "cherry-pick",
+            SequencerOperation::Revert => "revert",
+            SequencerOperation::Merge => "merge",
+        }
+    }
+
+    /// The ref that git writes to the git directory while the operation is stopped.
+    pub fn head_ref(&self) -> &'static str {
+        match self {
+            SequencerOperation::CherryPick => "CHERRY_PICK_HEAD",
+            SequencerOperation::Revert => "REVERT_HEAD",
+            SequencerOperation::Merge => "MERGE_HEAD",
+        }
+    }
+
+    pub fn supports(&self, command: SequencerCommand) -> bool {
+        !(*self == SequencerOperation::Merge && command == SequencerCommand::Skip)
+    }
+
+    fn from_todo_action(action: &str) -> Option<Self> {
+        match action {
+            "p" | "pick" => Some(SequencerOperation::CherryPick),
+            "revert" => Some(SequencerOperation::Revert),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for SequencerOperation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            SequencerOperation::CherryPick => "Cherry-pick",
+            SequencerOperation::Revert => "Revert",
+            SequencerOperation::Merge => "Merge",
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SequencerCommand {
+    /// Commits the resolved conflicts and applies the remaining commits.
+    Continue,
+    /// Drops the current commit and applies the remaining commits.
+    Skip,
+    /// Restores the branch to its state before the operation started.
+    Abort,
+}
+
+impl SequencerCommand {
+    pub fn as_arg(&self) -> &'static str {
+        match self {
+            SequencerCommand::Continue => "--continue",
+            SequencerCommand::Skip => "--skip",
+            SequencerCommand::Abort => "--abort",
+        }
+    }
+}
+
+/// A cherry-pick, revert or merge that stopped because of conflicts.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SequencerState {
+    pub operation: SequencerOperation,
+    /// The commit that could not be applied.
+    pub head: Option<SharedString>,
+    /// The commits that will be applied once the current one is resolved.
+    pub remaining: Vec<SharedString>,
+}
+
+impl SequencerState {
+    /// Builds the state from the contents of the `*_HEAD` ref of `operation`, if any, and of
+    /// `sequencer/todo`, which lists the stopped commit followed by the remaining ones.
+    ///
+    /// When merging several heads, only the first one is kept.
+    pub fn parse(
+        operation: Option<SequencerOperation>,
+        head: Option<&str>,
+        todo: Option<&str>,
+    ) -> Option<Self> {
+        let head = head
+            .and_then(|head| head.lines().next())
+            .map(str::trim)
+            .filter(|head| !head.is_empty())
+            .map(|head| SharedString::from(head.to_string()));
+        let mut todo_operation = None;
+        let mut remaining = Vec::new();
+        for line in todo.unwrap_or_default().lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let mut parts = line.split_whitespace();
+            let (Some(action), Some(sha)) = (parts.next(), parts.next()) else {
+                continue;
+            };
+            todo_operation = todo_operation.or(SequencerOperation::from_todo_action(action));
+            remaining.push(SharedString::from(sha.to_string()));
+        }
+        if let Some(head) = &head
+            && remaining
+                .first()
+                .is_some_and(|sha| head.starts_with(sha.as_ref()))
+        {
+            remaining.remove(0);
+        }
+        Some(Self {
+            operation: operation.or(todo_operation)?,
+            head,
+            remaining,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_sequencer_state() {
+        let todo = "pick 0123abc First\npick 4567def Second\n# comment\n";
+        assert_eq!(
+            SequencerState::parse(
+                Some(SequencerOperation::CherryPick),
+                Some("0123abcdef0123456789\n"),
+                Some(todo)
+            ),
+            Some(SequencerState {
+                operation: SequencerOperation::CherryPick,
+                head: Some("0123abcdef0123456789".into()),
+                remaining: vec!["4567def".into()],
+            })
+        );
+
+        let state = SequencerState::parse(None, None, Some("revert 89abcde Third\n")).unwrap();
+        assert_eq!(state.operation, SequencerOperation::Revert);
+        assert_eq!(state.head, None);
+        assert_eq!(state.remaining, vec![SharedString::from("89abcde")]);
+
+        let state =
+            SequencerState::parse(Some(SequencerOperation::Merge), Some("fedcba9"), None).unwrap();
+        assert!(state.remaining.is_empty());
+        assert!(!state.operation.supports(SequencerCommand::Skip));
+
+        assert_eq!(SequencerState::parse(None, None, None), None);
+    }
+}
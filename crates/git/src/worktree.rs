@@ -0,0 +1,120 @@
+use gpui::SharedString;
+use std::path::PathBuf;
+
+/// A working tree attached to a repository, as listed by `git worktree list`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Worktree {
+    pub path: PathBuf,
+    /// The commit checked out in the worktree, or `None` for a bare repository.
+    pub head_sha: Option<SharedString>,
+    /// The branch checked out in the worktree, or `None` if its HEAD is detached.
+    pub ref_name: Option<SharedString>,
+    /// Whether this is the main worktree rather than a linked one.
+    pub is_main: bool,
+    pub is_bare: bool,
+    pub is_locked: bool,
+    pub lock_reason: Option<SharedString>,
+    /// Whether the worktree's directory is missing, so that `git worktree prune` would remove it.
+    pub is_prunable: bool,
+}
+
+impl Worktree {
+    pub fn branch_name(&self) -> Option<&str> {
+        self.ref_name
+            .as_ref()
+            .map(|ref_name| ref_name.strip_prefix("refs/heads/").unwrap_or(ref_name))
+    }
+
+    /// A short name for the worktree, taken from its directory name.
+    pub fn name(&self) -> SharedString {
+        self.path
+            .file_name()
+            .map(|name| SharedString::from(name.to_string_lossy().into_owned()))
+            .unwrap_or_else(|| SharedString::from(self.path.to_string_lossy().into_owned()))
+    }
+}
+
+/// Parses the output of `git worktree list --porcelain`.
+///
+/// Entries are separated by blank lines, and the main worktree always comes first.
+pub fn parse_worktree_list(output: &str) -> Vec<Worktree> {
+    let mut worktrees = Vec::new();
+    let mut current: Option<Worktree> = None;
+    for line in output.lines() {
+        let (key, value) = line.split_once(' ').unwrap_or((line, ""));
+        if key == "worktree" {
+            worktrees.extend(current.take());
+            current = Some(Worktree {
+                path: PathBuf::from(value),
+                is_main: worktrees.is_empty(),
+                ..Default::default()
+            });
+            continue;
+        }
+        let Some(worktree) = current.as_mut() else {
+            continue;
+        };
+        match key {
+            "HEAD" => worktree.head_sha = Some(value.to_string().into()),
+            "branch" => worktree.ref_name = Some(value.to_string().into()),
+            "bare" => worktree.is_bare = true,
+            "locked" => {
+                worktree.is_locked = true;
+                worktree.lock_reason =
+                    (!value.is_empty()).then(|| SharedString::from(value.to_string()));
+            }
+            "prunable" => worktree.is_prunable = true,
+            _ => {}
+        }
+    }
+    worktrees.extend(current);
+    worktrees
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_worktree_list() {
+        let output = "\
+worktree /projects/zed
+HEAD 060964da10574cd9bf06463a53bf6e0769c5c45e
+branch refs/heads/main
+
+worktree /projects/zed-review
+HEAD 1733187470aaaabbbbccccddddeeeeffff00001111
+branch refs/heads/review
+locked reviewing a pull request
+
+worktree /tmp/zed-bisect
+HEAD 0000111122223333444455556666777788889999
+detached
+locked
+prunable gitdir file points to non-existent location
+";
+        let worktrees = parse_worktree_list(output);
+        assert_eq!(worktrees.len(), 3);
+
+        assert!(worktrees[0].is_main);
+        assert_eq!(worktrees[0].path, PathBuf::from("/projects/zed"));
+        assert_eq!(worktrees[0].branch_name(), Some("main"));
+        assert!(!worktrees[0].is_locked);
+
+        assert!(!worktrees[1].is_main);
+        assert_eq!(worktrees[1].name(), "zed-review");
+        assert_eq!(worktrees[1].branch_name(), Some("review"));
+        assert!(worktrees[1].is_locked);
+        assert_eq!(
+            worktrees[1].lock_reason.as_deref(),
+            Some("reviewing a pull request")
+        );
+
+        assert_eq!(worktrees[2].ref_name, None);
+        assert!(worktrees[2].is_locked);
+        assert_eq!(worktrees[2].lock_reason, None);
+        assert!(worktrees[2].is_prunable);
+
+        assert!(parse_worktree_list("").is_empty());
+    }
+}
@@ -3,20 +3,23 @@ use fuzzy::StringMatchCandidate;
 
 use collections::HashSet;
 use git::repository::Branch;
+use git::worktree::Worktree;
 use gpui::{
     App, Context, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, InteractiveElement,
-    IntoElement, Modifiers, ModifiersChangedEvent, ParentElement, Render, SharedString, Styled,
-    Subscription, Task, Window, rems,
+    IntoElement, Modifiers, ModifiersChangedEvent, ParentElement, PromptLevel, Render,
+    SharedString, Styled, Subscription, Task, Window, rems,
 };
 use picker::{Picker, PickerDelegate, PickerEditorPosition};
 use project::git_store::Repository;
 use project::project_settings::ProjectSettings;
 use settings::Settings;
+use std::path::PathBuf;
 use std::sync::Arc;
 use time::OffsetDateTime;
 use time_format::format_local_timestamp;
 use ui::{HighlightedLabel, ListItem, ListItemSpacing, Tooltip, prelude::*};
 use util::ResultExt;
+use util::paths::PathExt;
 use workspace::notifications::DetachAndPromptErr;
 use workspace::{ModalView, Workspace};
 
@@ -95,6 +98,13 @@ impl BranchList {
         let default_branch_request = repository
             .clone()
             .map(|repository| repository.update(cx, |repository, _| repository.default_branch()));
+        let is_local_repository = repository
+            .as_ref()
+            .is_some_and(|repository| repository.read(cx).is_local(cx));
+        let worktrees_request = repository
+            .clone()
+            .filter(|_| is_local_repository)
+            .map(|repository| repository.update(cx, |repository, _| repository.worktrees()));
 
         cx.spawn_in(window, async move |this, cx| {
             let mut all_branches = all_branches_request
@@ -107,6 +117,10 @@ impl BranchList {
                 .ok()
                 .flatten()
                 .flatten();
+            let worktrees = match worktrees_request {
+                Some(request) => request.await.ok().and_then(Result::ok).unwrap_or_default(),
+                None => Vec::new(),
+            };
 
             let all_branches = cx
                 .background_spawn(async move {
@@ -140,6 +154,7 @@ impl BranchList {
             this.update_in(cx, |this, window, cx| {
                 this.picker.update(cx, |picker, cx| {
                     picker.delegate.default_branch = default_branch;
+                    picker.delegate.worktrees = worktrees;
                     picker.delegate.all_branches = Some(all_branches);
                     picker.refresh(window, cx);
                 })
@@ -149,7 +164,8 @@ impl BranchList {
         })
         .detach_and_log_err(cx);
 
-        let delegate = BranchListDelegate::new(repository, style);
+        let mut delegate = BranchListDelegate::new(repository, style);
+        delegate.is_local_repository = is_local_repository;
         let picker = cx.new(|cx| Picker::uniform_list(delegate, window, cx));
 
         let _subscription = cx.subscribe(&picker, |_, _, _, cx| {
@@ -210,6 +226,8 @@ pub struct BranchListDelegate {
     matches: Vec<BranchEntry>,
     all_branches: Option<Vec<Branch>>,
     default_branch: Option<SharedString>,
+    worktrees: Vec<Worktree>,
+    is_local_repository: bool,
     repo: Option<Entity<Repository>>,
     style: BranchListStyle,
     selected_index: usize,
@@ -225,6 +243,8 @@ impl BranchListDelegate {
             style,
             all_branches: None,
             default_branch: None,
+            worktrees: Vec::new(),
+            is_local_repository: false,
             selected_index: 0,
             last_query: Default::default(),
             modifiers: Default::default(),
@@ -264,6 +284,94 @@ impl BranchListDelegate {
         });
         cx.emit(DismissEvent);
     }
+
+    /// Returns the linked worktree other than the current one that has `branch` checked out.
+    fn worktree_for_branch(&self, branch: &Branch, cx: &App) -> Option<&Worktree> {
+        let current_path = self
+            .repo
+            .as_ref()
+            .map(|repo| repo.read(cx).work_directory_abs_path.clone());
+        self.worktrees.iter().find(|worktree| {
+            worktree.ref_name.as_ref() == Some(&branch.ref_name)
+                && current_path
+                    .as_ref()
+                    .is_none_or(|current_path| worktree.path != **current_path)
+        })
+    }
+
+    fn open_worktree(&self, path: PathBuf, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        window.dispatch_action(Box::new(zed_actions::OpenWorktree { path: Some(path) }), cx);
+        cx.emit(DismissEvent);
+    }
+
+    /// Checks out `branch` in a new worktree next to the current one, then opens it.
+    fn create_worktree(
+        &self,
+        branch: SharedString,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) {
+        let Some(repo) = self.repo.clone() else {
+            return;
+        };
+        let work_directory = repo.read(cx).work_directory_abs_path.clone();
+        let Some(parent) = work_directory.parent() else {
+            return;
+        };
+        let directory_name = work_directory
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let path = parent.join(format!("{directory_name}-{}", branch.replace('/', "-")));
+        cx.spawn_in(window, async move |picker, cx| {
+            repo.update(cx, |repo, _| {
+                repo.create_worktree(path.clone(), branch.to_string(), false)
+            })?
+            .await??;
+            picker.update_in(cx, |picker, window, cx| {
+                picker.delegate.open_worktree(path, window, cx)
+            })
+        })
+        .detach_and_prompt_err("Failed to create worktree", window, cx, |e, _, _| {
+            Some(e.to_string())
+        });
+    }
+
+    /// Merges `branch` into the current branch, leaving any conflicts to be resolved from the
+    /// git panel.
+    fn merge(&self, branch: SharedString, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(repo) = self.repo.clone() else {
+            return;
+        };
+        let prompt = window.prompt(
+            PromptLevel::Warning,
+            &format!("Are you sure you want to merge {branch} into the current branch?"),
+            None,
+            &["Merge", "Cancel"],
+            cx,
+        );
+        cx.spawn_in(window, async move |_, cx| {
+            if prompt.await? != 0 {
+                return anyhow::Ok(());
+            }
+            let state = repo
+                .update(cx, |repo, _| repo.merge(branch.to_string()))?
+                .await??;
+            if state.is_some() {
+                let _ = cx.prompt(
+                    PromptLevel::Info,
+                    &format!("Merging {branch} stopped on conflicts"),
+                    Some("Resolve the conflicts and stage them, then continue from the git panel."),
+                    &["Ok"],
+                );
+            }
+            anyhow::Ok(())
+        })
+        .detach_and_prompt_err("Failed to merge branch", window, cx, |e, _, _| {
+            Some(e.to_string())
+        });
+        cx.emit(DismissEvent);
+    }
 }
 
 impl PickerDelegate for BranchListDelegate {
@@ -396,6 +504,11 @@ impl PickerDelegate for BranchListDelegate {
             return;
         }
 
+        if let Some(worktree) = self.worktree_for_branch(&entry.branch, cx) {
+            self.open_worktree(worktree.path.clone(), window, cx);
+            return;
+        }
+
         let current_branch = self.repo.as_ref().map(|repo| {
             repo.read_with(cx, |repo, _| {
                 repo.branch.as_ref().map(|branch| branch.ref_name.clone())
@@ -474,7 +587,23 @@ impl PickerDelegate for BranchListDelegate {
             })
             .unwrap_or_else(|| (None, None, None));
 
-        let icon = if let Some(default_branch) = self.default_branch.clone()
+        let worktree = if entry.is_new {
+            None
+        } else {
+            self.worktree_for_branch(&entry.branch, cx)
+        };
+
+        let icon = if let Some(worktree) = worktree {
+            let worktree_path = worktree.path.compact().to_string_lossy().into_owned();
+            Some(
+                IconButton::new("open-worktree", IconName::FolderOpen)
+                    .on_click(cx.listener(move |this, _, window, cx| {
+                        this.delegate.set_selected_index(ix, window, cx);
+                        this.delegate.confirm(false, window, cx);
+                    }))
+                    .tooltip(Tooltip::text(format!("Open worktree at {worktree_path}"))),
+            )
+        } else if let Some(default_branch) = self.default_branch.clone()
             && entry.is_new
         {
             Some(
@@ -496,6 +625,43 @@ impl PickerDelegate for BranchListDelegate {
             None
         };
 
+        let hover_buttons = (self.is_local_repository && !entry.is_new && !entry.branch.is_head)
+            .then(|| {
+                let branch_name = SharedString::from(entry.branch.name().to_string());
+                h_flex()
+                    .gap_1()
+                    .child(
+                        IconButton::new("merge-branch", IconName::GitBranchAlt)
+                            .on_click(cx.listener({
+                                let branch_name = branch_name.clone();
+                                move |this, _, window, cx| {
+                                    this.delegate.merge(branch_name.clone(), window, cx);
+                                }
+                            }))
+                            .tooltip(Tooltip::text(format!(
+                                "Merge {branch_name} into the current branch"
+                            ))),
+                    )
+                    .when(worktree.is_none() && !entry.branch.is_remote(), |this| {
+                        this.child(
+                            IconButton::new("create-worktree", IconName::FileTree)
+                                .on_click(cx.listener({
+                                    let branch_name = branch_name.clone();
+                                    move |this, _, window, cx| {
+                                        this.delegate.create_worktree(
+                                            branch_name.clone(),
+                                            window,
+                                            cx,
+                                        );
+                                    }
+                                }))
+                                .tooltip(Tooltip::text(format!(
+                                    "Check out {branch_name} in a new worktree"
+                                ))),
+                        )
+                    })
+            });
+
         let branch_name = if entry.is_new {
             h_flex()
                 .gap_1()
@@ -560,6 +726,11 @@ impl PickerDelegate for BranchListDelegate {
                                     } else {
                                         "based off the current branch".to_string()
                                     }
+                                } else if let Some(worktree) = worktree {
+                                    format!(
+                                        "checked out in {}",
+                                        worktree.path.compact().to_string_lossy()
+                                    )
                                 } else {
                                     let show_author_name = ProjectSettings::get_global(cx)
                                         .git
@@ -581,7 +752,8 @@ impl PickerDelegate for BranchListDelegate {
                             }))
                         }),
                 )
-                .end_slot::<IconButton>(icon),
+                .end_slot::<IconButton>(icon)
+                .end_hover_slot::<Div>(hover_buttons),
         )
     }
 
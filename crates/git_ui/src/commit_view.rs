@@ -1,10 +1,14 @@
 use anyhow::{Context as _, Result};
 use buffer_diff::{BufferDiff, BufferDiffSnapshot};
 use editor::{Editor, EditorEvent, MultiBuffer, SelectionEffects, multibuffer_context_lines};
-use git::repository::{CommitDetails, CommitDiff, CommitSummary, RepoPath};
+use git::{
+    CherryPickCommit, RevertCommit,
+    repository::{CommitDetails, CommitDiff, CommitSummary, RepoPath},
+    sequencer::SequencerOperation,
+};
 use gpui::{
     AnyElement, AnyView, App, AppContext as _, AsyncApp, Context, Entity, EventEmitter,
-    FocusHandle, Focusable, IntoElement, Render, WeakEntity, Window,
+    FocusHandle, Focusable, IntoElement, PromptLevel, Render, WeakEntity, Window,
 };
 use language::{
     Anchor, Buffer, Capability, DiskState, File, LanguageRegistry, LineEnding, OffsetRangeExt as _,
@@ -19,11 +23,12 @@ use std::{
     path::{Path, PathBuf},
     sync::Arc,
 };
-use ui::{Color, Icon, IconName, Label, LabelCommon as _, SharedString};
+use ui::{Tooltip, prelude::*};
 use util::{ResultExt, truncate_and_trailoff};
 use workspace::{
     Item, ItemHandle as _, ItemNavHistory, ToolbarItemLocation, Workspace,
     item::{BreadcrumbText, ItemEvent, TabContentParams},
+    notifications::DetachAndPromptErr as _,
     searchable::SearchableItemHandle,
 };
 
@@ -31,12 +36,15 @@ pub struct CommitView {
     commit: CommitDetails,
     editor: Entity<Editor>,
     multibuffer: Entity<MultiBuffer>,
+    repository: Entity<Repository>,
+    /// Whether the commit can be cherry-picked or reverted onto the current branch.
+    can_apply: bool,
 }
 
-struct GitBlob {
-    path: RepoPath,
-    worktree_id: WorktreeId,
-    is_deleted: bool,
+pub(crate) struct GitBlob {
+    pub(crate) path: RepoPath,
+    pub(crate) worktree_id: WorktreeId,
+    pub(crate) is_deleted: bool,
 }
 
 struct CommitMetadataFile {
@@ -206,12 +214,64 @@ impl CommitView {
         })
         .detach();
 
+        // Stashes aren't commits to apply to a branch.
+        let can_apply = {
+            let repository = repository.read(cx);
+            repository.is_local(cx)
+                && !repository
+                    .cached_stash()
+                    .entries
+                    .iter()
+                    .any(|entry| entry.oid.to_string() == commit.sha.as_ref())
+        };
+
         Self {
             commit,
             editor,
             multibuffer,
+            repository,
+            can_apply,
         }
     }
+
+    fn cherry_pick(&mut self, _: &CherryPickCommit, window: &mut Window, cx: &mut Context<Self>) {
+        self.apply(SequencerOperation::CherryPick, window, cx);
+    }
+
+    fn revert(&mut self, _: &RevertCommit, window: &mut Window, cx: &mut Context<Self>) {
+        self.apply(SequencerOperation::Revert, window, cx);
+    }
+
+    /// Cherry-picks or reverts the commit onto the current branch.
+    fn apply(
+        &mut self,
+        operation: SequencerOperation,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let commits = vec![self.commit.sha.to_string()];
+        let task = self.repository.update(cx, |repository, _| match operation {
+            SequencerOperation::Revert => repository.revert(commits),
+            _ => repository.cherry_pick(commits),
+        });
+        cx.spawn_in(window, async move |_, cx| {
+            if task.await??.is_some() {
+                let _ = cx.prompt(
+                    PromptLevel::Info,
+                    &format!("{operation} stopped on conflicts"),
+                    Some("Resolve the conflicts and stage them, then continue from the git panel."),
+                    &["Ok"],
+                );
+            }
+            anyhow::Ok(())
+        })
+        .detach_and_prompt_err(
+            &format!("Failed to {}", operation.as_command()),
+            window,
+            cx,
+            |e, _, _| Some(e.to_string()),
+        );
+    }
 }
 
 impl language::File for GitBlob {
@@ -286,7 +346,7 @@ impl language::File for CommitMetadataFile {
     }
 }
 
-async fn build_buffer(
+pub(crate) async fn build_buffer(
     mut text: String,
     blob: Arc<dyn File>,
     language_registry: &Arc<language::LanguageRegistry>,
@@ -516,7 +576,44 @@ impl Item for CommitView {
 }
 
 impl Render for CommitView {
-    fn render(&mut self, _: &mut Window, _: &mut Context<Self>) -> impl IntoElement {
-        self.editor.clone()
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .size_full()
+            .when(self.can_apply, |this| {
+                this.on_action(cx.listener(Self::cherry_pick))
+                    .on_action(cx.listener(Self::revert))
+                    .child(
+                        h_flex()
+                            .px_2()
+                            .py_1()
+                            .gap_1()
+                            .justify_end()
+                            .border_b_1()
+                            .border_color(cx.theme().colors().border_variant)
+                            .child(
+                                Button::new("cherry-pick", "Cherry-pick")
+                                    .label_size(LabelSize::Small)
+                                    .tooltip(Tooltip::for_action_title(
+                                        "Apply this commit on top of the current branch",
+                                        &CherryPickCommit,
+                                    ))
+                                    .on_click(cx.listener(|this, _, window, cx| {
+                                        this.cherry_pick(&CherryPickCommit, window, cx)
+                                    })),
+                            )
+                            .child(
+                                Button::new("revert", "Revert")
+                                    .label_size(LabelSize::Small)
+                                    .tooltip(Tooltip::for_action_title(
+                                        "Create a commit that undoes this commit",
+                                        &RevertCommit,
+                                    ))
+                                    .on_click(cx.listener(|this, _, window, cx| {
+                                        this.revert(&RevertCommit, window, cx)
+                                    })),
+                            ),
+                    )
+            })
+            .child(div().flex_1().min_h_0().child(self.editor.clone()))
     }
 }
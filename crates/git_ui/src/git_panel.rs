@@ -20,13 +20,14 @@ use git::repository::{
     PushOptions, Remote, RemoteCommandOutput, ResetMode, Upstream, UpstreamTracking,
     UpstreamTrackingStatus, get_git_committer,
 };
+use git::sequencer::{SequencerCommand, SequencerState};
 use git::stash::GitStash;
 use git::status::StageStatus;
-use git::{Amend, Signoff, ToggleStaged, repository::RepoPath, status::FileStatus};
 use git::{
-    ExpandCommitEditor, RestoreTrackedFiles, StageAll, StashAll, StashApply, StashPop,
-    TrashUntrackedFiles, UnstageAll,
+    AbortOperation, ContinueOperation, ExpandCommitEditor, RestoreTrackedFiles,
+    SkipOperationCommit, StageAll, StashAll, StashApply, StashPop, TrashUntrackedFiles, UnstageAll,
 };
+use git::{Amend, Signoff, ToggleStaged, repository::RepoPath, status::FileStatus};
 use gpui::{
     Action, AsyncApp, AsyncWindowContext, ClickEvent, Corner, DismissEvent, Entity, EventEmitter,
     FocusHandle, Focusable, KeyContext, ListHorizontalSizingBehavior, ListSizingBehavior,
@@ -118,6 +119,7 @@ struct GitMenuState {
     has_new_changes: bool,
     sort_by_path: bool,
     has_stash_items: bool,
+    has_history: bool,
 }
 
 fn git_panel_context_menu(
@@ -149,6 +151,11 @@ fn git_panel_context_menu(
             .action("View Stash", zed_actions::git::ViewStash.boxed_clone())
             .separator()
             .action("Open Diff", project_diff::Diff.boxed_clone())
+            .action_disabled_when(
+                !state.has_history,
+                "View History",
+                git::ViewLog.boxed_clone(),
+            )
             .separator()
             .action_disabled_when(
                 !state.has_tracked_changes,
@@ -324,6 +331,9 @@ pub struct GitPanel {
     local_committer_task: Option<Task<()>>,
     bulk_staging: Option<BulkStaging>,
     stash_entries: GitStash,
+    /// The cherry-pick, revert or merge stopped on conflicts in the active repository.
+    sequencer_state: Option<SequencerState>,
+    load_sequencer_state_task: Task<()>,
     _settings_subscription: Subscription,
 }
 
@@ -484,6 +494,8 @@ impl GitPanel {
                 entry_count: 0,
                 bulk_staging: None,
                 stash_entries: Default::default(),
+                sequencer_state: None,
+                load_sequencer_state_task: Task::ready(()),
                 _settings_subscription,
             };
 
@@ -1334,6 +1346,65 @@ impl GitPanel {
         .detach();
     }
 
+    pub fn continue_operation(
+        &mut self,
+        _: &ContinueOperation,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.run_sequencer_command(SequencerCommand::Continue, cx);
+    }
+
+    pub fn skip_operation_commit(
+        &mut self,
+        _: &SkipOperationCommit,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.run_sequencer_command(SequencerCommand::Skip, cx);
+    }
+
+    pub fn abort_operation(
+        &mut self,
+        _: &AbortOperation,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.run_sequencer_command(SequencerCommand::Abort, cx);
+    }
+
+    fn run_sequencer_command(&mut self, command: SequencerCommand, cx: &mut Context<Self>) {
+        let Some(active_repository) = self.active_repository.clone() else {
+            return;
+        };
+        let Some(operation) = self.sequencer_state.as_ref().map(|state| state.operation) else {
+            return;
+        };
+        if !operation.supports(command) {
+            return;
+        }
+
+        cx.spawn(async move |this, cx| {
+            let result = active_repository
+                .update(cx, |repo, _| repo.sequencer_command(operation, command))?
+                .await
+                .map_err(anyhow::Error::from)
+                .and_then(|result| result);
+            this.update(cx, |this, cx| {
+                match result {
+                    Ok(sequencer_state) => this.sequencer_state = sequencer_state,
+                    Err(e) => this.show_error_toast(
+                        format!("{} {}", operation.as_command(), command.as_arg()),
+                        e,
+                        cx,
+                    ),
+                }
+                cx.notify();
+            })
+        })
+        .detach();
+    }
+
     pub fn commit_message_buffer(&self, cx: &App) -> Entity<Buffer> {
         self.commit_editor
             .read(cx)
@@ -2504,6 +2575,7 @@ impl GitPanel {
                             git_panel.clear_pending();
                         }
                         git_panel.update_visible_entries(window, cx);
+                        git_panel.load_sequencer_state(cx);
                     })
                     .ok();
             }
@@ -2555,6 +2627,34 @@ impl GitPanel {
         self.pending.retain(|v| !v.finished)
     }
 
+    /// Loads the commits left to apply by the cherry-pick, revert or merge in progress.
+    fn load_sequencer_state(&mut self, cx: &mut Context<Self>) {
+        let Some(repository) = self.active_repository.clone() else {
+            self.sequencer_state = None;
+            return;
+        };
+        let in_progress = repository.read(cx).merge.sequencer_operation().is_some();
+        if !in_progress || !repository.read(cx).is_local(cx) {
+            self.sequencer_state = None;
+            return;
+        }
+
+        let sequencer_state = repository.update(cx, |repository, _| repository.sequencer_state());
+        self.load_sequencer_state_task = cx.spawn(async move |this, cx| {
+            let sequencer_state = sequencer_state
+                .await
+                .map_err(anyhow::Error::from)
+                .and_then(|result| result)
+                .log_err()
+                .flatten();
+            this.update(cx, |this, cx| {
+                this.sequencer_state = sequencer_state;
+                cx.notify();
+            })
+            .ok();
+        });
+    }
+
     fn update_visible_entries(&mut self, window: &mut Window, cx: &mut Context<Self>) {
         let bulk_staging = self.bulk_staging.take();
         let last_staged_path_prev_index = bulk_staging
@@ -2960,6 +3060,7 @@ impl GitPanel {
         let has_unstaged_changes = self.has_unstaged_changes();
         let has_new_changes = self.new_count > 0;
         let has_stash_items = self.stash_entries.entries.len() > 0;
+        let active_repository = self.active_repository.clone();
 
         PopoverMenu::new(id.into())
             .trigger(
@@ -2977,6 +3078,9 @@ impl GitPanel {
                         has_new_changes,
                         sort_by_path: GitPanelSettings::get_global(cx).sort_by_path,
                         has_stash_items,
+                        has_history: active_repository
+                            .as_ref()
+                            .is_some_and(|repository| repository.read(cx).is_local(cx)),
                     },
                     window,
                     cx,
@@ -3486,6 +3590,105 @@ impl GitPanel {
             ))
     }
 
+    /// Shows the cherry-pick, revert or merge that stopped on conflicts, and how to carry on.
+    fn render_sequencer_banner(&self, cx: &mut Context<Self>) -> Option<impl IntoElement> {
+        let sequencer_state = self.sequencer_state.as_ref()?;
+        let operation = sequencer_state.operation;
+        let head = sequencer_state
+            .head
+            .as_ref()
+            .map(|sha| sha[..sha.len().min(git::SHORT_SHA_LENGTH)].to_string());
+        let unresolved_count = self.conflicted_count - self.conflicted_staged_count;
+        let mut status = match unresolved_count {
+            0 => "All conflicts resolved".to_string(),
+            1 => "1 unresolved conflict".to_string(),
+            _ => format!("{unresolved_count} unresolved conflicts"),
+        };
+        match sequencer_state.remaining.len() {
+            0 => {}
+            1 => status.push_str(", 1 commit left"),
+            remaining => status.push_str(&format!(", {remaining} commits left")),
+        }
+
+        Some(
+            v_flex()
+                .py_1p5()
+                .px_2()
+                .gap_1()
+                .border_b_1()
+                .border_color(cx.theme().colors().border.opacity(0.8))
+                .child(
+                    h_flex()
+                        .gap_1p5()
+                        .child(
+                            Icon::new(IconName::Warning)
+                                .size(IconSize::Small)
+                                .color(Color::Warning),
+                        )
+                        .child(
+                            Label::new(format!("{operation} in progress")).size(LabelSize::Small),
+                        )
+                        .when_some(head, |this, head| {
+                            this.child(Label::new(head).size(LabelSize::Small).color(Color::Muted))
+                        }),
+                )
+                .child(
+                    h_flex()
+                        .gap_1()
+                        .justify_between()
+                        .child(
+                            Label::new(status)
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        )
+                        .child(
+                            h_flex()
+                                .gap_1()
+                                .child(
+                                    panel_button("Abort")
+                                        .tooltip(Tooltip::for_action_title_in(
+                                            format!("git {} --abort", operation.as_command()),
+                                            &AbortOperation,
+                                            &self.focus_handle,
+                                        ))
+                                        .on_click(cx.listener(|this, _, window, cx| {
+                                            this.abort_operation(&AbortOperation, window, cx)
+                                        })),
+                                )
+                                .when(operation.supports(SequencerCommand::Skip), |this| {
+                                    this.child(
+                                        panel_button("Skip")
+                                            .tooltip(Tooltip::for_action_title_in(
+                                                format!("git {} --skip", operation.as_command()),
+                                                &SkipOperationCommit,
+                                                &self.focus_handle,
+                                            ))
+                                            .on_click(cx.listener(|this, _, window, cx| {
+                                                this.skip_operation_commit(
+                                                    &SkipOperationCommit,
+                                                    window,
+                                                    cx,
+                                                )
+                                            })),
+                                    )
+                                })
+                                .child(
+                                    panel_filled_button("Continue")
+                                        .tooltip(Tooltip::for_action_title_in(
+                                            format!("git {} --continue", operation.as_command()),
+                                            &ContinueOperation,
+                                            &self.focus_handle,
+                                        ))
+                                        .disabled(unresolved_count > 0)
+                                        .on_click(cx.listener(|this, _, window, cx| {
+                                            this.continue_operation(&ContinueOperation, window, cx)
+                                        })),
+                                ),
+                        ),
+                ),
+        )
+    }
+
     fn render_pending_amend(&self, cx: &mut Context<Self>) -> impl IntoElement {
         h_flex()
             .py_1p5()
@@ -3847,6 +4050,10 @@ impl GitPanel {
                 has_new_changes: self.new_count > 0,
                 sort_by_path: GitPanelSettings::get_global(cx).sort_by_path,
                 has_stash_items: self.stash_entries.entries.len() > 0,
+                has_history: self
+                    .active_repository
+                    .as_ref()
+                    .is_some_and(|repository| repository.read(cx).is_local(cx)),
             },
             window,
             cx,
@@ -4246,6 +4453,9 @@ impl Render for GitPanel {
                     .on_action(cx.listener(Self::generate_commit_message_action))
                     .on_action(cx.listener(Self::stash_all))
                     .on_action(cx.listener(Self::stash_pop))
+                    .on_action(cx.listener(Self::continue_operation))
+                    .on_action(cx.listener(Self::skip_operation_commit))
+                    .on_action(cx.listener(Self::abort_operation))
             })
             .on_action(cx.listener(Self::select_first))
             .on_action(cx.listener(Self::select_next))
@@ -4268,6 +4478,7 @@ impl Render for GitPanel {
                 v_flex()
                     .size_full()
                     .children(self.render_panel_header(window, cx))
+                    .children(self.render_sequencer_banner(cx))
                     .map(|this| {
                         if has_entries {
                             this.child(self.render_entries(has_write_access, window, cx))
@@ -4888,6 +5099,7 @@ impl Component for PanelRepoFooter {
 
 #[cfg(test)]
 mod tests {
+    use git::sequencer::SequencerOperation;
     use git::status::{StatusCode, UnmergedStatus, UnmergedStatusCode};
     use gpui::{TestAppContext, VisualTestContext};
     use project::{FakeFs, WorktreeSettings};
@@ -5030,6 +5242,64 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    async fn test_sequencer_banner_state(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.background_executor.clone());
+        fs.insert_tree(
+            path!("/root"),
+            json!({
+                "project": {
+                    ".git": {},
+                    "a.txt": "a",
+                },
+            }),
+        )
+        .await;
+        fs.with_git_state(Path::new(path!("/root/project/.git")), true, |state| {
+            state.commit_history = vec![("base".into(), "Commit base".into())];
+            state.simulated_sequencer_conflicts.insert("two".into());
+        })
+        .unwrap();
+
+        let project = Project::test(fs.clone(), [path!("/root/project").as_ref()], cx).await;
+        let workspace =
+            cx.add_window(|window, cx| Workspace::test_new(project.clone(), window, cx));
+        let cx = &mut VisualTestContext::from_window(*workspace, cx);
+        cx.executor().run_until_parked();
+        let panel = workspace.update(cx, GitPanel::new).unwrap();
+        cx.executor().advance_clock(2 * UPDATE_DEBOUNCE);
+        cx.executor().run_until_parked();
+        assert!(panel.read_with(cx, |panel, _| panel.sequencer_state.is_none()));
+
+        let repository = panel.read_with(cx, |panel, _| panel.active_repository.clone().unwrap());
+        let commits = ["one", "two", "three"].map(String::from).to_vec();
+        repository
+            .update(cx, |repository, _| repository.cherry_pick(commits))
+            .await
+            .unwrap()
+            .unwrap();
+        cx.executor().run_until_parked();
+        cx.executor().advance_clock(2 * UPDATE_DEBOUNCE);
+        cx.executor().run_until_parked();
+        let sequencer_state = panel.read_with(cx, |panel, _| panel.sequencer_state.clone());
+        let sequencer_state = sequencer_state.expect("the cherry-pick should be in progress");
+        assert_eq!(sequencer_state.operation, SequencerOperation::CherryPick);
+        assert_eq!(sequencer_state.head.as_deref(), Some("two"));
+        assert_eq!(sequencer_state.remaining, ["three"]);
+
+        panel.update_in(cx, |panel, window, cx| {
+            panel.skip_operation_commit(&SkipOperationCommit, window, cx)
+        });
+        cx.executor().run_until_parked();
+        cx.executor().advance_clock(2 * UPDATE_DEBOUNCE);
+        cx.executor().run_until_parked();
+        assert!(
+            panel.read_with(cx, |panel, _| panel.sequencer_state.is_none()),
+            "The banner should be hidden once the remaining commits are applied"
+        );
+    }
+
     #[gpui::test]
     async fn test_bulk_staging(cx: &mut TestAppContext) {
         use GitListEntry::*;
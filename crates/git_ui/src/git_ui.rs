@@ -39,11 +39,14 @@ mod conflict_view;
 pub mod file_diff_view;
 pub mod git_panel;
 mod git_panel_settings;
+pub mod log_view;
 pub mod onboarding;
 pub mod picker_prompt;
 pub mod project_diff;
+pub mod rebase_modal;
 pub(crate) mod remote_output;
 pub mod repository_selector;
+pub mod revision_view;
 pub mod stash_picker;
 pub mod text_diff_view;
 
@@ -74,6 +77,10 @@ pub fn init(cx: &mut App) {
         stash_picker::register(workspace);
 
         let project = workspace.project().read(cx);
+        if project.is_local() {
+            rebase_modal::register(workspace);
+            log_view::register(workspace);
+        }
         if project.is_read_only(cx) {
             return;
         }
@@ -151,6 +158,30 @@ pub fn init(cx: &mut App) {
                 panel.stash_apply(action, window, cx);
             });
         });
+        workspace.register_action(|workspace, action: &git::ContinueOperation, window, cx| {
+            let Some(panel) = workspace.panel::<git_panel::GitPanel>(cx) else {
+                return;
+            };
+            panel.update(cx, |panel, cx| {
+                panel.continue_operation(action, window, cx);
+            });
+        });
+        workspace.register_action(|workspace, action: &git::SkipOperationCommit, window, cx| {
+            let Some(panel) = workspace.panel::<git_panel::GitPanel>(cx) else {
+                return;
+            };
+            panel.update(cx, |panel, cx| {
+                panel.skip_operation_commit(action, window, cx);
+            });
+        });
+        workspace.register_action(|workspace, action: &git::AbortOperation, window, cx| {
+            let Some(panel) = workspace.panel::<git_panel::GitPanel>(cx) else {
+                return;
+            };
+            panel.update(cx, |panel, cx| {
+                panel.abort_operation(action, window, cx);
+            });
+        });
         workspace.register_action(|workspace, action: &git::StageAll, window, cx| {
             let Some(panel) = workspace.panel::<git_panel::GitPanel>(cx) else {
                 return;
@@ -0,0 +1,697 @@
+//! LogView lists the commits of a repository as a commit graph, or the commits that changed a
+//! file or a range of its lines.
+
+use editor::Editor;
+use git::{
+    FileHistory, SelectionHistory, ViewLog,
+    history::{CommitGraph, GraphRow, LOG_PAGE_SIZE, LogEntry, LogOptions},
+    repository::CommitSummary,
+};
+use gpui::{
+    AnyElement, App, Context, Entity, EventEmitter, FocusHandle, Focusable, IntoElement, Render,
+    ScrollStrategy, SharedString, Task, UniformListScrollHandle, WeakEntity, Window, px,
+    uniform_list,
+};
+use language::Point;
+use project::git_store::Repository;
+use std::ops::Range;
+use time::OffsetDateTime;
+use time_format::format_local_timestamp;
+use ui::{Tooltip, WithScrollbar, prelude::*};
+use workspace::{
+    Item, Workspace,
+    item::{ItemEvent, TabContentParams},
+    notifications::DetachAndPromptErr as _,
+};
+
+use crate::{commit_view::CommitView, revision_view::RevisionView};
+
+const LANE_WIDTH: f32 = 14.;
+const DOT_SIZE: f32 = 8.;
+const LINE_WIDTH: f32 = 2.;
+/// How close to the end of the list a page has to be scrolled before the next one is loaded.
+const LOAD_MORE_THRESHOLD: usize = 50;
+
+pub fn register(workspace: &mut Workspace) {
+    workspace.register_action(|workspace, _: &ViewLog, window, cx| {
+        let Some(repository) = workspace.project().read(cx).active_repository(cx) else {
+            return;
+        };
+        LogView::open(repository, LogOptions::default(), workspace, window, cx);
+    });
+    workspace.register_action(|workspace, _: &FileHistory, window, cx| {
+        open_file_history(workspace, false, window, cx);
+    });
+    workspace.register_action(|workspace, _: &SelectionHistory, window, cx| {
+        open_file_history(workspace, true, window, cx);
+    });
+}
+
+/// Opens the history of the file in the active editor, restricted to its selected lines if
+/// `selected_lines` is set.
+fn open_file_history(
+    workspace: &mut Workspace,
+    selected_lines: bool,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let Some(editor) = workspace.active_item_as::<Editor>(cx) else {
+        return;
+    };
+    let Some((buffer, selection)) = editor.update(cx, |editor, cx| {
+        let buffer = editor.buffer().read(cx).as_singleton()?;
+        Some((buffer, editor.selections.newest::<Point>(cx)))
+    }) else {
+        return;
+    };
+    let Some(project_path) = buffer.read(cx).project_path(cx) else {
+        return;
+    };
+    let Some((repository, repo_path)) = workspace
+        .project()
+        .read(cx)
+        .git_store()
+        .read(cx)
+        .repository_and_path_for_project_path(&project_path, cx)
+    else {
+        return;
+    };
+
+    // A selection that ends at the start of a line doesn't include that line.
+    let end_row = if selection.end.column == 0 && selection.end.row > selection.start.row {
+        selection.end.row - 1
+    } else {
+        selection.end.row
+    };
+    let options = LogOptions {
+        path: Some(repo_path),
+        line_range: selected_lines.then(|| selection.start.row + 1..=end_row + 1),
+        ..Default::default()
+    };
+    LogView::open(repository, options, workspace, window, cx);
+}
+
+struct LogViewEntry {
+    commit: LogEntry,
+    /// Only set when the full history is listed, since filtered histories skip commits.
+    graph_row: Option<GraphRow>,
+}
+
+pub struct LogView {
+    repository: Entity<Repository>,
+    workspace: WeakEntity<Workspace>,
+    options: LogOptions,
+    entries: Vec<LogViewEntry>,
+    graph: CommitGraph,
+    has_more: bool,
+    pending_page: Option<Task<()>>,
+    error: Option<SharedString>,
+    selected_index: Option<usize>,
+    focus_handle: FocusHandle,
+    scroll_handle: UniformListScrollHandle,
+}
+
+impl LogView {
+    pub fn open(
+        repository: Entity<Repository>,
+        options: LogOptions,
+        workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        let existing = workspace.items_of_type::<LogView>(cx).find(|view| {
+            let view = view.read(cx);
+            view.repository == repository && view.options == options
+        });
+        if let Some(existing) = existing {
+            workspace.activate_item(&existing, true, true, window, cx);
+            return;
+        }
+
+        let workspace_handle = workspace.weak_handle();
+        let view = cx.new(|cx| LogView::new(repository, options, workspace_handle, cx));
+        workspace.add_item_to_active_pane(Box::new(view), None, true, window, cx);
+    }
+
+    fn new(
+        repository: Entity<Repository>,
+        options: LogOptions,
+        workspace: WeakEntity<Workspace>,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let mut this = Self {
+            repository,
+            workspace,
+            options,
+            entries: Vec::new(),
+            graph: CommitGraph::default(),
+            has_more: true,
+            pending_page: None,
+            error: None,
+            selected_index: None,
+            focus_handle: cx.focus_handle(),
+            scroll_handle: UniformListScrollHandle::new(),
+        };
+        this.load_next_page(cx);
+        this
+    }
+
+    fn load_next_page(&mut self, cx: &mut Context<Self>) {
+        if self.pending_page.is_some() || !self.has_more {
+            return;
+        }
+        let options = LogOptions {
+            skip: self.entries.len(),
+            limit: LOG_PAGE_SIZE,
+            ..self.options.clone()
+        };
+        let page = self
+            .repository
+            .update(cx, |repository, _| repository.log(options));
+        self.pending_page = Some(cx.spawn(async move |this, cx| {
+            let page = page
+                .await
+                .map_err(anyhow::Error::from)
+                .and_then(|page| page);
+            this.update(cx, |this, cx| {
+                this.pending_page = None;
+                match page {
+                    Ok(commits) => {
+                        this.has_more = commits.len() == LOG_PAGE_SIZE;
+                        let draw_graph = this.options.path.is_none();
+                        for commit in commits {
+                            let graph_row = draw_graph.then(|| this.graph.push(&commit));
+                            this.entries.push(LogViewEntry { commit, graph_row });
+                        }
+                    }
+                    Err(error) => {
+                        this.has_more = false;
+                        this.error = Some(format!("{error:#}").into());
+                    }
+                }
+                cx.notify();
+            })
+            .ok();
+        }));
+    }
+
+    fn title(&self) -> SharedString {
+        match (&self.options.path, &self.options.line_range) {
+            (Some(path), Some(range)) => format!(
+                "History of {}:{}-{}",
+                file_name(path),
+                range.start(),
+                range.end()
+            )
+            .into(),
+            (Some(path), None) => format!("History of {}", file_name(path)).into(),
+            (None, _) => "Git History".into(),
+        }
+    }
+
+    fn select_next(&mut self, _: &menu::SelectNext, _: &mut Window, cx: &mut Context<Self>) {
+        if self.entries.is_empty() {
+            return;
+        }
+        let ix = self
+            .selected_index
+            .map_or(0, |ix| (ix + 1).min(self.entries.len() - 1));
+        self.select(ix, cx);
+    }
+
+    fn select_previous(
+        &mut self,
+        _: &menu::SelectPrevious,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.entries.is_empty() {
+            return;
+        }
+        let ix = self.selected_index.map_or(0, |ix| ix.saturating_sub(1));
+        self.select(ix, cx);
+    }
+
+    fn select(&mut self, ix: usize, cx: &mut Context<Self>) {
+        self.selected_index = Some(ix);
+        self.scroll_handle
+            .scroll_to_item(ix, ScrollStrategy::Center);
+        cx.notify();
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(ix) = self.selected_index {
+            self.open_commit(ix, window, cx);
+        }
+    }
+
+    fn open_commit(&mut self, ix: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(entry) = self.entries.get(ix) else {
+            return;
+        };
+        let commit = &entry.commit;
+        CommitView::open(
+            CommitSummary {
+                sha: commit.sha.clone(),
+                subject: commit.subject.clone(),
+                commit_timestamp: commit.commit_timestamp,
+                author_name: commit.author_name.clone(),
+                has_parent: !commit.parents.is_empty(),
+            },
+            self.repository.downgrade(),
+            self.workspace.clone(),
+            window,
+            cx,
+        );
+    }
+
+    /// Opens the filtered file as of the commit at `ix`, under the path it had at that commit.
+    fn open_revision(&mut self, ix: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(entry) = self.entries.get(ix) else {
+            return;
+        };
+        let Some(path) = entry
+            .commit
+            .path
+            .clone()
+            .or_else(|| self.options.path.clone())
+        else {
+            return;
+        };
+        RevisionView::open(
+            entry.commit.sha.clone(),
+            path,
+            self.repository.clone(),
+            self.workspace.clone(),
+            window,
+            cx,
+        )
+        .detach_and_prompt_err("Failed to open revision", window, cx, |e, _, _| {
+            Some(e.to_string())
+        });
+    }
+
+    fn render_entry(&self, ix: usize, cx: &mut Context<Self>) -> impl IntoElement + use<> {
+        let entry = &self.entries[ix];
+        let commit = &entry.commit;
+        let short_sha = commit.sha[..commit.sha.len().min(git::SHORT_SHA_LENGTH)].to_string();
+        let commit_time = OffsetDateTime::from_unix_timestamp(commit.commit_timestamp)
+            .unwrap_or_else(|_| OffsetDateTime::now_utc());
+        let commit_time = format_local_timestamp(
+            commit_time,
+            OffsetDateTime::now_utc(),
+            time_format::TimestampFormat::Relative,
+        );
+        let colors = cx.theme().colors();
+        let can_open_revision = self.options.path.is_some();
+
+        h_flex()
+            .id(ix)
+            .group("log-entry")
+            .h_7()
+            .w_full()
+            .px_2()
+            .gap_2()
+            .cursor_pointer()
+            .when(self.selected_index == Some(ix), |this| {
+                this.bg(colors.element_selected)
+            })
+            .hover(|this| this.bg(colors.element_hover))
+            .when_some(entry.graph_row.as_ref(), |this, graph_row| {
+                this.child(render_graph(graph_row, commit.is_merge(), cx))
+            })
+            .child(
+                h_flex()
+                    .flex_1()
+                    .min_w_0()
+                    .gap_1()
+                    .children(commit.refs.iter().map(|name| {
+                        div()
+                            .px_1()
+                            .rounded_sm()
+                            .border_1()
+                            .border_color(colors.border_variant)
+                            .child(
+                                Label::new(name.clone())
+                                    .size(LabelSize::XSmall)
+                                    .color(Color::Accent),
+                            )
+                    }))
+                    .child(Label::new(commit.subject.clone()).truncate()),
+            )
+            .child(
+                Label::new(commit.author_name.clone())
+                    .size(LabelSize::Small)
+                    .color(Color::Muted)
+                    .truncate(),
+            )
+            .child(
+                Label::new(commit_time)
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            )
+            .child(
+                Label::new(short_sha)
+                    .size(LabelSize::Small)
+                    .color(Color::Muted)
+                    .buffer_font(cx),
+            )
+            .when(can_open_revision, |this| {
+                this.child(
+                    div().visible_on_hover("log-entry").child(
+                        IconButton::new(("open-revision", ix), IconName::FileText)
+                            .icon_size(IconSize::Small)
+                            .tooltip(Tooltip::text("Open File at This Revision"))
+                            .on_click(cx.listener(move |this, _, window, cx| {
+                                cx.stop_propagation();
+                                this.open_revision(ix, window, cx);
+                            })),
+                    ),
+                )
+            })
+            .on_click(cx.listener(move |this, _, window, cx| {
+                this.selected_index = Some(ix);
+                this.open_commit(ix, window, cx);
+                cx.notify();
+            }))
+    }
+
+    fn render_empty_state(&self) -> impl IntoElement {
+        let message = if let Some(error) = &self.error {
+            error.clone()
+        } else if self.pending_page.is_some() {
+            "Loading history…".into()
+        } else {
+            "No commits found".into()
+        };
+        h_flex()
+            .size_full()
+            .justify_center()
+            .child(Label::new(message).color(Color::Muted))
+    }
+}
+
+/// Draws the lanes of a row of the commit graph, with a dot for the commit itself.
+fn render_graph(row: &GraphRow, is_merge: bool, cx: &App) -> impl IntoElement + use<> {
+    let accents = cx.theme().accents();
+    let lane_color = |lane: usize| accents.color_for_index(lane as u32);
+    let lane_center = |lane: usize| lane as f32 * LANE_WIDTH + LANE_WIDTH / 2.;
+    let vertical_line = |lane: usize| {
+        div()
+            .absolute()
+            .left(px(lane_center(lane) - LINE_WIDTH / 2.))
+            .w(px(LINE_WIDTH))
+            .bg(lane_color(lane))
+    };
+
+    let mut lines = Vec::new();
+    for &lane in &row.passing_lanes {
+        lines.push(vertical_line(lane).top_0().h_full());
+    }
+    for &lane in &row.merged_lanes {
+        lines.push(vertical_line(lane).top_0().h_1_2());
+    }
+    if row.has_children {
+        lines.push(vertical_line(row.lane).top_0().h_1_2());
+    }
+    for &lane in &row.parent_lanes {
+        lines.push(vertical_line(lane).bottom_0().h_1_2());
+    }
+    let connected_lanes = row
+        .merged_lanes
+        .iter()
+        .chain(&row.parent_lanes)
+        .copied()
+        .chain([row.lane]);
+    let (first_lane, last_lane) = (
+        connected_lanes.clone().min().unwrap_or(row.lane),
+        connected_lanes.max().unwrap_or(row.lane),
+    );
+    if first_lane != last_lane {
+        lines.push(
+            div()
+                .absolute()
+                .top_1_2()
+                .mt(px(-LINE_WIDTH / 2.))
+                .left(px(lane_center(first_lane)))
+                .w(px((last_lane - first_lane) as f32 * LANE_WIDTH))
+                .h(px(LINE_WIDTH))
+                .bg(lane_color(row.lane)),
+        );
+    }
+
+    let color = lane_color(row.lane);
+    let dot = div()
+        .absolute()
+        .top_1_2()
+        .mt(px(-DOT_SIZE / 2.))
+        .left(px(lane_center(row.lane) - DOT_SIZE / 2.))
+        .size(px(DOT_SIZE))
+        .rounded_full()
+        .border_2()
+        .border_color(color)
+        .when(!is_merge, |this| this.bg(color))
+        .when(is_merge, |this| {
+            this.bg(cx.theme().colors().editor_background)
+        });
+
+    div()
+        .relative()
+        .flex_none()
+        .h_full()
+        .w(px(row.width() as f32 * LANE_WIDTH))
+        .children(lines)
+        .child(dot)
+}
+
+fn file_name(path: &git::repository::RepoPath) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.to_string())
+}
+
+impl EventEmitter<ItemEvent> for LogView {}
+
+impl Focusable for LogView {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Item for LogView {
+    type Event = ItemEvent;
+
+    fn tab_icon(&self, _window: &Window, _cx: &App) -> Option<Icon> {
+        Some(Icon::new(IconName::HistoryRerun).color(Color::Muted))
+    }
+
+    fn tab_content(&self, params: TabContentParams, _window: &Window, cx: &App) -> AnyElement {
+        Label::new(self.tab_content_text(params.detail.unwrap_or_default(), cx))
+            .color(if params.selected {
+                Color::Default
+            } else {
+                Color::Muted
+            })
+            .into_any_element()
+    }
+
+    fn tab_content_text(&self, _detail: usize, _cx: &App) -> SharedString {
+        self.title()
+    }
+
+    fn tab_tooltip_text(&self, _: &App) -> Option<SharedString> {
+        self.options
+            .path
+            .as_ref()
+            .map(|path| SharedString::from(path.to_string()))
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("Git Log Opened")
+    }
+
+    fn to_item_events(event: &Self::Event, mut f: impl FnMut(ItemEvent)) {
+        f(*event)
+    }
+}
+
+impl Render for LogView {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let entry_count = self.entries.len();
+        let content = if entry_count == 0 {
+            self.render_empty_state().into_any_element()
+        } else {
+            div()
+                .size_full()
+                .child(
+                    uniform_list(
+                        "git-log-entries",
+                        entry_count,
+                        cx.processor(move |this, range: Range<usize>, _window, cx| {
+                            if range.end + LOAD_MORE_THRESHOLD >= this.entries.len() {
+                                this.load_next_page(cx);
+                            }
+                            range
+                                .map(|ix| this.render_entry(ix, cx))
+                                .collect::<Vec<_>>()
+                        }),
+                    )
+                    .size_full()
+                    .track_scroll(self.scroll_handle.clone()),
+                )
+                .vertical_scrollbar_for(self.scroll_handle.clone(), window, cx)
+                .into_any_element()
+        };
+
+        v_flex()
+            .key_context("GitLog")
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::select_next))
+            .on_action(cx.listener(Self::select_previous))
+            .on_action(cx.listener(Self::confirm))
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .child(content)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use git::repository::RepoPath;
+    use gpui::{TestAppContext, VisualTestContext};
+    use project::{FakeFs, Project, WorktreeSettings};
+    use serde_json::json;
+    use settings::SettingsStore;
+    use std::path::Path;
+    use theme::LoadThemes;
+    use util::path;
+
+    use super::*;
+
+    fn init_test(cx: &mut TestAppContext) {
+        zlog::init_test();
+
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            WorktreeSettings::register(cx);
+            workspace::init_settings(cx);
+            theme::init(LoadThemes::JustBase, cx);
+            language::init(cx);
+            editor::init(cx);
+            Project::init_settings(cx);
+        });
+    }
+
+    #[gpui::test]
+    async fn test_file_history(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.background_executor.clone());
+        fs.insert_tree(
+            path!("/root/project"),
+            json!({
+                ".git": {},
+                "src": {
+                    "main.rs": "fn main() {}",
+                    "parser.rs": "",
+                },
+            }),
+        )
+        .await;
+        let main_rs = RepoPath::from_str("src/main.rs");
+        let parser_rs = RepoPath::from_str("src/parser.rs");
+        fs.with_git_state(Path::new(path!("/root/project/.git")), true, |state| {
+            state.commit_history = ["Initial commit", "Add the parser", "Fix main"]
+                .into_iter()
+                .enumerate()
+                .map(|(ix, message)| (format!("sha{ix}"), message.to_string()))
+                .collect();
+            state.changed_paths.insert(
+                "sha0".into(),
+                vec![main_rs.clone(), RepoPath::from_str("README.md")],
+            );
+            state
+                .changed_paths
+                .insert("sha1".into(), vec![parser_rs.clone()]);
+            state
+                .changed_paths
+                .insert("sha2".into(), vec![main_rs.clone()]);
+        })
+        .unwrap();
+
+        let project = Project::test(fs.clone(), [path!("/root/project").as_ref()], cx).await;
+        let workspace =
+            cx.add_window(|window, cx| Workspace::test_new(project.clone(), window, cx));
+        let cx = &mut VisualTestContext::from_window(*workspace, cx);
+        cx.executor().run_until_parked();
+        let repository =
+            project.read_with(cx, |project, cx| project.active_repository(cx).unwrap());
+
+        let open_log = |options: LogOptions, cx: &mut VisualTestContext| {
+            let repository = repository.clone();
+            let view = workspace
+                .update(cx, |workspace, window, cx| {
+                    LogView::open(repository, options, workspace, window, cx);
+                    workspace.active_item_as::<LogView>(cx).unwrap()
+                })
+                .unwrap();
+            cx.executor().run_until_parked();
+            view.read_with(cx, |view, _| {
+                view.entries
+                    .iter()
+                    .map(|entry| {
+                        (
+                            entry.commit.sha.to_string(),
+                            entry.commit.path.clone(),
+                            entry.graph_row.is_some(),
+                        )
+                    })
+                    .collect::<Vec<_>>()
+            })
+        };
+
+        assert_eq!(
+            open_log(LogOptions::default(), cx),
+            [
+                ("sha2".to_string(), None, true),
+                ("sha1".to_string(), None, true),
+                ("sha0".to_string(), None, true),
+            ]
+        );
+        assert_eq!(
+            open_log(
+                LogOptions {
+                    path: Some(main_rs.clone()),
+                    ..Default::default()
+                },
+                cx
+            ),
+            [
+                ("sha2".to_string(), Some(main_rs.clone()), false),
+                ("sha0".to_string(), Some(main_rs.clone()), false),
+            ]
+        );
+        assert_eq!(
+            open_log(
+                LogOptions {
+                    revision: Some("sha1".into()),
+                    path: Some(main_rs.clone()),
+                    ..Default::default()
+                },
+                cx
+            ),
+            [("sha0".to_string(), Some(main_rs.clone()), false)]
+        );
+        assert_eq!(
+            open_log(
+                LogOptions {
+                    path: Some(parser_rs.clone()),
+                    line_range: Some(1..=1),
+                    ..Default::default()
+                },
+                cx
+            ),
+            [("sha1".to_string(), Some(parser_rs), false)]
+        );
+    }
+}
@@ -0,0 +1,688 @@
+use editor::{Editor, EditorEvent};
+use git::{
+    rebase::{RebaseAction, RebaseCommand, RebaseStop, RebaseTodo},
+    repository::RepoPath,
+};
+use gpui::{
+    App, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, PromptLevel, SharedString,
+    Subscription, WeakEntity, actions, prelude::*,
+};
+use project::git_store::Repository;
+use ui::{KeyBinding, ListItem, ListItemSpacing, Tooltip, prelude::*};
+use workspace::{ModalView, Workspace, notifications::DetachAndPromptErr};
+
+actions!(
+    rebase_modal,
+    [
+        /// Keeps the selected commit as is.
+        PickCommit,
+        /// Changes the message of the selected commit.
+        RewordCommit,
+        /// Melds the selected commit into the previous one, combining their messages.
+        SquashCommit,
+        /// Melds the selected commit into the previous one, discarding its message.
+        FixupCommit,
+        /// Removes the selected commit.
+        DropCommit,
+        /// Moves the selected commit before the previous one.
+        MoveCommitUp,
+        /// Moves the selected commit after the next one.
+        MoveCommitDown,
+        /// Rewrites the branch according to the plan.
+        StartRebase,
+        /// Continues the rebase after resolving the conflicts.
+        ContinueRebase,
+        /// Skips the commit the rebase stopped at.
+        SkipRebaseCommit,
+        /// Aborts the rebase, restoring the branch to its previous state.
+        AbortRebase,
+    ]
+);
+
+pub fn register(workspace: &mut Workspace) {
+    workspace.register_action(open);
+}
+
+fn open(
+    workspace: &mut Workspace,
+    _: &zed_actions::git::InteractiveRebase,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let Some(repository) = workspace.project().read(cx).active_repository(cx) else {
+        return;
+    };
+    let workspace_handle = workspace.weak_handle();
+    workspace.toggle_modal(window, cx, |window, cx| {
+        RebaseModal::new(repository, workspace_handle, window, cx)
+    });
+}
+
+enum RebaseState {
+    SelectBase,
+    Loading,
+    Editing {
+        todo: RebaseTodo,
+        selected_index: usize,
+        rewording: bool,
+    },
+    Running,
+    Stopped(RebaseStop),
+}
+
+/// Builds an interactive rebase plan, and resolves the stops of a rebase in progress.
+pub struct RebaseModal {
+    repository: Entity<Repository>,
+    workspace: WeakEntity<Workspace>,
+    state: RebaseState,
+    base_editor: Entity<Editor>,
+    message_editor: Entity<Editor>,
+    list_focus_handle: FocusHandle,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl ModalView for RebaseModal {}
+
+impl EventEmitter<DismissEvent> for RebaseModal {}
+
+impl Focusable for RebaseModal {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        match &self.state {
+            RebaseState::SelectBase | RebaseState::Loading => self.base_editor.focus_handle(cx),
+            RebaseState::Editing {
+                rewording: true, ..
+            } => self.message_editor.focus_handle(cx),
+            _ => self.list_focus_handle.clone(),
+        }
+    }
+}
+
+impl RebaseModal {
+    fn new(
+        repository: Entity<Repository>,
+        workspace: WeakEntity<Workspace>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let upstream = repository
+            .read(cx)
+            .branch
+            .as_ref()
+            .and_then(|branch| branch.upstream.as_ref())
+            .map(|upstream| upstream.ref_name.clone());
+        let base_editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text("Rebase onto, e.g. main or HEAD~5", window, cx);
+            if let Some(upstream) = upstream {
+                editor.set_text(upstream, window, cx);
+            }
+            editor
+        });
+        let message_editor = cx.new(|cx| {
+            let mut editor = Editor::auto_height(1, 6, window, cx);
+            editor.set_placeholder_text("Commit message", window, cx);
+            editor
+        });
+        let _subscriptions = vec![
+            cx.subscribe(&message_editor, |this, _, event: &EditorEvent, cx| {
+                if let EditorEvent::Blurred = event
+                    && let RebaseState::Editing { rewording, .. } = &mut this.state
+                {
+                    *rewording = false;
+                    cx.notify();
+                }
+            }),
+            cx.observe(&repository, |_, _, cx| cx.notify()),
+        ];
+
+        let mut this = Self {
+            repository,
+            workspace,
+            state: RebaseState::Loading,
+            base_editor,
+            message_editor,
+            list_focus_handle: cx.focus_handle(),
+            _subscriptions,
+        };
+        this.load_stop(window, cx);
+        this
+    }
+
+    fn load_stop(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let stop = self
+            .repository
+            .update(cx, |repository, _| repository.rebase_stop());
+        cx.spawn_in(window, async move |this, cx| {
+            let stop = stop.await??;
+            this.update_in(cx, |this, window, cx| {
+                this.state = match stop {
+                    Some(stop) => RebaseState::Stopped(stop),
+                    None => RebaseState::SelectBase,
+                };
+                this.focus_handle(cx).focus(window);
+                cx.notify();
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn load_todo(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let base = self.base_editor.read(cx).text(cx).trim().to_string();
+        if base.is_empty() {
+            return;
+        }
+        let todo = self
+            .repository
+            .update(cx, |repository, _| repository.rebase_todo(base));
+        self.state = RebaseState::Loading;
+        cx.notify();
+        cx.spawn_in(window, async move |this, cx| {
+            let todo = todo
+                .await
+                .map_err(anyhow::Error::from)
+                .and_then(|todo| todo);
+            this.update_in(cx, |this, window, cx| {
+                this.state = match &todo {
+                    Ok(todo) => RebaseState::Editing {
+                        todo: todo.clone(),
+                        selected_index: 0,
+                        rewording: false,
+                    },
+                    Err(_) => RebaseState::SelectBase,
+                };
+                this.focus_handle(cx).focus(window);
+                cx.notify();
+            })?;
+            todo.map(|_| ())
+        })
+        .detach_and_prompt_err("Failed to list commits", window, cx, |_, _, _| None);
+    }
+
+    fn run(
+        &mut self,
+        task: impl Future<Output = anyhow::Result<Option<RebaseStop>>> + 'static,
+        error_message: &'static str,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.state = RebaseState::Running;
+        cx.notify();
+        cx.spawn_in(window, async move |this, cx| {
+            let result = task.await;
+            this.update_in(cx, |this, window, cx| match &result {
+                Ok(Some(stop)) => {
+                    this.state = RebaseState::Stopped(stop.clone());
+                    this.focus_handle(cx).focus(window);
+                    cx.notify();
+                }
+                Ok(None) => cx.emit(DismissEvent),
+                Err(_) => this.load_stop(window, cx),
+            })?;
+            result.map(|_| ())
+        })
+        .detach_and_prompt_err(error_message, window, cx, |_, _, _| None);
+    }
+
+    fn set_action(&mut self, action: RebaseAction, window: &mut Window, cx: &mut Context<Self>) {
+        let RebaseState::Editing {
+            todo,
+            selected_index,
+            rewording,
+        } = &mut self.state
+        else {
+            return;
+        };
+        todo.set_action(*selected_index, action);
+        if action == RebaseAction::Reword
+            && let Some(entry) = todo.entries.get(*selected_index)
+        {
+            let message = entry
+                .message
+                .clone()
+                .unwrap_or_else(|| entry.subject.to_string());
+            *rewording = true;
+            self.message_editor.update(cx, |editor, cx| {
+                editor.set_text(message, window, cx);
+            });
+            self.message_editor.focus_handle(cx).focus(window);
+        }
+        cx.notify();
+    }
+
+    fn pick(&mut self, _: &PickCommit, window: &mut Window, cx: &mut Context<Self>) {
+        self.set_action(RebaseAction::Pick, window, cx);
+    }
+
+    fn reword(&mut self, _: &RewordCommit, window: &mut Window, cx: &mut Context<Self>) {
+        self.set_action(RebaseAction::Reword, window, cx);
+    }
+
+    fn squash(&mut self, _: &SquashCommit, window: &mut Window, cx: &mut Context<Self>) {
+        self.set_action(RebaseAction::Squash, window, cx);
+    }
+
+    fn fixup(&mut self, _: &FixupCommit, window: &mut Window, cx: &mut Context<Self>) {
+        self.set_action(RebaseAction::Fixup, window, cx);
+    }
+
+    fn drop_commit(&mut self, _: &DropCommit, window: &mut Window, cx: &mut Context<Self>) {
+        self.set_action(RebaseAction::Drop, window, cx);
+    }
+
+    fn move_selected(&mut self, up: bool, cx: &mut Context<Self>) {
+        let RebaseState::Editing {
+            todo,
+            selected_index,
+            ..
+        } = &mut self.state
+        else {
+            return;
+        };
+        let target = if up {
+            selected_index.checked_sub(1)
+        } else {
+            Some(*selected_index + 1).filter(|ix| *ix < todo.entries.len())
+        };
+        if let Some(target) = target {
+            todo.move_entry(*selected_index, target);
+            *selected_index = target;
+            cx.notify();
+        }
+    }
+
+    fn move_up(&mut self, _: &MoveCommitUp, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_selected(true, cx);
+    }
+
+    fn move_down(&mut self, _: &MoveCommitDown, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_selected(false, cx);
+    }
+
+    fn select_index(&mut self, ix: usize, cx: &mut Context<Self>) {
+        if let RebaseState::Editing {
+            todo,
+            selected_index,
+            ..
+        } = &mut self.state
+            && ix < todo.entries.len()
+        {
+            *selected_index = ix;
+            cx.notify();
+        }
+    }
+
+    fn select_previous(
+        &mut self,
+        _: &menu::SelectPrevious,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let RebaseState::Editing { selected_index, .. } = self.state {
+            self.select_index(selected_index.saturating_sub(1), cx);
+        }
+    }
+
+    fn select_next(&mut self, _: &menu::SelectNext, _: &mut Window, cx: &mut Context<Self>) {
+        if let RebaseState::Editing { selected_index, .. } = self.state {
+            self.select_index(selected_index + 1, cx);
+        }
+    }
+
+    fn start(&mut self, _: &StartRebase, window: &mut Window, cx: &mut Context<Self>) {
+        let RebaseState::Editing { todo, .. } = &self.state else {
+            return;
+        };
+        if let Err(error) = todo.validate() {
+            let detail = error.to_string();
+            let _ = window.prompt(
+                PromptLevel::Info,
+                "Invalid rebase plan",
+                Some(&detail),
+                &["Ok"],
+                cx,
+            );
+            return;
+        }
+        let todo = todo.clone();
+        let task = self
+            .repository
+            .update(cx, |repository, _| repository.rebase_interactive(todo));
+        self.run(async move { task.await? }, "Failed to rebase", window, cx);
+    }
+
+    fn run_command(&mut self, command: RebaseCommand, window: &mut Window, cx: &mut Context<Self>) {
+        if !matches!(self.state, RebaseState::Stopped(_)) {
+            return;
+        }
+        let task = self
+            .repository
+            .update(cx, |repository, _| repository.rebase_command(command));
+        let error_message = match command {
+            RebaseCommand::Continue => "Failed to continue rebase",
+            RebaseCommand::Skip => "Failed to skip commit",
+            RebaseCommand::Abort => "Failed to abort rebase",
+        };
+        self.run(async move { task.await? }, error_message, window, cx);
+    }
+
+    fn continue_rebase(&mut self, _: &ContinueRebase, window: &mut Window, cx: &mut Context<Self>) {
+        self.run_command(RebaseCommand::Continue, window, cx);
+    }
+
+    fn skip_commit(&mut self, _: &SkipRebaseCommit, window: &mut Window, cx: &mut Context<Self>) {
+        self.run_command(RebaseCommand::Skip, window, cx);
+    }
+
+    fn abort(&mut self, _: &AbortRebase, window: &mut Window, cx: &mut Context<Self>) {
+        self.run_command(RebaseCommand::Abort, window, cx);
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        match &mut self.state {
+            RebaseState::SelectBase => self.load_todo(window, cx),
+            RebaseState::Editing {
+                todo,
+                selected_index,
+                rewording,
+            } if *rewording => {
+                let message = self.message_editor.read(cx).text(cx);
+                if !message.trim().is_empty() {
+                    todo.set_message(*selected_index, message);
+                }
+                *rewording = false;
+                self.list_focus_handle.focus(window);
+                cx.notify();
+            }
+            RebaseState::Editing { .. } => self.start(&StartRebase, window, cx),
+            RebaseState::Stopped(_) => self.run_command(RebaseCommand::Continue, window, cx),
+            RebaseState::Loading | RebaseState::Running => {}
+        }
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, window: &mut Window, cx: &mut Context<Self>) {
+        if let RebaseState::Editing { rewording, .. } = &mut self.state
+            && *rewording
+        {
+            *rewording = false;
+            self.list_focus_handle.focus(window);
+            cx.notify();
+        } else {
+            cx.emit(DismissEvent);
+        }
+    }
+
+    fn open_conflict(&mut self, repo_path: &RepoPath, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(project_path) = self
+            .repository
+            .read(cx)
+            .repo_path_to_project_path(repo_path, cx)
+        else {
+            return;
+        };
+        self.workspace
+            .update(cx, |workspace, cx| {
+                workspace
+                    .open_path(project_path, None, true, window, cx)
+                    .detach_and_prompt_err("Failed to open file", window, cx, |_, _, _| None);
+            })
+            .ok();
+    }
+
+    fn render_entries(
+        &self,
+        todo: &RebaseTodo,
+        selected_index: usize,
+        rewording: bool,
+        cx: &Context<Self>,
+    ) -> impl IntoElement {
+        v_flex()
+            .id("rebase-entries")
+            .track_focus(&self.list_focus_handle)
+            .key_context("RebaseCommitList")
+            .max_h(rems(24.))
+            .overflow_y_scroll()
+            .p_1()
+            .when(todo.entries.is_empty(), |this| {
+                this.child(
+                    Label::new("There are no commits to rebase")
+                        .color(Color::Muted)
+                        .size(LabelSize::Small),
+                )
+            })
+            .children(todo.entries.iter().enumerate().map(|(ix, entry)| {
+                let action_color = match entry.action {
+                    RebaseAction::Pick => Color::Default,
+                    RebaseAction::Reword => Color::Accent,
+                    RebaseAction::Squash | RebaseAction::Fixup => Color::Warning,
+                    RebaseAction::Drop => Color::Deleted,
+                };
+                let subject = match (&entry.action, &entry.message) {
+                    (RebaseAction::Reword, Some(message)) => {
+                        SharedString::from(message.lines().next().unwrap_or_default().to_string())
+                    }
+                    _ => entry.subject.clone(),
+                };
+                let selected = ix == selected_index;
+                v_flex()
+                    .child(
+                        ListItem::new(("rebase-entry", ix))
+                            .spacing(ListItemSpacing::Sparse)
+                            .toggle_state(selected)
+                            .start_slot(
+                                h_flex()
+                                    .w(rems(4.))
+                                    .child(Label::new(entry.action.as_str()).color(action_color)),
+                            )
+                            .child(
+                                h_flex()
+                                    .gap_2()
+                                    .min_w_0()
+                                    .child(
+                                        Label::new(
+                                            entry
+                                                .sha
+                                                .get(..git::SHORT_SHA_LENGTH)
+                                                .unwrap_or(&entry.sha)
+                                                .to_string(),
+                                        )
+                                        .color(Color::Muted)
+                                        .buffer_font(cx),
+                                    )
+                                    .child(
+                                        Label::new(subject)
+                                            .when(entry.action == RebaseAction::Drop, |label| {
+                                                label.strikethrough()
+                                            })
+                                            .truncate(),
+                                    ),
+                            )
+                            .on_click(cx.listener(move |this, _, window, cx| {
+                                this.select_index(ix, cx);
+                                this.list_focus_handle.focus(window);
+                            })),
+                    )
+                    .when(selected && rewording, |this| {
+                        this.child(
+                            div()
+                                .mx_2()
+                                .my_1()
+                                .p_1()
+                                .border_1()
+                                .rounded_sm()
+                                .border_color(cx.theme().colors().border_focused)
+                                .child(self.message_editor.clone()),
+                        )
+                    })
+            }))
+    }
+
+    fn render_stop(&self, stop: &RebaseStop, cx: &Context<Self>) -> impl IntoElement {
+        let repository = self.repository.read(cx);
+        let conflicted_paths = repository
+            .merge
+            .conflicted_paths
+            .iter()
+            .cloned()
+            .collect::<Vec<_>>();
+        let title = match &stop.sha {
+            Some(sha) => format!(
+                "Stopped at {} ({} of {})",
+                sha.get(..git::SHORT_SHA_LENGTH).unwrap_or(sha),
+                stop.done,
+                stop.total
+            ),
+            None => format!("Stopped at commit {} of {}", stop.done, stop.total),
+        };
+
+        v_flex()
+            .id("rebase-stop")
+            .track_focus(&self.list_focus_handle)
+            .p_2()
+            .gap_1()
+            .child(Label::new(title))
+            .child(
+                Label::new(if conflicted_paths.is_empty() {
+                    "All conflicts are resolved. Stage your changes and continue."
+                } else {
+                    "Resolve these conflicts, stage the files and continue:"
+                })
+                .size(LabelSize::Small)
+                .color(Color::Muted),
+            )
+            .children(conflicted_paths.into_iter().enumerate().map(|(ix, path)| {
+                ListItem::new(("conflicted-path", ix))
+                    .spacing(ListItemSpacing::Sparse)
+                    .start_slot(
+                        Icon::new(IconName::Warning)
+                            .size(IconSize::Small)
+                            .color(Color::Conflict),
+                    )
+                    .child(Label::new(path.display().to_string()).truncate())
+                    .on_click(cx.listener(move |this, _, window, cx| {
+                        this.open_conflict(&path, window, cx);
+                    }))
+            }))
+    }
+
+    fn render_footer(&self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let focus_handle = self.focus_handle(cx);
+        let buttons = match &self.state {
+            RebaseState::SelectBase => vec![
+                Button::new("load-commits", "List Commits")
+                    .key_binding(KeyBinding::for_action_in(
+                        &menu::Confirm,
+                        &focus_handle,
+                        window,
+                        cx,
+                    ))
+                    .on_click(cx.listener(|this, _, window, cx| this.load_todo(window, cx)))
+                    .into_any_element(),
+            ],
+            RebaseState::Editing { .. } => vec![
+                Button::new("start-rebase", "Rebase")
+                    .key_binding(KeyBinding::for_action_in(
+                        &StartRebase,
+                        &focus_handle,
+                        window,
+                        cx,
+                    ))
+                    .on_click(
+                        cx.listener(|this, _, window, cx| this.start(&StartRebase, window, cx)),
+                    )
+                    .into_any_element(),
+            ],
+            RebaseState::Stopped(_) => vec![
+                Button::new("abort-rebase", "Abort")
+                    .tooltip(Tooltip::text(
+                        "Restore the branch to its state before the rebase",
+                    ))
+                    .on_click(
+                        cx.listener(|this, _, window, cx| this.abort(&AbortRebase, window, cx)),
+                    )
+                    .into_any_element(),
+                Button::new("skip-commit", "Skip")
+                    .on_click(cx.listener(|this, _, window, cx| {
+                        this.skip_commit(&SkipRebaseCommit, window, cx)
+                    }))
+                    .into_any_element(),
+                Button::new("continue-rebase", "Continue")
+                    .key_binding(KeyBinding::for_action_in(
+                        &ContinueRebase,
+                        &focus_handle,
+                        window,
+                        cx,
+                    ))
+                    .on_click(cx.listener(|this, _, window, cx| {
+                        this.continue_rebase(&ContinueRebase, window, cx)
+                    }))
+                    .into_any_element(),
+            ],
+            RebaseState::Loading | RebaseState::Running => Vec::new(),
+        };
+        h_flex()
+            .p_2()
+            .gap_1()
+            .justify_end()
+            .border_t_1()
+            .border_color(cx.theme().colors().border_variant)
+            .children(buttons)
+    }
+}
+
+impl Render for RebaseModal {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let body = match &self.state {
+            RebaseState::SelectBase => div()
+                .px_2()
+                .py_1()
+                .child(self.base_editor.clone())
+                .into_any_element(),
+            RebaseState::Loading | RebaseState::Running => h_flex()
+                .p_2()
+                .child(Label::new("Working…").color(Color::Muted))
+                .into_any_element(),
+            RebaseState::Editing {
+                todo,
+                selected_index,
+                rewording,
+            } => self
+                .render_entries(todo, *selected_index, *rewording, cx)
+                .into_any_element(),
+            RebaseState::Stopped(stop) => self.render_stop(stop, cx).into_any_element(),
+        };
+        let title = match &self.state {
+            RebaseState::Stopped(_) => "Rebase in Progress",
+            _ => "Interactive Rebase",
+        };
+
+        v_flex()
+            .key_context("RebaseModal")
+            .w(rems(34.))
+            .elevation_3(cx)
+            .on_action(cx.listener(Self::confirm))
+            .on_action(cx.listener(Self::cancel))
+            .on_action(cx.listener(Self::pick))
+            .on_action(cx.listener(Self::reword))
+            .on_action(cx.listener(Self::squash))
+            .on_action(cx.listener(Self::fixup))
+            .on_action(cx.listener(Self::drop_commit))
+            .on_action(cx.listener(Self::move_up))
+            .on_action(cx.listener(Self::move_down))
+            .on_action(cx.listener(Self::select_previous))
+            .on_action(cx.listener(Self::select_next))
+            .on_action(cx.listener(Self::start))
+            .on_action(cx.listener(Self::continue_rebase))
+            .on_action(cx.listener(Self::skip_commit))
+            .on_action(cx.listener(Self::abort))
+            .child(
+                h_flex()
+                    .px_2()
+                    .py_1()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .child(Headline::new(title).size(HeadlineSize::XSmall)),
+            )
+            .child(body)
+            .child(self.render_footer(window, cx))
+    }
+}
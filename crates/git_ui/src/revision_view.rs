@@ -0,0 +1,306 @@
+//! RevisionView shows a file as it was at a past commit, diffed against its working copy.
+
+use anyhow::{Context as _, Result};
+use buffer_diff::{BufferDiff, BufferDiffSnapshot};
+use editor::{Editor, EditorEvent, MultiBuffer};
+use git::{SHORT_SHA_LENGTH, repository::RepoPath};
+use gpui::{
+    AnyElement, AnyView, App, AppContext as _, Context, Entity, EventEmitter, FocusHandle,
+    Focusable, IntoElement, Render, Subscription, Task, WeakEntity, Window,
+};
+use language::Buffer;
+use project::git_store::Repository;
+use std::{
+    any::{Any, TypeId},
+    sync::Arc,
+    time::Duration,
+};
+use ui::{Color, Icon, IconName, Label, LabelCommon as _, SharedString};
+use workspace::{
+    Item, ItemHandle as _, ItemNavHistory, ToolbarItemLocation, Workspace,
+    item::{BreadcrumbText, ItemEvent, TabContentParams},
+    searchable::SearchableItemHandle,
+};
+
+use crate::commit_view::{GitBlob, build_buffer};
+
+pub struct RevisionView {
+    revision: SharedString,
+    path: RepoPath,
+    editor: Entity<Editor>,
+    buffer: Entity<Buffer>,
+    working_buffer: Option<Entity<Buffer>>,
+    diff: Entity<BufferDiff>,
+    _recalculate_diff_task: Task<Result<()>>,
+    _working_buffer_subscription: Option<Subscription>,
+}
+
+const RECALCULATE_DIFF_DEBOUNCE: Duration = Duration::from_millis(250);
+
+impl RevisionView {
+    /// Opens `path` as of `revision` in a read-only editor, highlighting how it differs from the
+    /// working copy.
+    pub fn open(
+        revision: SharedString,
+        path: RepoPath,
+        repository: Entity<Repository>,
+        workspace: WeakEntity<Workspace>,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Task<Result<Entity<Self>>> {
+        let text = repository.update(cx, |repository, _| {
+            repository.load_text_at_revision(revision.to_string(), path.clone())
+        });
+        let project_path = repository.read(cx).repo_path_to_project_path(&path, cx);
+        window.spawn(cx, async move |cx| {
+            let short_sha = &revision[..revision.len().min(SHORT_SHA_LENGTH)];
+            let text = text
+                .await??
+                .with_context(|| format!("{path} does not exist at {short_sha}"))?;
+            let project_path = project_path.context("file is outside of the project")?;
+            let project = workspace.read_with(cx, |workspace, _| workspace.project().clone())?;
+            let language_registry =
+                project.read_with(cx, |project, _| project.languages().clone())?;
+
+            let working_buffer = project
+                .update(cx, |project, cx| {
+                    project.open_buffer(project_path.clone(), cx)
+                })?
+                .await
+                .ok();
+            let blob = Arc::new(GitBlob {
+                path: path.clone(),
+                worktree_id: project_path.worktree_id,
+                is_deleted: false,
+            });
+            let buffer = build_buffer(text, blob, &language_registry, cx).await?;
+
+            workspace.update_in(cx, |workspace, window, cx| {
+                let pane = workspace.active_pane().clone();
+                let existing = pane.read(cx).items().find_map(|item| {
+                    let view = item.downcast::<RevisionView>()?;
+                    let is_same = {
+                        let view = view.read(cx);
+                        view.revision == revision && view.path == path
+                    };
+                    is_same.then_some(view)
+                });
+                if let Some(view) = existing {
+                    pane.update(cx, |pane, cx| {
+                        if let Some(ix) = pane.index_for_item(&view) {
+                            pane.activate_item(ix, true, true, window, cx);
+                        }
+                    });
+                    return view;
+                }
+
+                let view = cx.new(|cx| {
+                    RevisionView::new(revision, path, buffer, working_buffer, project, window, cx)
+                });
+                pane.update(cx, |pane, cx| {
+                    pane.add_item(Box::new(view.clone()), true, true, None, window, cx);
+                });
+                view
+            })
+        })
+    }
+
+    fn new(
+        revision: SharedString,
+        path: RepoPath,
+        buffer: Entity<Buffer>,
+        working_buffer: Option<Entity<Buffer>>,
+        project: Entity<project::Project>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let diff = cx.new(|cx| BufferDiff::new(&buffer.read(cx).text_snapshot(), cx));
+        let multibuffer = cx.new(|cx| {
+            let mut multibuffer = MultiBuffer::singleton(buffer.clone(), cx);
+            multibuffer.add_diff(diff.clone(), cx);
+            multibuffer
+        });
+        let editor = cx.new(|cx| {
+            let mut editor = Editor::for_multibuffer(multibuffer, Some(project), window, cx);
+            editor.set_read_only(true);
+            editor.disable_diagnostics(cx);
+            editor.set_render_diff_hunk_controls(
+                Arc::new(|_, _, _, _, _, _, _, _| gpui::Empty.into_any_element()),
+                cx,
+            );
+            editor
+        });
+
+        // The working copy may change while the revision is open, so keep the diff up to date.
+        let working_buffer_subscription = working_buffer.as_ref().map(|working_buffer| {
+            cx.subscribe(working_buffer, |this, _, event, cx| {
+                if let language::BufferEvent::Edited = event {
+                    this.recalculate_diff(Some(RECALCULATE_DIFF_DEBOUNCE), cx);
+                }
+            })
+        });
+
+        let mut this = Self {
+            revision,
+            path,
+            editor,
+            buffer,
+            working_buffer,
+            diff,
+            _recalculate_diff_task: Task::ready(Ok(())),
+            _working_buffer_subscription: working_buffer_subscription,
+        };
+        this.recalculate_diff(None, cx);
+        this
+    }
+
+    /// Diffs the revision against the working copy, treating the working copy as the base text.
+    fn recalculate_diff(&mut self, debounce: Option<Duration>, cx: &mut Context<Self>) {
+        let Some(working_buffer) = &self.working_buffer else {
+            return;
+        };
+        let working_snapshot = working_buffer.read(cx).snapshot();
+        let snapshot = self.buffer.read(cx).text_snapshot();
+        let diff = self.diff.clone();
+        self._recalculate_diff_task = cx.spawn(async move |_, cx| {
+            if let Some(debounce) = debounce {
+                cx.background_executor().timer(debounce).await;
+            }
+            let diff_snapshot = cx
+                .update(|cx| {
+                    BufferDiffSnapshot::new_with_base_buffer(
+                        snapshot.clone(),
+                        Some(working_snapshot.text().into()),
+                        working_snapshot,
+                        cx,
+                    )
+                })?
+                .await;
+            diff.update(cx, |diff, cx| {
+                diff.set_snapshot(diff_snapshot, &snapshot, cx);
+            })
+        });
+    }
+}
+
+impl EventEmitter<EditorEvent> for RevisionView {}
+
+impl Focusable for RevisionView {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.editor.focus_handle(cx)
+    }
+}
+
+impl Item for RevisionView {
+    type Event = EditorEvent;
+
+    fn tab_icon(&self, _window: &Window, _cx: &App) -> Option<Icon> {
+        Some(Icon::new(IconName::HistoryRerun).color(Color::Muted))
+    }
+
+    fn tab_content(&self, params: TabContentParams, _window: &Window, cx: &App) -> AnyElement {
+        Label::new(self.tab_content_text(params.detail.unwrap_or_default(), cx))
+            .color(if params.selected {
+                Color::Default
+            } else {
+                Color::Muted
+            })
+            .into_any_element()
+    }
+
+    fn tab_content_text(&self, _detail: usize, _cx: &App) -> SharedString {
+        let file_name = self
+            .path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| self.path.to_string());
+        let short_sha = &self.revision[..self.revision.len().min(SHORT_SHA_LENGTH)];
+        format!("{file_name} @ {short_sha}").into()
+    }
+
+    fn tab_tooltip_text(&self, _: &App) -> Option<SharedString> {
+        Some(format!("{} @ {}", self.path, self.revision).into())
+    }
+
+    fn to_item_events(event: &EditorEvent, f: impl FnMut(ItemEvent)) {
+        Editor::to_item_events(event, f)
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("Revision View Opened")
+    }
+
+    fn deactivated(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.editor
+            .update(cx, |editor, cx| editor.deactivated(window, cx));
+    }
+
+    fn is_singleton(&self, _: &App) -> bool {
+        false
+    }
+
+    fn act_as_type<'a>(
+        &'a self,
+        type_id: TypeId,
+        self_handle: &'a Entity<Self>,
+        _: &'a App,
+    ) -> Option<AnyView> {
+        if type_id == TypeId::of::<Self>() {
+            Some(self_handle.to_any())
+        } else if type_id == TypeId::of::<Editor>() {
+            Some(self.editor.to_any())
+        } else {
+            None
+        }
+    }
+
+    fn as_searchable(&self, _: &Entity<Self>) -> Option<Box<dyn SearchableItemHandle>> {
+        Some(Box::new(self.editor.clone()))
+    }
+
+    fn set_nav_history(
+        &mut self,
+        nav_history: ItemNavHistory,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.editor.update(cx, |editor, _| {
+            editor.set_nav_history(Some(nav_history));
+        });
+    }
+
+    fn navigate(
+        &mut self,
+        data: Box<dyn Any>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        self.editor
+            .update(cx, |editor, cx| editor.navigate(data, window, cx))
+    }
+
+    fn breadcrumb_location(&self, _: &App) -> ToolbarItemLocation {
+        ToolbarItemLocation::PrimaryLeft
+    }
+
+    fn breadcrumbs(&self, theme: &theme::Theme, cx: &App) -> Option<Vec<BreadcrumbText>> {
+        self.editor.breadcrumbs(theme, cx)
+    }
+
+    fn added_to_workspace(
+        &mut self,
+        workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.editor.update(cx, |editor, cx| {
+            editor.added_to_workspace(workspace, window, cx)
+        });
+    }
+}
+
+impl Render for RevisionView {
+    fn render(&mut self, _: &mut Window, _: &mut Context<Self>) -> impl IntoElement {
+        self.editor.clone()
+    }
+}
@@ -0,0 +1,137 @@
+use anyhow::Result;
+use editor::Editor;
+use gpui::{App, Task, actions};
+use project::{CallHierarchyItem, Project};
+use settings::{PanelSettingsContent, Settings as _, SettingsContent};
+use ui::prelude::*;
+use workspace::Workspace;
+
+use crate::{
+    hierarchy_panel_settings::{CallHierarchyPanelSettings, HierarchyPanelSettings},
+    tree_panel::{Hierarchy, HierarchyPanel, HierarchyRelative},
+};
+
+actions!(
+    call_hierarchy,
+    [
+        /// Toggles focus on the call hierarchy panel.
+        ToggleFocus,
+        /// Shows the callers of the symbol under the cursor in the call hierarchy panel.
+        ShowIncomingCalls,
+        /// Shows the callees of the symbol under the cursor in the call hierarchy panel.
+        ShowOutgoingCalls,
+        /// Switches the call hierarchy panel between showing callers and callees.
+        ToggleDirection,
+    ]
+);
+
+pub(crate) fn register(workspace: &mut Workspace) {
+    workspace.register_action(|workspace, _: &ToggleFocus, window, cx| {
+        workspace.toggle_panel_focus::<CallHierarchyPanel>(window, cx);
+    });
+    workspace.register_action(|workspace, _: &ShowIncomingCalls, window, cx| {
+        show_call_hierarchy(workspace, CallDirection::Incoming, window, cx);
+    });
+    workspace.register_action(|workspace, _: &ShowOutgoingCalls, window, cx| {
+        show_call_hierarchy(workspace, CallDirection::Outgoing, window, cx);
+    });
+}
+
+fn show_call_hierarchy(
+    workspace: &mut Workspace,
+    direction: CallDirection,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let Some(editor) = workspace.active_item_as::<Editor>(cx) else {
+        return;
+    };
+    let Some((buffer, position)) = ({
+        let editor = editor.read(cx);
+        let head = editor.selections.newest_anchor().head();
+        editor.buffer().read(cx).text_anchor_for_position(head, cx)
+    }) else {
+        return;
+    };
+    let items = workspace.project().update(cx, |project, cx| {
+        project.prepare_call_hierarchy(&buffer, position, cx)
+    });
+    if let Some(panel) = workspace.focus_panel::<CallHierarchyPanel>(window, cx) {
+        panel.update(cx, |panel, cx| panel.show_items(items, direction, cx));
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CallDirection {
+    /// The tree lists the callers of its items.
+    Incoming,
+    /// The tree lists the callees of its items.
+    Outgoing,
+}
+
+/// Shows the callers or callees of a symbol as a tree.
+pub type CallHierarchyPanel = HierarchyPanel<CallHierarchy>;
+
+pub enum CallHierarchy {}
+
+impl Hierarchy for CallHierarchy {
+    type Direction = CallDirection;
+    type ToggleFocus = ToggleFocus;
+    type ToggleDirection = ToggleDirection;
+
+    const PANEL_NAME: &'static str = "CallHierarchyPanel";
+    const KEY_CONTEXT: &'static str = "CallHierarchyPanel";
+    const INITIAL_DIRECTION: CallDirection = CallDirection::Incoming;
+    const ICON_TOOLTIP: &'static str = "Call Hierarchy";
+    const TOGGLE_DIRECTION_TOOLTIP: &'static str = "Toggle Callers and Callees";
+    const EMPTY_MESSAGE: &'static str = "No call hierarchy for the symbol under the cursor";
+    const ACTIVATION_PRIORITY: u32 = 10;
+
+    fn title(direction: CallDirection) -> &'static str {
+        match direction {
+            CallDirection::Incoming => "Incoming Calls",
+            CallDirection::Outgoing => "Outgoing Calls",
+        }
+    }
+
+    fn toggled(direction: CallDirection) -> CallDirection {
+        match direction {
+            CallDirection::Incoming => CallDirection::Outgoing,
+            CallDirection::Outgoing => CallDirection::Incoming,
+        }
+    }
+
+    fn relatives(
+        project: &mut Project,
+        item: &CallHierarchyItem,
+        direction: CallDirection,
+        cx: &mut Context<Project>,
+    ) -> Task<Result<Vec<HierarchyRelative>>> {
+        let calls = match direction {
+            CallDirection::Incoming => project.incoming_calls(item, cx),
+            CallDirection::Outgoing => project.outgoing_calls(item, cx),
+        };
+        cx.background_spawn(async move {
+            Ok(calls
+                .await?
+                .into_iter()
+                .map(|call| HierarchyRelative {
+                    item: call.item,
+                    locations: call.call_sites,
+                })
+                .collect())
+        })
+    }
+
+    fn locations_label(count: usize) -> String {
+        format!("{count} calls")
+    }
+
+    fn settings(cx: &App) -> HierarchyPanelSettings {
+        CallHierarchyPanelSettings::get_global(cx).0
+    }
+
+    fn settings_content(settings: &mut SettingsContent) -> &mut Option<PanelSettingsContent> {
+        &mut settings.call_hierarchy_panel
+    }
+}
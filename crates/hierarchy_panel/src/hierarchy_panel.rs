@@ -0,0 +1,40 @@
+mod call_hierarchy_panel;
+mod hierarchy_panel_settings;
+mod tree_panel;
+mod type_hierarchy_panel;
+
+use gpui::App;
+use settings::Settings as _;
+use workspace::Workspace;
+
+pub use call_hierarchy_panel::{CallDirection, CallHierarchyPanel};
+pub use hierarchy_panel_settings::{
+    CallHierarchyPanelSettings, HierarchyPanelSettings, TypeHierarchyPanelSettings,
+};
+pub use type_hierarchy_panel::TypeHierarchyPanel;
+
+pub fn init(cx: &mut App) {
+    CallHierarchyPanelSettings::register(cx);
+    TypeHierarchyPanelSettings::register(cx);
+
+    cx.observe_new(|workspace: &mut Workspace, window, cx| {
+        call_hierarchy_panel::register(workspace);
+        type_hierarchy_panel::register(workspace);
+
+        let Some(window) = window else {
+            return;
+        };
+        cx.spawn_in(window, async move |workspace, cx| {
+            let call_hierarchy_panel = CallHierarchyPanel::load(workspace.clone(), cx.clone());
+            let type_hierarchy_panel = TypeHierarchyPanel::load(workspace.clone(), cx.clone());
+            let (call_hierarchy_panel, type_hierarchy_panel) =
+                futures::try_join!(call_hierarchy_panel, type_hierarchy_panel)?;
+            workspace.update_in(cx, |workspace, window, cx| {
+                workspace.add_panel(call_hierarchy_panel, window, cx);
+                workspace.add_panel(type_hierarchy_panel, window, cx);
+            })
+        })
+        .detach_and_log_err(cx);
+    })
+    .detach();
+}
@@ -0,0 +1,43 @@
+use gpui::{App, Pixels};
+use settings::{PanelSettingsContent, Settings, SettingsContent};
+use ui::px;
+use workspace::dock::DockPosition;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HierarchyPanelSettings {
+    pub button: bool,
+    pub dock: DockPosition,
+    pub default_width: Pixels,
+}
+
+impl HierarchyPanelSettings {
+    fn from_content(panel: &PanelSettingsContent) -> Self {
+        Self {
+            button: panel.button.unwrap(),
+            dock: panel.dock.unwrap().into(),
+            default_width: panel.default_width.map(px).unwrap(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct CallHierarchyPanelSettings(pub HierarchyPanelSettings);
+
+#[derive(Debug)]
+pub struct TypeHierarchyPanelSettings(pub HierarchyPanelSettings);
+
+impl Settings for CallHierarchyPanelSettings {
+    fn from_settings(content: &SettingsContent, _cx: &mut App) -> Self {
+        Self(HierarchyPanelSettings::from_content(
+            content.call_hierarchy_panel.as_ref().unwrap(),
+        ))
+    }
+}
+
+impl Settings for TypeHierarchyPanelSettings {
+    fn from_settings(content: &SettingsContent, _cx: &mut App) -> Self {
+        Self(HierarchyPanelSettings::from_content(
+            content.type_hierarchy_panel.as_ref().unwrap(),
+        ))
+    }
+}
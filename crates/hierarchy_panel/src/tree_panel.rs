@@ -0,0 +1,511 @@
+use std::{ops::Range, sync::Arc};
+
+use anyhow::Result;
+use db::kvp::KEY_VALUE_STORE;
+use editor::{Editor, SelectionEffects, scroll::Autoscroll};
+use fs::Fs;
+use gpui::{
+    Action, App, AsyncWindowContext, Entity, EventEmitter, FocusHandle, Focusable, Pixels, Task,
+    WeakEntity, px,
+};
+use language::{Anchor, Buffer, OffsetRangeExt as _};
+use project::{CallHierarchyItem, Location, Project};
+use serde::{Deserialize, Serialize};
+use settings::{PanelSettingsContent, SettingsContent};
+use ui::{ListItem, ListItemSpacing, Tooltip, prelude::*};
+use util::ResultExt as _;
+use workspace::{
+    Workspace,
+    dock::{DockPosition, Panel, PanelEvent},
+};
+
+use crate::hierarchy_panel_settings::HierarchyPanelSettings;
+
+/// A hierarchy of symbols shown in a [`HierarchyPanel`], e.g. the callers of a function.
+pub trait Hierarchy: 'static {
+    /// Which relatives of its items the tree lists.
+    type Direction: Copy + PartialEq + 'static;
+    /// Toggles focus on the panel.
+    type ToggleFocus: Action + Default;
+    /// Switches the panel between its two directions.
+    type ToggleDirection: Action + Default;
+
+    /// The name the panel state is persisted under.
+    const PANEL_NAME: &'static str;
+    const KEY_CONTEXT: &'static str;
+    const INITIAL_DIRECTION: Self::Direction;
+    const ICON_TOOLTIP: &'static str;
+    const TOGGLE_DIRECTION_TOOLTIP: &'static str;
+    const EMPTY_MESSAGE: &'static str;
+    const ACTIVATION_PRIORITY: u32;
+
+    fn title(direction: Self::Direction) -> &'static str;
+
+    fn toggled(direction: Self::Direction) -> Self::Direction;
+
+    /// Queries the language server for the relatives of the item in the given direction.
+    fn relatives(
+        project: &mut Project,
+        item: &CallHierarchyItem,
+        direction: Self::Direction,
+        cx: &mut Context<Project>,
+    ) -> Task<Result<Vec<HierarchyRelative>>>;
+
+    /// Describes the number of locations relating a node to its parent, shown when there are several.
+    fn locations_label(count: usize) -> String;
+
+    fn settings(cx: &App) -> HierarchyPanelSettings;
+
+    fn settings_content(settings: &mut SettingsContent) -> &mut Option<PanelSettingsContent>;
+}
+
+/// A relative of a hierarchy item, with the locations relating the two, e.g. the call sites
+/// between a function and its caller.
+pub struct HierarchyRelative {
+    pub item: CallHierarchyItem,
+    pub locations: Vec<Location>,
+}
+
+struct HierarchyNode {
+    item: CallHierarchyItem,
+    /// The locations relating this node to its parent, empty for the roots of the tree.
+    locations: Vec<Location>,
+    /// The location to jump to on the next click, so that repeated clicks cycle through them.
+    next_location: usize,
+    depth: usize,
+    expanded: bool,
+    children: HierarchyNodeChildren,
+}
+
+enum HierarchyNodeChildren {
+    NotLoaded,
+    Loading(Task<()>),
+    Loaded(Vec<usize>),
+}
+
+impl HierarchyNode {
+    fn new(item: CallHierarchyItem, locations: Vec<Location>, depth: usize) -> Self {
+        Self {
+            item,
+            locations,
+            next_location: 0,
+            depth,
+            expanded: false,
+            children: HierarchyNodeChildren::NotLoaded,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct SerializedHierarchyPanel {
+    width: Option<Pixels>,
+}
+
+/// Shows a [`Hierarchy`] as a tree, whose nodes are queried from the language server as they
+/// get expanded.
+pub struct HierarchyPanel<H: Hierarchy> {
+    project: Entity<Project>,
+    workspace: WeakEntity<Workspace>,
+    fs: Arc<dyn Fs>,
+    direction: H::Direction,
+    nodes: Vec<HierarchyNode>,
+    roots: Vec<usize>,
+    pending_items: Option<Task<()>>,
+    focus_handle: FocusHandle,
+    width: Option<Pixels>,
+    pending_serialization: Task<Option<()>>,
+}
+
+impl<H: Hierarchy> HierarchyPanel<H> {
+    pub fn new(workspace: &Workspace, cx: &mut Context<Self>) -> Self {
+        Self {
+            project: workspace.project().clone(),
+            workspace: workspace.weak_handle(),
+            fs: workspace.app_state().fs.clone(),
+            direction: H::INITIAL_DIRECTION,
+            nodes: Vec::new(),
+            roots: Vec::new(),
+            pending_items: None,
+            focus_handle: cx.focus_handle(),
+            width: None,
+            pending_serialization: Task::ready(None),
+        }
+    }
+
+    pub fn load(
+        workspace: WeakEntity<Workspace>,
+        cx: AsyncWindowContext,
+    ) -> Task<Result<Entity<Self>>> {
+        cx.spawn(async move |cx| {
+            let serialized_panel = if let Some(panel) = cx
+                .background_spawn(async move { KEY_VALUE_STORE.read_kvp(H::PANEL_NAME) })
+                .await
+                .log_err()
+                .flatten()
+            {
+                Some(serde_json::from_str::<SerializedHierarchyPanel>(&panel)?)
+            } else {
+                None
+            };
+
+            workspace.update(cx, |workspace, cx| {
+                let panel = cx.new(|cx| Self::new(workspace, cx));
+                if let Some(serialized_panel) = serialized_panel {
+                    panel.update(cx, |panel, cx| {
+                        panel.width = serialized_panel.width.map(|width| width.round());
+                        cx.notify();
+                    });
+                }
+                panel
+            })
+        })
+    }
+
+    fn serialize(&mut self, cx: &mut Context<Self>) {
+        let width = self.width;
+        self.pending_serialization = cx.background_spawn(
+            async move {
+                KEY_VALUE_STORE
+                    .write_kvp(
+                        H::PANEL_NAME.into(),
+                        serde_json::to_string(&SerializedHierarchyPanel { width })?,
+                    )
+                    .await?;
+                anyhow::Ok(())
+            }
+            .log_err(),
+        );
+    }
+
+    /// Replaces the tree with the given items, once the language server returns them.
+    pub fn show_items(
+        &mut self,
+        items: Task<Result<Vec<CallHierarchyItem>>>,
+        direction: H::Direction,
+        cx: &mut Context<Self>,
+    ) {
+        self.direction = direction;
+        self.nodes.clear();
+        self.roots.clear();
+        self.pending_items = Some(cx.spawn(async move |this, cx| {
+            let items = items.await.log_err().unwrap_or_default();
+            this.update(cx, |this, cx| {
+                this.pending_items = None;
+                this.set_roots(items, cx);
+            })
+            .ok();
+        }));
+        cx.notify();
+    }
+
+    fn set_roots(&mut self, items: Vec<CallHierarchyItem>, cx: &mut Context<Self>) {
+        self.nodes = items
+            .into_iter()
+            .map(|item| HierarchyNode::new(item, Vec::new(), 0))
+            .collect();
+        self.roots = (0..self.nodes.len()).collect();
+        for ix in 0..self.roots.len() {
+            self.toggle_expanded(ix, cx);
+        }
+        cx.notify();
+    }
+
+    fn toggle_direction(&mut self, cx: &mut Context<Self>) {
+        self.direction = H::toggled(self.direction);
+        let items = self
+            .roots
+            .iter()
+            .map(|ix| self.nodes[*ix].item.clone())
+            .collect();
+        self.set_roots(items, cx);
+    }
+
+    fn toggle_expanded(&mut self, ix: usize, cx: &mut Context<Self>) {
+        let node = &mut self.nodes[ix];
+        node.expanded = !node.expanded;
+        if node.expanded && matches!(node.children, HierarchyNodeChildren::NotLoaded) {
+            let item = node.item.clone();
+            let direction = self.direction;
+            let relatives = self.project.update(cx, |project, cx| {
+                H::relatives(project, &item, direction, cx)
+            });
+            // Dropping the nodes, when the tree is replaced, cancels the queries of their children.
+            self.nodes[ix].children =
+                HierarchyNodeChildren::Loading(cx.spawn(async move |this, cx| {
+                    let relatives = relatives.await;
+                    this.update(cx, |this, cx| this.insert_children(ix, relatives, cx))
+                        .ok();
+                }));
+        }
+        cx.notify();
+    }
+
+    fn insert_children(
+        &mut self,
+        parent: usize,
+        relatives: Result<Vec<HierarchyRelative>>,
+        cx: &mut Context<Self>,
+    ) {
+        let depth = self.nodes[parent].depth + 1;
+        let children = relatives
+            .log_err()
+            .unwrap_or_default()
+            .into_iter()
+            .map(|relative| {
+                self.nodes
+                    .push(HierarchyNode::new(relative.item, relative.locations, depth));
+                self.nodes.len() - 1
+            })
+            .collect();
+        self.nodes[parent].children = HierarchyNodeChildren::Loaded(children);
+        cx.notify();
+    }
+
+    /// Jumps to the next location relating the node to its parent, or to the item itself if
+    /// there are none.
+    fn open_node(&mut self, ix: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let node = &mut self.nodes[ix];
+        let (buffer, range) = if node.locations.is_empty() {
+            (node.item.buffer.clone(), node.item.selection_range.clone())
+        } else {
+            let location = &node.locations[node.next_location % node.locations.len()];
+            node.next_location += 1;
+            (location.buffer.clone(), location.range.clone())
+        };
+        self.open_location(buffer, range, window, cx);
+    }
+
+    fn open_item(&self, ix: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let item = &self.nodes[ix].item;
+        self.open_location(
+            item.buffer.clone(),
+            item.selection_range.clone(),
+            window,
+            cx,
+        );
+    }
+
+    fn open_location(
+        &self,
+        buffer: Entity<Buffer>,
+        range: Range<Anchor>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.workspace
+            .update(cx, |workspace, cx| {
+                let range = range.to_point(&buffer.read(cx).snapshot());
+                let pane = workspace.active_pane().clone();
+                let editor =
+                    workspace.open_project_item::<Editor>(pane, buffer, true, true, window, cx);
+                editor.update(cx, |editor, cx| {
+                    editor.change_selections(
+                        SelectionEffects::scroll(Autoscroll::center()),
+                        window,
+                        cx,
+                        |selections| selections.select_ranges([range]),
+                    );
+                });
+            })
+            .ok();
+    }
+
+    fn visible_nodes(&self) -> Vec<usize> {
+        let mut visible_nodes = Vec::new();
+        let mut stack = self.roots.iter().rev().copied().collect::<Vec<_>>();
+        while let Some(ix) = stack.pop() {
+            visible_nodes.push(ix);
+            let node = &self.nodes[ix];
+            if node.expanded
+                && let HierarchyNodeChildren::Loaded(children) = &node.children
+            {
+                stack.extend(children.iter().rev().copied());
+            }
+        }
+        visible_nodes
+    }
+
+    fn render_node(&self, ix: usize, cx: &mut Context<Self>) -> impl IntoElement {
+        let node = &self.nodes[ix];
+        let toggle = match &node.children {
+            HierarchyNodeChildren::Loaded(children) if children.is_empty() => None,
+            _ => Some(node.expanded),
+        };
+        let location_count = node.locations.len();
+
+        v_flex()
+            .child(
+                ListItem::new(("hierarchy-node", ix))
+                    .spacing(ListItemSpacing::Sparse)
+                    .indent_level(node.depth)
+                    .indent_step_size(px(12.))
+                    .toggle(toggle)
+                    .on_toggle(cx.listener(move |this, _, _, cx| this.toggle_expanded(ix, cx)))
+                    .child(
+                        h_flex()
+                            .w_full()
+                            .gap_2()
+                            .child(Label::new(node.item.name.clone()).truncate())
+                            .when_some(node.item.detail.clone(), |this, detail| {
+                                this.child(
+                                    Label::new(detail)
+                                        .size(LabelSize::Small)
+                                        .color(Color::Muted)
+                                        .truncate(),
+                                )
+                            })
+                            .when(location_count > 1, |this| {
+                                this.child(
+                                    Label::new(H::locations_label(location_count))
+                                        .size(LabelSize::Small)
+                                        .color(Color::Muted),
+                                )
+                            }),
+                    )
+                    // Clicking a node with locations jumps to them, so its item is opened separately.
+                    .when(location_count > 0, |this| {
+                        this.end_hover_slot(
+                            IconButton::new(("open-hierarchy-item", ix), IconName::ArrowUpRight)
+                                .icon_size(IconSize::Small)
+                                .tooltip(Tooltip::text("Go to Definition"))
+                                .on_click(cx.listener(move |this, _, window, cx| {
+                                    this.open_item(ix, window, cx)
+                                })),
+                        )
+                    })
+                    .on_click(
+                        cx.listener(move |this, _, window, cx| this.open_node(ix, window, cx)),
+                    ),
+            )
+            .when(
+                node.expanded && matches!(node.children, HierarchyNodeChildren::Loading(_)),
+                |this| {
+                    this.child(
+                        ListItem::new(("hierarchy-loading", ix))
+                            .spacing(ListItemSpacing::Sparse)
+                            .indent_level(node.depth + 1)
+                            .indent_step_size(px(12.))
+                            .selectable(false)
+                            .child(
+                                Label::new("Loading…")
+                                    .size(LabelSize::Small)
+                                    .color(Color::Muted),
+                            ),
+                    )
+                },
+            )
+    }
+}
+
+impl<H: Hierarchy> Render for HierarchyPanel<H> {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let visible_nodes = self.visible_nodes();
+
+        v_flex()
+            .id("hierarchy-panel")
+            .key_context(H::KEY_CONTEXT)
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(|this, _: &H::ToggleDirection, _, cx| this.toggle_direction(cx)))
+            .size_full()
+            .child(
+                h_flex()
+                    .px_2()
+                    .py_1()
+                    .justify_between()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border)
+                    .child(Label::new(H::title(self.direction)).size(LabelSize::Small))
+                    .child(
+                        IconButton::new("toggle-hierarchy-direction", IconName::ArrowRightLeft)
+                            .icon_size(IconSize::Small)
+                            .disabled(self.roots.is_empty())
+                            .tooltip(Tooltip::for_action_title(
+                                H::TOGGLE_DIRECTION_TOOLTIP,
+                                &H::ToggleDirection::default(),
+                            ))
+                            .on_click(|_, window, cx| {
+                                window.dispatch_action(Box::new(H::ToggleDirection::default()), cx)
+                            }),
+                    ),
+            )
+            .map(|this| {
+                if visible_nodes.is_empty() {
+                    let message = if self.pending_items.is_some() {
+                        "Loading…"
+                    } else {
+                        H::EMPTY_MESSAGE
+                    };
+                    this.child(
+                        h_flex().size_full().justify_center().child(
+                            Label::new(message)
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        ),
+                    )
+                } else {
+                    this.child(
+                        v_flex()
+                            .id("hierarchy-nodes")
+                            .p_1()
+                            .size_full()
+                            .overflow_y_scroll()
+                            .children(visible_nodes.into_iter().map(|ix| self.render_node(ix, cx))),
+                    )
+                }
+            })
+    }
+}
+
+impl<H: Hierarchy> Focusable for HierarchyPanel<H> {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl<H: Hierarchy> EventEmitter<PanelEvent> for HierarchyPanel<H> {}
+
+impl<H: Hierarchy> Panel for HierarchyPanel<H> {
+    fn persistent_name() -> &'static str {
+        H::PANEL_NAME
+    }
+
+    fn position(&self, _: &Window, cx: &App) -> DockPosition {
+        H::settings(cx).dock
+    }
+
+    fn position_is_valid(&self, _: DockPosition) -> bool {
+        true
+    }
+
+    fn set_position(&mut self, position: DockPosition, _: &mut Window, cx: &mut Context<Self>) {
+        settings::update_settings_file(self.fs.clone(), cx, move |settings, _| {
+            H::settings_content(settings).get_or_insert_default().dock = Some(position.into())
+        });
+    }
+
+    fn size(&self, _: &Window, cx: &App) -> Pixels {
+        self.width.unwrap_or_else(|| H::settings(cx).default_width)
+    }
+
+    fn set_size(&mut self, size: Option<Pixels>, _: &mut Window, cx: &mut Context<Self>) {
+        self.width = size;
+        self.serialize(cx);
+        cx.notify();
+    }
+
+    fn icon(&self, _: &Window, cx: &App) -> Option<IconName> {
+        H::settings(cx).button.then_some(IconName::ListTree)
+    }
+
+    fn icon_tooltip(&self, _: &Window, _: &App) -> Option<&'static str> {
+        Some(H::ICON_TOOLTIP)
+    }
+
+    fn toggle_action(&self) -> Box<dyn Action> {
+        Box::new(H::ToggleFocus::default())
+    }
+
+    fn activation_priority(&self) -> u32 {
+        H::ACTIVATION_PRIORITY
+    }
+}
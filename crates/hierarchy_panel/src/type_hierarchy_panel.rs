@@ -0,0 +1,126 @@
+use anyhow::Result;
+use editor::Editor;
+use gpui::{App, Task, actions};
+use project::{Project, TypeHierarchyDirection, TypeHierarchyItem};
+use settings::{PanelSettingsContent, Settings as _, SettingsContent};
+use ui::prelude::*;
+use workspace::Workspace;
+
+use crate::{
+    hierarchy_panel_settings::{HierarchyPanelSettings, TypeHierarchyPanelSettings},
+    tree_panel::{Hierarchy, HierarchyPanel, HierarchyRelative},
+};
+
+actions!(
+    type_hierarchy,
+    [
+        /// Toggles focus on the type hierarchy panel.
+        ToggleFocus,
+        /// Shows the supertypes of the type under the cursor in the type hierarchy panel.
+        ShowSupertypes,
+        /// Shows the subtypes of the type under the cursor in the type hierarchy panel.
+        ShowSubtypes,
+        /// Switches the type hierarchy panel between showing supertypes and subtypes.
+        ToggleDirection,
+    ]
+);
+
+pub(crate) fn register(workspace: &mut Workspace) {
+    workspace.register_action(|workspace, _: &ToggleFocus, window, cx| {
+        workspace.toggle_panel_focus::<TypeHierarchyPanel>(window, cx);
+    });
+    workspace.register_action(|workspace, _: &ShowSupertypes, window, cx| {
+        show_type_hierarchy(workspace, TypeHierarchyDirection::Supertypes, window, cx);
+    });
+    workspace.register_action(|workspace, _: &ShowSubtypes, window, cx| {
+        show_type_hierarchy(workspace, TypeHierarchyDirection::Subtypes, window, cx);
+    });
+}
+
+fn show_type_hierarchy(
+    workspace: &mut Workspace,
+    direction: TypeHierarchyDirection,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let Some(editor) = workspace.active_item_as::<Editor>(cx) else {
+        return;
+    };
+    let Some((buffer, position)) = ({
+        let editor = editor.read(cx);
+        let head = editor.selections.newest_anchor().head();
+        editor.buffer().read(cx).text_anchor_for_position(head, cx)
+    }) else {
+        return;
+    };
+    let items = workspace.project().update(cx, |project, cx| {
+        project.prepare_type_hierarchy(&buffer, position, cx)
+    });
+    if let Some(panel) = workspace.focus_panel::<TypeHierarchyPanel>(window, cx) {
+        panel.update(cx, |panel, cx| panel.show_items(items, direction, cx));
+    }
+}
+
+/// Shows the supertypes or subtypes of a type as a tree.
+pub type TypeHierarchyPanel = HierarchyPanel<TypeHierarchy>;
+
+pub enum TypeHierarchy {}
+
+impl Hierarchy for TypeHierarchy {
+    type Direction = TypeHierarchyDirection;
+    type ToggleFocus = ToggleFocus;
+    type ToggleDirection = ToggleDirection;
+
+    const PANEL_NAME: &'static str = "TypeHierarchyPanel";
+    const KEY_CONTEXT: &'static str = "TypeHierarchyPanel";
+    const INITIAL_DIRECTION: TypeHierarchyDirection = TypeHierarchyDirection::Supertypes;
+    const ICON_TOOLTIP: &'static str = "Type Hierarchy";
+    const TOGGLE_DIRECTION_TOOLTIP: &'static str = "Toggle Supertypes and Subtypes";
+    const EMPTY_MESSAGE: &'static str = "No type hierarchy for the symbol under the cursor";
+    const ACTIVATION_PRIORITY: u32 = 11;
+
+    fn title(direction: TypeHierarchyDirection) -> &'static str {
+        match direction {
+            TypeHierarchyDirection::Supertypes => "Supertypes",
+            TypeHierarchyDirection::Subtypes => "Subtypes",
+        }
+    }
+
+    fn toggled(direction: TypeHierarchyDirection) -> TypeHierarchyDirection {
+        match direction {
+            TypeHierarchyDirection::Supertypes => TypeHierarchyDirection::Subtypes,
+            TypeHierarchyDirection::Subtypes => TypeHierarchyDirection::Supertypes,
+        }
+    }
+
+    fn relatives(
+        project: &mut Project,
+        item: &TypeHierarchyItem,
+        direction: TypeHierarchyDirection,
+        cx: &mut Context<Project>,
+    ) -> Task<Result<Vec<HierarchyRelative>>> {
+        let related_types = project.related_types(item, direction, cx);
+        cx.background_spawn(async move {
+            Ok(related_types
+                .await?
+                .into_iter()
+                .map(|item| HierarchyRelative {
+                    item,
+                    locations: Vec::new(),
+                })
+                .collect())
+        })
+    }
+
+    fn locations_label(count: usize) -> String {
+        format!("{count} references")
+    }
+
+    fn settings(cx: &App) -> HierarchyPanelSettings {
+        TypeHierarchyPanelSettings::get_global(cx).0
+    }
+
+    fn settings_content(settings: &mut SettingsContent) -> &mut Option<PanelSettingsContent> {
+        &mut settings.type_hierarchy_panel
+    }
+}
@@ -1,30 +1,145 @@
-use std::path::Path;
+use std::collections::BinaryHeap;
+use std::path::{Path, PathBuf};
+use std::slice;
 use std::sync::Arc;
 
-use anyhow::Result;
+use anyhow::{Context as _, Result};
+use collections::{HashMap, HashSet};
+use futures::{AsyncReadExt as _, StreamExt as _, executor};
 use gpui::SharedString;
+use jj_lib::backend::{CommitId, Timestamp, TreeValue};
+use jj_lib::commit::Commit;
 use jj_lib::config::StackedConfig;
-use jj_lib::repo::StoreFactories;
+use jj_lib::conflicts::ConflictMarkerStyle;
+use jj_lib::fsmonitor::FsmonitorSettings;
+use jj_lib::git::{self, REMOTE_NAME_FOR_LOCAL_GIT_REPO};
+use jj_lib::gitignore::GitIgnoreFile;
+use jj_lib::hex_util::to_forward_hex;
+use jj_lib::matchers::EverythingMatcher;
+use jj_lib::object_id::{HexPrefix, ObjectId as _, PrefixResolution};
+use jj_lib::op_walk;
+use jj_lib::ref_name::{WorkspaceName, WorkspaceNameBuf};
+use jj_lib::repo::{MutableRepo, ReadonlyRepo, Repo, StoreFactories};
+use jj_lib::repo_path::RepoPathBuf;
 use jj_lib::settings::UserSettings;
-use jj_lib::workspace::{self, DefaultWorkspaceLoaderFactory, WorkspaceLoaderFactory};
+use jj_lib::working_copy::{CheckoutOptions, SnapshotOptions};
+use jj_lib::workspace::{self, DefaultWorkspaceLoaderFactory, Workspace, WorkspaceLoaderFactory};
+use parking_lot::Mutex;
+use time::OffsetDateTime;
+use time_format::{TimestampFormat, format_local_timestamp};
 
 #[derive(Debug, Clone)]
 pub struct Bookmark {
     pub ref_name: SharedString,
 }
 
+/// A change (a commit with a stable change ID) from the change log.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Change {
+    pub change_id: SharedString,
+    pub commit_id: SharedString,
+    pub description: SharedString,
+    pub author: SharedString,
+    /// A human-readable time of the change authoring, e.g. `2 hours ago`.
+    pub timestamp: SharedString,
+    pub bookmarks: Vec<SharedString>,
+    pub is_working_copy: bool,
+    pub is_empty: bool,
+    pub is_immutable: bool,
+    pub has_conflict: bool,
+}
+
+/// An entry of the operation log.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Operation {
+    pub id: SharedString,
+    pub description: SharedString,
+    pub user: SharedString,
+    /// A human-readable time of the operation, e.g. `2 hours ago`.
+    pub timestamp: SharedString,
+    pub is_current: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileChangeKind {
+    Added,
+    Modified,
+    Removed,
+}
+
+/// A file changed in a revision, compared to its parent.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileChange {
+    /// The path of the file, relative to the workspace root.
+    pub path: PathBuf,
+    pub kind: FileChangeKind,
+}
+
+/// The contents of a file changed in the working copy, before and after the change.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileDiff {
+    pub change: FileChange,
+    /// The text of the file in the parent change, or `None` if the file was added.
+    pub old_text: Option<String>,
+    /// The text of the file in the working copy, or `None` if the file was removed.
+    pub new_text: Option<String>,
+}
+
 pub trait JujutsuRepository: Send + Sync {
+    fn workspace_root(&self) -> &Path;
+
     fn list_bookmarks(&self) -> Vec<Bookmark>;
+
+    /// Lists the revision and its ancestors, or the visible changes if no revision is given,
+    /// newest first.
+    ///
+    /// A revision is either `@`, the working-copy change, or a change ID (or its unique prefix),
+    /// optionally followed by `-` to refer to the parent of the change.
+    fn change_log(&self, revision: Option<&str>, limit: usize) -> Result<Vec<Change>>;
+
+    /// Lists the latest operations, newest first.
+    fn operation_log(&self, limit: usize) -> Result<Vec<Operation>>;
+
+    /// Lists the files changed in the revision, compared to its parent.
+    fn changed_files(&self, revision: &str) -> Result<Vec<FileChange>>;
+
+    /// Returns the contents of the file at the revision.
+    fn file_text(&self, revision: &str, path: &Path) -> Result<String>;
+
+    /// Lists the files with unresolved conflicts at the revision.
+    fn conflicted_files(&self, revision: &str) -> Result<Vec<PathBuf>>;
+
+    fn describe(&self, revision: &str, message: &str) -> Result<()>;
+
+    /// Creates a new empty change on top of the revision and makes it the working copy.
+    fn new_change(&self, parent: &str) -> Result<()>;
+
+    /// Moves the changes of the revision into its parent, describing the result with the message.
+    fn squash(&self, revision: &str, message: &str) -> Result<()>;
+
+    fn abandon(&self, revision: &str) -> Result<()>;
 }
 
+/// The number of hex digits shown of the change and commit IDs.
+const SHORT_ID_LENGTH: usize = 12;
+/// The largest new file added to the working-copy change when snapshotting, as in `jj`.
+const MAX_NEW_FILE_SIZE: u64 = 1024 * 1024;
+/// The bookmarks `trunk()` resolves to, whose remote bookmarks are immutable.
+const TRUNK_BOOKMARKS: [&str; 3] = ["main", "master", "trunk"];
+
 pub struct RealJujutsuRepository {
-    repository: Arc<jj_lib::repo::ReadonlyRepo>,
+    workspace: Mutex<Workspace>,
+    /// The repository as of its latest operation, reloaded after each operation.
+    repository: Mutex<Arc<ReadonlyRepo>>,
+    settings: UserSettings,
+    workspace_root: PathBuf,
 }
 
 impl RealJujutsuRepository {
     pub fn new(cwd: &Path) -> Result<Self> {
+        let workspace_root = Self::find_workspace_dir(cwd);
         let workspace_loader_factory = DefaultWorkspaceLoaderFactory;
-        let workspace_loader = workspace_loader_factory.create(Self::find_workspace_dir(cwd))?;
+        let workspace_loader = workspace_loader_factory.create(workspace_root)?;
 
         let config = StackedConfig::with_defaults();
         let settings = UserSettings::from_config(config)?;
@@ -38,7 +153,12 @@ impl RealJujutsuRepository {
         let repo_loader = workspace.repo_loader();
         let repository = repo_loader.load_at_head()?;
 
-        Ok(Self { repository })
+        Ok(Self {
+            workspace: Mutex::new(workspace),
+            repository: Mutex::new(repository),
+            settings,
+            workspace_root: workspace_root.to_path_buf(),
+        })
     }
 
     fn find_workspace_dir(cwd: &Path) -> &Path {
@@ -46,11 +166,68 @@ impl RealJujutsuRepository {
             .find(|path| path.join(".jj").is_dir())
             .unwrap_or(cwd)
     }
+
+    fn repository(&self) -> Arc<ReadonlyRepo> {
+        self.repository.lock().clone()
+    }
+
+    fn workspace_name(&self) -> WorkspaceNameBuf {
+        self.workspace.lock().workspace_name().to_owned()
+    }
+
+    /// Reloads the repository at its latest operation, which may have been made by another process.
+    fn reload(&self) -> Result<Arc<ReadonlyRepo>> {
+        let repository = self.workspace.lock().repo_loader().load_at_head()?;
+        *self.repository.lock() = repository.clone();
+        Ok(repository)
+    }
+
+    /// Modifies the repository in a transaction, the way `jj` commands do: the working copy is
+    /// snapshotted before the modification, and updated to the new working-copy change after it.
+    ///
+    /// In a workspace colocated with a Git repository, the Git refs and `HEAD` are imported
+    /// before the modification and exported after it, so that `git` sees the changes.
+    fn transact(
+        &self,
+        description: &str,
+        modify: impl FnOnce(&mut MutableRepo, &WorkspaceName) -> Result<()>,
+    ) -> Result<()> {
+        let mut workspace = self.workspace.lock();
+        let repository = snapshot_working_copy(&mut workspace)?;
+        let colocated = is_colocated(repository.as_ref(), &self.workspace_root);
+        let mut transaction = repository.start_transaction();
+        if colocated {
+            git::import_head(transaction.repo_mut())?;
+            git::import_refs(transaction.repo_mut(), &self.settings.git_settings()?)?;
+        }
+        modify(transaction.repo_mut(), workspace.workspace_name())?;
+        transaction.repo_mut().rebase_descendants()?;
+        if colocated {
+            git::export_refs(transaction.repo_mut())?;
+            let working_copy_id = transaction
+                .repo()
+                .view()
+                .get_wc_commit_id(workspace.workspace_name())
+                .cloned();
+            if let Some(working_copy_id) = working_copy_id {
+                let working_copy = transaction.repo().store().get_commit(&working_copy_id)?;
+                git::reset_head(transaction.repo_mut(), &working_copy)?;
+            }
+        }
+        let repository = transaction.commit(description)?;
+        update_working_copy(&mut workspace, &repository)?;
+        *self.repository.lock() = repository;
+        Ok(())
+    }
 }
 
 impl JujutsuRepository for RealJujutsuRepository {
+    fn workspace_root(&self) -> &Path {
+        &self.workspace_root
+    }
+
     fn list_bookmarks(&self) -> Vec<Bookmark> {
-        self.repository
+        self.repository()
             .view()
             .bookmarks()
             .map(|(ref_name, _target)| Bookmark {
@@ -58,12 +235,735 @@ impl JujutsuRepository for RealJujutsuRepository {
             })
             .collect()
     }
+
+    fn change_log(&self, revision: Option<&str>, limit: usize) -> Result<Vec<Change>> {
+        let repository = self.reload()?;
+        let repository = repository.as_ref();
+        let workspace_name = self.workspace_name();
+        let heads = match revision {
+            Some(revision) => vec![
+                resolve_revision(repository, &workspace_name, revision)?
+                    .id()
+                    .clone(),
+            ],
+            None => repository.view().heads().iter().cloned().collect(),
+        };
+        let commits = ancestors(repository, heads, limit)?;
+        let working_copy_id = repository.view().get_wc_commit_id(&workspace_name);
+        let immutable_heads = immutable_heads(repository);
+        commits
+            .iter()
+            .map(|commit| {
+                Ok(Change {
+                    change_id: short_id(&commit.change_id().reverse_hex()),
+                    commit_id: short_id(&commit.id().hex()),
+                    description: commit.description().trim_end().to_string().into(),
+                    author: commit.author().name.clone().into(),
+                    timestamp: format_timestamp(&commit.author().timestamp),
+                    bookmarks: repository
+                        .view()
+                        .local_bookmarks_for_commit(commit.id())
+                        .map(|(ref_name, _)| ref_name.as_str().to_string().into())
+                        .collect(),
+                    is_working_copy: working_copy_id == Some(commit.id()),
+                    is_empty: commit.is_empty(repository)?,
+                    is_immutable: is_immutable(repository, &immutable_heads, commit.id()),
+                    has_conflict: commit.has_conflict()?,
+                })
+            })
+            .collect()
+    }
+
+    fn operation_log(&self, limit: usize) -> Result<Vec<Operation>> {
+        let repository = self.reload()?;
+        let head = repository.operation();
+        op_walk::walk_ancestors(slice::from_ref(head))
+            .take(limit)
+            .map(|operation| {
+                let operation = operation?;
+                let metadata = operation.metadata();
+                Ok(Operation {
+                    id: short_id(&operation.id().hex()),
+                    description: metadata
+                        .description
+                        .lines()
+                        .next()
+                        .unwrap_or_default()
+                        .to_string()
+                        .into(),
+                    user: format!("{}@{}", metadata.username, metadata.hostname).into(),
+                    timestamp: format_timestamp(&metadata.time.end),
+                    is_current: operation.id() == head.id(),
+                })
+            })
+            .collect()
+    }
+
+    fn changed_files(&self, revision: &str) -> Result<Vec<FileChange>> {
+        let repository = self.repository();
+        let commit = resolve_revision(repository.as_ref(), &self.workspace_name(), revision)?;
+        let parent_tree = commit.parent_tree(repository.as_ref())?;
+        let tree = commit.tree()?;
+        let entries = executor::block_on(
+            parent_tree
+                .diff_stream(&tree, &EverythingMatcher)
+                .collect::<Vec<_>>(),
+        );
+        entries
+            .into_iter()
+            .map(|entry| {
+                let (before, after) = entry.values?;
+                let kind = match (before.is_present(), after.is_present()) {
+                    (false, _) => FileChangeKind::Added,
+                    (_, false) => FileChangeKind::Removed,
+                    (true, true) => FileChangeKind::Modified,
+                };
+                Ok(FileChange {
+                    path: entry.path.to_fs_path_unchecked(Path::new("")),
+                    kind,
+                })
+            })
+            .collect()
+    }
+
+    fn file_text(&self, revision: &str, path: &Path) -> Result<String> {
+        let repository = self.repository();
+        let commit = resolve_revision(repository.as_ref(), &self.workspace_name(), revision)?;
+        let repo_path = RepoPathBuf::from_relative_path(path)?;
+        let value = commit.tree()?.path_value(&repo_path)?;
+        let Some(Some(TreeValue::File { id, .. })) = value.as_resolved() else {
+            anyhow::bail!("{path:?} is not a file without conflicts at {revision}");
+        };
+        let mut content = Vec::new();
+        executor::block_on(async {
+            let mut reader = repository.store().read_file(&repo_path, id).await?;
+            reader.read_to_end(&mut content).await?;
+            anyhow::Ok(())
+        })?;
+        String::from_utf8(content).with_context(|| format!("{path:?} is not valid UTF-8"))
+    }
+
+    fn conflicted_files(&self, revision: &str) -> Result<Vec<PathBuf>> {
+        let repository = self.repository();
+        let commit = resolve_revision(repository.as_ref(), &self.workspace_name(), revision)?;
+        Ok(commit
+            .tree()?
+            .conflicts()
+            .map(|(path, _)| path.to_fs_path_unchecked(Path::new("")))
+            .collect())
+    }
+
+    fn describe(&self, revision: &str, message: &str) -> Result<()> {
+        self.transact("describe commit", |repository, workspace_name| {
+            let commit = resolve_mutable_revision(repository, workspace_name, revision)?;
+            repository
+                .rewrite_commit(&commit)
+                .set_description(complete_newline(message))
+                .write()?;
+            Ok(())
+        })
+    }
+
+    fn new_change(&self, parent: &str) -> Result<()> {
+        self.transact("new empty commit", |repository, workspace_name| {
+            let parent = resolve_revision(&*repository, workspace_name, parent)?;
+            let commit = repository
+                .new_commit(vec![parent.id().clone()], parent.tree_id().clone())
+                .write()?;
+            repository.edit(workspace_name.to_owned(), &commit)?;
+            Ok(())
+        })
+    }
+
+    fn squash(&self, revision: &str, message: &str) -> Result<()> {
+        self.transact("squash commits", |repository, workspace_name| {
+            let commit = resolve_mutable_revision(repository, workspace_name, revision)?;
+            let [parent_id] = commit.parent_ids() else {
+                anyhow::bail!("cannot squash {revision}, as it doesn't have a single parent");
+            };
+            let parent = repository.store().get_commit(parent_id)?;
+            anyhow::ensure!(
+                !is_immutable(&*repository, &immutable_heads(&*repository), parent.id()),
+                "commit {} is immutable",
+                short_id(&parent.id().hex())
+            );
+            // The parent gets all the changes of the commit, whose descendants are moved onto the
+            // parent once it's abandoned.
+            repository
+                .rewrite_commit(&parent)
+                .set_tree_id(commit.tree_id().clone())
+                .set_description(complete_newline(message))
+                .set_predecessors(vec![parent.id().clone(), commit.id().clone()])
+                .write()?;
+            repository.record_abandoned_commit(&commit);
+            Ok(())
+        })
+    }
+
+    fn abandon(&self, revision: &str) -> Result<()> {
+        self.transact("abandon commit", |repository, workspace_name| {
+            let commit = resolve_mutable_revision(repository, workspace_name, revision)?;
+            repository.record_abandoned_commit(&commit);
+            Ok(())
+        })
+    }
+}
+
+/// Resolves a revision, as described in [`JujutsuRepository::change_log`].
+fn resolve_revision(
+    repository: &dyn Repo,
+    workspace_name: &WorkspaceName,
+    revision: &str,
+) -> Result<Commit> {
+    let (change, parent) = match revision.strip_suffix('-') {
+        Some(change) => (change, true),
+        None => (revision, false),
+    };
+    let commit_id = if change == "@" {
+        repository
+            .view()
+            .get_wc_commit_id(workspace_name)
+            .cloned()
+            .context("the workspace has no working-copy change")?
+    } else {
+        let prefix = to_forward_hex(change)
+            .and_then(|hex| HexPrefix::new(&hex))
+            .with_context(|| format!("`{change}` is not a change ID"))?;
+        match repository.resolve_change_id_prefix(&prefix) {
+            PrefixResolution::SingleMatch(commit_ids) => match commit_ids.as_slice() {
+                [commit_id] => commit_id.clone(),
+                _ => anyhow::bail!("change {change} is divergent"),
+            },
+            PrefixResolution::AmbiguousMatch => {
+                anyhow::bail!("change ID prefix {change} is ambiguous")
+            }
+            PrefixResolution::NoMatch => anyhow::bail!("revision `{change}` doesn't exist"),
+        }
+    };
+    let commit = repository.store().get_commit(&commit_id)?;
+    if !parent {
+        return Ok(commit);
+    }
+    let parent_id = commit
+        .parent_ids()
+        .first()
+        .with_context(|| format!("revision `{change}` has no parent"))?;
+    Ok(repository.store().get_commit(parent_id)?)
+}
+
+/// Resolves a revision that's going to be rewritten, which `jj` doesn't allow for immutable ones.
+fn resolve_mutable_revision(
+    repository: &MutableRepo,
+    workspace_name: &WorkspaceName,
+    revision: &str,
+) -> Result<Commit> {
+    let commit = resolve_revision(repository, workspace_name, revision)?;
+    anyhow::ensure!(
+        !is_immutable(repository, &immutable_heads(repository), commit.id()),
+        "commit {} is immutable",
+        short_id(&commit.id().hex())
+    );
+    Ok(commit)
+}
+
+/// Lists the heads and their ancestors, newest first.
+fn ancestors(repository: &dyn Repo, heads: Vec<CommitId>, limit: usize) -> Result<Vec<Commit>> {
+    let store = repository.store();
+    let mut queue = BinaryHeap::new();
+    let mut queued = HashSet::default();
+    for head_id in heads {
+        let head = store.get_commit(&head_id)?;
+        if queued.insert(head_id.clone()) {
+            queue.push((head.committer().timestamp.timestamp, head_id));
+        }
+    }
+
+    let mut commits = Vec::new();
+    while commits.len() < limit {
+        let Some((_, commit_id)) = queue.pop() else {
+            break;
+        };
+        let commit = store.get_commit(&commit_id)?;
+        for parent_id in commit.parent_ids() {
+            if queued.insert(parent_id.clone()) {
+                let parent = store.get_commit(parent_id)?;
+                queue.push((parent.committer().timestamp.timestamp, parent_id.clone()));
+            }
+        }
+        commits.push(commit);
+    }
+    Ok(commits)
+}
+
+/// The commits whose ancestors are immutable by default in `jj`: the root commit, tags and
+/// the remote bookmarks that are either untracked or `trunk()`.
+fn immutable_heads(repository: &dyn Repo) -> Vec<CommitId> {
+    let view = repository.view();
+    let tags = view
+        .tags()
+        .into_iter()
+        .flat_map(|(_, target)| target.added_ids().cloned());
+    let remote_bookmarks = view
+        .all_remote_bookmarks()
+        .filter(|(symbol, remote_ref)| {
+            symbol.remote != REMOTE_NAME_FOR_LOCAL_GIT_REPO
+                && (!remote_ref.is_tracked() || TRUNK_BOOKMARKS.contains(&symbol.name.as_str()))
+        })
+        .flat_map(|(_, remote_ref)| remote_ref.target.added_ids().cloned());
+    tags.chain(remote_bookmarks)
+        .chain([repository.store().root_commit_id().clone()])
+        .collect()
+}
+
+/// Whether the workspace is the working tree of the Git repository backing the jj repository,
+/// as created by `jj git init --colocate`.
+fn is_colocated(repository: &dyn Repo, workspace_root: &Path) -> bool {
+    git::get_git_backend(repository.store()).is_ok_and(|backend| {
+        backend.git_workdir().is_some_and(|git_workdir| {
+            git_workdir.canonicalize().ok() == workspace_root.canonicalize().ok()
+        })
+    })
+}
+
+fn is_immutable(repository: &dyn Repo, immutable_heads: &[CommitId], commit_id: &CommitId) -> bool {
+    let index = repository.index();
+    immutable_heads
+        .iter()
+        .any(|head_id| index.is_ancestor(commit_id, head_id))
+}
+
+/// Snapshots the files of the working copy into the working-copy change, like `jj` does before
+/// each command, returning the repository with the snapshot.
+fn snapshot_working_copy(workspace: &mut Workspace) -> Result<Arc<ReadonlyRepo>> {
+    let repository = workspace.repo_loader().load_at_head()?;
+    let Some(working_copy_id) = repository
+        .view()
+        .get_wc_commit_id(workspace.workspace_name())
+    else {
+        return Ok(repository);
+    };
+    let working_copy = repository.store().get_commit(working_copy_id)?;
+
+    let mut locked_workspace = workspace.start_working_copy_mutation()?;
+    let (tree_id, _) = locked_workspace.locked_wc().snapshot(&SnapshotOptions {
+        base_ignores: GitIgnoreFile::empty(),
+        fsmonitor_settings: FsmonitorSettings::None,
+        progress: None,
+        start_tracking_matcher: &EverythingMatcher,
+        max_new_file_size: MAX_NEW_FILE_SIZE,
+        conflict_marker_style: ConflictMarkerStyle::default(),
+    })?;
+    if tree_id == *working_copy.tree_id() {
+        return Ok(repository);
+    }
+
+    let mut transaction = repository.start_transaction();
+    transaction
+        .repo_mut()
+        .rewrite_commit(&working_copy)
+        .set_tree_id(tree_id)
+        .write()?;
+    transaction.repo_mut().rebase_descendants()?;
+    let repository = transaction.commit("snapshot working copy")?;
+    locked_workspace.finish(repository.op_id().clone())?;
+    Ok(repository)
+}
+
+/// Updates the files of the working copy to the working-copy change of the repository.
+fn update_working_copy(workspace: &mut Workspace, repository: &Arc<ReadonlyRepo>) -> Result<()> {
+    let Some(working_copy_id) = repository
+        .view()
+        .get_wc_commit_id(workspace.workspace_name())
+    else {
+        return Ok(());
+    };
+    let working_copy = repository.store().get_commit(working_copy_id)?;
+    let old_tree_id = workspace.working_copy().tree_id()?.clone();
+    workspace.check_out(
+        repository.op_id().clone(),
+        Some(&old_tree_id),
+        &working_copy,
+        &CheckoutOptions {
+            conflict_marker_style: ConflictMarkerStyle::default(),
+        },
+    )?;
+    Ok(())
+}
+
+fn short_id(id: &str) -> SharedString {
+    id[..id.len().min(SHORT_ID_LENGTH)].to_string().into()
+}
+
+/// Formats the timestamp relative to now, e.g. `2 hours ago`.
+fn format_timestamp(timestamp: &Timestamp) -> SharedString {
+    let time = OffsetDateTime::from_unix_timestamp(timestamp.timestamp.0 / 1000)
+        .unwrap_or_else(|_| OffsetDateTime::now_utc());
+    format_local_timestamp(time, OffsetDateTime::now_utc(), TimestampFormat::Relative).into()
+}
+
+/// Ends a non-empty description with a newline, as `jj` stores them.
+fn complete_newline(description: &str) -> String {
+    let description = description.trim_end();
+    if description.is_empty() {
+        String::new()
+    } else {
+        format!("{description}\n")
+    }
 }
 
-pub struct FakeJujutsuRepository {}
+#[derive(Debug, Clone, Default)]
+pub struct FakeJujutsuRepositoryState {
+    pub bookmarks: Vec<Bookmark>,
+    /// Changes, newest first, each one being the parent of the previous.
+    pub changes: Vec<Change>,
+    pub operations: Vec<Operation>,
+    pub changed_files: HashMap<SharedString, Vec<FileChange>>,
+    pub file_texts: HashMap<(SharedString, PathBuf), String>,
+    pub conflicted_files: HashMap<SharedString, Vec<PathBuf>>,
+    next_id: usize,
+}
+
+pub struct FakeJujutsuRepository {
+    workspace_root: PathBuf,
+    state: Mutex<FakeJujutsuRepositoryState>,
+}
+
+impl FakeJujutsuRepository {
+    pub fn new(workspace_root: impl Into<PathBuf>, state: FakeJujutsuRepositoryState) -> Self {
+        Self {
+            workspace_root: workspace_root.into(),
+            state: Mutex::new(state),
+        }
+    }
+
+    pub fn state(&self) -> FakeJujutsuRepositoryState {
+        self.state.lock().clone()
+    }
+
+    fn with_state<T>(
+        &self,
+        operation: &str,
+        f: impl FnOnce(&mut FakeJujutsuRepositoryState) -> Result<T>,
+    ) -> Result<T> {
+        let mut state = self.state.lock();
+        let result = f(&mut state)?;
+        let id = state.next_id;
+        state.next_id += 1;
+        for operation in &mut state.operations {
+            operation.is_current = false;
+        }
+        state.operations.insert(
+            0,
+            Operation {
+                id: format!("op{id}").into(),
+                description: operation.to_string().into(),
+                user: "test@example.com".into(),
+                timestamp: "now".into(),
+                is_current: true,
+            },
+        );
+        Ok(result)
+    }
+}
+
+impl FakeJujutsuRepositoryState {
+    fn change_index(&self, revision: &str) -> Result<usize> {
+        self.changes
+            .iter()
+            .position(|change| {
+                if revision == "@" {
+                    change.is_working_copy
+                } else {
+                    change.change_id == revision
+                }
+            })
+            .with_context(|| format!("revision `{revision}` doesn't exist"))
+    }
+
+    /// Inserts a new empty working-copy change at the index, on top of the change after it.
+    fn insert_working_copy(&mut self, ix: usize) {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.changes.insert(
+            ix,
+            Change {
+                change_id: format!("change{id}").into(),
+                commit_id: format!("commit{id}").into(),
+                description: SharedString::default(),
+                author: "test".into(),
+                timestamp: "now".into(),
+                bookmarks: Vec::new(),
+                is_working_copy: true,
+                is_empty: true,
+                is_immutable: false,
+                has_conflict: false,
+            },
+        );
+    }
+}
 
 impl JujutsuRepository for FakeJujutsuRepository {
+    fn workspace_root(&self) -> &Path {
+        &self.workspace_root
+    }
+
     fn list_bookmarks(&self) -> Vec<Bookmark> {
-        Vec::new()
+        self.state.lock().bookmarks.clone()
+    }
+
+    fn change_log(&self, revset: Option<&str>, limit: usize) -> Result<Vec<Change>> {
+        let state = self.state.lock();
+        let start = match revset {
+            None => 0,
+            Some(revset) => {
+                let (revision, parent) = match revset.strip_suffix('-') {
+                    Some(revision) => (revision, true),
+                    None => (revset, false),
+                };
+                state.change_index(revision)? + usize::from(parent)
+            }
+        };
+        Ok(state
+            .changes
+            .iter()
+            .skip(start)
+            .take(limit)
+            .cloned()
+            .collect())
+    }
+
+    fn operation_log(&self, limit: usize) -> Result<Vec<Operation>> {
+        Ok(self
+            .state
+            .lock()
+            .operations
+            .iter()
+            .take(limit)
+            .cloned()
+            .collect())
+    }
+
+    fn changed_files(&self, revision: &str) -> Result<Vec<FileChange>> {
+        let state = self.state.lock();
+        let change = &state.changes[state.change_index(revision)?];
+        Ok(state
+            .changed_files
+            .get(&change.change_id)
+            .cloned()
+            .unwrap_or_default())
+    }
+
+    fn file_text(&self, revision: &str, path: &Path) -> Result<String> {
+        let state = self.state.lock();
+        let (revision, parent) = match revision.strip_suffix('-') {
+            Some(revision) => (revision, true),
+            None => (revision, false),
+        };
+        let ix = state.change_index(revision)? + usize::from(parent);
+        let change = state.changes.get(ix).context("revision has no parent")?;
+        state
+            .file_texts
+            .get(&(change.change_id.clone(), path.to_path_buf()))
+            .cloned()
+            .with_context(|| format!("no such path {path:?}"))
+    }
+
+    fn conflicted_files(&self, revision: &str) -> Result<Vec<PathBuf>> {
+        let state = self.state.lock();
+        let change = &state.changes[state.change_index(revision)?];
+        Ok(state
+            .conflicted_files
+            .get(&change.change_id)
+            .cloned()
+            .unwrap_or_default())
+    }
+
+    fn describe(&self, revision: &str, message: &str) -> Result<()> {
+        self.with_state("describe commit", |state| {
+            let ix = state.change_index(revision)?;
+            state.changes[ix].description = message.to_string().into();
+            Ok(())
+        })
+    }
+
+    fn new_change(&self, parent: &str) -> Result<()> {
+        self.with_state("new empty commit", |state| {
+            let mut ix = state.change_index(parent)?;
+            // Like jj, the previous working copy is abandoned if it's empty, undescribed and
+            // not the parent of the new change.
+            if let Ok(working_copy_ix) = state.change_index("@") {
+                let working_copy = &state.changes[working_copy_ix];
+                if working_copy_ix != ix
+                    && working_copy.is_empty
+                    && working_copy.description.is_empty()
+                {
+                    state.changes.remove(working_copy_ix);
+                    if working_copy_ix < ix {
+                        ix -= 1;
+                    }
+                }
+            }
+            for change in &mut state.changes {
+                change.is_working_copy = false;
+            }
+            state.insert_working_copy(ix);
+            Ok(())
+        })
+    }
+
+    fn squash(&self, revision: &str, message: &str) -> Result<()> {
+        self.with_state("squash commits", |state| {
+            let ix = state.change_index(revision)?;
+            anyhow::ensure!(
+                ix + 1 < state.changes.len(),
+                "cannot squash the root commit"
+            );
+            let squashed = state.changes.remove(ix);
+            let parent = &mut state.changes[ix];
+            parent.description = message.to_string().into();
+            parent.is_empty &= squashed.is_empty;
+            let parent_id = parent.change_id.clone();
+            if let Some(files) = state.changed_files.remove(&squashed.change_id) {
+                state
+                    .changed_files
+                    .entry(parent_id)
+                    .or_default()
+                    .extend(files);
+            }
+            // Like jj, squashing the working copy starts a new change on top of its parent.
+            if squashed.is_working_copy {
+                state.insert_working_copy(ix);
+            }
+            Ok(())
+        })
+    }
+
+    fn abandon(&self, revision: &str) -> Result<()> {
+        self.with_state("abandon commit", |state| {
+            let ix = state.change_index(revision)?;
+            anyhow::ensure!(
+                !state.changes[ix].is_immutable,
+                "commit {} is immutable",
+                state.changes[ix].change_id
+            );
+            let abandoned = state.changes.remove(ix);
+            // Like jj, abandoning the working copy starts a new change on top of its parent.
+            if abandoned.is_working_copy {
+                state.insert_working_copy(ix);
+            }
+            Ok(())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_real_repository_operations() {
+        let dir = tempfile::tempdir().unwrap();
+        let settings = UserSettings::from_config(StackedConfig::with_defaults()).unwrap();
+        Workspace::init_simple(&settings, dir.path()).unwrap();
+        let repository = RealJujutsuRepository::new(dir.path()).unwrap();
+        let working_copy = |repository: &RealJujutsuRepository, revision: &str| {
+            repository
+                .change_log(Some(revision), 1)
+                .unwrap()
+                .into_iter()
+                .next()
+                .unwrap()
+        };
+
+        std::fs::write(dir.path().join("a.txt"), "a\n").unwrap();
+        repository.describe("@", "Add a").unwrap();
+        let change = working_copy(&repository, "@");
+        assert!(change.is_working_copy);
+        assert_eq!(change.description, "Add a");
+        assert_eq!(
+            repository.changed_files("@").unwrap(),
+            vec![FileChange {
+                path: "a.txt".into(),
+                kind: FileChangeKind::Added,
+            }],
+            "The working copy should be snapshotted before the operation"
+        );
+
+        repository.new_change(&change.change_id).unwrap();
+        assert!(working_copy(&repository, "@").is_empty);
+        assert_eq!(working_copy(&repository, "@-").change_id, change.change_id);
+        assert_eq!(
+            repository.file_text("@-", Path::new("a.txt")).unwrap(),
+            "a\n"
+        );
+
+        std::fs::write(dir.path().join("b.txt"), "b\n").unwrap();
+        repository.describe("@", "Add b").unwrap();
+        repository.squash("@", "Add a and b").unwrap();
+        let parent = working_copy(&repository, "@-");
+        assert_eq!(parent.change_id, change.change_id);
+        assert_eq!(parent.description, "Add a and b");
+        assert_eq!(repository.changed_files("@-").unwrap().len(), 2);
+        assert!(working_copy(&repository, "@").is_empty);
+        let ancestors = repository.change_log(Some("@"), 2).unwrap();
+        assert_eq!(
+            ancestors.len(),
+            2,
+            "The ancestors should be listed up to the limit"
+        );
+        assert!(ancestors.iter().any(|change| change.is_working_copy));
+        assert!(
+            ancestors
+                .iter()
+                .any(|ancestor| ancestor.change_id == change.change_id)
+        );
+        assert!(
+            repository.abandon("@--").is_err(),
+            "Only a single parent suffix is supported"
+        );
+        assert!(
+            repository
+                .abandon(&working_copy(&repository, "@-").change_id)
+                .is_ok()
+        );
+        assert!(
+            !dir.path().join("b.txt").exists(),
+            "The working copy should be updated"
+        );
+
+        let operations = repository.operation_log(3).unwrap();
+        assert_eq!(operations[0].description, "abandon commit");
+        assert!(operations[0].is_current);
+        assert!(!operations[1].is_current);
+        let log = repository.change_log(None, 10).unwrap();
+        assert!(log.iter().any(|change| change.is_working_copy));
+        assert!(
+            log.last().unwrap().is_immutable,
+            "The root commit is immutable"
+        );
+    }
+
+    #[test]
+    fn test_colocated_repository_operations() {
+        let dir = tempfile::tempdir().unwrap();
+        let settings = UserSettings::from_config(StackedConfig::with_defaults()).unwrap();
+        Workspace::init_colocated_git(&settings, dir.path()).unwrap();
+        let repository = RealJujutsuRepository::new(dir.path()).unwrap();
+
+        std::fs::write(dir.path().join("a.txt"), "a\n").unwrap();
+        repository.describe("@", "Add a").unwrap();
+        let change = repository
+            .change_log(Some("@"), 1)
+            .unwrap()
+            .into_iter()
+            .next()
+            .unwrap();
+        repository.new_change(&change.change_id).unwrap();
+
+        let head = std::fs::read_to_string(dir.path().join(".git").join("HEAD")).unwrap();
+        assert!(
+            head.starts_with(change.commit_id.as_ref()),
+            "Git's HEAD should be exported as the parent of the working copy, got {head:?}"
+        );
     }
 }
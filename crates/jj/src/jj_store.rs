@@ -1,19 +1,52 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
-use gpui::{App, Entity, Global, prelude::*};
+use anyhow::{Context as _, Result};
+use gpui::{App, Entity, EventEmitter, Global, SharedString, Task, prelude::*};
 
-use crate::{JujutsuRepository, RealJujutsuRepository};
+use crate::{
+    Change, FileChange, FileChangeKind, FileDiff, JujutsuRepository, Operation,
+    RealJujutsuRepository,
+};
+
+/// The number of changes to show in the change log.
+const CHANGE_LOG_LIMIT: usize = 50;
+/// The number of operations to show in the operation log.
+const OPERATION_LOG_LIMIT: usize = 20;
 
 /// Note: We won't ultimately be storing the jj store in a global, we're just doing this for exploration purposes.
 struct GlobalJujutsuStore(Entity<JujutsuStore>);
 
 impl Global for GlobalJujutsuStore {}
 
+/// The state of the repository, as of the last refresh.
+#[derive(Debug, Clone, Default)]
+pub struct JujutsuSnapshot {
+    pub changes: Vec<Change>,
+    pub operations: Vec<Operation>,
+    /// Files changed in the working-copy change, compared to its parent.
+    pub working_copy_changes: Vec<FileChange>,
+    /// Files with unresolved conflicts in the working-copy change.
+    pub conflicted_files: Vec<PathBuf>,
+}
+
+impl JujutsuSnapshot {
+    pub fn working_copy(&self) -> Option<&Change> {
+        self.changes.iter().find(|change| change.is_working_copy)
+    }
+}
+
+pub enum JujutsuStoreEvent {
+    SnapshotUpdated,
+}
+
 pub struct JujutsuStore {
     repository: Arc<dyn JujutsuRepository>,
+    snapshot: JujutsuSnapshot,
 }
 
+impl EventEmitter<JujutsuStoreEvent> for JujutsuStore {}
+
 impl JujutsuStore {
     pub fn init_global(cx: &mut App) {
         let Some(repository) = RealJujutsuRepository::new(Path::new(".")).ok() else {
@@ -21,6 +54,7 @@ impl JujutsuStore {
         };
 
         let repository = Arc::new(repository);
+        // The snapshot is loaded once the Jujutsu panel gets opened.
         let jj_store = cx.new(|cx| JujutsuStore::new(repository, cx));
 
         cx.set_global(GlobalJujutsuStore(jj_store));
@@ -32,10 +66,278 @@ impl JujutsuStore {
     }
 
     pub fn new(repository: Arc<dyn JujutsuRepository>, _cx: &mut Context<Self>) -> Self {
-        Self { repository }
+        Self {
+            repository,
+            snapshot: JujutsuSnapshot::default(),
+        }
     }
 
     pub fn repository(&self) -> &Arc<dyn JujutsuRepository> {
         &self.repository
     }
+
+    pub fn snapshot(&self) -> &JujutsuSnapshot {
+        &self.snapshot
+    }
+
+    /// Reloads the change log, the operation log and the working-copy state.
+    pub fn refresh(&mut self, cx: &mut Context<Self>) -> Task<Result<()>> {
+        let repository = self.repository.clone();
+        cx.spawn(async move |this, cx| {
+            let snapshot = cx
+                .background_spawn(async move { load_snapshot(repository.as_ref()) })
+                .await?;
+            this.update(cx, |this, cx| {
+                this.snapshot = snapshot;
+                cx.emit(JujutsuStoreEvent::SnapshotUpdated);
+                cx.notify();
+            })
+        })
+    }
+
+    pub fn describe(
+        &mut self,
+        change_id: SharedString,
+        message: String,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        self.run_command(cx, move |repository| {
+            repository.describe(&change_id, &message)
+        })
+    }
+
+    pub fn new_change(
+        &mut self,
+        parent_id: SharedString,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        self.run_command(cx, move |repository| repository.new_change(&parent_id))
+    }
+
+    /// Squashes the change into its parent, keeping the descriptions of both.
+    pub fn squash(&mut self, change_id: SharedString, cx: &mut Context<Self>) -> Task<Result<()>> {
+        self.run_command(cx, move |repository| {
+            let change = repository
+                .change_log(Some(&change_id), 1)?
+                .into_iter()
+                .next()
+                .with_context(|| format!("change {change_id} not found"))?;
+            let parent = repository
+                .change_log(Some(&format!("{change_id}-")), 1)?
+                .into_iter()
+                .next()
+                .with_context(|| format!("change {change_id} has no parent"))?;
+            let message = [&parent.description, &change.description]
+                .into_iter()
+                .filter(|description| !description.is_empty())
+                .map(|description| description.as_ref())
+                .collect::<Vec<&str>>()
+                .join("\n\n");
+            repository.squash(&change_id, &message)
+        })
+    }
+
+    pub fn abandon(&mut self, change_id: SharedString, cx: &mut Context<Self>) -> Task<Result<()>> {
+        self.run_command(cx, move |repository| repository.abandon(&change_id))
+    }
+
+    /// Computes the diff of the working-copy change against its parent.
+    pub fn working_copy_diff(&self, cx: &App) -> Task<Result<Vec<FileDiff>>> {
+        let repository = self.repository.clone();
+        cx.background_spawn(async move {
+            repository
+                .changed_files("@")?
+                .into_iter()
+                .map(|change| {
+                    let old_text = match change.kind {
+                        FileChangeKind::Added => None,
+                        _ => Some(repository.file_text("@-", &change.path)?),
+                    };
+                    let new_text = match change.kind {
+                        FileChangeKind::Removed => None,
+                        _ => Some(repository.file_text("@", &change.path)?),
+                    };
+                    Ok(FileDiff {
+                        change,
+                        old_text,
+                        new_text,
+                    })
+                })
+                .collect()
+        })
+    }
+
+    /// Runs a command modifying the repository in the background, then refreshes the snapshot.
+    fn run_command(
+        &mut self,
+        cx: &mut Context<Self>,
+        command: impl FnOnce(&dyn JujutsuRepository) -> Result<()> + Send + 'static,
+    ) -> Task<Result<()>> {
+        let repository = self.repository.clone();
+        cx.spawn(async move |this, cx| {
+            cx.background_spawn(async move { command(repository.as_ref()) })
+                .await?;
+            this.update(cx, |this, cx| this.refresh(cx))?.await
+        })
+    }
+}
+
+fn load_snapshot(repository: &dyn JujutsuRepository) -> Result<JujutsuSnapshot> {
+    let changes = repository.change_log(None, CHANGE_LOG_LIMIT)?;
+    let operations = repository.operation_log(OPERATION_LOG_LIMIT)?;
+    let working_copy_changes = repository.changed_files("@")?;
+    let has_conflict = repository
+        .change_log(Some("@"), 1)?
+        .first()
+        .is_some_and(|change| change.has_conflict);
+    let conflicted_files = if has_conflict {
+        repository.conflicted_files("@")?
+    } else {
+        Vec::new()
+    };
+    Ok(JujutsuSnapshot {
+        changes,
+        operations,
+        working_copy_changes,
+        conflicted_files,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FakeJujutsuRepository, FakeJujutsuRepositoryState};
+    use gpui::TestAppContext;
+
+    fn change(change_id: &str, description: &str, is_working_copy: bool) -> Change {
+        Change {
+            change_id: change_id.to_string().into(),
+            commit_id: format!("{change_id}-commit").into(),
+            description: description.to_string().into(),
+            author: "test".into(),
+            timestamp: "now".into(),
+            bookmarks: Vec::new(),
+            is_working_copy,
+            is_empty: false,
+            is_immutable: change_id == "root",
+            has_conflict: false,
+        }
+    }
+
+    #[gpui::test]
+    async fn test_jujutsu_store_commands(cx: &mut TestAppContext) {
+        let mut state = FakeJujutsuRepositoryState::default();
+        state.changes = vec![
+            change("working", "Add tests", true),
+            change("parent", "Add the parser", false),
+            change("root", "", false),
+        ];
+        state.changed_files.insert(
+            "working".into(),
+            vec![FileChange {
+                path: "src/parser.rs".into(),
+                kind: FileChangeKind::Modified,
+            }],
+        );
+        state.file_texts.insert(
+            ("parent".into(), "src/parser.rs".into()),
+            "fn parse() {}\n".into(),
+        );
+        state.file_texts.insert(
+            ("working".into(), "src/parser.rs".into()),
+            "fn parse() {}\n\n#[test]\nfn test() {}\n".into(),
+        );
+        let repository = Arc::new(FakeJujutsuRepository::new("/root", state));
+        let jj_store = cx.new(|cx| JujutsuStore::new(repository.clone(), cx));
+
+        jj_store
+            .update(cx, |jj_store, cx| jj_store.refresh(cx))
+            .await
+            .unwrap();
+        jj_store.read_with(cx, |jj_store, _| {
+            let snapshot = jj_store.snapshot();
+            assert_eq!(snapshot.changes.len(), 3);
+            assert_eq!(
+                snapshot
+                    .working_copy()
+                    .map(|change| change.change_id.clone()),
+                Some("working".into())
+            );
+            assert_eq!(snapshot.working_copy_changes.len(), 1);
+            assert!(snapshot.conflicted_files.is_empty());
+        });
+
+        let diff = jj_store
+            .read_with(cx, |jj_store, cx| jj_store.working_copy_diff(cx))
+            .await
+            .unwrap();
+        assert_eq!(diff.len(), 1);
+        assert_eq!(diff[0].old_text.as_deref(), Some("fn parse() {}\n"));
+        assert_eq!(
+            diff[0].new_text.as_deref(),
+            Some("fn parse() {}\n\n#[test]\nfn test() {}\n")
+        );
+
+        jj_store
+            .update(cx, |jj_store, cx| {
+                jj_store.describe("working".into(), "Test the parser".into(), cx)
+            })
+            .await
+            .unwrap();
+        jj_store
+            .update(cx, |jj_store, cx| jj_store.squash("working".into(), cx))
+            .await
+            .unwrap();
+        jj_store.read_with(cx, |jj_store, _| {
+            let snapshot = jj_store.snapshot();
+            let descriptions = snapshot
+                .changes
+                .iter()
+                .map(|change| change.description.to_string())
+                .collect::<Vec<_>>();
+            assert_eq!(descriptions, ["", "Add the parser\n\nTest the parser", ""]);
+            let working_copy = snapshot.working_copy().unwrap();
+            assert!(working_copy.is_empty);
+            assert_ne!(working_copy.change_id, "working");
+            assert!(
+                snapshot.working_copy_changes.is_empty(),
+                "The changes should be moved into the parent"
+            );
+            let operations = snapshot
+                .operations
+                .iter()
+                .map(|operation| operation.description.to_string())
+                .collect::<Vec<_>>();
+            assert_eq!(operations, ["squash commits", "describe commit"]);
+        });
+
+        jj_store
+            .update(cx, |jj_store, cx| jj_store.new_change("parent".into(), cx))
+            .await
+            .unwrap();
+        let new_change_id = jj_store.read_with(cx, |jj_store, _| {
+            let working_copy = jj_store.snapshot().working_copy().unwrap();
+            assert!(working_copy.is_empty);
+            working_copy.change_id.clone()
+        });
+        jj_store
+            .update(cx, |jj_store, cx| {
+                jj_store.abandon(new_change_id.clone(), cx)
+            })
+            .await
+            .unwrap();
+        assert!(
+            jj_store
+                .update(cx, |jj_store, cx| jj_store.abandon("root".into(), cx))
+                .await
+                .is_err()
+        );
+        jj_store.read_with(cx, |jj_store, _| {
+            let snapshot = jj_store.snapshot();
+            assert_eq!(snapshot.changes.len(), 3);
+            let working_copy = snapshot.working_copy().unwrap();
+            assert!(working_copy.is_empty);
+            assert_ne!(working_copy.change_id, new_change_id);
+        });
+    }
 }
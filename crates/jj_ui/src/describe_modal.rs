@@ -0,0 +1,93 @@
+use editor::{Editor, actions::SelectAll};
+use gpui::{App, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, prelude::*};
+use jj::{Change, JujutsuStore};
+use ui::prelude::*;
+use workspace::ModalView;
+use workspace::notifications::DetachAndPromptErr as _;
+
+/// Edits the first line of a change's description, keeping the rest of it intact.
+pub struct DescribeModal {
+    jj_store: Entity<JujutsuStore>,
+    change_id: SharedString,
+    body: Option<String>,
+    subject_editor: Entity<Editor>,
+}
+
+impl ModalView for DescribeModal {}
+
+impl EventEmitter<DismissEvent> for DescribeModal {}
+
+impl Focusable for DescribeModal {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.subject_editor.focus_handle(cx)
+    }
+}
+
+impl DescribeModal {
+    pub fn new(
+        jj_store: Entity<JujutsuStore>,
+        change: &Change,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let (subject, body) = match change.description.split_once('\n') {
+            Some((subject, body)) => (subject, Some(body.to_string())),
+            None => (change.description.as_ref(), None),
+        };
+        let subject_editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text("Describe the change", window, cx);
+            editor.set_text(subject, window, cx);
+            editor.select_all(&SelectAll, window, cx);
+            editor
+        });
+
+        Self {
+            jj_store,
+            change_id: change.change_id.clone(),
+            body,
+            subject_editor,
+        }
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, _: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        let mut message = self.subject_editor.read(cx).text(cx).trim().to_string();
+        if let Some(body) = &self.body {
+            message.push('\n');
+            message.push_str(body);
+        }
+
+        self.jj_store
+            .update(cx, |jj_store, cx| {
+                jj_store.describe(self.change_id.clone(), message, cx)
+            })
+            .detach_and_prompt_err("Failed to describe change", window, cx, |_, _, _| None);
+        cx.emit(DismissEvent);
+    }
+}
+
+impl Render for DescribeModal {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .w(rems(34.))
+            .elevation_2(cx)
+            .key_context("JujutsuDescribeModal")
+            .on_action(cx.listener(Self::cancel))
+            .on_action(cx.listener(Self::confirm))
+            .child(
+                div()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .px_2()
+                    .py_1()
+                    .child(self.subject_editor.clone()),
+            )
+            .child(h_flex().px_2().py_1().gap_1().child(
+                Label::new(format!("Describe change {}", self.change_id)).color(Color::Muted),
+            ))
+    }
+}
@@ -0,0 +1,723 @@
+use std::path::{Path, PathBuf};
+
+use collections::HashMap;
+use feature_flags::{FeatureFlagAppExt as _, JjUiFeatureFlag};
+use gpui::{
+    Action, App, Entity, EventEmitter, FocusHandle, Focusable, Pixels, Subscription, Task,
+    WeakEntity, actions, prelude::*, px,
+};
+use jj::{
+    Change, FileChange, FileChangeKind, FileDiff, JujutsuStore, JujutsuStoreEvent, Operation,
+};
+use ui::{ListItem, ListItemSpacing, Tooltip, prelude::*};
+use util::ResultExt as _;
+use workspace::notifications::DetachAndPromptErr as _;
+use workspace::{
+    OpenOptions, Workspace,
+    dock::{DockPosition, Panel, PanelEvent},
+};
+
+use crate::describe_modal::DescribeModal;
+
+actions!(
+    jj,
+    [
+        /// Toggles focus on the Jujutsu panel.
+        ToggleFocus,
+        /// Reloads the change log and the working-copy state.
+        Refresh,
+        /// Starts a new change on top of the working-copy change.
+        NewChange,
+        /// Edits the description of the working-copy change.
+        DescribeWorkingCopy,
+        /// Squashes the working-copy change into its parent.
+        SquashWorkingCopy,
+    ]
+);
+
+pub fn register(workspace: &mut Workspace) {
+    workspace.register_action(|workspace, _: &ToggleFocus, window, cx| {
+        workspace.toggle_panel_focus::<JujutsuPanel>(window, cx);
+    });
+}
+
+/// The number of lines added and removed in a file of the working-copy change.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct LineCounts {
+    added: u32,
+    removed: u32,
+}
+
+impl LineCounts {
+    fn new(file_diff: &FileDiff) -> Self {
+        let old_text = file_diff.old_text.as_deref().unwrap_or_default();
+        let new_text = file_diff.new_text.as_deref().unwrap_or_default();
+        language::line_diff(old_text, new_text).into_iter().fold(
+            Self::default(),
+            |counts, (old_rows, new_rows)| Self {
+                added: counts.added + new_rows.len() as u32,
+                removed: counts.removed + old_rows.len() as u32,
+            },
+        )
+    }
+}
+
+pub struct JujutsuPanel {
+    workspace: WeakEntity<Workspace>,
+    jj_store: Entity<JujutsuStore>,
+    focus_handle: FocusHandle,
+    position: DockPosition,
+    width: Option<Pixels>,
+    line_counts: HashMap<PathBuf, LineCounts>,
+    load_line_counts: Task<()>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl JujutsuPanel {
+    pub fn new(
+        workspace: &Workspace,
+        jj_store: Entity<JujutsuStore>,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let _subscriptions = vec![
+            cx.observe(&jj_store, |_, _, cx| cx.notify()),
+            cx.subscribe(&jj_store, |this, _, event, cx| match event {
+                JujutsuStoreEvent::SnapshotUpdated => this.load_line_counts(cx),
+            }),
+        ];
+        Self {
+            workspace: workspace.weak_handle(),
+            jj_store,
+            focus_handle: cx.focus_handle(),
+            position: DockPosition::Left,
+            width: None,
+            line_counts: HashMap::default(),
+            load_line_counts: Task::ready(()),
+            _subscriptions,
+        }
+    }
+
+    /// Loads the diff of the working-copy change to show how many lines of each file changed.
+    fn load_line_counts(&mut self, cx: &mut Context<Self>) {
+        let diff = self.jj_store.read(cx).working_copy_diff(cx);
+        self.load_line_counts = cx.spawn(async move |this, cx| {
+            let Some(diff) = diff.await.log_err() else {
+                return;
+            };
+            let line_counts = cx
+                .background_spawn(async move {
+                    diff.iter()
+                        .map(|file_diff| {
+                            (file_diff.change.path.clone(), LineCounts::new(file_diff))
+                        })
+                        .collect()
+                })
+                .await;
+            this.update(cx, |this, cx| {
+                this.line_counts = line_counts;
+                cx.notify();
+            })
+            .ok();
+        });
+    }
+
+    fn working_copy(&self, cx: &App) -> Option<Change> {
+        self.jj_store.read(cx).snapshot().working_copy().cloned()
+    }
+
+    fn refresh(&mut self, _: &Refresh, window: &mut Window, cx: &mut Context<Self>) {
+        self.jj_store
+            .update(cx, |jj_store, cx| jj_store.refresh(cx))
+            .detach_and_prompt_err("Failed to refresh", window, cx, |_, _, _| None);
+    }
+
+    fn new_change(&mut self, _: &NewChange, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(working_copy) = self.working_copy(cx) {
+            self.new_change_on(working_copy.change_id, window, cx);
+        }
+    }
+
+    fn describe_working_copy(
+        &mut self,
+        _: &DescribeWorkingCopy,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(working_copy) = self.working_copy(cx) {
+            self.describe(&working_copy, window, cx);
+        }
+    }
+
+    fn squash_working_copy(
+        &mut self,
+        _: &SquashWorkingCopy,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(working_copy) = self.working_copy(cx) {
+            self.squash(working_copy.change_id, window, cx);
+        }
+    }
+
+    fn new_change_on(
+        &mut self,
+        parent_id: SharedString,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.jj_store
+            .update(cx, |jj_store, cx| jj_store.new_change(parent_id, cx))
+            .detach_and_prompt_err("Failed to create change", window, cx, |_, _, _| None);
+    }
+
+    fn describe(&mut self, change: &Change, window: &mut Window, cx: &mut Context<Self>) {
+        let jj_store = self.jj_store.clone();
+        let change = change.clone();
+        self.workspace
+            .update(cx, |workspace, cx| {
+                workspace.toggle_modal(window, cx, |window, cx| {
+                    DescribeModal::new(jj_store, &change, window, cx)
+                });
+            })
+            .ok();
+    }
+
+    fn squash(&mut self, change_id: SharedString, window: &mut Window, cx: &mut Context<Self>) {
+        self.jj_store
+            .update(cx, |jj_store, cx| jj_store.squash(change_id, cx))
+            .detach_and_prompt_err("Failed to squash change", window, cx, |_, _, _| None);
+    }
+
+    fn abandon(&mut self, change_id: SharedString, window: &mut Window, cx: &mut Context<Self>) {
+        self.jj_store
+            .update(cx, |jj_store, cx| jj_store.abandon(change_id, cx))
+            .detach_and_prompt_err("Failed to abandon change", window, cx, |_, _, _| None);
+    }
+
+    fn open_file(&mut self, path: &Path, window: &mut Window, cx: &mut Context<Self>) {
+        let abs_path = self
+            .jj_store
+            .read(cx)
+            .repository()
+            .workspace_root()
+            .join(path);
+        self.workspace
+            .update(cx, |workspace, cx| {
+                workspace
+                    .open_abs_path(abs_path, OpenOptions::default(), window, cx)
+                    .detach_and_prompt_err("Failed to open file", window, cx, |_, _, _| None);
+            })
+            .ok();
+    }
+
+    fn render_section_header(&self, label: impl Into<SharedString>) -> impl IntoElement {
+        h_flex()
+            .px_2()
+            .pt_2()
+            .pb_1()
+            .child(Label::new(label).size(LabelSize::Small).color(Color::Muted))
+    }
+
+    fn render_working_copy(&self, working_copy: &Change, cx: &Context<Self>) -> impl IntoElement {
+        let description = if working_copy.description.is_empty() {
+            "(no description set)".into()
+        } else {
+            first_line(&working_copy.description)
+        };
+
+        h_flex()
+            .px_2()
+            .py_1()
+            .gap_1()
+            .justify_between()
+            .border_b_1()
+            .border_color(cx.theme().colors().border_variant)
+            .child(
+                v_flex()
+                    .min_w_0()
+                    .child(Label::new(description).truncate())
+                    .child(
+                        Label::new(short_id(&working_copy.change_id))
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    ),
+            )
+            .child(
+                h_flex()
+                    .gap_0p5()
+                    .child(
+                        IconButton::new("new-change", IconName::Plus)
+                            .icon_size(IconSize::Small)
+                            .tooltip(Tooltip::for_action_title("New Change", &NewChange))
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.new_change(&NewChange, window, cx)
+                            })),
+                    )
+                    .child(
+                        IconButton::new("describe-change", IconName::Pencil)
+                            .icon_size(IconSize::Small)
+                            .tooltip(Tooltip::for_action_title(
+                                "Describe Change",
+                                &DescribeWorkingCopy,
+                            ))
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.describe_working_copy(&DescribeWorkingCopy, window, cx)
+                            })),
+                    )
+                    .child(
+                        IconButton::new("squash-change", IconName::ArrowUp)
+                            .icon_size(IconSize::Small)
+                            .disabled(working_copy.is_empty && working_copy.description.is_empty())
+                            .tooltip(Tooltip::for_action_title(
+                                "Squash into Parent",
+                                &SquashWorkingCopy,
+                            ))
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.squash_working_copy(&SquashWorkingCopy, window, cx)
+                            })),
+                    )
+                    .child(
+                        IconButton::new("refresh", IconName::ArrowCircle)
+                            .icon_size(IconSize::Small)
+                            .tooltip(Tooltip::for_action_title("Refresh", &Refresh))
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.refresh(&Refresh, window, cx)
+                            })),
+                    ),
+            )
+    }
+
+    fn render_file(
+        &self,
+        ix: usize,
+        id_prefix: &'static str,
+        path: &Path,
+        icon: IconName,
+        color: Color,
+        cx: &Context<Self>,
+    ) -> ListItem {
+        let path = path.to_path_buf();
+        ListItem::new((id_prefix, ix))
+            .spacing(ListItemSpacing::Sparse)
+            .start_slot(Icon::new(icon).size(IconSize::Small).color(color))
+            .child(Label::new(path.to_string_lossy().into_owned()).truncate())
+            .on_click(cx.listener(move |this, _, window, cx| {
+                this.open_file(&path, window, cx);
+            }))
+    }
+
+    fn render_file_change(
+        &self,
+        ix: usize,
+        file_change: &FileChange,
+        cx: &Context<Self>,
+    ) -> impl IntoElement {
+        let (icon, color) = match file_change.kind {
+            FileChangeKind::Added => (IconName::SquarePlus, Color::Created),
+            FileChangeKind::Modified => (IconName::SquareDot, Color::Modified),
+            FileChangeKind::Removed => (IconName::SquareMinus, Color::Deleted),
+        };
+        let line_counts = self.line_counts.get(&file_change.path).copied();
+        self.render_file(ix, "file-change", &file_change.path, icon, color, cx)
+            .end_slot(line_counts.map(|line_counts| {
+                h_flex()
+                    .gap_1()
+                    .child(
+                        Label::new(format!("+{}", line_counts.added))
+                            .size(LabelSize::Small)
+                            .color(Color::Created),
+                    )
+                    .child(
+                        Label::new(format!("-{}", line_counts.removed))
+                            .size(LabelSize::Small)
+                            .color(Color::Deleted),
+                    )
+            }))
+    }
+
+    fn render_change(&self, ix: usize, change: &Change, cx: &Context<Self>) -> impl IntoElement {
+        let description = if change.description.is_empty() {
+            "(no description set)".into()
+        } else {
+            first_line(&change.description)
+        };
+        let description_color = if change.description.is_empty() {
+            Color::Muted
+        } else {
+            Color::Default
+        };
+        let mutable = !change.is_immutable;
+
+        ListItem::new(("change", ix))
+            .spacing(ListItemSpacing::Sparse)
+            .toggle_state(change.is_working_copy)
+            .start_slot(
+                Label::new(short_id(&change.change_id))
+                    .size(LabelSize::Small)
+                    .color(Color::Accent),
+            )
+            .child(
+                h_flex()
+                    .min_w_0()
+                    .gap_1()
+                    .children(change.bookmarks.iter().map(|bookmark| {
+                        Label::new(bookmark.clone())
+                            .size(LabelSize::Small)
+                            .color(Color::Info)
+                    }))
+                    .when(change.has_conflict, |this| {
+                        this.child(
+                            Icon::new(IconName::Warning)
+                                .size(IconSize::Small)
+                                .color(Color::Warning),
+                        )
+                    })
+                    .child(Label::new(description).color(description_color).truncate()),
+            )
+            .tooltip({
+                let tooltip = format!(
+                    "{}\n{} · {}",
+                    change.description, change.author, change.timestamp
+                );
+                Tooltip::text(tooltip)
+            })
+            .end_hover_slot(
+                h_flex()
+                    .gap_0p5()
+                    .child(
+                        IconButton::new(("new-change-on", ix), IconName::Plus)
+                            .icon_size(IconSize::Small)
+                            .tooltip(Tooltip::text("New Change on Top"))
+                            .on_click({
+                                let change_id = change.change_id.clone();
+                                cx.listener(move |this, _, window, cx| {
+                                    this.new_change_on(change_id.clone(), window, cx);
+                                })
+                            }),
+                    )
+                    .when(mutable, |this| {
+                        this.child(
+                            IconButton::new(("describe", ix), IconName::Pencil)
+                                .icon_size(IconSize::Small)
+                                .tooltip(Tooltip::text("Describe"))
+                                .on_click({
+                                    let change = change.clone();
+                                    cx.listener(move |this, _, window, cx| {
+                                        this.describe(&change, window, cx);
+                                    })
+                                }),
+                        )
+                        .child(
+                            IconButton::new(("squash", ix), IconName::ArrowUp)
+                                .icon_size(IconSize::Small)
+                                .tooltip(Tooltip::text("Squash into Parent"))
+                                .on_click({
+                                    let change_id = change.change_id.clone();
+                                    cx.listener(move |this, _, window, cx| {
+                                        this.squash(change_id.clone(), window, cx);
+                                    })
+                                }),
+                        )
+                        .child(
+                            IconButton::new(("abandon", ix), IconName::Trash)
+                                .icon_size(IconSize::Small)
+                                .tooltip(Tooltip::text("Abandon"))
+                                .on_click({
+                                    let change_id = change.change_id.clone();
+                                    cx.listener(move |this, _, window, cx| {
+                                        this.abandon(change_id.clone(), window, cx);
+                                    })
+                                }),
+                        )
+                    }),
+            )
+    }
+
+    fn render_operation(&self, ix: usize, operation: &Operation) -> impl IntoElement {
+        ListItem::new(("operation", ix))
+            .spacing(ListItemSpacing::Sparse)
+            .toggle_state(operation.is_current)
+            .start_slot(
+                Label::new(short_id(&operation.id))
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            )
+            .child(Label::new(operation.description.clone()).truncate())
+            .tooltip(Tooltip::text(format!(
+                "{} · {}",
+                operation.user, operation.timestamp
+            )))
+    }
+}
+
+fn first_line(text: &str) -> SharedString {
+    text.lines().next().unwrap_or_default().to_string().into()
+}
+
+fn short_id(id: &str) -> SharedString {
+    id.chars().take(8).collect::<String>().into()
+}
+
+impl Render for JujutsuPanel {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let snapshot = self.jj_store.read(cx).snapshot().clone();
+
+        v_flex()
+            .id("jj_panel")
+            .key_context("JujutsuPanel")
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::refresh))
+            .on_action(cx.listener(Self::new_change))
+            .on_action(cx.listener(Self::describe_working_copy))
+            .on_action(cx.listener(Self::squash_working_copy))
+            .size_full()
+            .overflow_y_scroll()
+            .bg(cx.theme().colors().panel_background)
+            .children(
+                snapshot
+                    .working_copy()
+                    .map(|working_copy| self.render_working_copy(working_copy, cx)),
+            )
+            .when(!snapshot.conflicted_files.is_empty(), |this| {
+                this.child(self.render_section_header("Conflicts"))
+                    .children(
+                        snapshot
+                            .conflicted_files
+                            .iter()
+                            .enumerate()
+                            .map(|(ix, path)| {
+                                self.render_file(
+                                    ix,
+                                    "conflict",
+                                    path,
+                                    IconName::Warning,
+                                    Color::Conflict,
+                                    cx,
+                                )
+                            }),
+                    )
+            })
+            .child(self.render_section_header("Working Copy Changes"))
+            .when(snapshot.working_copy_changes.is_empty(), |this| {
+                this.child(
+                    h_flex().px_2().child(
+                        Label::new("The working copy is clean")
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    ),
+                )
+            })
+            .children(
+                snapshot
+                    .working_copy_changes
+                    .iter()
+                    .enumerate()
+                    .map(|(ix, file_change)| self.render_file_change(ix, file_change, cx)),
+            )
+            .child(self.render_section_header("Changes"))
+            .children(
+                snapshot
+                    .changes
+                    .iter()
+                    .enumerate()
+                    .map(|(ix, change)| self.render_change(ix, change, cx)),
+            )
+            .child(self.render_section_header("Operations"))
+            .children(
+                snapshot
+                    .operations
+                    .iter()
+                    .enumerate()
+                    .map(|(ix, operation)| self.render_operation(ix, operation)),
+            )
+    }
+}
+
+impl Focusable for JujutsuPanel {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl EventEmitter<PanelEvent> for JujutsuPanel {}
+
+impl Panel for JujutsuPanel {
+    fn persistent_name() -> &'static str {
+        "JujutsuPanel"
+    }
+
+    fn position(&self, _: &Window, _: &App) -> DockPosition {
+        self.position
+    }
+
+    fn position_is_valid(&self, position: DockPosition) -> bool {
+        matches!(position, DockPosition::Left | DockPosition::Right)
+    }
+
+    fn set_position(&mut self, position: DockPosition, _: &mut Window, cx: &mut Context<Self>) {
+        self.position = position;
+        cx.notify();
+    }
+
+    fn size(&self, _: &Window, _: &App) -> Pixels {
+        self.width.unwrap_or(px(360.))
+    }
+
+    fn set_size(&mut self, size: Option<Pixels>, _: &mut Window, cx: &mut Context<Self>) {
+        self.width = size;
+        cx.notify();
+    }
+
+    fn icon(&self, _: &Window, _: &App) -> Option<IconName> {
+        Some(IconName::GitBranch)
+    }
+
+    fn icon_tooltip(&self, _: &Window, _: &App) -> Option<&'static str> {
+        Some("Jujutsu Panel")
+    }
+
+    fn toggle_action(&self) -> Box<dyn Action> {
+        Box::new(ToggleFocus)
+    }
+
+    fn activation_priority(&self) -> u32 {
+        7
+    }
+
+    fn enabled(&self, cx: &App) -> bool {
+        cx.has_flag::<JjUiFeatureFlag>()
+    }
+
+    fn set_active(&mut self, active: bool, _: &mut Window, cx: &mut Context<Self>) {
+        if active {
+            self.jj_store
+                .update(cx, |jj_store, cx| jj_store.refresh(cx))
+                .detach_and_log_err(cx);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use gpui::TestAppContext;
+    use jj::{FakeJujutsuRepository, FakeJujutsuRepositoryState};
+    use project::{FakeFs, Project};
+    use settings::SettingsStore;
+
+    use super::*;
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            theme::init(theme::LoadThemes::JustBase, cx);
+            workspace::init_settings(cx);
+            Project::init_settings(cx);
+        });
+    }
+
+    fn change(change_id: &str, description: &str, is_working_copy: bool) -> Change {
+        Change {
+            change_id: change_id.to_string().into(),
+            commit_id: format!("{change_id}-commit").into(),
+            description: description.to_string().into(),
+            author: "test".into(),
+            timestamp: "now".into(),
+            bookmarks: Vec::new(),
+            is_working_copy,
+            is_empty: false,
+            is_immutable: change_id == "root",
+            has_conflict: false,
+        }
+    }
+
+    #[gpui::test]
+    async fn test_jj_panel_working_copy_actions(cx: &mut TestAppContext) {
+        init_test(cx);
+        let project = Project::test(FakeFs::new(cx.executor()), [], cx).await;
+        let (workspace, cx) =
+            cx.add_window_view(|window, cx| Workspace::test_new(project, window, cx));
+
+        let mut state = FakeJujutsuRepositoryState::default();
+        state.changes = vec![
+            change("working", "Add tests", true),
+            change("parent", "Add the parser", false),
+            change("root", "", false),
+        ];
+        state.changed_files.insert(
+            "working".into(),
+            vec![FileChange {
+                path: "src/parser.rs".into(),
+                kind: FileChangeKind::Modified,
+            }],
+        );
+        state.file_texts.insert(
+            ("parent".into(), "src/parser.rs".into()),
+            "fn parse() {}\n".into(),
+        );
+        state.file_texts.insert(
+            ("working".into(), "src/parser.rs".into()),
+            "fn parse() {}\n\n#[test]\nfn test() {}\n".into(),
+        );
+        let repository = Arc::new(FakeJujutsuRepository::new("/root", state));
+        let jj_store = cx.new(|cx| JujutsuStore::new(repository.clone(), cx));
+        let panel = workspace.update_in(cx, |workspace, window, cx| {
+            let panel = cx.new(|cx| JujutsuPanel::new(workspace, jj_store.clone(), cx));
+            workspace.add_panel(panel.clone(), window, cx);
+            panel
+        });
+        assert!(
+            jj_store.read_with(cx, |jj_store, _| jj_store.snapshot().changes.is_empty()),
+            "The snapshot should only be loaded once the panel gets opened"
+        );
+
+        panel.update_in(cx, |panel, window, cx| panel.set_active(true, window, cx));
+        cx.run_until_parked();
+        assert_eq!(
+            panel.read_with(cx, |panel, cx| panel
+                .working_copy(cx)
+                .map(|change| change.change_id)),
+            Some("working".into())
+        );
+        assert_eq!(
+            panel.read_with(cx, |panel, _| panel
+                .line_counts
+                .get(Path::new("src/parser.rs"))
+                .copied()),
+            Some(LineCounts {
+                added: 3,
+                removed: 0
+            }),
+            "The panel should show how many lines of the working-copy files changed"
+        );
+
+        panel.update_in(cx, |panel, window, cx| {
+            panel.squash_working_copy(&SquashWorkingCopy, window, cx)
+        });
+        cx.run_until_parked();
+        panel.update_in(cx, |panel, window, cx| {
+            panel.new_change(&NewChange, window, cx)
+        });
+        cx.run_until_parked();
+
+        let state = repository.state();
+        let descriptions = state
+            .changes
+            .iter()
+            .map(|change| change.description.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            descriptions,
+            ["", "", "Add the parser\n\nAdd tests", ""],
+            "The working copy should be squashed into its parent, leaving an empty working copy, \
+            and a new change should be started on top of it"
+        );
+        let working_copy = panel
+            .read_with(cx, |panel, cx| panel.working_copy(cx))
+            .unwrap();
+        assert!(working_copy.is_empty);
+        assert_eq!(working_copy.change_id, state.changes[0].change_id);
+    }
+}
@@ -1,4 +1,6 @@
 mod bookmark_picker;
+mod describe_modal;
+mod jj_panel;
 
 use command_palette_hooks::CommandPaletteFilter;
 use feature_flags::FeatureFlagAppExt as _;
@@ -6,11 +8,22 @@ use gpui::App;
 use jj::JujutsuStore;
 use workspace::Workspace;
 
+pub use jj_panel::JujutsuPanel;
+
 pub fn init(cx: &mut App) {
     JujutsuStore::init_global(cx);
 
-    cx.observe_new(|workspace: &mut Workspace, _window, _cx| {
+    cx.observe_new(|workspace: &mut Workspace, window, cx| {
         bookmark_picker::register(workspace);
+        jj_panel::register(workspace);
+
+        let Some(window) = window else {
+            return;
+        };
+        if let Some(jj_store) = JujutsuStore::try_global(cx) {
+            let jj_panel = cx.new(|cx| JujutsuPanel::new(workspace, jj_store, cx));
+            workspace.add_panel(jj_panel, window, cx);
+        }
     })
     .detach();
 
@@ -130,6 +130,12 @@ pub struct LanguageSettings {
     /// Whether to use additional LSP queries to format (and amend) the code after
     /// every "trigger" symbol input, defined by LSP server capabilities.
     pub use_on_type_format: bool,
+    /// Whether to highlight the code with the semantic tokens provided by language servers,
+    /// layered over the tree-sitter syntax highlighting.
+    pub semantic_tokens: bool,
+    /// Whether to fold code using the folding ranges provided by language servers,
+    /// instead of indentation.
+    pub lsp_folding_ranges: bool,
     /// Whether indentation should be adjusted based on the context whilst typing.
     pub auto_indent: bool,
     /// Whether indentation of pasted content should be adjusted based on the context.
@@ -552,6 +558,8 @@ impl settings::Settings for AllLanguageSettings {
                 use_autoclose: settings.use_autoclose.unwrap(),
                 use_auto_surround: settings.use_auto_surround.unwrap(),
                 use_on_type_format: settings.use_on_type_format.unwrap(),
+                semantic_tokens: settings.semantic_tokens.unwrap(),
+                lsp_folding_ranges: settings.lsp_folding_ranges.unwrap(),
                 auto_indent: settings.auto_indent.unwrap(),
                 auto_indent_on_paste: settings.auto_indent_on_paste.unwrap(),
                 always_treat_brackets_as_autoclosed: settings
@@ -720,6 +728,19 @@ impl settings::Settings for AllLanguageSettings {
             },
         );
         vscode.bool_setting("editor.formatOnType", &mut d.use_on_type_format);
+        vscode.bool_setting(
+            "editor.semanticHighlighting.enabled",
+            &mut d.semantic_tokens,
+        );
+        vscode.enum_setting(
+            "editor.foldingStrategy",
+            &mut d.lsp_folding_ranges,
+            |s| match s {
+                "auto" => Some(true),
+                "indentation" => Some(false),
+                _ => None,
+            },
+        );
         vscode.bool_setting("editor.linkedEditing", &mut d.linked_edits);
         vscode.bool_setting("editor.formatOnPaste", &mut d.auto_indent_on_paste);
         vscode.bool_setting(
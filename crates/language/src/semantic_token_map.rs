@@ -0,0 +1,125 @@
+use gpui::{FontWeight, HighlightStyle, StrikethroughStyle, UnderlineStyle, px};
+use theme::SyntaxTheme;
+
+use crate::HighlightId;
+
+/// Returns the highlight name of a semantic token type, named after the tree-sitter capture of
+/// the same kind of symbol so that themes style both alike.
+pub fn semantic_token_highlight_name(token_type: &str) -> Option<&'static str> {
+    let name = match token_type {
+        "namespace" => "namespace",
+        "type" | "class" | "interface" | "struct" | "typeParameter" | "typeAlias" | "union"
+        | "selfTypeKeyword" => "type",
+        "builtinType" => "type.builtin",
+        "enum" => "enum",
+        "enumMember" => "variant",
+        "parameter" | "variable" => "variable",
+        "selfKeyword" => "variable.special",
+        "property" | "event" => "property",
+        "function" => "function",
+        "method" => "function.method",
+        "macro" => "function.special",
+        "keyword" | "modifier" => "keyword",
+        "comment" => "comment",
+        "string" => "string",
+        "escapeSequence" => "string.escape",
+        "regexp" => "string.regex",
+        "number" => "number",
+        "boolean" => "boolean",
+        "constParameter" => "constant",
+        "operator" => "operator",
+        "formatSpecifier" => "punctuation.special",
+        "decorator" | "attribute" => "attribute",
+        _ => return None,
+    };
+    Some(name)
+}
+
+/// Returns the style of a modifier that themes rarely distinguish, so that it shows up anyway.
+fn semantic_token_modifier_style(token_modifier: &str) -> Option<HighlightStyle> {
+    match token_modifier {
+        "mutable" => Some(HighlightStyle {
+            underline: Some(UnderlineStyle {
+                thickness: px(1.),
+                ..UnderlineStyle::default()
+            }),
+            ..HighlightStyle::default()
+        }),
+        "unsafe" => Some(HighlightStyle {
+            font_weight: Some(FontWeight::BOLD),
+            ..HighlightStyle::default()
+        }),
+        "deprecated" => Some(HighlightStyle {
+            strikethrough: Some(StrikethroughStyle {
+                thickness: px(1.),
+                ..StrikethroughStyle::default()
+            }),
+            ..HighlightStyle::default()
+        }),
+        _ => None,
+    }
+}
+
+/// Resolves the style of a semantic token, to be layered over the syntax highlighting.
+///
+/// The modifiers are appended to the highlight name of the token type, so that a theme can style
+/// e.g. `variable.mutable` differently from `variable`. Returns `None` when the theme has no style
+/// for the token, leaving its syntax highlighting as is.
+pub fn semantic_token_style<'a>(
+    token_type: &str,
+    token_modifiers: impl IntoIterator<Item = &'a str>,
+    theme: &SyntaxTheme,
+) -> Option<HighlightStyle> {
+    let mut modifier_style = None::<HighlightStyle>;
+    let mut highlight_name = semantic_token_highlight_name(token_type).map(String::from);
+    for token_modifier in token_modifiers {
+        if let Some(style) = semantic_token_modifier_style(token_modifier) {
+            modifier_style = Some(modifier_style.unwrap_or_default().highlight(style));
+        }
+        if let Some(highlight_name) = &mut highlight_name {
+            highlight_name.push('.');
+            highlight_name.push_str(token_modifier);
+        }
+    }
+
+    let style =
+        highlight_name.and_then(|name| HighlightId::for_capture_name(&name, theme).style(theme));
+    match (style, modifier_style) {
+        (Some(style), Some(modifier_style)) => Some(style.highlight(modifier_style)),
+        (style, modifier_style) => style.or(modifier_style),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::rgba;
+
+    #[test]
+    fn test_semantic_token_style() {
+        let theme = SyntaxTheme {
+            highlights: [
+                ("function", rgba(0x100000ff)),
+                ("function.async", rgba(0x200000ff)),
+                ("variable", rgba(0x300000ff)),
+            ]
+            .iter()
+            .map(|(name, color)| (name.to_string(), (*color).into()))
+            .collect(),
+        };
+
+        let style = semantic_token_style("method", ["async", "declaration"], &theme).unwrap();
+        assert_eq!(style.color, Some(rgba(0x200000ff).into()));
+
+        let style = semantic_token_style("variable", ["mutable"], &theme).unwrap();
+        assert_eq!(style.color, Some(rgba(0x300000ff).into()));
+        assert!(style.underline.is_some());
+
+        let style = semantic_token_style("lifetime", ["unsafe"], &theme).unwrap();
+        assert_eq!(style.color, None);
+        assert_eq!(style.font_weight, Some(FontWeight::BOLD));
+
+        assert_eq!(semantic_token_style("lifetime", [], &theme), None);
+        assert_eq!(semantic_token_style("namespace", [], &theme), None);
+    }
+}
@@ -3,13 +3,21 @@ pub mod registry;
 
 use std::{path::Path, sync::Arc};
 
-use anyhow::{Context as _, Result};
+use anyhow::{Context as _, Result, anyhow};
 use collections::{HashMap, HashSet};
-use context_server::{ContextServer, ContextServerCommand, ContextServerId};
+use context_server::{
+    ContextServer, ContextServerCommand, ContextServerEndpoint, ContextServerId,
+    client::IncomingRequestHandler,
+    protocol::{InitializedContextServerProtocol, ServerCapability},
+    types::{
+        self, CreateMessageRequest, CreateMessageResult, Notification as _, Request as _, requests,
+    },
+};
 use futures::{FutureExt as _, future::join_all};
 use gpui::{App, AsyncApp, Context, Entity, EventEmitter, Subscription, Task, WeakEntity, actions};
 use registry::ContextServerDescriptorRegistry;
 use settings::{Settings as _, SettingsStore};
+use url::Url;
 use util::ResultExt as _;
 
 use crate::{
@@ -95,6 +103,9 @@ pub enum ContextServerConfiguration {
     Custom {
         command: ContextServerCommand,
     },
+    Http {
+        endpoint: ContextServerEndpoint,
+    },
     Extension {
         command: ContextServerCommand,
         settings: serde_json::Value,
@@ -102,10 +113,12 @@ pub enum ContextServerConfiguration {
 }
 
 impl ContextServerConfiguration {
-    pub fn command(&self) -> &ContextServerCommand {
+    /// The command that launches the server, unless it's reached over HTTP.
+    pub fn command(&self) -> Option<&ContextServerCommand> {
         match self {
-            ContextServerConfiguration::Custom { command } => command,
-            ContextServerConfiguration::Extension { command, .. } => command,
+            ContextServerConfiguration::Custom { command } => Some(command),
+            ContextServerConfiguration::Http { .. } => None,
+            ContextServerConfiguration::Extension { command, .. } => Some(command),
         }
     }
 
@@ -121,6 +134,10 @@ impl ContextServerConfiguration {
                 enabled: _,
                 command,
             } => Some(ContextServerConfiguration::Custom { command }),
+            ContextServerSettings::Http {
+                enabled: _,
+                endpoint,
+            } => Some(ContextServerConfiguration::Http { endpoint }),
             ContextServerSettings::Extension {
                 enabled: _,
                 settings,
@@ -138,6 +155,21 @@ impl ContextServerConfiguration {
     }
 }
 
+/// A `sampling/createMessage` request sent by a context server, which asks the client to run a
+/// completion with one of its language models.
+#[derive(Clone)]
+pub struct ContextServerSamplingRequest {
+    pub server_id: ContextServerId,
+    pub params: Arc<CreateMessageRequest>,
+    response_channel: smol::channel::Sender<Result<CreateMessageResult>>,
+}
+
+impl ContextServerSamplingRequest {
+    pub async fn respond(self, result: Result<CreateMessageResult>) -> Option<()> {
+        self.response_channel.send(result).await.ok()
+    }
+}
+
 pub type ContextServerFactory =
     Box<dyn Fn(ContextServerId, Arc<ContextServerConfiguration>) -> Arc<ContextServer>>;
 
@@ -150,6 +182,8 @@ pub struct ContextServerStore {
     update_servers_task: Option<Task<Result<()>>>,
     context_server_factory: Option<ContextServerFactory>,
     needs_server_update: bool,
+    /// The number of subscribers to each resource, per server.
+    resource_subscriptions: HashMap<ContextServerId, HashMap<Url, usize>>,
     _subscriptions: Vec<Subscription>,
 }
 
@@ -158,6 +192,17 @@ pub enum Event {
         server_id: ContextServerId,
         status: ContextServerStatus,
     },
+    ResourceUpdated {
+        server_id: ContextServerId,
+        uri: String,
+    },
+    ResourcesChanged {
+        server_id: ContextServerId,
+    },
+    PromptsChanged {
+        server_id: ContextServerId,
+    },
+    SamplingRequested(ContextServerSamplingRequest),
 }
 
 impl EventEmitter<Event> for ContextServerStore {}
@@ -252,6 +297,7 @@ impl ContextServerStore {
             servers: HashMap::default(),
             update_servers_task: None,
             context_server_factory,
+            resource_subscriptions: HashMap::default(),
         };
         if maintain_server_loop {
             this.available_context_servers_changed(cx);
@@ -309,6 +355,142 @@ impl ContextServerStore {
             .collect()
     }
 
+    fn running_client(
+        &self,
+        id: &ContextServerId,
+    ) -> Result<Arc<InitializedContextServerProtocol>> {
+        self.get_running_server(id)
+            .and_then(|server| server.client())
+            .with_context(|| format!("Context server {id} is not running"))
+    }
+
+    /// Lists the resources of a running server, following pagination cursors.
+    pub fn list_resources(
+        &self,
+        id: &ContextServerId,
+        cx: &App,
+    ) -> Task<Result<Vec<types::Resource>>> {
+        let client = match self.running_client(id) {
+            Ok(client) => client,
+            Err(error) => return Task::ready(Err(error)),
+        };
+        if !client.capable(ServerCapability::Resources) {
+            return Task::ready(Ok(Vec::new()));
+        }
+        cx.foreground_executor().spawn(async move {
+            let mut resources = Vec::new();
+            let mut cursor = None;
+            loop {
+                let response = client
+                    .request::<requests::ResourcesList>(types::ResourcesListParams { cursor })
+                    .await?;
+                resources.extend(response.resources);
+                match response.next_cursor {
+                    Some(next_cursor) => cursor = Some(next_cursor),
+                    None => break,
+                }
+            }
+            Ok(resources)
+        })
+    }
+
+    pub fn read_resource(
+        &self,
+        id: &ContextServerId,
+        uri: Url,
+        cx: &App,
+    ) -> Task<Result<Vec<types::ResourceContentsType>>> {
+        let client = match self.running_client(id) {
+            Ok(client) => client,
+            Err(error) => return Task::ready(Err(error)),
+        };
+        cx.foreground_executor().spawn(async move {
+            let response = client
+                .request::<requests::ResourcesRead>(types::ResourcesReadParams { uri, meta: None })
+                .await?;
+            Ok(response.contents)
+        })
+    }
+
+    /// Asks the server to send [`Event::ResourceUpdated`] when the resource at `uri` changes.
+    /// Every call must be balanced by a call to [`Self::unsubscribe_from_resource`].
+    pub fn subscribe_to_resource(
+        &mut self,
+        id: &ContextServerId,
+        uri: Url,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        let subscribers = self
+            .resource_subscriptions
+            .entry(id.clone())
+            .or_default()
+            .entry(uri.clone())
+            .or_default();
+        *subscribers += 1;
+        if *subscribers > 1 {
+            return Task::ready(Ok(()));
+        }
+        self.send_resource_subscription(id, uri, cx)
+    }
+
+    pub fn unsubscribe_from_resource(
+        &mut self,
+        id: &ContextServerId,
+        uri: &Url,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        let Some(server_subscriptions) = self.resource_subscriptions.get_mut(id) else {
+            return Task::ready(Ok(()));
+        };
+        let Some(subscribers) = server_subscriptions.get_mut(uri) else {
+            return Task::ready(Ok(()));
+        };
+        *subscribers -= 1;
+        if *subscribers > 0 {
+            return Task::ready(Ok(()));
+        }
+        server_subscriptions.remove(uri);
+
+        let Some(client) = self.running_client(id).ok() else {
+            return Task::ready(Ok(()));
+        };
+        if !client.capable(ServerCapability::ResourceSubscriptions) {
+            return Task::ready(Ok(()));
+        }
+        let uri = uri.clone();
+        cx.foreground_executor().spawn(async move {
+            client
+                .request::<requests::ResourcesUnsubscribe>(types::ResourcesUnsubscribeParams {
+                    uri,
+                    meta: None,
+                })
+                .await
+        })
+    }
+
+    fn send_resource_subscription(
+        &self,
+        id: &ContextServerId,
+        uri: Url,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        // Subscriptions are sent again once the server is (re)started.
+        let Some(client) = self.running_client(id).ok() else {
+            return Task::ready(Ok(()));
+        };
+        if !client.capable(ServerCapability::ResourceSubscriptions) {
+            return Task::ready(Ok(()));
+        }
+        cx.foreground_executor().spawn(async move {
+            client
+                .request::<requests::ResourcesSubscribe>(types::ResourcesSubscribeParams {
+                    uri,
+                    meta: None,
+                })
+                .await
+        })
+    }
+
     pub fn start_server(&mut self, server: Arc<ContextServer>, cx: &mut Context<Self>) {
         cx.spawn(async move |this, cx| {
             let this = this.upgrade().context("Context server store dropped")?;
@@ -407,7 +589,13 @@ impl ContextServerStore {
             let server = server.clone();
             let configuration = configuration.clone();
             async move |this, cx| {
-                match server.clone().start(cx).await {
+                let notification_handlers = Self::notification_handlers(&id, &this);
+                let request_handlers = Self::request_handlers(&id, &this);
+                match server
+                    .clone()
+                    .start_with_handlers(notification_handlers, request_handlers, cx)
+                    .await
+                {
                     Ok(_) => {
                         debug_assert!(server.client().is_some());
 
@@ -453,6 +641,87 @@ impl ContextServerStore {
         );
     }
 
+    #[allow(clippy::type_complexity)]
+    fn notification_handlers(
+        server_id: &ContextServerId,
+        this: &WeakEntity<Self>,
+    ) -> Vec<(
+        &'static str,
+        Box<dyn 'static + Send + FnMut(serde_json::Value, AsyncApp)>,
+    )> {
+        fn emit(
+            this: WeakEntity<ContextServerStore>,
+            event: impl 'static + Send + Fn(serde_json::Value) -> Option<Event>,
+        ) -> Box<dyn 'static + Send + FnMut(serde_json::Value, AsyncApp)> {
+            Box::new(move |params, mut cx| {
+                if let Some(event) = event(params) {
+                    this.update(&mut cx, |_, cx| cx.emit(event)).ok();
+                }
+            })
+        }
+
+        vec![
+            (types::notifications::ResourcesUpdated::METHOD, {
+                let server_id = server_id.clone();
+                emit(this.clone(), move |params| {
+                    let params = serde_json::from_value::<types::ResourcesUpdatedParams>(params)
+                        .log_err()?;
+                    Some(Event::ResourceUpdated {
+                        server_id: server_id.clone(),
+                        uri: params.uri,
+                    })
+                })
+            }),
+            (types::notifications::ResourcesListChanged::METHOD, {
+                let server_id = server_id.clone();
+                emit(this.clone(), move |_| {
+                    Some(Event::ResourcesChanged {
+                        server_id: server_id.clone(),
+                    })
+                })
+            }),
+            (types::notifications::PromptsListChanged::METHOD, {
+                let server_id = server_id.clone();
+                emit(this.clone(), move |_| {
+                    Some(Event::PromptsChanged {
+                        server_id: server_id.clone(),
+                    })
+                })
+            }),
+        ]
+    }
+
+    fn request_handlers(
+        server_id: &ContextServerId,
+        this: &WeakEntity<Self>,
+    ) -> Vec<(&'static str, IncomingRequestHandler)> {
+        let server_id = server_id.clone();
+        let this = this.clone();
+        vec![(
+            requests::CreateMessage::METHOD,
+            Box::new(move |params, cx| {
+                let server_id = server_id.clone();
+                let this = this.clone();
+                cx.spawn(async move |cx| {
+                    let params: CreateMessageRequest = serde_json::from_value(params)?;
+                    let (response_channel, response) = smol::channel::bounded(1);
+                    this.update(cx, |_, cx| {
+                        cx.emit(Event::SamplingRequested(ContextServerSamplingRequest {
+                            server_id,
+                            params: Arc::new(params),
+                            response_channel,
+                        }))
+                    })?;
+                    let result = response
+                        .recv()
+                        .await
+                        .map_err(|_| anyhow!("Sampling request was not handled"))??;
+                    Ok(serde_json::to_value(result)?)
+                })
+            }),
+        )]
+    }
+
     fn remove_server(&mut self, id: &ContextServerId, cx: &mut Context<Self>) -> Result<()> {
         let state = self
             .servers
@@ -490,13 +759,16 @@ impl ContextServerStore {
             });
 
         if let Some(factory) = self.context_server_factory.as_ref() {
-            factory(id, configuration)
-        } else {
-            Arc::new(ContextServer::stdio(
-                id,
-                configuration.command().clone(),
-                root_path,
-            ))
+            return factory(id, configuration);
+        }
+        match configuration.as_ref() {
+            ContextServerConfiguration::Http { endpoint } => {
+                Arc::new(ContextServer::http(id, endpoint.clone(), cx.http_client()))
+            }
+            ContextServerConfiguration::Custom { command }
+            | ContextServerConfiguration::Extension { command, .. } => {
+                Arc::new(ContextServer::stdio(id, command.clone(), root_path))
+            }
         }
     }
 
@@ -523,6 +795,14 @@ impl ContextServerStore {
     ) {
         let status = ContextServerStatus::from_state(&state);
         self.servers.insert(id.clone(), state);
+        if status == ContextServerStatus::Running
+            && let Some(subscriptions) = self.resource_subscriptions.get(&id)
+        {
+            for uri in subscriptions.keys() {
+                self.send_resource_subscription(&id, uri.clone(), cx)
+                    .detach_and_log_err(cx);
+            }
+        }
         cx.emit(Event::ServerStatusChanged {
             server_id: id,
             status,
@@ -643,7 +923,8 @@ mod tests {
         FakeFs, Project, context_server_store::registry::ContextServerDescriptor,
         project_settings::ProjectSettings,
     };
-    use context_server::test::create_fake_transport;
+    use context_server::test::{FakeTransport, create_fake_transport};
+    use context_server::types::{ResourcesCapabilities, ServerCapabilities};
     use gpui::{AppContext, TestAppContext, UpdateGlobal as _};
     use serde_json::json;
     use std::{cell::RefCell, path::PathBuf, rc::Rc};
@@ -1203,6 +1484,201 @@ mod tests {
         }
     }
 
+    #[gpui::test]
+    async fn test_context_server_resources(cx: &mut TestAppContext) {
+        const SERVER_ID: &str = "mcp-1";
+
+        let (_fs, project) = setup_context_server_test(
+            cx,
+            json!({"code.rs": ""}),
+            vec![(SERVER_ID.into(), dummy_server_settings())],
+        )
+        .await;
+        let registry = cx.new(|_| ContextServerDescriptorRegistry::new());
+        let store = cx.new(|cx| {
+            ContextServerStore::test(
+                registry.clone(),
+                project.read(cx).worktree_store(),
+                project.downgrade(),
+                cx,
+            )
+        });
+
+        let subscribed_uris = Arc::new(parking_lot::Mutex::new(Vec::new()));
+        let unsubscribed_uris = Arc::new(parking_lot::Mutex::new(Vec::new()));
+        let transport = FakeTransport::new(cx.executor())
+            .on_request::<requests::Initialize, _>(|_| async {
+                initialize_response(ServerCapabilities {
+                    resources: Some(ResourcesCapabilities {
+                        subscribe: Some(true),
+                        list_changed: None,
+                    }),
+                    ..Default::default()
+                })
+            })
+            .on_request::<requests::ResourcesList, _>(|params| async move {
+                let (name, next_cursor) = match params.cursor {
+                    None => ("first", Some("page-2".to_string())),
+                    Some(_) => ("second", None),
+                };
+                types::ResourcesListResponse {
+                    resources: vec![types::Resource {
+                        uri: Url::parse(&format!("file:///{name}.txt")).unwrap(),
+                        name: name.to_string(),
+                        description: None,
+                        mime_type: None,
+                    }],
+                    next_cursor,
+                    meta: None,
+                }
+            })
+            .on_request::<requests::ResourcesRead, _>(|params| async move {
+                types::ResourcesReadResponse {
+                    contents: vec![types::ResourceContentsType::Text(
+                        types::TextResourceContents {
+                            text: format!("contents of {}", params.uri),
+                            uri: params.uri,
+                            mime_type: None,
+                        },
+                    )],
+                    meta: None,
+                }
+            })
+            .on_request::<requests::ResourcesSubscribe, _>({
+                let subscribed_uris = subscribed_uris.clone();
+                move |params| {
+                    subscribed_uris.lock().push(params.uri);
+                    async {}
+                }
+            })
+            .on_request::<requests::ResourcesUnsubscribe, _>({
+                let unsubscribed_uris = unsubscribed_uris.clone();
+                move |params| {
+                    unsubscribed_uris.lock().push(params.uri);
+                    async {}
+                }
+            });
+        let server_id = ContextServerId(SERVER_ID.into());
+        let server = Arc::new(ContextServer::new(server_id.clone(), Arc::new(transport)));
+        store.update(cx, |store, cx| store.start_server(server, cx));
+        cx.run_until_parked();
+
+        let resources = store
+            .update(cx, |store, cx| store.list_resources(&server_id, cx))
+            .await
+            .unwrap();
+        assert_eq!(
+            resources
+                .iter()
+                .map(|resource| resource.name.as_str())
+                .collect::<Vec<_>>(),
+            ["first", "second"],
+            "every page of resources should be listed"
+        );
+
+        let uri = Url::parse("file:///first.txt").unwrap();
+        let contents = store
+            .update(cx, |store, cx| {
+                store.read_resource(&server_id, uri.clone(), cx)
+            })
+            .await
+            .unwrap();
+        match contents.as_slice() {
+            [types::ResourceContentsType::Text(contents)] => {
+                assert_eq!(contents.text, "contents of file:///first.txt")
+            }
+            contents => panic!("unexpected resource contents: {contents:?}"),
+        }
+
+        for _ in 0..2 {
+            store
+                .update(cx, |store, cx| {
+                    store.subscribe_to_resource(&server_id, uri.clone(), cx)
+                })
+                .await
+                .unwrap();
+        }
+        assert_eq!(
+            *subscribed_uris.lock(),
+            [uri.clone()],
+            "the server should only be asked once to send updates for the same resource"
+        );
+
+        store
+            .update(cx, |store, cx| {
+                store.unsubscribe_from_resource(&server_id, &uri, cx)
+            })
+            .await
+            .unwrap();
+        assert!(
+            unsubscribed_uris.lock().is_empty(),
+            "the resource should stay subscribed while it has subscribers"
+        );
+        store
+            .update(cx, |store, cx| {
+                store.unsubscribe_from_resource(&server_id, &uri, cx)
+            })
+            .await
+            .unwrap();
+        assert_eq!(*unsubscribed_uris.lock(), [uri]);
+    }
+
+    #[gpui::test]
+    async fn test_context_server_unknown_request(cx: &mut TestAppContext) {
+        const SERVER_ID: &str = "mcp-1";
+
+        let (_fs, project) = setup_context_server_test(
+            cx,
+            json!({"code.rs": ""}),
+            vec![(SERVER_ID.into(), dummy_server_settings())],
+        )
+        .await;
+        let registry = cx.new(|_| ContextServerDescriptorRegistry::new());
+        let store = cx.new(|cx| {
+            ContextServerStore::test(
+                registry.clone(),
+                project.read(cx).worktree_store(),
+                project.downgrade(),
+                cx,
+            )
+        });
+
+        let transport = Arc::new(create_fake_transport(SERVER_ID, cx.executor()));
+        let server = Arc::new(ContextServer::new(
+            ContextServerId(SERVER_ID.into()),
+            transport.clone(),
+        ));
+        store.update(cx, |store, cx| store.start_server(server, cx));
+        cx.run_until_parked();
+
+        transport.send_to_client(json!({
+            "jsonrpc": "2.0",
+            "id": 7,
+            "method": "unknown/method",
+        }));
+        cx.run_until_parked();
+
+        let responses = transport.client_responses();
+        assert_eq!(responses.len(), 1);
+        assert_eq!(responses[0]["id"], json!(7));
+        assert_eq!(
+            responses[0]["error"]["code"],
+            json!(context_server::client::METHOD_NOT_FOUND)
+        );
+    }
+
+    fn initialize_response(capabilities: ServerCapabilities) -> types::InitializeResponse {
+        types::InitializeResponse {
+            protocol_version: types::ProtocolVersion(types::LATEST_PROTOCOL_VERSION.to_string()),
+            capabilities,
+            server_info: types::Implementation {
+                name: "fake".to_string(),
+                version: "1.0.0".to_string(),
+            },
+            meta: None,
+        }
+    }
+
     fn set_context_server_configuration(
         context_servers: Vec<(Arc<str>, settings::ContextServerSettingsContent)>,
         cx: &mut TestAppContext,
@@ -1280,6 +1756,7 @@ mod tests {
                         ix += 1;
                         *received_event_count.borrow_mut() += 1;
                     }
+                    _ => {}
                 }
             });
             ServerEvents {
@@ -246,7 +246,7 @@ impl DapLocator for GoLocator {
 mod tests {
     use super::*;
     use gpui::TestAppContext;
-    use task::{HideStrategy, RevealStrategy, RevealTarget, Shell, TaskTemplate};
+    use task::{DependsOrder, HideStrategy, RevealStrategy, RevealTarget, Shell, TaskTemplate};
 
     #[gpui::test]
     async fn test_create_scenario_for_go_build(_: &mut TestAppContext) {
@@ -266,6 +266,10 @@ mod tests {
             tags: vec![],
             show_summary: true,
             show_command: true,
+            depends_on: Vec::new(),
+            depends_order: DependsOrder::Parallel,
+            problem_matcher: Vec::new(),
+            inputs: Vec::new(),
         };
 
         let scenario = locator
@@ -293,6 +297,10 @@ mod tests {
             tags: vec![],
             show_summary: true,
             show_command: true,
+            depends_on: Vec::new(),
+            depends_order: DependsOrder::Parallel,
+            problem_matcher: Vec::new(),
+            inputs: Vec::new(),
         };
 
         let scenario = locator
@@ -431,6 +439,10 @@ mod tests {
             tags: vec![],
             show_summary: true,
             show_command: true,
+            depends_on: Vec::new(),
+            depends_order: DependsOrder::Parallel,
+            problem_matcher: Vec::new(),
+            inputs: Vec::new(),
         };
 
         let scenario = locator
@@ -22,16 +22,20 @@ use futures::{
 use git::{
     BuildPermalinkParams, GitHostingProviderRegistry, Oid, WORK_DIRECTORY_REPO_PATH,
     blame::Blame,
+    history::{LogEntry, LogOptions},
     parse_git_remote_url,
+    rebase::{RebaseCommand, RebaseStop, RebaseTodo},
     repository::{
         Branch, CommitDetails, CommitDiff, CommitFile, CommitOptions, DiffType, FetchOptions,
         GitRepository, GitRepositoryCheckpoint, PushOptions, Remote, RemoteCommandOutput, RepoPath,
         ResetMode, UpstreamTrackingStatus,
     },
+    sequencer::{SequencerCommand, SequencerOperation, SequencerState},
     stash::{GitStash, StashEntry},
     status::{
         FileStatus, GitSummary, StatusCode, TrackedStatus, UnmergedStatus, UnmergedStatusCode,
     },
+    worktree::Worktree,
 };
 use gpui::{
     App, AppContext, AsyncApp, Context, Entity, EventEmitter, SharedString, Subscription, Task,
@@ -2949,7 +2953,51 @@ pub fn proto_to_stash(entry: &proto::StashEntry) -> Result<StashEntry> {
     })
 }
 
+/// The refs git sets while an operation is in progress, in the order of [`MergeDetails::heads`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum MergeHead {
+    Merge,
+    CherryPick,
+    Rebase,
+    Revert,
+    Apply,
+}
+
+impl MergeHead {
+    const ALL: [MergeHead; 5] = [
+        MergeHead::Merge,
+        MergeHead::CherryPick,
+        MergeHead::Rebase,
+        MergeHead::Revert,
+        MergeHead::Apply,
+    ];
+
+    fn ref_name(self) -> &'static str {
+        match self {
+            MergeHead::Merge => "MERGE_HEAD",
+            MergeHead::CherryPick => "CHERRY_PICK_HEAD",
+            MergeHead::Rebase => "REBASE_HEAD",
+            MergeHead::Revert => "REVERT_HEAD",
+            MergeHead::Apply => "APPLY_HEAD",
+        }
+    }
+}
+
 impl MergeDetails {
+    /// Returns the cherry-pick, revert or merge in progress, based on which of its heads is set.
+    pub fn sequencer_operation(&self) -> Option<SequencerOperation> {
+        let is_set = |head: MergeHead| self.heads.get(head as usize).is_some_and(Option::is_some);
+        if is_set(MergeHead::Merge) {
+            Some(SequencerOperation::Merge)
+        } else if is_set(MergeHead::CherryPick) {
+            Some(SequencerOperation::CherryPick)
+        } else if is_set(MergeHead::Revert) {
+            Some(SequencerOperation::Revert)
+        } else {
+            None
+        }
+    }
+
     async fn load(
         backend: &Arc<dyn GitRepository>,
         status: &SumTree<StatusEntry>,
@@ -2958,13 +3006,12 @@ impl MergeDetails {
         log::debug!("load merge details");
         let message = backend.merge_message().await;
         let heads = backend
-            .revparse_batch(vec![
-                "MERGE_HEAD".into(),
-                "CHERRY_PICK_HEAD".into(),
-                "REBASE_HEAD".into(),
-                "REVERT_HEAD".into(),
-                "APPLY_HEAD".into(),
-            ])
+            .revparse_batch(
+                MergeHead::ALL
+                    .iter()
+                    .map(|head| head.ref_name().into())
+                    .collect(),
+            )
             .await
             .log_err()
             .unwrap_or_default()
@@ -3078,6 +3125,16 @@ impl Repository {
         self.git_store.upgrade()
     }
 
+    /// Whether git runs on this machine, rather than on a remote host or a collaborator's.
+    ///
+    /// Interactive rebases, the history, cherry-picks, reverts, merges and worktrees aren't
+    /// forwarded to remote repositories, so they're only offered for local ones.
+    pub fn is_local(&self, cx: &App) -> bool {
+        self.git_store
+            .upgrade()
+            .is_some_and(|git_store| git_store.read(cx).is_local())
+    }
+
     fn reload_buffer_diff_bases(&mut self, cx: &mut Context<Self>) {
         let this = cx.weak_entity();
         let git_store = self.git_store.clone();
@@ -4274,6 +4331,231 @@ impl Repository {
         })
     }
 
+    pub fn rebase_todo(&mut self, upstream: String) -> oneshot::Receiver<Result<RebaseTodo>> {
+        self.send_job(None, move |repo, _| async move {
+            match repo {
+                RepositoryState::Local { backend, .. } => backend.rebase_todo(upstream).await,
+                RepositoryState::Remote { .. } => anyhow::bail!("not implemented yet"),
+            }
+        })
+    }
+
+    pub fn rebase_interactive(
+        &mut self,
+        todo: RebaseTodo,
+    ) -> oneshot::Receiver<Result<Option<RebaseStop>>> {
+        self.send_job(
+            Some("git rebase --interactive".into()),
+            move |repo, _| async move {
+                match repo {
+                    RepositoryState::Local {
+                        backend,
+                        environment,
+                        ..
+                    } => backend.rebase_interactive(todo, environment).await,
+                    RepositoryState::Remote { .. } => anyhow::bail!("not implemented yet"),
+                }
+            },
+        )
+    }
+
+    pub fn rebase_command(
+        &mut self,
+        command: RebaseCommand,
+    ) -> oneshot::Receiver<Result<Option<RebaseStop>>> {
+        self.send_job(
+            Some(format!("git rebase {}", command.as_arg()).into()),
+            move |repo, _| async move {
+                match repo {
+                    RepositoryState::Local {
+                        backend,
+                        environment,
+                        ..
+                    } => backend.rebase_command(command, environment).await,
+                    RepositoryState::Remote { .. } => anyhow::bail!("not implemented yet"),
+                }
+            },
+        )
+    }
+
+    pub fn rebase_stop(&mut self) -> oneshot::Receiver<Result<Option<RebaseStop>>> {
+        self.send_job(None, move |repo, _| async move {
+            match repo {
+                RepositoryState::Local { backend, .. } => backend.rebase_stop().await,
+                RepositoryState::Remote { .. } => anyhow::bail!("not implemented yet"),
+            }
+        })
+    }
+
+    pub fn cherry_pick(
+        &mut self,
+        commits: Vec<String>,
+    ) -> oneshot::Receiver<Result<Option<SequencerState>>> {
+        self.send_job(Some("git cherry-pick".into()), move |repo, _| async move {
+            match repo {
+                RepositoryState::Local {
+                    backend,
+                    environment,
+                    ..
+                } => backend.cherry_pick(commits, environment).await,
+                RepositoryState::Remote { .. } => anyhow::bail!("not implemented yet"),
+            }
+        })
+    }
+
+    pub fn revert(
+        &mut self,
+        commits: Vec<String>,
+    ) -> oneshot::Receiver<Result<Option<SequencerState>>> {
+        self.send_job(Some("git revert".into()), move |repo, _| async move {
+            match repo {
+                RepositoryState::Local {
+                    backend,
+                    environment,
+                    ..
+                } => backend.revert(commits, environment).await,
+                RepositoryState::Remote { .. } => anyhow::bail!("not implemented yet"),
+            }
+        })
+    }
+
+    pub fn merge(&mut self, revision: String) -> oneshot::Receiver<Result<Option<SequencerState>>> {
+        self.send_job(
+            Some(format!("git merge {revision}").into()),
+            move |repo, _| async move {
+                match repo {
+                    RepositoryState::Local {
+                        backend,
+                        environment,
+                        ..
+                    } => backend.merge(revision, environment).await,
+                    RepositoryState::Remote { .. } => anyhow::bail!("not implemented yet"),
+                }
+            },
+        )
+    }
+
+    pub fn sequencer_command(
+        &mut self,
+        operation: SequencerOperation,
+        command: SequencerCommand,
+    ) -> oneshot::Receiver<Result<Option<SequencerState>>> {
+        self.send_job(
+            Some(format!("git {} {}", operation.as_command(), command.as_arg()).into()),
+            move |repo, _| async move {
+                match repo {
+                    RepositoryState::Local {
+                        backend,
+                        environment,
+                        ..
+                    } => {
+                        backend
+                            .sequencer_command(operation, command, environment)
+                            .await
+                    }
+                    RepositoryState::Remote { .. } => anyhow::bail!("not implemented yet"),
+                }
+            },
+        )
+    }
+
+    pub fn sequencer_state(&mut self) -> oneshot::Receiver<Result<Option<SequencerState>>> {
+        self.send_job(None, move |repo, _| async move {
+            match repo {
+                RepositoryState::Local { backend, .. } => backend.sequencer_state().await,
+                RepositoryState::Remote { .. } => anyhow::bail!("not implemented yet"),
+            }
+        })
+    }
+
+    pub fn log(&mut self, options: LogOptions) -> oneshot::Receiver<Result<Vec<LogEntry>>> {
+        self.send_job(None, move |repo, _| async move {
+            match repo {
+                RepositoryState::Local { backend, .. } => backend.log(options).await,
+                RepositoryState::Remote { .. } => anyhow::bail!("not implemented yet"),
+            }
+        })
+    }
+
+    pub fn load_text_at_revision(
+        &mut self,
+        revision: String,
+        path: RepoPath,
+    ) -> oneshot::Receiver<Result<Option<String>>> {
+        self.send_job(None, move |repo, _| async move {
+            match repo {
+                RepositoryState::Local { backend, .. } => {
+                    backend.load_text_at_revision(revision, path).await
+                }
+                RepositoryState::Remote { .. } => anyhow::bail!("not implemented yet"),
+            }
+        })
+    }
+
+    pub fn worktrees(&mut self) -> oneshot::Receiver<Result<Vec<Worktree>>> {
+        self.send_job(None, move |repo, _| async move {
+            match repo {
+                RepositoryState::Local { backend, .. } => backend.worktrees().await,
+                RepositoryState::Remote { .. } => anyhow::bail!("not implemented yet"),
+            }
+        })
+    }
+
+    pub fn create_worktree(
+        &mut self,
+        path: PathBuf,
+        branch: String,
+        create_branch: bool,
+    ) -> oneshot::Receiver<Result<()>> {
+        self.send_job(
+            Some(format!("git worktree add {}", path.display()).into()),
+            move |repo, _| async move {
+                match repo {
+                    RepositoryState::Local { backend, .. } => {
+                        backend.create_worktree(path, branch, create_branch).await
+                    }
+                    RepositoryState::Remote { .. } => anyhow::bail!("not implemented yet"),
+                }
+            },
+        )
+    }
+
+    pub fn lock_worktree(
+        &mut self,
+        path: PathBuf,
+        reason: Option<String>,
+    ) -> oneshot::Receiver<Result<()>> {
+        self.send_job(None, move |repo, _| async move {
+            match repo {
+                RepositoryState::Local { backend, .. } => backend.lock_worktree(path, reason).await,
+                RepositoryState::Remote { .. } => anyhow::bail!("not implemented yet"),
+            }
+        })
+    }
+
+    pub fn unlock_worktree(&mut self, path: PathBuf) -> oneshot::Receiver<Result<()>> {
+        self.send_job(None, move |repo, _| async move {
+            match repo {
+                RepositoryState::Local { backend, .. } => backend.unlock_worktree(path).await,
+                RepositoryState::Remote { .. } => anyhow::bail!("not implemented yet"),
+            }
+        })
+    }
+
+    pub fn remove_worktree(&mut self, path: PathBuf, force: bool) -> oneshot::Receiver<Result<()>> {
+        self.send_job(
+            Some(format!("git worktree remove {}", path.display()).into()),
+            move |repo, _| async move {
+                match repo {
+                    RepositoryState::Local { backend, .. } => {
+                        backend.remove_worktree(path, force).await
+                    }
+                    RepositoryState::Remote { .. } => anyhow::bail!("not implemented yet"),
+                }
+            },
+        )
+    }
+
     pub fn diff(&mut self, diff_type: DiffType, _cx: &App) -> oneshot::Receiver<Result<String>> {
         let id = self.id;
         self.send_job(None, move |repo, _cx| async move {
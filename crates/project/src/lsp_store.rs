@@ -14,11 +14,12 @@ pub mod json_language_server_ext;
 pub mod log_store;
 pub mod lsp_ext_command;
 pub mod rust_analyzer_ext;
+pub mod semantic_tokens;
 
 use crate::{
     CodeAction, ColorPresentation, Completion, CompletionDisplayOptions, CompletionResponse,
-    CompletionSource, CoreCompletion, DocumentColor, Hover, InlayHint, LocationLink, LspAction,
-    LspPullDiagnostics, ManifestProvidersStore, Project, ProjectItem, ProjectPath,
+    CompletionSource, CoreCompletion, DocumentColor, DocumentLink, Hover, InlayHint, LocationLink,
+    LspAction, LspPullDiagnostics, ManifestProvidersStore, Project, ProjectItem, ProjectPath,
     ProjectTransaction, PulledDiagnostics, ResolveState, Symbol,
     buffer_store::{BufferStore, BufferStoreEvent},
     environment::ProjectEnvironment,
@@ -26,6 +27,9 @@ use crate::{
     lsp_store::{
         self,
         log_store::{GlobalLogStore, LanguageServerKind},
+        semantic_tokens::{
+            SemanticTokens, SemanticTokensLegend, apply_lsp_edits, lsp_tokens_to_data,
+        },
     },
     manifest_tree::{
         LanguageServerTree, LanguageServerTreeNode, LaunchDisposition, ManifestQueryDelegate,
@@ -112,6 +116,7 @@ use std::{
     time::{Duration, Instant},
 };
 use sum_tree::Dimensions;
+use task::{ProblemSeverity, TaskProblem};
 use text::{Anchor, BufferId, LineEnding, OffsetRangeExt, ToPoint as _};
 
 use util::{
@@ -131,6 +136,9 @@ pub use worktree::{
 
 const SERVER_LAUNCHING_BEFORE_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
 pub const SERVER_PROGRESS_THROTTLE_TIMEOUT: Duration = Duration::from_millis(100);
+/// The diagnostics source the problems found in task output are reported under.
+/// It's not a language server and never gets assigned to one.
+pub const TASK_DIAGNOSTICS_SERVER_ID: LanguageServerId = LanguageServerId(usize::MAX);
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum FormatTrigger {
@@ -222,6 +230,9 @@ pub struct LocalLspStore {
     registered_buffers: HashMap<BufferId, usize>,
     buffers_opened_in_servers: HashMap<BufferId, HashSet<LanguageServerId>>,
     buffer_pull_diagnostics_result_ids: HashMap<LanguageServerId, HashMap<PathBuf, Option<String>>>,
+    /// Diagnostics produced by the problem matchers of tasks, by task label and file path.
+    task_diagnostics:
+        HashMap<String, HashMap<PathBuf, Vec<DiagnosticEntry<Unclipped<PointUtf16>>>>>,
 }
 
 impl LocalLspStore {
@@ -838,6 +849,28 @@ impl LocalLspStore {
             })
             .detach();
 
+        language_server
+            .on_request::<lsp::request::SemanticTokensRefresh, _, _>({
+                let this = this.clone();
+                move |(), cx| {
+                    let this = this.clone();
+                    let mut cx = cx.clone();
+                    async move {
+                        this.update(&mut cx, |this, cx| {
+                            cx.emit(LspStoreEvent::RefreshSemanticTokens);
+                            this.downstream_client.as_ref().map(|(client, project_id)| {
+                                client.send(proto::RefreshSemanticTokens {
+                                    project_id: *project_id,
+                                })
+                            })
+                        })?
+                        .transpose()?;
+                        Ok(())
+                    }
+                }
+            })
+            .detach();
+
         language_server
             .on_request::<lsp::request::WorkspaceDiagnosticRefresh, _, _>({
                 let this = this.clone();
@@ -3486,7 +3519,9 @@ pub struct LspStore {
         HashMap<WorktreeId, HashMap<Arc<Path>, HashMap<LanguageServerId, DiagnosticSummary>>>,
     pub lsp_server_capabilities: HashMap<LanguageServerId, lsp::ServerCapabilities>,
     lsp_document_colors: HashMap<BufferId, DocumentColorData>,
+    lsp_semantic_tokens: HashMap<BufferId, SemanticTokensData>,
     lsp_code_lens: HashMap<BufferId, CodeLensData>,
+    lsp_document_links: HashMap<BufferId, DocumentLinksData>,
     running_lsp_requests: HashMap<TypeId, (Global, HashMap<LspRequestId, Task<()>>)>,
 }
 
@@ -3496,8 +3531,17 @@ pub struct DocumentColors {
     pub cache_version: Option<usize>,
 }
 
+#[derive(Debug, Default, Clone)]
+pub struct BufferSemanticTokens {
+    pub servers: HashMap<LanguageServerId, Arc<SemanticTokens>>,
+    pub cache_version: Option<usize>,
+}
+
 type DocumentColorTask = Shared<Task<std::result::Result<DocumentColors, Arc<anyhow::Error>>>>;
+type SemanticTokensTask =
+    Shared<Task<std::result::Result<BufferSemanticTokens, Arc<anyhow::Error>>>>;
 type CodeLensTask = Shared<Task<std::result::Result<Option<Vec<CodeAction>>, Arc<anyhow::Error>>>>;
+type DocumentLinksTask = Shared<Task<std::result::Result<Vec<DocumentLink>, Arc<anyhow::Error>>>>;
 
 #[derive(Debug, Default)]
 struct DocumentColorData {
@@ -3507,6 +3551,16 @@ struct DocumentColorData {
     colors_update: Option<(Global, DocumentColorTask)>,
 }
 
+#[derive(Debug, Default)]
+struct SemanticTokensData {
+    tokens_for_version: Global,
+    tokens: HashMap<LanguageServerId, Arc<SemanticTokens>>,
+    /// The last result id and integer encoding reported by each server, for delta requests.
+    delta_bases: HashMap<LanguageServerId, (String, Vec<u32>)>,
+    cache_version: usize,
+    tokens_update: Option<(Global, SemanticTokensTask)>,
+}
+
 #[derive(Debug, Default)]
 struct CodeLensData {
     lens_for_version: Global,
@@ -3514,6 +3568,13 @@ struct CodeLensData {
     update: Option<(Global, CodeLensTask)>,
 }
 
+#[derive(Debug, Default)]
+struct DocumentLinksData {
+    links_for_version: Global,
+    links: HashMap<LanguageServerId, Vec<DocumentLink>>,
+    update: Option<(Global, DocumentLinksTask)>,
+}
+
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum LspFetchStrategy {
     IgnoreCache,
@@ -3538,6 +3599,7 @@ pub enum LspStoreEvent {
     Notification(String),
     RefreshInlayHints,
     RefreshCodeLens,
+    RefreshSemanticTokens,
     DiagnosticsUpdated {
         server_id: LanguageServerId,
         paths: Vec<ProjectPath>,
@@ -3598,6 +3660,8 @@ impl LspStore {
         client.add_entity_request_handler(Self::handle_open_buffer_for_symbol);
         client.add_entity_request_handler(Self::handle_refresh_inlay_hints);
         client.add_entity_request_handler(Self::handle_refresh_code_lens);
+        client.add_entity_request_handler(Self::handle_get_semantic_tokens);
+        client.add_entity_request_handler(Self::handle_refresh_semantic_tokens);
         client.add_entity_request_handler(Self::handle_on_type_formatting);
         client.add_entity_request_handler(Self::handle_apply_additional_edits_for_completion);
         client.add_entity_request_handler(Self::handle_register_buffer_with_language_servers);
@@ -3606,6 +3670,14 @@ impl LspStore {
         client.add_entity_request_handler(Self::handle_lsp_command::<GetCompletions>);
         client.add_entity_request_handler(Self::handle_lsp_command::<GetDocumentHighlights>);
         client.add_entity_request_handler(Self::handle_lsp_command::<GetDocumentSymbols>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<PrepareCallHierarchy>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<GetIncomingCalls>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<GetOutgoingCalls>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<PrepareTypeHierarchy>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<GetSupertypes>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<GetSubtypes>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<GetFoldingRanges>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<GetSelectionRanges>);
         client.add_entity_request_handler(Self::handle_lsp_command::<PrepareRename>);
         client.add_entity_request_handler(Self::handle_lsp_command::<PerformRename>);
         client.add_entity_request_handler(Self::handle_lsp_command::<LinkedEditingRange>);
@@ -3738,6 +3810,7 @@ impl LspStore {
                 registered_buffers: HashMap::default(),
                 buffers_opened_in_servers: HashMap::default(),
                 buffer_pull_diagnostics_result_ids: HashMap::default(),
+                task_diagnostics: HashMap::default(),
                 watched_manifest_filenames: ManifestProvidersStore::global(cx)
                     .manifest_file_names(),
             }),
@@ -3751,7 +3824,9 @@ impl LspStore {
             diagnostic_summaries: HashMap::default(),
             lsp_server_capabilities: HashMap::default(),
             lsp_document_colors: HashMap::default(),
+            lsp_semantic_tokens: HashMap::default(),
             lsp_code_lens: HashMap::default(),
+            lsp_document_links: HashMap::default(),
             running_lsp_requests: HashMap::default(),
             active_entry: None,
             _maintain_workspace_config,
@@ -3812,7 +3887,9 @@ impl LspStore {
             diagnostic_summaries: HashMap::default(),
             lsp_server_capabilities: HashMap::default(),
             lsp_document_colors: HashMap::default(),
+            lsp_semantic_tokens: HashMap::default(),
             lsp_code_lens: HashMap::default(),
+            lsp_document_links: HashMap::default(),
             running_lsp_requests: HashMap::default(),
             active_entry: None,
 
@@ -4105,7 +4182,9 @@ impl LspStore {
                     };
                     if refcount == 0 {
                         lsp_store.lsp_document_colors.remove(&buffer_id);
+                        lsp_store.lsp_semantic_tokens.remove(&buffer_id);
                         lsp_store.lsp_code_lens.remove(&buffer_id);
+                        lsp_store.lsp_document_links.remove(&buffer_id);
                         let local = lsp_store.as_local_mut().unwrap();
                         local.registered_buffers.remove(&buffer_id);
                         local.buffers_opened_in_servers.remove(&buffer_id);
@@ -5758,6 +5837,159 @@ impl LspStore {
         }
     }
 
+    pub fn document_links(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        cx: &mut Context<Self>,
+    ) -> DocumentLinksTask {
+        let version_queried_for = buffer.read(cx).version();
+        let buffer_id = buffer.read(cx).remote_id();
+
+        if let Some(cached_data) = self.lsp_document_links.get(&buffer_id)
+            && !version_queried_for.changed_since(&cached_data.links_for_version)
+        {
+            let has_different_servers = self.as_local().is_some_and(|local| {
+                local
+                    .buffers_opened_in_servers
+                    .get(&buffer_id)
+                    .cloned()
+                    .unwrap_or_default()
+                    != cached_data.links.keys().copied().collect()
+            });
+            if !has_different_servers {
+                return Task::ready(Ok(cached_data.links.values().flatten().cloned().collect()))
+                    .shared();
+            }
+        }
+
+        let lsp_data = self.lsp_document_links.entry(buffer_id).or_default();
+        if let Some((updating_for, running_update)) = &lsp_data.update
+            && !version_queried_for.changed_since(updating_for)
+        {
+            return running_update.clone();
+        }
+        let buffer = buffer.clone();
+        let query_version_queried_for = version_queried_for.clone();
+        let new_task = cx
+            .spawn(async move |lsp_store, cx| {
+                cx.background_executor()
+                    .timer(Duration::from_millis(30))
+                    .await;
+                let fetched_links = lsp_store
+                    .update(cx, |lsp_store, cx| {
+                        lsp_store.fetch_document_links(&buffer, cx)
+                    })
+                    .map_err(Arc::new)?
+                    .await
+                    .context("fetching document links")
+                    .map_err(Arc::new);
+                let fetched_links = match fetched_links {
+                    Ok(fetched_links) => fetched_links,
+                    Err(e) => {
+                        lsp_store
+                            .update(cx, |lsp_store, _| {
+                                lsp_store
+                                    .lsp_document_links
+                                    .entry(buffer_id)
+                                    .or_default()
+                                    .update = None;
+                            })
+                            .ok();
+                        return Err(e);
+                    }
+                };
+
+                lsp_store
+                    .update(cx, |lsp_store, _| {
+                        let lsp_data = lsp_store.lsp_document_links.entry(buffer_id).or_default();
+                        if let Some(fetched_links) = fetched_links {
+                            if lsp_data.links_for_version == query_version_queried_for {
+                                lsp_data.links.extend(fetched_links);
+                            } else if !lsp_data
+                                .links_for_version
+                                .changed_since(&query_version_queried_for)
+                            {
+                                lsp_data.links_for_version = query_version_queried_for;
+                                lsp_data.links = fetched_links;
+                            }
+                        }
+                        lsp_data.update = None;
+                        lsp_data.links.values().flatten().cloned().collect()
+                    })
+                    .map_err(Arc::new)
+            })
+            .shared();
+        lsp_data.update = Some((version_queried_for, new_task.clone()));
+        new_task
+    }
+
+    fn fetch_document_links(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Option<HashMap<LanguageServerId, Vec<DocumentLink>>>>> {
+        if let Some((upstream_client, project_id)) = self.upstream_client() {
+            let request = GetDocumentLinks;
+            if !self.is_capable_for_proto_request(buffer, &request, cx) {
+                return Task::ready(Ok(None));
+            }
+            let request_task = upstream_client.request_lsp(
+                project_id,
+                LSP_REQUEST_TIMEOUT,
+                cx.background_executor().clone(),
+                request.to_proto(project_id, buffer.read(cx)),
+            );
+            let buffer = buffer.clone();
+            cx.spawn(async move |weak_lsp_store, cx| {
+                let Some(lsp_store) = weak_lsp_store.upgrade() else {
+                    return Ok(None);
+                };
+                let Some(responses) = request_task.await? else {
+                    return Ok(None);
+                };
+
+                let document_links = join_all(responses.payload.into_iter().map(|response| {
+                    let lsp_store = lsp_store.clone();
+                    let buffer = buffer.clone();
+                    let cx = cx.clone();
+                    async move {
+                        (
+                            LanguageServerId::from_proto(response.server_id),
+                            GetDocumentLinks
+                                .response_from_proto(response.response, lsp_store, buffer, cx)
+                                .await,
+                        )
+                    }
+                }))
+                .await;
+
+                let mut has_errors = false;
+                let document_links = document_links
+                    .into_iter()
+                    .filter_map(|(server_id, links)| match links {
+                        Ok(links) => Some((server_id, links)),
+                        Err(e) => {
+                            has_errors = true;
+                            log::error!("{e:#}");
+                            None
+                        }
+                    })
+                    .collect::<HashMap<_, _>>();
+                anyhow::ensure!(
+                    !has_errors || !document_links.is_empty(),
+                    "Failed to fetch document links"
+                );
+                Ok(Some(document_links))
+            })
+        } else {
+            let document_links_task =
+                self.request_multiple_lsp_locally(buffer, None::<usize>, GetDocumentLinks, cx);
+            cx.background_spawn(
+                async move { Ok(Some(document_links_task.await.into_iter().collect())) },
+            )
+        }
+    }
+
     #[inline(never)]
     pub fn completions(
         &self,
@@ -6806,6 +7038,307 @@ impl LspStore {
         }
     }
 
+    pub fn semantic_tokens(
+        &mut self,
+        fetch_strategy: LspFetchStrategy,
+        buffer: Entity<Buffer>,
+        cx: &mut Context<Self>,
+    ) -> Option<SemanticTokensTask> {
+        let version_queried_for = buffer.read(cx).version();
+        let buffer_id = buffer.read(cx).remote_id();
+
+        match fetch_strategy {
+            LspFetchStrategy::IgnoreCache => {}
+            LspFetchStrategy::UseCache {
+                known_cache_version,
+            } => {
+                if let Some(cached_data) = self.lsp_semantic_tokens.get(&buffer_id)
+                    && !version_queried_for.changed_since(&cached_data.tokens_for_version)
+                {
+                    let has_different_servers = self.as_local().is_some_and(|local| {
+                        local
+                            .buffers_opened_in_servers
+                            .get(&buffer_id)
+                            .cloned()
+                            .unwrap_or_default()
+                            != cached_data.tokens.keys().copied().collect()
+                    });
+                    if !has_different_servers {
+                        if Some(cached_data.cache_version) == known_cache_version {
+                            return None;
+                        } else {
+                            return Some(
+                                Task::ready(Ok(BufferSemanticTokens {
+                                    servers: cached_data.tokens.clone(),
+                                    cache_version: Some(cached_data.cache_version),
+                                }))
+                                .shared(),
+                            );
+                        }
+                    }
+                }
+            }
+        }
+
+        let lsp_data = self.lsp_semantic_tokens.entry(buffer_id).or_default();
+        if let Some((updating_for, running_update)) = &lsp_data.tokens_update
+            && !version_queried_for.changed_since(updating_for)
+        {
+            return Some(running_update.clone());
+        }
+        let query_version_queried_for = version_queried_for.clone();
+        let new_task = cx
+            .spawn(async move |lsp_store, cx| {
+                cx.background_executor()
+                    .timer(Duration::from_millis(30))
+                    .await;
+                let fetched_tokens = lsp_store
+                    .update(cx, |lsp_store, cx| {
+                        lsp_store.fetch_semantic_tokens_for_buffer(&buffer, cx)
+                    })?
+                    .await
+                    .context("fetching semantic tokens")
+                    .map_err(Arc::new);
+                let fetched_tokens = match fetched_tokens {
+                    Ok(fetched_tokens) => {
+                        if fetch_strategy != LspFetchStrategy::IgnoreCache
+                            && Some(true)
+                                == buffer
+                                    .update(cx, |buffer, _| {
+                                        buffer.version() != query_version_queried_for
+                                    })
+                                    .ok()
+                        {
+                            return Ok(BufferSemanticTokens::default());
+                        }
+                        fetched_tokens
+                    }
+                    Err(e) => {
+                        lsp_store
+                            .update(cx, |lsp_store, _| {
+                                lsp_store
+                                    .lsp_semantic_tokens
+                                    .entry(buffer_id)
+                                    .or_default()
+                                    .tokens_update = None;
+                            })
+                            .ok();
+                        return Err(e);
+                    }
+                };
+
+                lsp_store
+                    .update(cx, |lsp_store, _| {
+                        let lsp_data = lsp_store.lsp_semantic_tokens.entry(buffer_id).or_default();
+
+                        if let Some(fetched_tokens) = fetched_tokens {
+                            if lsp_data.tokens_for_version == query_version_queried_for {
+                                lsp_data.tokens.extend(fetched_tokens);
+                                lsp_data.cache_version += 1;
+                            } else if !lsp_data
+                                .tokens_for_version
+                                .changed_since(&query_version_queried_for)
+                            {
+                                lsp_data.tokens_for_version = query_version_queried_for;
+                                lsp_data.tokens = fetched_tokens;
+                                lsp_data.cache_version += 1;
+                            }
+                        }
+                        lsp_data.tokens_update = None;
+                        BufferSemanticTokens {
+                            servers: lsp_data.tokens.clone(),
+                            cache_version: Some(lsp_data.cache_version),
+                        }
+                    })
+                    .map_err(Arc::new)
+            })
+            .shared();
+        lsp_data.tokens_update = Some((version_queried_for, new_task.clone()));
+        Some(new_task)
+    }
+
+    fn fetch_semantic_tokens_for_buffer(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        cx: &mut Context<Self>,
+    ) -> Task<anyhow::Result<Option<HashMap<LanguageServerId, Arc<SemanticTokens>>>>> {
+        let buffer_id = buffer.read(cx).remote_id();
+        if let Some((client, project_id)) = self.upstream_client() {
+            let request = client.request(proto::GetSemanticTokens {
+                project_id,
+                buffer_id: buffer_id.to_proto(),
+                version: serialize_version(&buffer.read(cx).version()),
+            });
+            let buffer = buffer.clone();
+            return cx.spawn(async move |_, cx| {
+                let response = request.await?;
+                buffer
+                    .update(cx, |buffer, _| {
+                        buffer.wait_for_version(deserialize_version(&response.version))
+                    })?
+                    .await?;
+                Ok(Some(
+                    response
+                        .tokens
+                        .into_iter()
+                        .map(|tokens| {
+                            (
+                                LanguageServerId::from_proto(tokens.server_id),
+                                Arc::new(SemanticTokens::from_proto(tokens)),
+                            )
+                        })
+                        .collect(),
+                ))
+            });
+        }
+
+        let Some(local) = self.as_local() else {
+            return Task::ready(Ok(None));
+        };
+        let Some(abs_path) = File::from_dyn(buffer.read(cx).file())
+            .filter(|file| file.is_local())
+            .map(|file| file.abs_path(cx))
+        else {
+            return Task::ready(Ok(None));
+        };
+        let uri = match file_path_to_lsp_url(&abs_path) {
+            Ok(uri) => uri,
+            Err(e) => return Task::ready(Err(e)),
+        };
+        let delta_bases = self
+            .lsp_semantic_tokens
+            .get(&buffer_id)
+            .map(|lsp_data| lsp_data.delta_bases.clone())
+            .unwrap_or_default();
+        let servers = buffer.update(cx, |buffer, cx| {
+            local
+                .language_servers_for_buffer(buffer, cx)
+                .filter(|(_, server)| {
+                    local
+                        .buffers_opened_in_servers
+                        .get(&buffer_id)
+                        .is_some_and(|servers| servers.contains(&server.server_id()))
+                })
+                .filter_map(|(_, server)| {
+                    let capabilities = server.capabilities();
+                    let options = match capabilities.semantic_tokens_provider? {
+                        lsp::SemanticTokensServerCapabilities::SemanticTokensOptions(options) => {
+                            options
+                        }
+                        lsp::SemanticTokensServerCapabilities::SemanticTokensRegistrationOptions(
+                            options,
+                        ) => options.semantic_tokens_options,
+                    };
+                    let supports_delta = match options.full? {
+                        lsp::SemanticTokensFullOptions::Bool(full) => {
+                            if !full {
+                                return None;
+                            }
+                            false
+                        }
+                        lsp::SemanticTokensFullOptions::Delta { delta } => delta.unwrap_or(false),
+                    };
+                    let legend = Arc::new(SemanticTokensLegend::from_lsp(&options.legend));
+                    Some((server.clone(), legend, supports_delta))
+                })
+                .collect::<Vec<_>>()
+        });
+
+        let requests = servers
+            .into_iter()
+            .map(|(server, legend, supports_delta)| {
+                let server_id = server.server_id();
+                let text_document = lsp::TextDocumentIdentifier::new(uri.clone());
+                let delta_base = delta_bases
+                    .get(&server_id)
+                    .cloned()
+                    .filter(|_| supports_delta);
+                async move {
+                    let response = match delta_base {
+                        Some((previous_result_id, mut data)) => server
+                            .request::<lsp::request::SemanticTokensFullDeltaRequest>(
+                                lsp::SemanticTokensDeltaParams {
+                                    text_document,
+                                    previous_result_id,
+                                    work_done_progress_params: Default::default(),
+                                    partial_result_params: Default::default(),
+                                },
+                            )
+                            .await
+                            .into_response()
+                            .map(|result| match result {
+                                Some(lsp::SemanticTokensFullDeltaResult::Tokens(tokens)) => {
+                                    (tokens.result_id, lsp_tokens_to_data(&tokens.data))
+                                }
+                                Some(lsp::SemanticTokensFullDeltaResult::TokensDelta(delta)) => {
+                                    apply_lsp_edits(&mut data, delta.edits);
+                                    (delta.result_id, data)
+                                }
+                                Some(lsp::SemanticTokensFullDeltaResult::PartialTokensDelta {
+                                    edits,
+                                }) => {
+                                    apply_lsp_edits(&mut data, edits);
+                                    (None, data)
+                                }
+                                None => (None, Vec::new()),
+                            }),
+                        None => server
+                            .request::<lsp::request::SemanticTokensFullRequest>(
+                                lsp::SemanticTokensParams {
+                                    text_document,
+                                    work_done_progress_params: Default::default(),
+                                    partial_result_params: Default::default(),
+                                },
+                            )
+                            .await
+                            .into_response()
+                            .map(|result| match result {
+                                Some(lsp::SemanticTokensResult::Tokens(tokens)) => {
+                                    (tokens.result_id, lsp_tokens_to_data(&tokens.data))
+                                }
+                                Some(lsp::SemanticTokensResult::Partial(partial)) => {
+                                    (None, lsp_tokens_to_data(&partial.data))
+                                }
+                                None => (None, Vec::new()),
+                            }),
+                    };
+                    (server_id, legend, supports_delta, response)
+                }
+            })
+            .collect::<Vec<_>>();
+
+        cx.spawn(async move |lsp_store, cx| {
+            let responses = join_all(requests).await;
+            lsp_store.update(cx, |lsp_store, _| {
+                let lsp_data = lsp_store.lsp_semantic_tokens.entry(buffer_id).or_default();
+                let mut tokens = HashMap::default();
+                for (server_id, legend, supports_delta, response) in responses {
+                    match response {
+                        Ok((result_id, data)) => {
+                            let semantic_tokens = SemanticTokens::from_lsp_data(legend, &data);
+                            match result_id.filter(|_| supports_delta) {
+                                Some(result_id) => {
+                                    lsp_data.delta_bases.insert(server_id, (result_id, data));
+                                }
+                                None => {
+                                    lsp_data.delta_bases.remove(&server_id);
+                                }
+                            }
+                            tokens.insert(server_id, Arc::new(semantic_tokens));
+                        }
+                        Err(e) => {
+                            log::error!(
+                                "Failed to fetch semantic tokens from server {server_id}: {e:#}"
+                            );
+                            lsp_data.delta_bases.remove(&server_id);
+                        }
+                    }
+                }
+                Some(tokens)
+            })
+        })
+    }
+
     pub fn signature_help<T: ToPointUtf16>(
         &mut self,
         buffer: &Entity<Buffer>,
@@ -8126,6 +8659,17 @@ impl LspStore {
                 )
                 .await?;
             }
+            Request::GetDocumentLinks(get_document_links) => {
+                Self::query_lsp_locally::<GetDocumentLinks>(
+                    lsp_store,
+                    sender_id,
+                    lsp_request_id,
+                    get_document_links,
+                    None,
+                    cx.clone(),
+                )
+                .await?;
+            }
             Request::GetDefinition(get_definition) => {
                 let position = get_definition.position.clone().and_then(deserialize_anchor);
                 Self::query_lsp_locally::<GetDefinitions>(
@@ -9428,6 +9972,60 @@ impl LspStore {
         Ok(proto::Ack {})
     }
 
+    async fn handle_get_semantic_tokens(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GetSemanticTokens>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GetSemanticTokensResponse> {
+        let buffer_id = BufferId::new(envelope.payload.buffer_id)?;
+        let buffer = this.update(&mut cx, |this, cx| {
+            this.buffer_store.read(cx).get_existing(buffer_id)
+        })??;
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&envelope.payload.version))
+            })?
+            .await
+            .with_context(|| format!("waiting for version for buffer {}", buffer.entity_id()))?;
+
+        let version = buffer.read_with(&cx, |buffer, _| buffer.version())?;
+        let tokens_task = this.update(&mut cx, |lsp_store, cx| {
+            lsp_store.semantic_tokens(
+                LspFetchStrategy::UseCache {
+                    known_cache_version: None,
+                },
+                buffer,
+                cx,
+            )
+        })?;
+        let tokens = match tokens_task {
+            Some(tokens_task) => tokens_task
+                .await
+                .map_err(|e| anyhow!("{e:#}"))
+                .context("semantic tokens fetch")?,
+            None => BufferSemanticTokens::default(),
+        };
+        Ok(proto::GetSemanticTokensResponse {
+            tokens: tokens
+                .servers
+                .iter()
+                .map(|(server_id, tokens)| tokens.to_proto(*server_id))
+                .collect(),
+            version: serialize_version(&version),
+        })
+    }
+
+    async fn handle_refresh_semantic_tokens(
+        this: Entity<Self>,
+        _: TypedEnvelope<proto::RefreshSemanticTokens>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        this.update(&mut cx, |_, cx| {
+            cx.emit(LspStoreEvent::RefreshSemanticTokens);
+        })?;
+        Ok(proto::Ack {})
+    }
+
     async fn handle_open_buffer_for_symbol(
         this: Entity<Self>,
         envelope: TypedEnvelope<proto::OpenBufferForSymbol>,
@@ -10297,6 +10895,110 @@ impl LspStore {
         Ok(())
     }
 
+    /// Replaces the diagnostics reported by the task with the given label with the problems,
+    /// found in the task's latest output.
+    ///
+    /// Task diagnostics of all tasks are reported under [`TASK_DIAGNOSTICS_SERVER_ID`], and are
+    /// kept by task, so that the run of one task doesn't replace the problems reported by another.
+    pub fn update_task_diagnostics(
+        &mut self,
+        task_label: &str,
+        problems: Vec<TaskProblem>,
+        cx: &mut Context<Self>,
+    ) -> Result<()> {
+        let Some(local) = self.as_local_mut() else {
+            // The task runs on the remote host, whose project reports its problems.
+            return Ok(());
+        };
+
+        let mut diagnostics_by_path =
+            HashMap::<PathBuf, Vec<DiagnosticEntry<Unclipped<PointUtf16>>>>::default();
+        for problem in problems {
+            if local
+                .worktree_store
+                .read(cx)
+                .find_worktree(&problem.path, cx)
+                .is_none()
+            {
+                continue;
+            }
+            let start = PointUtf16::new(
+                problem.line.saturating_sub(1),
+                problem.column.unwrap_or(1).saturating_sub(1),
+            );
+            let end = match (problem.end_line, problem.end_column) {
+                (Some(end_line), end_column) => PointUtf16::new(
+                    end_line.saturating_sub(1),
+                    end_column.unwrap_or(1).saturating_sub(1),
+                ),
+                (None, Some(end_column)) => {
+                    PointUtf16::new(start.row, end_column.saturating_sub(1))
+                }
+                (None, None) => start,
+            };
+            diagnostics_by_path
+                .entry(problem.path)
+                .or_default()
+                .push(DiagnosticEntry {
+                    range: Unclipped(start)..Unclipped(end.max(start)),
+                    diagnostic: Diagnostic {
+                        source: Some(task_label.to_string()),
+                        source_kind: DiagnosticSourceKind::Other,
+                        code: problem.code.map(lsp::NumberOrString::String),
+                        severity: match problem.severity {
+                            ProblemSeverity::Error => DiagnosticSeverity::ERROR,
+                            ProblemSeverity::Warning => DiagnosticSeverity::WARNING,
+                            ProblemSeverity::Info => DiagnosticSeverity::INFORMATION,
+                            ProblemSeverity::Hint => DiagnosticSeverity::HINT,
+                        },
+                        message: problem.message,
+                        group_id: post_inc(&mut local.next_diagnostic_group_id),
+                        is_primary: true,
+                        // Task output describes the files as they were on disk when the task ran.
+                        is_disk_based: true,
+                        ..Diagnostic::default()
+                    },
+                });
+        }
+
+        let previous_diagnostics = if diagnostics_by_path.is_empty() {
+            local.task_diagnostics.remove(task_label)
+        } else {
+            local
+                .task_diagnostics
+                .insert(task_label.to_string(), diagnostics_by_path.clone())
+        };
+        let updated_paths = previous_diagnostics
+            .into_iter()
+            .flat_map(|diagnostics| diagnostics.into_keys())
+            .chain(diagnostics_by_path.into_keys())
+            .collect::<BTreeSet<_>>();
+        // All tasks share the diagnostics source, so each path gets the problems of every task.
+        let updates = updated_paths
+            .into_iter()
+            .map(|document_abs_path| {
+                let diagnostics = local
+                    .task_diagnostics
+                    .values()
+                    .filter_map(|task_diagnostics| task_diagnostics.get(&document_abs_path))
+                    .flatten()
+                    .cloned()
+                    .collect();
+                DocumentDiagnosticsUpdate {
+                    diagnostics: DocumentDiagnostics {
+                        diagnostics,
+                        document_abs_path,
+                        version: None,
+                    },
+                    result_id: None,
+                    server_id: TASK_DIAGNOSTICS_SERVER_ID,
+                    disk_based_sources: Cow::Borrowed(&[]),
+                }
+            })
+            .collect();
+        self.merge_diagnostic_entries(updates, |_, _, _| false, cx)
+    }
+
     fn lsp_to_document_diagnostics(
         &mut self,
         document_abs_path: PathBuf,
@@ -11112,9 +11814,17 @@ impl LspStore {
             buffer_colors.colors.remove(&for_server);
             buffer_colors.cache_version += 1;
         }
+        for buffer_tokens in self.lsp_semantic_tokens.values_mut() {
+            buffer_tokens.tokens.remove(&for_server);
+            buffer_tokens.delta_bases.remove(&for_server);
+            buffer_tokens.cache_version += 1;
+        }
         for buffer_lens in self.lsp_code_lens.values_mut() {
             buffer_lens.lens.remove(&for_server);
         }
+        for buffer_links in self.lsp_document_links.values_mut() {
+            buffer_links.links.remove(&for_server);
+        }
         if let Some(local) = self.as_local_mut() {
             local.buffer_pull_diagnostics_result_ids.remove(&for_server);
             for buffer_servers in local.buffers_opened_in_servers.values_mut() {
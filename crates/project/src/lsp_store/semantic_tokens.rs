@@ -0,0 +1,239 @@
+use std::sync::Arc;
+
+use language::{PointUtf16, Unclipped};
+use rpc::proto;
+
+/// The names a language server gives to the token types and modifiers it reports.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct SemanticTokensLegend {
+    pub token_types: Vec<String>,
+    pub token_modifiers: Vec<String>,
+}
+
+impl SemanticTokensLegend {
+    pub fn from_lsp(legend: &lsp::SemanticTokensLegend) -> Self {
+        Self {
+            token_types: legend
+                .token_types
+                .iter()
+                .map(|token_type| token_type.as_str().to_string())
+                .collect(),
+            token_modifiers: legend
+                .token_modifiers
+                .iter()
+                .map(|token_modifier| token_modifier.as_str().to_string())
+                .collect(),
+        }
+    }
+}
+
+/// A single-line semantic token, with an absolute position.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SemanticToken {
+    pub start: Unclipped<PointUtf16>,
+    pub length: u32,
+    pub token_type: u32,
+    pub token_modifiers: u32,
+}
+
+impl SemanticToken {
+    pub fn end(&self) -> Unclipped<PointUtf16> {
+        Unclipped(PointUtf16::new(
+            self.start.0.row,
+            self.start.0.column + self.length,
+        ))
+    }
+}
+
+/// The semantic tokens of a buffer, as reported by a single language server.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct SemanticTokens {
+    pub legend: Arc<SemanticTokensLegend>,
+    pub tokens: Vec<SemanticToken>,
+}
+
+impl SemanticTokens {
+    /// Decodes the tokens from the LSP encoding, where each token is five integers positioned
+    /// relative to the previous token.
+    pub fn from_lsp_data(legend: Arc<SemanticTokensLegend>, data: &[u32]) -> Self {
+        let mut row = 0;
+        let mut column = 0;
+        let tokens = data
+            .chunks_exact(5)
+            .map(|token| {
+                if token[0] == 0 {
+                    column += token[1];
+                } else {
+                    row += token[0];
+                    column = token[1];
+                }
+                SemanticToken {
+                    start: Unclipped(PointUtf16::new(row, column)),
+                    length: token[2],
+                    token_type: token[3],
+                    token_modifiers: token[4],
+                }
+            })
+            .collect();
+        Self { legend, tokens }
+    }
+
+    pub fn token_type(&self, token: &SemanticToken) -> Option<&str> {
+        self.legend
+            .token_types
+            .get(token.token_type as usize)
+            .map(String::as_str)
+    }
+
+    pub fn token_modifiers(&self, token: &SemanticToken) -> impl Iterator<Item = &str> {
+        let token_modifiers = token.token_modifiers;
+        self.legend
+            .token_modifiers
+            .iter()
+            .enumerate()
+            .filter(move |(i, _)| *i < 32 && token_modifiers & (1 << i) != 0)
+            .map(|(_, token_modifier)| token_modifier.as_str())
+    }
+
+    pub fn to_proto(&self, server_id: lsp::LanguageServerId) -> proto::SemanticTokens {
+        proto::SemanticTokens {
+            server_id: server_id.to_proto(),
+            token_types: self.legend.token_types.clone(),
+            token_modifiers: self.legend.token_modifiers.clone(),
+            data: self
+                .tokens
+                .iter()
+                .flat_map(|token| {
+                    [
+                        token.start.0.row,
+                        token.start.0.column,
+                        token.length,
+                        token.token_type,
+                        token.token_modifiers,
+                    ]
+                })
+                .collect(),
+        }
+    }
+
+    pub fn from_proto(tokens: proto::SemanticTokens) -> Self {
+        Self {
+            legend: Arc::new(SemanticTokensLegend {
+                token_types: tokens.token_types,
+                token_modifiers: tokens.token_modifiers,
+            }),
+            tokens: tokens
+                .data
+                .chunks_exact(5)
+                .map(|token| SemanticToken {
+                    start: Unclipped(PointUtf16::new(token[0], token[1])),
+                    length: token[2],
+                    token_type: token[3],
+                    token_modifiers: token[4],
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Flattens the tokens into the LSP integer encoding, which delta edits index into.
+pub(super) fn lsp_tokens_to_data(tokens: &[lsp::SemanticToken]) -> Vec<u32> {
+    tokens
+        .iter()
+        .flat_map(|token| {
+            [
+                token.delta_line,
+                token.delta_start,
+                token.length,
+                token.token_type,
+                token.token_modifiers_bitset,
+            ]
+        })
+        .collect()
+}
+
+/// Applies the edits of a delta response to the previously reported integer encoding.
+pub(super) fn apply_lsp_edits(data: &mut Vec<u32>, mut edits: Vec<lsp::SemanticTokensEdit>) {
+    edits.sort_by_key(|edit| edit.start);
+    for edit in edits.into_iter().rev() {
+        let start = (edit.start as usize).min(data.len());
+        let end = (start + edit.delete_count as usize).min(data.len());
+        let inserted = edit
+            .data
+            .as_deref()
+            .map(lsp_tokens_to_data)
+            .unwrap_or_default();
+        data.splice(start..end, inserted);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lsp_token(delta_line: u32, delta_start: u32, length: u32) -> lsp::SemanticToken {
+        lsp::SemanticToken {
+            delta_line,
+            delta_start,
+            length,
+            token_type: 0,
+            token_modifiers_bitset: 0,
+        }
+    }
+
+    #[test]
+    fn test_semantic_tokens_decoding_and_edits() {
+        let legend = Arc::new(SemanticTokensLegend {
+            token_types: vec!["function".into()],
+            token_modifiers: vec!["declaration".into(), "async".into()],
+        });
+        let mut data = lsp_tokens_to_data(&[lsp_token(1, 3, 4), lsp_token(0, 6, 2)]);
+        let tokens = SemanticTokens::from_lsp_data(legend.clone(), &data);
+        assert_eq!(
+            tokens
+                .tokens
+                .iter()
+                .map(|token| (token.start.0, token.end().0))
+                .collect::<Vec<_>>(),
+            [
+                (PointUtf16::new(1, 3), PointUtf16::new(1, 7)),
+                (PointUtf16::new(1, 9), PointUtf16::new(1, 11)),
+            ]
+        );
+
+        apply_lsp_edits(
+            &mut data,
+            vec![lsp::SemanticTokensEdit {
+                start: 5,
+                delete_count: 5,
+                data: Some(vec![lsp_token(2, 1, 5)]),
+            }],
+        );
+        let tokens = SemanticTokens::from_lsp_data(legend, &data);
+        assert_eq!(
+            tokens
+                .tokens
+                .iter()
+                .map(|token| (token.start.0, token.end().0))
+                .collect::<Vec<_>>(),
+            [
+                (PointUtf16::new(1, 3), PointUtf16::new(1, 7)),
+                (PointUtf16::new(3, 1), PointUtf16::new(3, 6)),
+            ]
+        );
+
+        let token = SemanticToken {
+            token_modifiers: 0b11,
+            ..tokens.tokens[0]
+        };
+        assert_eq!(tokens.token_type(&token), Some("function"));
+        assert_eq!(
+            tokens.token_modifiers(&token).collect::<Vec<_>>(),
+            ["declaration", "async"]
+        );
+        assert_eq!(
+            SemanticTokens::from_proto(tokens.to_proto(lsp::LanguageServerId(1))),
+            tokens
+        );
+    }
+}
@@ -141,7 +141,7 @@ pub use language::Location;
 pub use prettier::FORMAT_SUFFIX as TEST_PRETTIER_FORMAT_SUFFIX;
 pub use task_inventory::{
     BasicContextProvider, ContextProviderWithTasks, DebugScenarioContext, Inventory, TaskContexts,
-    TaskSourceKind,
+    TaskPipeline, TaskRun, TaskRunEvent, TaskRunId, TaskRunStatus, TaskSourceKind,
 };
 
 pub use buffer_store::ProjectTransaction;
@@ -342,6 +342,7 @@ pub enum Event {
     Rejoined,
     RefreshInlayHints,
     RefreshCodeLens,
+    RefreshSemanticTokens,
     RevealInProjectPanel(ProjectEntryId),
     SnippetEdit(BufferId, Vec<(lsp::Range, Snippet)>),
     ExpandedAllForEntry(WorktreeId, ProjectEntryId),
@@ -744,6 +745,23 @@ pub struct DocumentHighlight {
     pub kind: DocumentHighlightKind,
 }
 
+/// A range of lines that a language server reports as foldable.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FoldingRange {
+    pub range: Range<language::Anchor>,
+    /// The text to show in place of the folded lines, if the language server provided one.
+    pub collapsed_text: Option<SharedString>,
+}
+
+/// A range of text that a language server reports as linking to a URI.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DocumentLink {
+    pub range: Range<language::Anchor>,
+    /// The URI the link points to, if the language server resolved it.
+    pub target: Option<String>,
+    pub tooltip: Option<String>,
+}
+
 #[derive(Clone, Debug)]
 pub struct Symbol {
     pub language_server_name: LanguageServerName,
@@ -766,6 +784,39 @@ pub struct DocumentSymbol {
     pub children: Vec<DocumentSymbol>,
 }
 
+/// A function-like symbol of a call hierarchy, as returned by `textDocument/prepareCallHierarchy`.
+#[derive(Clone, Debug)]
+pub struct CallHierarchyItem {
+    pub name: String,
+    pub kind: lsp::SymbolKind,
+    pub detail: Option<String>,
+    pub buffer: Entity<Buffer>,
+    pub range: Range<language::Anchor>,
+    pub selection_range: Range<language::Anchor>,
+    /// The language server that returned the item, which has to be queried for its relatives.
+    pub server_id: LanguageServerId,
+    /// The item as returned by the language server, sent back as is when querying its relatives.
+    pub lsp_item: lsp::CallHierarchyItem,
+}
+
+/// A caller or callee of a [`CallHierarchyItem`], with the locations of the calls between the two.
+#[derive(Clone, Debug)]
+pub struct CallHierarchyCall {
+    pub item: CallHierarchyItem,
+    pub call_sites: Vec<Location>,
+}
+
+/// A type-like symbol of a type hierarchy, as returned by `textDocument/prepareTypeHierarchy`.
+///
+/// The protocol defines type hierarchy items with the same fields as call hierarchy items.
+pub type TypeHierarchyItem = CallHierarchyItem;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TypeHierarchyDirection {
+    Supertypes,
+    Subtypes,
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct HoverBlock {
     pub text: String,
@@ -3004,6 +3055,7 @@ impl Project {
             }
             LspStoreEvent::RefreshInlayHints => cx.emit(Event::RefreshInlayHints),
             LspStoreEvent::RefreshCodeLens => cx.emit(Event::RefreshCodeLens),
+            LspStoreEvent::RefreshSemanticTokens => cx.emit(Event::RefreshSemanticTokens),
             LspStoreEvent::LanguageServerPrompt(prompt) => {
                 cx.emit(Event::LanguageServerPrompt(prompt.clone()))
             }
@@ -3684,6 +3736,170 @@ impl Project {
         )
     }
 
+    pub fn prepare_call_hierarchy<T: ToPointUtf16>(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        position: T,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<CallHierarchyItem>>> {
+        let position = position.to_point_utf16(buffer.read(cx));
+        self.request_lsp(
+            buffer.clone(),
+            LanguageServerToQuery::FirstCapable,
+            PrepareCallHierarchy { position },
+            cx,
+        )
+    }
+
+    /// Queries the callers of a call hierarchy item, from the language server that returned it.
+    pub fn incoming_calls(
+        &mut self,
+        item: &CallHierarchyItem,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<CallHierarchyCall>>> {
+        self.request_lsp(
+            item.buffer.clone(),
+            LanguageServerToQuery::Other(item.server_id),
+            GetIncomingCalls { item: item.clone() },
+            cx,
+        )
+    }
+
+    /// Queries the callees of a call hierarchy item, from the language server that returned it.
+    pub fn outgoing_calls(
+        &mut self,
+        item: &CallHierarchyItem,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<CallHierarchyCall>>> {
+        self.request_lsp(
+            item.buffer.clone(),
+            LanguageServerToQuery::Other(item.server_id),
+            GetOutgoingCalls { item: item.clone() },
+            cx,
+        )
+    }
+
+    pub fn prepare_type_hierarchy<T: ToPointUtf16>(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        position: T,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<TypeHierarchyItem>>> {
+        let position = position.to_point_utf16(buffer.read(cx));
+        self.request_lsp(
+            buffer.clone(),
+            LanguageServerToQuery::FirstCapable,
+            PrepareTypeHierarchy { position },
+            cx,
+        )
+    }
+
+    /// Queries the direct supertypes or subtypes of a type hierarchy item, from the language
+    /// server that returned it.
+    pub fn related_types(
+        &mut self,
+        item: &TypeHierarchyItem,
+        direction: TypeHierarchyDirection,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<TypeHierarchyItem>>> {
+        let item = item.clone();
+        let buffer = item.buffer.clone();
+        let server = LanguageServerToQuery::Other(item.server_id);
+        match direction {
+            TypeHierarchyDirection::Supertypes => {
+                self.request_lsp(buffer, server, GetSupertypes { item }, cx)
+            }
+            TypeHierarchyDirection::Subtypes => {
+                self.request_lsp(buffer, server, GetSubtypes { item }, cx)
+            }
+        }
+    }
+
+    /// Links the type at the position to its direct supertypes or subtypes, for the editor to
+    /// navigate to them like to definitions.
+    pub fn related_type_links<T: ToPointUtf16>(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        position: T,
+        direction: TypeHierarchyDirection,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Option<Vec<LocationLink>>>> {
+        let items = self.prepare_type_hierarchy(buffer, position, cx);
+        cx.spawn(async move |project, cx| {
+            let items = items.await?;
+            let related_types = project.update(cx, |project, cx| {
+                items
+                    .iter()
+                    .map(|item| project.related_types(item, direction, cx))
+                    .collect::<Vec<_>>()
+            })?;
+            let related_types = try_join_all(related_types).await?;
+
+            let mut links = Vec::new();
+            for (item, related_types) in items.into_iter().zip(related_types) {
+                let origin = Location {
+                    buffer: item.buffer,
+                    range: item.selection_range,
+                };
+                links.extend(related_types.into_iter().map(|related_type| LocationLink {
+                    origin: Some(origin.clone()),
+                    target: Location {
+                        buffer: related_type.buffer,
+                        range: related_type.selection_range,
+                    },
+                }));
+            }
+            Ok(Some(links))
+        })
+    }
+
+    /// Queries the foldable ranges of the buffer from the first capable language server.
+    pub fn folding_ranges(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<FoldingRange>>> {
+        self.request_lsp(
+            buffer.clone(),
+            LanguageServerToQuery::FirstCapable,
+            GetFoldingRanges,
+            cx,
+        )
+    }
+
+    /// Queries the ranges around each position, from the innermost to the outermost, that
+    /// selections can be expanded to.
+    pub fn selection_ranges(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        positions: Vec<Anchor>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<Vec<Range<Anchor>>>>> {
+        self.request_lsp(
+            buffer.clone(),
+            LanguageServerToQuery::FirstCapable,
+            GetSelectionRanges { positions },
+            cx,
+        )
+    }
+
+    /// Returns the links of the buffer reported by its language servers, cached for the buffer
+    /// version.
+    pub fn document_links(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<DocumentLink>>> {
+        let document_links = self
+            .lsp_store
+            .update(cx, |lsp_store, cx| lsp_store.document_links(buffer, cx));
+        cx.background_spawn(async move {
+            document_links
+                .await
+                .map_err(|e| anyhow!("document links fetch failed: {e:#}"))
+        })
+    }
+
     pub fn symbols(&self, query: &str, cx: &mut Context<Self>) -> Task<Result<Vec<Symbol>>> {
         self.lsp_store
             .update(cx, |lsp_store, cx| lsp_store.symbols(query, cx))
@@ -5316,6 +5532,27 @@ impl Project {
     }
 
     pub fn any_language_server_supports_inlay_hints(&self, buffer: &Buffer, cx: &mut App) -> bool {
+        self.any_language_server_supports(buffer, InlayHints::check_capabilities, cx)
+    }
+
+    pub fn any_language_server_supports_selection_ranges(
+        &self,
+        buffer: &Buffer,
+        cx: &mut App,
+    ) -> bool {
+        self.any_language_server_supports(
+            buffer,
+            GetSelectionRanges::check_server_capabilities,
+            cx,
+        )
+    }
+
+    fn any_language_server_supports(
+        &self,
+        buffer: &Buffer,
+        check_capabilities: impl Fn(&lsp::ServerCapabilities) -> bool,
+        cx: &mut App,
+    ) -> bool {
         let Some(language) = buffer.language().cloned() else {
             return false;
         };
@@ -5334,7 +5571,7 @@ impl Project {
                         .then_some(server_id)
                 })
                 .filter_map(|server_id| lsp_store.lsp_server_capabilities.get(&server_id))
-                .any(InlayHints::check_capabilities)
+                .any(check_capabilities)
         })
     }
 
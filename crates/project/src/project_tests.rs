@@ -20,9 +20,10 @@ use git2::RepositoryInitOptions;
 use gpui::{App, BackgroundExecutor, SemanticVersion, UpdateGlobal};
 use itertools::Itertools;
 use language::{
-    Diagnostic, DiagnosticEntry, DiagnosticSet, DiagnosticSourceKind, DiskState, FakeLspAdapter,
-    LanguageConfig, LanguageMatcher, LanguageName, LineEnding, ManifestName, ManifestProvider,
-    ManifestQuery, OffsetRangeExt, Point, ToPoint, ToolchainList, ToolchainLister,
+    Bias, Diagnostic, DiagnosticEntry, DiagnosticSet, DiagnosticSourceKind, DiskState,
+    FakeLspAdapter, LanguageConfig, LanguageMatcher, LanguageName, LineEnding, ManifestName,
+    ManifestProvider, ManifestQuery, OffsetRangeExt, Point, ToPoint, ToolchainList,
+    ToolchainLister,
     language_settings::{LanguageSettingsContent, language_settings},
     tree_sitter_rust, tree_sitter_typescript,
 };
@@ -39,7 +40,7 @@ use serde_json::json;
 #[cfg(not(windows))]
 use std::os;
 use std::{env, mem, num::NonZeroU32, ops::Range, str::FromStr, sync::OnceLock, task::Poll};
-use task::{ResolvedTask, ShellKind, TaskContext};
+use task::{ProblemSeverity, ResolvedTask, ShellKind, TaskContext, TaskProblem};
 use unindent::Unindent as _;
 use util::{
     TryFutureExt as _, assert_set_eq, maybe, path,
@@ -1611,6 +1612,120 @@ async fn test_single_file_worktrees_diagnostics(cx: &mut gpui::TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_task_problems_diagnostics(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            "a.rs": "let a = 1;",
+            "b.rs": "let b = 2;"
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+    let lsp_store = project.read_with(cx, |project, _| project.lsp_store());
+    let buffer_a = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer(path!("/dir/a.rs"), cx)
+        })
+        .await
+        .unwrap();
+
+    let problem = |path: &str, column: u32, severity: ProblemSeverity| TaskProblem {
+        path: PathBuf::from(path),
+        line: 1,
+        column: Some(column),
+        end_line: None,
+        end_column: Some(column + 1),
+        severity,
+        code: None,
+        message: format!("problem at {column}"),
+    };
+    lsp_store.update(cx, |lsp_store, cx| {
+        lsp_store
+            .update_task_diagnostics(
+                "check",
+                vec![
+                    problem(path!("/dir/a.rs"), 5, ProblemSeverity::Error),
+                    problem(path!("/dir/b.rs"), 5, ProblemSeverity::Warning),
+                    problem(path!("/outside/c.rs"), 5, ProblemSeverity::Error),
+                ],
+                cx,
+            )
+            .unwrap();
+        lsp_store
+            .update_task_diagnostics(
+                "lint",
+                vec![problem(path!("/dir/a.rs"), 9, ProblemSeverity::Warning)],
+                cx,
+            )
+            .unwrap();
+    });
+
+    buffer_a.update(cx, |buffer, _| {
+        let chunks = chunks_with_diagnostics(buffer, 0..buffer.len());
+        assert_eq!(
+            chunks
+                .iter()
+                .map(|(s, d)| (s.as_str(), *d))
+                .collect::<Vec<_>>(),
+            &[
+                ("let ", None),
+                ("a", Some(DiagnosticSeverity::ERROR)),
+                (" = ", None),
+                ("1", Some(DiagnosticSeverity::WARNING)),
+                (";", None),
+            ],
+            "Problems of both tasks should be shown"
+        );
+    });
+    project.read_with(cx, |project, cx| {
+        assert_eq!(
+            project.diagnostic_summary(false, cx),
+            DiagnosticSummary {
+                error_count: 1,
+                warning_count: 2,
+            },
+            "Problems in files outside of the project should be skipped"
+        );
+    });
+
+    lsp_store.update(cx, |lsp_store, cx| {
+        lsp_store
+            .update_task_diagnostics("check", Vec::new(), cx)
+            .unwrap();
+    });
+
+    buffer_a.update(cx, |buffer, _| {
+        let chunks = chunks_with_diagnostics(buffer, 0..buffer.len());
+        assert_eq!(
+            chunks
+                .iter()
+                .map(|(s, d)| (s.as_str(), *d))
+                .collect::<Vec<_>>(),
+            &[
+                ("let a = ", None),
+                ("1", Some(DiagnosticSeverity::WARNING)),
+                (";", None),
+            ],
+            "A rerun should replace the problems of its task only"
+        );
+    });
+    project.read_with(cx, |project, cx| {
+        assert_eq!(
+            project.diagnostic_summary(false, cx),
+            DiagnosticSummary {
+                error_count: 0,
+                warning_count: 1,
+            }
+        );
+    });
+}
+
 #[gpui::test]
 async fn test_omitted_diagnostics(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -3273,6 +3388,464 @@ async fn test_definition(cx: &mut gpui::TestAppContext) {
     }
 }
 
+#[gpui::test]
+async fn test_call_hierarchy(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            "a.rs": "fn a() { b() }",
+            "b.rs": "fn b() {}",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+    let mut fake_servers = language_registry.register_fake_lsp(
+        "Rust",
+        FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                call_hierarchy_provider: Some(lsp::CallHierarchyServerCapability::Simple(true)),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    );
+
+    let (buffer, _handle) = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer_with_lsp(path!("/dir/b.rs"), cx)
+        })
+        .await
+        .unwrap();
+
+    let lsp_item = |path: &str, name: &str, range: lsp::Range| lsp::CallHierarchyItem {
+        name: name.into(),
+        kind: lsp::SymbolKind::FUNCTION,
+        tags: None,
+        detail: None,
+        uri: lsp::Uri::from_file_path(path).unwrap(),
+        range,
+        selection_range: range,
+        data: Some(json!({ "name": name })),
+    };
+    let b_item = lsp_item(
+        path!("/dir/b.rs"),
+        "b",
+        lsp::Range::new(lsp::Position::new(0, 3), lsp::Position::new(0, 4)),
+    );
+    let a_item = lsp_item(
+        path!("/dir/a.rs"),
+        "a",
+        lsp::Range::new(lsp::Position::new(0, 3), lsp::Position::new(0, 4)),
+    );
+
+    let fake_server = fake_servers.next().await.unwrap();
+    fake_server.set_request_handler::<lsp::request::CallHierarchyPrepare, _, _>({
+        let b_item = b_item.clone();
+        move |params, _| {
+            let b_item = b_item.clone();
+            async move {
+                assert_eq!(
+                    params.text_document_position_params.position,
+                    lsp::Position::new(0, 3)
+                );
+                Ok(Some(vec![b_item]))
+            }
+        }
+    });
+    fake_server.set_request_handler::<lsp::request::CallHierarchyIncomingCalls, _, _>({
+        let a_item = a_item.clone();
+        move |params, _| {
+            let a_item = a_item.clone();
+            async move {
+                // The item is sent back to the server as it returned it.
+                assert_eq!(params.item.data, Some(json!({ "name": "b" })));
+                Ok(Some(vec![lsp::CallHierarchyIncomingCall {
+                    from: a_item,
+                    from_ranges: vec![lsp::Range::new(
+                        lsp::Position::new(0, 9),
+                        lsp::Position::new(0, 10),
+                    )],
+                }]))
+            }
+        }
+    });
+
+    let items = project
+        .update(cx, |project, cx| {
+            project.prepare_call_hierarchy(&buffer, 3, cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0].name, "b");
+
+    let calls = project
+        .update(cx, |project, cx| project.incoming_calls(&items[0], cx))
+        .await
+        .unwrap();
+    assert_eq!(calls.len(), 1);
+    cx.update(|cx| {
+        let caller = &calls[0].item;
+        assert_eq!(caller.name, "a");
+        let caller_buffer = caller.buffer.read(cx);
+        assert_eq!(
+            caller_buffer
+                .file()
+                .unwrap()
+                .as_local()
+                .unwrap()
+                .abs_path(cx),
+            Path::new(path!("/dir/a.rs")),
+        );
+        assert_eq!(caller.selection_range.to_offset(caller_buffer), 3..4);
+
+        assert_eq!(calls[0].call_sites.len(), 1);
+        let call_site = &calls[0].call_sites[0];
+        assert_eq!(call_site.buffer, caller.buffer);
+        assert_eq!(call_site.range.to_offset(caller_buffer), 9..10);
+    });
+}
+
+#[gpui::test]
+async fn test_type_hierarchy(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            "a.rs": "trait A {}",
+            "b.rs": "struct B; impl A for B {}",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+    let mut fake_servers = language_registry.register_fake_lsp(
+        "Rust",
+        FakeLspAdapter {
+            capabilities: serde_json::from_value(json!({ "typeHierarchyProvider": true })).unwrap(),
+            ..Default::default()
+        },
+    );
+
+    let (buffer, _handle) = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer_with_lsp(path!("/dir/b.rs"), cx)
+        })
+        .await
+        .unwrap();
+
+    let lsp_item =
+        |path: &str, name: &str, kind: lsp::SymbolKind, range: lsp::Range| lsp::TypeHierarchyItem {
+            name: name.into(),
+            kind,
+            tags: None,
+            detail: None,
+            uri: lsp::Uri::from_file_path(path).unwrap(),
+            range,
+            selection_range: range,
+            data: Some(json!({ "name": name })),
+        };
+    let b_item = lsp_item(
+        path!("/dir/b.rs"),
+        "B",
+        lsp::SymbolKind::STRUCT,
+        lsp::Range::new(lsp::Position::new(0, 7), lsp::Position::new(0, 8)),
+    );
+    let a_item = lsp_item(
+        path!("/dir/a.rs"),
+        "A",
+        lsp::SymbolKind::INTERFACE,
+        lsp::Range::new(lsp::Position::new(0, 6), lsp::Position::new(0, 7)),
+    );
+
+    let fake_server = fake_servers.next().await.unwrap();
+    fake_server.set_request_handler::<lsp::request::TypeHierarchyPrepare, _, _>({
+        let b_item = b_item.clone();
+        move |params, _| {
+            let b_item = b_item.clone();
+            async move {
+                assert_eq!(
+                    params.text_document_position_params.position,
+                    lsp::Position::new(0, 7)
+                );
+                Ok(Some(vec![b_item]))
+            }
+        }
+    });
+    fake_server.set_request_handler::<lsp::request::TypeHierarchySupertypes, _, _>({
+        let a_item = a_item.clone();
+        move |params, _| {
+            let a_item = a_item.clone();
+            async move {
+                // The item is sent back to the server as it returned it.
+                assert_eq!(params.item.data, Some(json!({ "name": "B" })));
+                Ok(Some(vec![a_item]))
+            }
+        }
+    });
+
+    let links = project
+        .update(cx, |project, cx| {
+            project.related_type_links(&buffer, 7, TypeHierarchyDirection::Supertypes, cx)
+        })
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(links.len(), 1);
+    cx.update(|cx| {
+        let origin = links[0].origin.as_ref().unwrap();
+        assert_eq!(origin.buffer, buffer);
+        assert_eq!(origin.range.to_offset(buffer.read(cx)), 7..8);
+
+        let target = &links[0].target;
+        let target_buffer = target.buffer.read(cx);
+        assert_eq!(
+            target_buffer
+                .file()
+                .unwrap()
+                .as_local()
+                .unwrap()
+                .abs_path(cx),
+            Path::new(path!("/dir/a.rs")),
+        );
+        assert_eq!(target.range.to_offset(target_buffer), 6..7);
+    });
+}
+
+#[gpui::test]
+async fn test_semantic_tokens(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            "a.rs": "fn main() {}",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+    let mut fake_servers = language_registry.register_fake_lsp(
+        "Rust",
+        FakeLspAdapter {
+            capabilities: serde_json::from_value(json!({
+                "semanticTokensProvider": {
+                    "legend": {
+                        "tokenTypes": ["function", "keyword"],
+                        "tokenModifiers": ["declaration"],
+                    },
+                    "full": { "delta": true },
+                },
+            }))
+            .unwrap(),
+            ..Default::default()
+        },
+    );
+
+    let (buffer, _handle) = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer_with_lsp(path!("/dir/a.rs"), cx)
+        })
+        .await
+        .unwrap();
+
+    let lsp_token =
+        |delta_line, delta_start, length, token_type, token_modifiers_bitset| lsp::SemanticToken {
+            delta_line,
+            delta_start,
+            length,
+            token_type,
+            token_modifiers_bitset,
+        };
+    let fake_server = fake_servers.next().await.unwrap();
+    fake_server.set_request_handler::<lsp::request::SemanticTokensFullRequest, _, _>(
+        move |_, _| async move {
+            Ok(Some(lsp::SemanticTokensResult::Tokens(
+                lsp::SemanticTokens {
+                    result_id: Some("1".into()),
+                    data: vec![lsp_token(0, 0, 2, 1, 0), lsp_token(0, 3, 4, 0, 1)],
+                },
+            )))
+        },
+    );
+    fake_server.set_request_handler::<lsp::request::SemanticTokensFullDeltaRequest, _, _>(
+        move |params, _| async move {
+            // Only the changes since the last result are requested.
+            assert_eq!(params.previous_result_id, "1");
+            Ok(Some(lsp::SemanticTokensFullDeltaResult::TokensDelta(
+                lsp::SemanticTokensDelta {
+                    result_id: Some("2".into()),
+                    edits: vec![lsp::SemanticTokensEdit {
+                        start: 5,
+                        delete_count: 5,
+                        data: Some(vec![lsp_token(1, 3, 5, 0, 1)]),
+                    }],
+                },
+            )))
+        },
+    );
+
+    let fetch_tokens = |fetch_strategy, cx: &mut gpui::TestAppContext| {
+        let tokens_task = project.update(cx, |project, cx| {
+            project.lsp_store().update(cx, |lsp_store, cx| {
+                lsp_store.semantic_tokens(fetch_strategy, buffer.clone(), cx)
+            })
+        });
+        cx.executor().advance_clock(Duration::from_millis(100));
+        tokens_task
+    };
+    let token_texts = |tokens: &lsp_store::BufferSemanticTokens, cx: &mut gpui::TestAppContext| {
+        let tokens = tokens.servers.get(&fake_server.server.server_id()).unwrap();
+        buffer.read_with(cx, |buffer, _| {
+            tokens
+                .tokens
+                .iter()
+                .map(|token| {
+                    let start = buffer.clip_point_utf16(token.start, Bias::Left);
+                    let end = buffer.clip_point_utf16(token.end(), Bias::Right);
+                    (
+                        buffer.text_for_range(start..end).collect::<String>(),
+                        tokens.token_type(token).unwrap().to_string(),
+                        tokens.token_modifiers(token).collect::<Vec<_>>().join("."),
+                    )
+                })
+                .collect::<Vec<_>>()
+        })
+    };
+
+    let tokens = fetch_tokens(lsp_store::LspFetchStrategy::IgnoreCache, cx)
+        .unwrap()
+        .await
+        .unwrap();
+    assert_eq!(
+        token_texts(&tokens, cx),
+        [
+            ("fn".to_string(), "keyword".to_string(), String::new()),
+            (
+                "main".to_string(),
+                "function".to_string(),
+                "declaration".to_string()
+            ),
+        ]
+    );
+
+    // Cached tokens are not reported again.
+    let fetch_strategy = lsp_store::LspFetchStrategy::UseCache {
+        known_cache_version: tokens.cache_version,
+    };
+    assert!(fetch_tokens(fetch_strategy, cx).is_none());
+
+    buffer.update(cx, |buffer, cx| {
+        buffer.edit([(12..12, "\nfn other() {}")], None, cx)
+    });
+    let tokens = fetch_tokens(fetch_strategy, cx).unwrap().await.unwrap();
+    assert_eq!(
+        token_texts(&tokens, cx),
+        [
+            ("fn".to_string(), "keyword".to_string(), String::new()),
+            (
+                "other".to_string(),
+                "function".to_string(),
+                "declaration".to_string()
+            ),
+        ]
+    );
+}
+
+#[gpui::test]
+async fn test_folding_ranges(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            "a.rs": "fn main() {\n    let a = 1;\n    let b = 2;\n}\n",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+    let mut fake_servers = language_registry.register_fake_lsp(
+        "Rust",
+        FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                folding_range_provider: Some(lsp::FoldingRangeProviderCapability::Simple(true)),
+                ..lsp::ServerCapabilities::default()
+            },
+            ..FakeLspAdapter::default()
+        },
+    );
+
+    let (buffer, _handle) = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer_with_lsp(path!("/dir/a.rs"), cx)
+        })
+        .await
+        .unwrap();
+
+    let lsp_range = |start_line, end_line, collapsed_text: Option<&str>| lsp::FoldingRange {
+        start_line,
+        start_character: None,
+        end_line,
+        end_character: None,
+        kind: None,
+        collapsed_text: collapsed_text.map(ToString::to_string),
+    };
+    let fake_server = fake_servers.next().await.unwrap();
+    fake_server.set_request_handler::<lsp::request::FoldingRangeRequest, _, _>(
+        move |_, _| async move {
+            Ok(Some(vec![
+                lsp_range(1, 2, None),
+                lsp_range(0, 2, Some("{ … }")),
+                // Single-line, duplicate and out of bounds ranges are dropped.
+                lsp_range(1, 1, None),
+                lsp_range(1, 2, None),
+                lsp_range(2, 7, None),
+            ]))
+        },
+    );
+
+    let folding_ranges = project
+        .update(cx, |project, cx| project.folding_ranges(&buffer, cx))
+        .await
+        .unwrap();
+    buffer.read_with(cx, |buffer, _| {
+        assert_eq!(
+            folding_ranges
+                .iter()
+                .map(|folding_range| (
+                    folding_range.range.to_point(buffer),
+                    folding_range.collapsed_text.as_deref()
+                ))
+                .collect::<Vec<_>>(),
+            [
+                (Point::new(0, 11)..Point::new(2, 14), Some("{ … }")),
+                (Point::new(1, 14)..Point::new(2, 14), None),
+            ]
+        );
+    });
+}
+
 #[gpui::test]
 async fn test_completions_with_text_edit(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -6,12 +6,13 @@ use std::{
     collections::hash_map,
     path::{Path, PathBuf},
     sync::Arc,
+    time::SystemTime,
 };
 
-use anyhow::Result;
+use anyhow::{Result, anyhow};
 use collections::{HashMap, HashSet, VecDeque};
 use dap::DapRegistry;
-use gpui::{App, AppContext as _, Context, Entity, SharedString, Task, WeakEntity};
+use gpui::{App, AppContext as _, Context, Entity, EventEmitter, SharedString, Task, WeakEntity};
 use itertools::Itertools;
 use language::{
     Buffer, ContextLocation, ContextProvider, File, Language, LanguageToolchainStore, Location,
@@ -43,8 +44,59 @@ pub struct Inventory {
     last_scheduled_scenarios: VecDeque<(DebugScenario, DebugScenarioContext)>,
     templates_from_settings: InventoryFor<TaskTemplate>,
     scenarios_from_settings: InventoryFor<DebugScenario>,
+    task_runs: VecDeque<TaskRun>,
+    next_task_run_id: usize,
 }
 
+/// The maximum number of task runs kept in the run history.
+const MAX_TASK_RUNS: usize = 1_000;
+
+/// Identifies a task run within the [`Inventory`] it was recorded in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct TaskRunId(pub usize);
+
+/// A single run of a task spawned in a terminal.
+#[derive(Debug, Clone)]
+pub struct TaskRun {
+    pub id: TaskRunId,
+    /// The source of the task, or `None` if the run was restored from a previous session
+    /// and its source is not known anymore.
+    pub source_kind: Option<TaskSourceKind>,
+    /// The task that was spawned, which can be spawned again to rerun it exactly the same way.
+    pub resolved_task: ResolvedTask,
+    pub started_at: SystemTime,
+    pub finished_at: Option<SystemTime>,
+    pub status: TaskRunStatus,
+}
+
+impl TaskRun {
+    /// How long the task ran for, or has been running for if it did not finish yet.
+    pub fn duration(&self) -> std::time::Duration {
+        self.finished_at
+            .unwrap_or_else(SystemTime::now)
+            .duration_since(self.started_at)
+            .unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TaskRunStatus {
+    Running,
+    Succeeded,
+    /// The task exited unsuccessfully, or could not be spawned at all if there is no exit code.
+    Failed {
+        exit_code: Option<i32>,
+    },
+    Cancelled,
+}
+
+pub enum TaskRunEvent {
+    Started(TaskRunId),
+    Finished(TaskRunId),
+}
+
+impl EventEmitter<TaskRunEvent> for Inventory {}
+
 impl std::fmt::Debug for Inventory {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("Inventory")
@@ -52,6 +104,7 @@ impl std::fmt::Debug for Inventory {
             .field("last_scheduled_scenarios", &self.last_scheduled_scenarios)
             .field("templates_from_settings", &self.templates_from_settings)
             .field("scenarios_from_settings", &self.scenarios_from_settings)
+            .field("task_runs", &self.task_runs)
             .finish()
     }
 }
@@ -216,6 +269,86 @@ impl TaskContexts {
     }
 }
 
+/// A task template together with all tasks it depends on, as declared in [`TaskTemplate::depends_on`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TaskPipeline {
+    pub source_kind: TaskSourceKind,
+    pub template: TaskTemplate,
+    /// Tasks that have to finish successfully before `template` is spawned,
+    /// in the same order as they are listed in [`TaskTemplate::depends_on`].
+    pub dependencies: Vec<TaskPipeline>,
+}
+
+impl TaskPipeline {
+    /// Builds the dependency tree for the template given, looking up dependencies by their labels among the `available` templates.
+    /// If multiple templates share the same label, the first one is used, so the order of `available` should prefer more specific sources.
+    ///
+    /// Fails if any of the dependencies cannot be found, or if the dependencies form a cycle.
+    pub fn resolve(
+        source_kind: TaskSourceKind,
+        template: TaskTemplate,
+        available: &[(TaskSourceKind, TaskTemplate)],
+    ) -> Result<Self> {
+        let mut templates_by_label = HashMap::default();
+        for (source_kind, template) in available {
+            templates_by_label
+                .entry(template.label.as_str())
+                .or_insert((source_kind, template));
+        }
+        let mut dependency_chain = Vec::new();
+        Self::resolve_recursive(
+            source_kind,
+            template,
+            &templates_by_label,
+            &mut dependency_chain,
+        )
+    }
+
+    fn resolve_recursive(
+        source_kind: TaskSourceKind,
+        template: TaskTemplate,
+        templates_by_label: &HashMap<&str, (&TaskSourceKind, &TaskTemplate)>,
+        dependency_chain: &mut Vec<String>,
+    ) -> Result<Self> {
+        if dependency_chain.contains(&template.label) {
+            let cycle = dependency_chain
+                .iter()
+                .skip_while(|label| **label != template.label)
+                .chain(Some(&template.label))
+                .join(" -> ");
+            return Err(anyhow!("Task dependency cycle detected: {cycle}"));
+        }
+
+        dependency_chain.push(template.label.clone());
+        let dependencies = template
+            .depends_on
+            .iter()
+            .map(|label| {
+                let (source_kind, dependency) =
+                    templates_by_label.get(label.as_str()).ok_or_else(|| {
+                        anyhow!(
+                            "Task `{}` depends on an unknown task `{label}`",
+                            template.label
+                        )
+                    })?;
+                Self::resolve_recursive(
+                    (*source_kind).clone(),
+                    (*dependency).clone(),
+                    templates_by_label,
+                    dependency_chain,
+                )
+            })
+            .collect::<Result<Vec<_>>>();
+        dependency_chain.pop();
+
+        Ok(Self {
+            source_kind,
+            template,
+            dependencies: dependencies?,
+        })
+    }
+}
+
 impl TaskSourceKind {
     pub fn to_id_base(&self) -> String {
         match self {
@@ -246,6 +379,8 @@ impl Inventory {
             last_scheduled_scenarios: VecDeque::default(),
             templates_from_settings: InventoryFor::default(),
             scenarios_from_settings: InventoryFor::default(),
+            task_runs: VecDeque::default(),
+            next_task_run_id: 0,
         })
     }
 
@@ -373,6 +508,39 @@ impl Inventory {
         })
     }
 
+    /// Resolves the [`TaskTemplate::depends_on`] graph of the template given,
+    /// looking up the dependencies among the tasks relevant to the buffer and worktree given.
+    pub fn task_pipeline(
+        &self,
+        buffer: Option<Entity<Buffer>>,
+        worktree_id: Option<WorktreeId>,
+        source_kind: TaskSourceKind,
+        template: TaskTemplate,
+        cx: &App,
+    ) -> Task<Result<TaskPipeline>> {
+        let worktree_id = match &source_kind {
+            TaskSourceKind::Worktree { id, .. } => Some(*id),
+            _ => worktree_id,
+        };
+        let (buffer_worktree_id, file, language) = buffer
+            .map(|buffer| {
+                let buffer = buffer.read(cx);
+                let file = buffer.file().cloned();
+                (
+                    file.as_ref().map(|file| file.worktree_id(cx)),
+                    file,
+                    buffer.language().cloned(),
+                )
+            })
+            .unwrap_or((None, None, None));
+
+        let tasks = self.list_tasks(file, language, worktree_id.or(buffer_worktree_id), cx);
+        cx.background_spawn(async move {
+            let tasks = tasks.await;
+            TaskPipeline::resolve(source_kind, template, &tasks)
+        })
+    }
+
     /// Pulls its task sources relevant to the worktree and the language given,
     /// returns all task templates with their source kinds, worktree tasks first, language tasks second
     /// and global tasks last. No specific order inside source kinds groups.
@@ -603,6 +771,88 @@ impl Inventory {
         }
     }
 
+    /// Records a new run of the task given, which is considered running until [`Self::task_run_finished`] is called.
+    pub fn task_run_started(
+        &mut self,
+        task_source_kind: TaskSourceKind,
+        resolved_task: ResolvedTask,
+        cx: &mut Context<Self>,
+    ) -> TaskRunId {
+        let id = TaskRunId(post_inc(&mut self.next_task_run_id));
+        self.push_task_run(TaskRun {
+            id,
+            source_kind: Some(task_source_kind),
+            resolved_task,
+            started_at: SystemTime::now(),
+            finished_at: None,
+            status: TaskRunStatus::Running,
+        });
+        cx.emit(TaskRunEvent::Started(id));
+        cx.notify();
+        id
+    }
+
+    pub fn task_run_finished(
+        &mut self,
+        id: TaskRunId,
+        status: TaskRunStatus,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(task_run) = self.task_runs.iter_mut().find(|task_run| task_run.id == id) else {
+            return;
+        };
+        task_run.finished_at = Some(SystemTime::now());
+        task_run.status = status;
+        cx.emit(TaskRunEvent::Finished(id));
+        cx.notify();
+    }
+
+    /// Adds finished runs from a previous session to the history, before the runs of the current one.
+    pub fn restore_task_runs(
+        &mut self,
+        task_runs: impl IntoIterator<Item = (ResolvedTask, SystemTime, SystemTime, TaskRunStatus)>,
+        cx: &mut Context<Self>,
+    ) {
+        let current_runs = std::mem::take(&mut self.task_runs);
+        for (resolved_task, started_at, finished_at, status) in task_runs {
+            let id = TaskRunId(post_inc(&mut self.next_task_run_id));
+            self.push_task_run(TaskRun {
+                id,
+                source_kind: None,
+                resolved_task,
+                started_at,
+                finished_at: Some(finished_at),
+                status,
+            });
+        }
+        for task_run in current_runs {
+            self.push_task_run(task_run);
+        }
+        cx.notify();
+    }
+
+    /// All runs recorded, from the oldest to the newest.
+    pub fn task_runs(&self) -> impl DoubleEndedIterator<Item = &TaskRun> {
+        self.task_runs.iter()
+    }
+
+    pub fn task_run(&self, id: TaskRunId) -> Option<&TaskRun> {
+        self.task_runs.iter().find(|task_run| task_run.id == id)
+    }
+
+    pub fn clear_task_runs(&mut self, cx: &mut Context<Self>) {
+        self.task_runs
+            .retain(|task_run| task_run.status == TaskRunStatus::Running);
+        cx.notify();
+    }
+
+    fn push_task_run(&mut self, task_run: TaskRun) {
+        self.task_runs.push_back(task_run);
+        if self.task_runs.len() > MAX_TASK_RUNS {
+            self.task_runs.pop_front();
+        }
+    }
+
     /// Deletes a resolved task from history, using its id.
     /// A similar may still resurface in `used_and_current_resolved_tasks` when its [`TaskTemplate`] is resolved again.
     pub fn delete_previously_used(&mut self, id: &TaskId) {
@@ -1542,6 +1792,127 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    async fn test_task_run_history(cx: &mut TestAppContext) {
+        init_test(cx);
+        let inventory = cx.update(|cx| Inventory::new(cx));
+        let resolve = |label: &str| {
+            TaskTemplate {
+                label: label.to_string(),
+                command: "echo".to_string(),
+                ..TaskTemplate::default()
+            }
+            .resolve_task("test", &TaskContext::default())
+            .unwrap()
+        };
+
+        let (build, test) = inventory.update(cx, |inventory, cx| {
+            (
+                inventory.task_run_started(TaskSourceKind::UserInput, resolve("build"), cx),
+                inventory.task_run_started(TaskSourceKind::UserInput, resolve("test"), cx),
+            )
+        });
+        inventory.update(cx, |inventory, cx| {
+            inventory.task_run_finished(build, TaskRunStatus::Succeeded, cx);
+            inventory.restore_task_runs(
+                [(
+                    resolve("lint"),
+                    SystemTime::UNIX_EPOCH,
+                    SystemTime::UNIX_EPOCH,
+                    TaskRunStatus::Failed { exit_code: Some(1) },
+                )],
+                cx,
+            );
+        });
+        inventory.read_with(cx, |inventory, _| {
+            let runs = inventory
+                .task_runs()
+                .map(|run| (run.resolved_task.resolved_label.as_str(), run.status))
+                .collect::<Vec<_>>();
+            assert_eq!(
+                runs,
+                vec![
+                    ("lint", TaskRunStatus::Failed { exit_code: Some(1) }),
+                    ("build", TaskRunStatus::Succeeded),
+                    ("test", TaskRunStatus::Running),
+                ]
+            );
+            assert!(inventory.task_run(build).unwrap().finished_at.is_some());
+            assert!(inventory.task_run(test).unwrap().finished_at.is_none());
+        });
+
+        inventory.update(cx, |inventory, cx| inventory.clear_task_runs(cx));
+        inventory.read_with(cx, |inventory, _| {
+            let runs = inventory.task_runs().map(|run| run.id).collect::<Vec<_>>();
+            assert_eq!(runs, vec![test], "Only running tasks should be kept");
+        });
+    }
+
+    #[test]
+    fn test_task_pipeline_resolution() {
+        let source_kind = TaskSourceKind::AbsPath {
+            id_base: "global tasks.json".into(),
+            abs_path: paths::tasks_file().clone(),
+        };
+        let template = |label: &str, depends_on: &[&str]| TaskTemplate {
+            label: label.to_string(),
+            command: "echo".to_string(),
+            depends_on: depends_on.iter().map(|label| label.to_string()).collect(),
+            ..TaskTemplate::default()
+        };
+        let available = [
+            template("build", &[]),
+            template("lint", &[]),
+            template("test", &["build"]),
+            template("deploy", &["test", "lint"]),
+            template("cycle_a", &["cycle_b"]),
+            template("cycle_b", &["build", "cycle_c"]),
+            template("cycle_c", &["cycle_a"]),
+            template("broken", &["missing"]),
+        ]
+        .into_iter()
+        .map(|template| (source_kind.clone(), template))
+        .collect::<Vec<_>>();
+
+        let pipeline = TaskPipeline::resolve(
+            source_kind.clone(),
+            template("deploy", &["test", "lint"]),
+            &available,
+        )
+        .unwrap();
+        fn labels(pipeline: &TaskPipeline) -> String {
+            if pipeline.dependencies.is_empty() {
+                pipeline.template.label.clone()
+            } else {
+                format!(
+                    "{}({})",
+                    pipeline.template.label,
+                    pipeline.dependencies.iter().map(labels).join(", ")
+                )
+            }
+        }
+        assert_eq!(labels(&pipeline), "deploy(test(build), lint)");
+
+        let cycle_error = TaskPipeline::resolve(
+            source_kind.clone(),
+            template("cycle_a", &["cycle_b"]),
+            &available,
+        )
+        .unwrap_err();
+        assert_eq!(
+            cycle_error.to_string(),
+            "Task dependency cycle detected: cycle_a -> cycle_b -> cycle_c -> cycle_a"
+        );
+
+        let missing_error =
+            TaskPipeline::resolve(source_kind, template("broken", &["missing"]), &available)
+                .unwrap_err();
+        assert_eq!(
+            missing_error.to_string(),
+            "Task `broken` depends on an unknown task `missing`"
+        );
+    }
+
     fn init_test(_cx: &mut TestAppContext) {
         zlog::init_test();
         TaskStore::init(None);
@@ -97,6 +97,8 @@ impl Project {
             show_summary: spawn_task.show_summary,
             show_command: spawn_task.show_command,
             show_rerun: spawn_task.show_rerun,
+            keep_output: !spawn_task.problem_matcher.is_empty(),
+            output: None,
             completion_rx,
         });
         let remote_client = self.remote_client.clone();
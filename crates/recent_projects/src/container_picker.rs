@@ -0,0 +1,289 @@
+use std::{path::PathBuf, sync::Arc};
+
+use fuzzy::{StringMatch, StringMatchCandidate};
+use gpui::{
+    App, Context, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, Subscription, Task,
+    Window,
+};
+use picker::{Picker, PickerDelegate};
+use remote::{ContainerInfo, ContainerRuntime, DockerConnectionOptions, RemoteConnectionOptions};
+use ui::{HighlightedLabel, ListItem, ListItemSpacing, prelude::*};
+use util::ResultExt as _;
+use workspace::{ModalView, OpenOptions, Workspace};
+
+use crate::open_remote_project;
+
+/// Opens `path` inside `options`' container, replacing `window` unless a new one is requested.
+pub(crate) fn open_in_container(
+    options: DockerConnectionOptions,
+    path: PathBuf,
+    create_new_window: bool,
+    window: &mut Window,
+    cx: &mut App,
+) -> Task<anyhow::Result<()>> {
+    let Some(app_state) = workspace::AppState::global(cx).upgrade() else {
+        return Task::ready(Ok(()));
+    };
+    let replace_window = if create_new_window {
+        None
+    } else {
+        window.window_handle().downcast::<Workspace>()
+    };
+    let open_options = OpenOptions {
+        replace_window,
+        ..Default::default()
+    };
+    window.spawn(cx, async move |cx| {
+        open_remote_project(
+            RemoteConnectionOptions::Docker(options),
+            vec![path],
+            app_state,
+            open_options,
+            cx,
+        )
+        .await
+    })
+}
+
+pub(crate) struct ContainerOpenModal {
+    picker: Entity<Picker<ContainerPickerDelegate>>,
+    _subscription: Subscription,
+}
+
+impl ContainerOpenModal {
+    pub fn new(create_new_window: bool, window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let delegate = ContainerPickerDelegate::new(create_new_window);
+        let picker = cx.new(|cx| {
+            let mut picker = Picker::uniform_list(delegate, window, cx);
+            picker.delegate.reload(window, cx);
+            picker
+        });
+        let _subscription = cx.subscribe(&picker, |_, _, _, cx| cx.emit(DismissEvent));
+        Self {
+            picker,
+            _subscription,
+        }
+    }
+}
+
+impl ModalView for ContainerOpenModal {}
+
+impl EventEmitter<DismissEvent> for ContainerOpenModal {}
+
+impl Focusable for ContainerOpenModal {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl Render for ContainerOpenModal {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .key_context("ContainerPicker")
+            .w(rems(34.))
+            .child(self.picker.clone())
+            .on_mouse_down_out(cx.listener(|this, _, window, cx| {
+                this.picker.update(cx, |this, cx| {
+                    this.cancel(&Default::default(), window, cx);
+                })
+            }))
+    }
+}
+
+pub(crate) struct ContainerPickerDelegate {
+    create_new_window: bool,
+    containers: Vec<(ContainerRuntime, ContainerInfo)>,
+    matches: Vec<StringMatch>,
+    selected_index: usize,
+    loading: bool,
+}
+
+impl ContainerPickerDelegate {
+    fn new(create_new_window: bool) -> Self {
+        Self {
+            create_new_window,
+            containers: Vec::new(),
+            matches: Vec::new(),
+            selected_index: 0,
+            loading: true,
+        }
+    }
+
+    /// Lists the running containers of every runtime that is installed.
+    fn reload(&mut self, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let containers = cx.background_spawn(async move {
+            let mut containers = Vec::new();
+            for runtime in [ContainerRuntime::Docker, ContainerRuntime::Podman] {
+                if let Some(list) = remote::running_containers(runtime).await.log_err() {
+                    containers.extend(list.into_iter().map(|container| (runtime, container)));
+                }
+            }
+            containers
+        });
+        cx.spawn_in(window, async move |picker, cx| {
+            let containers = containers.await;
+            picker.update_in(cx, |picker, window, cx| {
+                picker.delegate.containers = containers;
+                picker.delegate.loading = false;
+                picker.refresh(window, cx);
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+}
+
+impl PickerDelegate for ContainerPickerDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _window: &mut Window, _cx: &mut App) -> Arc<str> {
+        "Connect to container…".into()
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(
+        &mut self,
+        ix: usize,
+        _window: &mut Window,
+        _: &mut Context<Picker<Self>>,
+    ) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Task<()> {
+        let candidates = self
+            .containers
+            .iter()
+            .enumerate()
+            .map(|(ix, (_, container))| StringMatchCandidate::new(ix, &container.name))
+            .collect::<Vec<_>>();
+        cx.spawn_in(window, async move |picker, cx| {
+            let matches = if query.is_empty() {
+                candidates
+                    .into_iter()
+                    .map(|candidate| StringMatch {
+                        candidate_id: candidate.id,
+                        score: 0.,
+                        positions: Vec::new(),
+                        string: candidate.string,
+                    })
+                    .collect()
+            } else {
+                fuzzy::match_strings(
+                    &candidates,
+                    &query,
+                    false,
+                    true,
+                    100,
+                    &Default::default(),
+                    cx.background_executor().clone(),
+                )
+                .await
+            };
+            picker
+                .update(cx, |picker, _| {
+                    let delegate = &mut picker.delegate;
+                    delegate.matches = matches;
+                    delegate.selected_index = delegate
+                        .selected_index
+                        .min(delegate.matches.len().saturating_sub(1));
+                })
+                .log_err();
+        })
+    }
+
+    fn confirm(&mut self, secondary: bool, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some((runtime, container)) = self
+            .matches
+            .get(self.selected_index)
+            .and_then(|hit| self.containers.get(hit.candidate_id))
+            .cloned()
+        else {
+            return;
+        };
+        let create_new_window = self.create_new_window != secondary;
+        cx.spawn_in(window, async move |_, cx| {
+            let working_dir = remote::container_working_dir(runtime, &container.name)
+                .await
+                .log_err()
+                .flatten()
+                .unwrap_or_else(|| "/".to_string());
+            let options = DockerConnectionOptions {
+                runtime,
+                container: container.name,
+                user: None,
+            };
+            cx.update(|window, cx| {
+                open_in_container(
+                    options,
+                    PathBuf::from(working_dir),
+                    create_new_window,
+                    window,
+                    cx,
+                )
+            })?
+            .await
+        })
+        .detach_and_log_err(cx);
+        cx.emit(DismissEvent);
+    }
+
+    fn dismissed(&mut self, _: &mut Window, cx: &mut Context<Picker<Self>>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn no_matches_text(&self, _window: &mut Window, _cx: &mut App) -> Option<SharedString> {
+        Some(if self.loading {
+            "Loading containers…".into()
+        } else {
+            "No running containers found".into()
+        })
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _window: &mut Window,
+        _cx: &mut Context<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let hit = self.matches.get(ix)?;
+        let (runtime, container) = self.containers.get(hit.candidate_id)?;
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .toggle_state(selected)
+                .start_slot(Icon::new(IconName::Server).color(Color::Muted))
+                .child(
+                    v_flex()
+                        .w_full()
+                        .overflow_hidden()
+                        .child(
+                            HighlightedLabel::new(container.name.clone(), hit.positions.clone())
+                                .truncate(),
+                        )
+                        .child(
+                            Label::new(format!(
+                                "{runtime} • {} • {}",
+                                container.image, container.status
+                            ))
+                            .size(LabelSize::Small)
+                            .color(Color::Muted)
+                            .truncate(),
+                        ),
+                ),
+        )
+    }
+}
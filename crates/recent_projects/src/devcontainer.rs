@@ -0,0 +1,191 @@
+use std::path::Path;
+
+use anyhow::{Context as _, Result};
+use remote::{ContainerRuntime, DockerConnectionOptions};
+use serde::Deserialize;
+
+/// The locations, relative to the project root, where dev container tools look for a configuration.
+const CONFIG_PATHS: [&str; 2] = [".devcontainer/devcontainer.json", ".devcontainer.json"];
+
+/// The subset of `devcontainer.json` needed to find and open a running dev container.
+#[derive(Debug, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct DevContainerConfig {
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    run_args: Vec<String>,
+    #[serde(default)]
+    workspace_folder: Option<String>,
+    #[serde(default)]
+    remote_user: Option<String>,
+}
+
+impl DevContainerConfig {
+    pub(crate) fn parse(content: &str) -> Result<Self> {
+        settings::parse_json_with_comments(content)
+    }
+
+    /// Loads the configuration of the project at `root`, if it has one.
+    pub(crate) async fn load(root: &Path) -> Result<Option<Self>> {
+        for relative_path in CONFIG_PATHS {
+            let path = root.join(relative_path);
+            if let Ok(content) = smol::fs::read_to_string(&path).await {
+                return Self::parse(&content)
+                    .with_context(|| format!("failed to parse {}", path.display()))
+                    .map(Some);
+            }
+        }
+        Ok(None)
+    }
+
+    /// The container name passed with `--name` in `runArgs`, if any.
+    fn container_name(&self) -> Option<String> {
+        let mut args = self.run_args.iter();
+        while let Some(arg) = args.next() {
+            if arg == "--name" {
+                return args.next().cloned();
+            }
+            if let Some(name) = arg.strip_prefix("--name=") {
+                return Some(name.to_string());
+            }
+        }
+        None
+    }
+
+    /// The folder the project is mounted at inside the container, which dev container tools
+    /// default to `/workspaces/<folder name>`.
+    /// This is a path in the container, so it's always a POSIX one, whatever the host is.
+    pub(crate) fn workspace_folder(&self, root: &Path) -> String {
+        match &self.workspace_folder {
+            Some(folder) => substitute_variables(folder, root),
+            None => format!("/workspaces/{}", folder_name(root)),
+        }
+    }
+
+    pub(crate) fn display_name(&self, root: &Path) -> String {
+        self.name
+            .clone()
+            .unwrap_or_else(|| root.display().to_string())
+    }
+
+    /// Finds the running container of the project at `root`, either by the name set in
+    /// `runArgs` or by the `devcontainer.local_folder` label dev container tools attach to it.
+    pub(crate) async fn find_container(&self, root: &Path) -> Option<DockerConnectionOptions> {
+        let label = format!("devcontainer.local_folder={}", root.display());
+        for runtime in [ContainerRuntime::Docker, ContainerRuntime::Podman] {
+            let container = match self.container_name() {
+                Some(name) => {
+                    remote::running_containers(runtime)
+                        .await
+                        .ok()
+                        .and_then(|containers| {
+                            containers
+                                .into_iter()
+                                .any(|container| container.name == name)
+                                .then_some(name)
+                        })
+                }
+                None => remote::find_container_by_label(runtime, &label)
+                    .await
+                    .ok()
+                    .and_then(|container| container),
+            };
+            if let Some(container) = container {
+                return Some(DockerConnectionOptions {
+                    runtime,
+                    container,
+                    user: self.remote_user.clone(),
+                });
+            }
+        }
+        None
+    }
+}
+
+fn folder_name(root: &Path) -> String {
+    root.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+/// Replaces the `${localWorkspaceFolder}`, `${localWorkspaceFolderBasename}` and
+/// `${localEnv:NAME}` variables of a `devcontainer.json` value. Unknown variables are kept as is.
+fn substitute_variables(value: &str, root: &Path) -> String {
+    let mut result = String::with_capacity(value.len());
+    let mut rest = value;
+    while let Some(start) = rest.find("${") {
+        result.push_str(&rest[..start]);
+        let Some(end) = rest[start..].find('}') else {
+            rest = &rest[start..];
+            break;
+        };
+        let variable = &rest[start + 2..start + end];
+        match variable {
+            "localWorkspaceFolder" => result.push_str(&root.to_string_lossy()),
+            "localWorkspaceFolderBasename" => result.push_str(&folder_name(root)),
+            _ => match variable.strip_prefix("localEnv:") {
+                Some(name) => {
+                    let (name, default) = name.split_once(':').unwrap_or((name, ""));
+                    result.push_str(&std::env::var(name).unwrap_or_else(|_| default.to_string()));
+                }
+                None => result.push_str(&rest[start..=start + end]),
+            },
+        }
+        rest = &rest[start + end + 1..];
+    }
+    result.push_str(rest);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_devcontainer_config() {
+        let config = DevContainerConfig::parse(
+            r#"{
+                // Comments and trailing commas are allowed.
+                "name": "Zed",
+                "image": "mcr.microsoft.com/devcontainers/rust:1",
+                "runArgs": ["--cap-add=SYS_PTRACE", "--name", "zed-dev"],
+                "remoteUser": "vscode",
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(config.container_name().as_deref(), Some("zed-dev"));
+        assert_eq!(config.remote_user.as_deref(), Some("vscode"));
+        assert_eq!(
+            config.workspace_folder(Path::new("/home/me/zed")),
+            "/workspaces/zed"
+        );
+        assert_eq!(config.display_name(Path::new("/home/me/zed")), "Zed");
+
+        let config = DevContainerConfig::parse(
+            r#"{ "runArgs": ["--name=other"], "workspaceFolder": "/src" }"#,
+        )
+        .unwrap();
+        assert_eq!(config.container_name().as_deref(), Some("other"));
+        assert_eq!(config.workspace_folder(Path::new("/home/me/zed")), "/src");
+
+        let config = DevContainerConfig::parse(
+            r#"{ "workspaceFolder": "/src/${localWorkspaceFolderBasename}/${unknown}" }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.workspace_folder(Path::new("/home/me/zed")),
+            "/src/zed/${unknown}"
+        );
+        assert_eq!(
+            substitute_variables(
+                "${localWorkspaceFolder}:${localEnv:ZED_DEVCONTAINER_UNSET:fallback}${",
+                Path::new("/home/me/zed")
+            ),
+            "/home/me/zed:fallback${"
+        );
+
+        let config = DevContainerConfig::parse("{}").unwrap();
+        assert_eq!(config.container_name(), None);
+    }
+}
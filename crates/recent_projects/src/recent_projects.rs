@@ -1,11 +1,17 @@
+mod container_picker;
+mod devcontainer;
 pub mod disconnected_overlay;
 mod remote_connections;
 mod remote_servers;
 mod ssh_config;
+mod worktree_picker;
 
 #[cfg(target_os = "windows")]
 mod wsl_picker;
 
+use anyhow::Context as _;
+use container_picker::{ContainerOpenModal, open_in_container};
+use devcontainer::DevContainerConfig;
 use remote::RemoteConnectionOptions;
 pub use remote_connections::open_remote_project;
 
@@ -23,14 +29,22 @@ use picker::{
 pub use remote_connections::SshSettings;
 pub use remote_servers::RemoteServerProjects;
 use settings::Settings;
-use std::{path::Path, sync::Arc};
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 use ui::{KeyBinding, ListItem, ListItemSpacing, Tooltip, prelude::*, tooltip_container};
 use util::{ResultExt, paths::PathExt};
 use workspace::{
     CloseIntent, HistoryManager, ModalView, OpenOptions, PathList, SerializedWorkspaceLocation,
-    WORKSPACE_DB, Workspace, WorkspaceId, with_active_or_new_workspace,
+    WORKSPACE_DB, Workspace, WorkspaceId, notifications::DetachAndPromptErr as _,
+    with_active_or_new_workspace,
+};
+pub use worktree_picker::{WorktreePicker, open_worktree};
+use zed_actions::{
+    OpenRecent, OpenRemote, OpenWorktree,
+    container_actions::{OpenContainer, OpenDevContainer},
 };
-use zed_actions::{OpenRecent, OpenRemote};
 
 pub fn init(cx: &mut App) {
     SshSettings::register(cx);
@@ -102,6 +116,22 @@ pub fn init(cx: &mut App) {
         });
     });
 
+    cx.on_action(|open_container: &OpenContainer, cx| {
+        let create_new_window = open_container.create_new_window;
+        with_active_or_new_workspace(cx, move |workspace, window, cx| {
+            workspace.toggle_modal(window, cx, |window, cx| {
+                ContainerOpenModal::new(create_new_window, window, cx)
+            });
+        });
+    });
+
+    cx.on_action(|open_dev_container: &OpenDevContainer, cx| {
+        let create_new_window = open_dev_container.create_new_window;
+        with_active_or_new_workspace(cx, move |workspace, window, cx| {
+            open_dev_container(workspace, create_new_window, window, cx);
+        });
+    });
+
     cx.on_action(|open_recent: &OpenRecent, cx| {
         let create_new_window = open_recent.create_new_window;
         with_active_or_new_workspace(cx, move |workspace, window, cx| {
@@ -117,6 +147,13 @@ pub fn init(cx: &mut App) {
             });
         });
     });
+    cx.on_action(|action: &OpenWorktree, cx| {
+        let path = action.path.clone();
+        with_active_or_new_workspace(cx, move |workspace, window, cx| match path {
+            Some(path) => open_worktree(workspace, path, cx).detach_and_log_err(cx),
+            None => WorktreePicker::toggle(workspace, window, cx),
+        });
+    });
     cx.on_action(|open_remote: &OpenRemote, cx| {
         let from_existing_connection = open_remote.from_existing_connection;
         let create_new_window = open_remote.create_new_window;
@@ -136,6 +173,52 @@ pub fn init(cx: &mut App) {
     cx.observe_new(DisconnectedOverlay::register).detach();
 }
 
+/// Reopens the local project of `workspace` inside the running container described by its
+/// `devcontainer.json`.
+fn open_dev_container(
+    workspace: &mut Workspace,
+    create_new_window: bool,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let project = workspace.project().read(cx);
+    let root = project
+        .is_local()
+        .then(|| project.visible_worktrees(cx).next())
+        .flatten()
+        .map(|worktree| worktree.read(cx).abs_path());
+    let Some(root) = root else {
+        let _ = window.prompt(
+            gpui::PromptLevel::Info,
+            "Open a local project with a dev container configuration first",
+            None,
+            &["Ok"],
+            cx,
+        );
+        return;
+    };
+
+    cx.spawn_in(window, async move |_, cx| {
+        let config = DevContainerConfig::load(&root).await?.with_context(|| {
+            format!("{} has no .devcontainer/devcontainer.json", root.display())
+        })?;
+        let options = config.find_container(&root).await.with_context(|| {
+            format!(
+                "No running dev container found for {}, start it first",
+                config.display_name(&root)
+            )
+        })?;
+        let workspace_folder = PathBuf::from(config.workspace_folder(&root));
+        cx.update(|window, cx| {
+            open_in_container(options, workspace_folder, create_new_window, window, cx)
+        })?
+        .await
+    })
+    .detach_and_prompt_err("Failed to open dev container", window, cx, |e, _, _| {
+        Some(e.to_string())
+    });
+}
+
 pub struct RecentProjects {
     pub picker: Entity<Picker<RecentProjectsDelegate>>,
     rem_width: f32,
@@ -491,6 +574,7 @@ impl PickerDelegate for RecentProjectsDelegate {
                                     Icon::new(match options {
                                         RemoteConnectionOptions::Ssh { .. } => IconName::Server,
                                         RemoteConnectionOptions::Wsl { .. } => IconName::Linux,
+                                        RemoteConnectionOptions::Docker { .. } => IconName::Server,
                                     })
                                     .color(Color::Muted)
                                     .into_any_element()
@@ -541,6 +625,14 @@ impl PickerDelegate for RecentProjectsDelegate {
         window: &mut Window,
         cx: &mut Context<Picker<Self>>,
     ) -> Option<AnyElement> {
+        let has_local_repository = self.workspace.upgrade().is_some_and(|workspace| {
+            workspace
+                .read(cx)
+                .project()
+                .read(cx)
+                .active_repository(cx)
+                .is_some_and(|repository| repository.read(cx).is_local(cx))
+        });
         Some(
             h_flex()
                 .w_full()
@@ -549,6 +641,19 @@ impl PickerDelegate for RecentProjectsDelegate {
                 .justify_end()
                 .border_t_1()
                 .border_color(cx.theme().colors().border_variant)
+                .when(has_local_repository, |this| {
+                    this.child(
+                        Button::new("worktree", "Open Worktree")
+                            .key_binding(KeyBinding::for_action(
+                                &OpenWorktree::default(),
+                                window,
+                                cx,
+                            ))
+                            .on_click(|_, window, cx| {
+                                window.dispatch_action(OpenWorktree::default().boxed_clone(), cx)
+                            }),
+                    )
+                })
                 .child(
                     Button::new("remote", "Open Remote Folder")
                         .key_binding(KeyBinding::for_action(
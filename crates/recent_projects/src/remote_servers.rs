@@ -49,6 +49,7 @@ use workspace::{
     notifications::{DetachAndPromptErr, NotificationId},
     open_remote_project_with_existing_connection,
 };
+use zed_actions::container_actions::OpenContainer;
 
 pub struct RemoteServerProjects {
     mode: Mode,
@@ -132,6 +133,9 @@ enum ProjectPickerData {
     Wsl {
         distro_name: SharedString,
     },
+    Container {
+        container: SharedString,
+    },
 }
 
 struct ProjectPicker {
@@ -205,6 +209,9 @@ impl ProjectPicker {
             RemoteConnectionOptions::Wsl(connection) => ProjectPickerData::Wsl {
                 distro_name: connection.distro_name.clone().into(),
             },
+            RemoteConnectionOptions::Docker(connection) => ProjectPickerData::Container {
+                container: connection.container.clone().into(),
+            },
         };
         let _path_task = cx
             .spawn_in(window, {
@@ -321,6 +328,13 @@ impl gpui::Render for ProjectPicker {
                     is_wsl: true,
                 }
                 .render(window, cx),
+                ProjectPickerData::Container { container } => SshConnectionHeader {
+                    connection_string: container.clone(),
+                    paths: Default::default(),
+                    nickname: None,
+                    is_wsl: false,
+                }
+                .render(window, cx),
             })
             .child(
                 div()
@@ -404,6 +418,7 @@ struct DefaultState {
     scroll_handle: ScrollHandle,
     add_new_server: NavigableEntry,
     add_new_wsl: NavigableEntry,
+    add_new_container: NavigableEntry,
     servers: Vec<RemoteEntry>,
 }
 
@@ -412,6 +427,7 @@ impl DefaultState {
         let handle = ScrollHandle::new();
         let add_new_server = NavigableEntry::new(&handle, cx);
         let add_new_wsl = NavigableEntry::new(&handle, cx);
+        let add_new_container = NavigableEntry::new(&handle, cx);
 
         let ssh_settings = SshSettings::get_global(cx);
         let read_ssh_config = ssh_settings.read_ssh_config;
@@ -481,6 +497,7 @@ impl DefaultState {
             scroll_handle: handle,
             add_new_server,
             add_new_wsl,
+            add_new_container,
             servers,
         }
     }
@@ -1996,6 +2013,31 @@ impl RemoteServerProjects {
                 cx.notify();
             }));
 
+        let create_new_window = self.create_new_window;
+        let container_connect_button = div()
+            .id("container-connect-new-server")
+            .track_focus(&state.add_new_container.focus_handle)
+            .anchor_scroll(state.add_new_container.scroll_anchor.clone())
+            .child(
+                ListItem::new("container-add-new-server")
+                    .toggle_state(
+                        state
+                            .add_new_container
+                            .focus_handle
+                            .contains_focused(window, cx),
+                    )
+                    .inset(true)
+                    .spacing(ui::ListItemSpacing::Sparse)
+                    .start_slot(Icon::new(IconName::Plus).color(Color::Muted))
+                    .child(Label::new("Connect to Container"))
+                    .on_click(move |_, window, cx| {
+                        window.dispatch_action(Box::new(OpenContainer { create_new_window }), cx);
+                    }),
+            )
+            .on_action(move |_: &menu::Confirm, window, cx| {
+                window.dispatch_action(Box::new(OpenContainer { create_new_window }), cx);
+            });
+
         let modal_section = v_flex()
             .track_focus(&self.focus_handle(cx))
             .id("ssh-server-list")
@@ -2008,6 +2050,7 @@ impl RemoteServerProjects {
         let modal_section = modal_section.child(wsl_connect_button);
         #[cfg(not(target_os = "windows"))]
         let modal_section = modal_section;
+        let modal_section = modal_section.child(container_connect_button);
 
         let mut modal_section = Navigable::new(
             modal_section
@@ -2031,7 +2074,8 @@ impl RemoteServerProjects {
                 .into_any_element(),
         )
         .entry(state.add_new_server.clone())
-        .entry(state.add_new_wsl.clone());
+        .entry(state.add_new_wsl.clone())
+        .entry(state.add_new_container.clone());
 
         for server in &state.servers {
             match server {
@@ -0,0 +1,393 @@
+use std::{path::PathBuf, sync::Arc};
+
+use fuzzy::{StringMatch, StringMatchCandidate};
+use git::{SHORT_SHA_LENGTH, worktree::Worktree};
+use gpui::{
+    App, Context, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, PromptLevel,
+    Subscription, Task, WeakEntity, Window,
+};
+use picker::{Picker, PickerDelegate};
+use project::git_store::Repository;
+use ui::{HighlightedLabel, ListItem, ListItemSpacing, Tooltip, prelude::*};
+use util::{ResultExt as _, paths::PathExt};
+use workspace::{
+    ModalView, OpenOptions, Workspace, notifications::DetachAndPromptErr as _, open_paths,
+};
+
+/// Opens the worktree at `path` in a new window.
+pub fn open_worktree(
+    workspace: &Workspace,
+    path: PathBuf,
+    cx: &mut Context<Workspace>,
+) -> Task<anyhow::Result<()>> {
+    let open_task = open_paths(
+        &[path],
+        workspace.app_state().clone(),
+        OpenOptions {
+            open_new_workspace: Some(true),
+            ..Default::default()
+        },
+        cx,
+    );
+    cx.spawn(async move |_, _| {
+        open_task.await?;
+        Ok(())
+    })
+}
+
+pub struct WorktreePicker {
+    picker: Entity<Picker<WorktreePickerDelegate>>,
+    _subscription: Subscription,
+}
+
+impl ModalView for WorktreePicker {}
+
+impl WorktreePicker {
+    pub fn toggle(workspace: &mut Workspace, window: &mut Window, cx: &mut Context<Workspace>) {
+        let Some(repository) = workspace.project().read(cx).active_repository(cx) else {
+            return;
+        };
+        if !repository.read(cx).is_local(cx) {
+            return;
+        }
+        let weak = cx.entity().downgrade();
+        workspace.toggle_modal(window, cx, |window, cx| {
+            let delegate = WorktreePickerDelegate::new(weak, repository);
+            let picker = cx.new(|cx| {
+                let mut picker = Picker::uniform_list(delegate, window, cx);
+                picker.delegate.reload(window, cx);
+                picker
+            });
+            let _subscription = cx.subscribe(&picker, |_, _, _, cx| cx.emit(DismissEvent));
+            Self {
+                picker,
+                _subscription,
+            }
+        })
+    }
+}
+
+impl EventEmitter<DismissEvent> for WorktreePicker {}
+
+impl Focusable for WorktreePicker {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl Render for WorktreePicker {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .key_context("WorktreePicker")
+            .w(rems(34.))
+            .child(self.picker.clone())
+            .on_mouse_down_out(cx.listener(|this, _, window, cx| {
+                this.picker.update(cx, |this, cx| {
+                    this.cancel(&Default::default(), window, cx);
+                })
+            }))
+    }
+}
+
+pub struct WorktreePickerDelegate {
+    workspace: WeakEntity<Workspace>,
+    repository: Entity<Repository>,
+    worktrees: Vec<Worktree>,
+    matches: Vec<StringMatch>,
+    selected_index: usize,
+}
+
+impl WorktreePickerDelegate {
+    fn new(workspace: WeakEntity<Workspace>, repository: Entity<Repository>) -> Self {
+        Self {
+            workspace,
+            repository,
+            worktrees: Vec::new(),
+            matches: Vec::new(),
+            selected_index: 0,
+        }
+    }
+
+    fn reload(&mut self, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let worktrees = self
+            .repository
+            .update(cx, |repository, _| repository.worktrees());
+        cx.spawn_in(window, async move |picker, cx| {
+            let worktrees = worktrees.await??;
+            picker.update_in(cx, |picker, window, cx| {
+                picker.delegate.worktrees = worktrees;
+                picker.refresh(window, cx);
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn is_current(&self, worktree: &Worktree, cx: &App) -> bool {
+        worktree.path == *self.repository.read(cx).work_directory_abs_path
+    }
+
+    fn toggle_lock(&mut self, ix: usize, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(worktree) = self.worktree_at(ix).cloned() else {
+            return;
+        };
+        let task = self.repository.update(cx, |repository, _| {
+            if worktree.is_locked {
+                repository.unlock_worktree(worktree.path)
+            } else {
+                repository.lock_worktree(worktree.path, None)
+            }
+        });
+        cx.spawn_in(window, async move |picker, cx| {
+            task.await??;
+            picker.update_in(cx, |picker, window, cx| picker.delegate.reload(window, cx))
+        })
+        .detach_and_prompt_err("Failed to update worktree", window, cx, |e, _, _| {
+            Some(e.to_string())
+        });
+    }
+
+    fn remove(&mut self, ix: usize, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(worktree) = self.worktree_at(ix).cloned() else {
+            return;
+        };
+        let answer = window.prompt(
+            PromptLevel::Warning,
+            &format!(
+                "Remove the worktree at {}?",
+                worktree.path.compact().display()
+            ),
+            Some("Its directory will be deleted. Uncommitted changes prevent the removal."),
+            &["Remove", "Cancel"],
+            cx,
+        );
+        let repository = self.repository.clone();
+        cx.spawn_in(window, async move |picker, cx| {
+            if answer.await.ok() != Some(0) {
+                return Ok(());
+            }
+            repository
+                .update(cx, |repository, _| {
+                    repository.remove_worktree(worktree.path, false)
+                })?
+                .await??;
+            picker.update_in(cx, |picker, window, cx| picker.delegate.reload(window, cx))
+        })
+        .detach_and_prompt_err("Failed to remove worktree", window, cx, |e, _, _| {
+            Some(e.to_string())
+        });
+    }
+
+    fn worktree_at(&self, ix: usize) -> Option<&Worktree> {
+        let hit = self.matches.get(ix)?;
+        self.worktrees.get(hit.candidate_id)
+    }
+}
+
+impl PickerDelegate for WorktreePickerDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _window: &mut Window, _cx: &mut App) -> Arc<str> {
+        "Open worktree…".into()
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(
+        &mut self,
+        ix: usize,
+        _window: &mut Window,
+        _: &mut Context<Picker<Self>>,
+    ) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Task<()> {
+        let candidates = self
+            .worktrees
+            .iter()
+            .enumerate()
+            .map(|(ix, worktree)| {
+                let branch = worktree.branch_name().unwrap_or_default();
+                StringMatchCandidate::new(ix, &format!("{} {branch}", worktree.name()))
+            })
+            .collect::<Vec<_>>();
+        cx.spawn_in(window, async move |picker, cx| {
+            let matches = if query.is_empty() {
+                candidates
+                    .into_iter()
+                    .map(|candidate| StringMatch {
+                        candidate_id: candidate.id,
+                        score: 0.,
+                        positions: Vec::new(),
+                        string: candidate.string,
+                    })
+                    .collect()
+            } else {
+                fuzzy::match_strings(
+                    &candidates,
+                    &query,
+                    false,
+                    true,
+                    100,
+                    &Default::default(),
+                    cx.background_executor().clone(),
+                )
+                .await
+            };
+            picker
+                .update(cx, |picker, _| {
+                    let delegate = &mut picker.delegate;
+                    delegate.matches = matches;
+                    delegate.selected_index = delegate
+                        .selected_index
+                        .min(delegate.matches.len().saturating_sub(1));
+                })
+                .log_err();
+        })
+    }
+
+    fn confirm(&mut self, _secondary: bool, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(worktree) = self.worktree_at(self.selected_index) else {
+            return;
+        };
+        if !self.is_current(worktree, cx)
+            && let Some(workspace) = self.workspace.upgrade()
+        {
+            let path = worktree.path.clone();
+            workspace
+                .update(cx, |workspace, cx| open_worktree(workspace, path, cx))
+                .detach_and_prompt_err("Failed to open worktree", window, cx, |_, _, _| None);
+        }
+        cx.emit(DismissEvent);
+    }
+
+    fn dismissed(&mut self, _: &mut Window, cx: &mut Context<Picker<Self>>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn no_matches_text(&self, _window: &mut Window, _cx: &mut App) -> Option<SharedString> {
+        Some("No worktrees found".into())
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let hit = self.matches.get(ix)?;
+        let worktree = self.worktrees.get(hit.candidate_id)?;
+        let name = worktree.name();
+        let name_positions = hit
+            .positions
+            .iter()
+            .copied()
+            .filter(|position| *position < name.len())
+            .collect::<Vec<_>>();
+        let is_current = self.is_current(worktree, cx);
+        let details = match (worktree.branch_name(), &worktree.head_sha) {
+            (Some(branch), _) => branch.to_string(),
+            (None, Some(sha)) => format!("detached at {}", &sha[..sha.len().min(SHORT_SHA_LENGTH)]),
+            (None, None) => "bare".to_string(),
+        };
+
+        let buttons = (!worktree.is_main && !is_current).then(|| {
+            let is_locked = worktree.is_locked;
+            h_flex()
+                .gap_1()
+                .child(
+                    IconButton::new(
+                        "toggle-lock",
+                        if is_locked {
+                            IconName::FileLock
+                        } else {
+                            IconName::LockOutlined
+                        },
+                    )
+                    .icon_size(IconSize::Small)
+                    .on_click(cx.listener(move |this, _, window, cx| {
+                        cx.stop_propagation();
+                        this.delegate.toggle_lock(ix, window, cx);
+                    }))
+                    .tooltip(Tooltip::text(if is_locked {
+                        "Unlock Worktree"
+                    } else {
+                        "Lock Worktree"
+                    })),
+                )
+                .child(
+                    IconButton::new("remove", IconName::Trash)
+                        .icon_size(IconSize::Small)
+                        .disabled(is_locked)
+                        .on_click(cx.listener(move |this, _, window, cx| {
+                            cx.stop_propagation();
+                            this.delegate.remove(ix, window, cx);
+                        }))
+                        .tooltip(Tooltip::text("Remove Worktree…")),
+                )
+        });
+
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .toggle_state(selected)
+                .child(
+                    v_flex()
+                        .w_full()
+                        .overflow_hidden()
+                        .child(
+                            h_flex()
+                                .gap_2()
+                                .child(HighlightedLabel::new(name, name_positions).truncate())
+                                .when(worktree.is_main, |this| {
+                                    this.child(
+                                        Label::new("main")
+                                            .size(LabelSize::Small)
+                                            .color(Color::Muted),
+                                    )
+                                })
+                                .when(is_current, |this| {
+                                    this.child(
+                                        Label::new("current")
+                                            .size(LabelSize::Small)
+                                            .color(Color::Accent),
+                                    )
+                                })
+                                .when(worktree.is_locked, |this| {
+                                    this.child(
+                                        Icon::new(IconName::FileLock)
+                                            .size(IconSize::Small)
+                                            .color(Color::Warning),
+                                    )
+                                }),
+                        )
+                        .child(
+                            Label::new(format!(
+                                "{details} • {}",
+                                worktree.path.compact().display()
+                            ))
+                            .size(LabelSize::Small)
+                            .color(Color::Muted)
+                            .truncate(),
+                        ),
+                )
+                .when_some(worktree.lock_reason.clone(), |this, reason| {
+                    this.tooltip(Tooltip::text(format!("Locked: {reason}")))
+                })
+                .end_hover_slot::<Div>(buttons),
+        )
+    }
+}
@@ -8,5 +8,9 @@ pub use remote_client::{
     ConnectionIdentifier, ConnectionState, RemoteClient, RemoteClientDelegate, RemoteClientEvent,
     RemoteConnectionOptions, RemotePlatform,
 };
+pub use transport::docker::{
+    ContainerInfo, ContainerRuntime, DockerConnectionOptions, container_working_dir,
+    find_container_by_label, running_containers,
+};
 pub use transport::ssh::{SshConnectionOptions, SshPortForwardOption};
 pub use transport::wsl::WslConnectionOptions;
@@ -0,0 +1,723 @@
+use crate::{
+    RemoteClientDelegate, RemotePlatform,
+    remote_client::{CommandTemplate, RemoteConnection, RemoteConnectionOptions},
+};
+use anyhow::{Context as _, Result, anyhow, bail};
+use async_trait::async_trait;
+use collections::HashMap;
+use futures::{
+    AsyncWriteExt as _,
+    channel::mpsc::{Sender, UnboundedReceiver, UnboundedSender},
+};
+use gpui::{App, AppContext as _, AsyncApp, SemanticVersion, Task};
+use release_channel::{AppCommitSha, AppVersion, ReleaseChannel};
+use rpc::proto::Envelope;
+use smol::{fs, process};
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+    process::Stdio,
+    sync::Arc,
+    time::Instant,
+};
+use util::paths::{PathStyle, RemotePathBuf};
+
+/// The container engine used to run commands inside a container.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ContainerRuntime {
+    #[default]
+    Docker,
+    Podman,
+}
+
+impl ContainerRuntime {
+    pub fn program(&self) -> &'static str {
+        match self {
+            ContainerRuntime::Docker => "docker",
+            ContainerRuntime::Podman => "podman",
+        }
+    }
+}
+
+impl fmt::Display for ContainerRuntime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ContainerRuntime::Docker => "Docker",
+            ContainerRuntime::Podman => "Podman",
+        })
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct DockerConnectionOptions {
+    pub runtime: ContainerRuntime,
+    /// The name or id of a running container.
+    pub container: String,
+    /// The user to run commands as, instead of the container's default user.
+    pub user: Option<String>,
+}
+
+/// A running container, as listed by `docker ps`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContainerInfo {
+    pub id: String,
+    pub name: String,
+    pub image: String,
+    pub status: String,
+}
+
+/// Lists the running containers of `runtime`.
+pub async fn running_containers(runtime: ContainerRuntime) -> Result<Vec<ContainerInfo>> {
+    let output = util::command::new_smol_command(runtime.program())
+        .args([
+            "ps",
+            "--format",
+            "{{.ID}}\t{{.Names}}\t{{.Image}}\t{{.Status}}",
+        ])
+        .output()
+        .await
+        .with_context(|| format!("failed to run {}", runtime.program()))?;
+    anyhow::ensure!(
+        output.status.success(),
+        "failed to list containers: {}",
+        String::from_utf8_lossy(&output.stderr).trim()
+    );
+    Ok(parse_container_list(&String::from_utf8_lossy(
+        &output.stdout,
+    )))
+}
+
+/// Finds the running container whose labels include `label`, such as the
+/// `devcontainer.local_folder` label that dev container tools attach to the containers they create.
+pub async fn find_container_by_label(
+    runtime: ContainerRuntime,
+    label: &str,
+) -> Result<Option<String>> {
+    let output = util::command::new_smol_command(runtime.program())
+        .args(["ps", "--quiet", "--filter"])
+        .arg(format!("label={label}"))
+        .output()
+        .await
+        .with_context(|| format!("failed to run {}", runtime.program()))?;
+    anyhow::ensure!(
+        output.status.success(),
+        "failed to list containers: {}",
+        String::from_utf8_lossy(&output.stderr).trim()
+    );
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .next()
+        .map(|id| id.trim().to_string())
+        .filter(|id| !id.is_empty()))
+}
+
+/// Returns the working directory configured for `container`, if any.
+pub async fn container_working_dir(
+    runtime: ContainerRuntime,
+    container: &str,
+) -> Result<Option<String>> {
+    let output = util::command::new_smol_command(runtime.program())
+        .args(["inspect", "--format", "{{.Config.WorkingDir}}", container])
+        .output()
+        .await
+        .with_context(|| format!("failed to run {}", runtime.program()))?;
+    anyhow::ensure!(
+        output.status.success(),
+        "failed to inspect container {container}: {}",
+        String::from_utf8_lossy(&output.stderr).trim()
+    );
+    let working_dir = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    Ok(Some(working_dir).filter(|dir| !dir.is_empty()))
+}
+
+fn parse_container_list(output: &str) -> Vec<ContainerInfo> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split('\t');
+            Some(ContainerInfo {
+                id: fields.next()?.to_string(),
+                // Docker lists every name of the container, separated by commas.
+                name: fields.next()?.split(',').next()?.to_string(),
+                image: fields.next().unwrap_or_default().to_string(),
+                status: fields.next().unwrap_or_default().to_string(),
+            })
+        })
+        .filter(|container| !container.id.is_empty())
+        .collect()
+}
+
+pub(crate) struct DockerExecConnection {
+    remote_binary_path: Option<RemotePathBuf>,
+    platform: RemotePlatform,
+    shell: String,
+    default_system_shell: String,
+    home_dir: String,
+    connection_options: DockerConnectionOptions,
+}
+
+impl DockerExecConnection {
+    pub(crate) async fn new(
+        connection_options: DockerConnectionOptions,
+        delegate: Arc<dyn RemoteClientDelegate>,
+        cx: &mut AsyncApp,
+    ) -> Result<Self> {
+        log::info!(
+            "Connecting to {} container {} with user {:?}",
+            connection_options.runtime,
+            connection_options.container,
+            connection_options.user
+        );
+        let (release_channel, version, commit) = cx.update(|cx| {
+            (
+                ReleaseChannel::global(cx),
+                AppVersion::global(cx),
+                AppCommitSha::try_global(cx),
+            )
+        })?;
+
+        let mut this = Self {
+            connection_options,
+            remote_binary_path: None,
+            platform: RemotePlatform { os: "", arch: "" },
+            shell: String::new(),
+            default_system_shell: String::from("/bin/sh"),
+            home_dir: String::new(),
+        };
+        delegate.set_status(Some("Detecting container environment"), cx);
+        this.platform = this.detect_platform().await?;
+        this.shell = this.detect_shell().await?;
+        this.home_dir = this.detect_home_dir().await?;
+        this.remote_binary_path = Some(
+            this.ensure_server_binary(&delegate, release_channel, version, commit, cx)
+                .await?,
+        );
+
+        Ok(this)
+    }
+
+    async fn detect_platform(&self) -> Result<RemotePlatform> {
+        let uname = self.run_command("uname", &["-sm"]).await?;
+        let mut parts = uname.split_whitespace();
+        let (Some(os), Some(arch)) = (parts.next(), parts.next()) else {
+            bail!("unexpected output of uname -sm: {uname:?}");
+        };
+        let os = match os {
+            "Linux" => "linux",
+            _ => bail!("unsupported container platform: {os}"),
+        };
+        let arch = match arch {
+            "x86_64" | "amd64" => "x86_64",
+            "aarch64" | "arm64" | "armv8l" => "aarch64",
+            _ => bail!("unsupported container architecture: {arch}"),
+        };
+        Ok(RemotePlatform { os, arch })
+    }
+
+    async fn detect_shell(&self) -> Result<String> {
+        Ok(self
+            .run_command("sh", &["-c", "echo $SHELL"])
+            .await
+            .ok()
+            .map(|shell| shell.trim().to_string())
+            .filter(|shell| !shell.is_empty())
+            .unwrap_or_else(|| self.default_system_shell.clone()))
+    }
+
+    async fn detect_home_dir(&self) -> Result<String> {
+        let home_dir = self.run_command("sh", &["-c", "echo $HOME"]).await?;
+        let home_dir = home_dir.trim();
+        Ok(if home_dir.is_empty() {
+            "/root"
+        } else {
+            home_dir
+        }
+        .to_string())
+    }
+
+    fn exec_command(&self, interactive: bool) -> process::Command {
+        let mut command =
+            util::command::new_smol_command(self.connection_options.runtime.program());
+        command.arg("exec");
+        if interactive {
+            command.arg("--interactive");
+        }
+        if let Some(user) = &self.connection_options.user {
+            command.args(["--user", user]);
+        }
+        command
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        command
+    }
+
+    async fn run_command(&self, program: &str, args: &[&str]) -> Result<String> {
+        let output = self
+            .exec_command(false)
+            .arg(&self.connection_options.container)
+            .arg(program)
+            .args(args)
+            .output()
+            .await
+            .with_context(|| {
+                format!(
+                    "failed to run {}",
+                    self.connection_options.runtime.program()
+                )
+            })?;
+
+        if !output.status.success() {
+            return Err(anyhow!(
+                "Command '{}' failed: {}",
+                program,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
+
+    async fn ensure_server_binary(
+        &self,
+        delegate: &Arc<dyn RemoteClientDelegate>,
+        release_channel: ReleaseChannel,
+        version: SemanticVersion,
+        commit: Option<AppCommitSha>,
+        cx: &mut AsyncApp,
+    ) -> Result<RemotePathBuf> {
+        let version_str = match release_channel {
+            ReleaseChannel::Nightly => {
+                let commit = commit.map(|s| s.full()).unwrap_or_default();
+                format!("{}-{}", version, commit)
+            }
+            ReleaseChannel::Dev => "build".to_string(),
+            _ => version.to_string(),
+        };
+
+        let binary_name = format!(
+            "zed-remote-server-{}-{}",
+            release_channel.dev_name(),
+            version_str
+        );
+
+        let dst_path = RemotePathBuf::new(
+            Path::new(&self.home_dir)
+                .join(paths::remote_server_dir_relative())
+                .join(binary_name),
+            PathStyle::Posix,
+        );
+
+        #[cfg(debug_assertions)]
+        if let Some(remote_server_path) =
+            super::build_remote_server_from_source(&self.platform, delegate.as_ref(), cx).await?
+        {
+            let tmp_path = RemotePathBuf::new(
+                PathBuf::from(format!(
+                    "{}.{}.{}",
+                    dst_path,
+                    std::process::id(),
+                    remote_server_path
+                        .extension()
+                        .map_or("bin".into(), |extension| extension.to_string_lossy())
+                )),
+                PathStyle::Posix,
+            );
+            self.upload_file(&remote_server_path, &tmp_path, delegate, cx)
+                .await?;
+            self.extract_and_install(&tmp_path, &dst_path, delegate, cx)
+                .await?;
+            return Ok(dst_path);
+        }
+
+        if self
+            .run_command(&dst_path.to_string(), &["version"])
+            .await
+            .is_ok()
+        {
+            return Ok(dst_path);
+        }
+
+        delegate.set_status(Some("Installing remote server"), cx);
+
+        let wanted_version = match release_channel {
+            ReleaseChannel::Nightly | ReleaseChannel::Dev => None,
+            _ => Some(cx.update(|cx| AppVersion::global(cx))?),
+        };
+
+        let src_path = delegate
+            .download_server_binary_locally(self.platform, release_channel, wanted_version, cx)
+            .await?;
+
+        let tmp_path = RemotePathBuf::new(
+            PathBuf::from(format!("{}.{}.gz", dst_path, std::process::id())),
+            PathStyle::Posix,
+        );
+
+        self.upload_file(&src_path, &tmp_path, delegate, cx).await?;
+        self.extract_and_install(&tmp_path, &dst_path, delegate, cx)
+            .await?;
+
+        Ok(dst_path)
+    }
+
+    /// Streams the file into the container over the stdin of `docker exec`, so that it ends up
+    /// owned by the user the container is accessed as.
+    async fn upload_file(
+        &self,
+        src_path: &Path,
+        dst_path: &RemotePathBuf,
+        delegate: &Arc<dyn RemoteClientDelegate>,
+        cx: &mut AsyncApp,
+    ) -> Result<()> {
+        delegate.set_status(Some("Uploading remote server to container"), cx);
+
+        if let Some(parent) = dst_path.parent() {
+            self.run_command("mkdir", &["-p", &parent.to_string()])
+                .await
+                .map_err(|e| anyhow!("Failed to create directory when uploading file: {}", e))?;
+        }
+
+        let t0 = Instant::now();
+        let contents = fs::read(src_path).await?;
+        log::info!(
+            "uploading remote server to container {:?} ({}kb)",
+            dst_path,
+            contents.len() / 1024
+        );
+
+        let script = format!("cat > {}", shlex::try_quote(&dst_path.to_string())?);
+        let mut child = self
+            .exec_command(true)
+            .arg(&self.connection_options.container)
+            .args(["sh", "-c", &script])
+            .spawn()?;
+        let mut stdin = child.stdin.take().context("failed to open stdin")?;
+        stdin.write_all(&contents).await?;
+        stdin.close().await?;
+        drop(stdin);
+        let output = child.output().await?;
+        anyhow::ensure!(
+            output.status.success(),
+            "Failed to upload file {} to container {:?}: {}",
+            src_path.display(),
+            dst_path,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+
+        log::info!("uploaded remote server in {:?}", t0.elapsed());
+        Ok(())
+    }
+
+    async fn extract_and_install(
+        &self,
+        tmp_path: &RemotePathBuf,
+        dst_path: &RemotePathBuf,
+        delegate: &Arc<dyn RemoteClientDelegate>,
+        cx: &mut AsyncApp,
+    ) -> Result<()> {
+        delegate.set_status(Some("Extracting remote server"), cx);
+
+        let tmp_path_str = tmp_path.to_string();
+        let dst_path_str = dst_path.to_string();
+
+        let script = if tmp_path_str.ends_with(".gz") {
+            let uncompressed = tmp_path_str.trim_end_matches(".gz");
+            format!(
+                "set -e; gunzip -f '{}' && chmod 755 '{}' && mv -f '{}' '{}'",
+                tmp_path_str, uncompressed, uncompressed, dst_path_str
+            )
+        } else {
+            format!(
+                "set -e; chmod 755 '{}' && mv -f '{}' '{}'",
+                tmp_path_str, tmp_path_str, dst_path_str
+            )
+        };
+
+        self.run_command("sh", &["-c", &script])
+            .await
+            .map_err(|e| anyhow!("Failed to extract server binary: {}", e))?;
+        Ok(())
+    }
+}
+
+#[async_trait(?Send)]
+impl RemoteConnection for DockerExecConnection {
+    fn start_proxy(
+        &self,
+        unique_identifier: String,
+        reconnect: bool,
+        incoming_tx: UnboundedSender<Envelope>,
+        outgoing_rx: UnboundedReceiver<Envelope>,
+        connection_activity_tx: Sender<()>,
+        delegate: Arc<dyn RemoteClientDelegate>,
+        cx: &mut AsyncApp,
+    ) -> Task<Result<i32>> {
+        delegate.set_status(Some("Starting proxy"), cx);
+
+        let Some(remote_binary_path) = &self.remote_binary_path else {
+            return Task::ready(Err(anyhow!("Remote binary path not set")));
+        };
+
+        let mut proxy_command = format!(
+            "exec {} proxy --identifier {}",
+            remote_binary_path, unique_identifier
+        );
+        if reconnect {
+            proxy_command.push_str(" --reconnect");
+        }
+
+        let mut command = self.exec_command(true);
+        for env_var in ["RUST_LOG", "RUST_BACKTRACE", "ZED_GENERATE_MINIDUMPS"] {
+            if let Ok(value) = std::env::var(env_var) {
+                command.arg("--env").arg(format!("{env_var}={value}"));
+            }
+        }
+        let proxy_process = match command
+            .arg(&self.connection_options.container)
+            .args(["sh", "-lc", &proxy_command])
+            .kill_on_drop(true)
+            .spawn()
+        {
+            Ok(process) => process,
+            Err(error) => {
+                return Task::ready(Err(anyhow!("failed to spawn remote server: {}", error)));
+            }
+        };
+
+        super::handle_rpc_messages_over_child_process_stdio(
+            proxy_process,
+            incoming_tx,
+            outgoing_rx,
+            connection_activity_tx,
+            cx,
+        )
+    }
+
+    fn upload_directory(
+        &self,
+        src_path: PathBuf,
+        dest_path: RemotePathBuf,
+        cx: &App,
+    ) -> Task<Result<()>> {
+        let options = self.connection_options.clone();
+        cx.background_spawn(async move {
+            let output = util::command::new_smol_command(options.runtime.program())
+                .arg("cp")
+                .arg(&src_path)
+                .arg(format!("{}:{}", options.container, dest_path))
+                .output()
+                .await?;
+            anyhow::ensure!(
+                output.status.success(),
+                "failed to upload directory {} -> {}: {}",
+                src_path.display(),
+                dest_path,
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+            Ok(())
+        })
+    }
+
+    async fn kill(&self) -> Result<()> {
+        Ok(())
+    }
+
+    fn has_been_killed(&self) -> bool {
+        false
+    }
+
+    fn shares_network_interface(&self) -> bool {
+        false
+    }
+
+    fn build_command(
+        &self,
+        program: Option<String>,
+        args: &[String],
+        env: &HashMap<String, String>,
+        working_dir: Option<String>,
+        port_forward: Option<(u16, String, u16)>,
+    ) -> Result<CommandTemplate> {
+        if port_forward.is_some() {
+            bail!("port forwarding is not supported for containers");
+        }
+        build_command(
+            &self.connection_options,
+            &self.shell,
+            &self.home_dir,
+            program,
+            args,
+            env,
+            working_dir,
+        )
+    }
+
+    fn connection_options(&self) -> RemoteConnectionOptions {
+        RemoteConnectionOptions::Docker(self.connection_options.clone())
+    }
+
+    fn path_style(&self) -> PathStyle {
+        PathStyle::Posix
+    }
+
+    fn shell(&self) -> String {
+        self.shell.clone()
+    }
+
+    fn default_system_shell(&self) -> String {
+        self.default_system_shell.clone()
+    }
+}
+
+fn build_command(
+    options: &DockerConnectionOptions,
+    shell: &str,
+    home_dir: &str,
+    program: Option<String>,
+    args: &[String],
+    env: &HashMap<String, String>,
+    working_dir: Option<String>,
+) -> Result<CommandTemplate> {
+    let working_dir = match working_dir {
+        Some(working_dir) if working_dir == "~" => home_dir.to_string(),
+        Some(working_dir) => match working_dir.strip_prefix("~/") {
+            Some(relative) => format!("{home_dir}/{relative}"),
+            None => working_dir,
+        },
+        None => home_dir.to_string(),
+    };
+
+    let mut exec_args = vec![
+        "exec".to_string(),
+        "--interactive".to_string(),
+        "--tty".to_string(),
+        "--workdir".to_string(),
+        working_dir,
+    ];
+    if let Some(user) = &options.user {
+        exec_args.extend(["--user".to_string(), user.clone()]);
+    }
+    let mut env = env.iter().collect::<Vec<_>>();
+    env.sort();
+    for (key, value) in env {
+        exec_args.extend(["--env".to_string(), format!("{key}={value}")]);
+    }
+    exec_args.push(options.container.clone());
+
+    if let Some(program) = program {
+        let mut script = shlex::try_quote(&program)?.into_owned();
+        for arg in args {
+            script.push(' ');
+            script.push_str(&shlex::try_quote(arg)?);
+        }
+        exec_args.extend([shell.to_string(), "-c".to_string(), script]);
+    } else {
+        exec_args.extend([shell.to_string(), "-l".to_string()]);
+    }
+
+    Ok(CommandTemplate {
+        program: options.runtime.program().to_string(),
+        args: exec_args,
+        env: HashMap::default(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_container_list() {
+        let output = "\
+3f2a9c1e7b4d\tzed-dev,zed-dev-alias\tmcr.microsoft.com/devcontainers/rust:1\tUp 2 hours
+9b8c7d6e5f4a\tpostgres\tpostgres:16\tUp 5 minutes (healthy)
+";
+        let containers = parse_container_list(output);
+        assert_eq!(
+            containers,
+            [
+                ContainerInfo {
+                    id: "3f2a9c1e7b4d".into(),
+                    name: "zed-dev".into(),
+                    image: "mcr.microsoft.com/devcontainers/rust:1".into(),
+                    status: "Up 2 hours".into(),
+                },
+                ContainerInfo {
+                    id: "9b8c7d6e5f4a".into(),
+                    name: "postgres".into(),
+                    image: "postgres:16".into(),
+                    status: "Up 5 minutes (healthy)".into(),
+                },
+            ]
+        );
+        assert!(parse_container_list("").is_empty());
+    }
+
+    #[test]
+    fn test_build_command() -> Result<()> {
+        let options = DockerConnectionOptions {
+            runtime: ContainerRuntime::Podman,
+            container: "zed-dev".into(),
+            user: Some("vscode".into()),
+        };
+        let mut env = HashMap::default();
+        env.insert("INPUT_VA".to_string(), "val".to_string());
+
+        let command = build_command(
+            &options,
+            "/bin/bash",
+            "/home/vscode",
+            Some("cargo".to_string()),
+            &["test".to_string(), "my crate".to_string()],
+            &env,
+            Some("~/project".to_string()),
+        )?;
+        assert_eq!(command.program, "podman");
+        assert_eq!(
+            command.args.iter().map(String::as_str).collect::<Vec<_>>(),
+            [
+                "exec",
+                "--interactive",
+                "--tty",
+                "--workdir",
+                "/home/vscode/project",
+                "--user",
+                "vscode",
+                "--env",
+                "INPUT_VA=val",
+                "zed-dev",
+                "/bin/bash",
+                "-c",
+                "cargo test 'my crate'"
+            ]
+        );
+
+        let command = build_command(
+            &options,
+            "/bin/bash",
+            "/home/vscode",
+            None,
+            &[],
+            &HashMap::default(),
+            None,
+        )?;
+        assert_eq!(
+            command.args.iter().map(String::as_str).collect::<Vec<_>>(),
+            [
+                "exec",
+                "--interactive",
+                "--tty",
+                "--workdir",
+                "/home/vscode",
+                "--user",
+                "vscode",
+                "zed-dev",
+                "/bin/bash",
+                "-l"
+            ]
+        );
+
+        Ok(())
+    }
+}
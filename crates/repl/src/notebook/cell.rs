@@ -1,14 +1,18 @@
 #![allow(unused, dead_code)]
-use std::sync::Arc;
+use std::{mem, sync::Arc};
 
-use editor::{Editor, EditorMode, MultiBuffer};
+use anyhow::Result;
+use editor::{Editor, EditorEvent, EditorMode, MultiBuffer};
 use futures::future::Shared;
 use gpui::{
-    App, Entity, Hsla, RetainAllImageCache, Task, TextStyleRefinement, image_cache, prelude::*,
+    App, Entity, EventEmitter, Hsla, RetainAllImageCache, Subscription, Task, TextStyleRefinement,
+    image_cache, prelude::*,
 };
 use language::{Buffer, Language, LanguageRegistry};
 use markdown_preview::{markdown_parser::parse_markdown, markdown_renderer::render_markdown_block};
 use nbformat::v4::{CellId, CellMetadata, CellType};
+use runtimelib::{JupyterMessage, JupyterMessageContent};
+use serde_json::json;
 use settings::Settings as _;
 use theme::ThemeSettings;
 use ui::{IconButtonShape, prelude::*};
@@ -85,6 +89,30 @@ pub enum Cell {
     Raw(Entity<RawCell>),
 }
 
+pub enum CellEvent {
+    /// The user asked to run the cell.
+    Run,
+}
+
+fn convert_output(output: &nbformat::v4::Output, window: &mut Window, cx: &mut App) -> Output {
+    match output {
+        nbformat::v4::Output::Stream { text, .. } => Output::Stream {
+            content: cx.new(|cx| TerminalOutput::from(&text.0, window, cx)),
+        },
+        nbformat::v4::Output::DisplayData(display_data) => {
+            Output::new(&display_data.data, None, window, cx)
+        }
+        nbformat::v4::Output::ExecuteResult(execute_result) => {
+            Output::new(&execute_result.data, None, window, cx)
+        }
+        nbformat::v4::Output::Error(error) => Output::ErrorOutput(ErrorView {
+            ename: error.ename.clone(),
+            evalue: error.evalue.clone(),
+            traceback: cx.new(|cx| TerminalOutput::from(&error.traceback.join("\n"), window, cx)),
+        }),
+    }
+}
+
 fn convert_outputs(
     outputs: &Vec<nbformat::v4::Output>,
     window: &mut Window,
@@ -92,26 +120,105 @@ fn convert_outputs(
 ) -> Vec<Output> {
     outputs
         .iter()
-        .map(|output| match output {
-            nbformat::v4::Output::Stream { text, .. } => Output::Stream {
-                content: cx.new(|cx| TerminalOutput::from(&text.0, window, cx)),
-            },
-            nbformat::v4::Output::DisplayData(display_data) => {
-                Output::new(&display_data.data, None, window, cx)
-            }
-            nbformat::v4::Output::ExecuteResult(execute_result) => {
-                Output::new(&execute_result.data, None, window, cx)
-            }
-            nbformat::v4::Output::Error(error) => Output::ErrorOutput(ErrorView {
-                ename: error.ename.clone(),
-                evalue: error.evalue.clone(),
-                traceback: cx
-                    .new(|cx| TerminalOutput::from(&error.traceback.join("\n"), window, cx)),
-            }),
-        })
+        .map(|output| convert_output(output, window, cx))
         .collect()
 }
 
+/// Converts a message the kernel sent while executing a cell into the output that's saved in
+/// the notebook, if the message is an output at all.
+fn notebook_output(content: &JupyterMessageContent) -> Option<nbformat::v4::Output> {
+    let output = match content {
+        JupyterMessageContent::StreamContent(stream) => json!({
+            "output_type": "stream",
+            "name": stream.name,
+            "text": stream.text,
+        }),
+        JupyterMessageContent::DisplayData(display_data) => json!({
+            "output_type": "display_data",
+            "data": display_data.data,
+            "metadata": display_data.metadata,
+        }),
+        JupyterMessageContent::ExecuteResult(result) => json!({
+            "output_type": "execute_result",
+            "execution_count": result.execution_count,
+            "data": result.data,
+            "metadata": result.metadata,
+        }),
+        JupyterMessageContent::ErrorOutput(error) => json!({
+            "output_type": "error",
+            "ename": error.ename,
+            "evalue": error.evalue,
+            "traceback": error.traceback,
+        }),
+        _ => return None,
+    };
+    serde_json::from_value(output).log_err()
+}
+
+/// Splits the text into the lines nbformat stores cell sources as, each keeping its newline.
+fn source_lines(text: &str) -> Vec<String> {
+    text.split_inclusive('\n').map(ToOwned::to_owned).collect()
+}
+
+/// Creates an empty cell with a new id.
+pub fn new_cell(cell_type: CellType) -> Result<nbformat::v4::Cell> {
+    let id = uuid::Uuid::new_v4().to_string();
+    let cell = match cell_type {
+        CellType::Code => json!({
+            "cell_type": "code",
+            "id": id,
+            "metadata": {},
+            "execution_count": null,
+            "source": [],
+            "outputs": [],
+        }),
+        CellType::Markdown => json!({
+            "cell_type": "markdown",
+            "id": id,
+            "metadata": {},
+            "source": [],
+        }),
+        CellType::Raw => json!({
+            "cell_type": "raw",
+            "id": id,
+            "metadata": {},
+            "source": [],
+        }),
+    };
+    Ok(serde_json::from_value(cell)?)
+}
+
+fn cell_editor(buffer: Entity<Buffer>, window: &mut Window, cx: &mut App) -> Entity<Editor> {
+    let multi_buffer = cx.new(|cx| MultiBuffer::singleton(buffer, cx));
+
+    cx.new(|cx| {
+        let mut editor = Editor::new(
+            EditorMode::AutoHeight {
+                min_lines: 1,
+                max_lines: Some(1024),
+            },
+            multi_buffer,
+            None,
+            window,
+            cx,
+        );
+
+        let theme = ThemeSettings::get_global(cx);
+
+        let refinement = TextStyleRefinement {
+            font_family: Some(theme.buffer_font.family.clone()),
+            font_size: Some(theme.buffer_font_size(cx).into()),
+            color: Some(cx.theme().colors().editor_foreground),
+            background_color: Some(gpui::transparent_black()),
+            ..Default::default()
+        };
+
+        editor.set_show_gutter(false, cx);
+        editor.set_text_style_refinement(refinement);
+        editor
+    })
+}
+
 impl Cell {
     pub fn load(
         cell: &nbformat::v4::Cell,
@@ -125,40 +232,46 @@ impl Cell {
                 id,
                 metadata,
                 source,
-                ..
+                attachments,
             } => {
                 let source = source.join("");
 
                 let entity = cx.new(|cx| {
-                    let markdown_parsing_task = {
-                        let languages = languages.clone();
-                        let source = source.clone();
-
-                        cx.spawn_in(window, async move |this, cx| {
-                            let parsed_markdown = cx
-                                .background_spawn(async move {
-                                    parse_markdown(&source, None, Some(languages)).await
-                                })
-                                .await;
-
-                            this.update(cx, |cell: &mut MarkdownCell, _| {
-                                cell.parsed_markdown = Some(parsed_markdown);
-                            })
-                            .log_err();
-                        })
-                    };
+                    let buffer = cx.new(|cx| Buffer::local(source.clone(), cx));
+                    let editor = cell_editor(buffer.clone(), window, cx);
+                    let editor_subscription = cx.subscribe(
+                        &editor,
+                        |cell: &mut MarkdownCell, editor, event: &EditorEvent, cx| {
+                            if let EditorEvent::BufferEdited = event {
+                                cell.source = editor.read(cx).text(cx);
+                                cell.parse_markdown(cx);
+                            }
+                        },
+                    );
+
+                    let markdown_language = languages.language_for_name("Markdown");
+                    cx.spawn(async move |_, cx| {
+                        let language = markdown_language.await.log_err();
+                        buffer.update(cx, |buffer, cx| buffer.set_language(language, cx))
+                    })
+                    .detach_and_log_err(cx);
 
-                    MarkdownCell {
-                        markdown_parsing_task,
+                    let mut cell = MarkdownCell {
+                        markdown_parsing_task: Task::ready(()),
                         image_cache: RetainAllImageCache::new(cx),
                         languages: languages.clone(),
                         id: id.clone(),
                         metadata: metadata.clone(),
-                        source: source.clone(),
+                        attachments: attachments.clone(),
+                        source,
+                        editor,
                         parsed_markdown: None,
                         selected: false,
                         cell_position: None,
-                    }
+                        _editor_subscription: editor_subscription,
+                    };
+                    cell.parse_markdown(cx);
+                    cell
                 });
 
                 Cell::Markdown(entity)
@@ -173,37 +286,7 @@ impl Cell {
                 let text = source.join("");
 
                 let buffer = cx.new(|cx| Buffer::local(text.clone(), cx));
-                let multi_buffer = cx.new(|cx| MultiBuffer::singleton(buffer.clone(), cx));
-
-                let editor_view = cx.new(|cx| {
-                    let mut editor = Editor::new(
-                        EditorMode::AutoHeight {
-                            min_lines: 1,
-                            max_lines: Some(1024),
-                        },
-                        multi_buffer,
-                        None,
-                        window,
-                        cx,
-                    );
-
-                    let theme = ThemeSettings::get_global(cx);
-
-                    let refinement = TextStyleRefinement {
-                        font_family: Some(theme.buffer_font.family.clone()),
-                        font_size: Some(theme.buffer_font_size(cx).into()),
-                        color: Some(cx.theme().colors().editor_foreground),
-                        background_color: Some(gpui::transparent_black()),
-                        ..Default::default()
-                    };
-
-                    editor.set_text(text, window, cx);
-                    editor.set_show_gutter(false, cx);
-                    editor.set_text_style_refinement(refinement);
-
-                    // editor.set_read_only(true);
-                    editor
-                });
+                let editor_view = cell_editor(buffer.clone(), window, cx);
 
                 let buffer = buffer.clone();
                 let language_task = cx.spawn_in(window, async move |this, cx| {
@@ -221,6 +304,8 @@ impl Cell {
                     source: source.join(""),
                     editor: editor_view,
                     outputs: convert_outputs(outputs, window, cx),
+                    notebook_outputs: outputs.clone(),
+                    clear_outputs_on_next_output: false,
                     selected: false,
                     language_task,
                     cell_position: None,
@@ -239,6 +324,81 @@ impl Cell {
             })),
         }
     }
+
+    pub fn id(&self, cx: &App) -> CellId {
+        match self {
+            Cell::Code(cell) => cell.read(cx).id.clone(),
+            Cell::Markdown(cell) => cell.read(cx).id.clone(),
+            Cell::Raw(cell) => cell.read(cx).id.clone(),
+        }
+    }
+
+    pub fn editor(&self, cx: &App) -> Option<Entity<Editor>> {
+        match self {
+            Cell::Code(cell) => Some(cell.read(cx).editor.clone()),
+            Cell::Markdown(cell) => Some(cell.read(cx).editor.clone()),
+            Cell::Raw(_) => None,
+        }
+    }
+
+    /// Whether the source of the cell was edited since the notebook was last saved.
+    pub fn is_dirty(&self, cx: &App) -> bool {
+        self.editor(cx).is_some_and(|editor| {
+            editor
+                .read(cx)
+                .buffer()
+                .read(cx)
+                .as_singleton()
+                .is_some_and(|buffer| buffer.read(cx).is_dirty())
+        })
+    }
+
+    pub fn did_save(&self, cx: &mut App) {
+        let Some(buffer) = self
+            .editor(cx)
+            .and_then(|editor| editor.read(cx).buffer().read(cx).as_singleton())
+        else {
+            return;
+        };
+        buffer.update(cx, |buffer, cx| {
+            let version = buffer.version();
+            buffer.did_save(version, None, cx);
+        });
+    }
+
+    /// Converts the cell back into its nbformat representation, with its current source and
+    /// outputs.
+    pub fn to_nbformat(&self, cx: &App) -> nbformat::v4::Cell {
+        match self {
+            Cell::Code(cell) => {
+                let cell = cell.read(cx);
+                nbformat::v4::Cell::Code {
+                    id: cell.id.clone(),
+                    metadata: cell.metadata.clone(),
+                    execution_count: cell.execution_count,
+                    source: source_lines(&cell.editor.read(cx).text(cx)),
+                    outputs: cell.notebook_outputs.clone(),
+                }
+            }
+            Cell::Markdown(cell) => {
+                let cell = cell.read(cx);
+                nbformat::v4::Cell::Markdown {
+                    id: cell.id.clone(),
+                    metadata: cell.metadata.clone(),
+                    source: source_lines(&cell.source),
+                    attachments: cell.attachments.clone(),
+                }
+            }
+            Cell::Raw(cell) => {
+                let cell = cell.read(cx);
+                nbformat::v4::Cell::Raw {
+                    id: cell.id.clone(),
+                    metadata: cell.metadata.clone(),
+                    source: source_lines(&cell.source),
+                }
+            }
+        }
+    }
 }
 
 pub trait RenderableCell: Render {
@@ -335,13 +495,37 @@ pub trait RunnableCell: RenderableCell {
 pub struct MarkdownCell {
     id: CellId,
     metadata: CellMetadata,
+    attachments: Option<serde_json::Value>,
     image_cache: Entity<RetainAllImageCache>,
     source: String,
+    editor: Entity<Editor>,
     parsed_markdown: Option<markdown_preview::markdown_elements::ParsedMarkdown>,
     markdown_parsing_task: Task<()>,
     selected: bool,
     cell_position: Option<CellPosition>,
     languages: Arc<LanguageRegistry>,
+    _editor_subscription: Subscription,
+}
+
+impl MarkdownCell {
+    fn parse_markdown(&mut self, cx: &mut Context<Self>) {
+        let languages = self.languages.clone();
+        let source = self.source.clone();
+
+        self.markdown_parsing_task = cx.spawn(async move |this, cx| {
+            let parsed_markdown = cx
+                .background_spawn(
+                    async move { parse_markdown(&source, None, Some(languages)).await },
+                )
+                .await;
+
+            this.update(cx, |cell, cx| {
+                cell.parsed_markdown = Some(parsed_markdown);
+                cx.notify();
+            })
+            .log_err();
+        });
+    }
 }
 
 impl RenderableCell for MarkdownCell {
@@ -388,8 +572,42 @@ impl RenderableCell for MarkdownCell {
 
 impl Render for MarkdownCell {
     fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        // The source is edited in place while the cell is selected, and previewed otherwise.
+        let is_editing = self.selected || self.source.trim().is_empty();
+        if is_editing {
+            return v_flex()
+                .size_full()
+                .children(self.cell_position_spacer(true, window, cx))
+                .child(
+                    h_flex()
+                        .w_full()
+                        .pr_6()
+                        .rounded_xs()
+                        .items_start()
+                        .gap(DynamicSpacing::Base08.rems(cx))
+                        .bg(self.selected_bg_color(window, cx))
+                        .child(self.gutter(window, cx))
+                        .child(
+                            div().py_1p5().w_full().child(
+                                div()
+                                    .flex()
+                                    .size_full()
+                                    .flex_1()
+                                    .py_3()
+                                    .px_5()
+                                    .rounded_lg()
+                                    .border_1()
+                                    .border_color(cx.theme().colors().border)
+                                    .bg(cx.theme().colors().editor_background)
+                                    .child(div().w_full().child(self.editor.clone())),
+                            ),
+                        ),
+                )
+                .children(self.cell_position_spacer(false, window, cx));
+        }
+
         let Some(parsed) = self.parsed_markdown.as_ref() else {
-            return div();
+            return v_flex();
         };
 
         let mut markdown_render_context =
@@ -435,11 +653,16 @@ pub struct CodeCell {
     source: String,
     editor: Entity<editor::Editor>,
     outputs: Vec<Output>,
+    /// The outputs as they're saved in the notebook.
+    notebook_outputs: Vec<nbformat::v4::Output>,
+    clear_outputs_on_next_output: bool,
     selected: bool,
     cell_position: Option<CellPosition>,
     language_task: Task<()>,
 }
 
+impl EventEmitter<CellEvent> for CodeCell {}
+
 impl CodeCell {
     pub fn is_dirty(&self, cx: &App) -> bool {
         self.editor.read(cx).buffer().read(cx).is_dirty(cx)
@@ -450,6 +673,68 @@ impl CodeCell {
 
     pub fn clear_outputs(&mut self) {
         self.outputs.clear();
+        self.notebook_outputs.clear();
+        self.clear_outputs_on_next_output = false;
+        self.execution_count = None;
+    }
+
+    pub fn source_text(&self, cx: &App) -> String {
+        self.editor.read(cx).text(cx)
+    }
+
+    /// Accepts a message the kernel sent while executing the cell.
+    pub fn handle_message(
+        &mut self,
+        message: &JupyterMessage,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        match &message.content {
+            JupyterMessageContent::ExecuteInput(input) => {
+                self.execution_count = Some(input.execution_count.0 as i32);
+            }
+            JupyterMessageContent::ClearOutput(options) => {
+                if options.wait {
+                    self.clear_outputs_on_next_output = true;
+                } else {
+                    self.outputs.clear();
+                    self.notebook_outputs.clear();
+                }
+            }
+            content => {
+                let Some(output) = notebook_output(content) else {
+                    return;
+                };
+
+                if mem::take(&mut self.clear_outputs_on_next_output) {
+                    self.outputs.clear();
+                    self.notebook_outputs.clear();
+                }
+
+                // Consecutive text written to the same stream is combined into one output.
+                if let (
+                    Some(nbformat::v4::Output::Stream { name, text }),
+                    nbformat::v4::Output::Stream {
+                        name: new_name,
+                        text: new_text,
+                    },
+                ) = (self.notebook_outputs.last_mut(), &output)
+                    && *name == *new_name
+                {
+                    text.0.push_str(&new_text.0);
+                    if let Some(Output::Stream { content }) = self.outputs.last() {
+                        content.update(cx, |content, cx| {
+                            content.append_text(&new_text.0, cx);
+                            cx.notify();
+                        });
+                    }
+                } else {
+                    self.outputs.push(convert_output(&output, window, cx));
+                    self.notebook_outputs.push(output);
+                }
+            }
+        }
+        cx.notify();
     }
 
     fn output_control(&self) -> Option<CellControlType> {
@@ -526,10 +811,9 @@ impl RenderableCell for CodeCell {
             CellControl::new("rerun-cell", CellControlType::RerunCell)
         } else {
             CellControl::new("run-cell", CellControlType::RunCell)
-                .on_click(cx.listener(move |this, _, window, cx| this.run(window, cx)))
         };
 
-        Some(cell_control)
+        Some(cell_control.on_click(cx.listener(move |this, _, window, cx| this.run(window, cx))))
     }
 
     fn selected(&self) -> bool {
@@ -552,8 +836,8 @@ impl RenderableCell for CodeCell {
 }
 
 impl RunnableCell for CodeCell {
-    fn run(&mut self, window: &mut Window, cx: &mut Context<Self>) {
-        println!("Running code cell: {}", self.id);
+    fn run(&mut self, _window: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(CellEvent::Run);
     }
 
     fn execution_count(&self) -> Option<i32> {
@@ -642,6 +926,15 @@ impl Render for CodeCell {
                                             Output::Table { content, .. } => {
                                                 Some(content.clone().into_any_element())
                                             }
+                                            Output::Svg { content, .. } => {
+                                                Some(content.clone().into_any_element())
+                                            }
+                                            Output::Latex { content, .. } => {
+                                                Some(content.clone().into_any_element())
+                                            }
+                                            Output::Json { content, .. } => {
+                                                Some(content.clone().into_any_element())
+                                            }
                                             Output::ErrorOutput(error_view) => {
                                                 error_view.render(window, cx)
                                             }
@@ -744,3 +1037,74 @@ impl Render for RawCell {
             .children(self.cell_position_spacer(false, window, cx))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use runtimelib::{ClearOutput, ErrorOutput, Stdio, StreamContent};
+
+    #[test]
+    fn test_source_lines() {
+        assert_eq!(source_lines(""), Vec::<String>::new());
+        assert_eq!(source_lines("print(1)"), vec!["print(1)"]);
+        assert_eq!(
+            source_lines("import os\n\nprint(os.getcwd())\n"),
+            vec!["import os\n", "\n", "print(os.getcwd())\n"]
+        );
+    }
+
+    #[test]
+    fn test_notebook_output() {
+        let stream = notebook_output(&JupyterMessageContent::StreamContent(StreamContent {
+            name: Stdio::Stderr,
+            text: "warning\n".to_string(),
+        }));
+        assert_eq!(
+            serde_json::to_value(stream).unwrap(),
+            json!({"output_type": "stream", "name": "stderr", "text": "warning\n"})
+        );
+
+        let error = notebook_output(&JupyterMessageContent::ErrorOutput(ErrorOutput {
+            ename: "ZeroDivisionError".to_string(),
+            evalue: "division by zero".to_string(),
+            traceback: vec!["1 / 0".to_string()],
+        }));
+        assert_eq!(
+            serde_json::to_value(error).unwrap(),
+            json!({
+                "output_type": "error",
+                "ename": "ZeroDivisionError",
+                "evalue": "division by zero",
+                "traceback": ["1 / 0"],
+            })
+        );
+
+        assert!(
+            notebook_output(&JupyterMessageContent::ClearOutput(ClearOutput {
+                wait: false
+            }))
+            .is_none()
+        );
+    }
+
+    #[test]
+    fn test_new_cell() {
+        let nbformat::v4::Cell::Code {
+            source,
+            outputs,
+            execution_count,
+            ..
+        } = new_cell(CellType::Code).unwrap()
+        else {
+            panic!("expected a code cell");
+        };
+        assert!(source.is_empty());
+        assert!(outputs.is_empty());
+        assert_eq!(execution_count, None);
+
+        let first = new_cell(CellType::Markdown).unwrap();
+        let second = new_cell(CellType::Markdown).unwrap();
+        assert!(matches!(first, nbformat::v4::Cell::Markdown { .. }));
+        assert_ne!(first.id(), second.id());
+    }
+}
@@ -2,28 +2,34 @@
 use std::future::Future;
 use std::{path::PathBuf, sync::Arc};
 
-use anyhow::{Context as _, Result};
+use anyhow::{Context as _, Result, anyhow};
 use client::proto::ViewId;
 use collections::HashMap;
+use editor::EditorEvent;
 use feature_flags::{FeatureFlagAppExt as _, NotebookFeatureFlag};
 use futures::FutureExt;
 use futures::future::Shared;
 use gpui::{
     AnyElement, App, Entity, EventEmitter, FocusHandle, Focusable, ListScrollEvent, ListState,
-    Point, Task, actions, list, prelude::*,
+    PathPromptOptions, Point, Subscription, Task, WeakEntity, actions, list, prelude::*,
 };
 use language::{Language, LanguageRegistry};
-use project::{Project, ProjectEntryId, ProjectPath};
+use project::{DirectoryLister, Project, ProjectEntryId, ProjectPath};
+use serde::Serialize as _;
 use ui::{Tooltip, prelude::*};
+use util::ResultExt as _;
 use workspace::item::{ItemEvent, SaveOptions, TabContentParams};
 use workspace::searchable::SearchableItemHandle;
-use workspace::{Item, ItemHandle, Pane, ProjectItem, ToolbarItemLocation};
+use workspace::{Item, ItemHandle, OpenOptions, Pane, ProjectItem, ToolbarItemLocation, Workspace};
 use workspace::{ToolbarItemEvent, ToolbarItemView};
 
-use super::{Cell, CellPosition, RenderableCell};
+use super::{Cell, CellEvent, CellPosition, RenderableCell, new_cell};
+use crate::repl_store::ReplStore;
+use crate::session::SessionEvent;
+use crate::{KernelSpecification, Session};
 
-use nbformat::v4::CellId;
 use nbformat::v4::Metadata as NotebookMetadata;
+use nbformat::v4::{CellId, CellType};
 
 actions!(
     notebook,
@@ -74,9 +80,12 @@ pub fn init(cx: &mut App) {
 pub struct NotebookEditor {
     languages: Arc<LanguageRegistry>,
     project: Entity<Project>,
+    workspace: WeakEntity<Workspace>,
 
     focus_handle: FocusHandle,
     notebook_item: Entity<NotebookItem>,
+    notebook_language: Shared<Task<Option<Arc<Language>>>>,
+    session: Option<Entity<Session>>,
 
     remote_id: Option<ViewId>,
     cell_list: ListState,
@@ -84,6 +93,11 @@ pub struct NotebookEditor {
     selected_cell_index: usize,
     cell_order: Vec<CellId>,
     cell_map: HashMap<CellId, Cell>,
+    /// Whether cells were added, moved or run since the notebook was last saved. Edits to the
+    /// cell sources are tracked by their buffers.
+    has_unsaved_changes: bool,
+    cell_subscriptions: Vec<Subscription>,
+    _session_subscription: Option<Subscription>,
 }
 
 impl NotebookEditor {
@@ -96,49 +110,205 @@ impl NotebookEditor {
         let focus_handle = cx.focus_handle();
 
         let languages = project.read(cx).languages().clone();
-        let language_name = notebook_item.read(cx).language_name();
 
         let notebook_language = notebook_item.read(cx).notebook_language();
         let notebook_language = cx
             .spawn_in(window, async move |_, _| notebook_language.await)
             .shared();
 
-        let mut cell_order = vec![]; // Vec<CellId>
-        let mut cell_map = HashMap::default(); // HashMap<CellId, Cell>
+        let cell_list = ListState::new(0, gpui::ListAlignment::Top, px(1000.));
 
-        for (index, cell) in notebook_item
-            .read(cx)
-            .notebook
-            .clone()
-            .cells
-            .iter()
-            .enumerate()
-        {
-            let cell_id = cell.id();
-            cell_order.push(cell_id.clone());
-            cell_map.insert(
-                cell_id.clone(),
-                Cell::load(cell, &languages, notebook_language.clone(), window, cx),
-            );
-        }
-
-        let notebook_handle = cx.entity().downgrade();
-        let cell_count = cell_order.len();
-
-        let this = cx.entity();
-        let cell_list = ListState::new(cell_count, gpui::ListAlignment::Top, px(1000.));
+        // The kernel of a notebook is only reachable from its editor, so it goes away with it.
+        cx.on_release_in(window, |this, window, cx| {
+            if let Some(session) = this.session.take() {
+                session.update(cx, |session, cx| session.shutdown(window, cx));
+            }
+        })
+        .detach();
+        let notebook_id = cx.entity_id();
+        cx.on_release(move |_, cx| {
+            ReplStore::global(cx).update(cx, |store, _cx| {
+                store.remove_session(notebook_id);
+            });
+        })
+        .detach();
 
-        Self {
+        let mut this = Self {
             project,
-            languages: languages.clone(),
+            languages,
+            workspace: WeakEntity::new_invalid(),
             focus_handle,
             notebook_item,
+            notebook_language,
+            session: None,
             remote_id: None,
             cell_list,
             selected_cell_index: 0,
-            cell_order: cell_order.clone(),
-            cell_map: cell_map.clone(),
+            cell_order: Vec::new(),
+            cell_map: HashMap::default(),
+            has_unsaved_changes: false,
+            cell_subscriptions: Vec::new(),
+            _session_subscription: None,
+        };
+        this.load_cells(window, cx);
+        this
+    }
+
+    /// Replaces the cells with the ones of the notebook as it was last loaded or saved.
+    fn load_cells(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let cells = self.notebook_item.read(cx).notebook.cells.clone();
+
+        self.cell_order.clear();
+        self.cell_map.clear();
+        self.cell_subscriptions.clear();
+        for cell in &cells {
+            let cell_id = self.load_cell(cell, window, cx);
+            self.cell_order.push(cell_id);
+        }
+
+        self.cell_list.reset(self.cell_order.len());
+        self.selected_cell_index = self
+            .selected_cell_index
+            .min(self.cell_order.len().saturating_sub(1));
+    }
+
+    fn load_cell(
+        &mut self,
+        cell: &nbformat::v4::Cell,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> CellId {
+        let cell_id = cell.id().clone();
+        let cell = Cell::load(
+            cell,
+            &self.languages,
+            self.notebook_language.clone(),
+            window,
+            cx,
+        );
+
+        if let Cell::Code(code_cell) = &cell {
+            let cell_id = cell_id.clone();
+            self.cell_subscriptions.push(cx.subscribe_in(
+                code_cell,
+                window,
+                move |this, _, event: &CellEvent, window, cx| match event {
+                    CellEvent::Run => this.run_cell(&cell_id, window, cx),
+                },
+            ));
         }
+        if let Some(editor) = cell.editor(cx) {
+            self.cell_subscriptions
+                .push(cx.subscribe(&editor, |_, _, event: &EditorEvent, cx| {
+                    if let EditorEvent::DirtyChanged = event {
+                        cx.emit(());
+                    }
+                }));
+        }
+
+        self.cell_map.insert(cell_id.clone(), cell);
+        cell_id
+    }
+
+    fn notebook_changed(&mut self, cx: &mut Context<Self>) {
+        self.has_unsaved_changes = true;
+        cx.emit(());
+        cx.notify();
+    }
+
+    /// The notebook with the current cells, as it would be saved.
+    fn to_nbformat(&self, cx: &App) -> nbformat::v4::Notebook {
+        let mut notebook = self.notebook_item.read(cx).notebook.clone();
+        notebook.cells = self
+            .cell_order
+            .iter()
+            .filter_map(|cell_id| self.cell_map.get(cell_id))
+            .map(|cell| cell.to_nbformat(cx))
+            .collect();
+        notebook
+    }
+
+    fn kernel_specification(&self, cx: &App) -> Option<KernelSpecification> {
+        let store = ReplStore::global(cx);
+        let store = store.read(cx);
+        let notebook_item = self.notebook_item.read(cx);
+        let worktree_id = notebook_item.project_path.worktree_id;
+        let kernel_name = notebook_item
+            .notebook
+            .metadata
+            .kernelspec
+            .as_ref()
+            .map(|kernelspec| kernelspec.name.as_str());
+
+        // Prefer the kernel the notebook was last run with, then any kernel for its language.
+        store
+            .kernel_specifications_for_worktree(worktree_id)
+            .find(|specification| Some(specification.name().as_ref()) == kernel_name)
+            .or_else(|| {
+                let language = notebook_item.language_name()?;
+                store
+                    .kernel_specifications_for_worktree(worktree_id)
+                    .find(|specification| specification.language().eq_ignore_ascii_case(&language))
+            })
+            .cloned()
+    }
+
+    fn session(&mut self, window: &mut Window, cx: &mut Context<Self>) -> Option<Entity<Session>> {
+        if let Some(session) = &self.session {
+            return Some(session.clone());
+        }
+
+        let store = ReplStore::global(cx);
+        if !store.read(cx).is_enabled() {
+            return None;
+        }
+
+        let Some(kernel_specification) = self.kernel_specification(cx) else {
+            let language = self
+                .notebook_item
+                .read(cx)
+                .language_name()
+                .unwrap_or_else(|| "this notebook".to_string());
+            self.workspace
+                .update(cx, |workspace, cx| {
+                    workspace.show_error(&anyhow!("No kernel found for {language}"), cx);
+                })
+                .ok();
+            return None;
+        };
+
+        let working_directory = self.notebook_item.read(cx).path.parent()?.to_path_buf();
+        let fs = store.read(cx).fs().clone();
+        let notebook_id = cx.entity_id();
+        let session = cx.new(|cx| {
+            Session::detached(
+                notebook_id,
+                working_directory,
+                fs,
+                kernel_specification,
+                window,
+                cx,
+            )
+        });
+
+        self._session_subscription = Some(cx.subscribe(
+            &session,
+            move |this, _, event: &SessionEvent, cx| match event {
+                SessionEvent::Shutdown(_) => {
+                    ReplStore::global(cx).update(cx, |store, _cx| {
+                        store.remove_session(notebook_id);
+                    });
+                    this.session = None;
+                    cx.notify();
+                }
+            },
+        ));
+        store.update(cx, |store, _cx| {
+            store.insert_session(notebook_id, session.clone());
+        });
+
+        self.session = Some(session.clone());
+        Some(session)
     }
 
     fn has_outputs(&self, window: &mut Window, cx: &mut Context<Self>) -> bool {
@@ -152,37 +322,141 @@ impl NotebookEditor {
     }
 
     fn clear_outputs(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if !self.has_outputs(window, cx) {
+            return;
+        }
+
         for cell in self.cell_map.values() {
             if let Cell::Code(code_cell) = cell {
-                code_cell.update(cx, |cell, _cx| {
+                code_cell.update(cx, |cell, cx| {
                     cell.clear_outputs();
+                    cx.notify();
                 });
             }
         }
+        self.notebook_changed(cx);
     }
 
     fn run_cells(&mut self, window: &mut Window, cx: &mut Context<Self>) {
-        println!("Cells would all run here, if that was implemented!");
+        for cell_id in self.cell_order.clone() {
+            self.run_cell(&cell_id, window, cx);
+        }
     }
 
-    fn open_notebook(&mut self, _: &OpenNotebook, _window: &mut Window, _cx: &mut Context<Self>) {
-        println!("Open notebook triggered");
+    fn run_cell(&mut self, cell_id: &CellId, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(Cell::Code(cell)) = self.cell_map.get(cell_id).cloned() else {
+            return;
+        };
+        let Some(session) = self.session(window, cx) else {
+            return;
+        };
+
+        let code = cell.update(cx, |cell, cx| {
+            cell.clear_outputs();
+            cx.notify();
+            cell.source_text(cx)
+        });
+        let cell = cell.downgrade();
+        session.update(cx, |session, cx| {
+            session.execute_with_handler(
+                code,
+                move |message, window, cx| {
+                    cell.update(cx, |cell, cx| cell.handle_message(message, window, cx))
+                        .ok();
+                },
+                cx,
+            );
+        });
+        self.notebook_changed(cx);
+    }
+
+    fn open_notebook(&mut self, _: &OpenNotebook, window: &mut Window, cx: &mut Context<Self>) {
+        let workspace = self.workspace.clone();
+        let project = self.project.clone();
+        let Ok(paths) = workspace.update(cx, |workspace, cx| {
+            workspace.prompt_for_open_path(
+                PathPromptOptions {
+                    files: true,
+                    directories: false,
+                    multiple: true,
+                    prompt: None,
+                },
+                DirectoryLister::Project(project),
+                window,
+                cx,
+            )
+        }) else {
+            return;
+        };
+
+        cx.spawn_in(window, async move |_, cx| {
+            let Some(paths) = paths.await.log_err().flatten() else {
+                return Ok(());
+            };
+            for path in paths {
+                workspace
+                    .update_in(cx, |workspace, window, cx| {
+                        workspace.open_abs_path(path, OpenOptions::default(), window, cx)
+                    })?
+                    .await?;
+            }
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
     }
 
     fn move_cell_up(&mut self, window: &mut Window, cx: &mut Context<Self>) {
-        println!("Move cell up triggered");
+        let index = self.selected_cell_index;
+        if index == 0 || index >= self.cell_order.len() {
+            return;
+        }
+
+        self.cell_order.swap(index - 1, index);
+        self.cell_list.splice(index - 1..index + 1, 2);
+        self.set_selected_index(index - 1, true, window, cx);
+        self.notebook_changed(cx);
     }
 
     fn move_cell_down(&mut self, window: &mut Window, cx: &mut Context<Self>) {
-        println!("Move cell down triggered");
+        let index = self.selected_cell_index;
+        if index + 1 >= self.cell_order.len() {
+            return;
+        }
+
+        self.cell_order.swap(index, index + 1);
+        self.cell_list.splice(index..index + 2, 2);
+        self.set_selected_index(index + 1, true, window, cx);
+        self.notebook_changed(cx);
     }
 
     fn add_markdown_block(&mut self, window: &mut Window, cx: &mut Context<Self>) {
-        println!("Add markdown block triggered");
+        self.add_cell(CellType::Markdown, window, cx);
     }
 
     fn add_code_block(&mut self, window: &mut Window, cx: &mut Context<Self>) {
-        println!("Add code block triggered");
+        self.add_cell(CellType::Code, window, cx);
+    }
+
+    /// Adds an empty cell below the selected one, and focuses it.
+    fn add_cell(&mut self, cell_type: CellType, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(cell) = new_cell(cell_type).log_err() else {
+            return;
+        };
+
+        let index = if self.cell_order.is_empty() {
+            0
+        } else {
+            self.selected_cell_index + 1
+        };
+        let cell_id = self.load_cell(&cell, window, cx);
+        self.cell_order.insert(index, cell_id.clone());
+        self.cell_list.splice(index..index, 1);
+        self.set_selected_index(index, true, window, cx);
+
+        if let Some(editor) = self.cell_map.get(&cell_id).and_then(|cell| cell.editor(cx)) {
+            window.focus(&editor.focus_handle(cx));
+        }
+        self.notebook_changed(cx);
     }
 
     fn cell_count(&self) -> usize {
@@ -576,22 +850,7 @@ impl project::ProjectItem for NotebookItem {
 
                 // todo: watch for changes to the file
                 let file_content = fs.load(abs_path.as_path()).await?;
-                let notebook = nbformat::parse_notebook(&file_content);
-
-                let notebook = match notebook {
-                    Ok(nbformat::Notebook::V4(notebook)) => notebook,
-                    // 4.1 - 4.4 are converted to 4.5
-                    Ok(nbformat::Notebook::Legacy(legacy_notebook)) => {
-                        // TODO: Decide if we want to mutate the notebook by including Cell IDs
-                        // and any other conversions
-
-                        nbformat::upgrade_legacy_notebook(legacy_notebook)?
-                    }
-                    // Bad notebooks and notebooks v4.0 and below are not supported
-                    Err(e) => {
-                        anyhow::bail!("Failed to parse notebook: {:?}", e);
-                    }
-                };
+                let notebook = parse_notebook(&file_content)?;
 
                 let id = project
                     .update(cx, |project, cx| {
@@ -625,6 +884,34 @@ impl project::ProjectItem for NotebookItem {
     }
 }
 
+fn parse_notebook(content: &str) -> Result<nbformat::v4::Notebook> {
+    match nbformat::parse_notebook(content) {
+        Ok(nbformat::Notebook::V4(notebook)) => Ok(notebook),
+        // 4.1 - 4.4 are converted to 4.5
+        Ok(nbformat::Notebook::Legacy(legacy_notebook)) => {
+            // TODO: Decide if we want to mutate the notebook by including Cell IDs
+            // and any other conversions
+
+            Ok(nbformat::upgrade_legacy_notebook(legacy_notebook)?)
+        }
+        // Bad notebooks and notebooks v4.0 and below are not supported
+        Err(e) => {
+            anyhow::bail!("Failed to parse notebook: {:?}", e);
+        }
+    }
+}
+
+/// Serializes the notebook the way Jupyter does, with one space of indentation and a trailing
+/// newline, so that saving only changes the parts of the file that were edited.
+fn serialize_notebook(notebook: &nbformat::v4::Notebook) -> Result<String> {
+    let mut content = Vec::new();
+    let formatter = serde_json::ser::PrettyFormatter::with_indent(b" ");
+    let mut serializer = serde_json::Serializer::with_formatter(&mut content, formatter);
+    notebook.serialize(&mut serializer)?;
+    content.push(b'\n');
+    Ok(String::from_utf8(content)?)
+}
+
 impl NotebookItem {
     pub fn language_name(&self) -> Option<String> {
         self.notebook
@@ -704,6 +991,19 @@ impl EventEmitter<()> for NotebookEditor {}
 impl Item for NotebookEditor {
     type Event = ();
 
+    fn to_item_events(_event: &Self::Event, mut f: impl FnMut(ItemEvent)) {
+        f(ItemEvent::UpdateTab)
+    }
+
+    fn added_to_workspace(
+        &mut self,
+        workspace: &mut Workspace,
+        _window: &mut Window,
+        _cx: &mut Context<Self>,
+    ) {
+        self.workspace = workspace.weak_handle();
+    }
+
     fn clone_on_split(
         &self,
         _workspace_id: Option<workspace::WorkspaceId>,
@@ -773,49 +1073,64 @@ impl Item for NotebookEditor {
         // TODO
     }
 
-    // TODO
     fn can_save(&self, _cx: &App) -> bool {
-        false
+        true
     }
-    // TODO
+
     fn save(
         &mut self,
         _options: SaveOptions,
-        _project: Entity<Project>,
+        project: Entity<Project>,
         _window: &mut Window,
-        _cx: &mut Context<Self>,
+        cx: &mut Context<Self>,
     ) -> Task<Result<()>> {
-        unimplemented!("save() must be implemented if can_save() returns true")
-    }
+        let notebook = self.to_nbformat(cx);
+        let path = self.notebook_item.read(cx).path.clone();
+        let fs = project.read(cx).fs().clone();
 
-    // TODO
-    fn save_as(
-        &mut self,
-        _project: Entity<Project>,
-        _path: ProjectPath,
-        _window: &mut Window,
-        _cx: &mut Context<Self>,
-    ) -> Task<Result<()>> {
-        unimplemented!("save_as() must be implemented if can_save() returns true")
+        cx.spawn(async move |this, cx| {
+            let content = serialize_notebook(&notebook)?;
+            fs.atomic_write(path, content).await?;
+
+            this.update(cx, |this, cx| {
+                this.notebook_item
+                    .update(cx, |item, _| item.notebook = notebook);
+                for cell in this.cell_map.values() {
+                    cell.did_save(cx);
+                }
+                this.has_unsaved_changes = false;
+                cx.emit(());
+                cx.notify();
+            })
+        })
     }
-    // TODO
+
     fn reload(
         &mut self,
-        _project: Entity<Project>,
-        _window: &mut Window,
-        _cx: &mut Context<Self>,
+        project: Entity<Project>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
     ) -> Task<Result<()>> {
-        unimplemented!("reload() must be implemented if can_save() returns true")
+        let path = self.notebook_item.read(cx).path.clone();
+        let fs = project.read(cx).fs().clone();
+
+        cx.spawn_in(window, async move |this, cx| {
+            let content = fs.load(&path).await?;
+            let notebook = parse_notebook(&content)?;
+
+            this.update_in(cx, |this, window, cx| {
+                this.notebook_item
+                    .update(cx, |item, _| item.notebook = notebook);
+                this.load_cells(window, cx);
+                this.has_unsaved_changes = false;
+                cx.emit(());
+                cx.notify();
+            })
+        })
     }
 
     fn is_dirty(&self, cx: &App) -> bool {
-        self.cell_map.values().any(|cell| {
-            if let Cell::Code(code_cell) = cell {
-                code_cell.read(cx).is_dirty(cx)
-            } else {
-                false
-            }
-        })
+        self.has_unsaved_changes || self.cell_map.values().any(|cell| cell.is_dirty(cx))
     }
 }
 
@@ -838,3 +1153,154 @@ impl ProjectItem for NotebookEditor {
         Self::new(project, item, window, cx)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::TestAppContext;
+    use indoc::indoc;
+    use project::{FakeFs, Fs as _};
+    use serde_json::json;
+    use settings::{Settings as _, SettingsStore};
+    use util::path;
+
+    use crate::JupyterSettings;
+
+    const NOTEBOOK: &str = indoc! {r##"
+        {
+         "cells": [
+          {
+           "cell_type": "markdown",
+           "id": "intro",
+           "metadata": {},
+           "source": [
+            "# Arithmetic"
+           ]
+          },
+          {
+           "cell_type": "code",
+           "execution_count": 1,
+           "id": "sum",
+           "metadata": {},
+           "outputs": [
+            {
+             "name": "stdout",
+             "output_type": "stream",
+             "text": [
+              "2\n"
+             ]
+            }
+           ],
+           "source": [
+            "print(1 + 1)"
+           ]
+          }
+         ],
+         "metadata": {
+          "kernelspec": {
+           "display_name": "Python 3",
+           "language": "python",
+           "name": "python3"
+          }
+         },
+         "nbformat": 4,
+         "nbformat_minor": 5
+        }
+    "##};
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            theme::init(theme::LoadThemes::JustBase, cx);
+            language::init(cx);
+            editor::init(cx);
+            Project::init_settings(cx);
+            JupyterSettings::register(cx);
+        });
+    }
+
+    #[gpui::test]
+    async fn test_save_notebook(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(path!("/project"), json!({ "arithmetic.ipynb": NOTEBOOK }))
+            .await;
+        cx.update(|cx| ReplStore::init(fs.clone(), cx));
+        let project = Project::test(fs.clone(), [path!("/project").as_ref()], cx).await;
+
+        let project_path = project
+            .read_with(cx, |project, cx| {
+                project.find_project_path(path!("/project/arithmetic.ipynb"), cx)
+            })
+            .unwrap();
+        let notebook_item = cx
+            .update(|cx| NotebookItem::try_open(&project, &project_path, cx))
+            .unwrap()
+            .await
+            .unwrap();
+        let (notebook, cx) = cx.add_window_view(|window, cx| {
+            NotebookEditor::new(project.clone(), notebook_item, window, cx)
+        });
+
+        // Edit the code cell, move it to the top and add a markdown cell below it.
+        let cell_order = notebook.update_in(cx, |notebook, window, cx| {
+            let editor = notebook.cell_map[&notebook.cell_order[1]]
+                .editor(cx)
+                .unwrap();
+            editor.update(cx, |editor, cx| editor.set_text("print(2 + 2)", window, cx));
+            notebook.set_selected_index(1, false, window, cx);
+            notebook.move_cell_up(window, cx);
+            notebook.add_cell(CellType::Markdown, window, cx);
+            assert!(notebook.is_dirty(cx));
+            notebook.cell_order.clone()
+        });
+        assert_eq!(cell_order.len(), 3);
+
+        notebook
+            .update_in(cx, |notebook, window, cx| {
+                notebook.save(SaveOptions::default(), project.clone(), window, cx)
+            })
+            .await
+            .unwrap();
+        notebook.read_with(cx, |notebook, cx| assert!(!notebook.is_dirty(cx)));
+
+        let content = fs
+            .load(path!("/project/arithmetic.ipynb").as_ref())
+            .await
+            .unwrap();
+        assert!(content.starts_with("{\n \""));
+        assert!(content.ends_with("}\n"));
+
+        let saved = parse_notebook(&content).unwrap();
+        assert_eq!(
+            saved
+                .cells
+                .iter()
+                .map(|cell| cell.id().clone())
+                .collect::<Vec<_>>(),
+            cell_order
+        );
+        let nbformat::v4::Cell::Code {
+            source, outputs, ..
+        } = &saved.cells[0]
+        else {
+            panic!("expected a code cell");
+        };
+        assert_eq!(source, &vec!["print(2 + 2)".to_string()]);
+        assert_eq!(outputs.len(), 1);
+        let nbformat::v4::Cell::Markdown { source, .. } = &saved.cells[1] else {
+            panic!("expected a markdown cell");
+        };
+        assert!(source.is_empty());
+        let nbformat::v4::Cell::Markdown { source, .. } = &saved.cells[2] else {
+            panic!("expected a markdown cell");
+        };
+        assert_eq!(source, &vec!["# Arithmetic".to_string()]);
+        assert_eq!(
+            saved.metadata.kernelspec.map(|kernelspec| kernelspec.name),
+            Some("python3".to_string())
+        );
+    }
+}
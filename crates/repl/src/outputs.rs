@@ -14,7 +14,10 @@
 //! The module supports several output types, including:
 //! - Plain text
 //! - Markdown
-//! - Images (PNG and JPEG)
+//! - HTML (converted to Markdown)
+//! - LaTeX
+//! - Images (PNG, JPEG and SVG)
+//! - JSON trees
 //! - Tables
 //! - Error messages
 //!
@@ -44,9 +47,18 @@ use ui::{
 mod image;
 use image::ImageView;
 
+mod json;
+use json::JsonView;
+
+mod latex;
+use latex::LatexView;
+
 mod markdown;
 use markdown::MarkdownView;
 
+mod svg;
+use svg::SvgView;
+
 mod table;
 use table::TableView;
 
@@ -60,10 +72,14 @@ use workspace::Workspace;
 /// When deciding what to render from a collection of mediatypes, we need to rank them in order of importance
 fn rank_mime_type(mimetype: &MimeType) -> usize {
     match mimetype {
-        MimeType::DataTable(_) => 6,
-        MimeType::Png(_) => 4,
-        MimeType::Jpeg(_) => 3,
-        MimeType::Markdown(_) => 2,
+        MimeType::DataTable(_) => 9,
+        MimeType::Png(_) => 8,
+        MimeType::Jpeg(_) => 7,
+        MimeType::Svg(_) => 6,
+        MimeType::Json(_) => 5,
+        MimeType::Markdown(_) => 4,
+        MimeType::Html(_) => 3,
+        MimeType::Latex(_) => 2,
         MimeType::Plain(_) => 1,
         // All other media types are not supported in Zed at this time
         _ => 0,
@@ -123,6 +139,18 @@ pub enum Output {
         content: Entity<MarkdownView>,
         display_id: Option<String>,
     },
+    Svg {
+        content: Entity<SvgView>,
+        display_id: Option<String>,
+    },
+    Latex {
+        content: Entity<LatexView>,
+        display_id: Option<String>,
+    },
+    Json {
+        content: Entity<JsonView>,
+        display_id: Option<String>,
+    },
     ClearOutputWaitMarker,
 }
 
@@ -213,6 +241,9 @@ impl Output {
             Self::Image { content, .. } => Some(content.clone().into_any_element()),
             Self::Message(message) => Some(div().child(message.clone()).into_any_element()),
             Self::Table { content, .. } => Some(content.clone().into_any_element()),
+            Self::Svg { content, .. } => Some(content.clone().into_any_element()),
+            Self::Latex { content, .. } => Some(content.clone().into_any_element()),
+            Self::Json { content, .. } => Some(content.clone().into_any_element()),
             Self::ErrorOutput(error_view) => error_view.render(window, cx),
             Self::ClearOutputWaitMarker => None,
         };
@@ -243,6 +274,15 @@ impl Output {
                 Self::Table { content, .. } => {
                     Self::render_output_controls(content.clone(), workspace, window, cx)
                 }
+                Self::Svg { content, .. } => {
+                    Self::render_output_controls(content.clone(), workspace, window, cx)
+                }
+                Self::Latex { content, .. } => {
+                    Self::render_output_controls(content.clone(), workspace, window, cx)
+                }
+                Self::Json { content, .. } => {
+                    Self::render_output_controls(content.clone(), workspace, window, cx)
+                }
                 Self::ClearOutputWaitMarker => None,
             })
     }
@@ -256,6 +296,9 @@ impl Output {
             Output::Message(_) => None,
             Output::Table { display_id, .. } => display_id.clone(),
             Output::Markdown { display_id, .. } => display_id.clone(),
+            Output::Svg { display_id, .. } => display_id.clone(),
+            Output::Latex { display_id, .. } => display_id.clone(),
+            Output::Json { display_id, .. } => display_id.clone(),
             Output::ClearOutputWaitMarker => None,
         }
     }
@@ -278,6 +321,33 @@ impl Output {
                     display_id,
                 }
             }
+            Some(MimeType::Html(html)) => match markdown::html_to_markdown(html) {
+                // Widgets and plots made only of scripts convert to blank Markdown, so render the
+                // next-ranked media type, usually the plain text fallback, instead.
+                Ok(text) if text.trim().is_empty() => {
+                    let mut data = data.clone();
+                    data.content
+                        .retain(|mimetype| !matches!(mimetype, MimeType::Html(_)));
+                    Self::new(&data, display_id, window, cx)
+                }
+                Ok(text) => Output::Markdown {
+                    content: cx.new(|cx| MarkdownView::from(text, cx)),
+                    display_id,
+                },
+                Err(error) => Output::Message(format!("Failed to render HTML: {}", error)),
+            },
+            Some(MimeType::Svg(source)) => Output::Svg {
+                content: cx.new(|_| SvgView::from(source)),
+                display_id,
+            },
+            Some(MimeType::Latex(source)) => Output::Latex {
+                content: cx.new(|_| LatexView::from(source)),
+                display_id,
+            },
+            Some(MimeType::Json(json)) => Output::Json {
+                content: cx.new(|_| JsonView::from(serde_json::Value::from(json.clone()))),
+                display_id,
+            },
             Some(MimeType::Png(data)) | Some(MimeType::Jpeg(data)) => match ImageView::from(data) {
                 Ok(view) => Output::Image {
                     content: cx.new(|_| view),
@@ -0,0 +1,179 @@
+use std::collections::HashSet;
+
+use gpui::{AnyElement, App, ClipboardItem, Entity, Window};
+use language::Buffer;
+use serde_json::Value;
+use ui::{Disclosure, IntoElement, Styled, div, prelude::*, v_flex};
+
+use crate::outputs::OutputContent;
+
+/// JsonView renders an `application/json` output as a tree whose objects and arrays can be
+/// expanded and collapsed.
+pub struct JsonView {
+    value: Value,
+    /// The JSON pointers of the expanded objects and arrays.
+    expanded: HashSet<String>,
+}
+
+impl JsonView {
+    pub fn from(value: Value) -> Self {
+        Self {
+            value,
+            expanded: HashSet::from_iter([String::new()]),
+        }
+    }
+
+    fn text(&self) -> Option<String> {
+        serde_json::to_string_pretty(&self.value).ok()
+    }
+
+    fn toggle(&mut self, path: &str) {
+        if !self.expanded.remove(path) {
+            self.expanded.insert(path.to_string());
+        }
+    }
+
+    fn render_node(
+        &self,
+        key: Option<String>,
+        value: &Value,
+        path: String,
+        depth: usize,
+        rows: &mut Vec<AnyElement>,
+        cx: &mut Context<Self>,
+    ) {
+        let children: Vec<(String, &Value)> = match value {
+            Value::Object(map) => map
+                .iter()
+                .map(|(key, value)| (key.clone(), value))
+                .collect(),
+            Value::Array(items) => items
+                .iter()
+                .enumerate()
+                .map(|(ix, value)| (ix.to_string(), value))
+                .collect(),
+            _ => Vec::new(),
+        };
+        let is_container = matches!(value, Value::Object(_) | Value::Array(_));
+        let is_expanded = is_container && self.expanded.contains(&path);
+
+        let row = h_flex()
+            .gap_1()
+            .pl(rems(depth as f32 * 0.75))
+            .map(|row| {
+                if is_container {
+                    let toggled_path = path.clone();
+                    row.child(
+                        Disclosure::new(SharedString::from(format!("json{path}")), is_expanded)
+                            .on_click(cx.listener(move |this, _, _, cx| {
+                                this.toggle(&toggled_path);
+                                cx.notify();
+                            })),
+                    )
+                } else {
+                    row.child(div().w(rems(1.)))
+                }
+            })
+            .when_some(key, |row, key| {
+                row.child(
+                    Label::new(format!("{key}:"))
+                        .buffer_font(cx)
+                        .color(Color::Accent),
+                )
+            })
+            .child(match value {
+                Value::Object(_) if !is_expanded => Label::new(format!(
+                    "{{…}} {} {}",
+                    children.len(),
+                    if children.len() == 1 { "key" } else { "keys" }
+                ))
+                .buffer_font(cx)
+                .color(Color::Muted),
+                Value::Array(_) if !is_expanded => Label::new(format!(
+                    "[…] {} {}",
+                    children.len(),
+                    if children.len() == 1 { "item" } else { "items" }
+                ))
+                .buffer_font(cx)
+                .color(Color::Muted),
+                Value::Object(_) => Label::new("{").buffer_font(cx).color(Color::Muted),
+                Value::Array(_) => Label::new("[").buffer_font(cx).color(Color::Muted),
+                scalar => Label::new(scalar.to_string()).buffer_font(cx),
+            });
+        rows.push(row.into_any_element());
+
+        if is_expanded {
+            for (key, value) in children {
+                let child_path = child_path(&path, &key);
+                self.render_node(Some(key), value, child_path, depth + 1, rows, cx);
+            }
+        }
+    }
+}
+
+/// Returns the JSON pointer of the child with the given key.
+fn child_path(path: &str, key: &str) -> String {
+    format!("{path}/{}", key.replace('~', "~0").replace('/', "~1"))
+}
+
+impl Render for JsonView {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let mut rows = Vec::new();
+        self.render_node(None, &self.value, String::new(), 0, &mut rows, cx);
+        v_flex().children(rows)
+    }
+}
+
+impl OutputContent for JsonView {
+    fn clipboard_content(&self, _window: &Window, _cx: &App) -> Option<ClipboardItem> {
+        let text = self.text()?;
+        Some(ClipboardItem::new_string(text))
+    }
+
+    fn has_clipboard_content(&self, _window: &Window, _cx: &App) -> bool {
+        true
+    }
+
+    fn has_buffer_content(&self, _window: &Window, _cx: &App) -> bool {
+        true
+    }
+
+    fn buffer_content(&mut self, _: &mut Window, cx: &mut App) -> Option<Entity<Buffer>> {
+        let text = self.text()?;
+        let buffer = cx.new(|cx| {
+            let mut buffer =
+                Buffer::local(text, cx).with_language(language::PLAIN_TEXT.clone(), cx);
+            buffer.set_capability(language::Capability::ReadOnly, cx);
+            buffer
+        });
+        Some(buffer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_json_view() {
+        let mut view = JsonView::from(json!({ "a/b": { "c~d": [1, 2] } }));
+        assert_eq!(
+            view.text().unwrap(),
+            "{\n  \"a/b\": {\n    \"c~d\": [\n      1,\n      2\n    ]\n  }\n}"
+        );
+
+        let path = child_path("", "a/b");
+        assert_eq!(path, "/a~1b");
+        assert_eq!(child_path(&path, "c~d"), "/a~1b/c~0d");
+
+        assert!(view.expanded.contains(""));
+        assert!(!view.expanded.contains(&path));
+        view.toggle(&path);
+        assert!(view.expanded.contains(&path));
+        view.toggle("");
+        view.toggle(&path);
+        assert!(view.expanded.is_empty());
+    }
+}
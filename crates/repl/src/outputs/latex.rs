@@ -0,0 +1,343 @@
+use std::{iter::Peekable, str::Chars};
+
+use gpui::{App, ClipboardItem, Entity, Window};
+use language::Buffer;
+use ui::{IntoElement, Styled, div, prelude::*};
+
+use crate::outputs::OutputContent;
+
+/// LatexView renders a `text/latex` output as text, replacing the common math commands with
+/// their Unicode symbols.
+pub struct LatexView {
+    source: String,
+    text: String,
+}
+
+impl LatexView {
+    pub fn from(source: &str) -> Self {
+        Self {
+            source: source.to_string(),
+            text: latex_to_unicode(source),
+        }
+    }
+}
+
+impl Render for LatexView {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        div().font_buffer(cx).child(self.text.clone())
+    }
+}
+
+impl OutputContent for LatexView {
+    fn clipboard_content(&self, _window: &Window, _cx: &App) -> Option<ClipboardItem> {
+        Some(ClipboardItem::new_string(self.source.clone()))
+    }
+
+    fn has_clipboard_content(&self, _window: &Window, _cx: &App) -> bool {
+        true
+    }
+
+    fn has_buffer_content(&self, _window: &Window, _cx: &App) -> bool {
+        true
+    }
+
+    fn buffer_content(&mut self, _: &mut Window, cx: &mut App) -> Option<Entity<Buffer>> {
+        let buffer = cx.new(|cx| {
+            let mut buffer = Buffer::local(self.source.clone(), cx)
+                .with_language(language::PLAIN_TEXT.clone(), cx);
+            buffer.set_capability(language::Capability::ReadOnly, cx);
+            buffer
+        });
+        Some(buffer)
+    }
+}
+
+/// Converts LaTeX math to plain text, e.g. `$\frac{\alpha}{2} x^{2}$` to `α/2 x²`.
+///
+/// Commands that aren't known are kept as they are.
+pub fn latex_to_unicode(latex: &str) -> String {
+    let latex = latex.trim();
+    let latex = ["$$", "\\[", "\\("]
+        .iter()
+        .zip(["$$", "\\]", "\\)"])
+        .find_map(|(start, end)| latex.strip_prefix(start)?.strip_suffix(end))
+        .unwrap_or(latex);
+
+    convert_group(&mut latex.chars().peekable(), false)
+        .trim()
+        .to_string()
+}
+
+fn convert_group(chars: &mut Peekable<Chars>, until_closing_brace: bool) -> String {
+    let mut text = String::new();
+    while let Some(c) = chars.next() {
+        match c {
+            '}' if until_closing_brace => break,
+            '{' => text.push_str(&convert_group(chars, true)),
+            '\\' => text.push_str(&convert_command(chars)),
+            '^' => text.push_str(&script(&convert_argument(chars), '^', superscript)),
+            '_' => text.push_str(&script(&convert_argument(chars), '_', subscript)),
+            // Inline math delimiters and alignment points.
+            '$' | '&' => {}
+            '~' => text.push(' '),
+            c => text.push(c),
+        }
+    }
+    text
+}
+
+fn convert_argument(chars: &mut Peekable<Chars>) -> String {
+    while chars.next_if(|c| c.is_whitespace()).is_some() {}
+
+    match chars.next() {
+        Some('{') => convert_group(chars, true),
+        Some('\\') => convert_command(chars),
+        Some(c) => c.to_string(),
+        None => String::new(),
+    }
+}
+
+fn convert_command(chars: &mut Peekable<Chars>) -> String {
+    let mut name = String::new();
+    while let Some(c) = chars.next_if(|c| c.is_ascii_alphabetic()) {
+        name.push(c);
+    }
+
+    if name.is_empty() {
+        // A control symbol, such as a line break, a spacing command or an escaped character.
+        return match chars.next() {
+            Some('\\') => "\n".to_string(),
+            Some(',' | ':' | ';' | ' ') => " ".to_string(),
+            Some('!') | None => String::new(),
+            Some(c) => c.to_string(),
+        };
+    }
+
+    match name.as_str() {
+        "frac" | "dfrac" | "tfrac" => {
+            let numerator = convert_argument(chars);
+            let denominator = convert_argument(chars);
+            format!("{}/{}", parenthesize(numerator), parenthesize(denominator))
+        }
+        "sqrt" => format!("√{}", parenthesize(convert_argument(chars))),
+        "text" | "textrm" | "textbf" | "textit" | "mathrm" | "mathbf" | "mathit" | "mathsf"
+        | "mathtt" | "mathcal" | "boldsymbol" | "operatorname" => convert_argument(chars),
+        "mathbb" => convert_argument(chars)
+            .chars()
+            .map(|c| double_struck(c).unwrap_or(c))
+            .collect(),
+        "begin" | "end" => {
+            convert_argument(chars);
+            String::new()
+        }
+        "left" | "right" | "big" | "Big" | "bigg" | "Bigg" | "displaystyle" | "textstyle"
+        | "limits" | "nolimits" | "nonumber" => String::new(),
+        "quad" => " ".to_string(),
+        "qquad" => "  ".to_string(),
+        name => match symbol(name) {
+            Some(symbol) => symbol.to_string(),
+            None => format!("\\{name}"),
+        },
+    }
+}
+
+fn parenthesize(text: String) -> String {
+    if text.chars().count() <= 1 || text.chars().all(|c| c.is_alphanumeric()) {
+        text
+    } else {
+        format!("({text})")
+    }
+}
+
+/// Writes the text as a superscript or a subscript using the Unicode characters for them, or
+/// falls back to the LaTeX notation when some of its characters have none.
+fn script(text: &str, operator: char, map: fn(char) -> Option<char>) -> String {
+    if let Some(script) = text.chars().map(map).collect::<Option<String>>() {
+        script
+    } else if text.chars().count() == 1 {
+        format!("{operator}{text}")
+    } else {
+        format!("{operator}({text})")
+    }
+}
+
+fn superscript(c: char) -> Option<char> {
+    Some(match c {
+        '0' => '⁰',
+        '1' => '¹',
+        '2' => '²',
+        '3' => '³',
+        '4' => '⁴',
+        '5' => '⁵',
+        '6' => '⁶',
+        '7' => '⁷',
+        '8' => '⁸',
+        '9' => '⁹',
+        '+' => '⁺',
+        '-' | '−' => '⁻',
+        '=' => '⁼',
+        '(' => '⁽',
+        ')' => '⁾',
+        'i' => 'ⁱ',
+        'n' => 'ⁿ',
+        'T' => 'ᵀ',
+        '′' => '′',
+        _ => return None,
+    })
+}
+
+fn subscript(c: char) -> Option<char> {
+    Some(match c {
+        '0' => '₀',
+        '1' => '₁',
+        '2' => '₂',
+        '3' => '₃',
+        '4' => '₄',
+        '5' => '₅',
+        '6' => '₆',
+        '7' => '₇',
+        '8' => '₈',
+        '9' => '₉',
+        '+' => '₊',
+        '-' | '−' => '₋',
+        '=' => '₌',
+        '(' => '₍',
+        ')' => '₎',
+        'a' => 'ₐ',
+        'e' => 'ₑ',
+        'i' => 'ᵢ',
+        'j' => 'ⱼ',
+        'k' => 'ₖ',
+        'm' => 'ₘ',
+        'n' => 'ₙ',
+        'o' => 'ₒ',
+        't' => 'ₜ',
+        'x' => 'ₓ',
+        _ => return None,
+    })
+}
+
+fn double_struck(c: char) -> Option<char> {
+    Some(match c {
+        'C' => 'ℂ',
+        'N' => 'ℕ',
+        'P' => 'ℙ',
+        'Q' => 'ℚ',
+        'R' => 'ℝ',
+        'Z' => 'ℤ',
+        _ => return None,
+    })
+}
+
+fn symbol(name: &str) -> Option<&'static str> {
+    Some(match name {
+        "alpha" => "α",
+        "beta" => "β",
+        "gamma" => "γ",
+        "delta" => "δ",
+        "epsilon" | "varepsilon" => "ε",
+        "zeta" => "ζ",
+        "eta" => "η",
+        "theta" | "vartheta" => "θ",
+        "iota" => "ι",
+        "kappa" => "κ",
+        "lambda" => "λ",
+        "mu" => "μ",
+        "nu" => "ν",
+        "xi" => "ξ",
+        "pi" => "π",
+        "rho" => "ρ",
+        "sigma" => "σ",
+        "tau" => "τ",
+        "upsilon" => "υ",
+        "phi" | "varphi" => "φ",
+        "chi" => "χ",
+        "psi" => "ψ",
+        "omega" => "ω",
+        "Gamma" => "Γ",
+        "Delta" => "Δ",
+        "Theta" => "Θ",
+        "Lambda" => "Λ",
+        "Xi" => "Ξ",
+        "Pi" => "Π",
+        "Sigma" => "Σ",
+        "Phi" => "Φ",
+        "Psi" => "Ψ",
+        "Omega" => "Ω",
+        "times" => "×",
+        "cdot" => "·",
+        "div" => "÷",
+        "pm" => "±",
+        "mp" => "∓",
+        "leq" | "le" => "≤",
+        "geq" | "ge" => "≥",
+        "neq" | "ne" => "≠",
+        "approx" => "≈",
+        "equiv" => "≡",
+        "sim" => "∼",
+        "propto" => "∝",
+        "infty" => "∞",
+        "partial" => "∂",
+        "nabla" => "∇",
+        "sum" => "∑",
+        "prod" => "∏",
+        "int" => "∫",
+        "oint" => "∮",
+        "in" => "∈",
+        "notin" => "∉",
+        "subset" => "⊂",
+        "subseteq" => "⊆",
+        "cup" => "∪",
+        "cap" => "∩",
+        "emptyset" => "∅",
+        "forall" => "∀",
+        "exists" => "∃",
+        "neg" => "¬",
+        "wedge" | "land" => "∧",
+        "vee" | "lor" => "∨",
+        "to" | "rightarrow" => "→",
+        "leftarrow" => "←",
+        "leftrightarrow" => "↔",
+        "Rightarrow" => "⇒",
+        "Leftarrow" => "⇐",
+        "Leftrightarrow" => "⇔",
+        "mapsto" => "↦",
+        "ldots" | "dots" => "…",
+        "cdots" => "⋯",
+        "vdots" => "⋮",
+        "ddots" => "⋱",
+        "circ" => "∘",
+        "degree" => "°",
+        "prime" => "′",
+        "hbar" => "ℏ",
+        "ell" => "ℓ",
+        "langle" => "⟨",
+        "rangle" => "⟩",
+        "lfloor" => "⌊",
+        "rfloor" => "⌋",
+        "lceil" => "⌈",
+        "rceil" => "⌉",
+        "sin" | "cos" | "tan" | "log" | "ln" | "exp" | "lim" | "max" | "min" | "det" => name,
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_latex_to_unicode() {
+        assert_eq!(
+            latex_to_unicode("$\\displaystyle x^{2} + 2 x + 1$"),
+            "x² + 2 x + 1"
+        );
+        assert_eq!(latex_to_unicode("$$\\frac{\\alpha}{2}$$"), "α/2");
+        assert_eq!(latex_to_unicode("\\frac{a + b}{c}"), "(a + b)/c");
+        assert_eq!(latex_to_unicode("\\sum_{i=1}^{n} x_i"), "∑ᵢ₌₁ⁿ xᵢ");
+        assert_eq!(latex_to_unicode("\\sqrt{x^{2} + y^{2}}"), "√(x² + y²)");
+        assert_eq!(latex_to_unicode("x_{abc}"), "x_(abc)");
+        assert_eq!(latex_to_unicode("\\left(\\mathbb{R}\\right)"), "(ℝ)");
+        assert_eq!(latex_to_unicode("\\unknown x"), "\\unknown x");
+    }
+}
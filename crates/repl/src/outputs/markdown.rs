@@ -1,7 +1,10 @@
+use std::{cell::RefCell, rc::Rc};
+
 use anyhow::Result;
 use gpui::{
     App, ClipboardItem, Context, Entity, RetainAllImageCache, Task, Window, div, prelude::*,
 };
+use html_to_markdown::{TagHandler, convert_html_to_markdown, markdown};
 use language::Buffer;
 use markdown_preview::{
     markdown_elements::ParsedMarkdown, markdown_parser::parse_markdown,
@@ -43,6 +46,21 @@ impl MarkdownView {
     }
 }
 
+/// Converts an HTML output, such as a styled pandas data frame, to Markdown. Styles and scripts
+/// are dropped, and tables are kept as Markdown tables.
+pub fn html_to_markdown(html: &str) -> Result<String> {
+    let mut handlers: Vec<TagHandler> = vec![
+        Rc::new(RefCell::new(markdown::WebpageChromeRemover)),
+        Rc::new(RefCell::new(markdown::ParagraphHandler)),
+        Rc::new(RefCell::new(markdown::HeadingHandler)),
+        Rc::new(RefCell::new(markdown::ListHandler)),
+        Rc::new(RefCell::new(markdown::TableHandler::new())),
+        Rc::new(RefCell::new(markdown::StyledTextHandler)),
+        Rc::new(RefCell::new(markdown::CodeHandler)),
+    ];
+    convert_html_to_markdown(html.as_bytes(), &mut handlers)
+}
+
 impl OutputContent for MarkdownView {
     fn clipboard_content(&self, _window: &Window, _cx: &App) -> Option<ClipboardItem> {
         Some(ClipboardItem::new_string(self.raw_text.clone()))
@@ -91,3 +109,32 @@ impl Render for MarkdownView {
             .into_any_element()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_html_to_markdown() {
+        assert_eq!(
+            html_to_markdown("<h2>Title</h2><p>Some <strong>bold</strong> text</p>").unwrap(),
+            "## Title\n\nSome **bold** text"
+        );
+        assert_eq!(
+            html_to_markdown(
+                "<style>td { color: red; }</style>\
+                <table><thead><tr><th>a</th><th>b</th></tr></thead>\
+                <tbody><tr><td>1</td><td>2</td></tr></tbody></table>"
+            )
+            .unwrap(),
+            "| a | b |\n| --- | --- |\n| 1 | 2 |"
+        );
+        assert_eq!(
+            html_to_markdown(
+                "<div id=\"plot\"></div><script>Plotly.newPlot(\"plot\", []);</script>"
+            )
+            .unwrap(),
+            ""
+        );
+    }
+}
@@ -0,0 +1,61 @@
+use std::sync::Arc;
+
+use gpui::{App, ClipboardItem, Entity, Image, ImageFormat, Window, img};
+use language::Buffer;
+use ui::{IntoElement, Styled, div, prelude::*};
+
+use crate::outputs::OutputContent;
+
+/// SvgView renders an `image/svg+xml` output with GPUI's SVG renderer.
+pub struct SvgView {
+    source: String,
+    image: Arc<Image>,
+}
+
+impl SvgView {
+    pub fn from(source: &str) -> Self {
+        Self {
+            source: source.to_string(),
+            image: Arc::new(Image::from_bytes(
+                ImageFormat::Svg,
+                source.as_bytes().to_vec(),
+            )),
+        }
+    }
+}
+
+impl Render for SvgView {
+    fn render(&mut self, _: &mut Window, _: &mut Context<Self>) -> impl IntoElement {
+        div()
+            .max_w_full()
+            .child(img(self.image.clone()).with_fallback(|| {
+                Label::new("Failed to render SVG")
+                    .color(Color::Error)
+                    .into_any_element()
+            }))
+    }
+}
+
+impl OutputContent for SvgView {
+    fn clipboard_content(&self, _window: &Window, _cx: &App) -> Option<ClipboardItem> {
+        Some(ClipboardItem::new_string(self.source.clone()))
+    }
+
+    fn has_clipboard_content(&self, _window: &Window, _cx: &App) -> bool {
+        true
+    }
+
+    fn has_buffer_content(&self, _window: &Window, _cx: &App) -> bool {
+        true
+    }
+
+    fn buffer_content(&mut self, _: &mut Window, cx: &mut App) -> Option<Entity<Buffer>> {
+        let buffer = cx.new(|cx| {
+            let mut buffer = Buffer::local(self.source.clone(), cx)
+                .with_language(language::PLAIN_TEXT.clone(), cx);
+            buffer.set_capability(language::Capability::ReadOnly, cx);
+            buffer
+        });
+        Some(buffer)
+    }
+}
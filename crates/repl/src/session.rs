@@ -19,7 +19,8 @@ use editor::{
 };
 use futures::FutureExt as _;
 use gpui::{
-    Context, Entity, EventEmitter, Render, Subscription, Task, WeakEntity, Window, div, prelude::*,
+    Context, Entity, EntityId, EventEmitter, Render, Subscription, Task, WeakEntity, Window, div,
+    prelude::*,
 };
 use language::Point;
 use project::Fs;
@@ -27,7 +28,7 @@ use runtimelib::{
     ExecuteRequest, ExecutionState, InterruptRequest, JupyterMessage, JupyterMessageContent,
     ShutdownRequest,
 };
-use std::{env::temp_dir, ops::Range, sync::Arc, time::Duration};
+use std::{env::temp_dir, ops::Range, path::PathBuf, sync::Arc, time::Duration};
 use theme::ActiveTheme;
 use ui::{IconButtonShape, Tooltip, prelude::*};
 use util::ResultExt as _;
@@ -35,12 +36,20 @@ use util::ResultExt as _;
 pub struct Session {
     fs: Arc<dyn Fs>,
     editor: WeakEntity<Editor>,
+    /// The view the kernel runs for, used to name its connection file.
+    owner_id: EntityId,
+    working_directory: Option<PathBuf>,
     pub kernel: Kernel,
     blocks: HashMap<String, EditorBlock>,
+    /// Handlers for the executions whose outputs aren't displayed in the editor, keyed by the
+    /// id of their execute request.
+    message_handlers: HashMap<String, MessageHandler>,
     pub kernel_specification: KernelSpecification,
     _buffer_subscription: Subscription,
 }
 
+type MessageHandler = Box<dyn FnMut(&JupyterMessage, &mut Window, &mut App)>;
+
 struct EditorBlock {
     code_range: Range<Anchor>,
     invalidation_anchor: Anchor,
@@ -217,9 +226,12 @@ impl Session {
 
         let mut session = Self {
             fs,
+            owner_id: editor.entity_id(),
             editor,
+            working_directory: None,
             kernel: Kernel::StartingKernel(Task::ready(()).shared()),
             blocks: HashMap::default(),
+            message_handlers: HashMap::default(),
             kernel_specification,
             _buffer_subscription: subscription,
         };
@@ -228,13 +240,43 @@ impl Session {
         session
     }
 
+    /// Creates a session that isn't attached to an editor, such as the one of a notebook.
+    /// Code is run with [`Session::execute_with_handler`].
+    pub fn detached(
+        owner_id: EntityId,
+        working_directory: PathBuf,
+        fs: Arc<dyn Fs>,
+        kernel_specification: KernelSpecification,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let mut session = Self {
+            fs,
+            editor: WeakEntity::new_invalid(),
+            owner_id,
+            working_directory: Some(working_directory),
+            kernel: Kernel::StartingKernel(Task::ready(()).shared()),
+            blocks: HashMap::default(),
+            message_handlers: HashMap::default(),
+            kernel_specification,
+            _buffer_subscription: Subscription::new(|| {}),
+        };
+
+        session.start_kernel(window, cx);
+        session
+    }
+
     fn start_kernel(&mut self, window: &mut Window, cx: &mut Context<Self>) {
         let kernel_language = self.kernel_specification.language();
-        let entity_id = self.editor.entity_id();
+        let entity_id = self.owner_id;
         let working_directory = self
-            .editor
-            .upgrade()
-            .and_then(|editor| editor.read(cx).working_directory(cx))
+            .working_directory
+            .clone()
+            .or_else(|| {
+                self.editor
+                    .upgrade()
+                    .and_then(|editor| editor.read(cx).working_directory(cx))
+            })
             .unwrap_or_else(temp_dir);
 
         telemetry::event!(
@@ -350,6 +392,29 @@ impl Session {
         anyhow::Ok(())
     }
 
+    /// Sends the message right away when the kernel is running, or once it has started.
+    fn send_when_started(&mut self, message: JupyterMessage, cx: &mut Context<Self>) {
+        match &self.kernel {
+            Kernel::RunningKernel(_) => {
+                self.send(message, cx).ok();
+            }
+            Kernel::StartingKernel(task) => {
+                // Queue up the execution as a task to run after the kernel starts
+                let task = task.clone();
+
+                cx.spawn(async move |this, cx| {
+                    task.await;
+                    this.update(cx, |session, cx| {
+                        session.send(message, cx).ok();
+                    })
+                    .ok();
+                })
+                .detach();
+            }
+            _ => {}
+        }
+    }
+
     pub fn clear_outputs(&mut self, cx: &mut Context<Self>) {
         let blocks_to_remove: HashSet<CustomBlockId> =
             self.blocks.values().map(|block| block.block_id).collect();
@@ -361,6 +426,30 @@ impl Session {
             .ok();
 
         self.blocks.clear();
+        self.message_handlers.clear();
+    }
+
+    /// Executes the code without displaying its outputs in the editor. Instead, every message
+    /// the kernel sends for the execution is passed to `on_message`, until the kernel is idle
+    /// again.
+    pub fn execute_with_handler(
+        &mut self,
+        code: String,
+        on_message: impl FnMut(&JupyterMessage, &mut Window, &mut App) + 'static,
+        cx: &mut Context<Self>,
+    ) {
+        if code.is_empty() {
+            return;
+        }
+
+        let message: JupyterMessage = ExecuteRequest {
+            code,
+            ..ExecuteRequest::default()
+        }
+        .into();
+        self.message_handlers
+            .insert(message.header.msg_id.clone(), Box::new(on_message));
+        self.send_when_started(message, cx);
     }
 
     pub fn execute(
@@ -453,25 +542,7 @@ impl Session {
         self.blocks
             .insert(message.header.msg_id.clone(), editor_block);
 
-        match &self.kernel {
-            Kernel::RunningKernel(_) => {
-                self.send(message, cx).ok();
-            }
-            Kernel::StartingKernel(task) => {
-                // Queue up the execution as a task to run after the kernel starts
-                let task = task.clone();
-
-                cx.spawn(async move |this, cx| {
-                    task.await;
-                    this.update(cx, |session, cx| {
-                        session.send(message, cx).ok();
-                    })
-                    .ok();
-                })
-                .detach();
-            }
-            _ => {}
-        }
+        self.send_when_started(message, cx);
 
         if move_down {
             editor.update(cx, move |editor, cx| {
@@ -530,6 +601,16 @@ impl Session {
         if let Some(block) = self.blocks.get_mut(parent_message_id) {
             block.handle_message(message, window, cx);
         }
+
+        if let Some(handler) = self.message_handlers.get_mut(parent_message_id) {
+            handler(message, window, cx);
+
+            if let JupyterMessageContent::Status(status) = &message.content
+                && let ExecutionState::Idle = status.execution_state
+            {
+                self.message_handlers.remove(parent_message_id);
+            }
+        }
     }
 
     pub fn interrupt(&mut self, cx: &mut Context<Self>) {
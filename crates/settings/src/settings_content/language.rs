@@ -280,6 +280,16 @@ pub struct LanguageSettingsContent {
     ///
     /// Default: true
     pub use_on_type_format: Option<bool>,
+    /// Whether to highlight the code with the semantic tokens provided by language servers,
+    /// layered over the tree-sitter syntax highlighting.
+    ///
+    /// Default: false
+    pub semantic_tokens: Option<bool>,
+    /// Whether to fold code using the folding ranges provided by language servers,
+    /// instead of indentation.
+    ///
+    /// Default: false
+    pub lsp_folding_ranges: Option<bool>,
     /// Which code actions to run on save after the formatter.
     /// These are not run if formatting is off.
     ///
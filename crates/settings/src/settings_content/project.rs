@@ -176,6 +176,14 @@ pub enum ContextServerSettingsContent {
         #[serde(flatten)]
         command: ContextServerCommand,
     },
+    Http {
+        /// Whether the context server is enabled.
+        #[serde(default = "default_true")]
+        enabled: bool,
+
+        #[serde(flatten)]
+        endpoint: ContextServerEndpoint,
+    },
     Extension {
         /// Whether the context server is enabled.
         #[serde(default = "default_true")]
@@ -196,6 +204,10 @@ impl ContextServerSettingsContent {
             } => {
                 *custom_enabled = enabled;
             }
+            ContextServerSettingsContent::Http {
+                enabled: http_enabled,
+                endpoint: _,
+            } => *http_enabled = enabled,
             ContextServerSettingsContent::Extension {
                 enabled: ext_enabled,
                 settings: _,
@@ -240,6 +252,48 @@ impl std::fmt::Debug for ContextServerCommand {
     }
 }
 
+/// A context server reachable over MCP's Streamable HTTP transport, or the older HTTP with SSE one.
+#[skip_serializing_none]
+#[derive(Deserialize, Serialize, Clone, PartialEq, Eq, JsonSchema, MergeFrom)]
+pub struct ContextServerEndpoint {
+    /// The URL of the server's MCP endpoint, for example `http://mcp.internal:8080/mcp`.
+    pub url: String,
+    /// Headers to send with every request to the server.
+    ///
+    /// A bearer token stored in the system keychain for `url` is sent as the
+    /// `Authorization` header unless this sets one.
+    pub headers: Option<HashMap<String, String>>,
+    /// Timeout for tool calls in milliseconds. Defaults to 60000 (60 seconds) if not specified.
+    pub timeout: Option<u64>,
+}
+
+impl std::fmt::Debug for ContextServerEndpoint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let filtered_headers = self.headers.as_ref().map(|headers| {
+            headers
+                .iter()
+                .map(|(k, v)| {
+                    (
+                        k,
+                        if util::redact::should_redact(k) || k.eq_ignore_ascii_case("authorization")
+                        {
+                            "[REDACTED]"
+                        } else {
+                            v
+                        },
+                    )
+                })
+                .collect::<Vec<_>>()
+        });
+
+        f.debug_struct("ContextServerEndpoint")
+            .field("url", &self.url)
+            .field("headers", &filtered_headers)
+            .field("timeout", &self.timeout)
+            .finish()
+    }
+}
+
 #[skip_serializing_none]
 #[derive(Copy, Clone, Debug, PartialEq, Default, Serialize, Deserialize, JsonSchema, MergeFrom)]
 pub struct GitSettings {
@@ -1,4 +1,5 @@
 use anyhow::{Context as _, Result};
+use regex::{Captures, Regex, RegexBuilder};
 use smallvec::SmallVec;
 use std::{collections::BTreeMap, ops::Range};
 
@@ -12,18 +13,97 @@ pub struct Snippet {
 pub struct TabStop {
     pub ranges: SmallVec<[Range<isize>; 2]>,
     pub choices: Option<Vec<String>>,
+    /// Ranges that mirror the text of the tabstop, transformed with a regular expression,
+    /// e.g. `${1/(.*)/${1:/upcase}/}`.
+    pub transformations: Vec<(Range<isize>, Transformation)>,
+}
+
+/// A regular expression based transformation of a tabstop or a variable text,
+/// as defined in the LSP snippet grammar.
+#[derive(Clone, Debug)]
+pub struct Transformation {
+    regex: Regex,
+    format: Vec<FormatItem>,
+    global: bool,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum FormatItem {
+    Text(String),
+    Group(usize),
+    Case {
+        group: usize,
+        case: Case,
+    },
+    Conditional {
+        group: usize,
+        /// The text to insert if the group matched, or `None` to insert the group itself.
+        if_matched: Option<String>,
+        otherwise: String,
+    },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Case {
+    Upcase,
+    Downcase,
+    Capitalize,
+    Camelcase,
+    Pascalcase,
+}
+
+struct ParseContext<'a> {
+    text: String,
+    tabstops: BTreeMap<usize, TabStop>,
+    /// Placeholders for the variables that could not be resolved, in the order they appear.
+    variable_tabstops: Vec<TabStop>,
+    /// Resolves variables, or `None` when variables aren't supported and are rejected.
+    resolve_variable: Option<&'a dyn Fn(&str) -> Option<String>>,
 }
 
 impl Snippet {
+    /// Parses the snippet, rejecting variables (e.g. `$TM_FILENAME`) as invalid syntax.
     pub fn parse(source: &str) -> Result<Self> {
-        let mut text = String::with_capacity(source.len());
-        let mut tabstops = BTreeMap::new();
-        parse_snippet(source, false, &mut text, &mut tabstops)
-            .context("failed to parse snippet")?;
+        Self::parse_internal(source, None)
+    }
+
+    /// Parses the snippet, replacing its variables (e.g. `$TM_FILENAME` or `${CURRENT_YEAR}`)
+    /// with the values returned by `resolve_variable`.
+    ///
+    /// As in VS Code, variables that cannot be resolved become placeholders containing their
+    /// default value or name, visited after all numbered tabstops.
+    pub fn parse_with_variables(
+        source: &str,
+        resolve_variable: impl Fn(&str) -> Option<String>,
+    ) -> Result<Self> {
+        Self::parse_internal(source, Some(&resolve_variable))
+    }
+
+    fn parse_internal(
+        source: &str,
+        resolve_variable: Option<&dyn Fn(&str) -> Option<String>>,
+    ) -> Result<Self> {
+        let mut cx = ParseContext {
+            text: String::with_capacity(source.len()),
+            tabstops: BTreeMap::new(),
+            variable_tabstops: Vec::new(),
+            resolve_variable,
+        };
+        parse_snippet(source, false, &mut cx).context("failed to parse snippet")?;
+        let ParseContext {
+            text,
+            mut tabstops,
+            variable_tabstops,
+            ..
+        } = cx;
 
         let len = text.len() as isize;
         let final_tabstop = tabstops.remove(&0);
-        let mut tabstops = tabstops.into_values().collect::<Vec<_>>();
+        let mut tabstops = tabstops
+            .into_values()
+            .chain(variable_tabstops)
+            .filter(|tabstop| !tabstop.ranges.is_empty())
+            .collect::<Vec<_>>();
 
         if let Some(final_tabstop) = final_tabstop {
             tabstops.push(final_tabstop);
@@ -31,6 +111,7 @@ impl Snippet {
             let end_tabstop = TabStop {
                 ranges: [len..len].into_iter().collect(),
                 choices: None,
+                transformations: Vec::new(),
             };
 
             if !tabstops.last().is_some_and(|t| *t == end_tabstop) {
@@ -42,17 +123,109 @@ impl Snippet {
     }
 }
 
+impl Transformation {
+    /// Replaces the matches of the regular expression in `text` according to the format string.
+    pub fn apply(&self, text: &str) -> String {
+        let mut result = String::with_capacity(text.len());
+        let mut last_match_end = 0;
+        for captures in self.regex.captures_iter(text) {
+            let Some(regex_match) = captures.get(0) else {
+                continue;
+            };
+            result.push_str(&text[last_match_end..regex_match.start()]);
+            self.expand(&captures, &mut result);
+            last_match_end = regex_match.end();
+            if !self.global {
+                break;
+            }
+        }
+        result.push_str(&text[last_match_end..]);
+        result
+    }
+
+    fn expand(&self, captures: &Captures, output: &mut String) {
+        let group_text = |group: usize| captures.get(group).map_or("", |m| m.as_str());
+        for item in &self.format {
+            match item {
+                FormatItem::Text(text) => output.push_str(text),
+                FormatItem::Group(group) => output.push_str(group_text(*group)),
+                FormatItem::Case { group, case } => {
+                    output.push_str(&case.apply(group_text(*group)));
+                }
+                FormatItem::Conditional {
+                    group,
+                    if_matched,
+                    otherwise,
+                } => {
+                    let value = group_text(*group);
+                    if value.is_empty() {
+                        output.push_str(otherwise);
+                    } else {
+                        output.push_str(if_matched.as_deref().unwrap_or(value));
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl PartialEq for Transformation {
+    fn eq(&self, other: &Self) -> bool {
+        self.regex.as_str() == other.regex.as_str()
+            && self.format == other.format
+            && self.global == other.global
+    }
+}
+
+impl Case {
+    fn apply(&self, text: &str) -> String {
+        match self {
+            Self::Upcase => text.to_uppercase(),
+            Self::Downcase => text.to_lowercase(),
+            Self::Capitalize => capitalize(text),
+            Self::Camelcase | Self::Pascalcase => {
+                let mut words = text
+                    .split(|c: char| !c.is_alphanumeric())
+                    .filter(|word| !word.is_empty())
+                    .peekable();
+                if words.peek().is_none() {
+                    return text.to_string();
+                }
+                let mut result = String::with_capacity(text.len());
+                if *self == Self::Camelcase
+                    && let Some(first_word) = words.next()
+                {
+                    let mut chars = first_word.chars();
+                    result.extend(chars.next().into_iter().flat_map(char::to_lowercase));
+                    result.push_str(chars.as_str());
+                }
+                for word in words {
+                    result.push_str(&capitalize(word));
+                }
+                result
+            }
+        }
+    }
+}
+
+fn capitalize(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
 fn parse_snippet<'a>(
     mut source: &'a str,
     nested: bool,
-    text: &mut String,
-    tabstops: &mut BTreeMap<usize, TabStop>,
+    cx: &mut ParseContext<'_>,
 ) -> Result<&'a str> {
     loop {
         match source.chars().next() {
             None => return Ok(""),
             Some('$') => {
-                source = parse_tabstop(&source[1..], text, tabstops)?;
+                source = parse_tabstop(&source[1..], cx)?;
             }
             Some('\\') => {
                 // As specified in the LSP spec (`Grammar` section),
@@ -61,78 +234,169 @@ fn parse_snippet<'a>(
                 source = &source[1..];
                 if let Some(c) = source.chars().next() {
                     if c == '$' || c == '\\' || c == '}' {
-                        text.push(c);
+                        cx.text.push(c);
                         // All escapable characters are 1 byte long:
                         source = &source[1..];
                     } else {
-                        text.push('\\');
+                        cx.text.push('\\');
                     }
                 } else {
-                    text.push('\\');
+                    cx.text.push('\\');
                 }
             }
             Some('}') => {
                 if nested {
                     return Ok(source);
                 } else {
-                    text.push('}');
+                    cx.text.push('}');
                     source = &source[1..];
                 }
             }
             Some(_) => {
                 let chunk_end = source.find(['}', '$', '\\']).unwrap_or(source.len());
                 let (chunk, rest) = source.split_at(chunk_end);
-                text.push_str(chunk);
+                cx.text.push_str(chunk);
                 source = rest;
             }
         }
     }
 }
 
-fn parse_tabstop<'a>(
-    mut source: &'a str,
-    text: &mut String,
-    tabstops: &mut BTreeMap<usize, TabStop>,
-) -> Result<&'a str> {
-    let tabstop_start = text.len();
+fn parse_tabstop<'a>(mut source: &'a str, cx: &mut ParseContext<'_>) -> Result<&'a str> {
+    let tabstop_start = cx.text.len();
     let tabstop_index;
     let mut choices = None;
 
-    if source.starts_with('{') {
-        let (index, rest) = parse_int(&source[1..])?;
+    if let Some(rest) = source.strip_prefix('{') {
+        if cx.resolve_variable.is_some() && rest.starts_with(is_variable_start) {
+            return parse_variable(rest, true, cx);
+        }
+
+        let (index, rest) = parse_int(rest)?;
         tabstop_index = index;
         source = rest;
 
+        if let Some(rest) = source.strip_prefix('/') {
+            let (transformation, rest) = parse_transformation(rest)?;
+            let source = parse_closing_brace(rest)?;
+            let tabstop = cx.tabstops.entry(tabstop_index).or_default();
+            // The mirror is updated by the editor when the tabstop is left,
+            // so start with the transformed placeholder text, if it's already known.
+            let placeholder = tabstop
+                .ranges
+                .first()
+                .map(|range| &cx.text[range.start as usize..range.end as usize])
+                .unwrap_or_default();
+            let transformed = transformation.apply(placeholder);
+            cx.text.push_str(&transformed);
+            tabstop.transformations.push((
+                tabstop_start as isize..cx.text.len() as isize,
+                transformation,
+            ));
+            return Ok(source);
+        }
+
         if source.starts_with("|") {
-            (source, choices) = parse_choices(&source[1..], text)?;
+            (source, choices) = parse_choices(&source[1..], &mut cx.text)?;
         }
 
         if source.starts_with(':') {
-            source = parse_snippet(&source[1..], true, text, tabstops)?;
+            source = parse_snippet(&source[1..], true, cx)?;
         }
 
-        if source.starts_with('}') {
-            source = &source[1..];
-        } else {
-            anyhow::bail!("expected a closing brace");
-        }
+        source = parse_closing_brace(source)?;
+    } else if cx.resolve_variable.is_some() && source.starts_with(is_variable_start) {
+        return parse_variable(source, false, cx);
     } else {
         let (index, rest) = parse_int(source)?;
         tabstop_index = index;
         source = rest;
     }
 
-    tabstops
-        .entry(tabstop_index)
-        .or_insert_with(|| TabStop {
-            ranges: Default::default(),
-            choices,
-        })
+    let tabstop = cx.tabstops.entry(tabstop_index).or_default();
+    if tabstop.ranges.is_empty() {
+        tabstop.choices = choices;
+    }
+    tabstop
         .ranges
-        .push(tabstop_start as isize..text.len() as isize);
+        .push(tabstop_start as isize..cx.text.len() as isize);
     Ok(source)
 }
 
+fn is_variable_start(c: char) -> bool {
+    c == '_' || c.is_ascii_alphabetic()
+}
+
+fn parse_variable<'a>(source: &'a str, braced: bool, cx: &mut ParseContext<'_>) -> Result<&'a str> {
+    let name_len = source
+        .find(|c: char| c != '_' && !c.is_ascii_alphanumeric())
+        .unwrap_or(source.len());
+    let (name, mut source) = source.split_at(name_len);
+    let value = cx
+        .resolve_variable
+        .and_then(|resolve_variable| resolve_variable(name));
+    let variable_start = cx.text.len();
+
+    if braced && let Some(rest) = source.strip_prefix('/') {
+        let (transformation, rest) = parse_transformation(rest)?;
+        source = parse_closing_brace(rest)?;
+        match value {
+            Some(value) => cx.text.push_str(&transformation.apply(&value)),
+            None => push_variable_placeholder(name, cx),
+        }
+    } else if braced && let Some(rest) = source.strip_prefix(':') {
+        match value {
+            Some(value) if !value.is_empty() => {
+                // Skip the default value, keeping its tabstops out of the snippet.
+                let mut default_cx = ParseContext {
+                    text: String::new(),
+                    tabstops: BTreeMap::new(),
+                    variable_tabstops: Vec::new(),
+                    resolve_variable: cx.resolve_variable,
+                };
+                source = parse_snippet(rest, true, &mut default_cx)?;
+                cx.text.push_str(&value);
+            }
+            Some(_) => source = parse_snippet(rest, true, cx)?,
+            None => {
+                source = parse_snippet(rest, true, cx)?;
+                cx.variable_tabstops.push(TabStop {
+                    ranges: [variable_start as isize..cx.text.len() as isize]
+                        .into_iter()
+                        .collect(),
+                    ..TabStop::default()
+                });
+            }
+        }
+        source = parse_closing_brace(source)?;
+    } else {
+        if braced {
+            source = parse_closing_brace(source)?;
+        }
+        match value {
+            Some(value) => cx.text.push_str(&value),
+            None => push_variable_placeholder(name, cx),
+        }
+    }
+
+    Ok(source)
+}
+
+fn push_variable_placeholder(name: &str, cx: &mut ParseContext<'_>) {
+    let start = cx.text.len();
+    cx.text.push_str(name);
+    cx.variable_tabstops.push(TabStop {
+        ranges: [start as isize..cx.text.len() as isize]
+            .into_iter()
+            .collect(),
+        ..TabStop::default()
+    });
+}
+
+fn parse_closing_brace(source: &str) -> Result<&str> {
+    source.strip_prefix('}').context("expected a closing brace")
+}
+
 fn parse_int(source: &str) -> Result<(usize, &str)> {
     let len = source
         .find(|c: char| !c.is_ascii_digit())
@@ -142,6 +406,158 @@ fn parse_int(source: &str) -> Result<(usize, &str)> {
     Ok((prefix.parse()?, suffix))
 }
 
+fn parse_transformation(source: &str) -> Result<(Transformation, &str)> {
+    let mut pattern = String::new();
+    let mut chars = source.char_indices();
+    let source = loop {
+        match chars.next() {
+            None => anyhow::bail!("expected a closing slash after the regular expression"),
+            Some((ix, '/')) => break &source[ix + 1..],
+            Some((_, '\\')) => match chars.next() {
+                Some((_, '/')) => pattern.push('/'),
+                Some((_, c)) => {
+                    pattern.push('\\');
+                    pattern.push(c);
+                }
+                None => pattern.push('\\'),
+            },
+            Some((_, c)) => pattern.push(c),
+        }
+    };
+
+    let (format, source) = parse_format(source)?;
+    let options_len = source.find('}').context("expected a closing brace")?;
+    let (options, source) = source.split_at(options_len);
+    let regex = RegexBuilder::new(&pattern)
+        .case_insensitive(options.contains('i'))
+        .multi_line(options.contains('m'))
+        .dot_matches_new_line(options.contains('s'))
+        .build()
+        .with_context(|| format!("invalid regular expression `{pattern}`"))?;
+
+    Ok((
+        Transformation {
+            regex,
+            format,
+            global: options.contains('g'),
+        },
+        source,
+    ))
+}
+
+fn parse_format(mut source: &str) -> Result<(Vec<FormatItem>, &str)> {
+    let mut items = Vec::new();
+    let mut text = String::new();
+
+    loop {
+        let mut chars = source.chars();
+        match chars.next() {
+            None => anyhow::bail!("expected a closing slash after the format string"),
+            Some('/') => {
+                if !text.is_empty() {
+                    items.push(FormatItem::Text(text));
+                }
+                return Ok((items, &source[1..]));
+            }
+            Some('\\') => match chars.next() {
+                Some(c) => {
+                    if !matches!(c, '$' | '\\' | '/' | '}') {
+                        text.push('\\');
+                    }
+                    text.push(c);
+                    source = chars.as_str();
+                }
+                None => {
+                    text.push('\\');
+                    source = chars.as_str();
+                }
+            },
+            Some('$')
+                if chars
+                    .as_str()
+                    .starts_with(|c: char| c == '{' || c.is_ascii_digit()) =>
+            {
+                if !text.is_empty() {
+                    items.push(FormatItem::Text(std::mem::take(&mut text)));
+                }
+                let (item, rest) = parse_format_item(chars.as_str())?;
+                items.push(item);
+                source = rest;
+            }
+            Some(c) => {
+                text.push(c);
+                source = chars.as_str();
+            }
+        }
+    }
+}
+
+fn parse_format_item(source: &str) -> Result<(FormatItem, &str)> {
+    let Some(source) = source.strip_prefix('{') else {
+        let (group, source) = parse_int(source)?;
+        return Ok((FormatItem::Group(group), source));
+    };
+
+    let (group, source) = parse_int(source)?;
+    if let Some(source) = source.strip_prefix('}') {
+        return Ok((FormatItem::Group(group), source));
+    }
+    let source = source
+        .strip_prefix(':')
+        .context("expected a colon or a closing brace")?;
+
+    if let Some(source) = source.strip_prefix('/') {
+        let case_len = source.find('}').context("expected a closing brace")?;
+        let case = match &source[..case_len] {
+            "upcase" => Case::Upcase,
+            "downcase" => Case::Downcase,
+            "capitalize" => Case::Capitalize,
+            "camelcase" => Case::Camelcase,
+            "pascalcase" => Case::Pascalcase,
+            other => anyhow::bail!("unknown format modifier `{other}`"),
+        };
+        return Ok((FormatItem::Case { group, case }, &source[case_len + 1..]));
+    }
+
+    let (if_matched, otherwise, source) = if let Some(source) = source.strip_prefix('+') {
+        let (if_matched, source) = parse_format_text(source, '}')?;
+        (Some(if_matched), String::new(), source)
+    } else if let Some(source) = source.strip_prefix('?') {
+        let (if_matched, source) = parse_format_text(source, ':')?;
+        let (otherwise, source) = parse_format_text(source, '}')?;
+        (Some(if_matched), otherwise, source)
+    } else {
+        let source = source.strip_prefix('-').unwrap_or(source);
+        let (otherwise, source) = parse_format_text(source, '}')?;
+        (None, otherwise, source)
+    };
+    Ok((
+        FormatItem::Conditional {
+            group,
+            if_matched,
+            otherwise,
+        },
+        source,
+    ))
+}
+
+fn parse_format_text(source: &str, terminator: char) -> Result<(String, &str)> {
+    let mut text = String::new();
+    let mut chars = source.chars();
+    loop {
+        match chars.next() {
+            None => anyhow::bail!("expected `{terminator}` in the format string"),
+            Some('\\') => {
+                if let Some(c) = chars.next() {
+                    text.push(c);
+                }
+            }
+            Some(c) if c == terminator => return Ok((text, chars.as_str())),
+            Some(c) => text.push(c),
+        }
+    }
+}
+
 fn parse_choices<'a>(
     mut source: &'a str,
     text: &mut String,
@@ -324,6 +740,104 @@ mod tests {
         assert_eq!(tabstops(&snippet), &[vec![4..4], vec![7..7]]);
     }
 
+    #[test]
+    fn test_snippet_with_variables() {
+        let resolve_variable = |name: &str| match name {
+            "TM_FILENAME" => Some("main.rs".to_string()),
+            "TM_SELECTED_TEXT" => Some(String::new()),
+            _ => None,
+        };
+
+        let snippet =
+            Snippet::parse_with_variables("// $TM_FILENAME: ${1:todo}", resolve_variable).unwrap();
+        assert_eq!(snippet.text, "// main.rs: todo");
+        assert_eq!(tabstops(&snippet), &[vec![12..16], vec![16..16]]);
+
+        // Defaults are only used for variables with empty values.
+        let snippet = Snippet::parse_with_variables(
+            "${TM_FILENAME:unnamed} ${TM_SELECTED_TEXT:${1:selection}}",
+            resolve_variable,
+        )
+        .unwrap();
+        assert_eq!(snippet.text, "main.rs selection");
+        assert_eq!(tabstops(&snippet), &[vec![8..17], vec![17..17]]);
+
+        // Unknown variables become placeholders, visited after the numbered tabstops.
+        let snippet =
+            Snippet::parse_with_variables("$UNKNOWN ${OTHER:default} $1$0", resolve_variable)
+                .unwrap();
+        assert_eq!(snippet.text, "UNKNOWN default ");
+        assert_eq!(
+            tabstops(&snippet),
+            &[vec![16..16], vec![0..7], vec![8..15], vec![16..16]]
+        );
+
+        // Variables are rejected when parsing without a resolver.
+        assert!(Snippet::parse("${TM_FILENAME}").is_err());
+        assert!(Snippet::parse("$TM_FILENAME").is_err());
+        let snippet = Snippet::parse_with_variables("${TM_FILENAME}", |_| None).unwrap();
+        assert_eq!(snippet.text, "TM_FILENAME");
+        assert_eq!(tabstops(&snippet), &[vec![0..11], vec![11..11]]);
+    }
+
+    #[test]
+    fn test_snippet_with_variable_transformations() {
+        let resolve_variable = |name: &str| match name {
+            "TM_FILENAME" => Some("snippet_parser.rs".to_string()),
+            "TM_DIRECTORY" => Some(String::new()),
+            _ => None,
+        };
+        let expand = |source: &str| {
+            Snippet::parse_with_variables(source, resolve_variable)
+                .unwrap()
+                .text
+        };
+
+        assert_eq!(expand("${TM_FILENAME/(.*)\\..+$/$1/}"), "snippet_parser");
+        assert_eq!(
+            expand("${TM_FILENAME/(.*)\\..+$/${1:/upcase}/}"),
+            "SNIPPET_PARSER"
+        );
+        assert_eq!(
+            expand("${TM_FILENAME/(.*)\\..+$/${1:/pascalcase}/}"),
+            "SnippetParser"
+        );
+        assert_eq!(
+            expand("${TM_FILENAME/(.*)\\..+$/${1:/camelcase}/}"),
+            "snippetParser"
+        );
+        assert_eq!(
+            expand("${TM_FILENAME/^(s)/${1:/capitalize}/}"),
+            "Snippet_parser.rs"
+        );
+        assert_eq!(expand("${TM_FILENAME/_/-/}"), "snippet-parser.rs");
+        assert_eq!(expand("${TM_FILENAME/[aeiou]/*/g}"), "sn*pp*t_p*rs*r.rs");
+        assert_eq!(expand("${TM_FILENAME/SNIPPET/X/i}"), "X_parser.rs");
+        assert_eq!(expand("${TM_FILENAME/\\//x/}"), "snippet_parser.rs");
+        assert_eq!(
+            expand("${TM_FILENAME/(test)?.*/${1:+test}${1:?yes:no}${1:-none}/}"),
+            "nonone"
+        );
+        assert_eq!(expand("${TM_DIRECTORY/(.*)/${1:root}\\/\\$/}"), "root/$");
+
+        assert!(Snippet::parse("${TM_FILENAME/(/x/}").is_err());
+        assert!(Snippet::parse("${TM_FILENAME/a/${1:/unknown}/}").is_err());
+        assert!(Snippet::parse("${TM_FILENAME/a/b}").is_err());
+    }
+
+    #[test]
+    fn test_snippet_with_tabstop_transformations() {
+        let snippet = Snippet::parse("${1:my_name} ${1/(.*)/${1:/pascalcase}/} $0").unwrap();
+        assert_eq!(snippet.text, "my_name MyName ");
+        assert_eq!(tabstops(&snippet), &[vec![0..7], vec![15..15]]);
+
+        let transformations = &snippet.tabstops[0].transformations;
+        assert_eq!(transformations.len(), 1);
+        let (range, transformation) = &transformations[0];
+        assert_eq!(*range, 8..14);
+        assert_eq!(transformation.apply("other_name"), "OtherName");
+    }
+
     fn tabstops(snippet: &Snippet) -> Vec<Vec<Range<isize>>> {
         snippet.tabstops.iter().map(|t| t.ranges.to_vec()).collect()
     }
@@ -43,7 +43,11 @@ fn file_to_snippets(file_contents: VsSnippetsFile) -> Vec<Arc<Snippet>> {
             .description
             .map(|description| description.to_string());
         let body = snippet.body.to_string();
-        if snippet::Snippet::parse(&body).log_err().is_none() {
+        // Variables are resolved when the snippet is inserted, so accept any of them here.
+        if snippet::Snippet::parse_with_variables(&body, |_| None)
+            .log_err()
+            .is_none()
+        {
             continue;
         };
         snippets.push(Arc::new(Snippet {
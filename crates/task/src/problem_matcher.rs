@@ -0,0 +1,490 @@
+use anyhow::{Context as _, Result};
+use regex::{Captures, Regex};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// A way to extract problems (errors, warnings, etc.) from the output of a finished task,
+/// so that they can be shown as project diagnostics.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum ProblemMatcher {
+    /// One of the matchers for well-known tools, bundled with Zed.
+    Builtin(BuiltinProblemMatcher),
+    /// A matcher defined with regular expressions.
+    Custom(CustomProblemMatcher),
+}
+
+/// Problem matchers for well-known tools, bundled with Zed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BuiltinProblemMatcher {
+    /// Rust compiler (and `cargo`) errors and warnings, in the default (human-readable) format.
+    Rustc,
+    /// TypeScript compiler errors and warnings.
+    Tsc,
+    /// GCC and Clang errors, warnings and notes.
+    Gcc,
+    /// ESLint problems, reported with the default `stylish` formatter.
+    Eslint,
+}
+
+/// A problem matcher, defined with a list of regular expressions.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct CustomProblemMatcher {
+    /// Patterns to match consecutive lines of the task output with.
+    /// A problem is reported when the last pattern matches, using the data captured by all patterns.
+    pub pattern: Vec<ProblemPattern>,
+    /// Severity of the problems that have no severity captured by the patterns.
+    #[serde(default)]
+    pub severity: ProblemSeverity,
+}
+
+/// A regular expression to match a single line of the task output with,
+/// together with the indices of its capture groups that contain the problem data.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct ProblemPattern {
+    /// The regular expression to match the line with.
+    pub regexp: String,
+    /// The capture group with the file path, relative to the task's working directory, or absolute.
+    #[serde(default)]
+    pub file: Option<usize>,
+    /// The capture group with the 1-based line number of the problem.
+    #[serde(default)]
+    pub line: Option<usize>,
+    /// The capture group with the 1-based column number of the problem.
+    #[serde(default)]
+    pub column: Option<usize>,
+    /// The capture group with the 1-based line number where the problem ends.
+    #[serde(default)]
+    pub end_line: Option<usize>,
+    /// The capture group with the 1-based column number where the problem ends.
+    #[serde(default)]
+    pub end_column: Option<usize>,
+    /// The capture group with the severity of the problem, e.g. `error` or `warning`.
+    #[serde(default)]
+    pub severity: Option<usize>,
+    /// The capture group with the code of the problem.
+    #[serde(default)]
+    pub code: Option<usize>,
+    /// The capture group with the message of the problem.
+    #[serde(default)]
+    pub message: Option<usize>,
+    /// Whether the pattern can match multiple consecutive lines, reporting a problem for each of them.
+    /// Only applicable to the last pattern of a matcher.
+    #[serde(default, rename = "loop")]
+    pub is_loop: bool,
+}
+
+/// How severe a problem reported by a task is.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ProblemSeverity {
+    #[default]
+    Error,
+    Warning,
+    Info,
+    Hint,
+}
+
+impl ProblemSeverity {
+    pub(crate) fn from_output(text: &str) -> Option<Self> {
+        match text.to_lowercase().as_str() {
+            "error" | "fatal" | "fatal error" | "e" => Some(Self::Error),
+            "warning" | "warn" | "w" => Some(Self::Warning),
+            "info" | "information" | "note" | "i" => Some(Self::Info),
+            "hint" | "help" => Some(Self::Hint),
+            _ => None,
+        }
+    }
+}
+
+/// A problem found in the task output.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TaskProblem {
+    /// The path of the file the problem is in.
+    /// Relative paths from the output are joined with the task's working directory, if it's known.
+    pub path: PathBuf,
+    /// 1-based line number of the problem start.
+    pub line: u32,
+    /// 1-based column number of the problem start, if reported.
+    pub column: Option<u32>,
+    /// 1-based line number of the problem end, if reported.
+    pub end_line: Option<u32>,
+    /// 1-based column number of the problem end, if reported.
+    pub end_column: Option<u32>,
+    pub severity: ProblemSeverity,
+    pub code: Option<String>,
+    pub message: String,
+}
+
+impl BuiltinProblemMatcher {
+    /// A regex-based definition of the matcher.
+    pub fn custom_matcher(&self) -> CustomProblemMatcher {
+        match self {
+            Self::Rustc => CustomProblemMatcher {
+                pattern: vec![
+                    ProblemPattern {
+                        regexp: r"^(warning|error)(?:\[(\w+)\])?: (.*)$".to_string(),
+                        severity: Some(1),
+                        code: Some(2),
+                        message: Some(3),
+                        ..ProblemPattern::default()
+                    },
+                    ProblemPattern {
+                        regexp: r"^\s+-->\s+(.+?):(\d+):(\d+)$".to_string(),
+                        file: Some(1),
+                        line: Some(2),
+                        column: Some(3),
+                        ..ProblemPattern::default()
+                    },
+                ],
+                severity: ProblemSeverity::Error,
+            },
+            Self::Tsc => CustomProblemMatcher {
+                pattern: vec![ProblemPattern {
+                    regexp: r"^([^\s].*)[\(:](\d+)[,:](\d+)(?:\):\s+|\s+-\s+)(error|warning|info)\s+(TS\d+)\s*:\s*(.*)$".to_string(),
+                    file: Some(1),
+                    line: Some(2),
+                    column: Some(3),
+                    severity: Some(4),
+                    code: Some(5),
+                    message: Some(6),
+                    ..ProblemPattern::default()
+                }],
+                severity: ProblemSeverity::Error,
+            },
+            Self::Gcc => CustomProblemMatcher {
+                pattern: vec![ProblemPattern {
+                    regexp: r"^(.*?):(\d+):(\d*):?\s+(?:fatal\s+)?(warning|error|note):\s+(.*)$"
+                        .to_string(),
+                    file: Some(1),
+                    line: Some(2),
+                    column: Some(3),
+                    severity: Some(4),
+                    message: Some(5),
+                    ..ProblemPattern::default()
+                }],
+                severity: ProblemSeverity::Error,
+            },
+            Self::Eslint => CustomProblemMatcher {
+                pattern: vec![
+                    ProblemPattern {
+                        regexp: r"^((?:[a-zA-Z]:)*[./\\]+.*?)$".to_string(),
+                        file: Some(1),
+                        ..ProblemPattern::default()
+                    },
+                    ProblemPattern {
+                        regexp: r"^\s+(\d+):(\d+)\s+(error|warning|info)\s+(.+?)(?:\s\s+(.*))?$"
+                            .to_string(),
+                        line: Some(1),
+                        column: Some(2),
+                        severity: Some(3),
+                        message: Some(4),
+                        code: Some(5),
+                        is_loop: true,
+                        ..ProblemPattern::default()
+                    },
+                ],
+                severity: ProblemSeverity::Error,
+            },
+        }
+    }
+}
+
+impl ProblemMatcher {
+    /// Scans the task output line by line, and returns all problems found.
+    /// Relative file paths are resolved against the `cwd` given, if any.
+    pub fn find_problems(&self, output: &str, cwd: Option<&Path>) -> Result<Vec<TaskProblem>> {
+        match self {
+            Self::Builtin(builtin) => builtin.custom_matcher().find_problems(output, cwd),
+            Self::Custom(custom) => custom.find_problems(output, cwd),
+        }
+    }
+}
+
+#[derive(Clone, Default)]
+struct CapturedProblem {
+    file: Option<String>,
+    line: Option<u32>,
+    column: Option<u32>,
+    end_line: Option<u32>,
+    end_column: Option<u32>,
+    severity: Option<ProblemSeverity>,
+    code: Option<String>,
+    message: Option<String>,
+}
+
+impl CapturedProblem {
+    fn capture(&mut self, pattern: &ProblemPattern, captures: &Captures) {
+        let text = |group: Option<usize>| {
+            let text = captures.get(group?)?.as_str().trim();
+            (!text.is_empty()).then(|| text.to_string())
+        };
+        let number = |group: Option<usize>| text(group)?.parse::<u32>().ok();
+
+        if let Some(file) = text(pattern.file) {
+            self.file = Some(file);
+        }
+        if let Some(line) = number(pattern.line) {
+            self.line = Some(line);
+        }
+        if let Some(column) = number(pattern.column) {
+            self.column = Some(column);
+        }
+        if let Some(end_line) = number(pattern.end_line) {
+            self.end_line = Some(end_line);
+        }
+        if let Some(end_column) = number(pattern.end_column) {
+            self.end_column = Some(end_column);
+        }
+        if let Some(severity) = text(pattern.severity) {
+            self.severity = ProblemSeverity::from_output(&severity);
+        }
+        if let Some(code) = text(pattern.code) {
+            self.code = Some(code);
+        }
+        if let Some(message) = text(pattern.message) {
+            self.message = Some(message);
+        }
+    }
+
+    fn to_problem(
+        &self,
+        default_severity: ProblemSeverity,
+        cwd: Option<&Path>,
+    ) -> Option<TaskProblem> {
+        let file = Path::new(self.file.as_ref()?);
+        let path = match cwd {
+            Some(cwd) if file.is_relative() => cwd.join(file),
+            _ => file.to_path_buf(),
+        };
+        Some(TaskProblem {
+            path,
+            line: self.line?,
+            column: self.column,
+            end_line: self.end_line,
+            end_column: self.end_column,
+            severity: self.severity.unwrap_or(default_severity),
+            code: self.code.clone(),
+            message: self.message.clone()?,
+        })
+    }
+}
+
+impl CustomProblemMatcher {
+    /// Scans the task output line by line, and returns all problems found.
+    /// Relative file paths are resolved against the `cwd` given, if any.
+    pub fn find_problems(&self, output: &str, cwd: Option<&Path>) -> Result<Vec<TaskProblem>> {
+        let patterns = self
+            .pattern
+            .iter()
+            .map(|pattern| {
+                let regex = Regex::new(&pattern.regexp).with_context(|| {
+                    format!("compiling problem matcher regex {:?}", pattern.regexp)
+                })?;
+                anyhow::Ok((regex, pattern))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let Some(last_pattern_ix) = patterns.len().checked_sub(1) else {
+            return Ok(Vec::new());
+        };
+
+        let mut problems = Vec::new();
+        let mut next_pattern_ix = 0;
+        let mut captured = CapturedProblem::default();
+        // Data captured by all patterns but the last one, reused when the last pattern loops.
+        let mut captured_before_loop = None::<CapturedProblem>;
+        for line in output.lines() {
+            let line = line.trim_end();
+            // When a multi-line match breaks, the same line may still start a new problem.
+            let mut attempts = if next_pattern_ix == 0 { 1 } else { 2 };
+            while attempts > 0 {
+                attempts -= 1;
+                let (regex, pattern) = &patterns[next_pattern_ix];
+                let Some(captures) = regex.captures(line) else {
+                    next_pattern_ix = 0;
+                    captured = CapturedProblem::default();
+                    captured_before_loop = None;
+                    continue;
+                };
+
+                captured.capture(pattern, &captures);
+                if next_pattern_ix == last_pattern_ix {
+                    problems.extend(captured.to_problem(self.severity, cwd));
+                    if pattern.is_loop {
+                        let before_loop = captured_before_loop.get_or_insert_default();
+                        captured = before_loop.clone();
+                    } else {
+                        next_pattern_ix = 0;
+                        captured = CapturedProblem::default();
+                    }
+                } else {
+                    next_pattern_ix += 1;
+                    if next_pattern_ix == last_pattern_ix {
+                        captured_before_loop = Some(captured.clone());
+                    }
+                }
+                break;
+            }
+        }
+
+        Ok(problems)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rustc_problems() {
+        let output = r#"   Compiling zed v0.1.0 (/projects/zed)
+warning: unused variable: `x`
+ --> src/main.rs:2:9
+  |
+2 |     let x = 5;
+  |         ^ help: if this is intentional, prefix it with an underscore: `_x`
+
+error[E0308]: mismatched types
+  --> src/lib.rs:10:18
+   |
+error: aborting due to 1 previous error
+error: could not compile `zed` (lib) due to 1 previous error"#;
+        let problems = ProblemMatcher::Builtin(BuiltinProblemMatcher::Rustc)
+            .find_problems(output, Some(Path::new("/projects/zed")))
+            .unwrap();
+        assert_eq!(
+            problems,
+            vec![
+                TaskProblem {
+                    path: PathBuf::from("/projects/zed/src/main.rs"),
+                    line: 2,
+                    column: Some(9),
+                    end_line: None,
+                    end_column: None,
+                    severity: ProblemSeverity::Warning,
+                    code: None,
+                    message: "unused variable: `x`".to_string(),
+                },
+                TaskProblem {
+                    path: PathBuf::from("/projects/zed/src/lib.rs"),
+                    line: 10,
+                    column: Some(18),
+                    end_line: None,
+                    end_column: None,
+                    severity: ProblemSeverity::Error,
+                    code: Some("E0308".to_string()),
+                    message: "mismatched types".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tsc_and_gcc_problems() {
+        let tsc_output = "src/index.ts(3,7): error TS2322: Type 'string' is not assignable to type 'number'.\n\
+                          src/util.ts:12:1 - warning TS6133: 'foo' is declared but its value is never read.";
+        let problems = ProblemMatcher::Builtin(BuiltinProblemMatcher::Tsc)
+            .find_problems(tsc_output, None)
+            .unwrap();
+        assert_eq!(problems.len(), 2);
+        assert_eq!(problems[0].path, PathBuf::from("src/index.ts"));
+        assert_eq!((problems[0].line, problems[0].column), (3, Some(7)));
+        assert_eq!(problems[0].code.as_deref(), Some("TS2322"));
+        assert_eq!(problems[1].severity, ProblemSeverity::Warning);
+        assert_eq!(
+            problems[1].message,
+            "'foo' is declared but its value is never read."
+        );
+
+        let gcc_output = "main.c: In function 'main':\n\
+                          main.c:5:3: error: 'y' undeclared (first use in this function)\n\
+                          main.c:7: note: each undeclared identifier is reported only once";
+        let problems = ProblemMatcher::Builtin(BuiltinProblemMatcher::Gcc)
+            .find_problems(gcc_output, None)
+            .unwrap();
+        assert_eq!(problems.len(), 2);
+        assert_eq!((problems[0].line, problems[0].column), (5, Some(3)));
+        assert_eq!(problems[0].severity, ProblemSeverity::Error);
+        assert_eq!((problems[1].line, problems[1].column), (7, None));
+        assert_eq!(problems[1].severity, ProblemSeverity::Info);
+    }
+
+    #[test]
+    fn test_looping_patterns() {
+        let output = "/project/src/app.js\n  \
+                      1:10  error    'foo' is defined but never used  no-unused-vars\n  \
+                      3:1   warning  Unexpected console statement     no-console\n\
+                      \n\
+                      ✖ 2 problems (1 error, 1 warning)";
+        let problems = ProblemMatcher::Builtin(BuiltinProblemMatcher::Eslint)
+            .find_problems(output, None)
+            .unwrap();
+        assert_eq!(
+            problems
+                .iter()
+                .map(|problem| (
+                    problem.path.to_string_lossy().into_owned(),
+                    problem.line,
+                    problem.severity,
+                    problem.code.clone(),
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    "/project/src/app.js".to_string(),
+                    1,
+                    ProblemSeverity::Error,
+                    Some("no-unused-vars".to_string())
+                ),
+                (
+                    "/project/src/app.js".to_string(),
+                    3,
+                    ProblemSeverity::Warning,
+                    Some("no-console".to_string())
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_deserialize_problem_matchers() {
+        let matchers: Vec<ProblemMatcher> = serde_json::from_value(serde_json::json!([
+            "rustc",
+            {
+                "pattern": [{
+                    "regexp": "^(.*):(\\d+): (.*)$",
+                    "file": 1,
+                    "line": 2,
+                    "message": 3,
+                }],
+                "severity": "warning",
+            }
+        ]))
+        .unwrap();
+        assert_eq!(
+            matchers[0],
+            ProblemMatcher::Builtin(BuiltinProblemMatcher::Rustc)
+        );
+
+        let problems = matchers[1]
+            .find_problems("lint.txt:4: trailing whitespace", None)
+            .unwrap();
+        assert_eq!(
+            problems,
+            vec![TaskProblem {
+                path: PathBuf::from("lint.txt"),
+                line: 4,
+                column: None,
+                end_line: None,
+                end_column: None,
+                severity: ProblemSeverity::Warning,
+                code: None,
+                message: "trailing whitespace".to_string(),
+            }]
+        );
+    }
+}
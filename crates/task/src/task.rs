@@ -2,6 +2,7 @@
 
 mod adapter_schema;
 mod debug_format;
+mod problem_matcher;
 mod serde_helpers;
 mod shell_builder;
 pub mod static_source;
@@ -22,10 +23,14 @@ pub use debug_format::{
     AttachRequest, BuildTaskDefinition, DebugRequest, DebugScenario, DebugTaskFile, LaunchRequest,
     Request, TcpArgumentsTemplate, ZedDebugConfig,
 };
+pub use problem_matcher::{
+    BuiltinProblemMatcher, CustomProblemMatcher, ProblemMatcher, ProblemPattern, ProblemSeverity,
+    TaskProblem,
+};
 pub use shell_builder::{ShellBuilder, ShellKind};
 pub use task_template::{
-    DebugArgsRequest, HideStrategy, RevealStrategy, TaskTemplate, TaskTemplates,
-    substitute_variables_in_map, substitute_variables_in_str,
+    DebugArgsRequest, DependsOrder, HideStrategy, RevealStrategy, TaskInput, TaskInputKind,
+    TaskTemplate, TaskTemplates, substitute_variables_in_map, substitute_variables_in_str,
 };
 pub use vscode_debug_format::VsCodeDebugTaskFile;
 pub use vscode_format::VsCodeTaskFile;
@@ -74,6 +79,8 @@ pub struct SpawnInTerminal {
     pub show_command: bool,
     /// Whether to show the rerun button in the terminal tab.
     pub show_rerun: bool,
+    /// Problem matchers to run over the task output after it finishes, to turn it into project diagnostics.
+    pub problem_matcher: Vec<ProblemMatcher>,
 }
 
 impl SpawnInTerminal {
@@ -121,6 +128,8 @@ pub struct ResolvedTask {
     pub resolved_label: String,
     /// Variables that were substituted during the task template resolution.
     substituted_variables: HashSet<VariableName>,
+    /// A context the task got resolved with, used to resolve the tasks it depends on.
+    task_context: TaskContext,
     /// Further actions that need to take place after the resolved task is spawned,
     /// with all task variables resolved.
     pub resolved: SpawnInTerminal,
@@ -137,10 +146,77 @@ impl ResolvedTask {
         &self.substituted_variables
     }
 
+    /// A context the task got resolved with.
+    /// Tasks from [`TaskTemplate::depends_on`] should be resolved with the same context.
+    pub fn task_context(&self) -> &TaskContext {
+        &self.task_context
+    }
+
     /// A human-readable label to display in the UI.
     pub fn display_label(&self) -> &str {
         self.resolved.label.as_str()
     }
+
+    /// Inputs of the task the user has not provided the values for yet.
+    /// Until they are provided, the task is resolved with placeholders in their place and must not be spawned.
+    pub fn pending_inputs(&self) -> Vec<&TaskInput> {
+        self.original_task
+            .pending_inputs(&self.task_context)
+            .collect()
+    }
+
+    /// Resolves the task again, with the values the user provided for its inputs.
+    pub fn resolve_inputs(
+        &self,
+        id_base: &str,
+        values: impl IntoIterator<Item = (String, String)>,
+    ) -> Option<ResolvedTask> {
+        let mut task_context = self.task_context.clone();
+        for (id, value) in values {
+            task_context
+                .task_variables
+                .insert(VariableName::Input(Cow::Owned(id)), value);
+        }
+        self.original_task.resolve_task(id_base, &task_context)
+    }
+
+    /// Resolves the task again with the given project environment, keeping the task id.
+    /// Used for the task runs restored from the database, which doesn't store the project environment.
+    pub fn with_project_env(&self, project_env: HashMap<String, String>) -> Option<ResolvedTask> {
+        let mut task_context = self.task_context.clone();
+        task_context.project_env = project_env;
+        let mut resolved_task = self.original_task.resolve_task("", &task_context)?;
+        resolved_task.id = self.id.clone();
+        resolved_task.resolved.id = self.resolved.id.clone();
+        Some(resolved_task)
+    }
+
+    /// Resolves the task again, without the values of its secret inputs, to be kept around after it is spawned.
+    /// Keeps the task id, so that reruns still find the task, and ask for the secret values again.
+    pub fn without_secret_inputs(&self, id_base: &str) -> Option<ResolvedTask> {
+        let mut task_context = self.task_context.clone();
+        let mut had_secrets = false;
+        for input in self
+            .original_task
+            .inputs
+            .iter()
+            .filter(|input| input.is_secret())
+        {
+            had_secrets |= task_context
+                .task_variables
+                .0
+                .remove(&VariableName::Input(Cow::Owned(input.id.clone())))
+                .is_some();
+        }
+        if !had_secrets {
+            return Some(self.clone());
+        }
+
+        let mut resolved_task = self.original_task.resolve_task(id_base, &task_context)?;
+        resolved_task.id = self.id.clone();
+        resolved_task.resolved.id = self.resolved.id.clone();
+        Some(resolved_task)
+    }
 }
 
 /// Variables, available for use in [`TaskContext`] when a Zed's [`TaskTemplate`] gets resolved into a [`ResolvedTask`].
@@ -176,6 +252,9 @@ pub enum VariableName {
     /// Custom variable, provided by the plugin or other external source.
     /// Will be printed with `CUSTOM_` prefix to avoid potential conflicts with other variables.
     Custom(Cow<'static, str>),
+    /// A value the user was asked for before the task was spawned, see [`TaskInput`].
+    /// Will be printed with `INPUT_` prefix, followed by the input's id.
+    Input(Cow<'static, str>),
 }
 
 impl VariableName {
@@ -212,6 +291,10 @@ impl FromStr for VariableName {
                     without_prefix.strip_prefix(ZED_CUSTOM_VARIABLE_NAME_PREFIX)
                 {
                     Self::Custom(Cow::Owned(custom_name.to_owned()))
+                } else if let Some(input_id) =
+                    without_prefix.strip_prefix(ZED_INPUT_VARIABLE_NAME_PREFIX)
+                {
+                    Self::Input(Cow::Owned(input_id.to_owned()))
                 } else {
                     return Err(());
                 }
@@ -224,6 +307,7 @@ impl FromStr for VariableName {
 /// A prefix that all [`VariableName`] variants are prefixed with when used in environment variables and similar template contexts.
 pub const ZED_VARIABLE_NAME_PREFIX: &str = "ZED_";
 const ZED_CUSTOM_VARIABLE_NAME_PREFIX: &str = "CUSTOM_";
+const ZED_INPUT_VARIABLE_NAME_PREFIX: &str = "INPUT_";
 
 impl std::fmt::Display for VariableName {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
@@ -244,6 +328,10 @@ impl std::fmt::Display for VariableName {
                 f,
                 "{ZED_VARIABLE_NAME_PREFIX}{ZED_CUSTOM_VARIABLE_NAME_PREFIX}{s}"
             ),
+            Self::Input(id) => write!(
+                f,
+                "{ZED_VARIABLE_NAME_PREFIX}{ZED_INPUT_VARIABLE_NAME_PREFIX}{id}"
+            ),
         }
     }
 }
@@ -372,6 +460,10 @@ impl EnvVariableReplacer {
                 let variable_name = &right[1..];
                 return Some(format!("${{{variable_name}}}"));
             }
+            if left == "input" && !right.is_empty() {
+                let input_variable = VariableName::Input(Cow::Owned(right[1..].to_owned()));
+                return Some(format!("${{{input_variable}}}"));
+            }
             let (variable_name, default) = (left, right);
             let append_previous_default = |ret: &mut String| {
                 if !default.is_empty() {
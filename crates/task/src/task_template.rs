@@ -3,14 +3,14 @@ use collections::{HashMap, HashSet};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
-use std::path::PathBuf;
+use std::{borrow::Cow, path::PathBuf};
 use util::schemars::DefaultDenyUnknownFields;
 use util::serde::default_true;
 use util::{ResultExt, truncate_and_remove_front};
 
 use crate::{
-    AttachRequest, ResolvedTask, RevealTarget, Shell, SpawnInTerminal, TaskContext, TaskId,
-    VariableName, ZED_VARIABLE_NAME_PREFIX, serde_helpers::non_empty_string_vec,
+    AttachRequest, ProblemMatcher, ResolvedTask, RevealTarget, Shell, SpawnInTerminal, TaskContext,
+    TaskId, VariableName, ZED_VARIABLE_NAME_PREFIX, serde_helpers::non_empty_string_vec,
 };
 
 /// A template definition of a Zed task to run.
@@ -72,6 +72,71 @@ pub struct TaskTemplate {
     /// Whether to show the command line in the task output.
     #[serde(default = "default_true")]
     pub show_command: bool,
+    /// Labels of the tasks that have to finish successfully before this task is spawned.
+    /// If any of them exits with a non-zero code, the rest of the chain (including this task) is not spawned.
+    #[serde(default, deserialize_with = "non_empty_string_vec")]
+    pub depends_on: Vec<String>,
+    /// How to run the tasks from `depends_on`:
+    /// * `parallel` — spawn all dependencies at once (default)
+    /// * `sequence` — spawn dependencies one after another, in the order they are listed
+    #[serde(default)]
+    pub depends_order: DependsOrder,
+    /// Problem matchers to run over the task output after it finishes, to show the problems found as project diagnostics.
+    /// Either names of the bundled matchers (`rustc`, `tsc`, `gcc`, `eslint`), or custom, regex-based matchers.
+    #[serde(default)]
+    pub problem_matcher: Vec<ProblemMatcher>,
+    /// Values to ask the user for before the task is spawned.
+    /// Each value is available as the `ZED_INPUT_<id>` variable.
+    #[serde(default)]
+    pub inputs: Vec<TaskInput>,
+}
+
+/// A value the user is asked for before a task is spawned.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct TaskInput {
+    /// Identifier of the input, the value is available as the `ZED_INPUT_<id>` variable.
+    pub id: String,
+    /// Text to show when asking for the value.
+    #[serde(default)]
+    pub description: Option<String>,
+    /// How to ask for the value.
+    #[serde(flatten)]
+    pub kind: TaskInputKind,
+}
+
+impl TaskInput {
+    /// Whether the value must not be kept after the task is spawned, as it was typed in as a password.
+    pub fn is_secret(&self) -> bool {
+        matches!(
+            self.kind,
+            TaskInputKind::PromptString { password: true, .. }
+        )
+    }
+}
+
+/// How to ask the user for the value of a [`TaskInput`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TaskInputKind {
+    /// Ask for an arbitrary string.
+    PromptString {
+        /// Value to prefill the prompt with.
+        #[serde(default)]
+        default: Option<String>,
+        /// Whether to hide the value while typing it.
+        /// Such values are not kept after the task is spawned, and are asked for again on rerun.
+        #[serde(default)]
+        password: bool,
+    },
+    /// Ask to pick one of the given strings.
+    PickString {
+        /// Strings to pick from.
+        options: Vec<String>,
+        /// Option to select initially.
+        #[serde(default)]
+        default: Option<String>,
+    },
 }
 
 #[derive(Deserialize, Eq, PartialEq, Clone, Debug)]
@@ -109,6 +174,17 @@ pub enum HideStrategy {
     OnSuccess,
 }
 
+/// How to run the tasks a [`TaskTemplate`] depends on.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DependsOrder {
+    /// Spawn all dependencies at once, and wait for all of them to finish.
+    #[default]
+    Parallel,
+    /// Spawn dependencies one after another, in the order they are listed.
+    Sequence,
+}
+
 /// A group of Tasks defined in a JSON file.
 #[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 pub struct TaskTemplates(pub Vec<TaskTemplate>);
@@ -126,6 +202,18 @@ impl TaskTemplates {
 }
 
 impl TaskTemplate {
+    /// Inputs that have no value in the given context yet.
+    pub fn pending_inputs<'a>(
+        &'a self,
+        cx: &'a TaskContext,
+    ) -> impl Iterator<Item = &'a TaskInput> {
+        self.inputs.iter().filter(|input| {
+            cx.task_variables
+                .get(&VariableName::Input(Cow::Owned(input.id.clone())))
+                .is_none()
+        })
+    }
+
     /// Replaces all `VariableName` task variables in the task template string fields.
     /// If any replacement fails or the new string substitutions still have [`ZED_VARIABLE_NAME_PREFIX`],
     /// `None` is returned.
@@ -137,12 +225,24 @@ impl TaskTemplate {
             return None;
         }
 
+        // Inputs the user was not asked for yet are substituted with placeholders, for the task to be listed:
+        // it is resolved again with the actual values before it gets spawned.
+        let input_placeholders = self
+            .pending_inputs(cx)
+            .map(|input| {
+                (
+                    VariableName::Input(Cow::Owned(input.id.clone())),
+                    format!("<{}>", input.id),
+                )
+            })
+            .collect::<Vec<_>>();
         let mut variable_names = HashMap::default();
         let mut substituted_variables = HashSet::default();
         let task_variables = cx
             .task_variables
             .0
             .iter()
+            .chain(input_placeholders.iter().map(|(key, value)| (key, value)))
             .map(|(key, value)| {
                 let key_string = key.to_string();
                 if !variable_names.contains_key(&key_string) {
@@ -244,6 +344,7 @@ impl TaskTemplate {
         Some(ResolvedTask {
             id: id.clone(),
             substituted_variables,
+            task_context: cx.clone(),
             original_task: self.clone(),
             resolved_label: full_label.clone(),
             resolved: SpawnInTerminal {
@@ -271,6 +372,7 @@ impl TaskTemplate {
                 show_summary: self.show_summary,
                 show_command: self.show_command,
                 show_rerun: true,
+                problem_matcher: self.problem_matcher.clone(),
             },
         })
     }
@@ -790,6 +892,37 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_deserialize_task_dependencies() {
+        let template: TaskTemplate = serde_json::from_value(serde_json::json!({
+            "label": "deploy",
+            "command": "./deploy.sh",
+            "depends_on": ["build", "test"],
+            "depends_order": "sequence",
+        }))
+        .unwrap();
+        assert_eq!(template.depends_on, vec!["build", "test"]);
+        assert_eq!(template.depends_order, DependsOrder::Sequence);
+
+        let template: TaskTemplate = serde_json::from_value(serde_json::json!({
+            "label": "build",
+            "command": "cargo build",
+        }))
+        .unwrap();
+        assert!(template.depends_on.is_empty());
+        assert_eq!(template.depends_order, DependsOrder::Parallel);
+
+        assert!(
+            serde_json::from_value::<TaskTemplate>(serde_json::json!({
+                "label": "deploy",
+                "command": "./deploy.sh",
+                "depends_on": [""],
+            }))
+            .is_err(),
+            "Empty dependency labels should be rejected"
+        );
+    }
+
     #[track_caller]
     fn assert_substituted_variables(resolved_task: &ResolvedTask, mut expected: Vec<VariableName>) {
         let mut resolved_variables = resolved_task
@@ -970,4 +1103,144 @@ mod tests {
             "Should fail when ZED variable has no default and doesn't exist"
         );
     }
+
+    #[test]
+    fn test_resolving_templates_with_inputs() {
+        let template = TaskTemplate {
+            label: "deploy to $ZED_INPUT_environment".to_string(),
+            command: "./deploy.sh".to_string(),
+            args: vec!["--env".to_string(), "$ZED_INPUT_environment".to_string()],
+            inputs: vec![TaskInput {
+                id: "environment".to_string(),
+                description: None,
+                kind: TaskInputKind::PickString {
+                    options: vec!["staging".to_string(), "production".to_string()],
+                    default: None,
+                },
+            }],
+            ..TaskTemplate::default()
+        };
+
+        let pending = template
+            .resolve_task(TEST_ID_BASE, &TaskContext::default())
+            .expect("Should resolve task with placeholders for the pending inputs");
+        assert_eq!(pending.resolved.label, "deploy to <environment>");
+        assert_eq!(
+            pending
+                .pending_inputs()
+                .into_iter()
+                .map(|input| input.id.as_str())
+                .collect::<Vec<_>>(),
+            vec!["environment"]
+        );
+
+        let resolved = pending
+            .resolve_inputs(
+                TEST_ID_BASE,
+                [("environment".to_string(), "production".to_string())],
+            )
+            .expect("Should resolve task with the input values");
+        assert!(resolved.pending_inputs().is_empty());
+        assert_eq!(resolved.resolved.label, "deploy to production");
+        assert_eq!(resolved.resolved.args, vec!["--env", "production"]);
+        assert_eq!(
+            resolved.resolved.env.get("ZED_INPUT_environment"),
+            Some(&"production".to_string())
+        );
+        assert!(
+            resolved
+                .substituted_variables()
+                .contains(&VariableName::Input(Cow::Borrowed("environment")))
+        );
+        assert_ne!(pending.id, resolved.id);
+        assert_eq!(
+            "ZED_INPUT_environment".parse::<VariableName>(),
+            Ok(VariableName::Input(Cow::Borrowed("environment")))
+        );
+    }
+
+    #[test]
+    fn test_forgetting_secret_inputs() {
+        let template = TaskTemplate {
+            label: "publish $ZED_INPUT_package".to_string(),
+            command: "./publish.sh".to_string(),
+            env: [("TOKEN".to_string(), "$ZED_INPUT_token".to_string())]
+                .into_iter()
+                .collect(),
+            inputs: vec![
+                TaskInput {
+                    id: "package".to_string(),
+                    description: None,
+                    kind: TaskInputKind::PromptString {
+                        default: None,
+                        password: false,
+                    },
+                },
+                TaskInput {
+                    id: "token".to_string(),
+                    description: None,
+                    kind: TaskInputKind::PromptString {
+                        default: None,
+                        password: true,
+                    },
+                },
+            ],
+            ..TaskTemplate::default()
+        };
+
+        let resolved = template
+            .resolve_task(TEST_ID_BASE, &TaskContext::default())
+            .unwrap()
+            .resolve_inputs(
+                TEST_ID_BASE,
+                [
+                    ("package".to_string(), "zed".to_string()),
+                    ("token".to_string(), "hunter2".to_string()),
+                ],
+            )
+            .unwrap();
+        assert_eq!(
+            resolved.resolved.env.get("TOKEN"),
+            Some(&"hunter2".to_string())
+        );
+
+        let forgotten = resolved.without_secret_inputs(TEST_ID_BASE).unwrap();
+        assert_eq!(forgotten.id, resolved.id);
+        assert_eq!(forgotten.resolved.label, "publish zed");
+        assert!(
+            forgotten
+                .resolved
+                .env
+                .values()
+                .all(|value| !value.contains("hunter2")),
+            "Secret values should not be kept: {:?}",
+            forgotten.resolved.env
+        );
+        assert_eq!(
+            forgotten
+                .task_context()
+                .task_variables
+                .get(&VariableName::Input(Cow::Borrowed("token"))),
+            None
+        );
+        assert_eq!(
+            forgotten
+                .pending_inputs()
+                .into_iter()
+                .map(|input| input.id.as_str())
+                .collect::<Vec<_>>(),
+            vec!["token"],
+            "Secret inputs should be asked for again on rerun"
+        );
+
+        let without_secrets = template
+            .resolve_task(TEST_ID_BASE, &TaskContext::default())
+            .unwrap()
+            .resolve_inputs(TEST_ID_BASE, [("package".to_string(), "zed".to_string())])
+            .unwrap();
+        assert_eq!(
+            without_secrets.without_secret_inputs(TEST_ID_BASE),
+            Some(without_secrets)
+        );
+    }
 }
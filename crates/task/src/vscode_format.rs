@@ -3,7 +3,13 @@ use collections::HashMap;
 use serde::Deserialize;
 use util::ResultExt;
 
-use crate::{EnvVariableReplacer, TaskTemplate, TaskTemplates, VariableName};
+use std::borrow::Cow;
+
+use crate::{
+    BuiltinProblemMatcher, CustomProblemMatcher, EnvVariableReplacer, ProblemMatcher,
+    ProblemPattern, ProblemSeverity, TaskInput, TaskInputKind, TaskTemplate, TaskTemplates,
+    VariableName,
+};
 
 #[derive(Clone, Debug, Deserialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
@@ -41,10 +47,170 @@ enum Command {
     },
 }
 
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(untagged)]
+enum VsCodeProblemMatchers {
+    Single(VsCodeProblemMatcher),
+    Multiple(Vec<VsCodeProblemMatcher>),
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(untagged)]
+enum VsCodeProblemMatcher {
+    Named(String),
+    Definition(VsCodeProblemMatcherDefinition),
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+struct VsCodeProblemMatcherDefinition {
+    base: Option<String>,
+    pattern: Option<VsCodeProblemPatterns>,
+    severity: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(untagged)]
+enum VsCodeProblemPatterns {
+    Single(VsCodeProblemPattern),
+    Multiple(Vec<VsCodeProblemPattern>),
+    Named(String),
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+struct VsCodeProblemPattern {
+    regexp: String,
+    file: Option<usize>,
+    line: Option<usize>,
+    column: Option<usize>,
+    end_line: Option<usize>,
+    end_column: Option<usize>,
+    severity: Option<usize>,
+    code: Option<usize>,
+    message: Option<usize>,
+    #[serde(default, rename = "loop")]
+    is_loop: bool,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+struct VsCodeTaskInput {
+    id: String,
+    description: Option<String>,
+    #[serde(flatten)]
+    kind: VsCodeTaskInputKind,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(tag = "type")]
+#[serde(rename_all = "camelCase")]
+enum VsCodeTaskInputKind {
+    PromptString {
+        default: Option<String>,
+        #[serde(default)]
+        password: bool,
+    },
+    PickString {
+        options: Vec<VsCodePickStringOption>,
+        default: Option<String>,
+    },
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(untagged)]
+enum VsCodePickStringOption {
+    Value(String),
+    Labeled { value: String },
+}
+
+impl VsCodeTaskInput {
+    fn into_zed_format(self) -> TaskInput {
+        let kind = match self.kind {
+            VsCodeTaskInputKind::PromptString { default, password } => {
+                TaskInputKind::PromptString { default, password }
+            }
+            VsCodeTaskInputKind::PickString { options, default } => TaskInputKind::PickString {
+                options: options
+                    .into_iter()
+                    .map(|option| match option {
+                        VsCodePickStringOption::Value(value)
+                        | VsCodePickStringOption::Labeled { value } => value,
+                    })
+                    .collect(),
+                default,
+            },
+        };
+        TaskInput {
+            id: self.id,
+            description: self.description,
+            kind,
+        }
+    }
+}
+
+impl VsCodeProblemMatcher {
+    fn into_zed_format(self) -> Option<ProblemMatcher> {
+        match self {
+            Self::Named(name) => builtin_problem_matcher(&name).map(ProblemMatcher::Builtin),
+            Self::Definition(definition) => {
+                let patterns = match definition.pattern {
+                    Some(VsCodeProblemPatterns::Single(pattern)) => vec![pattern],
+                    Some(VsCodeProblemPatterns::Multiple(patterns)) => patterns,
+                    Some(VsCodeProblemPatterns::Named(name)) => {
+                        log::warn!("Skipping unsupported named problem pattern `{name}`");
+                        return None;
+                    }
+                    None => {
+                        return builtin_problem_matcher(definition.base.as_deref()?)
+                            .map(ProblemMatcher::Builtin);
+                    }
+                };
+                Some(ProblemMatcher::Custom(CustomProblemMatcher {
+                    pattern: patterns
+                        .into_iter()
+                        .map(|pattern| ProblemPattern {
+                            regexp: pattern.regexp,
+                            file: pattern.file,
+                            line: pattern.line,
+                            column: pattern.column,
+                            end_line: pattern.end_line,
+                            end_column: pattern.end_column,
+                            severity: pattern.severity,
+                            code: pattern.code,
+                            message: pattern.message,
+                            is_loop: pattern.is_loop,
+                        })
+                        .collect(),
+                    severity: definition
+                        .severity
+                        .as_deref()
+                        .and_then(ProblemSeverity::from_output)
+                        .unwrap_or_default(),
+                }))
+            }
+        }
+    }
+}
+
+fn builtin_problem_matcher(vscode_name: &str) -> Option<BuiltinProblemMatcher> {
+    match vscode_name {
+        "$rustc" | "$rustc-watch" => Some(BuiltinProblemMatcher::Rustc),
+        "$tsc" | "$tsc-watch" => Some(BuiltinProblemMatcher::Tsc),
+        "$gcc" => Some(BuiltinProblemMatcher::Gcc),
+        "$eslint-stylish" => Some(BuiltinProblemMatcher::Eslint),
+        _ => {
+            log::warn!("Skipping unsupported problem matcher `{vscode_name}`");
+            None
+        }
+    }
+}
+
 impl VsCodeTaskDefinition {
     fn into_zed_format(
         self,
         replacer: &EnvVariableReplacer,
+        inputs: &[TaskInput],
     ) -> anyhow::Result<Option<TaskTemplate>> {
         if self.other_attributes.contains_key("dependsOn") {
             log::warn!(
@@ -66,19 +232,62 @@ impl VsCodeTaskDefinition {
             Command::Shell { command, args } => (command, args),
             Command::Gulp { task } => ("gulp".to_owned(), vec![task]),
         };
-        // Per VSC docs, only `command`, `args` and `options` support variable substitution.
+        // Per VSC docs, only `command`, `args` and `options` support variable substitution;
+        // the label is substituted too, for it to show the input values the task got spawned with.
         let command = replacer.replace(&command);
         let args = args.into_iter().map(|arg| replacer.replace(&arg)).collect();
+        let problem_matcher = match self.other_attributes.get("problemMatcher").cloned() {
+            Some(problem_matcher) => {
+                match serde_json_lenient::from_value::<VsCodeProblemMatchers>(problem_matcher) {
+                    Ok(VsCodeProblemMatchers::Single(matcher)) => {
+                        matcher.into_zed_format().into_iter().collect()
+                    }
+                    Ok(VsCodeProblemMatchers::Multiple(matchers)) => matchers
+                        .into_iter()
+                        .filter_map(VsCodeProblemMatcher::into_zed_format)
+                        .collect(),
+                    Err(e) => {
+                        log::warn!(
+                            "Skipping problem matchers of a task `{}` that failed to parse: {e}",
+                            self.label
+                        );
+                        Vec::new()
+                    }
+                }
+            }
+            None => Vec::new(),
+        };
         let mut template = TaskTemplate {
-            label: self.label,
+            label: replacer.replace(&self.label),
             command,
             args,
+            problem_matcher,
             ..TaskTemplate::default()
         };
         if let Some(options) = self.options {
             template.cwd = options.cwd.map(|cwd| replacer.replace(&cwd));
-            template.env = options.env;
+            template.env = options
+                .env
+                .into_iter()
+                .map(|(name, value)| (name, replacer.replace(&value)))
+                .collect();
         }
+        // Inputs are defined for the whole file, but the user should only be asked for the ones the task uses.
+        template.inputs = inputs
+            .iter()
+            .filter(|input| {
+                let variable = VariableName::Input(Cow::Owned(input.id.clone())).to_string();
+                template.label.contains(&variable)
+                    || template.command.contains(&variable)
+                    || template.args.iter().any(|arg| arg.contains(&variable))
+                    || template.env.values().any(|value| value.contains(&variable))
+                    || template
+                        .cwd
+                        .as_ref()
+                        .is_some_and(|cwd| cwd.contains(&variable))
+            })
+            .cloned()
+            .collect();
         Ok(Some(template))
     }
 }
@@ -87,6 +296,8 @@ impl VsCodeTaskDefinition {
 #[derive(Debug, Deserialize, PartialEq)]
 pub struct VsCodeTaskFile {
     tasks: Vec<VsCodeTaskDefinition>,
+    #[serde(default)]
+    inputs: Vec<serde_json_lenient::Value>,
 }
 
 impl TryFrom<VsCodeTaskFile> for TaskTemplates {
@@ -105,12 +316,25 @@ impl TryFrom<VsCodeTaskFile> for TaskTemplates {
                 VariableName::SelectedText.to_string(),
             ),
         ]));
+        let inputs = value
+            .inputs
+            .into_iter()
+            .filter_map(
+                |input| match serde_json_lenient::from_value::<VsCodeTaskInput>(input) {
+                    Ok(input) => Some(input.into_zed_format()),
+                    Err(e) => {
+                        log::warn!("Skipping task input that failed to parse: {e}");
+                        None
+                    }
+                },
+            )
+            .collect::<Vec<_>>();
         let templates = value
             .tasks
             .into_iter()
             .filter_map(|vscode_definition| {
                 vscode_definition
-                    .into_zed_format(&replacer)
+                    .into_zed_format(&replacer, &inputs)
                     .log_err()
                     .flatten()
             })
@@ -124,7 +348,8 @@ mod tests {
     use std::collections::HashMap;
 
     use crate::{
-        TaskTemplate, TaskTemplates, VsCodeTaskFile,
+        BuiltinProblemMatcher, ProblemMatcher, TaskInput, TaskInputKind, TaskTemplate,
+        TaskTemplates, VsCodeTaskFile,
         vscode_format::{Command, VsCodeTaskDefinition},
     };
 
@@ -227,6 +452,7 @@ mod tests {
                 label: "gulp: tests".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "build:tests:notypecheck".to_string()],
+                problem_matcher: vec![ProblemMatcher::Builtin(BuiltinProblemMatcher::Tsc)],
                 ..Default::default()
             },
             TaskTemplate {
@@ -238,18 +464,21 @@ mod tests {
                     "${ZED_WORKTREE_ROOT}/src".to_string(),
                     "--watch".to_string(),
                 ],
+                problem_matcher: vec![ProblemMatcher::Builtin(BuiltinProblemMatcher::Tsc)],
                 ..Default::default()
             },
             TaskTemplate {
                 label: "npm: build:compiler".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "build:compiler".to_string()],
+                problem_matcher: vec![ProblemMatcher::Builtin(BuiltinProblemMatcher::Tsc)],
                 ..Default::default()
             },
             TaskTemplate {
                 label: "npm: build:tests".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "build:tests:notypecheck".to_string()],
+                problem_matcher: vec![ProblemMatcher::Builtin(BuiltinProblemMatcher::Tsc)],
                 ..Default::default()
             },
         ];
@@ -330,32 +559,180 @@ mod tests {
                 label: "Build Extension in Background".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "watch".to_string()],
+                problem_matcher: vec![ProblemMatcher::Builtin(BuiltinProblemMatcher::Tsc)],
                 ..Default::default()
             },
             TaskTemplate {
                 label: "Build Extension".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "build".to_string()],
+                problem_matcher: vec![ProblemMatcher::Builtin(BuiltinProblemMatcher::Tsc)],
                 ..Default::default()
             },
             TaskTemplate {
                 label: "Build Server".to_string(),
                 command: "cargo build --package rust-analyzer".to_string(),
+                problem_matcher: vec![ProblemMatcher::Builtin(BuiltinProblemMatcher::Rustc)],
                 ..Default::default()
             },
             TaskTemplate {
                 label: "Build Server (Release)".to_string(),
                 command: "cargo build --release --package rust-analyzer".to_string(),
+                problem_matcher: vec![ProblemMatcher::Builtin(BuiltinProblemMatcher::Rustc)],
                 ..Default::default()
             },
             TaskTemplate {
                 label: "Pretest".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "pretest".to_string()],
+                problem_matcher: vec![ProblemMatcher::Builtin(BuiltinProblemMatcher::Tsc)],
                 ..Default::default()
             },
         ];
         let tasks: TaskTemplates = vscode_definitions.try_into().unwrap();
         assert_eq!(tasks.0, expected);
     }
+
+    #[test]
+    fn can_deserialize_tasks_with_inputs() {
+        let vscode_definitions: VsCodeTaskFile = serde_json_lenient::from_str(
+            r#"{
+                "version": "2.0.0",
+                "tasks": [
+                    {
+                        "label": "deploy",
+                        "type": "shell",
+                        "command": "./deploy.sh",
+                        "args": ["--env", "${input:environment}", "--tag=${input:tag}"]
+                    },
+                    {
+                        "label": "build",
+                        "type": "shell",
+                        "command": "make"
+                    }
+                ],
+                "inputs": [
+                    {
+                        "id": "environment",
+                        "type": "pickString",
+                        "description": "Where to deploy",
+                        "options": ["staging", { "label": "Production", "value": "production" }],
+                        "default": "staging"
+                    },
+                    {
+                        "id": "tag",
+                        "type": "promptString",
+                        "description": "Release tag"
+                    },
+                    {
+                        "id": "branch",
+                        "type": "command",
+                        "command": "git.branch"
+                    }
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let environment = TaskInput {
+            id: "environment".to_string(),
+            description: Some("Where to deploy".to_string()),
+            kind: TaskInputKind::PickString {
+                options: vec!["staging".to_string(), "production".to_string()],
+                default: Some("staging".to_string()),
+            },
+        };
+        let tag = TaskInput {
+            id: "tag".to_string(),
+            description: Some("Release tag".to_string()),
+            kind: TaskInputKind::PromptString {
+                default: None,
+                password: false,
+            },
+        };
+        let expected = vec![
+            TaskTemplate {
+                label: "deploy".to_string(),
+                command: "./deploy.sh".to_string(),
+                args: vec![
+                    "--env".to_string(),
+                    "${ZED_INPUT_environment}".to_string(),
+                    "--tag=${ZED_INPUT_tag}".to_string(),
+                ],
+                inputs: vec![environment, tag],
+                ..Default::default()
+            },
+            TaskTemplate {
+                label: "build".to_string(),
+                command: "make".to_string(),
+                ..Default::default()
+            },
+        ];
+        let tasks: TaskTemplates = vscode_definitions.try_into().unwrap();
+        assert_eq!(tasks.0, expected);
+    }
+
+    #[test]
+    fn finds_inputs_in_env_and_label() {
+        let vscode_definitions: VsCodeTaskFile = serde_json_lenient::from_str(
+            r#"{
+                "version": "2.0.0",
+                "tasks": [
+                    {
+                        "label": "publish ${input:package}",
+                        "type": "shell",
+                        "command": "./publish.sh",
+                        "options": {
+                            "env": { "TOKEN": "${input:token}" }
+                        }
+                    }
+                ],
+                "inputs": [
+                    {
+                        "id": "package",
+                        "type": "promptString"
+                    },
+                    {
+                        "id": "token",
+                        "type": "promptString",
+                        "password": true
+                    },
+                    {
+                        "id": "unused",
+                        "type": "promptString"
+                    }
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let expected = vec![TaskTemplate {
+            label: "publish ${ZED_INPUT_package}".to_string(),
+            command: "./publish.sh".to_string(),
+            env: [("TOKEN".to_string(), "${ZED_INPUT_token}".to_string())]
+                .into_iter()
+                .collect(),
+            inputs: vec![
+                TaskInput {
+                    id: "package".to_string(),
+                    description: None,
+                    kind: TaskInputKind::PromptString {
+                        default: None,
+                        password: false,
+                    },
+                },
+                TaskInput {
+                    id: "token".to_string(),
+                    description: None,
+                    kind: TaskInputKind::PromptString {
+                        default: None,
+                        password: true,
+                    },
+                },
+            ],
+            ..Default::default()
+        }];
+        let tasks: TaskTemplates = vscode_definitions.try_into().unwrap();
+        assert_eq!(tasks.0, expected);
+    }
 }
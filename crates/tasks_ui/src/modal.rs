@@ -11,7 +11,7 @@ use gpui::{
 use itertools::Itertools;
 use picker::{Picker, PickerDelegate, highlighted_match_with_paths::HighlightedMatch};
 use project::{TaskSourceKind, task_store::TaskStore};
-use task::{DebugScenario, ResolvedTask, RevealTarget, TaskContext, TaskTemplate};
+use task::{DebugScenario, DependsOrder, ResolvedTask, RevealTarget, TaskContext, TaskTemplate};
 use ui::{
     ActiveTheme, Clickable, FluentBuilder as _, IconButtonShape, IconWithIndicator, Indicator,
     IntoElement, KeyBinding, ListItem, ListItemSpacing, RenderOnce, Toggleable, Tooltip, div,
@@ -473,6 +473,18 @@ impl PickerDelegate for TasksModalDelegate {
                     .as_str(),
             );
         }
+
+        if !template.depends_on.is_empty() {
+            if !tooltip_label_text.trim().is_empty() {
+                tooltip_label_text.push('\n');
+            }
+            let separator = match template.depends_order {
+                DependsOrder::Parallel => ", ",
+                DependsOrder::Sequence => " → ",
+            };
+            tooltip_label_text.push_str("Depends on: ");
+            tooltip_label_text.push_str(&template.depends_on.join(separator));
+        }
         let tooltip_label = if tooltip_label_text.trim().is_empty() {
             None
         } else {
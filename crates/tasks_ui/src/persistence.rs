@@ -0,0 +1,311 @@
+use std::{
+    path::PathBuf,
+    time::{Duration, SystemTime},
+};
+
+use anyhow::{Context as _, Result};
+use collections::HashMap;
+use db::{
+    query,
+    sqlez::{
+        bindable::Column, domain::Domain, statement::Statement,
+        thread_safe_connection::ThreadSafeConnection,
+    },
+    sqlez_macros::sql,
+};
+use project::{TaskRun, TaskRunStatus};
+use serde::{Deserialize, Serialize};
+use task::{ResolvedTask, TaskContext, TaskTemplate, TaskVariables, VariableName};
+use workspace::{WorkspaceDb, WorkspaceId};
+
+/// The number of task runs kept per workspace.
+const MAX_PERSISTED_TASK_RUNS: i64 = 100;
+
+/// A finished task run, stored as the template and context it was resolved from,
+/// so that it resolves into the very same task again when restored.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct SerializedTaskRun {
+    pub id_base: String,
+    pub template: TaskTemplate,
+    pub task_context: SerializedTaskContext,
+    pub started_at: SystemTime,
+    pub finished_at: SystemTime,
+    pub status: TaskRunStatus,
+}
+
+/// [`TaskContext`] with its variables stored by their names, as they are not valid JSON keys otherwise.
+/// The project environment is not stored, as it may hold secrets: it's loaded again when the task is rerun.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct SerializedTaskContext {
+    cwd: Option<PathBuf>,
+    task_variables: Vec<(String, String)>,
+}
+
+impl From<&TaskContext> for SerializedTaskContext {
+    fn from(task_context: &TaskContext) -> Self {
+        Self {
+            cwd: task_context.cwd.clone(),
+            task_variables: task_context
+                .task_variables
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.clone()))
+                .collect(),
+        }
+    }
+}
+
+impl From<SerializedTaskContext> for TaskContext {
+    fn from(task_context: SerializedTaskContext) -> Self {
+        Self {
+            cwd: task_context.cwd,
+            task_variables: task_context
+                .task_variables
+                .into_iter()
+                .filter_map(|(name, value)| Some((name.parse::<VariableName>().ok()?, value)))
+                .collect::<TaskVariables>(),
+            project_env: HashMap::default(),
+        }
+    }
+}
+
+impl SerializedTaskRun {
+    /// Serializes a finished task run, returning `None` for runs that are still going or were
+    /// themselves restored, as their source is unknown.
+    /// The values of secret inputs are never written to the database.
+    pub fn new(task_run: &TaskRun) -> Option<Self> {
+        let id_base = task_run.source_kind.as_ref()?.to_id_base();
+        let resolved_task = task_run.resolved_task.without_secret_inputs(&id_base)?;
+        Some(Self {
+            template: resolved_task.original_task().clone(),
+            task_context: resolved_task.task_context().into(),
+            id_base,
+            started_at: task_run.started_at,
+            finished_at: task_run.finished_at?,
+            status: task_run.status,
+        })
+    }
+
+    pub fn resolve(self) -> Option<(ResolvedTask, SystemTime, SystemTime, TaskRunStatus)> {
+        let resolved_task = self
+            .template
+            .resolve_task(&self.id_base, &self.task_context.into())?;
+        Some((
+            resolved_task,
+            self.started_at,
+            self.finished_at,
+            self.status,
+        ))
+    }
+}
+
+fn to_millis(time: SystemTime) -> i64 {
+    time.duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as i64
+}
+
+fn from_millis(millis: i64) -> SystemTime {
+    SystemTime::UNIX_EPOCH + Duration::from_millis(millis.max(0) as u64)
+}
+
+fn status_to_columns(status: TaskRunStatus) -> (&'static str, Option<i32>) {
+    match status {
+        TaskRunStatus::Running => ("running", None),
+        TaskRunStatus::Succeeded => ("succeeded", None),
+        TaskRunStatus::Failed { exit_code } => ("failed", exit_code),
+        TaskRunStatus::Cancelled => ("cancelled", None),
+    }
+}
+
+fn status_from_columns(status: &str, exit_code: Option<i32>) -> Result<TaskRunStatus> {
+    Ok(match status {
+        "running" => TaskRunStatus::Running,
+        "succeeded" => TaskRunStatus::Succeeded,
+        "failed" => TaskRunStatus::Failed { exit_code },
+        "cancelled" => TaskRunStatus::Cancelled,
+        _ => anyhow::bail!("Invalid task run status: '{status}'"),
+    })
+}
+
+impl Column for SerializedTaskRun {
+    fn column(statement: &mut Statement, start_index: i32) -> Result<(Self, i32)> {
+        let (id_base, next_index): (String, i32) = Column::column(statement, start_index)?;
+        let (template, next_index): (String, i32) = Column::column(statement, next_index)?;
+        let (task_context, next_index): (String, i32) = Column::column(statement, next_index)?;
+        let (started_at, next_index): (i64, i32) = Column::column(statement, next_index)?;
+        let (finished_at, next_index): (i64, i32) = Column::column(statement, next_index)?;
+        let (status, next_index): (String, i32) = Column::column(statement, next_index)?;
+        let (exit_code, next_index): (Option<i32>, i32) = Column::column(statement, next_index)?;
+
+        let task_run = Self {
+            id_base,
+            template: serde_json::from_str(&template).context("deserializing task template")?,
+            task_context: serde_json::from_str(&task_context)
+                .context("deserializing task context")?,
+            started_at: from_millis(started_at),
+            finished_at: from_millis(finished_at),
+            status: status_from_columns(&status, exit_code)?,
+        };
+        Ok((task_run, next_index))
+    }
+}
+
+pub struct TaskRunsDb(ThreadSafeConnection);
+
+impl Domain for TaskRunsDb {
+    const NAME: &str = stringify!(TaskRunsDb);
+
+    const MIGRATIONS: &[&str] = &[sql!(
+        CREATE TABLE task_runs (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            workspace_id INTEGER NOT NULL,
+            id_base TEXT NOT NULL,
+            template TEXT NOT NULL,
+            task_context TEXT NOT NULL,
+            started_at INTEGER NOT NULL,
+            finished_at INTEGER NOT NULL,
+            status TEXT NOT NULL,
+            exit_code INTEGER,
+            FOREIGN KEY(workspace_id) REFERENCES workspaces(workspace_id)
+            ON DELETE CASCADE
+        ) STRICT;
+    )];
+}
+
+db::static_connection!(TASK_RUNS_DB, TaskRunsDb, [WorkspaceDb]);
+
+impl TaskRunsDb {
+    pub(crate) async fn save_task_run(
+        &self,
+        workspace_id: WorkspaceId,
+        task_run: SerializedTaskRun,
+    ) -> Result<()> {
+        let template = serde_json::to_string(&task_run.template)?;
+        let task_context = serde_json::to_string(&task_run.task_context)?;
+        let (status, exit_code) = status_to_columns(task_run.status);
+        self.insert_task_run(
+            workspace_id,
+            task_run.id_base,
+            template,
+            task_context,
+            to_millis(task_run.started_at),
+            to_millis(task_run.finished_at),
+            status.to_string(),
+            exit_code,
+        )
+        .await?;
+        self.trim_task_runs(workspace_id, MAX_PERSISTED_TASK_RUNS)
+            .await
+    }
+
+    query! {
+        async fn insert_task_run(
+            workspace_id: WorkspaceId,
+            id_base: String,
+            template: String,
+            task_context: String,
+            started_at: i64,
+            finished_at: i64,
+            status: String,
+            exit_code: Option<i32>
+        ) -> Result<()> {
+            INSERT INTO task_runs
+                (workspace_id, id_base, template, task_context, started_at, finished_at, status, exit_code)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+        }
+    }
+
+    query! {
+        async fn trim_task_runs(workspace_id: WorkspaceId, max_runs: i64) -> Result<()> {
+            DELETE FROM task_runs
+            WHERE workspace_id = ?1 AND id NOT IN (
+                SELECT id FROM task_runs
+                WHERE workspace_id = ?1
+                ORDER BY id DESC
+                LIMIT ?2
+            )
+        }
+    }
+
+    query! {
+        pub(crate) fn task_runs(workspace_id: WorkspaceId) -> Result<Vec<SerializedTaskRun>> {
+            SELECT id_base, template, task_context, started_at, finished_at, status, exit_code
+            FROM task_runs
+            WHERE workspace_id = ?
+            ORDER BY id ASC
+        }
+    }
+
+    query! {
+        pub(crate) async fn clear_task_runs(workspace_id: WorkspaceId) -> Result<()> {
+            DELETE FROM task_runs
+            WHERE workspace_id = ?
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[gpui::test]
+    async fn test_save_and_restore_task_runs() {
+        let workspace_id = workspace::WORKSPACE_DB.next_id().await.unwrap();
+
+        let mut task_variables = TaskVariables::default();
+        task_variables.insert(VariableName::File, "/project/src/main.rs".into());
+        task_variables.insert(VariableName::Custom("RUST_PACKAGE".into()), "zed".into());
+        let task_context = TaskContext {
+            cwd: Some(PathBuf::from("/project")),
+            task_variables,
+            project_env: HashMap::default(),
+        };
+        let template = TaskTemplate {
+            label: "check $ZED_CUSTOM_RUST_PACKAGE".into(),
+            command: "cargo".into(),
+            args: vec![
+                "check".into(),
+                "-p".into(),
+                "$ZED_CUSTOM_RUST_PACKAGE".into(),
+            ],
+            ..TaskTemplate::default()
+        };
+        let started_at = from_millis(1_000);
+        let task_run = SerializedTaskRun {
+            id_base: "test".into(),
+            template,
+            task_context: (&task_context).into(),
+            started_at,
+            finished_at: from_millis(3_500),
+            status: TaskRunStatus::Failed {
+                exit_code: Some(101),
+            },
+        };
+        TASK_RUNS_DB
+            .save_task_run(workspace_id, task_run.clone())
+            .await
+            .unwrap();
+
+        let restored = TASK_RUNS_DB.task_runs(workspace_id).unwrap();
+        assert_eq!(restored, vec![task_run.clone()]);
+
+        let (resolved_task, restored_started_at, _, status) =
+            restored.into_iter().next().unwrap().resolve().unwrap();
+        let original_task = task_run
+            .template
+            .resolve_task("test", &task_context)
+            .unwrap();
+        assert_eq!(resolved_task, original_task);
+        assert_eq!(resolved_task.resolved.label, "check zed");
+        assert_eq!(restored_started_at, started_at);
+        assert_eq!(
+            status,
+            TaskRunStatus::Failed {
+                exit_code: Some(101)
+            }
+        );
+
+        TASK_RUNS_DB.clear_task_runs(workspace_id).await.unwrap();
+        assert!(TASK_RUNS_DB.task_runs(workspace_id).unwrap().is_empty());
+    }
+}
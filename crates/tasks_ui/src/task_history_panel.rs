@@ -0,0 +1,441 @@
+use std::{borrow::Cow, time::Duration};
+
+use collections::HashSet;
+use futures::FutureExt as _;
+use gpui::{
+    Action, App, Entity, EventEmitter, FocusHandle, Focusable, Pixels, Subscription, Task,
+    WeakEntity, actions, px,
+};
+use project::{Inventory, TaskRun, TaskRunEvent, TaskRunId, TaskRunStatus, TaskSourceKind};
+use task::{ResolvedTask, VariableName};
+use time::OffsetDateTime;
+use time_format::{TimestampFormat, format_local_timestamp};
+use ui::{ListItem, ListItemSpacing, Tooltip, prelude::*};
+use util::ResultExt as _;
+use workspace::{
+    Workspace,
+    dock::{DockPosition, Panel, PanelEvent},
+};
+
+use crate::persistence::{SerializedTaskRun, TASK_RUNS_DB};
+
+actions!(
+    task_history,
+    [
+        /// Toggles focus on the task history panel.
+        ToggleFocus,
+        /// Clears the history of finished task runs.
+        ClearHistory,
+    ]
+);
+
+pub fn register(workspace: &mut Workspace) {
+    workspace.register_action(|workspace, _: &ToggleFocus, window, cx| {
+        workspace.toggle_panel_focus::<TaskHistoryPanel>(window, cx);
+    });
+}
+
+/// Lists the task runs of the project, with their exit status and timing, and allows rerunning them.
+pub struct TaskHistoryPanel {
+    workspace: WeakEntity<Workspace>,
+    task_inventory: Option<Entity<Inventory>>,
+    expanded_runs: HashSet<TaskRunId>,
+    focus_handle: FocusHandle,
+    position: DockPosition,
+    width: Option<Pixels>,
+    _restore_task_runs: Task<()>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl TaskHistoryPanel {
+    pub fn new(workspace: &Workspace, cx: &mut Context<Self>) -> Self {
+        let task_inventory = workspace
+            .project()
+            .read(cx)
+            .task_store()
+            .read(cx)
+            .task_inventory()
+            .cloned();
+        let workspace_id = workspace.database_id();
+
+        let mut subscriptions = Vec::new();
+        let mut restore_task_runs = Task::ready(());
+        if let Some(task_inventory) = &task_inventory {
+            subscriptions.push(cx.observe(task_inventory, |_, _, cx| cx.notify()));
+            if let Some(workspace_id) = workspace_id {
+                subscriptions.push(cx.subscribe(
+                    task_inventory,
+                    move |_, task_inventory, event, cx| {
+                        if let TaskRunEvent::Finished(id) = event
+                            && let Some(task_run) = task_inventory
+                                .read(cx)
+                                .task_run(*id)
+                                .and_then(SerializedTaskRun::new)
+                        {
+                            cx.background_spawn(TASK_RUNS_DB.save_task_run(workspace_id, task_run))
+                                .detach_and_log_err(cx);
+                        }
+                    },
+                ));
+                restore_task_runs = cx.spawn(async move |this, cx| {
+                    let Some(task_runs) = cx
+                        .background_spawn(async move { TASK_RUNS_DB.task_runs(workspace_id) })
+                        .await
+                        .log_err()
+                    else {
+                        return;
+                    };
+                    this.update(cx, |this, cx| {
+                        if let Some(task_inventory) = &this.task_inventory {
+                            task_inventory.update(cx, |inventory, cx| {
+                                inventory.restore_task_runs(
+                                    task_runs.into_iter().filter_map(SerializedTaskRun::resolve),
+                                    cx,
+                                )
+                            });
+                        }
+                    })
+                    .ok();
+                });
+            }
+        }
+
+        Self {
+            workspace: workspace.weak_handle(),
+            task_inventory,
+            expanded_runs: HashSet::default(),
+            focus_handle: cx.focus_handle(),
+            position: DockPosition::Bottom,
+            width: None,
+            _restore_task_runs: restore_task_runs,
+            _subscriptions: subscriptions,
+        }
+    }
+
+    fn clear_history(&mut self, _: &ClearHistory, _: &mut Window, cx: &mut Context<Self>) {
+        let Some(task_inventory) = &self.task_inventory else {
+            return;
+        };
+        task_inventory.update(cx, |inventory, cx| inventory.clear_task_runs(cx));
+        if let Some(workspace_id) = self
+            .workspace
+            .read_with(cx, |workspace, _| workspace.database_id())
+            .ok()
+            .flatten()
+        {
+            cx.background_spawn(TASK_RUNS_DB.clear_task_runs(workspace_id))
+                .detach_and_log_err(cx);
+        }
+    }
+
+    /// Spawns the task of a previous run again, resolved exactly as it was back then.
+    fn rerun(&mut self, task_run: &TaskRun, window: &mut Window, cx: &mut Context<Self>) {
+        let resolved_task = task_run.resolved_task.clone();
+        let Some(task_source_kind) = task_run.source_kind.clone() else {
+            self.rerun_restored(resolved_task, window, cx);
+            return;
+        };
+        self.workspace
+            .update(cx, |workspace, cx| {
+                workspace.schedule_resolved_task(task_source_kind, resolved_task, false, window, cx)
+            })
+            .ok();
+    }
+
+    /// Spawns the task of a run restored from a previous session again, with the project
+    /// environment loaded anew, as it's not persisted.
+    /// These runs are not added to the recently used tasks, as their source may not exist anymore.
+    fn rerun_restored(
+        &mut self,
+        resolved_task: ResolvedTask,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(project) = self
+            .workspace
+            .read_with(cx, |workspace, _| workspace.project().clone())
+            .ok()
+        else {
+            return;
+        };
+        let project_env = match resolved_task.task_context().cwd.clone() {
+            Some(cwd) => project.update(cx, |project, cx| {
+                project.directory_environment(cwd.into(), cx)
+            }),
+            None => Task::ready(project.read(cx).cli_environment(cx)).shared(),
+        };
+        cx.spawn_in(window, async move |this, cx| {
+            let project_env = project_env.await.unwrap_or_default();
+            let Some(resolved_task) = resolved_task.with_project_env(project_env) else {
+                return;
+            };
+            this.update_in(cx, |this, window, cx| {
+                this.workspace
+                    .update(cx, |workspace, cx| {
+                        workspace.schedule_resolved_task(
+                            TaskSourceKind::UserInput,
+                            resolved_task,
+                            true,
+                            window,
+                            cx,
+                        )
+                    })
+                    .ok();
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    fn toggle_expanded(&mut self, id: TaskRunId, cx: &mut Context<Self>) {
+        if !self.expanded_runs.remove(&id) {
+            self.expanded_runs.insert(id);
+        }
+        cx.notify();
+    }
+
+    fn render_task_run(&self, task_run: &TaskRun, cx: &mut Context<Self>) -> impl IntoElement {
+        let id = task_run.id;
+        let is_expanded = self.expanded_runs.contains(&id);
+        let (status_icon, status_color, status_label) = match task_run.status {
+            TaskRunStatus::Running => (IconName::ArrowCircle, Color::Muted, "Running".into()),
+            TaskRunStatus::Succeeded => (IconName::Check, Color::Success, "Succeeded".into()),
+            TaskRunStatus::Failed {
+                exit_code: Some(exit_code),
+            } => (
+                IconName::XCircle,
+                Color::Error,
+                format!("Exited with code {exit_code}"),
+            ),
+            TaskRunStatus::Failed { exit_code: None } => {
+                (IconName::XCircle, Color::Error, "Failed".into())
+            }
+            TaskRunStatus::Cancelled => (IconName::Stop, Color::Muted, "Cancelled".into()),
+        };
+        let started_at = format_local_timestamp(
+            OffsetDateTime::from(task_run.started_at),
+            OffsetDateTime::now_utc(),
+            TimestampFormat::Relative,
+        );
+        let duration = format_duration(task_run.duration());
+        let spawn_in_terminal = &task_run.resolved_task.resolved;
+        let secret_variables = task_run
+            .resolved_task
+            .original_task()
+            .inputs
+            .iter()
+            .filter(|input| input.is_secret())
+            .map(|input| VariableName::Input(Cow::Borrowed(&input.id)).to_string())
+            .collect::<Vec<_>>();
+        let task_run = task_run.clone();
+
+        v_flex()
+            .child(
+                ListItem::new(("task-run", id.0))
+                    .spacing(ListItemSpacing::Sparse)
+                    .start_slot(
+                        Icon::new(status_icon)
+                            .size(IconSize::Small)
+                            .color(status_color),
+                    )
+                    .child(
+                        h_flex()
+                            .w_full()
+                            .gap_2()
+                            .child(Label::new(spawn_in_terminal.label.clone()).truncate())
+                            .child(
+                                Label::new(format!("{status_label} • {duration} • {started_at}"))
+                                    .size(LabelSize::Small)
+                                    .color(Color::Muted)
+                                    .truncate(),
+                            ),
+                    )
+                    .end_slot(
+                        IconButton::new(("rerun-task-run", id.0), IconName::Rerun)
+                            .icon_size(IconSize::Small)
+                            .tooltip(Tooltip::text("Rerun"))
+                            .on_click(cx.listener(move |this, _, window, cx| {
+                                this.rerun(&task_run, window, cx)
+                            })),
+                    )
+                    .on_click(cx.listener(move |this, _, _, cx| this.toggle_expanded(id, cx))),
+            )
+            .when(is_expanded, |this| {
+                let mut env = spawn_in_terminal
+                    .env
+                    .iter()
+                    .filter(|(name, _)| !secret_variables.contains(name))
+                    .collect::<Vec<_>>();
+                env.sort();
+                this.child(
+                    v_flex()
+                        .pl_8()
+                        .pb_1()
+                        .gap_0p5()
+                        .child(detail_label(
+                            format!("Command: {}", spawn_in_terminal.command_label),
+                            cx,
+                        ))
+                        .when_some(spawn_in_terminal.cwd.as_ref(), |this, cwd| {
+                            this.child(detail_label(format!("Directory: {}", cwd.display()), cx))
+                        })
+                        .children(
+                            env.into_iter()
+                                .map(|(name, value)| detail_label(format!("{name}={value}"), cx)),
+                        ),
+                )
+            })
+    }
+}
+
+fn detail_label(text: String, cx: &App) -> Label {
+    Label::new(text)
+        .size(LabelSize::Small)
+        .color(Color::Muted)
+        .buffer_font(cx)
+}
+
+fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    if seconds >= 3600 {
+        format!("{}h {}m", seconds / 3600, seconds % 3600 / 60)
+    } else if seconds >= 60 {
+        format!("{}m {}s", seconds / 60, seconds % 60)
+    } else {
+        format!("{:.1}s", duration.as_secs_f32())
+    }
+}
+
+impl Render for TaskHistoryPanel {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let task_runs = self
+            .task_inventory
+            .as_ref()
+            .map(|task_inventory| {
+                task_inventory
+                    .read(cx)
+                    .task_runs()
+                    .rev()
+                    .cloned()
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+
+        v_flex()
+            .id("task-history-panel")
+            .key_context("TaskHistoryPanel")
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::clear_history))
+            .size_full()
+            .child(
+                h_flex()
+                    .px_2()
+                    .py_1()
+                    .justify_between()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border)
+                    .child(Label::new("Task History").size(LabelSize::Small))
+                    .child(
+                        IconButton::new("clear-task-history", IconName::Trash)
+                            .icon_size(IconSize::Small)
+                            .disabled(task_runs.is_empty())
+                            .tooltip(Tooltip::for_action_title("Clear History", &ClearHistory))
+                            .on_click(|_, window, cx| {
+                                window.dispatch_action(ClearHistory.boxed_clone(), cx)
+                            }),
+                    ),
+            )
+            .map(|this| {
+                if task_runs.is_empty() {
+                    this.child(
+                        h_flex().size_full().justify_center().child(
+                            Label::new("No tasks were run yet")
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        ),
+                    )
+                } else {
+                    this.child(
+                        v_flex()
+                            .id("task-runs")
+                            .p_1()
+                            .size_full()
+                            .overflow_y_scroll()
+                            .children(
+                                task_runs
+                                    .iter()
+                                    .map(|task_run| self.render_task_run(task_run, cx)),
+                            ),
+                    )
+                }
+            })
+    }
+}
+
+impl Focusable for TaskHistoryPanel {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl EventEmitter<PanelEvent> for TaskHistoryPanel {}
+
+impl Panel for TaskHistoryPanel {
+    fn persistent_name() -> &'static str {
+        "TaskHistoryPanel"
+    }
+
+    fn position(&self, _: &Window, _: &App) -> DockPosition {
+        self.position
+    }
+
+    fn position_is_valid(&self, _: DockPosition) -> bool {
+        true
+    }
+
+    fn set_position(&mut self, position: DockPosition, _: &mut Window, cx: &mut Context<Self>) {
+        self.position = position;
+        cx.notify();
+    }
+
+    fn size(&self, _: &Window, _: &App) -> Pixels {
+        self.width.unwrap_or(px(320.))
+    }
+
+    fn set_size(&mut self, size: Option<Pixels>, _: &mut Window, cx: &mut Context<Self>) {
+        self.width = size;
+        cx.notify();
+    }
+
+    fn icon(&self, _: &Window, _: &App) -> Option<IconName> {
+        Some(IconName::ListTodo)
+    }
+
+    fn icon_tooltip(&self, _: &Window, _: &App) -> Option<&'static str> {
+        Some("Task History")
+    }
+
+    fn toggle_action(&self) -> Box<dyn Action> {
+        Box::new(ToggleFocus)
+    }
+
+    fn activation_priority(&self) -> u32 {
+        4
+    }
+
+    fn enabled(&self, _: &App) -> bool {
+        self.task_inventory.is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(Duration::from_millis(2_345)), "2.3s");
+        assert_eq!(format_duration(Duration::from_secs(125)), "2m 5s");
+        assert_eq!(format_duration(Duration::from_secs(3_725)), "1h 2m");
+    }
+}
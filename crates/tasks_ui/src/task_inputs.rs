@@ -0,0 +1,291 @@
+use std::sync::Arc;
+
+use editor::{Editor, EditorEvent, actions::SelectAll};
+use futures::channel::oneshot;
+use fuzzy::{StringMatch, StringMatchCandidate};
+use gpui::{DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, Subscription, Task};
+use picker::{Picker, PickerDelegate};
+use task::{TaskInput, TaskInputKind};
+use ui::{HighlightedLabel, ListItem, ListItemSpacing, prelude::*};
+use workspace::{ModalView, TaskInputProvider, Workspace};
+
+/// Asks for the values of task inputs with modals: a picker for `pick_string` inputs
+/// and a text prompt for `prompt_string` ones.
+pub(crate) struct TaskInputModals;
+
+impl TaskInputProvider for TaskInputModals {
+    fn prompt_inputs(
+        &self,
+        inputs: Vec<TaskInput>,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) -> Task<Option<Vec<(String, String)>>> {
+        cx.spawn_in(window, async move |workspace, cx| {
+            let mut values = Vec::with_capacity(inputs.len());
+            for input in inputs {
+                let (tx, rx) = oneshot::channel();
+                workspace
+                    .update_in(cx, |workspace, window, cx| match &input.kind {
+                        TaskInputKind::PickString { options, default } => {
+                            let delegate = TaskInputPickerDelegate::new(
+                                &input,
+                                options.clone(),
+                                default.as_deref(),
+                                tx,
+                            );
+                            workspace.toggle_modal(window, cx, |window, cx| {
+                                Picker::uniform_list(delegate, window, cx).width(rems(34.))
+                            });
+                        }
+                        TaskInputKind::PromptString { default, password } => {
+                            workspace.toggle_modal(window, cx, |window, cx| {
+                                TaskInputPrompt::new(
+                                    &input,
+                                    default.as_deref(),
+                                    *password,
+                                    tx,
+                                    window,
+                                    cx,
+                                )
+                            });
+                        }
+                    })
+                    .ok()?;
+                values.push((input.id, rx.await.ok()?));
+            }
+            Some(values)
+        })
+    }
+}
+
+fn input_description(input: &TaskInput) -> String {
+    input
+        .description
+        .clone()
+        .unwrap_or_else(|| format!("Value for `{}`", input.id))
+}
+
+struct TaskInputPickerDelegate {
+    placeholder_text: Arc<str>,
+    options: Vec<String>,
+    matches: Vec<StringMatch>,
+    selected_index: usize,
+    tx: Option<oneshot::Sender<String>>,
+}
+
+impl TaskInputPickerDelegate {
+    fn new(
+        input: &TaskInput,
+        options: Vec<String>,
+        default: Option<&str>,
+        tx: oneshot::Sender<String>,
+    ) -> Self {
+        let selected_index = default
+            .and_then(|default| options.iter().position(|option| option == default))
+            .unwrap_or(0);
+        let matches = options
+            .iter()
+            .enumerate()
+            .map(|(candidate_id, option)| StringMatch {
+                candidate_id,
+                score: 0.,
+                positions: Vec::new(),
+                string: option.clone(),
+            })
+            .collect();
+        Self {
+            placeholder_text: input_description(input).into(),
+            options,
+            matches,
+            selected_index,
+            tx: Some(tx),
+        }
+    }
+}
+
+impl PickerDelegate for TaskInputPickerDelegate {
+    type ListItem = ListItem;
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(&mut self, ix: usize, _: &mut Window, _: &mut Context<Picker<Self>>) {
+        self.selected_index = ix;
+    }
+
+    fn placeholder_text(&self, _: &mut Window, _: &mut App) -> Arc<str> {
+        self.placeholder_text.clone()
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Task<()> {
+        let candidates = self
+            .options
+            .iter()
+            .enumerate()
+            .map(|(id, option)| StringMatchCandidate::new(id, option))
+            .collect::<Vec<_>>();
+        cx.spawn_in(window, async move |picker, cx| {
+            let matches = if query.is_empty() {
+                candidates
+                    .into_iter()
+                    .map(|candidate| StringMatch {
+                        candidate_id: candidate.id,
+                        score: 0.,
+                        positions: Vec::new(),
+                        string: candidate.string,
+                    })
+                    .collect()
+            } else {
+                fuzzy::match_strings(
+                    &candidates,
+                    &query,
+                    true,
+                    true,
+                    100,
+                    &Default::default(),
+                    cx.background_executor().clone(),
+                )
+                .await
+            };
+            picker
+                .update(cx, |picker, _| {
+                    let delegate = &mut picker.delegate;
+                    delegate.matches = matches;
+                    delegate.selected_index = delegate
+                        .selected_index
+                        .min(delegate.matches.len().saturating_sub(1));
+                })
+                .ok();
+        })
+    }
+
+    fn confirm(&mut self, _: bool, _: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(hit) = self.matches.get(self.selected_index) else {
+            return;
+        };
+        if let Some(tx) = self.tx.take() {
+            tx.send(self.options[hit.candidate_id].clone()).ok();
+        }
+        cx.emit(DismissEvent);
+    }
+
+    fn dismissed(&mut self, _: &mut Window, cx: &mut Context<Picker<Self>>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _: &mut Window,
+        _: &mut Context<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let hit = self.matches.get(ix)?;
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .toggle_state(selected)
+                .child(HighlightedLabel::new(
+                    hit.string.clone(),
+                    hit.positions.clone(),
+                )),
+        )
+    }
+}
+
+/// A single line editor to type the value of a `prompt_string` task input into.
+struct TaskInputPrompt {
+    editor: Entity<Editor>,
+    description: SharedString,
+    tx: Option<oneshot::Sender<String>>,
+    _subscription: Subscription,
+}
+
+impl TaskInputPrompt {
+    fn new(
+        input: &TaskInput,
+        default: Option<&str>,
+        password: bool,
+        tx: oneshot::Sender<String>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_masked(password, cx);
+            if let Some(default) = default {
+                editor.set_text(default, window, cx);
+                editor.select_all(&SelectAll, window, cx);
+            }
+            editor
+        });
+        let subscription = cx.subscribe_in(&editor, window, |_, _, event, _, cx| {
+            if let EditorEvent::Blurred = event {
+                cx.emit(DismissEvent);
+            }
+        });
+        Self {
+            editor,
+            description: input_description(input).into(),
+            tx: Some(tx),
+            _subscription: subscription,
+        }
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, _: &mut Window, cx: &mut Context<Self>) {
+        if let Some(tx) = self.tx.take() {
+            tx.send(self.editor.read(cx).text(cx)).ok();
+        }
+        cx.emit(DismissEvent);
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, _: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(DismissEvent);
+    }
+}
+
+impl ModalView for TaskInputPrompt {}
+
+impl EventEmitter<DismissEvent> for TaskInputPrompt {}
+
+impl Focusable for TaskInputPrompt {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.editor.focus_handle(cx)
+    }
+}
+
+impl Render for TaskInputPrompt {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .w(rems(34.))
+            .elevation_2(cx)
+            .key_context("TaskInputPrompt")
+            .on_action(cx.listener(Self::confirm))
+            .on_action(cx.listener(Self::cancel))
+            .child(
+                div()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .px_2()
+                    .py_1()
+                    .child(self.editor.clone()),
+            )
+            .child(
+                h_flex()
+                    .px_2()
+                    .py_1()
+                    .child(Label::new(self.description.clone()).color(Color::Muted)),
+            )
+    }
+}
@@ -8,8 +8,12 @@ use task::{RevealTarget, TaskContext, TaskId, TaskTemplate, TaskVariables, Varia
 use workspace::Workspace;
 
 mod modal;
+mod persistence;
+mod task_history_panel;
+mod task_inputs;
 
 pub use modal::{Rerun, ShowAttachModal, Spawn, TaskOverrides, TasksModal};
+pub use task_history_panel::TaskHistoryPanel;
 
 pub fn init(cx: &mut App) {
     cx.observe_new(
@@ -93,6 +97,12 @@ pub fn init(cx: &mut App) {
         },
     )
     .detach();
+
+    cx.observe_new(|workspace: &mut Workspace, _, _| {
+        task_history_panel::register(workspace);
+        workspace.set_task_input_provider(task_inputs::TaskInputModals);
+    })
+    .detach();
 }
 
 fn spawn_task_or_modal(
@@ -0,0 +1,1443 @@
+//! Shell integration: shells that emit the OSC 133 (FinalTerm) or OSC 633 (VS Code) sequences
+//! mark where their prompts start, where the command is typed, when it's run and how it exited.
+//! These marks are turned into [`CommandBlock`]s, which the terminal view uses to move between
+//! commands and copy their output.
+
+use std::{
+    collections::VecDeque,
+    io,
+    ops::Range,
+    path::PathBuf,
+    sync::{Arc, mpsc},
+    thread,
+    time::{Duration, Instant},
+};
+
+use alacritty_terminal::{
+    Term,
+    event::{Event as AlacTermEvent, EventListener, OnResize, WindowSize},
+    grid::{BidirectionalIterator as _, Dimensions, Grid, GridCell as _},
+    index::{Column, Line, Point as AlacPoint},
+    sync::FairMutex,
+    term::{TermMode, cell::Cell},
+    tty::{self, ChildEvent, EventedPty, EventedReadWrite},
+    vte::ansi::{
+        Attr, CharsetIndex, ClearMode, CursorShape, CursorStyle, Handler, Hyperlink, KeyboardModes,
+        KeyboardModesApplyBehavior, LineClearMode, Mode, ModifyOtherKeys, NamedPrivateMode,
+        PrivateMode, Processor, Rgb, ScpCharPath, ScpUpdateMode, StandardCharset,
+        TabulationClearMode, cursor_icon::CursorIcon,
+    },
+};
+use parking_lot::Mutex;
+use polling::{Event as PollingEvent, PollMode, Poller};
+use util::ResultExt as _;
+
+use crate::ZedListener;
+
+/// The most command blocks kept for a terminal; the oldest ones are dropped first.
+const MAX_COMMAND_BLOCKS: usize = 1000;
+/// The longest OSC payload kept, which is plenty for the command lines reported by OSC 633.
+const MAX_OSC_PAYLOAD_LENGTH: usize = 64 * 1024;
+/// Synchronized updates are only looked for, so their parameters don't need to be kept in full.
+const MAX_CSI_PARAMS_LENGTH: usize = 16;
+const READ_BUFFER_SIZE: usize = 64 * 1024;
+/// How much output is parsed before the event loop gets to handle its other events.
+const MAX_READ_PER_EVENT: usize = 1024 * 1024;
+/// How long a synchronized update holds off redrawing the terminal, the same as in Alacritty.
+const SYNCHRONIZED_UPDATE_TIMEOUT: Duration = Duration::from_millis(150);
+
+/// A command run in a shell with shell integration.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CommandBlock {
+    /// The command line, as reported by the shell or as typed after the prompt.
+    pub command: Option<String>,
+    /// The working directory the command was run in, if the shell reported it.
+    pub cwd: Option<PathBuf>,
+    /// The exit code of the command, which is `None` while it runs or if the shell didn't report it.
+    pub exit_code: Option<i32>,
+    /// The line the prompt of the command starts on.
+    pub prompt_line: Line,
+    /// The lines of the output of the command, which is `None` until the command is run.
+    pub output: Option<Range<Line>>,
+}
+
+impl CommandBlock {
+    pub fn failed(&self) -> bool {
+        self.exit_code.is_some_and(|exit_code| exit_code != 0)
+    }
+
+    /// The lines from the prompt to the end of the output.
+    pub fn lines(&self) -> Range<Line> {
+        let end = self
+            .output
+            .as_ref()
+            .map_or(Line(self.prompt_line.0 + 1), |output| output.end);
+        self.prompt_line..end
+    }
+}
+
+/// A mark reported through a shell integration sequence.
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum ShellMark {
+    PromptStart,
+    CommandStart,
+    CommandExecuted,
+    CommandFinished { exit_code: Option<i32> },
+    CommandLine(String),
+    WorkingDirectory(PathBuf),
+}
+
+impl ShellMark {
+    /// Parses the payload of an OSC sequence, the part between `ESC ]` and the terminator.
+    fn parse(payload: &[u8]) -> Option<Self> {
+        let payload = String::from_utf8_lossy(payload);
+        let (code, params) = payload.split_once(';')?;
+        if code == "7" {
+            return working_directory_from_url(params).map(Self::WorkingDirectory);
+        }
+        if code != "133" && code != "633" {
+            return None;
+        }
+
+        let mut params = params.split(';');
+        match params.next()? {
+            "A" => Some(Self::PromptStart),
+            "B" => Some(Self::CommandStart),
+            "C" => Some(Self::CommandExecuted),
+            "D" => Some(Self::CommandFinished {
+                exit_code: params.next().and_then(|exit_code| exit_code.parse().ok()),
+            }),
+            "E" if code == "633" => Some(Self::CommandLine(unescape(
+                params.next().unwrap_or_default(),
+            ))),
+            "P" if code == "633" => {
+                let cwd = params.next()?.strip_prefix("Cwd=")?;
+                Some(Self::WorkingDirectory(PathBuf::from(unescape(cwd))))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Reads the path of an OSC 7 `file://host/path` URL.
+fn working_directory_from_url(url: &str) -> Option<PathBuf> {
+    let host_and_path = url.strip_prefix("file://")?;
+    let path = &host_and_path[host_and_path.find('/')?..];
+    let path = urlencoding::decode(path).ok()?;
+    Some(PathBuf::from(path.into_owned()))
+}
+
+/// Undoes the escaping of OSC 633 values, which write `\` as `\\` and other characters, such as
+/// `;`, as `\xAB`.
+fn unescape(value: &str) -> String {
+    let value = value.as_bytes();
+    let mut bytes = Vec::with_capacity(value.len());
+    let mut ix = 0;
+    while ix < value.len() {
+        if value[ix] == b'\\' {
+            if value.get(ix + 1) == Some(&b'\\') {
+                bytes.push(b'\\');
+                ix += 2;
+                continue;
+            }
+            if value.get(ix + 1) == Some(&b'x')
+                && let Some(byte) = value
+                    .get(ix + 2..ix + 4)
+                    .and_then(|hex| std::str::from_utf8(hex).ok())
+                    .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+            {
+                bytes.push(byte);
+                ix += 4;
+                continue;
+            }
+        }
+        bytes.push(value[ix]);
+        ix += 1;
+    }
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum ScanState {
+    #[default]
+    Ground,
+    Escape,
+    Csi,
+    Osc,
+    OscEscape,
+}
+
+/// A sequence the output parser handles on top of what the terminal does with it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Sequence {
+    ShellMark(ShellMark),
+    /// Begins (`CSI ? 2026 h`) or ends (`CSI ? 2026 l`) a synchronized update.
+    SynchronizedUpdate {
+        begin: bool,
+    },
+}
+
+/// Finds the sequences in the output, including the ones split across reads.
+#[derive(Default)]
+struct SequenceScanner {
+    state: ScanState,
+    payload: Vec<u8>,
+}
+
+impl SequenceScanner {
+    /// Advances over a byte of output, returning the sequence the byte terminates.
+    fn advance(&mut self, byte: u8) -> Option<Sequence> {
+        self.state = match (self.state, byte) {
+            // BEL or ST terminate the sequence.
+            (ScanState::Osc, 0x07) | (ScanState::OscEscape, b'\\') => {
+                self.state = ScanState::Ground;
+                return ShellMark::parse(&self.payload).map(Sequence::ShellMark);
+            }
+            (ScanState::Osc, 0x1b) => ScanState::OscEscape,
+            // CAN and SUB cancel the sequence.
+            (ScanState::Osc | ScanState::Csi, 0x18 | 0x1a) => ScanState::Ground,
+            (ScanState::Osc, _) => {
+                if self.payload.len() < MAX_OSC_PAYLOAD_LENGTH {
+                    self.payload.push(byte);
+                }
+                ScanState::Osc
+            }
+            (ScanState::Csi, 0x40..=0x7e) => {
+                self.state = ScanState::Ground;
+                return match (self.payload.as_slice(), byte) {
+                    (b"?2026", b'h') => Some(Sequence::SynchronizedUpdate { begin: true }),
+                    (b"?2026", b'l') => Some(Sequence::SynchronizedUpdate { begin: false }),
+                    _ => None,
+                };
+            }
+            (ScanState::Csi, 0x20..=0x3f) => {
+                if self.payload.len() < MAX_CSI_PARAMS_LENGTH {
+                    self.payload.push(byte);
+                }
+                ScanState::Csi
+            }
+            (ScanState::Escape | ScanState::OscEscape, b']') => {
+                self.payload.clear();
+                ScanState::Osc
+            }
+            (ScanState::Escape | ScanState::OscEscape, b'[') => {
+                self.payload.clear();
+                ScanState::Csi
+            }
+            (_, 0x1b) => ScanState::Escape,
+            // Other control characters are executed without ending the sequence.
+            (ScanState::Csi, _) => ScanState::Csi,
+            _ => ScanState::Ground,
+        };
+        None
+    }
+}
+
+/// Parses the output of the PTY into the terminal, recording the shell integration marks at the
+/// positions they're written at.
+struct OutputParser {
+    parser: Processor,
+    scanner: SequenceScanner,
+    /// When the synchronized update being written began.
+    synchronized_update_start: Option<Instant>,
+}
+
+impl OutputParser {
+    fn new() -> Self {
+        Self {
+            parser: Processor::new(),
+            scanner: SequenceScanner::default(),
+            synchronized_update_start: None,
+        }
+    }
+
+    fn advance(
+        &mut self,
+        term: &mut Term<ZedListener>,
+        shell_integration: &mut ShellIntegration,
+        bytes: &[u8],
+    ) {
+        let mut parsed = 0;
+        for (ix, byte) in bytes.iter().enumerate() {
+            let Some(sequence) = self.scanner.advance(*byte) else {
+                continue;
+            };
+
+            self.parse(term, shell_integration, &bytes[parsed..=ix]);
+            parsed = ix + 1;
+            match sequence {
+                Sequence::ShellMark(mark) => shell_integration.apply(mark, term),
+                Sequence::SynchronizedUpdate { begin: true } => {
+                    self.synchronized_update_start
+                        .get_or_insert_with(Instant::now);
+                }
+                Sequence::SynchronizedUpdate { begin: false } => {
+                    self.synchronized_update_start = None;
+                }
+            }
+        }
+        self.parse(term, shell_integration, &bytes[parsed..]);
+        shell_integration.update(term);
+    }
+
+    /// Parses the output into the terminal right away, even in a synchronized update.
+    ///
+    /// Alacritty's parser holds the output of a synchronized update back until it ends, which would
+    /// apply the marks written in it where the update ends instead. Only redrawing the terminal is
+    /// held off, by [`Self::holds_redraw`].
+    fn parse(
+        &mut self,
+        term: &mut Term<ZedListener>,
+        shell_integration: &mut ShellIntegration,
+        bytes: &[u8],
+    ) {
+        let mut handler = CountingHandler {
+            term,
+            shell_integration,
+        };
+        self.parser.advance(&mut handler, bytes);
+        if self.parser.sync_timeout().sync_timeout().is_some() {
+            self.parser.stop_sync(&mut handler);
+        }
+    }
+
+    /// Whether a synchronized update holds off redrawing the terminal, until it ends or times out.
+    fn holds_redraw(&self) -> bool {
+        self.synchronized_update_start
+            .is_some_and(|start| start.elapsed() < SYNCHRONIZED_UPDATE_TIMEOUT)
+    }
+}
+
+/// A command block whose lines are counted from the first line written to the terminal, so that
+/// they don't change as the output scrolls.
+#[derive(Default)]
+struct RecordedBlock {
+    prompt_line: usize,
+    command_start: Option<AlacPoint<usize>>,
+    command: Option<String>,
+    cwd: Option<PathBuf>,
+    output_start: Option<usize>,
+    output_end: Option<usize>,
+    exit_code: Option<i32>,
+}
+
+impl RecordedBlock {
+    fn is_finished(&self) -> bool {
+        self.output_end.is_some()
+    }
+}
+
+/// The command blocks of a terminal, built from the marks its shell reported.
+#[derive(Default)]
+pub(crate) struct ShellIntegration {
+    blocks: VecDeque<RecordedBlock>,
+    working_directory: Option<PathBuf>,
+    /// The line of the top of the primary screen, counted from the first line written to the
+    /// terminal, which is how many lines were scrolled off the top of the screen.
+    screen_top: usize,
+    /// The scrolling region the output set, unless it's the whole screen.
+    scroll_region: Option<Range<usize>>,
+    alt_screen: bool,
+    cursor_line: Line,
+    /// Changes whenever the blocks, or the lines they're on, do.
+    revision: usize,
+}
+
+impl ShellIntegration {
+    pub(crate) fn revision(&self) -> usize {
+        self.revision
+    }
+
+    /// Moves the blocks up along with the lines scrolled off the top of the primary screen.
+    fn scroll_into_history(&mut self, lines: usize) {
+        self.screen_top += lines;
+        if lines > 0 && !self.blocks.is_empty() {
+            self.revision += 1;
+        }
+    }
+
+    /// Catches up with the output parsed into the terminal, forgetting the blocks that are no
+    /// longer in the scrollback.
+    fn update(&mut self, term: &Term<ZedListener>) {
+        let alt_screen = term.mode().contains(TermMode::ALT_SCREEN);
+        if alt_screen != self.alt_screen {
+            self.alt_screen = alt_screen;
+            self.revision += 1;
+        }
+        if alt_screen {
+            return;
+        }
+
+        let cursor_line = term.grid().cursor.point.line;
+        if cursor_line != self.cursor_line && !self.blocks.is_empty() {
+            self.revision += 1;
+        }
+        self.cursor_line = cursor_line;
+
+        let topmost_line = self.screen_top.saturating_sub(term.history_size());
+        while let Some(block) = self.blocks.front()
+            && block.output_end.is_some_and(|end| end <= topmost_line)
+        {
+            self.blocks.pop_front();
+        }
+    }
+
+    /// Resizes the terminal, keeping the blocks on the lines they were on.
+    ///
+    /// Lines are moved between the scrollback and the screen along with the cursor, so the blocks
+    /// follow it. Rewrapping the lines to a new width only keeps the lines of the blocks around the
+    /// cursor, and while the alternate screen is shown, the primary one is resized without them.
+    pub(crate) fn resize(&mut self, term: &mut Term<ZedListener>, size: impl Dimensions) {
+        if size.screen_lines() == term.screen_lines() && size.columns() == term.columns() {
+            return;
+        }
+
+        let cursor_line = term.grid().cursor.point.line;
+        term.resize(size);
+        self.scroll_region = None;
+        if term.mode().contains(TermMode::ALT_SCREEN) {
+            return;
+        }
+        let new_cursor_line = term.grid().cursor.point.line;
+        self.screen_top = self
+            .screen_top
+            .saturating_add_signed((cursor_line.0 - new_cursor_line.0) as isize);
+        self.cursor_line = new_cursor_line;
+        self.revision += 1;
+    }
+
+    fn apply(&mut self, mark: ShellMark, term: &Term<ZedListener>) {
+        // Full screen applications don't draw prompts, and their screen has no scrollback.
+        if term.mode().contains(TermMode::ALT_SCREEN) {
+            return;
+        }
+        self.revision += 1;
+
+        let screen_top = self.screen_top;
+        let cursor = term.grid().cursor.point;
+        let cursor_line = absolute_line(screen_top, cursor.line);
+        match mark {
+            ShellMark::WorkingDirectory(path) => self.working_directory = Some(path),
+            ShellMark::PromptStart => {
+                if let Some(block) = self.blocks.back_mut()
+                    && !block.is_finished()
+                {
+                    if block.output_start.is_some() {
+                        // The shell didn't report that the command finished.
+                        block.output_end = Some(cursor_line);
+                    } else {
+                        // The prompt is drawn again without a command having been run.
+                        self.blocks.pop_back();
+                    }
+                }
+                self.push_block(cursor_line);
+            }
+            ShellMark::CommandStart | ShellMark::CommandLine(_) | ShellMark::CommandExecuted => {
+                // Some shells only mark their commands, not their prompts.
+                if self.blocks.back().is_none_or(RecordedBlock::is_finished) {
+                    self.push_block(cursor_line);
+                }
+                let working_directory = self.working_directory.clone();
+                let Some(block) = self.blocks.back_mut() else {
+                    return;
+                };
+                match mark {
+                    ShellMark::CommandStart => {
+                        block.command_start = Some(AlacPoint::new(cursor_line, cursor.column));
+                    }
+                    ShellMark::CommandLine(command) => {
+                        block.command = Some(command).filter(|command| !command.is_empty());
+                    }
+                    _ => {
+                        block.output_start = Some(cursor_line);
+                        block.cwd = working_directory;
+                        if block.command.is_none()
+                            && let Some(command_start) = block.command_start
+                        {
+                            block.command =
+                                typed_command(term, screen_top, command_start, cursor_line);
+                        }
+                    }
+                }
+            }
+            ShellMark::CommandFinished { exit_code } => {
+                let Some(block) = self.blocks.back_mut().filter(|block| !block.is_finished())
+                else {
+                    return;
+                };
+                if block.output_start.is_some() {
+                    // Output that doesn't end with a newline leaves the cursor on its last line.
+                    let end = if cursor.column == Column(0) {
+                        cursor_line
+                    } else {
+                        cursor_line + 1
+                    };
+                    block.output_end = Some(end);
+                    block.exit_code = exit_code;
+                } else {
+                    // No command was run, such as when an empty line was entered.
+                    self.blocks.pop_back();
+                }
+            }
+        }
+    }
+
+    fn push_block(&mut self, prompt_line: usize) {
+        if self.blocks.len() == MAX_COMMAND_BLOCKS {
+            self.blocks.pop_front();
+        }
+        self.blocks.push_back(RecordedBlock {
+            prompt_line,
+            ..Default::default()
+        });
+    }
+
+    /// Forgets the blocks that are about to be cleared from the terminal along with its scrollback,
+    /// keeping the current one, which is moved to the top of the screen.
+    pub(crate) fn clear(&mut self, term: &Term<ZedListener>) {
+        if term.mode().contains(TermMode::ALT_SCREEN) {
+            return;
+        }
+        self.revision += 1;
+
+        let top_line = self.screen_top;
+        let current_block = self.blocks.pop_back().filter(|block| !block.is_finished());
+        self.blocks.clear();
+        if let Some(mut block) = current_block {
+            block.prompt_line = top_line;
+            block.command_start = block
+                .command_start
+                .map(|start| AlacPoint::new(top_line, start.column));
+            block.output_start = block.output_start.map(|_| top_line);
+            self.blocks.push_back(block);
+        }
+    }
+
+    /// Returns the command blocks that are still in the scrollback, oldest first, with their lines
+    /// in the grid of the terminal.
+    pub(crate) fn command_blocks(&self, term: &Term<ZedListener>) -> Vec<CommandBlock> {
+        if term.mode().contains(TermMode::ALT_SCREEN) {
+            return Vec::new();
+        }
+
+        let screen_top = self.screen_top;
+        let topmost_line = term.topmost_line();
+        let cursor_line = term.grid().cursor.point.line;
+        self.blocks
+            .iter()
+            .map(|block| {
+                let output = block.output_start.map(|start| {
+                    let end = block
+                        .output_end
+                        .map_or(Line(cursor_line.0 + 1), |end| grid_line(screen_top, end));
+                    grid_line(screen_top, start)..end
+                });
+                CommandBlock {
+                    command: block.command.clone(),
+                    cwd: block.cwd.clone(),
+                    exit_code: block.exit_code,
+                    prompt_line: grid_line(screen_top, block.prompt_line),
+                    output,
+                }
+            })
+            .filter(|block| block.lines().end > topmost_line)
+            .collect()
+    }
+}
+
+/// Counts the line from the first line written to the terminal.
+fn absolute_line(screen_top: usize, line: Line) -> usize {
+    screen_top.saturating_add_signed(line.0 as isize)
+}
+
+fn grid_line(screen_top: usize, absolute_line: usize) -> Line {
+    Line((absolute_line as i64 - screen_top as i64) as i32)
+}
+
+/// Reads the command typed after the prompt, for the shells that don't report the command line.
+fn typed_command(
+    term: &Term<ZedListener>,
+    screen_top: usize,
+    command_start: AlacPoint<usize>,
+    executed_line: usize,
+) -> Option<String> {
+    // The command was run when enter was pressed, which moved the cursor to the next line.
+    let end_line = executed_line.saturating_sub(1).max(command_start.line);
+    let start = AlacPoint::new(
+        grid_line(screen_top, command_start.line).max(term.topmost_line()),
+        command_start.column,
+    );
+    let end = AlacPoint::new(grid_line(screen_top, end_line), term.last_column());
+    let command = term.bounds_to_string(start, end);
+    let command = command.trim();
+    (!command.is_empty()).then(|| command.to_string())
+}
+
+/// Passes the output through to the terminal, counting the lines it scrolls off the top of the
+/// primary screen into the scrollback, so that the blocks keep to the lines they're on.
+struct CountingHandler<'a> {
+    term: &'a mut Term<ZedListener>,
+    shell_integration: &'a mut ShellIntegration,
+}
+
+impl CountingHandler<'_> {
+    fn scroll_region(&self) -> Range<usize> {
+        self.shell_integration
+            .scroll_region
+            .clone()
+            .unwrap_or(0..self.term.screen_lines())
+    }
+
+    /// Whether the lines scrolled off the top of the scrolling region go to the scrollback, which
+    /// only the primary screen has.
+    fn scrolls_into_history(&self) -> bool {
+        self.scroll_region().start == 0 && !self.term.mode().contains(TermMode::ALT_SCREEN)
+    }
+
+    /// Whether moving the cursor to the next line scrolls a line into the scrollback.
+    fn linefeed_scrolls(&self) -> bool {
+        self.scrolls_into_history()
+            && self.term.grid().cursor.point.line.0 + 1 == self.scroll_region().end as i32
+    }
+}
+
+/// Passes the handler methods that don't scroll the screen through to the terminal.
+macro_rules! delegate_to_term {
+    ($(fn $name:ident(&mut self $(, $arg:ident: $ty:ty)*);)*) => {
+        $(
+            fn $name(&mut self $(, $arg: $ty)*) {
+                Handler::$name(&mut *self.term $(, $arg)*)
+            }
+        )*
+    };
+}
+
+impl Handler for CountingHandler<'_> {
+    fn input(&mut self, c: char) {
+        if !self.linefeed_scrolls() {
+            Handler::input(&mut *self.term, c);
+            return;
+        }
+
+        let cursor = &self.term.grid().cursor;
+        let (point, needs_wrap) = (cursor.point, cursor.input_needs_wrap);
+        Handler::input(&mut *self.term, c);
+        // A character that doesn't fit on the line wraps to the start of the next one, and
+        // characters that don't take up any cells leave the cursor where it is.
+        let cursor = &self.term.grid().cursor;
+        let wrapped = if needs_wrap {
+            cursor.point != point || !cursor.input_needs_wrap
+        } else {
+            point.column == self.term.last_column() && cursor.point.column != point.column
+        };
+        if wrapped {
+            self.shell_integration.scroll_into_history(1);
+        }
+    }
+
+    fn linefeed(&mut self) {
+        if self.linefeed_scrolls() {
+            self.shell_integration.scroll_into_history(1);
+        }
+        Handler::linefeed(&mut *self.term);
+    }
+
+    fn newline(&mut self) {
+        if self.linefeed_scrolls() {
+            self.shell_integration.scroll_into_history(1);
+        }
+        Handler::newline(&mut *self.term);
+    }
+
+    fn scroll_up(&mut self, lines: usize) {
+        if self.scrolls_into_history() {
+            let lines = lines.min(self.scroll_region().len());
+            self.shell_integration.scroll_into_history(lines);
+        }
+        Handler::scroll_up(&mut *self.term, lines);
+    }
+
+    fn delete_lines(&mut self, lines: usize) {
+        // Deleting lines scrolls the ones below the cursor up, into the scrollback from the top.
+        if self.scrolls_into_history() && self.term.grid().cursor.point.line == Line(0) {
+            let lines = lines.min(self.scroll_region().len());
+            self.shell_integration.scroll_into_history(lines);
+        }
+        Handler::delete_lines(&mut *self.term, lines);
+    }
+
+    fn clear_screen(&mut self, mode: ClearMode) {
+        if matches!(mode, ClearMode::All) && !self.term.mode().contains(TermMode::ALT_SCREEN) {
+            let lines = cleared_lines(self.term.grid());
+            self.shell_integration.scroll_into_history(lines);
+        }
+        Handler::clear_screen(&mut *self.term, mode);
+    }
+
+    fn reset_state(&mut self) {
+        // Everything written to the terminal is cleared, along with the blocks on it.
+        if !self.shell_integration.blocks.is_empty() {
+            self.shell_integration.blocks.clear();
+            self.shell_integration.revision += 1;
+        }
+        self.shell_integration.scroll_region = None;
+        Handler::reset_state(&mut *self.term);
+    }
+
+    fn set_scrolling_region(&mut self, top: usize, bottom: Option<usize>) {
+        let screen_lines = self.term.screen_lines();
+        let end = bottom.unwrap_or(screen_lines);
+        // Invalid scrolling regions are ignored.
+        if top < end {
+            let region = top.saturating_sub(1).min(screen_lines)..end.min(screen_lines);
+            self.shell_integration.scroll_region =
+                Some(region).filter(|region| *region != (0..screen_lines));
+        }
+        Handler::set_scrolling_region(&mut *self.term, top, bottom);
+    }
+
+    fn set_private_mode(&mut self, mode: PrivateMode) {
+        // Switching the number of columns resets the scrolling region.
+        if mode == PrivateMode::Named(NamedPrivateMode::ColumnMode) {
+            self.shell_integration.scroll_region = None;
+        }
+        Handler::set_private_mode(&mut *self.term, mode);
+    }
+
+    fn unset_private_mode(&mut self, mode: PrivateMode) {
+        if mode == PrivateMode::Named(NamedPrivateMode::ColumnMode) {
+            self.shell_integration.scroll_region = None;
+        }
+        Handler::unset_private_mode(&mut *self.term, mode);
+    }
+
+    delegate_to_term! {
+        fn set_title(&mut self, title: Option<String>);
+        fn set_cursor_style(&mut self, style: Option<CursorStyle>);
+        fn set_cursor_shape(&mut self, shape: CursorShape);
+        fn goto(&mut self, line: i32, column: usize);
+        fn goto_line(&mut self, line: i32);
+        fn goto_col(&mut self, column: usize);
+        fn insert_blank(&mut self, count: usize);
+        fn move_up(&mut self, lines: usize);
+        fn move_down(&mut self, lines: usize);
+        fn identify_terminal(&mut self, intermediate: Option<char>);
+        fn device_status(&mut self, arg: usize);
+        fn move_forward(&mut self, columns: usize);
+        fn move_backward(&mut self, columns: usize);
+        fn move_down_and_cr(&mut self, lines: usize);
+        fn move_up_and_cr(&mut self, lines: usize);
+        fn put_tab(&mut self, count: u16);
+        fn backspace(&mut self);
+        fn carriage_return(&mut self);
+        fn bell(&mut self);
+        fn substitute(&mut self);
+        fn set_horizontal_tabstop(&mut self);
+        fn scroll_down(&mut self, lines: usize);
+        fn insert_blank_lines(&mut self, lines: usize);
+        fn erase_chars(&mut self, count: usize);
+        fn delete_chars(&mut self, count: usize);
+        fn move_backward_tabs(&mut self, count: u16);
+        fn move_forward_tabs(&mut self, count: u16);
+        fn save_cursor_position(&mut self);
+        fn restore_cursor_position(&mut self);
+        fn clear_line(&mut self, mode: LineClearMode);
+        fn clear_tabs(&mut self, mode: TabulationClearMode);
+        fn set_tabs(&mut self, interval: u16);
+        fn reverse_index(&mut self);
+        fn terminal_attribute(&mut self, attr: Attr);
+        fn set_mode(&mut self, mode: Mode);
+        fn unset_mode(&mut self, mode: Mode);
+        fn report_mode(&mut self, mode: Mode);
+        fn report_private_mode(&mut self, mode: PrivateMode);
+        fn set_keypad_application_mode(&mut self);
+        fn unset_keypad_application_mode(&mut self);
+        fn set_active_charset(&mut self, index: CharsetIndex);
+        fn configure_charset(&mut self, index: CharsetIndex, charset: StandardCharset);
+        fn set_color(&mut self, index: usize, color: Rgb);
+        fn dynamic_color_sequence(&mut self, prefix: String, index: usize, terminator: &str);
+        fn reset_color(&mut self, index: usize);
+        fn clipboard_store(&mut self, clipboard: u8, base64: &[u8]);
+        fn clipboard_load(&mut self, clipboard: u8, terminator: &str);
+        fn decaln(&mut self);
+        fn push_title(&mut self);
+        fn pop_title(&mut self);
+        fn text_area_size_pixels(&mut self);
+        fn text_area_size_chars(&mut self);
+        fn set_hyperlink(&mut self, hyperlink: Option<Hyperlink>);
+        fn set_mouse_cursor_icon(&mut self, icon: CursorIcon);
+        fn report_keyboard_mode(&mut self);
+        fn push_keyboard_mode(&mut self, mode: KeyboardModes);
+        fn pop_keyboard_modes(&mut self, count: u16);
+        fn set_keyboard_mode(&mut self, mode: KeyboardModes, behavior: KeyboardModesApplyBehavior);
+        fn set_modify_other_keys(&mut self, mode: ModifyOtherKeys);
+        fn report_modify_other_keys(&mut self);
+        fn set_scp(&mut self, char_path: ScpCharPath, update_mode: ScpUpdateMode);
+    }
+}
+
+/// The lines that clearing the primary screen scrolls into the scrollback, which are the ones down
+/// to the last line that isn't empty, found the way Alacritty does.
+fn cleared_lines(grid: &Grid<Cell>) -> usize {
+    let end = AlacPoint::new(Line(grid.screen_lines() as i32 - 1), Column(grid.columns()));
+    let mut cells = grid.iter_from(end);
+    while let Some(cell) = cells.prev() {
+        if !cell.is_empty() || cell.point.line < 0 {
+            break;
+        }
+    }
+    (cells.point().line.0 + 1) as usize
+}
+
+/// Wraps the PTY so that its output is parsed as soon as it's read, which records the shell
+/// integration marks at the positions they were written at.
+///
+/// The marks have to be applied at the bytes they're written at, and the lines the output scrolls
+/// into the scrollback counted as it's parsed. Alacritty's event loop parses the output in batches
+/// of its choosing with its own parser, so its event loop still polls the PTY and writes to it, but
+/// reading from the PTY parses everything there is and hands nothing back to it. Synchronized
+/// updates and the limit on how much is read at once are handled the way its event loop does.
+pub(crate) struct ShellIntegrationPty {
+    pty: tty::Pty,
+    output: PtyOutput,
+}
+
+impl ShellIntegrationPty {
+    pub(crate) fn new(
+        pty: tty::Pty,
+        term: Arc<FairMutex<Term<ZedListener>>>,
+        listener: ZedListener,
+        shell_integration: Arc<Mutex<ShellIntegration>>,
+    ) -> Self {
+        Self {
+            pty,
+            output: PtyOutput::new(term, listener, shell_integration),
+        }
+    }
+}
+
+/// Reads the output of the PTY into its terminal.
+struct PtyOutput {
+    term: Arc<FairMutex<Term<ZedListener>>>,
+    listener: ZedListener,
+    shell_integration: Arc<Mutex<ShellIntegration>>,
+    parser: OutputParser,
+    buffer: Box<[u8]>,
+    /// Wakes the terminal up once a synchronized update times out, since no more output may come.
+    redraw_timer: Option<mpsc::Sender<Instant>>,
+    /// When the synchronized update the redraw timer was last set for began.
+    timed_update_start: Option<Instant>,
+}
+
+impl PtyOutput {
+    fn new(
+        term: Arc<FairMutex<Term<ZedListener>>>,
+        listener: ZedListener,
+        shell_integration: Arc<Mutex<ShellIntegration>>,
+    ) -> Self {
+        Self {
+            term,
+            listener,
+            shell_integration,
+            parser: OutputParser::new(),
+            buffer: vec![0; READ_BUFFER_SIZE].into_boxed_slice(),
+            redraw_timer: None,
+            timed_update_start: None,
+        }
+    }
+
+    /// Parses what can be read, up to [`MAX_READ_PER_EVENT`], leaving the rest to be read once the
+    /// event loop polls the PTY again.
+    fn read_from(&mut self, reader: &mut impl io::Read) -> io::Result<usize> {
+        let mut parsed = 0;
+        let result = loop {
+            match reader.read(&mut self.buffer) {
+                Ok(0) => break Ok(0),
+                Ok(count) => {
+                    // The event loop holds the lease of the terminal while it reads from the PTY, so
+                    // locking it fairly would wait on the event loop itself.
+                    let mut term = self.term.lock_unfair();
+                    self.parser.advance(
+                        &mut term,
+                        &mut self.shell_integration.lock(),
+                        &self.buffer[..count],
+                    );
+                    parsed += count;
+                    if parsed >= MAX_READ_PER_EVENT {
+                        break Ok(0);
+                    }
+                }
+                Err(error) => break Err(error),
+            }
+        };
+
+        if parsed > 0 {
+            if self.parser.holds_redraw() {
+                self.set_redraw_timer();
+            } else {
+                self.listener.send_event(AlacTermEvent::Wakeup);
+            }
+        }
+        result
+    }
+
+    /// Sets the redraw timer for when the synchronized update being written times out.
+    fn set_redraw_timer(&mut self) {
+        let Some(start) = self.parser.synchronized_update_start else {
+            return;
+        };
+        if self.timed_update_start == Some(start) {
+            return;
+        }
+        self.timed_update_start = Some(start);
+        let listener = &self.listener;
+        let redraw_timer = self
+            .redraw_timer
+            .get_or_insert_with(|| spawn_redraw_timer(listener.clone()));
+        redraw_timer.send(start + SYNCHRONIZED_UPDATE_TIMEOUT).ok();
+    }
+}
+
+/// Wakes the terminal up at the deadlines it's sent, until the sender is dropped along with the
+/// PTY. A deadline replaces the one that's pending.
+fn spawn_redraw_timer(listener: ZedListener) -> mpsc::Sender<Instant> {
+    let (deadlines_tx, deadlines_rx) = mpsc::channel::<Instant>();
+    thread::Builder::new()
+        .name("terminal redraw timer".to_string())
+        .spawn(move || {
+            let mut deadline = None;
+            loop {
+                let next_deadline = match deadline {
+                    Some(deadline) => deadlines_rx
+                        .recv_timeout(deadline.saturating_duration_since(Instant::now())),
+                    None => deadlines_rx
+                        .recv()
+                        .map_err(|_| mpsc::RecvTimeoutError::Disconnected),
+                };
+                match next_deadline {
+                    Ok(next_deadline) => deadline = Some(next_deadline),
+                    Err(mpsc::RecvTimeoutError::Timeout) => {
+                        deadline = None;
+                        listener.send_event(AlacTermEvent::Wakeup);
+                    }
+                    Err(mpsc::RecvTimeoutError::Disconnected) => break,
+                }
+            }
+        })
+        .log_err();
+    deadlines_tx
+}
+
+impl io::Read for ShellIntegrationPty {
+    fn read(&mut self, _: &mut [u8]) -> io::Result<usize> {
+        self.output.read_from(self.pty.reader())
+    }
+}
+
+impl EventedReadWrite for ShellIntegrationPty {
+    type Reader = Self;
+    type Writer = <tty::Pty as EventedReadWrite>::Writer;
+
+    unsafe fn register(
+        &mut self,
+        poll: &Arc<Poller>,
+        interest: PollingEvent,
+        mode: PollMode,
+    ) -> io::Result<()> {
+        unsafe { self.pty.register(poll, interest, mode) }
+    }
+
+    fn reregister(
+        &mut self,
+        poll: &Arc<Poller>,
+        interest: PollingEvent,
+        mode: PollMode,
+    ) -> io::Result<()> {
+        self.pty.reregister(poll, interest, mode)
+    }
+
+    fn deregister(&mut self, poll: &Arc<Poller>) -> io::Result<()> {
+        self.pty.deregister(poll)
+    }
+
+    fn reader(&mut self) -> &mut Self::Reader {
+        self
+    }
+
+    fn writer(&mut self) -> &mut Self::Writer {
+        self.pty.writer()
+    }
+}
+
+impl EventedPty for ShellIntegrationPty {
+    fn next_child_event(&mut self) -> Option<ChildEvent> {
+        self.pty.next_child_event()
+    }
+}
+
+impl OnResize for ShellIntegrationPty {
+    fn on_resize(&mut self, window_size: WindowSize) {
+        self.pty.on_resize(window_size)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TerminalBounds;
+    use alacritty_terminal::{
+        grid::Scroll,
+        term::{Config, test::TermSize},
+    };
+    use futures::channel::mpsc::{UnboundedReceiver, unbounded};
+
+    const SCROLLING_HISTORY: usize = 10_000;
+
+    fn new_term(scrolling_history: usize, listener: ZedListener) -> Term<ZedListener> {
+        let config = Config {
+            scrolling_history,
+            ..Config::default()
+        };
+        Term::new(config, &TerminalBounds::default(), listener)
+    }
+
+    fn new_pty_output(scrolling_history: usize) -> (PtyOutput, UnboundedReceiver<AlacTermEvent>) {
+        let (events_tx, events_rx) = unbounded();
+        let term = new_term(scrolling_history, ZedListener(events_tx.clone()));
+        let output = PtyOutput::new(
+            Arc::new(FairMutex::new(term)),
+            ZedListener(events_tx),
+            Arc::new(Mutex::new(ShellIntegration::default())),
+        );
+        (output, events_rx)
+    }
+
+    fn parse(term: &mut Term<ZedListener>, shell_integration: &mut ShellIntegration, output: &str) {
+        let mut parser = OutputParser::new();
+        // Split the output to check that the sequences are found across reads.
+        for chunk in output.as_bytes().chunks(3) {
+            parser.advance(term, shell_integration, chunk);
+        }
+    }
+
+    fn run(output: &str) -> Vec<CommandBlock> {
+        let (events_tx, _events_rx) = unbounded();
+        let mut term = new_term(SCROLLING_HISTORY, ZedListener(events_tx));
+        let mut shell_integration = ShellIntegration::default();
+        parse(&mut term, &mut shell_integration, output);
+        shell_integration.command_blocks(&term)
+    }
+
+    fn line_text(term: &Term<ZedListener>, line: Line) -> String {
+        let text = term.bounds_to_string(
+            AlacPoint::new(line, Column(0)),
+            AlacPoint::new(line, term.last_column()),
+        );
+        text.trim_end().to_string()
+    }
+
+    fn woke_up(events_rx: &mut UnboundedReceiver<AlacTermEvent>) -> bool {
+        std::iter::from_fn(|| events_rx.try_next().ok().flatten())
+            .filter(|event| matches!(event, AlacTermEvent::Wakeup))
+            .count()
+            > 0
+    }
+
+    #[test]
+    fn test_parse_shell_marks() {
+        assert_eq!(ShellMark::parse(b"133;A"), Some(ShellMark::PromptStart));
+        assert_eq!(
+            ShellMark::parse(b"133;A;cl=m"),
+            Some(ShellMark::PromptStart)
+        );
+        assert_eq!(ShellMark::parse(b"633;B"), Some(ShellMark::CommandStart));
+        assert_eq!(
+            ShellMark::parse(b"133;D;127"),
+            Some(ShellMark::CommandFinished {
+                exit_code: Some(127)
+            })
+        );
+        assert_eq!(
+            ShellMark::parse(b"133;D"),
+            Some(ShellMark::CommandFinished { exit_code: None })
+        );
+        assert_eq!(
+            ShellMark::parse(b"633;E;echo a\\x3bb \\\\;nonce"),
+            Some(ShellMark::CommandLine("echo a;b \\".to_string()))
+        );
+        assert_eq!(
+            ShellMark::parse(b"633;P;Cwd=/home/user"),
+            Some(ShellMark::WorkingDirectory(PathBuf::from("/home/user")))
+        );
+        assert_eq!(
+            ShellMark::parse(b"7;file://host/home/user/my%20project"),
+            Some(ShellMark::WorkingDirectory(PathBuf::from(
+                "/home/user/my project"
+            )))
+        );
+        assert_eq!(ShellMark::parse(b"133;E;echo"), None);
+        assert_eq!(ShellMark::parse(b"0;title"), None);
+    }
+
+    #[test]
+    fn test_command_blocks() {
+        let blocks = run(concat!(
+            "\x1b]7;file://host/tmp\x07",
+            "\x1b]133;A\x07$ \x1b]133;B\x07ls\r\n\x1b]133;C\x07",
+            "a\r\nb\r\n",
+            "\x1b]133;D;0\x1b\\",
+            "\x1b]133;A\x07$ \x1b]133;B\x07",
+            "\x1b]633;E;false\x07false\r\n\x1b]133;C\x07",
+            "\x1b]133;D;1\x07",
+            "\x1b]133;A\x07$ \x1b]133;B\x07",
+        ));
+        assert_eq!(
+            blocks,
+            vec![
+                CommandBlock {
+                    command: Some("ls".to_string()),
+                    cwd: Some(PathBuf::from("/tmp")),
+                    exit_code: Some(0),
+                    prompt_line: Line(0),
+                    output: Some(Line(1)..Line(3)),
+                },
+                CommandBlock {
+                    command: Some("false".to_string()),
+                    cwd: Some(PathBuf::from("/tmp")),
+                    exit_code: Some(1),
+                    prompt_line: Line(3),
+                    output: Some(Line(4)..Line(4)),
+                },
+                CommandBlock {
+                    command: None,
+                    cwd: None,
+                    exit_code: None,
+                    prompt_line: Line(4),
+                    output: None,
+                },
+            ]
+        );
+        assert!(blocks[1].failed());
+    }
+
+    #[test]
+    fn test_prompt_without_command() {
+        let blocks = run(concat!(
+            "\x1b]133;A\x07$ \x1b]133;B\x07\r\n\x1b]133;D;0\x07",
+            "\x1b]133;A\x07$ \x1b]133;B\x07",
+        ));
+        assert_eq!(blocks.len(), 1);
+        assert_eq!(blocks[0].prompt_line, Line(1));
+    }
+
+    #[test]
+    fn test_command_blocks_after_scrollback_overflow() {
+        let (events_tx, _events_rx) = unbounded();
+        let mut term = new_term(20, ZedListener(events_tx));
+        let mut shell_integration = ShellIntegration::default();
+        let mut output = String::new();
+        for ix in 0..30 {
+            output.push_str(&format!(
+                "\x1b]133;A\x07$ \x1b]133;B\x07echo {ix}\r\n\x1b]133;C\x07{ix}\r\n\x1b]133;D;{}\x07",
+                ix % 2
+            ));
+        }
+        output.push_str("\x1b]133;A\x07$ \x1b]133;B\x07");
+        parse(&mut term, &mut shell_integration, &output);
+
+        // The output no longer fits in the scrollback, so the oldest commands were dropped from it.
+        let blocks = shell_integration.command_blocks(&term);
+        assert!(blocks.len() < 30);
+        assert_eq!(blocks.last().unwrap().prompt_line, Line(5));
+        for block in &blocks[..blocks.len() - 1] {
+            let command = block.command.as_deref().unwrap();
+            let ix = command
+                .strip_prefix("echo ")
+                .unwrap()
+                .parse::<i32>()
+                .unwrap();
+            assert_eq!(block.exit_code, Some(ix % 2));
+            if block.prompt_line >= term.topmost_line() {
+                assert_eq!(line_text(&term, block.prompt_line), format!("$ {command}"));
+            }
+            let output = block.output.clone().unwrap();
+            assert_eq!(output.end.0 - output.start.0, 1);
+            assert_eq!(line_text(&term, output.start), ix.to_string());
+        }
+        assert_eq!(blocks[blocks.len() - 2].command.as_deref(), Some("echo 29"));
+        assert_eq!(term.grid().display_offset(), 0);
+    }
+
+    #[test]
+    fn test_scrolled_up_viewport_follows_output() {
+        let (events_tx, _events_rx) = unbounded();
+        let mut term = new_term(SCROLLING_HISTORY, ZedListener(events_tx));
+        let mut shell_integration = ShellIntegration::default();
+        parse(&mut term, &mut shell_integration, &"line\r\n".repeat(20));
+
+        term.grid_mut().scroll_display(Scroll::Delta(2));
+        parse(&mut term, &mut shell_integration, "more\r\nlines\r\n");
+        assert_eq!(term.grid().display_offset(), 4);
+    }
+
+    #[test]
+    fn test_command_blocks_with_wrapped_lines() {
+        let (events_tx, _events_rx) = unbounded();
+        let mut term = new_term(SCROLLING_HISTORY, ZedListener(events_tx));
+        let mut shell_integration = ShellIntegration::default();
+        let mut output = "\x1b]133;A\x07$ \x1b]133;B\x07cat\r\n\x1b]133;C\x07".to_string();
+        // Lines longer than the terminal is wide wrap, and so do wide characters that don't fit.
+        output.push_str(&format!("{}\r\n", "x".repeat(250)).repeat(4));
+        output.push_str(&format!("{}中\r\n", "x".repeat(99)));
+        output.push_str("\x1b]133;D;0\x07\x1b]133;A\x07$ ");
+        parse(&mut term, &mut shell_integration, &output);
+
+        let blocks = shell_integration.command_blocks(&term);
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(blocks[0].prompt_line, Line(-10));
+        assert_eq!(line_text(&term, blocks[0].prompt_line), "$ cat");
+        assert_eq!(blocks[0].output, Some(Line(-9)..Line(5)));
+        assert_eq!(line_text(&term, Line(4)), "中");
+        assert_eq!(blocks[1].prompt_line, Line(5));
+    }
+
+    #[test]
+    fn test_command_blocks_with_scrolling_region() {
+        let (events_tx, _events_rx) = unbounded();
+        let mut term = new_term(SCROLLING_HISTORY, ZedListener(events_tx));
+        let mut shell_integration = ShellIntegration::default();
+        parse(
+            &mut term,
+            &mut shell_integration,
+            concat!(
+                "\x1b]133;A\x07$ \x1b]133;B\x07top\r\n\x1b]133;C\x07",
+                // The lines scrolled off the top of a scrolling region below the first line are
+                // dropped instead of going to the scrollback.
+                "\x1b[2;6r\x1b[6;1Ha\r\nb\r\nc\r\n",
+                "\x1b[r\x1b[6;1H\x1b]133;D;0\x07",
+                "\x1b]133;A\x07$ ",
+            ),
+        );
+        let blocks = shell_integration.command_blocks(&term);
+        assert_eq!(term.history_size(), 0);
+        assert_eq!(blocks[0].prompt_line, Line(0));
+        assert_eq!(line_text(&term, Line(0)), "$ top");
+        assert_eq!(blocks[0].output, Some(Line(1)..Line(5)));
+        assert_eq!(blocks[1].prompt_line, Line(5));
+
+        parse(&mut term, &mut shell_integration, "\r\n\r\n\r\n");
+        let blocks = shell_integration.command_blocks(&term);
+        assert_eq!(term.history_size(), 3);
+        assert_eq!(blocks[0].prompt_line, Line(-3));
+        assert_eq!(line_text(&term, Line(-3)), "$ top");
+        assert_eq!(blocks[1].prompt_line, Line(2));
+    }
+
+    #[test]
+    fn test_command_blocks_after_clearing_screen() {
+        let (events_tx, _events_rx) = unbounded();
+        let mut term = new_term(SCROLLING_HISTORY, ZedListener(events_tx));
+        let mut shell_integration = ShellIntegration::default();
+        parse(
+            &mut term,
+            &mut shell_integration,
+            concat!(
+                "\x1b]133;A\x07$ \x1b]133;B\x07ls\r\n\x1b]133;C\x07a\r\n\x1b]133;D;0\x07",
+                "\x1b]133;A\x07$ \x1b]133;B\x07clear\r\n\x1b]133;C\x07\x1b[H\x1b[2J",
+                "\x1b]133;D;0\x07\x1b]133;A\x07$ ",
+            ),
+        );
+
+        // Clearing the screen scrolls the lines that aren't empty into the scrollback.
+        let blocks = shell_integration.command_blocks(&term);
+        assert_eq!(blocks.len(), 3);
+        assert_eq!(blocks[0].prompt_line, Line(-3));
+        assert_eq!(line_text(&term, Line(-3)), "$ ls");
+        assert_eq!(line_text(&term, Line(-2)), "a");
+        assert_eq!(blocks[1].prompt_line, Line(-1));
+        assert_eq!(line_text(&term, Line(-1)), "$ clear");
+        assert_eq!(blocks[1].output, Some(Line(0)..Line(0)));
+        assert_eq!(blocks[2].prompt_line, Line(0));
+    }
+
+    #[test]
+    fn test_command_blocks_on_alternate_screen() {
+        let (events_tx, _events_rx) = unbounded();
+        let mut term = new_term(SCROLLING_HISTORY, ZedListener(events_tx));
+        let mut shell_integration = ShellIntegration::default();
+        parse(
+            &mut term,
+            &mut shell_integration,
+            "\x1b]133;A\x07$ \x1b]133;B\x07vim\r\n\x1b]133;C\x07\x1b[?1049h",
+        );
+        let revision = shell_integration.revision();
+        parse(&mut term, &mut shell_integration, &"line\r\n".repeat(20));
+        assert!(shell_integration.command_blocks(&term).is_empty());
+
+        // The lines written to the alternate screen don't go to the scrollback.
+        parse(
+            &mut term,
+            &mut shell_integration,
+            "\x1b[?1049l\x1b]133;D;0\x07\x1b]133;A\x07$ ",
+        );
+        assert_ne!(shell_integration.revision(), revision);
+        let blocks = shell_integration.command_blocks(&term);
+        assert_eq!(term.history_size(), 0);
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(blocks[0].prompt_line, Line(0));
+        assert_eq!(blocks[0].output, Some(Line(1)..Line(1)));
+        assert_eq!(blocks[1].prompt_line, Line(1));
+    }
+
+    #[test]
+    fn test_command_blocks_after_resize() {
+        let (events_tx, _events_rx) = unbounded();
+        let mut term = new_term(SCROLLING_HISTORY, ZedListener(events_tx));
+        let mut shell_integration = ShellIntegration::default();
+        let mut output = String::new();
+        for ix in 0..4 {
+            output.push_str(&format!(
+                "\x1b]133;A\x07$ \x1b]133;B\x07echo {ix}\r\n\x1b]133;C\x07{ix}\r\n\x1b]133;D;0\x07"
+            ));
+        }
+        output.push_str("\x1b]133;A\x07$ ");
+        parse(&mut term, &mut shell_integration, &output);
+
+        let assert_blocks = |term: &Term<ZedListener>, shell_integration: &ShellIntegration| {
+            let blocks = shell_integration.command_blocks(term);
+            assert_eq!(blocks.len(), 5);
+            for (ix, block) in blocks[..4].iter().enumerate() {
+                assert_eq!(line_text(term, block.prompt_line), format!("$ echo {ix}"));
+                let output = block.output.clone().unwrap();
+                assert_eq!(line_text(term, output.start), ix.to_string());
+            }
+            assert_eq!(line_text(term, blocks[4].prompt_line), "$");
+        };
+        assert_blocks(&term, &shell_integration);
+
+        // Shrinking the terminal moves the lines above the cursor to the scrollback, and growing it
+        // moves them back.
+        let revision = shell_integration.revision();
+        shell_integration.resize(&mut term, TermSize::new(100, 3));
+        assert_ne!(shell_integration.revision(), revision);
+        assert_eq!(term.grid().cursor.point.line, Line(2));
+        assert_blocks(&term, &shell_integration);
+
+        shell_integration.resize(&mut term, TermSize::new(100, 12));
+        assert_eq!(term.history_size(), 0);
+        assert_blocks(&term, &shell_integration);
+
+        // The scrolling region is reset along with the size.
+        parse(&mut term, &mut shell_integration, "\x1b[2;6r\x1b[9;1H");
+        shell_integration.resize(&mut term, TermSize::new(100, 6));
+        parse(&mut term, &mut shell_integration, &"\r\n".repeat(6));
+        assert_blocks(&term, &shell_integration);
+    }
+
+    #[test]
+    fn test_synchronized_update() {
+        let output = concat!(
+            "\x1b]133;A\x07$ \x1b]133;B\x07ls\r\n\x1b]133;C\x07",
+            "a\r\nb\r\n",
+            "\x1b]133;D;0\x07",
+            "\x1b]133;A\x07$ \x1b]133;B\x07",
+        );
+        // The marks of a synchronized update are placed where they're written, not where it ends.
+        assert_eq!(run(&format!("\x1b[?2026h{output}\x1b[?2026l")), run(output));
+
+        let (mut pty_output, mut events_rx) = new_pty_output(SCROLLING_HISTORY);
+        pty_output
+            .read_from(&mut &b"\x1b[?2026h$ ls\r\na"[..])
+            .unwrap();
+        assert!(!woke_up(&mut events_rx));
+        assert_eq!(line_text(&pty_output.term.lock(), Line(0)), "$ ls");
+
+        pty_output.read_from(&mut &b"\r\n\x1b[?2026l"[..]).unwrap();
+        assert!(woke_up(&mut events_rx));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_event_loop() {
+        use alacritty_terminal::event_loop::EventLoop;
+
+        let (events_tx, _events_rx) = unbounded();
+        let term = Arc::new(FairMutex::new(new_term(
+            SCROLLING_HISTORY,
+            ZedListener(events_tx.clone()),
+        )));
+        let shell_integration = Arc::new(Mutex::new(ShellIntegration::default()));
+        let script = concat!(
+            r"printf '\033]133;A\007$ \033]133;B\007ls\n\033]133;C\007",
+            r"a\nb\n\033]133;D;0\007'",
+        );
+        let options = tty::Options {
+            shell: Some(tty::Shell::new(
+                "/bin/sh".to_string(),
+                vec!["-c".to_string(), script.to_string()],
+            )),
+            drain_on_exit: true,
+            ..Default::default()
+        };
+        let pty = tty::new(&options, TerminalBounds::default().into(), 0).unwrap();
+        let pty = ShellIntegrationPty::new(
+            pty,
+            term.clone(),
+            ZedListener(events_tx.clone()),
+            shell_integration.clone(),
+        );
+
+        // The event loop reads from the PTY while it holds the lease of the terminal, and stops once
+        // the shell exits.
+        let event_loop =
+            EventLoop::new(term.clone(), ZedListener(events_tx), pty, true, false).unwrap();
+        event_loop.spawn().join().unwrap();
+
+        let term = term.lock();
+        let blocks = shell_integration.lock().command_blocks(&term);
+        assert_eq!(blocks.len(), 1);
+        assert_eq!(blocks[0].command.as_deref(), Some("ls"));
+        assert_eq!(blocks[0].exit_code, Some(0));
+        assert_eq!(blocks[0].output, Some(Line(1)..Line(3)));
+        assert_eq!(line_text(&term, Line(2)), "b");
+    }
+
+    #[test]
+    fn test_synchronized_update_timeout() {
+        let (mut pty_output, mut events_rx) = new_pty_output(SCROLLING_HISTORY);
+        pty_output.read_from(&mut &b"\x1b[?2026h$ ls"[..]).unwrap();
+        assert!(!woke_up(&mut events_rx));
+
+        // The terminal is redrawn once the synchronized update times out, without more output.
+        std::thread::sleep(SYNCHRONIZED_UPDATE_TIMEOUT * 2);
+        assert!(woke_up(&mut events_rx));
+    }
+
+    #[test]
+    fn test_read_more_than_one_event() {
+        let (mut pty_output, mut events_rx) = new_pty_output(SCROLLING_HISTORY);
+        let block = "\x1b]133;A\x07$ \x1b]133;B\x07echo\r\n\x1b]133;C\x07out\r\n\x1b]133;D;0\x07";
+        let output = block.repeat(MAX_READ_PER_EVENT / block.len() + 100);
+        let mut reader = output.as_bytes();
+
+        // The event loop gets to handle its other events before the rest of the output is read.
+        pty_output.read_from(&mut reader).unwrap();
+        assert_eq!(reader.len(), output.len() - MAX_READ_PER_EVENT);
+        assert!(woke_up(&mut events_rx));
+
+        pty_output.read_from(&mut reader).unwrap();
+        assert!(reader.is_empty());
+        assert!(woke_up(&mut events_rx));
+
+        let term = pty_output.term.lock();
+        let blocks = pty_output.shell_integration.lock().command_blocks(&term);
+        assert_eq!(blocks.len(), MAX_COMMAND_BLOCKS);
+        let last_block = blocks.last().unwrap();
+        assert_eq!(last_block.prompt_line, Line(3));
+        assert_eq!(last_block.output, Some(Line(4)..Line(5)));
+        for block in &blocks {
+            assert_eq!(line_text(&term, block.prompt_line), "$ echo");
+            assert_eq!(line_text(&term, block.output.clone().unwrap().start), "out");
+        }
+    }
+}
@@ -3,6 +3,7 @@ pub mod mappings;
 pub use alacritty_terminal;
 
 mod pty_info;
+mod shell_integration;
 mod terminal_hyperlinks;
 pub mod terminal_settings;
 
@@ -40,9 +41,11 @@ use mappings::mouse::{
 
 use collections::{HashMap, VecDeque};
 use futures::StreamExt;
+use parking_lot::Mutex;
 use pty_info::PtyProcessInfo;
 use serde::{Deserialize, Serialize};
 use settings::Settings;
+use shell_integration::{ShellIntegration, ShellIntegrationPty};
 use smol::channel::{Receiver, Sender};
 use task::{HideStrategy, Shell, TaskId};
 use terminal_hyperlinks::RegexSearches;
@@ -55,6 +58,7 @@ use std::{
     borrow::Cow,
     cmp::{self, min},
     fmt::Display,
+    mem,
     ops::{Deref, RangeInclusive},
     path::PathBuf,
     process::ExitStatus,
@@ -71,6 +75,8 @@ use gpui::{
 
 use crate::mappings::{colors::to_alac_rgb, keys::to_esc_str};
 
+pub use shell_integration::CommandBlock;
+
 actions!(
     terminal,
     [
@@ -100,6 +106,13 @@ actions!(
         ScrollToTop,
         /// Scrolls to the bottom of the terminal buffer.
         ScrollToBottom,
+        /// Scrolls to the prompt of the previous command.
+        ScrollToPreviousCommand,
+        /// Scrolls to the prompt of the next command.
+        ScrollToNextCommand,
+        /// Copies the output of the last command, or of the command at the top of the
+        /// terminal when scrolled up.
+        CopyCommandOutput,
         /// Toggles vi mode in the terminal.
         ToggleViMode,
         /// Selects all text in the terminal.
@@ -482,6 +495,14 @@ impl TerminalBuilder {
 
         let pty_info = PtyProcessInfo::new(&pty);
 
+        let shell_integration = Arc::new(Mutex::new(ShellIntegration::default()));
+        let pty = ShellIntegrationPty::new(
+            pty,
+            term.clone(),
+            ZedListener(events_tx.clone()),
+            shell_integration.clone(),
+        );
+
         //And connect them together
         let event_loop = EventLoop::new(
             term.clone(),
@@ -503,6 +524,8 @@ impl TerminalBuilder {
             pty_tx: Notifier(pty_tx),
             completion_tx,
             term,
+            shell_integration,
+            command_blocks_key: None,
             term_config: config,
             title_override: terminal_title_override,
             events: VecDeque::with_capacity(10), //Should never get this high.
@@ -664,6 +687,7 @@ pub struct TerminalContent {
     pub last_hovered_word: Option<HoveredWord>,
     pub scrolled_to_top: bool,
     pub scrolled_to_bottom: bool,
+    pub command_blocks: Vec<CommandBlock>,
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -690,6 +714,7 @@ impl Default for TerminalContent {
             last_hovered_word: None,
             scrolled_to_top: false,
             scrolled_to_bottom: false,
+            command_blocks: Vec::new(),
         }
     }
 }
@@ -704,6 +729,10 @@ pub struct Terminal {
     pty_tx: Notifier,
     completion_tx: Option<Sender<Option<ExitStatus>>>,
     term: Arc<FairMutex<Term<ZedListener>>>,
+    shell_integration: Arc<Mutex<ShellIntegration>>,
+    /// The revision of the shell integration, and the scrollback size and display offset of the
+    /// terminal, that the command blocks of `last_content` were built for.
+    command_blocks_key: Option<(usize, usize, usize)>,
     term_config: Config,
     events: VecDeque<InternalEvent>,
     /// This is only used for mouse mode cell change detection
@@ -750,6 +779,9 @@ pub struct TaskState {
     pub show_summary: bool,
     pub show_command: bool,
     pub show_rerun: bool,
+    /// Whether to keep the output of the task once it finishes, see [`Terminal::task_output`].
+    pub keep_output: bool,
+    pub output: Option<String>,
 }
 
 /// A status of the current terminal tab's task.
@@ -879,9 +911,11 @@ impl Terminal {
 
                 self.pty_tx.0.send(Msg::Resize(new_bounds.into())).ok();
 
-                term.resize(new_bounds);
+                self.shell_integration.lock().resize(term, new_bounds);
             }
             InternalEvent::Clear => {
+                self.shell_integration.lock().clear(term);
+
                 // Clear back buffer
                 term.clear_screen(ClearMode::Saved);
 
@@ -1222,6 +1256,93 @@ impl Terminal {
             .push_back(InternalEvent::Scroll(AlacScroll::Bottom));
     }
 
+    /// Scrolls the prompt of the closest command above the top of the terminal to its top.
+    pub fn scroll_to_previous_command(&mut self) {
+        let top_line = self.top_line();
+        if let Some(block) = self
+            .last_content
+            .command_blocks
+            .iter()
+            .rev()
+            .find(|block| block.prompt_line < top_line)
+        {
+            self.scroll_line_to_top(block.prompt_line);
+        }
+    }
+
+    /// Scrolls the prompt of the closest command below the top of the terminal to its top.
+    pub fn scroll_to_next_command(&mut self) {
+        let top_line = self.top_line();
+        if let Some(block) = self
+            .last_content
+            .command_blocks
+            .iter()
+            .find(|block| block.prompt_line > top_line)
+        {
+            self.scroll_line_to_top(block.prompt_line);
+        }
+    }
+
+    fn top_line(&self) -> Line {
+        Line(-(self.last_content.display_offset as i32))
+    }
+
+    fn scroll_line_to_top(&mut self, line: Line) {
+        let delta = self.top_line().0 - line.0;
+        self.events
+            .push_back(InternalEvent::Scroll(AlacScroll::Delta(delta)));
+    }
+
+    /// The commands run in the terminal, for shells that report them through shell integration.
+    pub fn command_blocks(&self) -> &[CommandBlock] {
+        &self.last_content.command_blocks
+    }
+
+    /// Returns the last command that was run, or the command at the top of the terminal when it's
+    /// scrolled up.
+    pub fn current_command_block(&self) -> Option<&CommandBlock> {
+        let mut run_blocks = self
+            .last_content
+            .command_blocks
+            .iter()
+            .filter(|block| block.output.is_some());
+        if self.last_content.display_offset == 0 {
+            return run_blocks.next_back();
+        }
+
+        let top_line = self.top_line();
+        let first_block = run_blocks.clone().next();
+        run_blocks
+            .rfind(|block| block.prompt_line <= top_line)
+            .or(first_block)
+    }
+
+    /// Returns the output of the command, without the trailing whitespace.
+    pub fn command_output(&self, block: &CommandBlock) -> Option<String> {
+        let output = block.output.as_ref()?;
+        let term = self.term.lock();
+        let start = output.start.max(term.topmost_line());
+        let end = Line(output.end.0 - 1).min(term.bottommost_line());
+        if start > end {
+            return None;
+        }
+
+        let output = term.bounds_to_string(
+            AlacPoint::new(start, Column(0)),
+            AlacPoint::new(end, term.last_column()),
+        );
+        Some(output.trim_end().to_string())
+    }
+
+    pub fn copy_command_output(&mut self, cx: &mut Context<Self>) {
+        if let Some(output) = self
+            .current_command_block()
+            .and_then(|block| self.command_output(block))
+        {
+            cx.write_to_clipboard(ClipboardItem::new_string(output));
+        }
+    }
+
     pub fn scrolled_to_top(&self) -> bool {
         self.last_content.scrolled_to_top
     }
@@ -1398,10 +1519,28 @@ impl Terminal {
             self.process_terminal_event(&e, &mut terminal, window, cx)
         }
 
-        self.last_content = Self::make_content(&terminal, &self.last_content);
+        let shell_integration = self.shell_integration.lock();
+        let command_blocks_key = (
+            shell_integration.revision(),
+            terminal.history_size(),
+            terminal.grid().display_offset(),
+        );
+        let command_blocks = if self.command_blocks_key == Some(command_blocks_key) {
+            mem::take(&mut self.last_content.command_blocks)
+        } else {
+            self.command_blocks_key = Some(command_blocks_key);
+            shell_integration.command_blocks(&terminal)
+        };
+        drop(shell_integration);
+
+        self.last_content = Self::make_content(&terminal, command_blocks, &self.last_content);
     }
 
-    fn make_content(term: &Term<ZedListener>, last_content: &TerminalContent) -> TerminalContent {
+    fn make_content(
+        term: &Term<ZedListener>,
+        command_blocks: Vec<CommandBlock>,
+        last_content: &TerminalContent,
+    ) -> TerminalContent {
         let content = term.renderable_content();
 
         // Pre-allocate with estimated size to reduce reallocations
@@ -1431,6 +1570,7 @@ impl Terminal {
             last_hovered_word: last_content.last_hovered_word.clone(),
             scrolled_to_top: content.display_offset == term.history_size(),
             scrolled_to_bottom: content.display_offset == 0,
+            command_blocks,
         }
     }
 
@@ -1896,6 +2036,12 @@ impl Terminal {
         self.task.as_ref()
     }
 
+    /// The output of the finished task of the terminal, without the summary shown after it.
+    /// Each task run gets its own terminal, so this is the output of that run only.
+    pub fn task_output(&self) -> Option<&str> {
+        self.task.as_ref()?.output.as_deref()
+    }
+
     pub fn wait_for_completed_task(&self, cx: &App) -> Task<Option<ExitStatus>> {
         if let Some(task) = self.task() {
             if task.status == TaskStatus::Running {
@@ -1947,6 +2093,13 @@ impl Terminal {
             }
         };
 
+        if task.keep_output {
+            let term = self.term.lock();
+            let start = AlacPoint::new(term.topmost_line(), Column(0));
+            let end = AlacPoint::new(term.grid().cursor.point.line, term.last_column());
+            task.output = Some(term.bounds_to_string(start, end));
+        }
+
         let (finished_successfully, task_line, command_line) = task_summary(task, error_code);
         let mut lines_to_show = Vec::new();
         if task.show_summary {
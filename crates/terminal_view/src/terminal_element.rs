@@ -32,7 +32,11 @@ use util::ResultExt;
 use workspace::Workspace;
 
 use std::mem;
-use std::{fmt::Debug, ops::RangeInclusive, rc::Rc};
+use std::{
+    fmt::Debug,
+    ops::{Range, RangeInclusive},
+    rc::Rc,
+};
 
 use crate::{BlockContext, BlockProperties, ContentMode, TerminalMode, TerminalView};
 
@@ -49,6 +53,8 @@ pub struct LayoutState {
     display_offset: usize,
     hyperlink_tooltip: Option<AnyElement>,
     gutter: Pixels,
+    /// The display lines of the failed commands, which are marked in the gutter.
+    failed_command_lines: Vec<Range<i32>>,
     block_below_cursor_element: Option<AnyElement>,
     base_text_style: TextStyle,
     content_mode: ContentMode,
@@ -1015,11 +1021,23 @@ impl Element for TerminalElement {
                     cursor_char,
                     selection,
                     cursor,
+                    command_blocks,
                     ..
                 } = &self.terminal.read(cx).last_content;
                 let mode = *mode;
                 let display_offset = *display_offset;
 
+                let failed_command_lines = command_blocks
+                    .iter()
+                    .filter(|block| block.failed())
+                    .map(|block| {
+                        let lines = block.lines();
+                        (lines.start.0 + display_offset as i32)
+                            ..(lines.end.0 + display_offset as i32)
+                    })
+                    .filter(|lines| lines.end > 0 && lines.start < dimensions.num_lines() as i32)
+                    .collect::<Vec<_>>();
+
                 // searches, highlights to a single range representations
                 let mut relative_highlighted_ranges = Vec::new();
                 for search_match in search_matches {
@@ -1165,6 +1183,7 @@ impl Element for TerminalElement {
                     display_offset,
                     hyperlink_tooltip,
                     gutter,
+                    failed_command_lines,
                     block_below_cursor_element,
                     base_text_style: text_style,
                     content_mode,
@@ -1251,6 +1270,15 @@ impl Element for TerminalElement {
                         rect.paint(origin, &layout.dimensions, window);
                     }
 
+                    let line_height = layout.dimensions.line_height;
+                    for lines in &layout.failed_command_lines {
+                        let marker_bounds = Bounds::new(
+                            point(bounds.origin.x, origin.y + lines.start as f32 * line_height),
+                            size(layout.gutter / 3., lines.len() as f32 * line_height),
+                        );
+                        window.paint_quad(fill(marker_bounds, cx.theme().status().error));
+                    }
+
                     for (relative_highlighted_range, color) in
                         layout.relative_highlighted_ranges.iter()
                     {
@@ -604,6 +604,40 @@ impl TerminalPanel {
         cx.spawn(async move |_, _| rx.await?)
     }
 
+    /// Runs the problem matchers of the finished task over its output,
+    /// reporting the problems found as project diagnostics.
+    fn report_task_problems(
+        &self,
+        task: &SpawnInTerminal,
+        terminal: &Entity<Terminal>,
+        cx: &mut App,
+    ) -> Result<()> {
+        let project = self
+            .workspace
+            .read_with(cx, |workspace, _| workspace.project().clone())?;
+        if !project.read(cx).is_local() {
+            return Ok(());
+        }
+
+        // Tasks without a working directory are run in the worktree root, see `create_terminal_task`.
+        let cwd = task.cwd.clone().or_else(|| {
+            project
+                .read(cx)
+                .active_project_directory(cx)
+                .map(|directory| directory.to_path_buf())
+        });
+        let Some(output) = terminal.read(cx).task_output() else {
+            return Ok(());
+        };
+        let mut problems = Vec::new();
+        for problem_matcher in &task.problem_matcher {
+            problems.extend(problem_matcher.find_problems(output, cwd.as_deref())?);
+        }
+        project.read(cx).lsp_store().update(cx, |lsp_store, cx| {
+            lsp_store.update_task_diagnostics(&task.label, problems, cx)
+        })
+    }
+
     fn spawn_in_new_terminal(
         &mut self,
         spawn_task: SpawnInTerminal,
@@ -1593,6 +1627,15 @@ impl workspace::TerminalProvider for TerminalProvider {
                         .read_with(cx, |terminal, cx| terminal.wait_for_completed_task(cx))
                         .ok()?
                         .await?;
+                    if !task.problem_matcher.is_empty() {
+                        let terminal = terminal.upgrade()?;
+                        terminal_panel
+                            .update(cx, |terminal_panel, cx| {
+                                terminal_panel.report_task_problems(&task, &terminal, cx)
+                            })
+                            .ok()?
+                            .log_err();
+                    }
                     Some(Ok(exit_status))
                 }
                 Err(e) => Some(Err(e)),
@@ -4,7 +4,6 @@ use command_palette_hooks::CommandInterceptResult;
 use editor::{
     Bias, Editor, SelectionEffects, ToPoint,
     actions::{SortLinesCaseInsensitive, SortLinesCaseSensitive},
-    display_map::ToDisplayPoint,
 };
 use gpui::{Action, App, AppContext as _, Context, Global, Keystroke, Window, actions};
 use itertools::Itertools;
@@ -33,7 +32,7 @@ use workspace::{SplitDirection, notifications::DetachAndPromptErr};
 use zed_actions::{OpenDocs, RevealTarget};
 
 use crate::{
-    ToggleMarksView, ToggleRegistersView, Vim,
+    OpenRegistersBuffer, ToggleMarksView, Vim,
     motion::{EndOfDocument, Motion, MotionKind, StartOfDocument},
     normal::{
         JoinLines,
@@ -215,6 +214,8 @@ struct VimEdit {
 struct VimNorm {
     pub range: Option<CommandRange>,
     pub command: String,
+    /// Runs the command at the start of each of the given rows, instead of the range or the current selections.
+    pub override_rows: Option<Vec<u32>>,
 }
 
 #[derive(Debug)]
@@ -239,6 +240,20 @@ impl Deref for WrappedAction {
     }
 }
 
+/// Converts the argument of `:normal` into the keystrokes to replay, one per character.
+fn norm_keystrokes(command: &str) -> Vec<Keystroke> {
+    command
+        .chars()
+        .filter_map(|c| {
+            let keystroke = match c {
+                ' ' => Keystroke::parse("space"),
+                c => Keystroke::parse(&c.to_string()),
+            };
+            keystroke.log_err()
+        })
+        .collect()
+}
+
 pub fn register(editor: &mut Editor, cx: &mut Context<Vim>) {
     // Vim::action(editor, cx, |vim, action: &StartOfLine, window, cx| {
     Vim::action(editor, cx, |vim, action: &VimSet, _, cx| {
@@ -456,65 +471,84 @@ pub fn register(editor: &mut Editor, cx: &mut Context<Vim>) {
     });
 
     Vim::action(editor, cx, |vim, action: &VimNorm, window, cx| {
-        let keystrokes = action
-            .command
-            .chars()
-            .map(|c| Keystroke::parse(&c.to_string()).unwrap())
-            .collect();
+        let keystrokes = norm_keystrokes(&action.command);
         vim.switch_mode(Mode::Normal, true, window, cx);
         let initial_selections =
             vim.update_editor(cx, |_, editor, _| editor.selections.disjoint_anchors_arc());
-        if let Some(range) = &action.range {
-            let result = vim.update_editor(cx, |vim, editor, cx| {
+
+        // With a range, the keystrokes are replayed at the start of every line in it, one line after another,
+        // tracking the lines with anchors as the previous ones get edited.
+        let result = vim.update_editor(cx, |vim, editor, cx| {
+            let rows = if let Some(rows) = &action.override_rows {
+                rows.clone()
+            } else if let Some(range) = &action.range {
                 let range = range.buffer_range(vim, editor, window, cx)?;
-                editor.change_selections(
-                    SelectionEffects::no_scroll().nav_history(false),
-                    window,
-                    cx,
-                    |s| {
-                        s.select_ranges(
-                            (range.start.0..=range.end.0)
-                                .map(|line| Point::new(line, 0)..Point::new(line, 0)),
-                        );
-                    },
-                );
-                anyhow::Ok(())
-            });
-            if let Some(Err(err)) = result {
+                (range.start.0..=range.end.0).collect()
+            } else {
+                return anyhow::Ok(None);
+            };
+            let snapshot = editor.buffer().read(cx).snapshot(cx);
+            anyhow::Ok(Some(
+                rows.into_iter()
+                    .map(|row| snapshot.anchor_before(Point::new(row, 0)))
+                    .collect::<Vec<_>>(),
+            ))
+        });
+        let lines = match result {
+            Some(Ok(lines)) => lines,
+            Some(Err(err)) => {
                 log::error!("Error selecting range: {}", err);
                 return;
             }
+            None => None,
         };
 
         let Some(workspace) = vim.workspace(window) else {
             return;
         };
-        let task = workspace.update(cx, |workspace, cx| {
-            workspace.send_keystrokes_impl(keystrokes, window, cx)
-        });
-        let had_range = action.range.is_some();
+        vim.norm_tx = Some(None);
 
         cx.spawn_in(window, async move |vim, cx| {
-            task.await;
-            vim.update_in(cx, |vim, window, cx| {
-                vim.update_editor(cx, |_, editor, cx| {
-                    if had_range {
-                        editor.change_selections(SelectionEffects::default(), window, cx, |s| {
-                            s.select_anchor_ranges([s.newest_anchor().range()]);
-                        })
+            let runs: Vec<Option<_>> = match lines {
+                Some(lines) => lines.into_iter().map(Some).collect(),
+                None => vec![None],
+            };
+            for line in runs {
+                let Ok(task) = vim.update_in(cx, |vim, window, cx| {
+                    if let Some(line) = line {
+                        vim.update_editor(cx, |_, editor, cx| {
+                            editor.change_selections(
+                                SelectionEffects::no_scroll().nav_history(false),
+                                window,
+                                cx,
+                                |s| s.select_anchor_ranges([line..line]),
+                            );
+                        });
                     }
-                });
-                if matches!(vim.mode, Mode::Insert | Mode::Replace) {
-                    vim.normal_before(&Default::default(), window, cx);
-                } else {
-                    vim.switch_mode(Mode::Normal, true, window, cx);
-                }
+                    workspace.update(cx, |workspace, cx| {
+                        workspace.send_keystrokes_impl(keystrokes.clone(), window, cx)
+                    })
+                }) else {
+                    return;
+                };
+                task.await;
+                vim.update_in(cx, |vim, window, cx| {
+                    if matches!(vim.mode, Mode::Insert | Mode::Replace) {
+                        vim.normal_before(&Default::default(), window, cx);
+                    } else {
+                        vim.switch_mode(Mode::Normal, true, window, cx);
+                    }
+                })
+                .ok();
+            }
+
+            vim.update(cx, |vim, cx| {
+                let Some(Some(tx_id)) = vim.norm_tx.take() else {
+                    return;
+                };
                 vim.update_editor(cx, |_, editor, cx| {
-                    if let Some(first_sel) = initial_selections
-                        && let Some(tx_id) = editor
-                            .buffer()
-                            .update(cx, |multi, cx| multi.last_transaction_id(cx))
-                    {
+                    editor.group_until_transaction(tx_id, cx);
+                    if let Some(first_sel) = initial_selections {
                         let last_sel = editor.selections.disjoint_anchors_arc();
                         editor.modify_transaction_selection_history(tx_id, |old| {
                             old.0 = first_sel;
@@ -548,8 +582,14 @@ pub fn register(editor: &mut Editor, cx: &mut Context<Vim>) {
         vim.switch_mode(Mode::Normal, false, window, cx);
         let result = vim.update_editor(cx, |vim, editor, cx| {
             let snapshot = editor.snapshot(window, cx);
-            let buffer_row = action.range.head().buffer_row(vim, editor, window, cx)?;
             let current = editor.selections.newest::<Point>(cx);
+            let buffer_row = action.range.head().buffer_row(
+                MultiBufferRow(current.head().row),
+                vim,
+                editor,
+                window,
+                cx,
+            )?;
             let target = snapshot
                 .buffer_snapshot
                 .clip_point(Point::new(buffer_row.0, current.head().column), Bias::Left);
@@ -896,6 +936,7 @@ enum Position {
 impl Position {
     fn buffer_row(
         &self,
+        current_row: MultiBufferRow,
         vim: &Vim,
         editor: &mut Editor,
         window: &mut Window,
@@ -937,13 +978,7 @@ impl Position {
                 .max_row()
                 .0
                 .saturating_add_signed(*offset),
-            Position::CurrentLine { offset } => editor
-                .selections
-                .newest_anchor()
-                .head()
-                .to_point(&snapshot.buffer_snapshot)
-                .row
-                .saturating_add_signed(*offset),
+            Position::CurrentLine { offset } => current_row.0.saturating_add_signed(*offset),
         };
 
         Ok(MultiBufferRow(target).min(snapshot.buffer_snapshot.max_row()))
@@ -968,9 +1003,29 @@ impl CommandRange {
         window: &mut Window,
         cx: &mut App,
     ) -> Result<Range<MultiBufferRow>> {
-        let start = self.start.buffer_row(vim, editor, window, cx)?;
+        let current_row = editor
+            .selections
+            .newest_anchor()
+            .head()
+            .to_point(&editor.buffer().read(cx).snapshot(cx))
+            .row;
+        self.buffer_range_at(MultiBufferRow(current_row), vim, editor, window, cx)
+    }
+
+    /// Like [`Self::buffer_range`], but with `.` referring to the given row instead of the cursor's one.
+    pub(crate) fn buffer_range_at(
+        &self,
+        current_row: MultiBufferRow,
+        vim: &Vim,
+        editor: &mut Editor,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Result<Range<MultiBufferRow>> {
+        let start = self
+            .start
+            .buffer_row(current_row, vim, editor, window, cx)?;
         let end = if let Some(end) = self.end.as_ref() {
-            end.buffer_row(vim, editor, window, cx)?
+            end.buffer_row(current_row, vim, editor, window, cx)?
         } else {
             start
         };
@@ -1147,6 +1202,7 @@ fn generate_commands(_: &App) -> Vec<VimCommand> {
             VimNorm {
                 command: "".into(),
                 range: None,
+                override_rows: None,
             },
         )
         .args(|_, args| {
@@ -1154,6 +1210,7 @@ fn generate_commands(_: &App) -> Vec<VimCommand> {
                 VimNorm {
                     command: args,
                     range: None,
+                    override_rows: None,
                 }
                 .boxed_clone(),
             )
@@ -1252,8 +1309,8 @@ fn generate_commands(_: &App) -> Vec<VimCommand> {
                 .boxed_clone(),
             )
         }),
-        VimCommand::new(("reg", "isters"), ToggleRegistersView).bang(ToggleRegistersView),
-        VimCommand::new(("di", "splay"), ToggleRegistersView).bang(ToggleRegistersView),
+        VimCommand::new(("reg", "isters"), OpenRegistersBuffer).bang(OpenRegistersBuffer),
+        VimCommand::new(("di", "splay"), OpenRegistersBuffer).bang(OpenRegistersBuffer),
         VimCommand::new(("marks", ""), ToggleMarksView).bang(ToggleMarksView),
         VimCommand::new(("delm", "arks"), ArgumentRequired)
             .bang(DeleteMarks::AllLocal)
@@ -1383,7 +1440,14 @@ pub fn command_interceptor(mut input: &str, cx: &App) -> Vec<CommandInterceptRes
                 start: Position::CurrentLine { offset: 0 },
                 end: None,
             });
-            Some(ReplaceCommand { replacement, range }.boxed_clone())
+            Some(
+                ReplaceCommand {
+                    replacement,
+                    range,
+                    override_rows: None,
+                }
+                .boxed_clone(),
+            )
         } else {
             None
         }
@@ -1604,10 +1668,10 @@ impl OnMatchingLines {
                     .buffer_snapshot
                     .clip_point(Point::new(range.end.0 + 1, 0), Bias::Left);
             cx.spawn_in(window, async move |editor, cx| {
-                let new_selections = cx
+                let new_rows = cx
                     .background_spawn(async move {
                         let mut line = String::new();
-                        let mut new_selections = Vec::new();
+                        let mut new_rows = Vec::new();
                         let chunks = snapshot
                             .buffer_snapshot
                             .text_for_range(point_range)
@@ -1619,8 +1683,7 @@ impl OnMatchingLines {
                                     if regexes.iter().all(|(regex, should_match)| {
                                         regex.is_match(&line) == *should_match
                                     }) {
-                                        new_selections
-                                            .push(Point::new(row, 0).to_display_point(&snapshot))
+                                        new_rows.push(row)
                                     }
                                     row += 1;
                                     line.clear();
@@ -1629,32 +1692,36 @@ impl OnMatchingLines {
                             }
                         }
 
-                        new_selections
+                        new_rows
                     })
                     .await;
 
-                if new_selections.is_empty() {
+                if new_rows.is_empty() {
                     return;
                 }
                 editor
                     .update_in(cx, |editor, window, cx| {
+                        // Commands that finish asynchronously are given all the rows at once.
+                        if let Some(norm) = action.as_any().downcast_ref::<VimNorm>() {
+                            let mut norm = norm.clone();
+                            norm.override_rows = Some(new_rows);
+                            window.dispatch_action(norm.boxed_clone(), cx);
+                            return;
+                        }
+                        if let Some(replace) = action.as_any().downcast_ref::<ReplaceCommand>() {
+                            let mut replace = replace.clone();
+                            replace.override_rows = Some(new_rows);
+                            window.dispatch_action(replace.boxed_clone(), cx);
+                            return;
+                        }
+
+                        let snapshot = editor.buffer().read(cx).snapshot(cx);
+                        let lines = new_rows
+                            .into_iter()
+                            .map(|row| snapshot.anchor_before(Point::new(row, 0)))
+                            .collect::<Vec<_>>();
                         editor.start_transaction_at(Instant::now(), window, cx);
-                        editor.change_selections(SelectionEffects::no_scroll(), window, cx, |s| {
-                            s.replace_cursors_with(|_| new_selections);
-                        });
-                        window.dispatch_action(action, cx);
-                        cx.defer_in(window, move |editor, window, cx| {
-                            let newest = editor.selections.newest::<Point>(cx);
-                            editor.change_selections(
-                                SelectionEffects::no_scroll(),
-                                window,
-                                cx,
-                                |s| {
-                                    s.select(vec![newest]);
-                                },
-                            );
-                            editor.end_transaction_at(Instant::now(), cx);
-                        })
+                        run_on_lines(editor, lines.into_iter(), action, window, cx);
                     })
                     .ok();
             })
@@ -1663,6 +1730,31 @@ impl OnMatchingLines {
     }
 }
 
+/// Runs the action with the cursor at the start of each of the lines, one after another,
+/// ending the transaction started by `:g` once all of them are processed.
+///
+/// Actions are dispatched on the next effect cycle, so each line is processed in
+/// a deferred callback, after the action for the previous line has run.
+fn run_on_lines(
+    editor: &mut Editor,
+    mut lines: std::vec::IntoIter<editor::Anchor>,
+    action: Box<dyn Action>,
+    window: &mut Window,
+    cx: &mut Context<Editor>,
+) {
+    let Some(line) = lines.next() else {
+        editor.end_transaction_at(Instant::now(), cx);
+        return;
+    };
+    editor.change_selections(SelectionEffects::no_scroll(), window, cx, |s| {
+        s.select_anchor_ranges([line..line]);
+    });
+    window.dispatch_action(action.boxed_clone(), cx);
+    cx.defer_in(window, move |editor, window, cx| {
+        run_on_lines(editor, lines, action, window, cx)
+    });
+}
+
 /// Executes a shell command and returns the output.
 #[derive(Clone, Debug, PartialEq, Action)]
 #[action(namespace = vim, no_json, no_register)]
@@ -1865,6 +1957,7 @@ impl ShellExec {
                     show_summary: false,
                     show_command: false,
                     show_rerun: false,
+                    problem_matcher: Vec::new(),
                 };
 
                 let task_status = workspace.spawn_in_terminal(spawn_in_terminal, window, cx);
@@ -2473,6 +2566,69 @@ mod test {
         // Once ctrl-v to input character literals is added there should be a test for redo
     }
 
+    #[gpui::test]
+    async fn test_normal_command_over_lines(cx: &mut TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state(
+            indoc! {"
+            ˇone
+            two
+            three
+            four"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes(": 2 , 3 n o r m space I - >");
+        cx.simulate_keystrokes("enter");
+        cx.run_until_parked();
+        assert_eq!(cx.buffer_text(), "one\n->two\n->three\nfour");
+
+        // All the edits are undone at once.
+        cx.simulate_keystrokes("u");
+        assert_eq!(cx.buffer_text(), "one\ntwo\nthree\nfour");
+
+        // Deleting lines shifts the ones that are left to process.
+        cx.simulate_keystrokes(": % n o r m space d d");
+        cx.simulate_keystrokes("enter");
+        cx.run_until_parked();
+        assert_eq!(cx.buffer_text(), "");
+    }
+
+    #[gpui::test]
+    async fn test_global_command(cx: &mut TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state(
+            indoc! {"
+            ˇfn a() {}
+            let b = 1;
+            fn c() {}
+            let d = 2;"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes(": g / f n / n o r m space A space / / x");
+        cx.simulate_keystrokes("enter");
+        cx.run_until_parked();
+        assert_eq!(
+            cx.buffer_text(),
+            "fn a() {} //x\nlet b = 1;\nfn c() {} //x\nlet d = 2;"
+        );
+        cx.simulate_keystrokes("u");
+        assert_eq!(
+            cx.buffer_text(),
+            "fn a() {}\nlet b = 1;\nfn c() {}\nlet d = 2;"
+        );
+
+        // Ex commands resolve their ranges from each matching line.
+        cx.simulate_keystrokes(": v / f n / s / = / : =");
+        cx.simulate_keystrokes("enter");
+        cx.run_until_parked();
+        assert_eq!(
+            cx.buffer_text(),
+            "fn a() {}\nlet b := 1;\nfn c() {}\nlet d := 2;"
+        );
+    }
+
     #[gpui::test]
     async fn test_command_tabnew(cx: &mut TestAppContext) {
         let mut cx = VimTestContext::new(cx, true).await;
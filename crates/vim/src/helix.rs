@@ -1,6 +1,7 @@
 mod boundary;
 mod object;
 mod paste;
+mod regex_prompt;
 mod select;
 
 use editor::display_map::DisplaySnapshot;
@@ -12,6 +13,7 @@ use gpui::{Context, Window};
 use language::{CharClassifier, CharKind, Point};
 use text::{Bias, SelectionGoal};
 
+use crate::helix::{regex_prompt::RegexSelectionPrompt, select::RegexSelection};
 use crate::motion;
 use crate::{
     Vim,
@@ -32,6 +34,18 @@ actions!(
         HelixGotoLastModification,
         /// Select entire line or multiple lines, extending downwards.
         HelixSelectLine,
+        /// Prompts for a regex and selects its matches within the selections.
+        HelixSelectRegex,
+        /// Prompts for a regex and splits the selections on its matches.
+        HelixSplitSelections,
+        /// Prompts for a regex and keeps the selections that match it.
+        HelixKeepSelections,
+        /// Prompts for a regex and removes the selections that match it.
+        HelixRemoveSelections,
+        /// Splits the selections on newlines.
+        HelixSplitSelectionsOnNewline,
+        /// Aligns the selections in columns by inserting spaces before them.
+        HelixAlignSelections,
     ]
 );
 
@@ -42,6 +56,28 @@ pub fn register(editor: &mut Editor, cx: &mut Context<Vim>) {
     Vim::action(editor, cx, Vim::helix_yank);
     Vim::action(editor, cx, Vim::helix_goto_last_modification);
     Vim::action(editor, cx, Vim::helix_paste);
+    Vim::action(editor, cx, |vim, _: &HelixSelectRegex, window, cx| {
+        vim.helix_prompt_regex_selection(RegexSelection::Select, window, cx)
+    });
+    Vim::action(editor, cx, |vim, _: &HelixSplitSelections, window, cx| {
+        vim.helix_prompt_regex_selection(RegexSelection::Split, window, cx)
+    });
+    Vim::action(editor, cx, |vim, _: &HelixKeepSelections, window, cx| {
+        vim.helix_prompt_regex_selection(RegexSelection::Keep, window, cx)
+    });
+    Vim::action(editor, cx, |vim, _: &HelixRemoveSelections, window, cx| {
+        vim.helix_prompt_regex_selection(RegexSelection::Remove, window, cx)
+    });
+    Vim::action(
+        editor,
+        cx,
+        |vim, _: &HelixSplitSelectionsOnNewline, window, cx| {
+            vim.helix_regex_selection(RegexSelection::Split, r"\r?\n", window, cx)
+        },
+    );
+    Vim::action(editor, cx, |vim, _: &HelixAlignSelections, window, cx| {
+        vim.helix_align_selections(window, cx)
+    });
 }
 
 impl Vim {
@@ -459,6 +495,23 @@ impl Vim {
         self.switch_mode(Mode::HelixNormal, true, window, cx);
     }
 
+    fn helix_prompt_regex_selection(
+        &mut self,
+        kind: RegexSelection,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(workspace) = self.workspace(window) else {
+            return;
+        };
+        let vim = cx.entity().downgrade();
+        workspace.update(cx, |workspace, cx| {
+            workspace.toggle_modal(window, cx, |window, cx| {
+                RegexSelectionPrompt::new(vim, kind, window, cx)
+            })
+        });
+    }
+
     pub fn helix_goto_last_modification(
         &mut self,
         _: &HelixGotoLastModification,
@@ -1121,4 +1174,50 @@ mod test {
         cx.simulate_keystrokes("v w");
         cx.assert_state("«one ˇ»two", Mode::HelixSelect);
     }
+
+    #[gpui::test]
+    async fn test_regex_selections(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+        cx.enable_helix();
+
+        cx.set_state("«foo bar baz\nqux fooˇ»", Mode::HelixNormal);
+        cx.simulate_keystrokes("s b a enter");
+        cx.assert_state("foo «baˇ»r «baˇ»z\nqux foo", Mode::HelixNormal);
+
+        cx.simulate_keystrokes("% shift-s space enter");
+        cx.assert_state("«fooˇ» «barˇ» «baz\nquxˇ» «fooˇ»", Mode::HelixNormal);
+
+        cx.simulate_keystrokes("shift-k F enter");
+        cx.assert_state("«fooˇ» «barˇ» «baz\nquxˇ» «fooˇ»", Mode::HelixNormal);
+        cx.simulate_keystrokes("shift-k f enter");
+        cx.assert_state("«fooˇ» bar baz\nqux «fooˇ»", Mode::HelixNormal);
+
+        // No selection would be left, so nothing changes.
+        cx.simulate_keystrokes("alt-shift-k o enter");
+        cx.assert_state("«fooˇ» bar baz\nqux «fooˇ»", Mode::HelixNormal);
+
+        cx.set_state("«ab\ncdˇ»", Mode::HelixNormal);
+        cx.simulate_keystrokes("alt-s");
+        cx.assert_state("«abˇ»\n«cdˇ»", Mode::HelixNormal);
+    }
+
+    #[gpui::test]
+    async fn test_align_selections(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+        cx.enable_helix();
+
+        cx.set_state(
+            indoc! {"
+            a «=ˇ» 1, x «=ˇ» 1
+            bcd «=ˇ» 2, yz «=ˇ» 2"},
+            Mode::HelixNormal,
+        );
+        cx.simulate_keystrokes("&");
+        cx.assert_state(
+            indoc! {"
+            a   «=ˇ» 1, x  «=ˇ» 1
+            bcd «=ˇ» 2, yz «=ˇ» 2"},
+            Mode::HelixNormal,
+        );
+    }
 }
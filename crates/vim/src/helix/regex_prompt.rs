@@ -0,0 +1,80 @@
+use editor::{Editor, EditorEvent};
+use gpui::{DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, Subscription, WeakEntity};
+use ui::prelude::*;
+use workspace::ModalView;
+
+use crate::{Vim, helix::select::RegexSelection};
+
+/// Asks for the regex of a helix selection command, like `s` or `K`, and applies it to the
+/// selections of the editor it was opened from.
+pub(crate) struct RegexSelectionPrompt {
+    vim: WeakEntity<Vim>,
+    kind: RegexSelection,
+    editor: Entity<Editor>,
+    _subscription: Subscription,
+}
+
+impl RegexSelectionPrompt {
+    pub(crate) fn new(
+        vim: WeakEntity<Vim>,
+        kind: RegexSelection,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let editor = cx.new(|cx| Editor::single_line(window, cx));
+        let subscription = cx.subscribe_in(&editor, window, |_, _, event, _, cx| {
+            if let EditorEvent::Blurred = event {
+                cx.emit(DismissEvent);
+            }
+        });
+        Self {
+            vim,
+            kind,
+            editor,
+            _subscription: subscription,
+        }
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        let query = self.editor.read(cx).text(cx);
+        if !query.is_empty() {
+            let kind = self.kind;
+            self.vim
+                .update(cx, |vim, cx| {
+                    vim.helix_regex_selection(kind, &query, window, cx)
+                })
+                .ok();
+        }
+        cx.emit(DismissEvent);
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, _: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(DismissEvent);
+    }
+}
+
+impl ModalView for RegexSelectionPrompt {}
+
+impl EventEmitter<DismissEvent> for RegexSelectionPrompt {}
+
+impl Focusable for RegexSelectionPrompt {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.editor.focus_handle(cx)
+    }
+}
+
+impl Render for RegexSelectionPrompt {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        h_flex()
+            .w(rems(34.))
+            .px_2()
+            .py_1()
+            .gap_2()
+            .elevation_2(cx)
+            .key_context("RegexSelectionPrompt")
+            .on_action(cx.listener(Self::confirm))
+            .on_action(cx.listener(Self::cancel))
+            .child(Label::new(self.kind.prompt()).color(Color::Muted))
+            .child(self.editor.clone())
+    }
+}
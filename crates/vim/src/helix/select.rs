@@ -1,8 +1,36 @@
+use collections::HashMap;
+use language::Point;
+use regex::RegexBuilder;
 use text::SelectionGoal;
 use ui::{Context, Window};
+use util::ResultExt as _;
 
 use crate::{Vim, helix::object::cursor_range, object::Object};
 
+/// How the matches of a regex typed into a prompt change the selections.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum RegexSelection {
+    /// Selects the matches within the selections.
+    Select,
+    /// Splits the selections on the matches.
+    Split,
+    /// Keeps the selections that contain a match.
+    Keep,
+    /// Removes the selections that contain a match.
+    Remove,
+}
+
+impl RegexSelection {
+    pub(crate) fn prompt(&self) -> &'static str {
+        match self {
+            RegexSelection::Select => "select:",
+            RegexSelection::Split => "split:",
+            RegexSelection::Keep => "keep:",
+            RegexSelection::Remove => "remove:",
+        }
+    }
+}
+
 impl Vim {
     /// Selects the object each cursor is over.
     /// Follows helix convention.
@@ -81,4 +109,145 @@ impl Vim {
             });
         });
     }
+
+    /// Changes the selections according to the matches of the given regex within them.
+    /// The regex is case insensitive unless it contains an uppercase character, and the
+    /// selections are left unchanged when none would remain.
+    pub(crate) fn helix_regex_selection(
+        &mut self,
+        kind: RegexSelection,
+        query: &str,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(regex) = RegexBuilder::new(query)
+            .case_insensitive(!query.chars().any(char::is_uppercase))
+            .build()
+            .log_err()
+        else {
+            return;
+        };
+        self.update_editor(cx, |_, editor, cx| {
+            let snapshot = editor.buffer().read(cx).snapshot(cx);
+            let mut ranges = Vec::new();
+            for selection in editor.selections.all::<usize>(cx) {
+                let start = selection.start;
+                let text = snapshot
+                    .text_for_range(selection.range())
+                    .collect::<String>();
+                match kind {
+                    RegexSelection::Select => ranges.extend(
+                        regex
+                            .find_iter(&text)
+                            .filter(|found| !found.is_empty())
+                            .map(|found| start + found.start()..start + found.end()),
+                    ),
+                    RegexSelection::Split => {
+                        let mut segment_start = start;
+                        for found in regex.find_iter(&text) {
+                            ranges.push(segment_start..start + found.start());
+                            segment_start = start + found.end();
+                        }
+                        if segment_start < selection.end {
+                            ranges.push(segment_start..selection.end);
+                        }
+                    }
+                    RegexSelection::Keep | RegexSelection::Remove => {
+                        if regex.is_match(&text) == (kind == RegexSelection::Keep) {
+                            ranges.push(if selection.reversed {
+                                selection.end..selection.start
+                            } else {
+                                selection.start..selection.end
+                            });
+                        }
+                    }
+                }
+            }
+            if ranges.is_empty() {
+                return;
+            }
+            editor.change_selections(Default::default(), window, cx, |s| {
+                s.select_ranges(ranges);
+            });
+        });
+    }
+
+    /// Aligns the selections in columns by inserting spaces before them, the n-th
+    /// selection of every line being aligned with the n-th selection of the other lines.
+    /// Does nothing if any selection spans multiple lines.
+    pub(crate) fn helix_align_selections(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.update_editor(cx, |_, editor, cx| {
+            let snapshot = editor.buffer().read(cx).snapshot(cx);
+            let mut selections = editor.selections.all::<Point>(cx);
+            if selections
+                .iter()
+                .any(|selection| selection.start.row != selection.end.row)
+            {
+                return;
+            }
+
+            let mut columns: Vec<Vec<(usize, usize)>> = Vec::new();
+            let mut last_row = None;
+            let mut column = 0;
+            for (ix, selection) in selections.iter().enumerate() {
+                let row = selection.start.row;
+                column = if last_row == Some(row) { column + 1 } else { 0 };
+                last_row = Some(row);
+                let head_column = snapshot
+                    .text_for_range(Point::new(row, 0)..selection.head())
+                    .map(|chunk| chunk.chars().count())
+                    .sum::<usize>();
+                if column == columns.len() {
+                    columns.push(Vec::new());
+                }
+                columns[column].push((ix, head_column));
+            }
+
+            // Spaces inserted so far on each row, all of them before the selections of
+            // the columns that are left to align.
+            let mut row_shifts = HashMap::<u32, usize>::default();
+            let mut selection_shifts = vec![0; selections.len()];
+            let mut edits = Vec::new();
+            for column in columns {
+                let shifted_column = |ix: usize, head_column: usize, row_shifts: &HashMap<_, _>| {
+                    head_column
+                        + row_shifts
+                            .get(&selections[ix].start.row)
+                            .copied()
+                            .unwrap_or(0)
+                };
+                let Some(max_column) = column
+                    .iter()
+                    .map(|(ix, head_column)| shifted_column(*ix, *head_column, &row_shifts))
+                    .max()
+                else {
+                    continue;
+                };
+                for (ix, head_column) in column {
+                    let count = max_column - shifted_column(ix, head_column, &row_shifts);
+                    let row_shift = row_shifts.entry(selections[ix].start.row).or_default();
+                    *row_shift += count;
+                    selection_shifts[ix] = *row_shift;
+                    if count > 0 {
+                        let start = snapshot.point_to_offset(selections[ix].start);
+                        edits.push((start..start, " ".repeat(count)));
+                    }
+                }
+            }
+            if edits.is_empty() {
+                return;
+            }
+
+            for (selection, shift) in selections.iter_mut().zip(selection_shifts) {
+                selection.start.column += shift as u32;
+                selection.end.column += shift as u32;
+            }
+            editor.transact(window, cx, |editor, window, cx| {
+                editor.edit(edits, cx);
+                editor.change_selections(Default::default(), window, cx, |s| {
+                    s.select(selections);
+                });
+            });
+        });
+    }
 }
@@ -8,7 +8,8 @@ use crate::{
     state::{Mode, Operator, RecordedSelection, ReplayableAction, VimGlobals},
 };
 use editor::Editor;
-use gpui::{Action, App, Context, Window, actions};
+use gpui::{Action, App, Context, Keystroke, Window, actions};
+use util::ResultExt as _;
 use workspace::Workspace;
 
 actions!(
@@ -52,10 +53,124 @@ fn repeatable_insert(action: &ReplayableAction) -> Option<Box<dyn Action>> {
                 None
             }
         }
-        ReplayableAction::Insertion { .. } => None,
+        ReplayableAction::Insertion { .. } | ReplayableAction::Keystroke(_) => None,
     }
 }
 
+/// The names Vim uses for the keys that don't type a character, by their GPUI names.
+const KEY_NOTATION_NAMES: &[(&str, &str)] = &[
+    ("escape", "Esc"),
+    ("enter", "CR"),
+    ("backspace", "BS"),
+    ("delete", "Del"),
+    ("tab", "Tab"),
+    ("space", "Space"),
+    ("up", "Up"),
+    ("down", "Down"),
+    ("left", "Left"),
+    ("right", "Right"),
+    ("home", "Home"),
+    ("end", "End"),
+    ("pageup", "PageUp"),
+    ("pagedown", "PageDown"),
+    ("insert", "Insert"),
+    ("<", "lt"),
+];
+
+/// Converts recorded keystrokes into the text of a macro register, writing the keys that
+/// don't type a character in Vim's key notation (e.g. `<Esc>` or `<C-a>`).
+pub(crate) fn keystrokes_to_register_text(keystrokes: &[Keystroke]) -> String {
+    let mut text = String::new();
+    for keystroke in keystrokes {
+        let modifiers = &keystroke.modifiers;
+        if !modifiers.control
+            && !modifiers.alt
+            && !modifiers.platform
+            && !modifiers.function
+            && let Some(key_char) = keystroke.key_char.as_deref()
+            && !key_char.is_empty()
+            && !key_char.chars().any(char::is_control)
+        {
+            if key_char == "<" {
+                text.push_str("<lt>");
+            } else {
+                text.push_str(key_char);
+            }
+            continue;
+        }
+
+        text.push('<');
+        if modifiers.control {
+            text.push_str("C-");
+        }
+        if modifiers.alt {
+            text.push_str("A-");
+        }
+        if modifiers.shift {
+            text.push_str("S-");
+        }
+        if modifiers.platform {
+            text.push_str("D-");
+        }
+        let name = KEY_NOTATION_NAMES
+            .iter()
+            .find(|(key, _)| *key == keystroke.key)
+            .map_or(keystroke.key.as_str(), |(_, name)| name);
+        text.push_str(name);
+        text.push('>');
+    }
+    text
+}
+
+/// Parses the text of a register into the keystrokes to replay, understanding the key
+/// notation written by [`keystrokes_to_register_text`].
+pub(crate) fn register_text_to_keystrokes(text: &str) -> Vec<Keystroke> {
+    let mut keystrokes = Vec::new();
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        if c == '<'
+            && let Some(end) = rest.find('>')
+            && let Some(keystroke) = parse_key_notation(&rest[1..end])
+        {
+            keystrokes.push(keystroke);
+            rest = &rest[end + 1..];
+            continue;
+        }
+        let source = match c {
+            ' ' => "space".to_string(),
+            '\n' => "enter".to_string(),
+            '\t' => "tab".to_string(),
+            c => c.to_string(),
+        };
+        keystrokes.extend(Keystroke::parse(&source).log_err());
+        rest = &rest[c.len_utf8()..];
+    }
+    keystrokes
+}
+
+fn parse_key_notation(mut notation: &str) -> Option<Keystroke> {
+    let mut source = String::new();
+    while notation.len() > 2 && notation.as_bytes()[1] == b'-' {
+        source.push_str(match notation.as_bytes()[0].to_ascii_lowercase() {
+            b'c' => "ctrl-",
+            b'a' | b'm' => "alt-",
+            b's' => "shift-",
+            b'd' => "cmd-",
+            _ => return None,
+        });
+        notation = &notation[2..];
+    }
+    if notation.is_empty() || notation.contains(char::is_whitespace) {
+        return None;
+    }
+    let key = KEY_NOTATION_NAMES
+        .iter()
+        .find(|(_, name)| name.eq_ignore_ascii_case(notation))
+        .map_or(notation, |(key, _)| key);
+    source.push_str(key);
+    Keystroke::parse(&source).ok()
+}
+
 pub(crate) fn register(editor: &mut Editor, cx: &mut Context<Vim>) {
     Vim::action(editor, cx, |vim, _: &EndRepeat, window, cx| {
         Vim::globals(cx).dot_replaying = false;
@@ -69,7 +184,19 @@ pub(crate) fn register(editor: &mut Editor, cx: &mut Context<Vim>) {
     Vim::action(editor, cx, |vim, _: &ToggleRecord, window, cx| {
         let globals = Vim::globals(cx);
         if let Some(char) = globals.recording_register.take() {
-            globals.last_recorded_register = Some(char)
+            globals.last_recorded_register = Some(char);
+            // The keystroke that stopped the recording is not part of the macro.
+            if globals.replayer.is_none() {
+                globals.recording_keystrokes.pop();
+            }
+            let keystrokes = std::mem::take(&mut globals.recording_keystrokes);
+            globals
+                .registers
+                .insert(char, keystrokes_to_register_text(&keystrokes).into());
+            let workspace = vim.workspace(window);
+            Vim::update_globals(cx, |globals, cx| {
+                globals.save_registers(workspace.as_ref(), cx)
+            });
         } else {
             vim.push_operator(Operator::RecordRegister, window, cx);
         }
@@ -138,6 +265,15 @@ impl Replayer {
                     cx.defer(move |cx| Vim::globals(cx).observe_action(action.boxed_clone()));
                 }
             }
+            ReplayableAction::Keystroke(keystroke) => {
+                let focused = window.focused(cx);
+                window.dispatch_keystroke(keystroke, cx);
+                if window.focused(cx) != focused {
+                    // Let the focus change take effect before the next keystroke, as
+                    // `Workspace::send_keystrokes` does.
+                    window.draw(cx).clear();
+                }
+            }
             ReplayableAction::Insertion {
                 text,
                 utf16_range_to_replace,
@@ -171,6 +307,7 @@ impl Vim {
         let globals = Vim::globals(cx);
         globals.recording_register = Some(register);
         globals.recordings.remove(&register);
+        globals.recording_keystrokes.clear();
         globals.ignore_current_insertion = true;
         self.clear_operator(window, cx)
     }
@@ -192,9 +329,25 @@ impl Vim {
             };
             register = last;
         }
-        let Some(actions) = globals.recordings.get(&register) else {
-            return;
+        // Registers that weren't recorded in this session, or were written to since,
+        // are replayed as the keystrokes their text describes.
+        let actions = match globals.recordings.get(&register) {
+            Some(actions) => actions.clone(),
+            None => {
+                let Some(text) = Vim::update_globals(cx, |globals, cx| {
+                    globals.read_register(Some(register), None, cx)
+                }) else {
+                    return;
+                };
+                register_text_to_keystrokes(&text.text)
+                    .into_iter()
+                    .map(ReplayableAction::Keystroke)
+                    .collect()
+            }
         };
+        if actions.is_empty() {
+            return;
+        }
 
         let mut repeated_actions = vec![];
         while count > 0 {
@@ -202,6 +355,7 @@ impl Vim {
             count -= 1
         }
 
+        let globals = Vim::globals(cx);
         globals.last_replayed_register = Some(register);
         let mut replayer = globals.replayer.get_or_insert_with(Replayer::new).clone();
         replayer.replay(repeated_actions, window, cx);
@@ -365,13 +519,15 @@ mod test {
     use futures::StreamExt;
     use indoc::indoc;
 
-    use gpui::EntityInputHandler;
+    use gpui::{EntityInputHandler, Keystroke};
 
     use crate::{
         state::Mode,
         test::{NeovimBackedTestContext, VimTestContext},
     };
 
+    use super::{keystrokes_to_register_text, register_text_to_keystrokes};
+
     #[gpui::test]
     async fn test_dot_repeat(cx: &mut gpui::TestAppContext) {
         let mut cx = NeovimBackedTestContext::new(cx).await;
@@ -793,4 +949,39 @@ mod test {
         cx.simulate_shared_keystrokes("@ b").await;
         cx.shared_state().await.assert_eq("aaaaaaabbbˇd");
     }
+
+    #[test]
+    fn test_register_text_keystrokes() {
+        let text = "Ax y<Esc>0<C-a><S-Tab>j<lt>";
+        let keystrokes = register_text_to_keystrokes(text)
+            .into_iter()
+            .map(Keystroke::with_simulated_ime)
+            .collect::<Vec<_>>();
+        assert_eq!(keystrokes.len(), 10);
+        assert_eq!(keystrokes_to_register_text(&keystrokes), text);
+
+        let keystrokes = register_text_to_keystrokes("d<F5> <\n")
+            .into_iter()
+            .map(Keystroke::with_simulated_ime)
+            .collect::<Vec<_>>();
+        assert_eq!(keystrokes_to_register_text(&keystrokes), "d<f5> <lt><CR>");
+    }
+
+    #[gpui::test]
+    async fn test_edit_recorded_macro(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state("ˇone\ntwo\nthree", Mode::Normal);
+        cx.simulate_keystrokes("q a A ! escape j q");
+        cx.assert_state("one!\ntwˇo\nthree", Mode::Normal);
+
+        // The macro is pasted as text, edited and yanked back into its register.
+        cx.simulate_keystrokes("G o escape \" a p");
+        cx.assert_state("one!\ntwo\nthree\nA!<Esc>ˇj", Mode::Normal);
+        cx.simulate_keystrokes("0 f ! r ? 0 \" a y $ d d");
+        cx.assert_state("one!\ntwo\nˇthree", Mode::Normal);
+
+        cx.simulate_keystrokes("g g j @ a");
+        cx.assert_state("one!\ntwo?\nthrˇee", Mode::Normal);
+    }
 }
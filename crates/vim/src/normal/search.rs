@@ -1,6 +1,7 @@
 use editor::{Editor, EditorSettings};
 use gpui::{Action, Context, Window, actions};
 use language::Point;
+use multi_buffer::MultiBufferRow;
 use schemars::JsonSchema;
 use search::{BufferSearchBar, SearchOptions, buffer_search};
 use serde::Deserialize;
@@ -68,6 +69,8 @@ pub struct FindCommand {
 pub struct ReplaceCommand {
     pub(crate) range: CommandRange,
     pub(crate) replacement: Replacement,
+    /// Rows to resolve the range from, one after another, instead of the cursor's row.
+    pub(crate) override_rows: Option<Vec<u32>>,
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -461,12 +464,27 @@ impl Vim {
             return;
         };
         if let Some(result) = self.update_editor(cx, |vim, editor, cx| {
-            let range = action.range.buffer_range(vim, editor, window, cx)?;
+            let ranges = if let Some(rows) = &action.override_rows {
+                rows.iter()
+                    .map(|row| {
+                        action
+                            .range
+                            .buffer_range_at(MultiBufferRow(*row), vim, editor, window, cx)
+                    })
+                    .collect::<anyhow::Result<Vec<_>>>()?
+            } else {
+                vec![action.range.buffer_range(vim, editor, window, cx)?]
+            };
             let snapshot = &editor.snapshot(window, cx).buffer_snapshot;
-            let end_point = Point::new(range.end.0, snapshot.line_len(range.end));
-            let range = snapshot.anchor_before(Point::new(range.start.0, 0))
-                ..snapshot.anchor_after(end_point);
-            editor.set_search_within_ranges(&[range], cx);
+            let ranges = ranges
+                .into_iter()
+                .map(|range| {
+                    let end_point = Point::new(range.end.0, snapshot.line_len(range.end));
+                    snapshot.anchor_before(Point::new(range.start.0, 0))
+                        ..snapshot.anchor_after(end_point)
+                })
+                .collect::<Vec<_>>();
+            editor.set_search_within_ranges(&ranges, cx);
             anyhow::Ok(())
         }) {
             workspace.update(cx, |workspace, cx| {
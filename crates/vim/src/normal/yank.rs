@@ -203,6 +203,7 @@ impl Vim {
         }
 
         let selected_register = self.selected_register.take();
+        let workspace = self.workspace(window);
         Vim::update_globals(cx, |globals, cx| {
             globals.write_registers(
                 Register {
@@ -212,6 +213,7 @@ impl Vim {
                 selected_register,
                 is_yank,
                 kind,
+                workspace.as_ref(),
                 cx,
             )
         });
@@ -2,7 +2,10 @@ use crate::command::command_interceptor;
 use crate::motion::MotionKind;
 use crate::normal::repeat::Replayer;
 use crate::surrounds::SurroundsType;
-use crate::{ToggleMarksView, ToggleRegistersView, UseSystemClipboard, Vim, VimAddon, VimSettings};
+use crate::{
+    OpenRegistersBuffer, ToggleMarksView, ToggleRegistersView, UseSystemClipboard, Vim, VimAddon,
+    VimSettings,
+};
 use crate::{motion::Motion, object::Object};
 use anyhow::Result;
 use collections::HashMap;
@@ -15,7 +18,8 @@ use editor::display_map::{is_invisible, replacement};
 use editor::{Anchor, ClipboardSelection, Editor, MultiBuffer, ToPoint as EditorToPoint};
 use gpui::{
     Action, App, AppContext, BorrowAppContext, ClipboardEntry, ClipboardItem, DismissEvent, Entity,
-    EntityId, Global, HighlightStyle, StyledText, Subscription, Task, TextStyle, WeakEntity,
+    EntityId, Global, HighlightStyle, Keystroke, StyledText, Subscription, Task, TextStyle,
+    WeakEntity,
 };
 use language::{Buffer, BufferEvent, BufferId, Chunk, Point};
 use multi_buffer::MultiBufferRow;
@@ -26,6 +30,7 @@ use settings::{Settings, SettingsStore};
 use std::borrow::BorrowMut;
 use std::collections::HashSet;
 use std::path::Path;
+use std::time::Duration;
 use std::{fmt::Display, ops::Range, sync::Arc};
 use text::{Bias, ToPoint};
 use theme::ThemeSettings;
@@ -204,6 +209,9 @@ impl From<String> for Register {
     }
 }
 
+/// How long the registers have to stay unchanged before they are written to the database.
+const SAVE_REGISTERS_DEBOUNCE: Duration = Duration::from_millis(500);
+
 #[derive(Default)]
 pub struct VimGlobals {
     pub last_find: Option<Motion>,
@@ -224,6 +232,9 @@ pub struct VimGlobals {
     pub recorded_selection: RecordedSelection,
 
     pub recording_register: Option<char>,
+    /// The keystrokes typed while recording a macro, written to its register as text
+    /// once the recording stops.
+    pub recording_keystrokes: Vec<Keystroke>,
     pub last_recorded_register: Option<char>,
     pub last_replayed_register: Option<char>,
     pub replayer: Option<Replayer>,
@@ -235,6 +246,10 @@ pub struct VimGlobals {
     pub focused_vim: Option<WeakEntity<Vim>>,
 
     pub marks: HashMap<EntityId, Entity<MarksState>>,
+
+    /// The workspaces the registers were written in since they were last saved.
+    unsaved_register_workspaces: HashMap<EntityId, WeakEntity<Workspace>>,
+    save_registers_task: Option<Task<()>>,
 }
 
 pub struct MarksState {
@@ -690,6 +705,14 @@ impl VimGlobals {
         })
         .detach();
 
+        cx.intercept_keystrokes(|event, _, cx| {
+            let globals = Vim::globals(cx);
+            if globals.replayer.is_none() && globals.recording_register.is_some() {
+                globals.recording_keystrokes.push(event.keystroke.clone());
+            }
+        })
+        .detach();
+
         cx.observe_new(|workspace: &mut Workspace, window, _| {
             RegistersView::register(workspace, window);
         })
@@ -754,6 +777,78 @@ impl VimGlobals {
             })
         })
         .detach();
+        Self::load_registers(cx);
+    }
+
+    fn load_registers(cx: &mut Context<Workspace>) {
+        cx.spawn(async move |workspace, cx| {
+            let Some(workspace_id) =
+                workspace.read_with(cx, |workspace, _| workspace.database_id())?
+            else {
+                return Ok(());
+            };
+            let registers = cx
+                .background_spawn(async move { DB.get_registers(workspace_id) })
+                .await?;
+            cx.update(|cx| {
+                Vim::update_globals(cx, |globals, _| {
+                    for (name, text) in registers {
+                        globals.registers.entry(name).or_insert_with(|| text.into());
+                    }
+                })
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    /// Stores the registers in the database of the workspace they were written in, replacing
+    /// the ones it had, so that they're restored when the workspace is opened again.
+    /// Registers change with every yank and delete, so the write is debounced.
+    pub(crate) fn save_registers(&mut self, workspace: Option<&Entity<Workspace>>, cx: &mut App) {
+        let Some(workspace) = workspace else {
+            return;
+        };
+        self.unsaved_register_workspaces
+            .insert(workspace.entity_id(), workspace.downgrade());
+        self.save_registers_task = Some(cx.spawn(async move |cx| {
+            cx.background_executor()
+                .timer(SAVE_REGISTERS_DEBOUNCE)
+                .await;
+            let Some((workspace_ids, registers)) = cx
+                .update(|cx| {
+                    let workspaces = std::mem::take(
+                        &mut cx.global_mut::<VimGlobals>().unsaved_register_workspaces,
+                    );
+                    let workspace_ids = workspaces
+                        .values()
+                        .filter_map(|workspace| {
+                            workspace
+                                .read_with(cx, |workspace, _| workspace.database_id())
+                                .ok()
+                                .flatten()
+                        })
+                        .collect::<Vec<_>>();
+                    let globals = cx.global::<VimGlobals>();
+                    let registers = globals
+                        .registers
+                        .iter()
+                        .filter(|(name, _)| {
+                            name.is_ascii_alphanumeric() || matches!(name, '"' | '-')
+                        })
+                        .map(|(name, register)| (name.to_string(), register.text.to_string()))
+                        .collect::<Vec<_>>();
+                    (workspace_ids, registers)
+                })
+                .log_err()
+            else {
+                return;
+            };
+            for workspace_id in workspace_ids {
+                DB.set_registers(workspace_id, registers.clone())
+                    .await
+                    .log_err();
+            }
+        }));
     }
 
     pub(crate) fn write_registers(
@@ -762,6 +857,7 @@ impl VimGlobals {
         register: Option<char>,
         is_yank: bool,
         kind: MotionKind,
+        workspace: Option<&Entity<Workspace>>,
         cx: &mut Context<Editor>,
     ) {
         if let Some(register) = register {
@@ -797,6 +893,8 @@ impl VimGlobals {
                     }
                 }
             }
+            // A macro that is written to is replayed from its new text from now on.
+            self.recordings.remove(&lower);
         } else {
             let setting = VimSettings::get_global(cx).use_system_clipboard;
             if setting == UseSystemClipboard::Always
@@ -830,6 +928,7 @@ impl VimGlobals {
                 }
             }
         }
+        self.save_registers(workspace, cx);
     }
 
     pub(crate) fn read_register(
@@ -963,6 +1062,7 @@ pub enum ReplayableAction {
         text: Arc<str>,
         utf16_range_to_replace: Option<Range<isize>>,
     },
+    Keystroke(Keystroke),
 }
 
 impl Clone for ReplayableAction {
@@ -976,6 +1076,7 @@ impl Clone for ReplayableAction {
                 text: text.clone(),
                 utf16_range_to_replace: utf16_range_to_replace.clone(),
             },
+            Self::Keystroke(keystroke) => Self::Keystroke(keystroke.clone()),
         }
     }
 }
@@ -1269,6 +1370,36 @@ impl RegistersView {
         workspace.register_action(|workspace, _: &ToggleRegistersView, window, cx| {
             Self::toggle(workspace, window, cx);
         });
+        workspace.register_action(|workspace, _: &OpenRegistersBuffer, window, cx| {
+            Self::open_buffer(workspace, window, cx);
+        });
+    }
+
+    /// Opens the registers in a new buffer, where macros can be edited and yanked back
+    /// into their register.
+    pub fn open_buffer(
+        workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        let editor = workspace
+            .active_item(cx)
+            .and_then(|item| item.act_as::<Editor>(cx));
+        let text = register_matches(editor, cx)
+            .into_iter()
+            .map(|register_match| {
+                format!(
+                    "\"{}  {}\n",
+                    register_match.name,
+                    register_display_text(&register_match.contents)
+                )
+            })
+            .collect::<String>();
+        let buffer = cx.new(|cx| Buffer::local(text, cx));
+        let buffer = cx.new(|cx| MultiBuffer::singleton(buffer, cx).with_title("Registers".into()));
+        let project = workspace.project().clone();
+        let editor = cx.new(|cx| Editor::for_multibuffer(buffer, Some(project), window, cx));
+        workspace.add_item_to_active_pane(Box::new(editor), None, true, window, cx);
     }
 
     pub fn toggle(workspace: &mut Workspace, window: &mut Window, cx: &mut Context<Workspace>) {
@@ -1285,38 +1416,7 @@ impl RegistersView {
         window: &mut Window,
         cx: &mut Context<Picker<RegistersViewDelegate>>,
     ) -> Picker<RegistersViewDelegate> {
-        let mut matches = Vec::default();
-        cx.update_global(|globals: &mut VimGlobals, cx| {
-            for name in ['"', '+', '*'] {
-                if let Some(register) = globals.read_register(Some(name), None, cx) {
-                    matches.push(RegisterMatch {
-                        name,
-                        contents: register.text.clone(),
-                    })
-                }
-            }
-            if let Some(editor) = editor {
-                let register = editor.update(cx, |editor, cx| {
-                    globals.read_register(Some('%'), Some(editor), cx)
-                });
-                if let Some(register) = register {
-                    matches.push(RegisterMatch {
-                        name: '%',
-                        contents: register.text,
-                    })
-                }
-            }
-            for (name, register) in globals.registers.iter() {
-                if ['"', '+', '*', '%'].contains(name) {
-                    continue;
-                };
-                matches.push(RegisterMatch {
-                    name: *name,
-                    contents: register.text.clone(),
-                })
-            }
-        });
-        matches.sort_by(|a, b| a.name.cmp(&b.name));
+        let matches = register_matches(editor, cx);
         let delegate = RegistersViewDelegate {
             selected_index: 0,
             matches,
@@ -1328,6 +1428,55 @@ impl RegistersView {
     }
 }
 
+fn register_matches(editor: Option<Entity<Editor>>, cx: &mut App) -> Vec<RegisterMatch> {
+    let mut matches = Vec::default();
+    cx.update_global(|globals: &mut VimGlobals, cx| {
+        for name in ['"', '+', '*'] {
+            if let Some(register) = globals.read_register(Some(name), None, cx) {
+                matches.push(RegisterMatch {
+                    name,
+                    contents: register.text.clone(),
+                })
+            }
+        }
+        if let Some(editor) = editor {
+            let register = editor.update(cx, |editor, cx| {
+                globals.read_register(Some('%'), Some(editor), cx)
+            });
+            if let Some(register) = register {
+                matches.push(RegisterMatch {
+                    name: '%',
+                    contents: register.text,
+                })
+            }
+        }
+        for (name, register) in globals.registers.iter() {
+            if ['"', '+', '*', '%'].contains(name) {
+                continue;
+            };
+            matches.push(RegisterMatch {
+                name: *name,
+                contents: register.text.clone(),
+            })
+        }
+    });
+    matches.sort_by(|a, b| a.name.cmp(&b.name));
+    matches
+}
+
+/// Writes the contents of a register on a single line, showing control characters
+/// the way Vim does (e.g. `^J` for a newline).
+fn register_display_text(contents: &str) -> String {
+    contents
+        .chars()
+        .map(|c| match c {
+            '\x7f' => "^?".to_string(),
+            c if c <= '\x1f' => format!("^{}", (c as u8 + b'@') as char),
+            c => c.to_string(),
+        })
+        .collect()
+}
+
 enum MarksMatchInfo {
     Path(Arc<Path>),
     Title(String),
@@ -1709,6 +1858,15 @@ impl Domain for VimDb {
             CREATE UNIQUE INDEX idx_vim_global_marks_paths
             ON vim_global_marks_paths(workspace_id, mark_name);
         ),
+        sql! (
+            CREATE TABLE vim_registers(
+                workspace_id INTEGER,
+                register_name TEXT,
+                value TEXT
+            );
+            CREATE UNIQUE INDEX idx_vim_registers
+            ON vim_registers(workspace_id, register_name);
+        ),
     ];
 }
 
@@ -1827,4 +1985,82 @@ impl VimDb {
         })
         .await
     }
+
+    pub(crate) async fn set_registers(
+        &self,
+        workspace_id: WorkspaceId,
+        registers: Vec<(String, String)>,
+    ) -> Result<()> {
+        log::debug!("Setting {} registers", registers.len());
+        self.write(move |conn| {
+            conn.exec_bound(sql!(
+                DELETE FROM vim_registers
+                WHERE workspace_id = ?
+            ))?(workspace_id)?;
+            let mut query = conn.exec_bound(sql!(
+                INSERT INTO vim_registers
+                    (workspace_id, register_name, value)
+                VALUES
+                    (?, ?, ?)
+            ))?;
+            for (register_name, value) in registers {
+                query((workspace_id, register_name, value))?;
+            }
+            Ok(())
+        })
+        .await
+    }
+
+    fn get_registers(&self, workspace_id: WorkspaceId) -> Result<Vec<(char, String)>> {
+        let result: Vec<(String, String)> = self.select_bound(sql!(
+            SELECT register_name, value FROM vim_registers
+                WHERE workspace_id = ?
+        ))?(workspace_id)?;
+
+        Ok(result
+            .into_iter()
+            .filter_map(|(name, value)| Some((name.chars().next()?, value)))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[gpui::test]
+    async fn test_save_and_get_registers() {
+        let first_workspace = workspace::WORKSPACE_DB.next_id().await.unwrap();
+        let second_workspace = workspace::WORKSPACE_DB.next_id().await.unwrap();
+
+        for workspace_id in [first_workspace, second_workspace] {
+            DB.set_registers(
+                workspace_id,
+                vec![
+                    ("a".to_string(), "first".to_string()),
+                    ("\"".to_string(), "yanked".to_string()),
+                ],
+            )
+            .await
+            .unwrap();
+        }
+        DB.set_registers(
+            first_workspace,
+            vec![("a".to_string(), "second".to_string())],
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            DB.get_registers(first_workspace).unwrap(),
+            vec![('a', "second".to_string())],
+            "Registers that were not saved again should be deleted"
+        );
+        let mut registers = DB.get_registers(second_workspace).unwrap();
+        registers.sort();
+        assert_eq!(
+            registers,
+            vec![('"', "yanked".to_string()), ('a', "first".to_string())]
+        );
+    }
 }
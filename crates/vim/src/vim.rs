@@ -230,6 +230,8 @@ actions!(
         PushRot47,
         /// Toggles the registers view.
         ToggleRegistersView,
+        /// Opens the contents of the registers in a new buffer.
+        OpenRegistersBuffer,
         /// Selects a register.
         PushRegister,
         /// Starts recording to a register.
@@ -392,6 +394,9 @@ pub(crate) struct Vim {
     pub(crate) current_anchor: Option<Selection<Anchor>>,
     pub(crate) undo_modes: HashMap<TransactionId, Mode>,
     pub(crate) undo_last_line_tx: Option<TransactionId>,
+    /// While `:normal` replays its keystrokes, the first transaction they started (if any),
+    /// so that all of their edits can be undone at once.
+    pub(crate) norm_tx: Option<Option<TransactionId>>,
 
     selected_register: Option<char>,
     pub search: SearchState,
@@ -439,6 +444,7 @@ impl Vim {
 
             stored_visual_mode: None,
             current_tx: None,
+            norm_tx: None,
             undo_last_line_tx: None,
             current_anchor: None,
             undo_modes: HashMap::default(),
@@ -1525,6 +1531,9 @@ impl Vim {
         if mode == Mode::VisualLine || mode == Mode::VisualBlock {
             self.undo_modes.insert(transaction_id, mode);
         }
+        if let Some(norm_tx @ None) = &mut self.norm_tx {
+            *norm_tx = Some(transaction_id);
+        }
     }
 
     fn transaction_undone(
@@ -20,7 +20,10 @@ use project::debugger::breakpoint_store::{BreakpointState, SourceBreakpoint};
 
 use language::{LanguageName, Toolchain, ToolchainScope};
 use project::WorktreeId;
-use remote::{RemoteConnectionOptions, SshConnectionOptions, WslConnectionOptions};
+use remote::{
+    ContainerRuntime, DockerConnectionOptions, RemoteConnectionOptions, SshConnectionOptions,
+    WslConnectionOptions,
+};
 use sqlez::{
     bindable::{Bind, Column, StaticColumnCount},
     statement::Statement,
@@ -1117,6 +1120,14 @@ impl WorkspaceDb {
                 distro = Some(options.distro_name);
                 user = options.user;
             }
+            RemoteConnectionOptions::Docker(options) => {
+                kind = match options.runtime {
+                    ContainerRuntime::Docker => RemoteConnectionKind::Docker,
+                    ContainerRuntime::Podman => RemoteConnectionKind::Podman,
+                };
+                host = Some(options.container);
+                user = options.user;
+            }
         }
         Self::get_or_create_remote_connection_query(this, kind, host, port, user, distro)
     }
@@ -1286,6 +1297,20 @@ impl WorkspaceDb {
                 username: user,
                 ..Default::default()
             })),
+            RemoteConnectionKind::Docker => {
+                Some(RemoteConnectionOptions::Docker(DockerConnectionOptions {
+                    runtime: ContainerRuntime::Docker,
+                    container: host?,
+                    user,
+                }))
+            }
+            RemoteConnectionKind::Podman => {
+                Some(RemoteConnectionOptions::Docker(DockerConnectionOptions {
+                    runtime: ContainerRuntime::Podman,
+                    container: host?,
+                    user,
+                }))
+            }
         }
     }
 
@@ -32,6 +32,8 @@ pub(crate) struct RemoteConnectionId(pub u64);
 pub(crate) enum RemoteConnectionKind {
     Ssh,
     Wsl,
+    Docker,
+    Podman,
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -75,6 +77,8 @@ impl RemoteConnectionKind {
         match self {
             RemoteConnectionKind::Ssh => "ssh",
             RemoteConnectionKind::Wsl => "wsl",
+            RemoteConnectionKind::Docker => "docker",
+            RemoteConnectionKind::Podman => "podman",
         }
     }
 
@@ -82,6 +86,8 @@ impl RemoteConnectionKind {
         match text {
             "ssh" => Some(Self::Ssh),
             "wsl" => Some(Self::Wsl),
+            "docker" => Some(Self::Docker),
+            "podman" => Some(Self::Podman),
             _ => None,
         }
     }
@@ -1,11 +1,16 @@
 use std::process::ExitStatus;
 
 use anyhow::Result;
-use gpui::{AppContext, Context, Entity, Task};
+use collections::HashMap;
+use futures::{
+    FutureExt as _,
+    future::{self, Shared},
+};
+use gpui::{AppContext, AsyncApp, Context, Entity, Task};
 use language::Buffer;
-use project::{TaskSourceKind, WorktreeId};
+use project::{Inventory, TaskPipeline, TaskRunId, TaskRunStatus, TaskSourceKind, WorktreeId};
 use remote::ConnectionState;
-use task::{DebugScenario, ResolvedTask, SpawnInTerminal, TaskContext, TaskTemplate};
+use task::{DebugScenario, DependsOrder, ResolvedTask, SpawnInTerminal, TaskContext, TaskTemplate};
 use ui::Window;
 
 use crate::{Toast, Workspace, notifications::NotificationId};
@@ -54,28 +59,108 @@ impl Workspace {
         window: &mut Window,
         cx: &mut Context<Workspace>,
     ) {
-        let spawn_in_terminal = resolved_task.resolved.clone();
+        if !resolved_task.pending_inputs().is_empty() {
+            let resolved_task =
+                self.resolve_task_inputs(resolved_task, &task_source_kind.to_id_base(), window, cx);
+            cx.spawn_in(window, async move |workspace, cx| {
+                let Some(resolved_task) = resolved_task.await else {
+                    return;
+                };
+                workspace
+                    .update_in(cx, |workspace, window, cx| {
+                        workspace.schedule_resolved_task(
+                            task_source_kind,
+                            resolved_task,
+                            omit_history,
+                            window,
+                            cx,
+                        )
+                    })
+                    .ok();
+            })
+            .detach();
+            return;
+        }
+
+        let task_to_spawn = (task_source_kind.clone(), resolved_task.clone());
+        let task_with_dependencies =
+            (!resolved_task.original_task().depends_on.is_empty()).then(|| {
+                (
+                    task_source_kind.clone(),
+                    resolved_task.original_task().clone(),
+                    resolved_task.task_context().clone(),
+                )
+            });
         if !omit_history {
             if let Some(debugger_provider) = self.debugger_provider.as_ref() {
                 debugger_provider.task_scheduled(cx);
             }
 
-            self.project().update(cx, |project, cx| {
-                if let Some(task_inventory) =
-                    project.task_store().read(cx).task_inventory().cloned()
-                {
-                    task_inventory.update(cx, |inventory, _| {
-                        inventory.task_scheduled(task_source_kind, resolved_task);
-                    })
-                }
-            });
+            // Secret input values are asked for again on rerun, instead of being kept in the history.
+            if let Some(resolved_task) =
+                resolved_task.without_secret_inputs(&task_source_kind.to_id_base())
+            {
+                self.project().update(cx, |project, cx| {
+                    if let Some(task_inventory) =
+                        project.task_store().read(cx).task_inventory().cloned()
+                    {
+                        task_inventory.update(cx, |inventory, _| {
+                            inventory.task_scheduled(task_source_kind, resolved_task);
+                        })
+                    }
+                });
+            }
+        }
+
+        if let Some((task_source_kind, task_template, task_context)) = task_with_dependencies {
+            self.spawn_after_dependencies(
+                task_source_kind,
+                task_template,
+                task_context,
+                task_to_spawn,
+                window,
+                cx,
+            );
+        } else {
+            self.spawn_scheduled_task(task_to_spawn, window, cx);
         }
+    }
+
+    /// Records the start of a task run in the project's run history, if the project keeps one.
+    /// The values of secret inputs are left out of the history.
+    fn task_run_started(
+        &self,
+        task_source_kind: TaskSourceKind,
+        resolved_task: &ResolvedTask,
+        cx: &mut Context<Workspace>,
+    ) -> Option<(Entity<Inventory>, TaskRunId)> {
+        let task_inventory = self
+            .project
+            .read(cx)
+            .task_store()
+            .read(cx)
+            .task_inventory()
+            .cloned()?;
+        let resolved_task = resolved_task.without_secret_inputs(&task_source_kind.to_id_base())?;
+        let task_run_id = task_inventory.update(cx, |inventory, cx| {
+            inventory.task_run_started(task_source_kind, resolved_task, cx)
+        });
+        Some((task_inventory, task_run_id))
+    }
 
+    fn spawn_scheduled_task(
+        &mut self,
+        (task_source_kind, resolved_task): (TaskSourceKind, ResolvedTask),
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
         if let Some(terminal_provider) = self.terminal_provider.as_ref() {
-            let task_status = terminal_provider.spawn(spawn_in_terminal, window, cx);
+            let task_run = self.task_run_started(task_source_kind, &resolved_task, cx);
+            let task_status = terminal_provider.spawn(resolved_task.resolved, window, cx);
 
-            let task = cx.spawn(async |w, cx| {
+            let task = cx.spawn(async move |w, cx| {
                 let res = cx.background_spawn(task_status).await;
+                finish_task_run(task_run, &res, cx);
                 match res {
                     Some(Ok(status)) => {
                         if status.success() {
@@ -98,6 +183,273 @@ impl Workspace {
         }
     }
 
+    /// Resolves the tasks the given task depends on, spawns them and spawns the task itself
+    /// only if all of its dependencies finished successfully.
+    fn spawn_after_dependencies(
+        &mut self,
+        task_source_kind: TaskSourceKind,
+        task_template: TaskTemplate,
+        task_context: TaskContext,
+        task_to_spawn: (TaskSourceKind, ResolvedTask),
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        let Some(task_inventory) = self
+            .project
+            .read(cx)
+            .task_store()
+            .read(cx)
+            .task_inventory()
+            .cloned()
+        else {
+            self.spawn_scheduled_task(task_to_spawn, window, cx);
+            return;
+        };
+
+        let active_project_path = self.active_item(cx).and_then(|item| item.project_path(cx));
+        let active_buffer = active_project_path
+            .as_ref()
+            .and_then(|project_path| self.project.read(cx).get_open_buffer(project_path, cx));
+        let pipeline = task_inventory.read(cx).task_pipeline(
+            active_buffer,
+            active_project_path.map(|project_path| project_path.worktree_id),
+            task_source_kind,
+            task_template,
+            cx,
+        );
+
+        let task = cx.spawn_in(window, async move |workspace, cx| {
+            let pipeline = match pipeline.await {
+                Ok(pipeline) => pipeline,
+                Err(e) => {
+                    log::error!("Failed to resolve task dependencies: {e:#}");
+                    workspace
+                        .update(cx, |workspace, cx| {
+                            let id = NotificationId::unique::<TaskPipeline>();
+                            workspace.show_toast(
+                                Toast::new(id, format!("Failed to resolve task dependencies: {e}")),
+                                cx,
+                            );
+                        })
+                        .ok();
+                    return;
+                }
+            };
+
+            let Ok(dependencies) = workspace.update_in(cx, |workspace, window, cx| {
+                workspace.spawn_task_dependencies(
+                    pipeline.dependencies,
+                    pipeline.template.depends_order,
+                    Vec::new(),
+                    &task_context,
+                    &mut HashMap::default(),
+                    window,
+                    cx,
+                )
+            }) else {
+                return;
+            };
+
+            if future::join_all(dependencies)
+                .await
+                .into_iter()
+                .all(|succeeded| succeeded)
+            {
+                workspace
+                    .update_in(cx, |workspace, window, cx| {
+                        workspace.spawn_scheduled_task(task_to_spawn, window, cx)
+                    })
+                    .ok();
+            } else {
+                let spawn_in_terminal = &task_to_spawn.1.resolved;
+                log::debug!(
+                    "Not spawning task `{}`, as some of its dependencies failed",
+                    spawn_in_terminal.full_label
+                );
+                workspace
+                    .update(cx, |workspace, cx| {
+                        let id = NotificationId::unique::<TaskPipeline>();
+                        workspace.show_toast(
+                            Toast::new(
+                                id,
+                                format!(
+                                    "Task `{}` was not spawned: one of its dependencies failed",
+                                    spawn_in_terminal.label
+                                ),
+                            ),
+                            cx,
+                        );
+                    })
+                    .ok();
+            }
+        });
+        self.scheduled_tasks.push(task);
+    }
+
+    /// Spawns the dependency tasks given, each after its own dependencies and the `prerequisites` succeed.
+    /// Returns the tasks that resolve to `true` if the corresponding dependency finished successfully.
+    ///
+    /// Every task is spawned at most once per pipeline, even if multiple tasks depend on it.
+    fn spawn_task_dependencies(
+        &mut self,
+        dependencies: Vec<TaskPipeline>,
+        depends_order: DependsOrder,
+        prerequisites: Vec<Shared<Task<bool>>>,
+        task_context: &TaskContext,
+        spawned: &mut HashMap<String, Shared<Task<bool>>>,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) -> Vec<Shared<Task<bool>>> {
+        let mut dependency_tasks = Vec::<Shared<Task<bool>>>::with_capacity(dependencies.len());
+        for dependency in dependencies {
+            let prerequisites = match (depends_order, dependency_tasks.last()) {
+                (DependsOrder::Sequence, Some(previous_dependency)) => {
+                    vec![previous_dependency.clone()]
+                }
+                (DependsOrder::Sequence, None) | (DependsOrder::Parallel, _) => {
+                    prerequisites.clone()
+                }
+            };
+            let dependency_task =
+                if let Some(spawned_task) = spawned.get(&dependency.template.label) {
+                    spawned_task.clone()
+                } else {
+                    self.spawn_task_dependency(
+                        dependency,
+                        prerequisites,
+                        task_context,
+                        spawned,
+                        window,
+                        cx,
+                    )
+                };
+            dependency_tasks.push(dependency_task);
+        }
+        dependency_tasks
+    }
+
+    fn spawn_task_dependency(
+        &mut self,
+        dependency: TaskPipeline,
+        mut prerequisites: Vec<Shared<Task<bool>>>,
+        task_context: &TaskContext,
+        spawned: &mut HashMap<String, Shared<Task<bool>>>,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) -> Shared<Task<bool>> {
+        let label = dependency.template.label.clone();
+        let source_kind = dependency.source_kind.clone();
+        let id_base = source_kind.to_id_base();
+        let resolved_task = dependency.template.resolve_task(&id_base, task_context);
+        prerequisites.extend(self.spawn_task_dependencies(
+            dependency.dependencies,
+            dependency.template.depends_order,
+            prerequisites.clone(),
+            task_context,
+            spawned,
+            window,
+            cx,
+        ));
+
+        let task = cx
+            .spawn_in(window, async move |workspace, cx| {
+                let prerequisites_succeeded = future::join_all(prerequisites)
+                    .await
+                    .into_iter()
+                    .all(|succeeded| succeeded);
+                if !prerequisites_succeeded {
+                    return false;
+                }
+                let Some(resolved_task) = resolved_task else {
+                    log::error!("Failed to resolve dependency task `{label}`");
+                    return false;
+                };
+                // Inputs are asked for once the prerequisites succeed, so that the prompts come in the order the tasks run.
+                let Ok(resolved_task) = workspace.update_in(cx, |workspace, window, cx| {
+                    workspace.resolve_task_inputs(resolved_task, &id_base, window, cx)
+                }) else {
+                    return false;
+                };
+                let Some(resolved_task) = resolved_task.await else {
+                    log::debug!("Inputs of dependency task `{label}` were not provided");
+                    return false;
+                };
+                let Ok((task_run, task_status)) =
+                    workspace.update_in(cx, |workspace, window, cx| {
+                        let task_run = workspace.task_run_started(source_kind, &resolved_task, cx);
+                        let task_status =
+                            workspace.spawn_in_terminal(resolved_task.resolved, window, cx);
+                        (task_run, task_status)
+                    })
+                else {
+                    return false;
+                };
+                let task_status = task_status.await;
+                finish_task_run(task_run, &task_status, cx);
+                match task_status {
+                    Some(Ok(status)) => {
+                        if !status.success() {
+                            log::debug!(
+                                "Dependency task `{label}` failed, code: {:?}",
+                                status.code()
+                            );
+                        }
+                        status.success()
+                    }
+                    Some(Err(e)) => {
+                        log::error!("Dependency task `{label}` spawn failed: {e:#}");
+                        false
+                    }
+                    None => {
+                        log::debug!("Dependency task `{label}` got cancelled");
+                        false
+                    }
+                }
+            })
+            .shared();
+        spawned.insert(label, task.clone());
+        task
+    }
+
+    /// Asks the user for the inputs of the task that have no value yet, and resolves the task again with their values.
+    /// Resolves to `None` if the inputs can't be asked for, or if any of the prompts got dismissed.
+    pub fn resolve_task_inputs(
+        &mut self,
+        resolved_task: ResolvedTask,
+        id_base: &str,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) -> Task<Option<ResolvedTask>> {
+        let pending_inputs = resolved_task.pending_inputs();
+        if pending_inputs.is_empty() {
+            return Task::ready(Some(resolved_task));
+        }
+        let Some(task_input_provider) = self.task_input_provider.as_ref() else {
+            log::error!(
+                "Cannot ask for the inputs of task `{}`",
+                resolved_task.resolved.label
+            );
+            return Task::ready(None);
+        };
+        let inputs = pending_inputs.into_iter().cloned().collect();
+        let values = task_input_provider.prompt_inputs(inputs, window, cx);
+        let id_base = id_base.to_string();
+        cx.background_spawn(async move {
+            let Some(values) = values.await else {
+                log::debug!("Task input prompt got dismissed");
+                return None;
+            };
+            let resolved_inputs = resolved_task.resolve_inputs(&id_base, values);
+            if resolved_inputs.is_none() {
+                log::error!(
+                    "Failed to resolve task `{}` with its inputs",
+                    resolved_task.resolved.label
+                );
+            }
+            resolved_inputs
+        })
+    }
+
     pub fn start_debug_session(
         &mut self,
         scenario: DebugScenario,
@@ -132,3 +484,252 @@ impl Workspace {
         }
     }
 }
+
+fn finish_task_run(
+    task_run: Option<(Entity<Inventory>, TaskRunId)>,
+    task_status: &Option<Result<ExitStatus>>,
+    cx: &mut AsyncApp,
+) {
+    let Some((task_inventory, task_run_id)) = task_run else {
+        return;
+    };
+    let status = match task_status {
+        Some(Ok(status)) if status.success() => TaskRunStatus::Succeeded,
+        Some(Ok(status)) => TaskRunStatus::Failed {
+            exit_code: status.code(),
+        },
+        Some(Err(_)) => TaskRunStatus::Failed { exit_code: None },
+        None => TaskRunStatus::Cancelled,
+    };
+    task_inventory
+        .update(cx, |inventory, cx| {
+            inventory.task_run_finished(task_run_id, status, cx)
+        })
+        .ok();
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{process::ExitStatus, sync::Arc};
+
+    use anyhow::Result;
+    use gpui::{App, Context, Task, TestAppContext};
+    use parking_lot::Mutex;
+    use project::{FakeFs, Project, task_store::TaskStore};
+    use serde_json::json;
+    use task::{SpawnInTerminal, TaskContext, TaskInput};
+    use ui::Window;
+    use util::path;
+
+    use crate::{TaskInputProvider, TerminalProvider, Workspace, tests::init_test};
+
+    /// Records the labels of the tasks spawned, failing the ones with the label given.
+    struct FakeTerminalProvider {
+        spawned: Arc<Mutex<Vec<String>>>,
+        failing_label: &'static str,
+    }
+
+    impl TerminalProvider for FakeTerminalProvider {
+        fn spawn(
+            &self,
+            task: SpawnInTerminal,
+            _: &mut Window,
+            _: &mut App,
+        ) -> Task<Option<Result<ExitStatus>>> {
+            let exit_code = if task.label == self.failing_label {
+                1
+            } else {
+                0
+            };
+            self.spawned.lock().push(task.label);
+            Task::ready(Some(Ok(exit_status(exit_code))))
+        }
+    }
+
+    /// Answers every input with its id in upper case, recording the ids asked for.
+    struct FakeTaskInputProvider {
+        prompted: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl TaskInputProvider for FakeTaskInputProvider {
+        fn prompt_inputs(
+            &self,
+            inputs: Vec<TaskInput>,
+            _: &mut Window,
+            _: &mut Context<Workspace>,
+        ) -> Task<Option<Vec<(String, String)>>> {
+            let values = inputs
+                .into_iter()
+                .map(|input| {
+                    self.prompted.lock().push(input.id.clone());
+                    let value = input.id.to_uppercase();
+                    (input.id, value)
+                })
+                .collect();
+            Task::ready(Some(values))
+        }
+    }
+
+    fn exit_status(code: i32) -> ExitStatus {
+        #[cfg(unix)]
+        {
+            std::os::unix::process::ExitStatusExt::from_raw(code << 8)
+        }
+        #[cfg(windows)]
+        {
+            std::os::windows::process::ExitStatusExt::from_raw(code as u32)
+        }
+    }
+
+    #[gpui::test]
+    async fn test_failing_dependency_stops_the_chain(cx: &mut TestAppContext) {
+        init_test(cx);
+        cx.update(|_| TaskStore::init(None));
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/dir"),
+            json!({
+                ".zed": {
+                    "tasks.json": r#"[
+                        { "label": "first", "command": "echo" },
+                        { "label": "second", "command": "false" },
+                        { "label": "third", "command": "echo" },
+                        {
+                            "label": "all",
+                            "command": "echo",
+                            "depends_on": ["first", "second", "third"],
+                            "depends_order": "sequence"
+                        }
+                    ]"#,
+                },
+            }),
+        )
+        .await;
+        let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+        cx.run_until_parked();
+
+        let worktree_id = project.update(cx, |project, cx| {
+            project.worktrees(cx).next().unwrap().read(cx).id()
+        });
+        let task_inventory = project.read_with(cx, |project, cx| {
+            project
+                .task_store()
+                .read(cx)
+                .task_inventory()
+                .cloned()
+                .unwrap()
+        });
+        let (source_kind, template) = task_inventory
+            .update(cx, |inventory, cx| {
+                inventory.list_tasks(None, None, Some(worktree_id), cx)
+            })
+            .await
+            .into_iter()
+            .find(|(_, template)| template.label == "all")
+            .expect("the task should be loaded from the worktree");
+
+        let spawned = Arc::new(Mutex::new(Vec::new()));
+        let (workspace, cx) =
+            cx.add_window_view(|window, cx| Workspace::test_new(project.clone(), window, cx));
+        workspace.update_in(cx, |workspace, window, cx| {
+            workspace.set_terminal_provider(FakeTerminalProvider {
+                spawned: spawned.clone(),
+                failing_label: "second",
+            });
+            workspace.schedule_task(
+                source_kind,
+                &template,
+                &TaskContext::default(),
+                false,
+                window,
+                cx,
+            );
+        });
+        cx.run_until_parked();
+
+        assert_eq!(
+            *spawned.lock(),
+            vec!["first".to_string(), "second".to_string()],
+            "Neither the dependency after the failed one nor the task itself should be spawned"
+        );
+    }
+
+    #[gpui::test]
+    async fn test_dependency_inputs_are_prompted_for(cx: &mut TestAppContext) {
+        init_test(cx);
+        cx.update(|_| TaskStore::init(None));
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/dir"),
+            json!({
+                ".zed": {
+                    "tasks.json": r#"[
+                        {
+                            "label": "build for $ZED_INPUT_target",
+                            "command": "echo",
+                            "inputs": [{ "id": "target", "type": "prompt_string" }]
+                        },
+                        {
+                            "label": "run",
+                            "command": "echo",
+                            "depends_on": ["build for $ZED_INPUT_target"]
+                        }
+                    ]"#,
+                },
+            }),
+        )
+        .await;
+        let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+        cx.run_until_parked();
+
+        let worktree_id = project.update(cx, |project, cx| {
+            project.worktrees(cx).next().unwrap().read(cx).id()
+        });
+        let task_inventory = project.read_with(cx, |project, cx| {
+            project
+                .task_store()
+                .read(cx)
+                .task_inventory()
+                .cloned()
+                .unwrap()
+        });
+        let (source_kind, template) = task_inventory
+            .update(cx, |inventory, cx| {
+                inventory.list_tasks(None, None, Some(worktree_id), cx)
+            })
+            .await
+            .into_iter()
+            .find(|(_, template)| template.label == "run")
+            .expect("the task should be loaded from the worktree");
+
+        let spawned = Arc::new(Mutex::new(Vec::new()));
+        let prompted = Arc::new(Mutex::new(Vec::new()));
+        let (workspace, cx) =
+            cx.add_window_view(|window, cx| Workspace::test_new(project.clone(), window, cx));
+        workspace.update_in(cx, |workspace, window, cx| {
+            workspace.set_terminal_provider(FakeTerminalProvider {
+                spawned: spawned.clone(),
+                failing_label: "",
+            });
+            workspace.set_task_input_provider(FakeTaskInputProvider {
+                prompted: prompted.clone(),
+            });
+            workspace.schedule_task(
+                source_kind,
+                &template,
+                &TaskContext::default(),
+                false,
+                window,
+                cx,
+            );
+        });
+        cx.run_until_parked();
+
+        assert_eq!(*prompted.lock(), vec!["target".to_string()]);
+        assert_eq!(
+            *spawned.lock(),
+            vec!["build for TARGET".to_string(), "run".to_string()],
+            "The dependency should be spawned with the value of its input"
+        );
+    }
+}
@@ -99,7 +99,7 @@ use std::{
     sync::{Arc, LazyLock, Weak, atomic::AtomicUsize},
     time::Duration,
 };
-use task::{DebugScenario, SpawnInTerminal, TaskContext};
+use task::{DebugScenario, SpawnInTerminal, TaskContext, TaskInput};
 use theme::{ActiveTheme, SystemAppearance, ThemeSettings};
 pub use toolbar::{Toolbar, ToolbarItemEvent, ToolbarItemLocation, ToolbarItemView};
 pub use ui;
@@ -142,6 +142,17 @@ pub trait TerminalProvider {
     ) -> Task<Option<Result<ExitStatus>>>;
 }
 
+pub trait TaskInputProvider {
+    /// Asks the user for the values of the given task inputs, one after another.
+    /// Resolves to the values by input id, or to `None` if any of the prompts was dismissed.
+    fn prompt_inputs(
+        &self,
+        inputs: Vec<TaskInput>,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) -> Task<Option<Vec<(String, String)>>>;
+}
+
 pub trait DebuggerProvider {
     // `active_buffer` is used to resolve build task's name against language-specific tasks.
     fn start_session(
@@ -1142,6 +1153,7 @@ pub struct Workspace {
     on_prompt_for_open_path: Option<PromptForOpenPath>,
     terminal_provider: Option<Box<dyn TerminalProvider>>,
     debugger_provider: Option<Arc<dyn DebuggerProvider>>,
+    task_input_provider: Option<Box<dyn TaskInputProvider>>,
     serializable_items_tx: UnboundedSender<Box<dyn SerializableItemHandle>>,
     _items_serializer: Task<Result<()>>,
     session_id: Option<String>,
@@ -1485,6 +1497,7 @@ impl Workspace {
             on_prompt_for_open_path: None,
             terminal_provider: None,
             debugger_provider: None,
+            task_input_provider: None,
             serializable_items_tx,
             _items_serializer,
             session_id: Some(session_id),
@@ -2061,6 +2074,10 @@ impl Workspace {
         self.debugger_provider = Some(Arc::new(provider));
     }
 
+    pub fn set_task_input_provider(&mut self, provider: impl TaskInputProvider + 'static) {
+        self.task_input_provider = Some(Box::new(provider));
+    }
+
     pub fn debugger_provider(&self) -> Option<Arc<dyn DebuggerProvider>> {
         self.debugger_provider.clone()
     }
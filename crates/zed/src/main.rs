@@ -182,6 +182,12 @@ pub fn main() {
         return;
     }
 
+    // `zed --git-sequence-editor` Makes zed replace the todo list of an interactive rebase
+    if let Some(todo_path) = &args.git_sequence_editor {
+        git::rebase::sequence_editor_main(todo_path);
+        return;
+    }
+
     // `zed --nc` Makes zed operate in nc/netcat mode for use with MCP
     if let Some(socket) = &args.nc {
         match nc::main(socket) {
@@ -593,6 +599,7 @@ pub fn main() {
         project_symbols::init(cx);
         project_panel::init(cx);
         outline_panel::init(cx);
+        hierarchy_panel::init(cx);
         tasks_ui::init(cx);
         snippets_ui::init(cx);
         channel::init(&app_state.client.clone(), app_state.user_store.clone(), cx);
@@ -1196,6 +1203,11 @@ struct Args {
     #[arg(long, hide = true)]
     askpass: Option<String>,
 
+    /// Used as git's sequence editor for interactive rebases, to remove the need for a POSIX `cp`,
+    /// by having Zed copy the planned todo list over the one git generated.
+    #[arg(long, hide = true)]
+    git_sequence_editor: Option<PathBuf>,
+
     /// Used for the MCP Server, to remove the need for netcat as a dependency,
     /// by having Zed act like netcat communicating over a Unix socket.
     #[arg(long, hide = true)]
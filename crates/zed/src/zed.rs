@@ -67,6 +67,7 @@ use std::{
     sync::Arc,
     sync::atomic::{self, AtomicBool},
 };
+use tasks_ui::TaskHistoryPanel;
 use terminal_view::terminal_panel::{self, TerminalPanel};
 use theme::{ActiveTheme, ThemeSettings};
 use ui::{PopoverMenuHandle, prelude::*};
@@ -588,6 +589,9 @@ fn initialize_panels(
             workspace.add_panel(channels_panel, window, cx);
             workspace.add_panel(notification_panel, window, cx);
             workspace.add_panel(debug_panel, window, cx);
+
+            let task_history_panel = cx.new(|cx| TaskHistoryPanel::new(workspace, cx));
+            workspace.add_panel(task_history_panel, window, cx);
         })?;
 
         let is_assistant2_enabled = !cfg!(test);
@@ -4457,6 +4461,7 @@ mod tests {
                 "auto_update",
                 "branches",
                 "buffer_search",
+                "call_hierarchy",
                 "channel_modal",
                 "cli",
                 "client",
@@ -4500,6 +4505,7 @@ mod tests {
                 "project_search",
                 "project_symbols",
                 "projects",
+                "rebase_modal",
                 "repl",
                 "rules_library",
                 "search",
@@ -4511,11 +4517,13 @@ mod tests {
                 "syntax_tree_view",
                 "tab_switcher",
                 "task",
+                "task_history",
                 "terminal",
                 "terminal_panel",
                 "theme_selector",
                 "toast",
                 "toolchain",
+                "type_hierarchy",
                 "variable_list",
                 "vim",
                 "window",
@@ -4656,6 +4664,7 @@ mod tests {
             git_ui::init(cx);
             project_panel::init(cx);
             outline_panel::init(cx);
+            hierarchy_panel::init(cx);
             terminal_view::init(cx);
             copilot::copilot_chat::init(
                 app_state.fs.clone(),
@@ -182,7 +182,9 @@ pub mod git {
             #[action(deprecated_aliases = ["branches::OpenRecent"])]
             Branch,
             /// Opens the git stash selector.
-            ViewStash
+            ViewStash,
+            /// Opens the interactive rebase editor.
+            InteractiveRebase
         ]
     );
 }
@@ -368,6 +370,16 @@ pub struct OpenRecent {
     pub create_new_window: bool,
 }
 
+/// Opens a worktree of the active git repository as a new workspace.
+#[derive(PartialEq, Clone, Deserialize, Default, JsonSchema, Action)]
+#[action(namespace = projects)]
+#[serde(deny_unknown_fields)]
+pub struct OpenWorktree {
+    /// The worktree to open. When omitted, a picker of the repository's worktrees is shown.
+    #[serde(default)]
+    pub path: Option<std::path::PathBuf>,
+}
+
 /// Creates a project from a selected template.
 #[derive(PartialEq, Clone, Deserialize, Default, JsonSchema, Action)]
 #[action(namespace = projects)]
@@ -498,6 +510,31 @@ actions!(
     ]
 );
 
+pub mod container_actions {
+    use gpui::Action;
+    use schemars::JsonSchema;
+    use serde::Deserialize;
+
+    /// Opens a project inside a running Docker or Podman container.
+    #[derive(PartialEq, Clone, Deserialize, Default, JsonSchema, Action)]
+    #[action(namespace = projects)]
+    #[serde(deny_unknown_fields)]
+    pub struct OpenContainer {
+        #[serde(default)]
+        pub create_new_window: bool,
+    }
+
+    /// Reopens the current project inside its running dev container, as configured
+    /// in `.devcontainer/devcontainer.json`.
+    #[derive(PartialEq, Clone, Deserialize, Default, JsonSchema, Action)]
+    #[action(namespace = projects)]
+    #[serde(deny_unknown_fields)]
+    pub struct OpenDevContainer {
+        #[serde(default)]
+        pub create_new_window: bool,
+    }
+}
+
 #[cfg(target_os = "windows")]
 pub mod wsl_actions {
     use gpui::Action;